@@ -0,0 +1,28 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use diffie_hellman_groups::{
+    group::{MODPGroup, MODPGroup14},
+    limbs::FixedElement,
+    Element,
+};
+use num_bigint::RandBigInt;
+
+fn bench_group14(c: &mut Criterion) {
+    let p = MODPGroup14::prime_modulus();
+    let mut rng = rand::thread_rng();
+    let a = Element::<MODPGroup14>::from_value(rng.gen_biguint_below(&p));
+    let b = Element::<MODPGroup14>::from_value(rng.gen_biguint_below(&p));
+    let (fa, fb) = (FixedElement::from(&a), FixedElement::from(&b));
+
+    let mut group = c.benchmark_group("clone/MODPGroup14");
+    group.bench_function("Element", |bench| bench.iter(|| a.clone()));
+    group.bench_function("FixedElement", |bench| bench.iter(|| fa));
+    group.finish();
+
+    let mut group = c.benchmark_group("mul/MODPGroup14");
+    group.bench_function("Element", |bench| bench.iter(|| &a * &b));
+    group.bench_function("FixedElement", |bench| bench.iter(|| fa * fb));
+    group.finish();
+}
+
+criterion_group!(benches, bench_group14);
+criterion_main!(benches);
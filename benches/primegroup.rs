@@ -0,0 +1,45 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use diffie_hellman_groups::{group::MODPGroup18, PrimeGroup};
+
+fn bench_generate(c: &mut Criterion) {
+    let mut group = c.benchmark_group("PrimeGroup::generate");
+    group.sample_size(10);
+    for bits in [512u64, 1024, 2048] {
+        group.bench_with_input(BenchmarkId::from_parameter(bits), &bits, |b, &bits| {
+            b.iter(|| PrimeGroup::generate(bits, &mut rand::thread_rng(), None).unwrap())
+        });
+    }
+    group.finish();
+}
+
+#[cfg(feature = "parallel")]
+fn bench_generate_parallel(c: &mut Criterion) {
+    let mut group = c.benchmark_group("PrimeGroup::generate_parallel/1024");
+    group.sample_size(10);
+    for threads in [1usize, 2, 4, 8] {
+        group.bench_with_input(
+            BenchmarkId::from_parameter(threads),
+            &threads,
+            |b, &threads| {
+                let mut seed = 0;
+                b.iter(|| {
+                    seed += 1;
+                    PrimeGroup::generate_parallel(1024, threads, seed).unwrap()
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+#[cfg(not(feature = "parallel"))]
+fn bench_generate_parallel(_: &mut Criterion) {}
+
+fn bench_new(c: &mut Criterion) {
+    c.bench_function("PrimeGroup::new::<MODPGroup18>", |b| {
+        b.iter(|| PrimeGroup::new::<MODPGroup18>(8192).unwrap())
+    });
+}
+
+criterion_group!(benches, bench_generate, bench_generate_parallel, bench_new);
+criterion_main!(benches);
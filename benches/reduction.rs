@@ -0,0 +1,218 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use diffie_hellman_groups::{
+    group::{MODPGroup, MODPGroup14, MODPGroup18},
+    Element,
+};
+use num_bigint::{BigUint, RandBigInt};
+
+/// Number of multiplications of a chain, as in the squarings of an exponentiation.
+const CHAIN: usize = 64;
+
+fn chain<G: MODPGroup>(c: &mut Criterion, name: &str) {
+    let p = G::prime_modulus();
+    let mut rng = rand::thread_rng();
+    let a = rng.gen_biguint_below(&p);
+    let b = rng.gen_biguint_below(&p);
+
+    let mut group = c.benchmark_group(format!("mul chain/{name}"));
+    group.bench_with_input(BenchmarkId::new("remainder", CHAIN), &CHAIN, |bench, &n| {
+        bench.iter(|| {
+            let mut acc: BigUint = a.clone();
+            for _ in 0..n {
+                acc = &acc * &b % &p;
+            }
+            acc
+        })
+    });
+    group.bench_with_input(
+        BenchmarkId::new("fast_reduce", CHAIN),
+        &CHAIN,
+        |bench, &n| {
+            bench.iter(|| {
+                let mut acc = a.clone();
+                for _ in 0..n {
+                    acc = G::mul(&acc, &b);
+                }
+                acc
+            })
+        },
+    );
+    group.finish();
+}
+
+/// Run a chain on each of `threads` threads at once, where the temporaries of the reductions
+/// would otherwise contend on the allocator.
+fn threaded_chain<G: MODPGroup>(c: &mut Criterion, name: &str) {
+    let p = G::prime_modulus();
+    let mut rng = rand::thread_rng();
+    let a = rng.gen_biguint_below(&p);
+    let b = rng.gen_biguint_below(&p);
+    let max_threads = std::thread::available_parallelism().map_or(4, |n| n.get());
+
+    let mut group = c.benchmark_group(format!("threaded mul chain/{name}"));
+    for threads in [1, 4, 16, 64]
+        .into_iter()
+        .filter(|&t| t <= max_threads.max(4))
+    {
+        group.bench_with_input(
+            BenchmarkId::from_parameter(threads),
+            &threads,
+            |bench, &threads| {
+                bench.iter(|| {
+                    std::thread::scope(|scope| {
+                        for _ in 0..threads {
+                            scope.spawn(|| {
+                                let mut acc = a.clone();
+                                for _ in 0..CHAIN {
+                                    acc = G::mul(&acc, &b);
+                                }
+                                acc
+                            });
+                        }
+                    })
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+fn bench_sum_product(c: &mut Criterion) {
+    let p = MODPGroup14::prime_modulus();
+    let mut rng = rand::thread_rng();
+    let elements: Vec<Element<MODPGroup14>> = (0..1000)
+        .map(|_| Element::from_value(rng.gen_biguint_below(&p)))
+        .collect();
+
+    let mut group = c.benchmark_group("sum/MODPGroup14/1000");
+    group.bench_function("fold", |bench| {
+        bench.iter(|| {
+            elements
+                .iter()
+                .fold(BigUint::ZERO, |acc, e| (acc + &e.value) % &p)
+        })
+    });
+    group.bench_function("sum_mod", |bench| {
+        bench.iter(|| Element::sum_mod(&elements))
+    });
+    group.finish();
+
+    let mut group = c.benchmark_group("product/MODPGroup14/1000");
+    group.bench_function("fold", |bench| {
+        bench.iter(|| {
+            elements
+                .iter()
+                .fold(BigUint::from(1u64), |acc, e| acc * &e.value % &p)
+        })
+    });
+    group.bench_function("product_mod", |bench| {
+        bench.iter(|| Element::product_mod(&elements))
+    });
+    group.finish();
+}
+
+/// Compare the squaring with a multiplication of a value by itself, alone and within full
+/// exponentiations by the windowed [Element::multi_pow].
+fn squaring<G: MODPGroup>(c: &mut Criterion, name: &str) {
+    let p = G::prime_modulus();
+    let mut rng = rand::thread_rng();
+    let a = rng.gen_biguint_below(&p);
+    let exponent = rng.gen_biguint_below(&G::subgroup_order());
+    let base = Element::<G>::from_value(a.clone());
+
+    let mut group = c.benchmark_group(format!("square/{name}"));
+    group.bench_function("mul", |bench| bench.iter(|| G::mul(&a, &a)));
+    group.bench_function("square", |bench| bench.iter(|| G::square(&a)));
+    group.finish();
+
+    let mut group = c.benchmark_group(format!("pow/{name}"));
+    group.sample_size(10);
+    group.bench_function("modpow", |bench| bench.iter(|| G::pow(&a, &exponent)));
+    group.bench_function("multi_pow", |bench| {
+        bench.iter(|| Element::multi_pow(&[(&base, &exponent)]))
+    });
+    group.finish();
+}
+
+/// Compare Straus' and Pippenger's multi-exponentiations over batch sizes around
+/// the threshold of [Element::multi_pow], with exponents of the size of q.
+fn multi_pow<G: MODPGroup>(c: &mut Criterion, name: &str) {
+    let p = G::prime_modulus();
+    let q = G::subgroup_order();
+    let mut rng = rand::thread_rng();
+
+    let mut group = c.benchmark_group(format!("multi_pow/{name}"));
+    group.sample_size(10);
+    for n in [16, 64, 128, 256, 1024] {
+        let bases: Vec<Element<G>> = (0..n)
+            .map(|_| Element::from_value(rng.gen_biguint_below(&p)))
+            .collect();
+        let exponents: Vec<BigUint> = (0..n).map(|_| rng.gen_biguint_below(&q)).collect();
+        let terms: Vec<_> = bases.iter().zip(&exponents).collect();
+        group.bench_with_input(BenchmarkId::new("straus", n), &terms, |bench, terms| {
+            bench.iter(|| Element::multi_pow_straus(terms))
+        });
+        group.bench_with_input(BenchmarkId::new("pippenger", n), &terms, |bench, terms| {
+            bench.iter(|| Element::multi_pow_pippenger(terms))
+        });
+    }
+    group.finish();
+}
+
+/// Compare raising each base to q with [Element::pow] and with the shared schedule of
+/// [Element::pow_many_bases], as in a batch validation of public keys.
+fn pow_many_bases<G: MODPGroup>(c: &mut Criterion, name: &str) {
+    let p = G::prime_modulus();
+    let q = G::subgroup_order();
+    let mut rng = rand::thread_rng();
+
+    let mut group = c.benchmark_group(format!("pow_many_bases/{name}"));
+    group.sample_size(10);
+    for n in [16, 256] {
+        let bases: Vec<Element<G>> = (0..n)
+            .map(|_| Element::from_value(rng.gen_biguint_below(&p)))
+            .collect();
+        group.bench_with_input(BenchmarkId::new("pow", n), &bases, |bench, bases| {
+            bench.iter(|| bases.iter().map(|b| b.pow(&q)).collect::<Vec<_>>())
+        });
+        group.bench_with_input(
+            BenchmarkId::new("pow_many_bases", n),
+            &bases,
+            |bench, bases| bench.iter(|| Element::pow_many_bases(bases, &q)),
+        );
+    }
+    group.finish();
+}
+
+fn bench_pow_many_bases(c: &mut Criterion) {
+    pow_many_bases::<MODPGroup14>(c, "MODPGroup14");
+}
+
+fn bench_multi_pow(c: &mut Criterion) {
+    multi_pow::<MODPGroup14>(c, "MODPGroup14");
+}
+
+fn bench_squaring(c: &mut Criterion) {
+    squaring::<MODPGroup14>(c, "MODPGroup14");
+    squaring::<MODPGroup18>(c, "MODPGroup18");
+}
+
+fn bench_mul_chain(c: &mut Criterion) {
+    chain::<MODPGroup14>(c, "MODPGroup14");
+    chain::<MODPGroup18>(c, "MODPGroup18");
+}
+
+fn bench_threaded_mul_chain(c: &mut Criterion) {
+    threaded_chain::<MODPGroup14>(c, "MODPGroup14");
+}
+
+criterion_group!(
+    benches,
+    bench_mul_chain,
+    bench_threaded_mul_chain,
+    bench_sum_product,
+    bench_squaring,
+    bench_multi_pow,
+    bench_pow_many_bases
+);
+criterion_main!(benches);
@@ -0,0 +1,226 @@
+//! Command line tool to manage Diffie-Hellman parameters and keys:
+//!
+//! ```text
+//! dhgroups-cli genparam --bits 2048 [--out params.pem]
+//! dhgroups-cli genkey (--group modp2048 | --params params.pem) [--out key.json]
+//! dhgroups-cli pubkey --key key.json [--out peer.json]
+//! dhgroups-cli shared --key key.json --peer peer.json
+//! dhgroups-cli inspect params.pem
+//! ```
+//!
+//! Parameters are PKCS #3 PEM files, as written by `openssl dhparam`. Keys are JSON Web Keys of
+//! the module `jwk`, where a secret key has the additional member `"x"`, the base64url encoding
+//! of its secret exponent. The built-in groups are named after the size of their modulus, e.g.
+//! `modp2048` for the group 14 of RFC 3526.
+//!
+//! Run it with `cargo run --example dhgroups-cli --features jwk,secrecy -- <command>`.
+
+use std::{error::Error, fs, sync::Arc};
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use diffie_hellman_groups::{
+    dhparam,
+    secret::{ExposeSecret, SecretExponent},
+    AnyModpGroup, PrimeGroup, RuntimePublicKey, RuntimeSecretKey, UnvalidatedRuntimePublicKey,
+};
+use num_bigint::BigUint;
+use rand_core::CryptoRngCore;
+use serde_json::{Map, Value};
+
+type CliResult<T> = Result<T, Box<dyn Error>>;
+
+const USAGE: &str = "usage:
+    dhgroups-cli genparam --bits <bits> [--out <file>]
+    dhgroups-cli genkey (--group <name> | --params <file>) [--out <file>]
+    dhgroups-cli pubkey --key <file> [--out <file>]
+    dhgroups-cli shared --key <file> --peer <file>
+    dhgroups-cli inspect <file>";
+
+/// Generate parameters with a random safe prime of `bits` bits, in PEM.
+pub fn genparam<R: CryptoRngCore + ?Sized>(bits: u64, rng: &mut R) -> CliResult<String> {
+    let group = PrimeGroup::generate(bits, rng, None)?;
+    Ok(dhparam::to_pem(&group))
+}
+
+/// The built-in group of RFC 3526 named `name`, e.g. `modp2048`, with its standard generator.
+pub fn builtin_group(name: &str) -> CliResult<PrimeGroup> {
+    let group = AnyModpGroup::RFC3526
+        .into_iter()
+        .find(|group| group.name() == name.to_ascii_lowercase())
+        .ok_or_else(|| format!("unknown group {name}"))?;
+    let p = group.prime_modulus();
+    Ok(PrimeGroup {
+        q: (&p - 1u64) >> 1,
+        g: group.generator(),
+        cofactor: BigUint::from(2u64),
+        p,
+    })
+}
+
+/// Generate a secret key of `group`, as a JSON Web Key with the member `"x"`.
+pub fn genkey<R: CryptoRngCore + ?Sized>(group: &PrimeGroup, rng: &mut R) -> String {
+    let (secret, public) = group.generate_keypair(rng);
+    let Ok(Value::Object(mut members)) = serde_json::from_str(&public.to_jwk()) else {
+        unreachable!("to_jwk writes a JSON object");
+    };
+    let x = secret.expose_exponent();
+    members.insert(
+        "x".to_string(),
+        Value::String(URL_SAFE_NO_PAD.encode(x.expose_secret().as_biguint().to_bytes_be())),
+    );
+    Value::Object(members).to_string()
+}
+
+/// Parse a secret key written by [genkey], checking that its public key `"y"` matches `"x"`.
+pub fn parse_secret_key(key: &str) -> CliResult<(RuntimeSecretKey, RuntimePublicKey)> {
+    let public = UnvalidatedRuntimePublicKey::from_jwk(key)?;
+    let members: Map<String, Value> = serde_json::from_str(key)?;
+    let x = members
+        .get("x")
+        .and_then(Value::as_str)
+        .and_then(|x| URL_SAFE_NO_PAD.decode(x).ok())
+        .ok_or("the key has no secret exponent \"x\"")?;
+    let exponent = SecretExponent::new_secret(BigUint::from_bytes_be(&x));
+    let secret = RuntimeSecretKey::from_secret(Arc::new(public.group().clone()), &exponent)?;
+    let computed = secret.public_key();
+    if UnvalidatedRuntimePublicKey::from(computed.clone()) != public {
+        return Err("the public key \"y\" does not match the secret exponent \"x\"".into());
+    }
+    Ok((secret, computed))
+}
+
+/// The public key of a secret key written by [genkey], as a JSON Web Key.
+pub fn pubkey(key: &str) -> CliResult<String> {
+    let (_, public) = parse_secret_key(key)?;
+    Ok(public.to_jwk())
+}
+
+/// The shared secret of the secret key `key` and the public key `peer` of the same group, in
+/// hexadecimal padded to the length of p.
+pub fn shared(key: &str, peer: &str) -> CliResult<String> {
+    let (secret, public) = parse_secret_key(key)?;
+    let group = Arc::new(public.group().clone());
+    let peer = UnvalidatedRuntimePublicKey::from_jwk_in(&group, peer)?.validate()?;
+    let z = secret.diffie_hellman(&peer)?;
+    Ok(z.to_bytes_be().iter().map(|b| format!("{b:02x}")).collect())
+}
+
+/// Describe PEM parameters in the layout of `openssl dhparam -text`, followed by the built-in
+/// group with the same modulus, if any.
+pub fn inspect(pem: &str) -> CliResult<String> {
+    let params = dhparam::parse_pem(pem)?;
+    let mut text = params.group.to_string();
+    match params.builtin {
+        Some(id) if params.group.identify_exact() == Some(id) => {
+            text += &format!("Identified: {} (group {})\n", id.name(), id.group_id())
+        }
+        Some(id) => {
+            text += &format!(
+                "Identified: {} (group {}) with another generator\n",
+                id.name(),
+                id.group_id()
+            )
+        }
+        None => text += "Identified: none\n",
+    }
+    if let Some(length) = params.private_value_length {
+        text += &format!("Private value length: {length} bits\n");
+    }
+    Ok(text)
+}
+
+/// Parse the options `--name value` of a command, in any order.
+fn options<'a>(args: &'a [String], names: &[&str]) -> CliResult<Vec<Option<&'a str>>> {
+    let mut values = vec![None; names.len()];
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let i = names
+            .iter()
+            .position(|name| arg.strip_prefix("--") == Some(name))
+            .ok_or_else(|| format!("unexpected argument {arg}\n{USAGE}"))?;
+        let value = args
+            .next()
+            .ok_or_else(|| format!("missing value of {arg}"))?;
+        values[i] = Some(value.as_str());
+    }
+    Ok(values)
+}
+
+fn required<'a>(value: Option<&'a str>, name: &str) -> CliResult<&'a str> {
+    value.ok_or_else(|| format!("missing option --{name}\n{USAGE}").into())
+}
+
+/// Write `contents` to `path`, readable only by the owner on Unix since keys are secret.
+fn write_file(path: &str, contents: &str) -> CliResult<()> {
+    let mut file = fs::OpenOptions::new();
+    file.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut file, 0o600);
+    std::io::Write::write_all(&mut file.open(path)?, contents.as_bytes())?;
+    Ok(())
+}
+
+/// Run the command `args`, without the program name. The output is written to the file of the
+/// option `--out` if given, and returned otherwise.
+pub fn run<R: CryptoRngCore + ?Sized>(args: &[String], rng: &mut R) -> CliResult<String> {
+    let (command, args) = args.split_first().ok_or(USAGE)?;
+    let (output, out) = match command.as_str() {
+        "genparam" => {
+            let [bits, out] = options(args, &["bits", "out"])?[..] else {
+                unreachable!()
+            };
+            (genparam(required(bits, "bits")?.parse()?, rng)?, out)
+        }
+        "genkey" => {
+            let [group, params, out] = options(args, &["group", "params", "out"])?[..] else {
+                unreachable!()
+            };
+            let group = match (group, params) {
+                (Some(name), None) => builtin_group(name)?,
+                (None, Some(path)) => dhparam::parse_pem(&fs::read_to_string(path)?)?.group,
+                _ => return Err(format!("expected one of --group or --params\n{USAGE}").into()),
+            };
+            (genkey(&group, rng), out)
+        }
+        "pubkey" => {
+            let [key, out] = options(args, &["key", "out"])?[..] else {
+                unreachable!()
+            };
+            (pubkey(&fs::read_to_string(required(key, "key")?)?)?, out)
+        }
+        "shared" => {
+            let [key, peer] = options(args, &["key", "peer"])?[..] else {
+                unreachable!()
+            };
+            let key = fs::read_to_string(required(key, "key")?)?;
+            let peer = fs::read_to_string(required(peer, "peer")?)?;
+            (shared(&key, &peer)?, None)
+        }
+        "inspect" => {
+            let [path] = args else {
+                return Err(USAGE.into());
+            };
+            (inspect(&fs::read_to_string(path)?)?, None)
+        }
+        _ => return Err(format!("unknown command {command}\n{USAGE}").into()),
+    };
+    match out {
+        Some(path) => {
+            write_file(path, &output)?;
+            Ok(String::new())
+        }
+        None => Ok(output),
+    }
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match run(&args, &mut rand::thread_rng()) {
+        Ok(output) if output.is_empty() => {}
+        Ok(output) => println!("{}", output.trim_end()),
+        Err(e) => {
+            eprintln!("error: {e}");
+            std::process::exit(1);
+        }
+    }
+}
@@ -0,0 +1,7 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    diffie_hellman_groups::fuzz_targets::element_from_bytes_be(data);
+});
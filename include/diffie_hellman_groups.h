@@ -0,0 +1,89 @@
+#ifndef DIFFIE_HELLMAN_GROUPS_H
+#define DIFFIE_HELLMAN_GROUPS_H
+
+/* C interface of the diffie-hellman-groups crate, built with the feature `capi`. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * The operation succeeded.
+ */
+#define DHG_OK 0
+
+/**
+ * A required pointer is null.
+ */
+#define DHG_ERR_NULL_POINTER -1
+
+/**
+ * The group number is not one of the MODP groups of RFC 3526.
+ */
+#define DHG_ERR_UNKNOWN_GROUP -2
+
+/**
+ * A buffer does not have the length returned by `dhg_element_len`.
+ */
+#define DHG_ERR_LENGTH -3
+
+/**
+ * A private key is not in `[1, q)`, or a public key fails validation.
+ */
+#define DHG_ERR_INVALID_KEY -4
+
+/**
+ * The library panicked. This is a bug.
+ */
+#define DHG_ERR_PANIC -99
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Return the length in bytes of the keys and shared secrets of the group numbered `group_id`,
+ * or 0 if the group is unknown.
+ */
+uintptr_t dhg_element_len(uint32_t group_id);
+
+/**
+ * Generate a key pair in the group numbered `group_id`, writing the private exponent to
+ * `out_priv` and the public key to `out_pub`.
+ *
+ * # Safety
+ * `out_priv` and `out_pub` must be valid for writes of `dhg_element_len` bytes.
+ */
+int32_t dhg_keypair_generate(uint32_t group_id, uint8_t *out_priv, uint8_t *out_pub);
+
+/**
+ * Compute the shared secret of the private key `priv` and the public key `peer` of the group
+ * numbered `group_id`, writing it to `out`. The peer key is validated first.
+ *
+ * # Safety
+ * `priv` and `peer` must be valid for reads of `priv_len` and `peer_len` bytes, and `out` for
+ * writes of `out_len` bytes.
+ */
+int32_t dhg_shared_secret(uint32_t group_id,
+                          const uint8_t *priv,
+                          uintptr_t priv_len,
+                          const uint8_t *peer,
+                          uintptr_t peer_len,
+                          uint8_t *out,
+                          uintptr_t out_len);
+
+/**
+ * Validate the public key in `buf` of the group numbered `group_id`.
+ * Returns `DHG_OK` if the key is valid, or `DHG_ERR_INVALID_KEY` if it is not.
+ *
+ * # Safety
+ * `buf` must be valid for reads of `len` bytes.
+ */
+int32_t dhg_validate_public(uint32_t group_id, const uint8_t *buf, uintptr_t len);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* DIFFIE_HELLMAN_GROUPS_H */
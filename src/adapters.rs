@@ -0,0 +1,397 @@
+//! [serde_with](https://crates.io/crates/serde_with) adapters to choose the encoding of each
+//! [Element] or [Scalar] field of a user struct, enabled by the `serde_with` feature.
+//!
+//! The adapters encode the fixed-length bytes of [Element::to_bytes_be] and
+//! [Scalar::to_bytes_be] as:
+//! - [ElementHex], [ScalarHex] and [PublicKeyHex]: a string of lowercase hexadecimal digits,
+//! - [ElementBase64Url], [ScalarBase64Url] and [PublicKeyBase64Url]: a string in base64url
+//!   without padding,
+//! - [ElementBytes], [ScalarBytes] and [PublicKeyBytes]: raw bytes, compact in binary formats.
+//!
+//! Deserializing checks the length and the range of the value as [Element::from_bytes_be] and
+//! [Scalar::from_bytes]. The public key adapters also check that the element is a valid public
+//! key with [Element::validate]. The adapters are usable with `#[serde_as(as = ...)]` as well as
+//! `#[serde(with = ...)]`.
+//!
+//! # Example
+//!
+//! ```rust
+//! use diffie_hellman_groups::{
+//!     adapters::{PublicKeyHex, ScalarBytes},
+//!     group::MODPGroup14,
+//!     Element, Scalar,
+//! };
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct KeyPair {
+//!     #[serde(with = "ScalarBytes")]
+//!     secret: Scalar<MODPGroup14>,
+//!     #[serde(with = "PublicKeyHex")]
+//!     public: Element<MODPGroup14>,
+//! }
+//!
+//! let secret = Scalar::random(&mut rand::thread_rng());
+//! let public = Element::from_biguint(secret.value.clone());
+//! let json = serde_json::to_string(&KeyPair { secret, public }).unwrap();
+//! let key_pair: KeyPair = serde_json::from_str(&json).unwrap();
+//! ```
+
+use std::{fmt, marker::PhantomData};
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use serde::{
+    de::{self, SeqAccess, Visitor},
+    Deserialize, Deserializer, Serializer,
+};
+use serde_with::{DeserializeAs, SerializeAs};
+
+use crate::{group::MODPGroup, Element, Scalar};
+
+/// Encoding of bytes in a serialization format, see [Hex], [Base64Url] and [Bytes].
+pub trait Format {
+    /// Serialize `bytes` in this encoding.
+    fn serialize_bytes<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error>;
+
+    /// Deserialize bytes in this encoding.
+    fn deserialize_bytes<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error>;
+}
+
+/// A string of hexadecimal digits, written in lowercase and read in either case.
+pub struct Hex;
+
+/// A string in base64url without padding.
+pub struct Base64Url;
+
+/// Raw bytes, e.g. a length-prefixed byte string in bincode, or an array of numbers in JSON.
+pub struct Bytes;
+
+impl Format for Hex {
+    fn serialize_bytes<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        let hex: String = bytes.iter().map(|b| format!("{b:02x}")).collect();
+        serializer.serialize_str(&hex)
+    }
+
+    fn deserialize_bytes<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+        let hex = String::deserialize(deserializer)?;
+        if !hex.is_ascii() || hex.len() % 2 != 0 {
+            return Err(de::Error::custom("invalid hexadecimal string"));
+        }
+        (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16))
+            .collect::<Result<_, _>>()
+            .map_err(|_| de::Error::custom("invalid hexadecimal string"))
+    }
+}
+
+impl Format for Base64Url {
+    fn serialize_bytes<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&URL_SAFE_NO_PAD.encode(bytes))
+    }
+
+    fn deserialize_bytes<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+        let encoded = String::deserialize(deserializer)?;
+        URL_SAFE_NO_PAD
+            .decode(encoded)
+            .map_err(|_| de::Error::custom("invalid base64url string"))
+    }
+}
+
+impl Format for Bytes {
+    fn serialize_bytes<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(bytes)
+    }
+
+    fn deserialize_bytes<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+        deserializer.deserialize_bytes(BytesVisitor)
+    }
+}
+
+/// Visitor of bytes, or of a sequence of numbers for formats without byte strings.
+struct BytesVisitor;
+
+impl<'de> Visitor<'de> for BytesVisitor {
+    type Value = Vec<u8>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "bytes")
+    }
+
+    fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+        Ok(v.to_vec())
+    }
+
+    fn visit_byte_buf<E: de::Error>(self, v: Vec<u8>) -> Result<Self::Value, E> {
+        Ok(v)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+        while let Some(b) = seq.next_element()? {
+            bytes.push(b);
+        }
+        Ok(bytes)
+    }
+}
+
+/// Adapter of [Element]s in the encoding `F`, checking the range of the value when deserializing.
+pub struct ElementAs<F>(PhantomData<F>);
+
+/// Adapter of [Scalar]s in the encoding `F`, checking the range of the value when deserializing.
+pub struct ScalarAs<F>(PhantomData<F>);
+
+/// Adapter of public keys, i.e. [Element]s, in the encoding `F`, checking that the element is a
+/// valid public key with [Element::validate] when deserializing.
+pub struct PublicKeyAs<F>(PhantomData<F>);
+
+/// [Element]s as hexadecimal strings.
+pub type ElementHex = ElementAs<Hex>;
+/// [Element]s as base64url strings.
+pub type ElementBase64Url = ElementAs<Base64Url>;
+/// [Element]s as raw bytes.
+pub type ElementBytes = ElementAs<Bytes>;
+/// [Scalar]s as hexadecimal strings.
+pub type ScalarHex = ScalarAs<Hex>;
+/// [Scalar]s as base64url strings.
+pub type ScalarBase64Url = ScalarAs<Base64Url>;
+/// [Scalar]s as raw bytes.
+pub type ScalarBytes = ScalarAs<Bytes>;
+/// Public keys as hexadecimal strings.
+pub type PublicKeyHex = PublicKeyAs<Hex>;
+/// Public keys as base64url strings.
+pub type PublicKeyBase64Url = PublicKeyAs<Base64Url>;
+/// Public keys as raw bytes.
+pub type PublicKeyBytes = PublicKeyAs<Bytes>;
+
+impl<F: Format, G: MODPGroup> SerializeAs<Element<G>> for ElementAs<F> {
+    fn serialize_as<S: Serializer>(source: &Element<G>, serializer: S) -> Result<S::Ok, S::Error> {
+        F::serialize_bytes(&source.to_bytes_be(), serializer)
+    }
+}
+
+impl<'de, F: Format, G: MODPGroup> DeserializeAs<'de, Element<G>> for ElementAs<F> {
+    fn deserialize_as<D: Deserializer<'de>>(deserializer: D) -> Result<Element<G>, D::Error> {
+        Element::from_bytes_be(&F::deserialize_bytes(deserializer)?).map_err(de::Error::custom)
+    }
+}
+
+impl<F: Format, G: MODPGroup> SerializeAs<Scalar<G>> for ScalarAs<F> {
+    fn serialize_as<S: Serializer>(source: &Scalar<G>, serializer: S) -> Result<S::Ok, S::Error> {
+        F::serialize_bytes(&source.to_bytes_be(), serializer)
+    }
+}
+
+impl<'de, F: Format, G: MODPGroup> DeserializeAs<'de, Scalar<G>> for ScalarAs<F> {
+    fn deserialize_as<D: Deserializer<'de>>(deserializer: D) -> Result<Scalar<G>, D::Error> {
+        Scalar::from_bytes(&F::deserialize_bytes(deserializer)?).map_err(de::Error::custom)
+    }
+}
+
+impl<F: Format, G: MODPGroup> SerializeAs<Element<G>> for PublicKeyAs<F> {
+    fn serialize_as<S: Serializer>(source: &Element<G>, serializer: S) -> Result<S::Ok, S::Error> {
+        ElementAs::<F>::serialize_as(source, serializer)
+    }
+}
+
+impl<'de, F: Format, G: MODPGroup> DeserializeAs<'de, Element<G>> for PublicKeyAs<F> {
+    fn deserialize_as<D: Deserializer<'de>>(deserializer: D) -> Result<Element<G>, D::Error> {
+        let element = ElementAs::<F>::deserialize_as(deserializer)?;
+        element.validate().map_err(de::Error::custom)?;
+        Ok(element)
+    }
+}
+
+impl<F: Format> ElementAs<F> {
+    /// Serialize an element, for `#[serde(with = ...)]`.
+    pub fn serialize<S: Serializer, G: MODPGroup>(
+        element: &Element<G>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        Self::serialize_as(element, serializer)
+    }
+
+    /// Deserialize an element, for `#[serde(with = ...)]`.
+    pub fn deserialize<'de, D: Deserializer<'de>, G: MODPGroup>(
+        deserializer: D,
+    ) -> Result<Element<G>, D::Error> {
+        Self::deserialize_as(deserializer)
+    }
+}
+
+impl<F: Format> ScalarAs<F> {
+    /// Serialize a scalar, for `#[serde(with = ...)]`.
+    pub fn serialize<S: Serializer, G: MODPGroup>(
+        scalar: &Scalar<G>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        Self::serialize_as(scalar, serializer)
+    }
+
+    /// Deserialize a scalar, for `#[serde(with = ...)]`.
+    pub fn deserialize<'de, D: Deserializer<'de>, G: MODPGroup>(
+        deserializer: D,
+    ) -> Result<Scalar<G>, D::Error> {
+        Self::deserialize_as(deserializer)
+    }
+}
+
+impl<F: Format> PublicKeyAs<F> {
+    /// Serialize a public key, for `#[serde(with = ...)]`.
+    pub fn serialize<S: Serializer, G: MODPGroup>(
+        public_key: &Element<G>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        Self::serialize_as(public_key, serializer)
+    }
+
+    /// Deserialize and validate a public key, for `#[serde(with = ...)]`.
+    pub fn deserialize<'de, D: Deserializer<'de>, G: MODPGroup>(
+        deserializer: D,
+    ) -> Result<Element<G>, D::Error> {
+        Self::deserialize_as(deserializer)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use num_bigint::BigUint;
+    use serde::{de::DeserializeOwned, Serialize};
+    use serde_json::{json, Value};
+    use serde_with::serde_as;
+
+    use super::*;
+    use crate::MODPGroup5;
+
+    #[serde_as]
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct WithSerdeAs {
+        #[serde_as(as = "ElementHex")]
+        element_hex: Element<MODPGroup5>,
+        #[serde_as(as = "ElementBase64Url")]
+        element_base64url: Element<MODPGroup5>,
+        #[serde_as(as = "ElementBytes")]
+        element_bytes: Element<MODPGroup5>,
+        #[serde_as(as = "ScalarHex")]
+        scalar_hex: Scalar<MODPGroup5>,
+        #[serde_as(as = "ScalarBase64Url")]
+        scalar_base64url: Scalar<MODPGroup5>,
+        #[serde_as(as = "ScalarBytes")]
+        scalar_bytes: Scalar<MODPGroup5>,
+        #[serde_as(as = "Vec<PublicKeyBase64Url>")]
+        public_keys: Vec<Element<MODPGroup5>>,
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct WithSerdeWith {
+        #[serde(with = "PublicKeyHex")]
+        public_hex: Element<MODPGroup5>,
+        #[serde(with = "PublicKeyBytes")]
+        public_bytes: Element<MODPGroup5>,
+        #[serde(with = "ScalarBytes")]
+        secret: Scalar<MODPGroup5>,
+    }
+
+    fn public_key() -> Element<MODPGroup5> {
+        Element::from_biguint(Scalar::<MODPGroup5>::random(&mut rand::thread_rng()).value)
+    }
+
+    fn round_trip<T: Serialize + DeserializeOwned + PartialEq + fmt::Debug>(value: &T) {
+        let json = serde_json::to_string(value).unwrap();
+        assert_eq!(serde_json::from_str::<T>(&json).unwrap(), *value);
+        let bincode = bincode::serialize(value).unwrap();
+        assert_eq!(bincode::deserialize::<T>(&bincode).unwrap(), *value);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let rng = &mut rand::thread_rng();
+        // values with leading zero bytes keep their fixed length
+        let small = Element::<MODPGroup5>::from_value(BigUint::from(0x0100u64));
+        let value = WithSerdeAs {
+            element_hex: public_key(),
+            element_base64url: Element::from_value(small.value.clone()),
+            element_bytes: small,
+            scalar_hex: Scalar::random(rng),
+            scalar_base64url: Scalar::from_biguint(1u64.into()),
+            scalar_bytes: Scalar::random(rng),
+            public_keys: vec![public_key(), public_key()],
+        };
+        round_trip(&value);
+
+        let json = serde_json::to_value(&value).unwrap();
+        let hex = json["element_hex"].as_str().unwrap();
+        assert_eq!(hex.len(), 2 * 192);
+        assert_eq!(hex, hex.to_lowercase());
+        assert_eq!(json["element_base64url"].as_str().unwrap().len(), 256);
+        assert_eq!(json["element_bytes"].as_array().unwrap().len(), 192);
+
+        round_trip(&WithSerdeWith {
+            public_hex: public_key(),
+            public_bytes: public_key(),
+            secret: Scalar::random(rng),
+        });
+    }
+
+    #[test]
+    fn test_bincode_is_compact() {
+        let value = WithSerdeWith {
+            public_hex: public_key(),
+            public_bytes: public_key(),
+            secret: Scalar::random(&mut rand::thread_rng()),
+        };
+        // length prefixes of 8 bytes, then the hexadecimal digits and the raw bytes
+        assert_eq!(
+            bincode::serialize(&value).unwrap().len(),
+            3 * 8 + 2 * 192 + 192 + 192
+        );
+    }
+
+    #[test]
+    fn test_validation() {
+        let valid = serde_json::to_value(WithSerdeWith {
+            public_hex: public_key(),
+            public_bytes: public_key(),
+            secret: Scalar::random(&mut rand::thread_rng()),
+        })
+        .unwrap();
+        let with = |name: &str, value: Value| {
+            let mut json = valid.clone();
+            json[name] = value;
+            serde_json::from_value::<WithSerdeWith>(json)
+        };
+        assert!(with("public_hex", valid["public_hex"].clone()).is_ok());
+
+        let p = MODPGroup5::prime_modulus().to_bytes_be();
+        let one = Element::<MODPGroup5>::from_value(1u64.into()).to_bytes_be();
+        let p_hex: String = p.iter().map(|b| format!("{b:02x}")).collect();
+        let one_hex: String = one.iter().map(|b| format!("{b:02x}")).collect();
+        for (name, value) in [
+            // not hexadecimal, of odd length, and too short
+            ("public_hex", json!("zz")),
+            ("public_hex", json!("abc")),
+            ("public_hex", json!("02")),
+            // out of range, and degenerate
+            ("public_hex", json!(p_hex)),
+            ("public_hex", json!(one_hex)),
+            ("public_bytes", json!(one)),
+            ("public_bytes", json!(p)),
+            // q is not a scalar
+            (
+                "secret",
+                json!(MODPGroup5::sophie_garmain_prime().to_bytes_be()),
+            ),
+        ] {
+            assert!(with(name, value.clone()).is_err(), "{name}: {value}");
+        }
+
+        // elements are only checked for their range
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct Wrapper(#[serde(with = "ElementHex")] Element<MODPGroup5>);
+        let one = Wrapper(Element::from_value(1u64.into()));
+        let json = serde_json::to_string(&one).unwrap();
+        assert_eq!(json, format!("\"{one_hex}\""));
+        assert_eq!(serde_json::from_str::<Wrapper>(&json).unwrap(), one);
+        assert!(serde_json::from_str::<Wrapper>(&format!("\"{p_hex}\"")).is_err());
+    }
+}
@@ -0,0 +1,220 @@
+//! Conversions between [Element]s and [Scalar]s and the `BigUint` of
+//! [num-bigint-dig](https://crates.io/crates/num-bigint-dig), used by crates such as `rsa` and
+//! `ssh-key`, enabled by the `bigint-dig` feature.
+//!
+//! Converting to a `num_bigint_dig::BigUint` never fails. Converting back with [TryFrom] returns
+//! [ValidationError::OutOfRange] instead of reducing the value, if it is not less than p for an
+//! element or q for a scalar. [Element::from_dig_bytes] and [Scalar::from_dig_bytes] decode the
+//! minimal big-endian bytes written by `num_bigint_dig::BigUint::to_bytes_be` directly, without
+//! building an intermediate integer or padding them to the length of [Element::from_bytes_be].
+//!
+//! # Example
+//!
+//! ```rust
+//! use diffie_hellman_groups::{group::MODPGroup14, Element};
+//! use num_bigint::BigUint;
+//!
+//! let element = Element::<MODPGroup14>::from_biguint(BigUint::from(42u64));
+//! let dig = num_bigint_dig::BigUint::from(&element);
+//! assert_eq!(Element::try_from(&dig).unwrap(), element);
+//! assert_eq!(Element::from_dig_bytes(&dig.to_bytes_be()).unwrap(), element);
+//! ```
+
+use num_bigint::BigUint;
+use num_bigint_dig::BigUint as DigBigUint;
+
+use crate::{group::MODPGroup, Element, Scalar, ValidationError};
+
+impl<G: MODPGroup> Element<G> {
+    /// Create an element from big-endian bytes of any length, e.g. written by
+    /// `num_bigint_dig::BigUint::to_bytes_be`.
+    ///
+    /// # Errors
+    /// Returns [ValidationError::OutOfRange] if the value is not less than p.
+    pub fn from_dig_bytes(bytes: &[u8]) -> Result<Self, ValidationError> {
+        checked(BigUint::from_bytes_be(bytes), &G::prime_modulus()).map(Element::from_value)
+    }
+}
+
+impl<G: MODPGroup> Scalar<G> {
+    /// Create a scalar from big-endian bytes of any length, e.g. written by
+    /// `num_bigint_dig::BigUint::to_bytes_be`.
+    ///
+    /// # Errors
+    /// Returns [ValidationError::OutOfRange] if the value is not less than q.
+    pub fn from_dig_bytes(bytes: &[u8]) -> Result<Self, ValidationError> {
+        checked(BigUint::from_bytes_be(bytes), &G::subgroup_order()).map(Scalar::from_biguint)
+    }
+}
+
+impl<G: MODPGroup> From<&Element<G>> for DigBigUint {
+    fn from(element: &Element<G>) -> Self {
+        to_dig(&element.value)
+    }
+}
+
+impl<G: MODPGroup> From<Element<G>> for DigBigUint {
+    fn from(element: Element<G>) -> Self {
+        to_dig(&element.value)
+    }
+}
+
+impl<G: MODPGroup> From<&Scalar<G>> for DigBigUint {
+    fn from(scalar: &Scalar<G>) -> Self {
+        to_dig(&scalar.value)
+    }
+}
+
+impl<G: MODPGroup> From<Scalar<G>> for DigBigUint {
+    fn from(scalar: Scalar<G>) -> Self {
+        to_dig(&scalar.value)
+    }
+}
+
+impl<G: MODPGroup> TryFrom<&DigBigUint> for Element<G> {
+    type Error = ValidationError;
+
+    fn try_from(value: &DigBigUint) -> Result<Self, Self::Error> {
+        checked(from_dig(value), &G::prime_modulus()).map(Element::from_value)
+    }
+}
+
+impl<G: MODPGroup> TryFrom<DigBigUint> for Element<G> {
+    type Error = ValidationError;
+
+    fn try_from(value: DigBigUint) -> Result<Self, Self::Error> {
+        Element::try_from(&value)
+    }
+}
+
+impl<G: MODPGroup> TryFrom<&DigBigUint> for Scalar<G> {
+    type Error = ValidationError;
+
+    fn try_from(value: &DigBigUint) -> Result<Self, Self::Error> {
+        checked(from_dig(value), &G::subgroup_order()).map(Scalar::from_biguint)
+    }
+}
+
+impl<G: MODPGroup> TryFrom<DigBigUint> for Scalar<G> {
+    type Error = ValidationError;
+
+    fn try_from(value: DigBigUint) -> Result<Self, Self::Error> {
+        Scalar::try_from(&value)
+    }
+}
+
+/// Copy the value through its little-endian bytes, the order in which both crates store digits.
+fn to_dig(value: &BigUint) -> DigBigUint {
+    DigBigUint::from_bytes_le(&value.to_bytes_le())
+}
+
+fn from_dig(value: &DigBigUint) -> BigUint {
+    BigUint::from_bytes_le(&value.to_bytes_le())
+}
+
+/// Check that `value` is less than `bound`.
+fn checked(value: BigUint, bound: &BigUint) -> Result<BigUint, ValidationError> {
+    if value >= *bound {
+        return Err(ValidationError::OutOfRange);
+    }
+    Ok(value)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::group::{
+        MODPGroup14, MODPGroup15, MODPGroup16, MODPGroup17, MODPGroup18, MODPGroup5,
+    };
+
+    fn test_round_trip<G: MODPGroup>() {
+        let rng = &mut rand::thread_rng();
+        let p = G::prime_modulus();
+        let len = p.bits().div_ceil(8) as usize;
+        // a random element, values with one and many leading zero bytes, and p - 1
+        let values = [
+            Element::<G>::from_biguint(Scalar::<G>::random(rng).value).value,
+            (BigUint::from(1u64) << (8 * (len - 1) - 1)) + 1u64,
+            BigUint::from(0x0100u64),
+            BigUint::from(1u64),
+            &p - 1u64,
+        ];
+        for value in values {
+            let element = Element::<G>::from_value(value.clone());
+            let dig = DigBigUint::from(&element);
+            assert_eq!(dig.to_bytes_be(), value.to_bytes_be());
+            assert_eq!(Element::<G>::try_from(&dig).unwrap(), element);
+            assert_eq!(
+                Element::<G>::from_dig_bytes(&dig.to_bytes_be()).unwrap(),
+                element
+            );
+            // the fixed-length encoding keeps its leading zeros
+            let padded = element.to_bytes_be();
+            assert_eq!(padded.len(), len);
+            assert_eq!(Element::<G>::from_dig_bytes(&padded).unwrap(), element);
+            assert_eq!(DigBigUint::from_bytes_be(&padded), dig);
+            assert_eq!(Element::<G>::try_from(dig).unwrap(), element);
+        }
+
+        let scalar = Scalar::<G>::random(rng);
+        let dig = DigBigUint::from(&scalar);
+        assert_eq!(Scalar::<G>::try_from(&dig).unwrap(), scalar);
+        assert_eq!(
+            Scalar::<G>::from_dig_bytes(&scalar.to_bytes_be()).unwrap(),
+            scalar
+        );
+        assert_eq!(
+            Scalar::<G>::try_from(DigBigUint::from(Scalar::<G>::from_biguint(
+                scalar.value.clone()
+            )))
+            .unwrap(),
+            scalar
+        );
+
+        // p is not an element, and q not a scalar
+        let dig_p = DigBigUint::from_bytes_be(&p.to_bytes_be());
+        assert_eq!(
+            Element::<G>::try_from(&dig_p),
+            Err(ValidationError::OutOfRange)
+        );
+        assert_eq!(
+            Element::<G>::from_dig_bytes(&p.to_bytes_be()),
+            Err(ValidationError::OutOfRange)
+        );
+        let q = G::subgroup_order();
+        assert_eq!(
+            Scalar::<G>::try_from(DigBigUint::from_bytes_be(&q.to_bytes_be())),
+            Err(ValidationError::OutOfRange)
+        );
+    }
+
+    #[test]
+    fn test_round_trip_group_5() {
+        test_round_trip::<MODPGroup5>();
+    }
+
+    #[test]
+    fn test_round_trip_group_14() {
+        test_round_trip::<MODPGroup14>();
+    }
+
+    #[test]
+    fn test_round_trip_group_15() {
+        test_round_trip::<MODPGroup15>();
+    }
+
+    #[test]
+    fn test_round_trip_group_16() {
+        test_round_trip::<MODPGroup16>();
+    }
+
+    #[test]
+    fn test_round_trip_group_17() {
+        test_round_trip::<MODPGroup17>();
+    }
+
+    #[test]
+    fn test_round_trip_group_18() {
+        test_round_trip::<MODPGroup18>();
+    }
+}
@@ -0,0 +1,383 @@
+//! Builder of [PrimeGroup] collecting the construction options.
+
+use num_bigint::BigUint;
+#[cfg(feature = "parallel")]
+use rand::Rng;
+use rand::{rngs::StdRng, SeedableRng};
+use rand_core::CryptoRngCore;
+
+#[cfg(not(feature = "parallel"))]
+use crate::primes::search_sophie_germain;
+#[cfg(feature = "parallel")]
+use crate::primes::search_sophie_germain_parallel;
+use crate::{
+    primegroup::{check_bits, sample_generator},
+    primes::PrimalityConfig,
+    DhGroupsError, PrimeGroup, ProgressHook,
+};
+
+/// How the generator of a group is chosen by [PrimeGroupBuilder].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GeneratorSpec {
+    /// Use the given generator, which must have order `q`.
+    Fixed(u64),
+    /// Sample `g = h^cofactor mod p` for `h` uniform in `[2, p - 1)`.
+    Random,
+    /// Sample `g = h^cofactor mod p` for `h` uniform in `[2, min(2^bits, p - 1))`.
+    RandomBits(u64),
+    /// Sample as in [GeneratorSpec::Random] from a [StdRng] seeded with the given seed, so that
+    /// the same modulus and seed always give the same generator (for a given version of `rand`).
+    Derived(u64),
+}
+
+#[derive(Debug, Clone)]
+enum Modulus {
+    Bits(u64),
+    SafePrime(BigUint),
+    Schnorr(BigUint, BigUint),
+    /// A prime and the prime order of a subgroup known to be prime, e.g. the safe primes of
+    /// RFC 3526 and their Sophie Germain primes, or the groups of RFC 5114.
+    Trusted(BigUint, BigUint),
+}
+
+/// Builder of a [PrimeGroup].
+///
+/// The modulus is set by one of [PrimeGroupBuilder::modulus_bits],
+/// [PrimeGroupBuilder::from_existing_prime] or [PrimeGroupBuilder::from_schnorr], and the group is
+/// created by [PrimeGroupBuilder::build].
+///
+/// # Example
+///
+/// ```rust
+/// use diffie_hellman_groups::{GeneratorSpec, PrimeGroup};
+///
+/// let pg = PrimeGroup::builder()
+///     .modulus_bits(64)
+///     .generator(GeneratorSpec::Derived(42))
+///     .build(&mut rand::thread_rng())
+///     .unwrap();
+/// assert_eq!(pg.p.bits(), 64);
+/// ```
+#[derive(Clone)]
+pub struct PrimeGroupBuilder<'a> {
+    modulus: Option<Modulus>,
+    generator: GeneratorSpec,
+    excluded_generator: Option<BigUint>,
+    primality: PrimalityConfig,
+    progress: Option<&'a dyn ProgressHook>,
+    #[cfg(feature = "parallel")]
+    threads: Option<usize>,
+}
+
+impl Default for PrimeGroupBuilder<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a> PrimeGroupBuilder<'a> {
+    /// Create a builder with a uniformly sampled generator and the default primality tests.
+    pub fn new() -> Self {
+        Self {
+            modulus: None,
+            generator: GeneratorSpec::Random,
+            excluded_generator: None,
+            primality: PrimalityConfig::default(),
+            progress: None,
+            #[cfg(feature = "parallel")]
+            threads: None,
+        }
+    }
+
+    /// Generate a random safe prime modulus of exactly `bits` bits, as in [PrimeGroup::generate].
+    pub fn modulus_bits(mut self, bits: u64) -> Self {
+        self.modulus = Some(Modulus::Bits(bits));
+        self
+    }
+
+    /// Use the safe prime `p`, with `q = (p - 1) / 2`.
+    pub fn from_existing_prime(mut self, p: BigUint) -> Self {
+        self.modulus = Some(Modulus::SafePrime(p));
+        self
+    }
+
+    /// Use the subgroup of prime order `q` modulo the prime `p`, where `q` divides `p - 1`.
+    pub fn from_schnorr(mut self, p: BigUint, q: BigUint) -> Self {
+        self.modulus = Some(Modulus::Schnorr(p, q));
+        self
+    }
+
+    pub(crate) fn trusted(mut self, p: BigUint, q: BigUint) -> Self {
+        self.modulus = Some(Modulus::Trusted(p, q));
+        self
+    }
+
+    /// Choose how the generator is picked. Defaults to [GeneratorSpec::Random].
+    pub fn generator(mut self, generator: GeneratorSpec) -> Self {
+        self.generator = generator;
+        self
+    }
+
+    /// Resample a random generator if it equals `g`.
+    pub(crate) fn exclude_generator(mut self, g: BigUint) -> Self {
+        self.excluded_generator = Some(g);
+        self
+    }
+
+    /// Set the primality tests run on generated and given primes.
+    pub fn primality(mut self, config: PrimalityConfig) -> Self {
+        self.primality = config;
+        self
+    }
+
+    /// Notify `hook` during the search of a safe prime, see [PrimeGroup::generate].
+    pub fn progress(mut self, hook: &'a dyn ProgressHook) -> Self {
+        self.progress = Some(hook);
+        self
+    }
+
+    /// Search safe primes on a dedicated pool of `threads` threads, see
+    /// [PrimeGroup::generate_parallel]. By default, the global rayon pool is used.
+    #[cfg(feature = "parallel")]
+    pub fn threads(mut self, threads: usize) -> Self {
+        self.threads = Some(threads);
+        self
+    }
+
+    /// Create the group, drawing randomness from `rng`.
+    ///
+    /// # Errors
+    /// Returns
+    /// - [DhGroupsError::MissingModulus] if the modulus was not set.
+    /// - [DhGroupsError::BitsOutOfRange] if the modulus bits are less than 3, or the bits of
+    ///   [GeneratorSpec::RandomBits] are less than 2 or more than the bits of the modulus.
+    /// - [DhGroupsError::NotSafePrime] if the prime given to
+    ///   [PrimeGroupBuilder::from_existing_prime] is not a safe prime.
+    /// - [DhGroupsError::NotPrime] or [DhGroupsError::InvalidCofactor] if the primes given to
+    ///   [PrimeGroupBuilder::from_schnorr] are not primes or `q` does not divide `p - 1`.
+    /// - [DhGroupsError::Cancelled] if the search is cancelled by the progress hook.
+    /// - [DhGroupsError::InvalidGenerator] if a [GeneratorSpec::Fixed] generator does not have
+    ///   order `q`.
+    /// - [DhGroupsError::InvalidThreadCount] if the number of threads set with the `parallel`
+    ///   feature is zero, or [DhGroupsError::ThreadPool] if their pool cannot be built.
+    pub fn build<R: CryptoRngCore + ?Sized>(
+        mut self,
+        rng: &mut R,
+    ) -> Result<PrimeGroup, DhGroupsError> {
+        let modulus = self.modulus.take().ok_or(DhGroupsError::MissingModulus)?;
+        let (p, q, cofactor) = match modulus {
+            Modulus::Bits(bits) => {
+                if bits < 3 {
+                    return Err(DhGroupsError::BitsOutOfRange);
+                }
+                self.check_generator_bits(bits)?;
+                let (q, p) = self.search(bits, rng)?;
+                (p, q, BigUint::from(2u64))
+            }
+            Modulus::SafePrime(p) => {
+                self.check_generator_bits(p.bits())?;
+                let q = (&p - 1u64) >> 1;
+                if &q * 2u64 + 1u64 != p
+                    || !self.primality.is_probable_prime(&q)
+                    || !self.primality.is_probable_prime(&p)
+                {
+                    return Err(DhGroupsError::NotSafePrime);
+                }
+                (p, q, BigUint::from(2u64))
+            }
+            Modulus::Schnorr(p, q) => {
+                self.check_generator_bits(p.bits())?;
+                if !self.primality.is_probable_prime(&p) || !self.primality.is_probable_prime(&q) {
+                    return Err(DhGroupsError::NotPrime);
+                }
+                let p_minus_one = &p - 1u64;
+                if (&p_minus_one % &q) != BigUint::from(0u64) {
+                    return Err(DhGroupsError::InvalidCofactor);
+                }
+                let cofactor = &p_minus_one / &q;
+                (p, q, cofactor)
+            }
+            Modulus::Trusted(p, q) => {
+                self.check_generator_bits(p.bits())?;
+                let cofactor = (&p - 1u64) / &q;
+                (p, q, cofactor)
+            }
+        };
+
+        let accept = |g: &BigUint| self.excluded_generator.as_ref() != Some(g);
+        let g = match self.generator {
+            GeneratorSpec::Fixed(g) => BigUint::from(g),
+            GeneratorSpec::Random => sample_generator(&p, &cofactor, p.bits(), rng, accept),
+            GeneratorSpec::RandomBits(bits) => sample_generator(&p, &cofactor, bits, rng, accept),
+            GeneratorSpec::Derived(seed) => {
+                let rng = &mut StdRng::seed_from_u64(seed);
+                sample_generator(&p, &cofactor, p.bits(), rng, accept)
+            }
+        };
+
+        let group = PrimeGroup { p, q, g, cofactor };
+        group.verify()?;
+        Ok(group)
+    }
+
+    fn check_generator_bits(&self, modulus_bits: u64) -> Result<(), DhGroupsError> {
+        match self.generator {
+            GeneratorSpec::RandomBits(bits) => check_bits(bits, modulus_bits),
+            _ => Ok(()),
+        }
+    }
+
+    #[cfg(not(feature = "parallel"))]
+    fn search<R: CryptoRngCore + ?Sized>(
+        &self,
+        bits: u64,
+        rng: &mut R,
+    ) -> Result<(BigUint, BigUint), DhGroupsError> {
+        search_sophie_germain(bits - 1, rng, &self.primality, self.progress)
+    }
+
+    #[cfg(feature = "parallel")]
+    fn search<R: CryptoRngCore + ?Sized>(
+        &self,
+        bits: u64,
+        rng: &mut R,
+    ) -> Result<(BigUint, BigUint), DhGroupsError> {
+        match self.threads {
+            Some(0) => Err(DhGroupsError::InvalidThreadCount),
+            Some(threads) => {
+                let pool = rayon::ThreadPoolBuilder::new()
+                    .num_threads(threads)
+                    .build()
+                    .map_err(|_| DhGroupsError::ThreadPool)?;
+                let seeds: Vec<u64> = (0..threads).map(|_| rng.gen()).collect();
+                search_sophie_germain_parallel(
+                    bits - 1,
+                    &seeds,
+                    &self.primality,
+                    self.progress,
+                    Some(&pool),
+                )
+            }
+            None => {
+                let seeds: Vec<u64> = (0..rayon::current_num_threads())
+                    .map(|_| rng.gen())
+                    .collect();
+                search_sophie_germain_parallel(
+                    bits - 1,
+                    &seeds,
+                    &self.primality,
+                    self.progress,
+                    None,
+                )
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_fixed_generator() {
+        // 2 is a quadratic residue modulo 23, so it has order 11
+        let pg = PrimeGroup::builder()
+            .from_existing_prime(BigUint::from(23u64))
+            .generator(GeneratorSpec::Fixed(2))
+            .build(&mut rand::thread_rng())
+            .unwrap();
+        assert_eq!(pg.g, BigUint::from(2u64));
+        assert_eq!(pg.q, BigUint::from(11u64));
+
+        // 5 has order 22
+        let result = PrimeGroup::builder()
+            .from_existing_prime(BigUint::from(23u64))
+            .generator(GeneratorSpec::Fixed(5))
+            .build(&mut rand::thread_rng());
+        assert_eq!(result.unwrap_err(), DhGroupsError::InvalidGenerator);
+    }
+
+    #[test]
+    fn test_derived_generator() {
+        let builder = PrimeGroup::builder().from_existing_prime(BigUint::from(1623299u64));
+        let derive = |seed| {
+            builder
+                .clone()
+                .generator(GeneratorSpec::Derived(seed))
+                .build(&mut rand::thread_rng())
+                .unwrap()
+                .g
+        };
+        assert_eq!(derive(1), derive(1));
+        assert_ne!(derive(1), derive(2));
+    }
+
+    #[test]
+    fn test_generated_modulus() {
+        let pg = PrimeGroup::builder()
+            .modulus_bits(96)
+            .generator(GeneratorSpec::RandomBits(16))
+            .primality(PrimalityConfig::strict())
+            .build(&mut rand::thread_rng())
+            .unwrap();
+        assert_eq!(pg.p.bits(), 96);
+        assert!(pg.validate().is_ok());
+
+        // the generator is the square of an element of at most 16 bits
+        assert!(pg.g.bits() <= 32);
+    }
+
+    #[test]
+    fn test_schnorr() {
+        let pg = PrimeGroup::builder()
+            .from_schnorr(BigUint::from(3571u64), BigUint::from(17u64))
+            .build(&mut rand::thread_rng())
+            .unwrap();
+        assert_eq!(pg.cofactor, BigUint::from(210u64));
+        assert!(pg.validate().is_ok());
+    }
+
+    #[test]
+    fn test_errors() {
+        let rng = &mut rand::thread_rng();
+        assert_eq!(
+            PrimeGroup::builder().build(rng).unwrap_err(),
+            DhGroupsError::MissingModulus
+        );
+        assert_eq!(
+            PrimeGroup::builder()
+                .modulus_bits(2)
+                .build(rng)
+                .unwrap_err(),
+            DhGroupsError::BitsOutOfRange
+        );
+        assert_eq!(
+            PrimeGroup::builder()
+                .modulus_bits(64)
+                .generator(GeneratorSpec::RandomBits(65))
+                .build(rng)
+                .unwrap_err(),
+            DhGroupsError::BitsOutOfRange
+        );
+        assert_eq!(
+            PrimeGroup::builder()
+                .from_existing_prime(BigUint::from(24u64))
+                .build(rng)
+                .unwrap_err(),
+            DhGroupsError::NotSafePrime
+        );
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn test_zero_threads() {
+        assert_eq!(
+            PrimeGroup::builder()
+                .modulus_bits(64)
+                .threads(0)
+                .build(&mut rand::thread_rng())
+                .unwrap_err(),
+            DhGroupsError::InvalidThreadCount
+        );
+    }
+}
@@ -0,0 +1,423 @@
+//! CBOR (RFC 8949) encoding of [Element]s and [Scalar]s, and COSE_Key (RFC 9052) export and
+//! import of [PrimeGroup] parameters and [RuntimePublicKey]s, enabled by the `cbor` feature.
+//!
+//! COSE registers no key type for finite field Diffie-Hellman, so the keys use the private-use key
+//! type [KEY_TYPE] with the following integer labels, each a byte string of the minimal
+//! big-endian value, as for the RSA parameters of RFC 8230:
+//! - `1`: the key type [KEY_TYPE],
+//! - `-1`: the prime modulus p,
+//! - `-2`: the generator g,
+//! - `-3`: the order q of the subgroup, optional, `(p - 1) / 2` if absent,
+//! - `-4`: the public key y, absent from parameters.
+//!
+//! The output is deterministically encoded as in section 4.2.1 of RFC 8949: integers and lengths
+//! take their shortest form and the labels are sorted in the bytewise order of their encoding,
+//! i.e. `1, -1, -2, -3, -4`, so that signatures over the encoding are stable. Other labels, e.g.
+//! `2` (kid), are ignored when parsing. The parsed group is checked with [PrimeGroup::validate].
+//! The public key is imported as an [UnvalidatedRuntimePublicKey], to be checked with
+//! [UnvalidatedRuntimePublicKey::validate].
+//!
+//! [Element::to_cbor] and [Scalar::to_cbor] encode a single CBOR byte string of the fixed-length
+//! bytes of [Element::to_bytes_be] and [Scalar::to_bytes_be], which any CBOR library decodes.
+//!
+//! # Example
+//!
+//! ```rust
+//! use std::sync::Arc;
+//! use diffie_hellman_groups::{group::MODPGroup14, PrimeGroup, UnvalidatedRuntimePublicKey};
+//!
+//! let group = Arc::new(PrimeGroup::new::<MODPGroup14>(256).unwrap());
+//! let (_, pk) = group.generate_keypair(&mut rand::thread_rng());
+//!
+//! let cose_key = pk.to_cose_key();
+//! let imported = UnvalidatedRuntimePublicKey::from_cose_key(&cose_key).unwrap();
+//! assert_eq!(imported.validate().unwrap(), pk);
+//! let imported = UnvalidatedRuntimePublicKey::from_cose_key_in(&group, &cose_key).unwrap();
+//! assert_eq!(imported.validate().unwrap(), pk);
+//! ```
+
+use std::sync::Arc;
+
+use ciborium::Value;
+use num_bigint::BigUint;
+
+use crate::{
+    group::MODPGroup, DhGroupsError, Element, PrimeGroup, RuntimePublicKey, Scalar,
+    UnvalidatedRuntimePublicKey,
+};
+
+/// Key type of the COSE keys, in the range of private use of the COSE Key Types registry.
+pub const KEY_TYPE: i64 = -65537;
+
+/// Label of the key type, common to all COSE keys.
+const LABEL_KTY: i64 = 1;
+/// Label of the prime modulus p.
+const LABEL_P: i64 = -1;
+/// Label of the generator g.
+const LABEL_G: i64 = -2;
+/// Label of the order q.
+const LABEL_Q: i64 = -3;
+/// Label of the public key y.
+const LABEL_Y: i64 = -4;
+
+impl<G: MODPGroup> Element<G> {
+    /// Encode the element as a CBOR byte string of [Element::to_bytes_be].
+    pub fn to_cbor(&self) -> Vec<u8> {
+        encode(&Value::Bytes(self.to_bytes_be()))
+    }
+
+    /// Decode an element written by [Element::to_cbor].
+    ///
+    /// # Errors
+    /// Returns [DhGroupsError::InvalidCbor] if the input is not a single CBOR byte string, or the
+    /// errors of [Element::from_bytes_be].
+    pub fn from_cbor(cbor: &[u8]) -> Result<Self, DhGroupsError> {
+        Element::from_bytes_be(&decode_bytes(cbor)?)
+    }
+}
+
+impl<G: MODPGroup> Scalar<G> {
+    /// Encode the scalar as a CBOR byte string of [Scalar::to_bytes_be].
+    pub fn to_cbor(&self) -> Vec<u8> {
+        encode(&Value::Bytes(self.to_bytes_be()))
+    }
+
+    /// Decode a scalar written by [Scalar::to_cbor].
+    ///
+    /// # Errors
+    /// Returns [DhGroupsError::InvalidCbor] if the input is not a single CBOR byte string, or the
+    /// errors of [Scalar::from_bytes].
+    pub fn from_cbor(cbor: &[u8]) -> Result<Self, DhGroupsError> {
+        Scalar::from_bytes(&decode_bytes(cbor)?)
+    }
+}
+
+impl PrimeGroup {
+    /// Export the parameters as a COSE_Key with the labels `1` (kty), `-1` (p), `-2` (g) and
+    /// `-3` (q).
+    pub fn to_cose_key(&self) -> Vec<u8> {
+        encode(&Value::Map(group_entries(self)))
+    }
+
+    /// Import parameters from a COSE_Key.
+    ///
+    /// # Errors
+    /// Returns [DhGroupsError::InvalidCbor] if the input is not a CBOR map of the key type
+    /// [KEY_TYPE] with the byte strings `-1` (p) and `-2` (g), and optionally `-3` (q), or the
+    /// error of [PrimeGroup::validate] if the parameters are invalid.
+    pub fn from_cose_key(cose_key: &[u8]) -> Result<Self, DhGroupsError> {
+        parse_group(&parse_map(cose_key)?)
+    }
+}
+
+impl RuntimePublicKey {
+    /// Export the key and its group as a COSE_Key with the labels `1` (kty), `-1` (p), `-2` (g),
+    /// `-3` (q) and `-4` (y).
+    pub fn to_cose_key(&self) -> Vec<u8> {
+        let mut entries = group_entries(self.group());
+        entries.push(entry(LABEL_Y, self.value()));
+        encode(&Value::Map(entries))
+    }
+}
+
+impl UnvalidatedRuntimePublicKey {
+    /// Import a public key and its group from a COSE_Key.
+    ///
+    /// # Errors
+    /// Returns the errors of [PrimeGroup::from_cose_key], [DhGroupsError::InvalidCbor] if the
+    /// label `-4` (y) is missing or malformed, or [DhGroupsError::InputTooLong] if it is longer
+    /// than p.
+    pub fn from_cose_key(cose_key: &[u8]) -> Result<Self, DhGroupsError> {
+        let entries = parse_map(cose_key)?;
+        let group = Arc::new(parse_group(&entries)?);
+        parse_key(group, &entries)
+    }
+
+    /// Import a public key of `group` from a COSE_Key. The group of the key is compared to
+    /// `group` instead of being validated.
+    ///
+    /// # Errors
+    /// Returns [DhGroupsError::InvalidCbor] if the input is malformed,
+    /// [DhGroupsError::InputTooLong] if the key is longer than p, or
+    /// [DhGroupsError::GroupMismatch] if the parameters of the key are not those of `group`.
+    pub fn from_cose_key_in(
+        group: &Arc<PrimeGroup>,
+        cose_key: &[u8],
+    ) -> Result<Self, DhGroupsError> {
+        let entries = parse_map(cose_key)?;
+        let (p, g, q) = group_values(&entries)?;
+        let q = q.unwrap_or_else(|| (&p - 1u64) >> 1);
+        if p != group.p || g != group.g || q != group.q {
+            return Err(DhGroupsError::GroupMismatch);
+        }
+        parse_key(group.clone(), &entries)
+    }
+}
+
+/// The entries `1` (kty), `-1` (p), `-2` (g) and `-3` (q) of `group`, in deterministic order.
+fn group_entries(group: &PrimeGroup) -> Vec<(Value, Value)> {
+    vec![
+        (Value::from(LABEL_KTY), Value::from(KEY_TYPE)),
+        entry(LABEL_P, &group.p),
+        entry(LABEL_G, &group.g),
+        entry(LABEL_Q, &group.q),
+    ]
+}
+
+/// The entry `label` of the minimal big-endian bytes of `value`.
+fn entry(label: i64, value: &BigUint) -> (Value, Value) {
+    (Value::from(label), Value::Bytes(value.to_bytes_be()))
+}
+
+/// Encode `value` in CBOR.
+fn encode(value: &Value) -> Vec<u8> {
+    let mut cbor = Vec::new();
+    ciborium::into_writer(value, &mut cbor).expect("writing to a Vec never fails");
+    cbor
+}
+
+/// Decode a single CBOR data item, with no trailing bytes.
+fn decode(mut cbor: &[u8]) -> Result<Value, DhGroupsError> {
+    match ciborium::from_reader(&mut cbor) {
+        Ok(value) if cbor.is_empty() => Ok(value),
+        _ => Err(DhGroupsError::InvalidCbor),
+    }
+}
+
+/// Decode a single CBOR byte string.
+fn decode_bytes(cbor: &[u8]) -> Result<Vec<u8>, DhGroupsError> {
+    match decode(cbor)? {
+        Value::Bytes(bytes) => Ok(bytes),
+        _ => Err(DhGroupsError::InvalidCbor),
+    }
+}
+
+/// Parse a CBOR map of the key type [KEY_TYPE].
+fn parse_map(cose_key: &[u8]) -> Result<Vec<(Value, Value)>, DhGroupsError> {
+    match decode(cose_key)? {
+        Value::Map(entries) if get(&entries, LABEL_KTY) == Some(&Value::from(KEY_TYPE)) => {
+            Ok(entries)
+        }
+        _ => Err(DhGroupsError::InvalidCbor),
+    }
+}
+
+/// The value of the entry `label`.
+fn get(entries: &[(Value, Value)], label: i64) -> Option<&Value> {
+    let label = Value::from(label);
+    entries
+        .iter()
+        .find(|(key, _)| *key == label)
+        .map(|(_, value)| value)
+}
+
+/// Decode the entries `-1` (p), `-2` (g) and the optional `-3` (q).
+fn group_values(
+    entries: &[(Value, Value)],
+) -> Result<(BigUint, BigUint, Option<BigUint>), DhGroupsError> {
+    let p = decode_entry(entries, LABEL_P)?;
+    let g = decode_entry(entries, LABEL_G)?;
+    let q = match get(entries, LABEL_Q) {
+        Some(_) => Some(decode_entry(entries, LABEL_Q)?),
+        None => None,
+    };
+    Ok((p, g, q))
+}
+
+/// Build and validate the group of the entries.
+fn parse_group(entries: &[(Value, Value)]) -> Result<PrimeGroup, DhGroupsError> {
+    let (p, g, q) = group_values(entries)?;
+    PrimeGroup::from_parameters(p, g, q)
+}
+
+/// Decode the entry `-4` (y) as a public key of `group`.
+fn parse_key(
+    group: Arc<PrimeGroup>,
+    entries: &[(Value, Value)],
+) -> Result<UnvalidatedRuntimePublicKey, DhGroupsError> {
+    let y = decode_entry(entries, LABEL_Y)?;
+    group.check_encoded_len(y.bits().div_ceil(8) as usize)?;
+    Ok(UnvalidatedRuntimePublicKey::from_biguint(group, y))
+}
+
+/// Decode the non-empty byte string of the entry `label`.
+fn decode_entry(entries: &[(Value, Value)], label: i64) -> Result<BigUint, DhGroupsError> {
+    match get(entries, label) {
+        Some(Value::Bytes(bytes)) if !bytes.is_empty() => Ok(BigUint::from_bytes_be(bytes)),
+        _ => Err(DhGroupsError::InvalidCbor),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{MODPGroup14, MODPGroup5, ValidationError};
+
+    /// The group of order 11 generated by 4 modulo 23.
+    fn small_group() -> Arc<PrimeGroup> {
+        Arc::new(PrimeGroup::from_parameters(23u64.into(), 4u64.into(), None).unwrap())
+    }
+
+    /// Import and validate a public key.
+    fn import(cose_key: &[u8]) -> RuntimePublicKey {
+        UnvalidatedRuntimePublicKey::from_cose_key(cose_key)
+            .unwrap()
+            .validate()
+            .unwrap()
+    }
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{b:02x}")).collect()
+    }
+
+    fn unhex(hex: &str) -> Vec<u8> {
+        (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_pinned_cose_key() {
+        let group = small_group();
+        // {1: -65537, -1: h'17', -2: h'04', -3: h'0b'}
+        let params = "a4013a0001000020411721410422410b";
+        assert_eq!(hex(&group.to_cose_key()), params);
+        assert_eq!(PrimeGroup::from_cose_key(&unhex(params)).unwrap(), *group);
+
+        // y = 4^3 mod 23 = 18, with the label -4 last
+        let pk = UnvalidatedRuntimePublicKey::from_biguint(group.clone(), 18u64.into())
+            .validate()
+            .unwrap();
+        let key = "a5013a0001000020411721410422410b234112";
+        assert_eq!(hex(&pk.to_cose_key()), key);
+        assert_eq!(import(&unhex(key)), pk);
+        assert_eq!(
+            UnvalidatedRuntimePublicKey::from_cose_key_in(&group, &unhex(key)).unwrap(),
+            pk.clone().into()
+        );
+
+        // the order of the labels and other labels do not matter when parsing, and q is optional
+        // {2: h'6b6964', -4: h'12', -2: h'04', -1: h'17', 1: -65537}
+        let reordered = "a502436b6964234112214104204117013a00010000";
+        assert_eq!(import(&unhex(reordered)), pk);
+    }
+
+    #[test]
+    fn test_pinned_element_and_scalar() {
+        let element = Element::<MODPGroup5>::from_value(2u64.into());
+        let pinned = format!("58c0{}02", "00".repeat(191));
+        assert_eq!(hex(&element.to_cbor()), pinned);
+        assert_eq!(
+            Element::<MODPGroup5>::from_cbor(&unhex(&pinned)).unwrap(),
+            element
+        );
+
+        let scalar = Scalar::<MODPGroup5>::from_biguint(0x0102u64.into());
+        let pinned = format!("58c0{}0102", "00".repeat(190));
+        assert_eq!(hex(&scalar.to_cbor()), pinned);
+        assert_eq!(
+            Scalar::<MODPGroup5>::from_cbor(&unhex(&pinned)).unwrap(),
+            scalar
+        );
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let rng = &mut rand::thread_rng();
+        let scalar = Scalar::<MODPGroup14>::random(rng);
+        let element = Element::<MODPGroup14>::from_biguint(scalar.value.clone());
+        assert_eq!(
+            Element::<MODPGroup14>::from_cbor(&element.to_cbor()).unwrap(),
+            element
+        );
+        assert_eq!(
+            Scalar::<MODPGroup14>::from_cbor(&scalar.to_cbor()).unwrap(),
+            scalar
+        );
+
+        // a group whose order is not (p - 1) / 2
+        let group = Arc::new(PrimeGroup::new_schnorr(3571u64.into(), 17u64.into()).unwrap());
+        let (_, pk) = group.generate_keypair(rng);
+        assert_eq!(import(&pk.to_cose_key()), pk);
+        assert_eq!(
+            PrimeGroup::from_cose_key(&group.to_cose_key()).unwrap(),
+            *group
+        );
+        // the encoding is deterministic
+        assert_eq!(pk.to_cose_key(), pk.to_cose_key());
+        // parameters are not a public key
+        assert_eq!(
+            UnvalidatedRuntimePublicKey::from_cose_key(&group.to_cose_key()).unwrap_err(),
+            DhGroupsError::InvalidCbor
+        );
+    }
+
+    #[test]
+    fn test_invalid_input() {
+        let element = Element::<MODPGroup5>::from_value(2u64.into()).to_cbor();
+        let mut trailing = element.clone();
+        trailing.push(0);
+        for cbor in [
+            &[][..],
+            &element[..element.len() - 1],
+            &trailing,
+            &unhex("02"),
+        ] {
+            assert_eq!(
+                Element::<MODPGroup5>::from_cbor(cbor).unwrap_err(),
+                DhGroupsError::InvalidCbor
+            );
+        }
+        // a byte string of the wrong length
+        assert_eq!(
+            Element::<MODPGroup5>::from_cbor(&unhex("4102")).unwrap_err(),
+            DhGroupsError::UnexpectedEof
+        );
+
+        for cose_key in [
+            "",
+            "80",
+            // another key type
+            "a4010320411721410422410b",
+            // no key type
+            "a320411721410422410b",
+            // p as an integer
+            "a4013a00010000201721410422410b",
+            // an empty y
+            "a5013a0001000020411721410422410b2340",
+        ] {
+            assert_eq!(
+                UnvalidatedRuntimePublicKey::from_cose_key(&unhex(cose_key)).unwrap_err(),
+                DhGroupsError::InvalidCbor,
+                "{cose_key}"
+            );
+        }
+
+        // values out of range or not in the group are imported, but not validated
+        let validate = |y: &str| {
+            let cose_key = unhex(&format!("a5013a0001000020411721410422410b23{y}"));
+            UnvalidatedRuntimePublicKey::from_cose_key(&cose_key)
+                .unwrap()
+                .validate()
+        };
+        assert_eq!(validate("4116"), Err(ValidationError::Degenerate));
+        assert_eq!(validate("4117"), Err(ValidationError::OutOfRange));
+        assert_eq!(validate("4105"), Err(ValidationError::NotInSubgroup));
+        assert_eq!(
+            PrimeGroup::from_cose_key(&unhex("a4013a0001000020411721410122410b")).unwrap_err(),
+            DhGroupsError::InvalidGenerator
+        );
+    }
+
+    #[test]
+    fn test_group_mismatch() {
+        let rng = &mut rand::thread_rng();
+        let (_, pk) = small_group().generate_keypair(rng);
+        let group = Arc::new(PrimeGroup::new_schnorr(3571u64.into(), 17u64.into()).unwrap());
+        assert_eq!(
+            UnvalidatedRuntimePublicKey::from_cose_key_in(&group, &pk.to_cose_key()).unwrap_err(),
+            DhGroupsError::GroupMismatch
+        );
+    }
+}
@@ -0,0 +1,146 @@
+//! Joint coin flipping by commitment: two parties agree on 32 uniformly random bytes that neither
+//! of them can bias.
+//!
+//! 1. The initiator A picks a random `r_A` and sends the hash commitment `D(dst || r_A)`, where
+//!    `dst` names `D` ([Initiator::new]).
+//! 2. The responder B picks a random `r_B` and reveals it ([Responder::new]).
+//! 3. A opens its commitment by sending `r_A` ([Initiator::open]), and both output
+//!    `r_A XOR r_B`. B checks the opening against the commitment ([Responder::finish]).
+//!
+//! A cannot change `r_A` after seeing `r_B`, and B learns nothing about `r_A` before revealing
+//! `r_B`. A can still abort after seeing the outcome, which B then detects as a missing opening.
+//!
+//! # Example
+//!
+//! ```rust
+//! use sha2::Sha256;
+//! use diffie_hellman_groups::cointoss::{Initiator, Responder};
+//!
+//! let rng = &mut rand::thread_rng();
+//! let (initiator, commitment) = Initiator::new::<Sha256, _>(rng);
+//! let (responder, reveal) = Responder::new(commitment, rng);
+//! let (coin_a, opening) = initiator.open(&reveal);
+//! let coin_b = responder.finish::<Sha256>(&opening).unwrap();
+//! assert_eq!(coin_a, coin_b);
+//! ```
+
+use rand::Rng;
+use rand_core::CryptoRngCore;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{hash::domain_tag, CheatDetected, NamedDigest};
+
+/// Domain separation tag of the commitments.
+const COMMITMENT_DST: &[u8] = b"diffie-hellman-groups cointoss commitment";
+
+/// The first message, the commitment `D(dst || r_A)` of the initiator.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Commitment {
+    pub digest: Vec<u8>,
+}
+
+/// The second message, the random value `r_B` of the responder.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Reveal {
+    pub value: [u8; 32],
+}
+
+/// The third message, the random value `r_A` of the initiator, opening its commitment.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Opening {
+    pub value: [u8; 32],
+}
+
+/// The initiator after sending its [Commitment], holding `r_A`.
+pub struct Initiator {
+    value: [u8; 32],
+}
+
+/// The responder after sending its [Reveal], holding the commitment and `r_B`.
+pub struct Responder {
+    commitment: Commitment,
+    value: [u8; 32],
+}
+
+impl Initiator {
+    /// Start the protocol, returning the initiator and the commitment to send to the responder.
+    pub fn new<D: NamedDigest, R: CryptoRngCore + ?Sized>(rng: &mut R) -> (Self, Commitment) {
+        let value: [u8; 32] = rng.gen();
+        (Initiator { value }, commit::<D>(&value))
+    }
+
+    /// Open the commitment after receiving the value of the responder, returning the coin and
+    /// the opening to send.
+    pub fn open(self, reveal: &Reveal) -> ([u8; 32], Opening) {
+        (
+            xor(&self.value, &reveal.value),
+            Opening { value: self.value },
+        )
+    }
+}
+
+impl Responder {
+    /// Answer the commitment of the initiator, returning the responder and the value to reveal.
+    pub fn new<R: CryptoRngCore + ?Sized>(commitment: Commitment, rng: &mut R) -> (Self, Reveal) {
+        let value: [u8; 32] = rng.gen();
+        (Responder { commitment, value }, Reveal { value })
+    }
+
+    /// Check the opening of the initiator against its commitment, returning the coin.
+    ///
+    /// # Errors
+    /// Returns [CheatDetected] if the opening does not match the commitment.
+    pub fn finish<D: NamedDigest>(self, opening: &Opening) -> Result<[u8; 32], CheatDetected> {
+        if commit::<D>(&opening.value) != self.commitment {
+            return Err(CheatDetected);
+        }
+        Ok(xor(&opening.value, &self.value))
+    }
+}
+
+fn commit<D: NamedDigest>(value: &[u8; 32]) -> Commitment {
+    Commitment {
+        digest: D::new()
+            .chain_update(domain_tag::<D>(COMMITMENT_DST))
+            .chain_update(value)
+            .finalize()
+            .to_vec(),
+    }
+}
+
+fn xor(a: &[u8; 32], b: &[u8; 32]) -> [u8; 32] {
+    std::array::from_fn(|i| a[i] ^ b[i])
+}
+
+#[cfg(test)]
+mod test {
+    use sha2::Sha256;
+
+    use super::*;
+
+    #[test]
+    fn test_honest_flow() {
+        let rng = &mut rand::thread_rng();
+        let (initiator, commitment) = Initiator::new::<Sha256, _>(rng);
+        let (responder, reveal) = Responder::new(commitment, rng);
+        let (coin_a, opening) = initiator.open(&reveal);
+        assert_eq!(responder.finish::<Sha256>(&opening), Ok(coin_a));
+        assert_eq!(coin_a, xor(&opening.value, &reveal.value));
+    }
+
+    #[test]
+    fn test_mismatched_opening() {
+        let rng = &mut rand::thread_rng();
+        let (initiator, commitment) = Initiator::new::<Sha256, _>(rng);
+        let (responder, reveal) = Responder::new(commitment, rng);
+        let (_, mut opening) = initiator.open(&reveal);
+
+        // the initiator tries to change r_A after seeing r_B
+        opening.value[0] ^= 1;
+        assert_eq!(responder.finish::<Sha256>(&opening), Err(CheatDetected));
+    }
+}
@@ -0,0 +1,228 @@
+//! Pedersen commitments `C = g^v * h^r` to a value `v` with a blinding `r`, and the proof of
+//! knowledge of their opening.
+//!
+//! The commitment is perfectly hiding, and binding as long as nobody knows the discrete logarithm
+//! of `h` to the base `g`. [PedersenParams::new] therefore derives `h` by hashing a seed to the
+//! subgroup of order q, so that anyone can check that it was not chosen with a trapdoor.
+//!
+//! The opening proof is the Schnorr proof of knowledge of a representation over two generators,
+//! made non-interactive with the Fiat-Shamir transform: the prover picks `k1, k2`, computes
+//! `t = g^k1 * h^k2`, the challenge `c = D(g || h || C || t)` and the responses `s1 = k1 + c v`
+//! and `s2 = k2 + c r`. The verifier recomputes `t = g^s1 * h^s2 * C^(-c)` and the challenge.
+//! The parameters are part of the challenge, so a proof does not verify under other parameters.
+//!
+//! # Example
+//!
+//! ```rust
+//! use sha2::Sha256;
+//! use diffie_hellman_groups::{
+//!     commitment::{self, PedersenParams}, group::MODPGroup5, Scalar,
+//! };
+//!
+//! let rng = &mut rand::thread_rng();
+//! let params = PedersenParams::<MODPGroup5>::new::<Sha256>(b"my-protocol-v1");
+//! let (value, blinding) = (Scalar::random(rng), Scalar::random(rng));
+//! let c = params.commit(&value, &blinding);
+//!
+//! let proof = commitment::prove_opening::<_, Sha256, _>(&value, &blinding, &params, rng);
+//! assert!(commitment::verify_opening_proof::<_, Sha256>(&c, &params, &proof));
+//! ```
+
+use num_bigint::BigUint;
+use rand_core::CryptoRngCore;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{group::MODPGroup, hash::hash_to_subgroup, Element, NamedDigest, Scalar};
+
+/// Domain separation tag of the generator `h`.
+const GENERATOR_DST: &[u8] = b"diffie-hellman-groups pedersen h";
+
+/// The generators `g` and `h` of the commitments.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(bound = ""))]
+pub struct PedersenParams<G: MODPGroup> {
+    pub g: Element<G>,
+    pub h: Element<G>,
+}
+
+/// A proof of knowledge of the opening of a commitment, as the challenge `c` and the responses
+/// `s1` and `s2` for the value and the blinding.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(bound = ""))]
+pub struct OpeningProof<G: MODPGroup> {
+    pub c: Scalar<G>,
+    pub s1: Scalar<G>,
+    pub s2: Scalar<G>,
+}
+
+impl<G: MODPGroup> PedersenParams<G> {
+    /// The generator of the group as `g`, and `seed` hashed to the subgroup of order q as `h`.
+    pub fn new<D: NamedDigest>(seed: &[u8]) -> Self {
+        PedersenParams {
+            g: Element::from_value(G::generator()),
+            h: hash_to_subgroup::<G, D>(seed, GENERATOR_DST),
+        }
+    }
+
+    /// Commit to `value` with `blinding`, i.e. compute `g^value * h^blinding`.
+    pub fn commit(&self, value: &Scalar<G>, blinding: &Scalar<G>) -> Element<G> {
+        self.g.pow(&value.value) * self.h.pow(&blinding.value)
+    }
+
+    /// Check that both generators are in `(1, p)`.
+    fn is_valid(&self) -> bool {
+        let one = BigUint::from(1u64);
+        let p = G::prime_modulus();
+        self.g.value > one && self.g.value < p && self.h.value > one && self.h.value < p
+    }
+}
+
+impl<G: MODPGroup> PartialEq for OpeningProof<G> {
+    fn eq(&self, other: &Self) -> bool {
+        self.c == other.c && self.s1 == other.s1 && self.s2 == other.s2
+    }
+}
+
+impl<G: MODPGroup> Eq for OpeningProof<G> {}
+
+/// Prove the knowledge of `value` and `blinding` opening the commitment
+/// [PedersenParams::commit]`(value, blinding)`.
+pub fn prove_opening<G: MODPGroup, D: NamedDigest, R: CryptoRngCore + ?Sized>(
+    value: &Scalar<G>,
+    blinding: &Scalar<G>,
+    params: &PedersenParams<G>,
+    rng: &mut R,
+) -> OpeningProof<G> {
+    let commitment = params.commit(value, blinding);
+    let (k1, k2) = (Scalar::<G>::random(rng), Scalar::<G>::random(rng));
+    let t = params.commit(&k1, &k2);
+    let c = challenge::<G, D>(params, &commitment, &t);
+    OpeningProof {
+        s1: k1 + &c * value,
+        s2: k2 + &c * blinding,
+        c,
+    }
+}
+
+/// Verify a proof of knowledge of the opening of `commitment` under `params`.
+pub fn verify_opening_proof<G: MODPGroup, D: NamedDigest>(
+    commitment: &Element<G>,
+    params: &PedersenParams<G>,
+    proof: &OpeningProof<G>,
+) -> bool {
+    let zero = BigUint::from(0u64);
+    if !params.is_valid() || commitment.value == zero || commitment.value >= G::prime_modulus() {
+        return false;
+    }
+    let t = params.commit(&proof.s1, &proof.s2) * commitment.pow(&(-&proof.c).value);
+    challenge::<G, D>(params, commitment, &t) == proof.c
+}
+
+/// Compute the Fiat-Shamir challenge `D(g || h || C || t)`, with the elements encoded as in
+/// [Element::to_bytes_be].
+fn challenge<G: MODPGroup, D: NamedDigest>(
+    params: &PedersenParams<G>,
+    commitment: &Element<G>,
+    t: &Element<G>,
+) -> Scalar<G> {
+    let digest = D::new()
+        .chain_update(params.g.to_bytes_be())
+        .chain_update(params.h.to_bytes_be())
+        .chain_update(commitment.to_bytes_be())
+        .chain_update(t.to_bytes_be())
+        .finalize();
+    Scalar::from_biguint(BigUint::from_bytes_be(&digest))
+}
+
+#[cfg(test)]
+mod test {
+    use sha2::Sha256;
+
+    use super::*;
+    use crate::group::MODPGroup5;
+
+    type G = MODPGroup5;
+
+    #[test]
+    fn test_honest_proof() {
+        let rng = &mut rand::thread_rng();
+        let params = PedersenParams::<G>::new::<Sha256>(b"test");
+        assert_eq!(
+            PedersenParams::<G>::new::<Sha256>(b"test").h,
+            params.h,
+            "the parameters are deterministic"
+        );
+        assert_ne!(params.g, params.h);
+
+        let (value, blinding) = (Scalar::random(rng), Scalar::random(rng));
+        let commitment = params.commit(&value, &blinding);
+        let proof = prove_opening::<_, Sha256, _>(&value, &blinding, &params, rng);
+        assert!(verify_opening_proof::<_, Sha256>(
+            &commitment,
+            &params,
+            &proof
+        ));
+
+        let mut tampered = proof.clone();
+        tampered.s2 = &tampered.s2 + Scalar::from_biguint(BigUint::from(1u64));
+        assert!(!verify_opening_proof::<_, Sha256>(
+            &commitment,
+            &params,
+            &tampered
+        ));
+    }
+
+    #[test]
+    fn test_other_commitment() {
+        let rng = &mut rand::thread_rng();
+        let params = PedersenParams::<G>::new::<Sha256>(b"test");
+        let (value, blinding) = (Scalar::random(rng), Scalar::random(rng));
+        let proof = prove_opening::<_, Sha256, _>(&value, &blinding, &params, rng);
+
+        // the same value with another blinding
+        let other = params.commit(&value, &Scalar::random(rng));
+        assert!(!verify_opening_proof::<_, Sha256>(&other, &params, &proof));
+        // invalid commitments
+        for value in [BigUint::from(0u64), G::prime_modulus()] {
+            let invalid = Element::from_value(value);
+            assert!(!verify_opening_proof::<_, Sha256>(
+                &invalid, &params, &proof
+            ));
+        }
+    }
+
+    #[test]
+    fn test_modified_params() {
+        let rng = &mut rand::thread_rng();
+        let params = PedersenParams::<G>::new::<Sha256>(b"test");
+        let (value, blinding) = (Scalar::random(rng), Scalar::random(rng));
+        let commitment = params.commit(&value, &blinding);
+        let proof = prove_opening::<_, Sha256, _>(&value, &blinding, &params, rng);
+
+        let other_h = PedersenParams::<G>::new::<Sha256>(b"other");
+        assert!(!verify_opening_proof::<_, Sha256>(
+            &commitment,
+            &other_h,
+            &proof
+        ));
+        let swapped = PedersenParams {
+            g: Element::from_value(params.h.value.clone()),
+            h: Element::from_value(params.g.value.clone()),
+        };
+        assert!(!verify_opening_proof::<_, Sha256>(
+            &commitment,
+            &swapped,
+            &proof
+        ));
+        let degenerate = PedersenParams {
+            g: Element::from_value(params.g.value.clone()),
+            h: Element::from_value(BigUint::from(1u64)),
+        };
+        assert!(!verify_opening_proof::<_, Sha256>(
+            &commitment,
+            &degenerate,
+            &proof
+        ));
+    }
+}
@@ -0,0 +1,106 @@
+use base64::{engine::general_purpose::STANDARD, Engine};
+use num_bigint::BigUint;
+
+use crate::DhGroupsError;
+
+/// Tag of a DER INTEGER.
+pub(crate) const TAG_INTEGER: u8 = 0x02;
+/// Tag of a DER BIT STRING.
+pub(crate) const TAG_BIT_STRING: u8 = 0x03;
+/// Tag of a DER SEQUENCE.
+pub(crate) const TAG_SEQUENCE: u8 = 0x30;
+
+/// Read a DER element of the given tag from the start of `bytes`, returning its content and the
+/// remaining bytes. Only the definite length form with minimal length encoding is accepted.
+pub(crate) fn read_tlv(bytes: &[u8], tag: u8) -> Result<(&[u8], &[u8]), DhGroupsError> {
+    let (&found, rest) = bytes.split_first().ok_or(DhGroupsError::UnexpectedEof)?;
+    if found != tag {
+        return Err(DhGroupsError::InvalidDer);
+    }
+    let (&first, mut rest) = rest.split_first().ok_or(DhGroupsError::UnexpectedEof)?;
+    let len = if first < 0x80 {
+        first as usize
+    } else {
+        let num_bytes = (first & 0x7f) as usize;
+        if num_bytes == 0 || num_bytes > 4 || rest.len() < num_bytes || rest[0] == 0 {
+            return Err(DhGroupsError::InvalidDer);
+        }
+        let len = rest[..num_bytes]
+            .iter()
+            .fold(0usize, |len, &b| (len << 8) | b as usize);
+        if len < 0x80 {
+            return Err(DhGroupsError::InvalidDer);
+        }
+        rest = &rest[num_bytes..];
+        len
+    };
+    if rest.len() < len {
+        return Err(DhGroupsError::UnexpectedEof);
+    }
+    Ok(rest.split_at(len))
+}
+
+/// Read a non-negative DER INTEGER from the start of `bytes`, returning the value and the
+/// remaining bytes.
+pub(crate) fn read_integer(bytes: &[u8]) -> Result<(BigUint, &[u8]), DhGroupsError> {
+    let (content, rest) = read_tlv(bytes, TAG_INTEGER)?;
+    match content {
+        [] => return Err(DhGroupsError::InvalidDer),
+        [b, ..] if b & 0x80 != 0 => return Err(DhGroupsError::InvalidDer),
+        [0, b, ..] if b & 0x80 == 0 => return Err(DhGroupsError::InvalidDer),
+        _ => {}
+    }
+    Ok((BigUint::from_bytes_be(content), rest))
+}
+
+/// Append a DER element of the given tag and content.
+pub(crate) fn write_tlv(buf: &mut Vec<u8>, tag: u8, content: &[u8]) {
+    buf.push(tag);
+    let len = content.len();
+    if len < 0x80 {
+        buf.push(len as u8);
+    } else {
+        let len_bytes: Vec<u8> = len
+            .to_be_bytes()
+            .into_iter()
+            .skip_while(|&b| b == 0)
+            .collect();
+        buf.push(0x80 | len_bytes.len() as u8);
+        buf.extend_from_slice(&len_bytes);
+    }
+    buf.extend_from_slice(content);
+}
+
+/// Append a non-negative DER INTEGER.
+pub(crate) fn write_integer(buf: &mut Vec<u8>, x: &BigUint) {
+    let mut bytes = x.to_bytes_be();
+    if bytes[0] & 0x80 != 0 {
+        bytes.insert(0, 0);
+    }
+    write_tlv(buf, TAG_INTEGER, &bytes);
+}
+
+/// Decode the base64 body of a PEM block with the given label, e.g. `DH PARAMETERS`.
+pub(crate) fn decode_pem(pem: &str, label: &str) -> Result<Vec<u8>, DhGroupsError> {
+    let begin = format!("-----BEGIN {label}-----");
+    let end = format!("-----END {label}-----");
+    let start = pem.find(&begin).ok_or(DhGroupsError::InvalidPem)? + begin.len();
+    let stop = pem[start..].find(&end).ok_or(DhGroupsError::InvalidPem)? + start;
+    let body: String = pem[start..stop]
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect();
+    STANDARD.decode(body).map_err(|_| DhGroupsError::InvalidPem)
+}
+
+/// Encode `der` as a PEM block with the given label, with lines of 64 characters.
+pub(crate) fn encode_pem(der: &[u8], label: &str) -> String {
+    let body = STANDARD.encode(der);
+    let mut pem = format!("-----BEGIN {label}-----\n");
+    for line in body.as_bytes().chunks(64) {
+        pem.push_str(std::str::from_utf8(line).expect("base64 is ASCII"));
+        pem.push('\n');
+    }
+    pem.push_str(&format!("-----END {label}-----\n"));
+    pem
+}
@@ -0,0 +1,272 @@
+//! DHIES hybrid encryption to a Diffie-Hellman public key, as a key encapsulation (KEM) followed
+//! by a data encapsulation (DEM).
+//!
+//! To encrypt to the public key `h = g^x`, the sender picks an ephemeral exponent `r` and derives
+//! the key of the DEM with HKDF over `D` from the shared element `h^r`, with the info binding the
+//! ephemeral public key `g^r` and `h`. The DEM encrypts the plaintext under that key and
+//! authenticates the additional data `aad`. The ciphertext is `g^r` and the output of the DEM
+//! ([DhiesCiphertext]). The recipient validates `g^r`, derives the key from `(g^r)^x` and opens
+//! the DEM.
+//!
+//! The DEM is any [Dem], e.g. an AEAD supplied by the caller. As each key is used once, the
+//! nonce of an AEAD can be fixed. With the `xor-hmac` feature, [XorHmac] provides an
+//! encrypt-then-MAC DEM built from `D` only.
+//!
+//! # Example
+//!
+//! ```rust
+//! # #[cfg(feature = "xor-hmac")]
+//! # {
+//! use sha2::Sha256;
+//! use diffie_hellman_groups::{dhies::{self, XorHmac}, Element, Scalar, group::MODPGroup5};
+//!
+//! type Dem = XorHmac<Sha256>;
+//! let rng = &mut rand::thread_rng();
+//! let sk = Scalar::<MODPGroup5>::random(rng);
+//! let pk = Element::<MODPGroup5>::from_biguint(sk.value.clone());
+//!
+//! let ct = dhies::encrypt::<_, Sha256, Dem, _>(&pk, b"plaintext", b"header", rng);
+//! assert_eq!(
+//!     dhies::decrypt::<_, Sha256, Dem>(&sk, &ct, b"header").unwrap(),
+//!     b"plaintext"
+//! );
+//! assert!(dhies::decrypt::<_, Sha256, Dem>(&sk, &ct, b"another header").is_err());
+//! # }
+//! ```
+
+#[cfg(feature = "xor-hmac")]
+use std::marker::PhantomData;
+
+use digest::core_api::BlockSizeUser;
+#[cfg(feature = "xor-hmac")]
+use digest::Digest;
+use hkdf::SimpleHkdf;
+use rand_core::CryptoRngCore;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "xor-hmac")]
+use hmac::{Mac, SimpleHmac};
+
+#[cfg(feature = "xor-hmac")]
+use crate::hash::expand_message;
+use crate::{group::MODPGroup, DhGroupsError, Element, NamedDigest, Scalar};
+
+/// HKDF info prefix of the DEM key.
+const KDF_INFO: &[u8] = b"diffie-hellman-groups dhies";
+
+/// A data encapsulation mechanism: a one-time authenticated encryption under a key of
+/// [Dem::key_len] bytes.
+pub trait Dem {
+    /// Length of the keys in bytes.
+    fn key_len() -> usize;
+
+    /// Encrypt `plaintext` and authenticate it with `aad` under `key`.
+    fn seal(key: &[u8], plaintext: &[u8], aad: &[u8]) -> Vec<u8>;
+
+    /// Decrypt `ciphertext` under `key`, returning `None` if it or `aad` is not authentic.
+    fn open(key: &[u8], ciphertext: &[u8], aad: &[u8]) -> Option<Vec<u8>>;
+}
+
+/// A DHIES ciphertext: the ephemeral public key `g^r` and the output of the DEM.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(bound = ""))]
+pub struct DhiesCiphertext<G: MODPGroup> {
+    pub ephemeral: Element<G>,
+    pub payload: Vec<u8>,
+}
+
+/// Encrypt `plaintext` to the public key `pk` and bind it to `aad`.
+pub fn encrypt<G, D, E, R>(
+    pk: &Element<G>,
+    plaintext: &[u8],
+    aad: &[u8],
+    rng: &mut R,
+) -> DhiesCiphertext<G>
+where
+    G: MODPGroup,
+    D: NamedDigest + BlockSizeUser + Clone,
+    E: Dem,
+    R: CryptoRngCore + ?Sized,
+{
+    let r = Scalar::<G>::random(rng);
+    let ephemeral = Element::<G>::from_biguint(r.value.clone());
+    let key = derive_key::<G, D, E>(&pk.pow(&r.value), &ephemeral, pk);
+    DhiesCiphertext {
+        payload: E::seal(&key, plaintext, aad),
+        ephemeral,
+    }
+}
+
+/// Decrypt `ct` with the secret key `sk`, checking that it is bound to `aad`.
+///
+/// # Errors
+/// Returns [DhGroupsError::Validation] if the ephemeral public key is not a valid element, or
+/// [DhGroupsError::DecryptionFailed] if the DEM rejects the ciphertext.
+pub fn decrypt<G, D, E>(
+    sk: &Scalar<G>,
+    ct: &DhiesCiphertext<G>,
+    aad: &[u8],
+) -> Result<Vec<u8>, DhGroupsError>
+where
+    G: MODPGroup,
+    D: NamedDigest + BlockSizeUser + Clone,
+    E: Dem,
+{
+    ct.ephemeral.validate()?;
+    let pk = Element::<G>::from_biguint(sk.value.clone());
+    let key = derive_key::<G, D, E>(&ct.ephemeral.pow(&sk.value), &ct.ephemeral, &pk);
+    E::open(&key, &ct.payload, aad).ok_or(DhGroupsError::DecryptionFailed)
+}
+
+/// Derive the key of the DEM from the shared element, binding both public keys.
+fn derive_key<G, D, E>(shared: &Element<G>, ephemeral: &Element<G>, pk: &Element<G>) -> Vec<u8>
+where
+    G: MODPGroup,
+    D: NamedDigest + BlockSizeUser + Clone,
+    E: Dem,
+{
+    let mut info = KDF_INFO.to_vec();
+    info.extend_from_slice(&ephemeral.to_bytes_be());
+    info.extend_from_slice(&pk.to_bytes_be());
+    let mut key = vec![0u8; E::key_len()];
+    SimpleHkdf::<D>::new(None, &shared.to_bytes_be())
+        .expand(&info, &mut key)
+        .expect("key length is valid for HKDF");
+    key
+}
+
+/// An encrypt-then-MAC DEM over the digest `D`: the plaintext is XORed with a keystream expanded
+/// from the first half of the key, and the result is followed by the HMAC, keyed with the second
+/// half, of `len(aad) || aad || ciphertext`, with the length as a big-endian `u64`.
+#[cfg(feature = "xor-hmac")]
+pub struct XorHmac<D> {
+    phantom: PhantomData<D>,
+}
+
+#[cfg(feature = "xor-hmac")]
+impl<D: NamedDigest + BlockSizeUser> XorHmac<D> {
+    /// Domain separation tag of the keystream.
+    const STREAM_DST: &'static [u8] = b"diffie-hellman-groups dhies stream";
+
+    fn mac(key: &[u8], body: &[u8], aad: &[u8]) -> SimpleHmac<D> {
+        let mut mac = SimpleHmac::<D>::new_from_slice(key).expect("any key length");
+        mac.update(&(aad.len() as u64).to_be_bytes());
+        mac.update(aad);
+        mac.update(body);
+        mac
+    }
+
+    fn xor_stream(key: &[u8], data: &[u8]) -> Vec<u8> {
+        let stream = expand_message::<D>(key, Self::STREAM_DST, 0, data.len());
+        data.iter().zip(stream).map(|(a, b)| a ^ b).collect()
+    }
+}
+
+#[cfg(feature = "xor-hmac")]
+impl<D: NamedDigest + BlockSizeUser> Dem for XorHmac<D> {
+    fn key_len() -> usize {
+        2 * <D as Digest>::output_size()
+    }
+
+    fn seal(key: &[u8], plaintext: &[u8], aad: &[u8]) -> Vec<u8> {
+        let (enc_key, mac_key) = key.split_at(key.len() / 2);
+        let mut ciphertext = Self::xor_stream(enc_key, plaintext);
+        let tag = Self::mac(mac_key, &ciphertext, aad).finalize().into_bytes();
+        ciphertext.extend_from_slice(&tag);
+        ciphertext
+    }
+
+    fn open(key: &[u8], ciphertext: &[u8], aad: &[u8]) -> Option<Vec<u8>> {
+        let (enc_key, mac_key) = key.split_at(key.len() / 2);
+        let body_len = ciphertext.len().checked_sub(<D as Digest>::output_size())?;
+        let (body, tag) = ciphertext.split_at(body_len);
+        Self::mac(mac_key, body, aad).verify_slice(tag).ok()?;
+        Some(Self::xor_stream(enc_key, body))
+    }
+}
+
+#[cfg(all(test, feature = "xor-hmac"))]
+mod test {
+    use num_bigint::BigUint;
+    use sha2::Sha256;
+
+    use super::*;
+    use crate::{group::MODPGroup5, ValidationError};
+
+    type G = MODPGroup5;
+    type Dem = XorHmac<Sha256>;
+
+    #[test]
+    fn test_round_trip() {
+        let rng = &mut rand::thread_rng();
+        let sk = Scalar::<G>::random(rng);
+        let pk = Element::<G>::from_biguint(sk.value.clone());
+
+        for len in [0, 1, 31, 32, 33, 100] {
+            let plaintext = vec![0x5a; len];
+            let ct = encrypt::<_, Sha256, Dem, _>(&pk, &plaintext, b"aad", rng);
+            assert_eq!(ct.payload.len(), len + 32);
+            assert_eq!(
+                decrypt::<_, Sha256, Dem>(&sk, &ct, b"aad").unwrap(),
+                plaintext
+            );
+        }
+
+        // another recipient cannot decrypt
+        let other = Scalar::<G>::random(rng);
+        let ct = encrypt::<_, Sha256, Dem, _>(&pk, b"plaintext", b"", rng);
+        assert_eq!(
+            decrypt::<_, Sha256, Dem>(&other, &ct, b""),
+            Err(DhGroupsError::DecryptionFailed)
+        );
+    }
+
+    #[test]
+    fn test_tampering() {
+        let rng = &mut rand::thread_rng();
+        let sk = Scalar::<G>::random(rng);
+        let pk = Element::<G>::from_biguint(sk.value.clone());
+        let ct = encrypt::<_, Sha256, Dem, _>(&pk, b"attack at dawn", b"aad", rng);
+
+        // the aad
+        assert_eq!(
+            decrypt::<_, Sha256, Dem>(&sk, &ct, b"aad!"),
+            Err(DhGroupsError::DecryptionFailed)
+        );
+
+        // the ephemeral public key, with another element of the subgroup and with invalid ones
+        let mut tampered = ct.clone();
+        tampered.ephemeral = &ct.ephemeral * Element::from_biguint(BigUint::from(1u64));
+        assert_eq!(
+            decrypt::<_, Sha256, Dem>(&sk, &tampered, b"aad"),
+            Err(DhGroupsError::DecryptionFailed)
+        );
+        tampered.ephemeral = Element::from_value(BigUint::from(1u64));
+        assert_eq!(
+            decrypt::<_, Sha256, Dem>(&sk, &tampered, b"aad"),
+            Err(DhGroupsError::Validation(ValidationError::Degenerate))
+        );
+        tampered.ephemeral = Element::from_value(G::prime_modulus() - 2u64);
+        assert_eq!(
+            decrypt::<_, Sha256, Dem>(&sk, &tampered, b"aad"),
+            Err(DhGroupsError::Validation(ValidationError::NotInSubgroup))
+        );
+
+        // each byte of the encrypted body and of the tag, and truncation
+        for i in 0..ct.payload.len() {
+            let mut tampered = ct.clone();
+            tampered.payload[i] ^= 0x01;
+            assert_eq!(
+                decrypt::<_, Sha256, Dem>(&sk, &tampered, b"aad"),
+                Err(DhGroupsError::DecryptionFailed)
+            );
+        }
+        let mut truncated = ct.clone();
+        truncated.payload.truncate(31);
+        assert_eq!(
+            decrypt::<_, Sha256, Dem>(&sk, &truncated, b"aad"),
+            Err(DhGroupsError::DecryptionFailed)
+        );
+    }
+}
@@ -0,0 +1,196 @@
+//! Import and export of Diffie-Hellman parameters in the PKCS #3 format, as written by
+//! `openssl dhparam`:
+//!
+//! ```text
+//! DHParameter ::= SEQUENCE {
+//!     prime INTEGER, -- p
+//!     base INTEGER, -- g
+//!     privateValueLength INTEGER OPTIONAL }
+//! ```
+//!
+//! The PEM form uses the label `DH PARAMETERS`.
+
+use num_bigint::BigUint;
+
+use crate::{
+    der::{decode_pem, encode_pem, read_integer, read_tlv, write_integer, write_tlv, TAG_SEQUENCE},
+    AnyModpGroup, DhGroupsError, PrimeGroup,
+};
+
+/// PEM label of PKCS #3 Diffie-Hellman parameters.
+pub const PEM_LABEL: &str = "DH PARAMETERS";
+
+/// Parameters imported from a PKCS #3 structure.
+#[derive(Clone, Debug)]
+pub struct DhParams {
+    /// The group, with `q = (p - 1) / 2`.
+    pub group: PrimeGroup,
+    /// The built-in group with the same modulus, if any (see [PrimeGroup::identify]).
+    pub builtin: Option<AnyModpGroup>,
+    /// Recommended length in bits of the private exponents, if present.
+    pub private_value_length: Option<u64>,
+}
+
+/// Parse DER encoded PKCS #3 parameters. The modulus must be a safe prime and the generator must
+/// generate the subgroup of order `q = (p - 1) / 2`.
+///
+/// # Errors
+/// Returns [DhGroupsError::InvalidDer] if the input is malformed, or the error of
+/// [PrimeGroup::validate] if the parameters are invalid.
+pub fn parse_der(der: &[u8]) -> Result<DhParams, DhGroupsError> {
+    let (content, rest) = read_tlv(der, TAG_SEQUENCE)?;
+    if !rest.is_empty() {
+        return Err(DhGroupsError::TrailingBytes);
+    }
+    let (p, content) = read_integer(content)?;
+    let (g, content) = read_integer(content)?;
+    let private_value_length = if content.is_empty() {
+        None
+    } else {
+        let (length, content) = read_integer(content)?;
+        if !content.is_empty() {
+            return Err(DhGroupsError::InvalidDer);
+        }
+        Some(u64::try_from(length).map_err(|_| DhGroupsError::InvalidDer)?)
+    };
+
+    if p.bits() < 3 {
+        return Err(DhGroupsError::NotSafePrime);
+    }
+    let group = PrimeGroup {
+        q: (&p - 1u64) >> 1,
+        p,
+        g,
+        cofactor: BigUint::from(2u64),
+    };
+    group.validate().map_err(|e| match e {
+        DhGroupsError::NotPrime => DhGroupsError::NotSafePrime,
+        e => e,
+    })?;
+
+    Ok(DhParams {
+        builtin: group.identify(),
+        group,
+        private_value_length,
+    })
+}
+
+/// Parse PEM encoded PKCS #3 parameters, as in [parse_der].
+///
+/// # Example
+///
+/// ```rust
+/// use diffie_hellman_groups::{dhparam, AnyModpGroup};
+///
+/// let pem = "-----BEGIN DH PARAMETERS-----
+/// MIIBCAKCAQEA///////////JD9qiIWjCNMTGYouA3BzRKQJOCIpnzHQCC76mOxOb
+/// IlFKCHmONATd75UZs806QxswKwpt8l8UN0/hNW1tUcJF5IW1dmJefsb0TELppjft
+/// awv/XLb0Brft7jhr+1qJn6WunyQRfEsf5kkoZlHs5Fs9wgB8uKFjvwWY2kg2HFXT
+/// mmkWP6j9JM9fg2VdI9yjrZYcYvNWIIVSu57VKQdwlpZtZww1Tkq8mATxdGwIyhgh
+/// fDKQXkYuNs474553LBgOhgObJ4Oi7Aeij7XFXfBvTFLJ3ivL9pVYFxg5lUl86pVq
+/// 5RXSJhiY+gUQFXKOWoqsqmj//////////wIBAg==
+/// -----END DH PARAMETERS-----";
+/// let params = dhparam::parse_pem(pem).unwrap();
+/// assert_eq!(params.builtin, Some(AnyModpGroup::Modp2048));
+/// ```
+pub fn parse_pem(pem: &str) -> Result<DhParams, DhGroupsError> {
+    parse_der(&decode_pem(pem, PEM_LABEL)?)
+}
+
+/// Encode the modulus and generator of `group` as DER PKCS #3 parameters.
+pub fn to_der(group: &PrimeGroup) -> Vec<u8> {
+    let mut content = Vec::new();
+    write_integer(&mut content, &group.p);
+    write_integer(&mut content, &group.g);
+    let mut der = Vec::new();
+    write_tlv(&mut der, TAG_SEQUENCE, &content);
+    der
+}
+
+/// Encode the modulus and generator of `group` as PEM PKCS #3 parameters.
+pub fn to_pem(group: &PrimeGroup) -> String {
+    encode_pem(&to_der(group), PEM_LABEL)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{MODPGroup, MODPGroup14};
+
+    const GROUP_14_PEM: &str = include_str!("../tests/fixtures/group14_dhparam.pem");
+
+    #[test]
+    fn test_parse_group_14() {
+        let params = parse_pem(GROUP_14_PEM).unwrap();
+        assert_eq!(params.builtin, Some(AnyModpGroup::Modp2048));
+        assert_eq!(params.group.identify_exact(), Some(AnyModpGroup::Modp2048));
+        assert_eq!(params.group.q, MODPGroup14::sophie_garmain_prime());
+        assert_eq!(params.private_value_length, None);
+
+        assert_eq!(to_pem(&params.group), GROUP_14_PEM);
+    }
+
+    #[test]
+    fn test_round_trip_generated() {
+        let group = PrimeGroup::generate(128, &mut rand::thread_rng(), None).unwrap();
+        let params = parse_pem(&to_pem(&group)).unwrap();
+        assert_eq!(params.builtin, None);
+        assert_eq!(params.group.p, group.p);
+        assert_eq!(params.group.g, group.g);
+    }
+
+    #[test]
+    fn test_private_value_length() {
+        let group = PrimeGroup::new_with(BigUint::from(1623299u64), 15).unwrap();
+        let mut der = to_der(&group);
+        // append privateValueLength = 16 to the sequence
+        der.extend_from_slice(&[0x02, 0x01, 0x10]);
+        der[1] += 3;
+        assert_eq!(parse_der(&der).unwrap().private_value_length, Some(16));
+    }
+
+    #[test]
+    fn test_invalid() {
+        let group = PrimeGroup::new_with(BigUint::from(1623299u64), 15).unwrap();
+        let der = to_der(&group);
+
+        assert_eq!(
+            parse_der(&der[..der.len() - 1]).unwrap_err(),
+            DhGroupsError::UnexpectedEof
+        );
+        let mut trailing = der.clone();
+        trailing.push(0);
+        assert_eq!(
+            parse_der(&trailing).unwrap_err(),
+            DhGroupsError::TrailingBytes
+        );
+
+        // generator 2 has order 2q modulo p
+        let invalid = PrimeGroup {
+            g: BigUint::from(2u64),
+            ..group.clone()
+        };
+        assert_eq!(
+            parse_der(&to_der(&invalid)).unwrap_err(),
+            DhGroupsError::InvalidGenerator
+        );
+        let invalid = PrimeGroup {
+            p: BigUint::from(1623301u64),
+            ..group
+        };
+        assert_eq!(
+            parse_der(&to_der(&invalid)).unwrap_err(),
+            DhGroupsError::NotSafePrime
+        );
+
+        assert_eq!(
+            parse_pem("-----BEGIN DH PARAMETERS-----\n!!!\n-----END DH PARAMETERS-----\n")
+                .unwrap_err(),
+            DhGroupsError::InvalidPem
+        );
+        assert_eq!(
+            parse_pem(&GROUP_14_PEM.replace("DH PARAMETERS", "X9.42 DH PARAMETERS")).unwrap_err(),
+            DhGroupsError::InvalidPem
+        );
+    }
+}
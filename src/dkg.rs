@@ -0,0 +1,545 @@
+//! Pedersen's distributed key generation (DKG) over a MODP group: `n` participants jointly
+//! generate a key pair whose secret key is shared with threshold `t`, i.e. any `t` of them can
+//! use it while no `t - 1` learn anything about it.
+//!
+//! Each participant `i` (numbered from 1) acts as a dealer of a Feldman verifiable secret
+//! sharing of a random value `a_i0`:
+//!
+//! 1. It picks a random polynomial `f_i(z) = a_i0 + a_i1 z + ... + a_i(t-1) z^(t-1)`, broadcasts
+//!    the commitments `C_ik = g^a_ik` ([DealerBroadcast]) and sends the share `f_i(j)` to each
+//!    participant `j` privately ([ShareMessage]).
+//! 2. Each participant checks its shares against the commitments, `g^f_i(j) = prod_k C_ik^(j^k)`,
+//!    and broadcasts a [Complaint] against each dealer whose share is invalid or missing.
+//! 3. A dealer answers each complaint against it by broadcasting the disputed share. It is
+//!    disqualified if it leaves a complaint unanswered, or answers with an invalid share.
+//!
+//! The joint public key is the product of the commitments `C_i0` of the qualified dealers, and
+//! the share of the joint secret key of participant `j` is the sum of the shares `f_i(j)` it
+//! received from them ([DkgOutput]).
+//!
+//! The API is driven by the messages, which are serializable, so that the protocol can run over
+//! any transport. Broadcasts must reach all participants consistently.
+//!
+//! # Example
+//!
+//! ```rust
+//! use diffie_hellman_groups::{dkg::{Parameters, Participant}, group::MODPGroup5};
+//!
+//! let params = Parameters::new(3, 2).unwrap();
+//! let rng = &mut rand::thread_rng();
+//! let (mut participants, mut broadcasts, mut shares) = (Vec::new(), Vec::new(), Vec::new());
+//! for index in 1..=3 {
+//!     let (participant, broadcast, messages) =
+//!         Participant::<MODPGroup5>::new(index, params, rng).unwrap();
+//!     participants.push(participant);
+//!     broadcasts.push(broadcast);
+//!     shares.extend(messages);
+//! }
+//! for participant in participants.iter_mut() {
+//!     for broadcast in &broadcasts {
+//!         participant.receive_broadcast(broadcast.clone()).unwrap();
+//!     }
+//!     let index = participant.index();
+//!     for share in shares.iter().filter(|m| m.recipient == index) {
+//!         participant.receive_share(share.clone()).unwrap();
+//!     }
+//!     assert!(participant.complaints().is_empty());
+//! }
+//! let outputs: Vec<_> = participants.into_iter().map(|p| p.finish().unwrap()).collect();
+//! assert_eq!(outputs[0].public_key, outputs[2].public_key);
+//! assert_eq!(outputs[1].qualified, vec![1, 2, 3]);
+//! ```
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use num_bigint::BigUint;
+use rand_core::CryptoRngCore;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{group::MODPGroup, shamir::evaluate, DhGroupsError, Element, Scalar};
+
+/// The number of participants `n` and the threshold `t`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Parameters {
+    n: u32,
+    threshold: u32,
+}
+
+/// The commitments `C_ik = g^a_ik` to the coefficients of the polynomial of a dealer.
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(bound = ""))]
+pub struct DealerBroadcast<G: MODPGroup> {
+    pub dealer: u32,
+    pub commitments: Vec<Element<G>>,
+}
+
+/// The share `f_i(j)` of the dealer `i` for the participant `j`. It is sent privately, except
+/// when the dealer answers a complaint.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(bound = ""))]
+pub struct ShareMessage<G: MODPGroup> {
+    pub dealer: u32,
+    pub recipient: u32,
+    pub share: Scalar<G>,
+}
+
+/// A complaint of `accuser` against `dealer`, whose share is invalid or missing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Complaint {
+    pub accuser: u32,
+    pub dealer: u32,
+}
+
+/// The result of the protocol for a participant.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(bound = ""))]
+pub struct DkgOutput<G: MODPGroup> {
+    /// The index of the participant.
+    pub index: u32,
+    /// The indices of the qualified dealers, in increasing order.
+    pub qualified: Vec<u32>,
+    /// The share `x_j` of the joint secret key.
+    pub share: Scalar<G>,
+    /// The joint public key.
+    pub public_key: Element<G>,
+    /// The commitments of the qualified dealers, to compute the public shares.
+    pub commitments: Vec<DealerBroadcast<G>>,
+}
+
+/// A participant of the protocol, acting both as a dealer and as a recipient of shares.
+pub struct Participant<G: MODPGroup> {
+    index: u32,
+    params: Parameters,
+    coefficients: Vec<Scalar<G>>,
+    broadcasts: BTreeMap<u32, DealerBroadcast<G>>,
+    /// Valid shares received from each dealer.
+    shares: BTreeMap<u32, Scalar<G>>,
+    /// Complaints against each dealer, with whether they are resolved.
+    complaints: BTreeMap<Complaint, bool>,
+    /// Dealers who answered a complaint with an invalid share.
+    disqualified: BTreeSet<u32>,
+}
+
+impl<G: MODPGroup> Clone for DealerBroadcast<G> {
+    fn clone(&self) -> Self {
+        DealerBroadcast {
+            dealer: self.dealer,
+            commitments: self
+                .commitments
+                .iter()
+                .map(|c| Element::from_value(c.value.clone()))
+                .collect(),
+        }
+    }
+}
+
+impl Parameters {
+    /// Create parameters for `n` participants and threshold `threshold`.
+    ///
+    /// # Errors
+    /// Returns [DhGroupsError::InvalidThreshold] unless `1 <= threshold <= n`.
+    pub fn new(n: u32, threshold: u32) -> Result<Self, DhGroupsError> {
+        if threshold == 0 || threshold > n {
+            return Err(DhGroupsError::InvalidThreshold);
+        }
+        Ok(Parameters { n, threshold })
+    }
+
+    pub fn n(&self) -> u32 {
+        self.n
+    }
+
+    pub fn threshold(&self) -> u32 {
+        self.threshold
+    }
+
+    fn check_index(&self, index: u32) -> Result<(), DhGroupsError> {
+        if index == 0 || index > self.n {
+            return Err(DhGroupsError::InvalidParticipant);
+        }
+        Ok(())
+    }
+}
+
+impl<G: MODPGroup> Participant<G> {
+    /// Start the protocol as the participant `index`, returning the participant, its broadcast
+    /// and the shares to send to the other participants.
+    ///
+    /// # Errors
+    /// Returns [DhGroupsError::InvalidParticipant] if `index` is not in `[1, n]`.
+    #[allow(clippy::type_complexity)]
+    pub fn new<R: CryptoRngCore + ?Sized>(
+        index: u32,
+        params: Parameters,
+        rng: &mut R,
+    ) -> Result<(Self, DealerBroadcast<G>, Vec<ShareMessage<G>>), DhGroupsError> {
+        params.check_index(index)?;
+        let coefficients: Vec<Scalar<G>> =
+            (0..params.threshold).map(|_| Scalar::random(rng)).collect();
+        let broadcast = DealerBroadcast {
+            dealer: index,
+            commitments: coefficients
+                .iter()
+                .map(|a| Element::from_biguint(a.value.clone()))
+                .collect(),
+        };
+        let messages = (1..=params.n)
+            .filter(|&j| j != index)
+            .map(|j| ShareMessage {
+                dealer: index,
+                recipient: j,
+                share: evaluate(&coefficients, j),
+            })
+            .collect();
+
+        let mut participant = Participant {
+            index,
+            params,
+            coefficients,
+            broadcasts: BTreeMap::new(),
+            shares: BTreeMap::new(),
+            complaints: BTreeMap::new(),
+            disqualified: BTreeSet::new(),
+        };
+        let own_share = evaluate(&participant.coefficients, index);
+        participant.shares.insert(index, own_share);
+        participant.broadcasts.insert(index, broadcast.clone());
+        Ok((participant, broadcast, messages))
+    }
+
+    /// The index of the participant.
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+
+    /// Record the broadcast of a dealer.
+    ///
+    /// # Errors
+    /// Returns [DhGroupsError::InvalidParticipant] if the dealer is not in `[1, n]`, or
+    /// [DhGroupsError::InvalidThreshold] if the number of commitments is not the threshold. The
+    /// dealer is then not qualified.
+    pub fn receive_broadcast(
+        &mut self,
+        broadcast: DealerBroadcast<G>,
+    ) -> Result<(), DhGroupsError> {
+        self.params.check_index(broadcast.dealer)?;
+        if broadcast.commitments.len() != self.params.threshold as usize {
+            return Err(DhGroupsError::InvalidThreshold);
+        }
+        self.broadcasts.entry(broadcast.dealer).or_insert(broadcast);
+        Ok(())
+    }
+
+    /// Record a share sent to this participant, after the broadcast of its dealer. An invalid
+    /// share is discarded, and leads to a complaint from [Participant::complaints].
+    ///
+    /// # Errors
+    /// Returns [DhGroupsError::InvalidParticipant] if the share is not addressed to this
+    /// participant or its dealer is not in `[1, n]`.
+    pub fn receive_share(&mut self, msg: ShareMessage<G>) -> Result<(), DhGroupsError> {
+        self.params.check_index(msg.dealer)?;
+        if msg.recipient != self.index {
+            return Err(DhGroupsError::InvalidParticipant);
+        }
+        if self.is_valid_share(&msg) {
+            self.shares.entry(msg.dealer).or_insert(msg.share);
+        }
+        Ok(())
+    }
+
+    /// The complaints of this participant against the dealers whose share is invalid or
+    /// missing, to broadcast once all shares are received.
+    pub fn complaints(&self) -> Vec<Complaint> {
+        (1..=self.params.n)
+            .filter(|dealer| !self.shares.contains_key(dealer))
+            .map(|dealer| Complaint {
+                accuser: self.index,
+                dealer,
+            })
+            .collect()
+    }
+
+    /// Record a complaint broadcast by a participant.
+    pub fn receive_complaint(&mut self, complaint: Complaint) -> Result<(), DhGroupsError> {
+        self.params.check_index(complaint.accuser)?;
+        self.params.check_index(complaint.dealer)?;
+        self.complaints.entry(complaint).or_insert(false);
+        Ok(())
+    }
+
+    /// The answer of this participant to a complaint against it: the disputed share, to
+    /// broadcast. Returns `None` if the complaint is against another dealer.
+    pub fn answer(&self, complaint: &Complaint) -> Option<ShareMessage<G>> {
+        (complaint.dealer == self.index).then(|| ShareMessage {
+            dealer: self.index,
+            recipient: complaint.accuser,
+            share: evaluate(&self.coefficients, complaint.accuser),
+        })
+    }
+
+    /// Record the answer of a dealer to a complaint. A valid answer resolves the complaint, and
+    /// gives the share to its accuser; an invalid one disqualifies the dealer.
+    pub fn receive_answer(&mut self, msg: ShareMessage<G>) -> Result<(), DhGroupsError> {
+        self.params.check_index(msg.dealer)?;
+        self.params.check_index(msg.recipient)?;
+        let complaint = Complaint {
+            accuser: msg.recipient,
+            dealer: msg.dealer,
+        };
+        if !self.complaints.contains_key(&complaint) {
+            return Ok(());
+        }
+        if !self.is_valid_share(&msg) {
+            self.disqualified.insert(msg.dealer);
+            return Ok(());
+        }
+        self.complaints.insert(complaint, true);
+        if msg.recipient == self.index {
+            self.shares.entry(msg.dealer).or_insert(msg.share);
+        }
+        Ok(())
+    }
+
+    /// The dealers qualified so far: those whose broadcast was received, and who answered all
+    /// complaints against them with valid shares.
+    pub fn qualified(&self) -> Vec<u32> {
+        self.broadcasts
+            .keys()
+            .copied()
+            .filter(|dealer| {
+                !self.disqualified.contains(dealer)
+                    && self
+                        .complaints
+                        .iter()
+                        .all(|(complaint, &resolved)| complaint.dealer != *dealer || resolved)
+            })
+            .collect()
+    }
+
+    /// Finish the protocol, once all complaints are answered.
+    ///
+    /// # Errors
+    /// Returns [DhGroupsError::InvalidThreshold] if less than `t` dealers are qualified.
+    pub fn finish(self) -> Result<DkgOutput<G>, DhGroupsError> {
+        let qualified = self.qualified();
+        if qualified.len() < self.params.threshold as usize {
+            return Err(DhGroupsError::InvalidThreshold);
+        }
+        let mut share = Scalar::from_biguint(BigUint::from(0u64));
+        let mut public_key = Element::from_value(BigUint::from(1u64));
+        let mut commitments = Vec::with_capacity(qualified.len());
+        for dealer in &qualified {
+            // a qualified dealer has no unresolved complaint, so our share is known
+            share = share + &self.shares[dealer];
+            let broadcast = &self.broadcasts[dealer];
+            public_key *= &broadcast.commitments[0];
+            commitments.push(broadcast.clone());
+        }
+        Ok(DkgOutput {
+            index: self.index,
+            qualified,
+            share,
+            public_key,
+            commitments,
+        })
+    }
+
+    /// Check a share against the broadcast of its dealer.
+    fn is_valid_share(&self, msg: &ShareMessage<G>) -> bool {
+        self.broadcasts.get(&msg.dealer).is_some_and(|broadcast| {
+            Element::<G>::from_biguint(msg.share.value.clone())
+                == commitment_at(&broadcast.commitments, msg.recipient)
+        })
+    }
+}
+
+impl<G: MODPGroup> DkgOutput<G> {
+    /// The public share `g^x_j` of the participant `j`, against which its use of the secret
+    /// share can be verified.
+    pub fn public_share(&self, j: u32) -> Element<G> {
+        self.commitments.iter().fold(
+            Element::from_value(BigUint::from(1u64)),
+            |acc, broadcast| acc * commitment_at(&broadcast.commitments, j),
+        )
+    }
+}
+
+/// Compute `prod_k C_k^(x^k)`, i.e. `g^f(x)` from the commitments to the coefficients of `f`.
+fn commitment_at<G: MODPGroup>(commitments: &[Element<G>], x: u32) -> Element<G> {
+    let x = BigUint::from(x);
+    let mut power = BigUint::from(1u64);
+    let mut result = Element::from_value(BigUint::from(1u64));
+    for c in commitments {
+        result *= c.pow(&power);
+        power = (power * &x) % G::subgroup_order();
+    }
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::group::MODPGroup5;
+
+    type G = MODPGroup5;
+
+    /// Interpolate the polynomial through the points `(j, y_j)` at 0.
+    fn interpolate_at_zero(points: &[(u32, &Scalar<G>)]) -> Scalar<G> {
+        let mut result = Scalar::from_biguint(BigUint::from(0u64));
+        for (j, y) in points {
+            let mut num = Scalar::from_biguint(BigUint::from(1u64));
+            let mut den = Scalar::from_biguint(BigUint::from(1u64));
+            for (m, _) in points.iter().filter(|(m, _)| m != j) {
+                num = num * Scalar::from_biguint(BigUint::from(*m));
+                den = den
+                    * (Scalar::from_biguint(BigUint::from(*m))
+                        - Scalar::from_biguint(BigUint::from(*j)));
+            }
+            result = result + num * den.inverse().unwrap() * *y;
+        }
+        result
+    }
+
+    #[test]
+    fn test_five_parties_threshold_three() {
+        let params = Parameters::new(5, 3).unwrap();
+        let rng = &mut rand::thread_rng();
+
+        let mut participants = Vec::new();
+        let mut broadcasts = Vec::new();
+        let mut shares = Vec::new();
+        for index in 1..=5 {
+            let (participant, broadcast, messages) =
+                Participant::<G>::new(index, params, rng).unwrap();
+            participants.push(participant);
+            broadcasts.push(broadcast);
+            shares.extend(messages);
+        }
+
+        // dealer 4 sends an invalid share to participant 2, but answers the complaint honestly.
+        // dealer 5 sends invalid shares to participants 1 and 3, and answers with invalid ones.
+        for msg in shares.iter_mut() {
+            if (msg.dealer, msg.recipient) == (4, 2) || msg.dealer == 5 && msg.recipient % 2 == 1 {
+                msg.share = &msg.share + Scalar::from_biguint(BigUint::from(1u64));
+            }
+        }
+
+        for participant in participants.iter_mut() {
+            for broadcast in &broadcasts {
+                participant.receive_broadcast(broadcast.clone()).unwrap();
+            }
+            let index = participant.index();
+            for msg in shares.iter().filter(|m| m.recipient == index) {
+                participant.receive_share(msg.clone()).unwrap();
+            }
+        }
+
+        let complaints: Vec<Complaint> = participants.iter().flat_map(|p| p.complaints()).collect();
+        assert_eq!(
+            complaints,
+            vec![
+                Complaint {
+                    accuser: 1,
+                    dealer: 5
+                },
+                Complaint {
+                    accuser: 2,
+                    dealer: 4
+                },
+                Complaint {
+                    accuser: 3,
+                    dealer: 5
+                },
+            ]
+        );
+        let mut answers = Vec::new();
+        for complaint in &complaints {
+            for participant in participants.iter() {
+                if let Some(mut answer) = participant.answer(complaint) {
+                    if answer.dealer == 5 {
+                        answer.share = &answer.share + Scalar::from_biguint(BigUint::from(1u64));
+                    }
+                    answers.push(answer);
+                }
+            }
+        }
+        for participant in participants.iter_mut() {
+            for complaint in &complaints {
+                participant.receive_complaint(*complaint).unwrap();
+            }
+            for answer in &answers {
+                participant.receive_answer(answer.clone()).unwrap();
+            }
+        }
+
+        let secret = participants[..4]
+            .iter()
+            .fold(Scalar::from_biguint(BigUint::from(0u64)), |acc, p| {
+                acc + &p.coefficients[0]
+            });
+        let outputs: Vec<DkgOutput<G>> = participants
+            .into_iter()
+            .map(|p| p.finish().unwrap())
+            .collect();
+
+        let expected = Element::<G>::from_biguint(secret.value.clone());
+        for output in &outputs {
+            assert_eq!(output.qualified, vec![1, 2, 3, 4]);
+            assert_eq!(output.public_key, expected);
+            assert_eq!(
+                output.public_share(output.index),
+                Element::from_biguint(output.share.value.clone())
+            );
+        }
+
+        // any 3 shares reconstruct the joint secret key
+        for subset in [[0, 1, 2], [1, 3, 4], [0, 2, 4]] {
+            let points: Vec<(u32, &Scalar<G>)> = subset
+                .iter()
+                .map(|&i| (outputs[i].index, &outputs[i].share))
+                .collect();
+            assert_eq!(interpolate_at_zero(&points), secret);
+        }
+    }
+
+    #[test]
+    fn test_invalid_inputs() {
+        assert_eq!(
+            Parameters::new(3, 0).unwrap_err(),
+            DhGroupsError::InvalidThreshold
+        );
+        assert_eq!(
+            Parameters::new(3, 4).unwrap_err(),
+            DhGroupsError::InvalidThreshold
+        );
+
+        let params = Parameters::new(3, 2).unwrap();
+        let rng = &mut rand::thread_rng();
+        assert_eq!(
+            Participant::<G>::new(4, params, rng).err(),
+            Some(DhGroupsError::InvalidParticipant)
+        );
+        let (mut participant, mut broadcast, shares) =
+            Participant::<G>::new(1, params, rng).unwrap();
+        assert_eq!(
+            participant.receive_share(shares[0].clone()),
+            Err(DhGroupsError::InvalidParticipant)
+        );
+        broadcast.dealer = 2;
+        broadcast.commitments.pop();
+        assert_eq!(
+            participant.receive_broadcast(broadcast),
+            Err(DhGroupsError::InvalidThreshold)
+        );
+
+        // no other dealer is qualified
+        assert_eq!(participant.qualified(), vec![1]);
+        assert_eq!(
+            participant.finish().unwrap_err(),
+            DhGroupsError::InvalidThreshold
+        );
+    }
+}
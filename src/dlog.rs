@@ -0,0 +1,63 @@
+//! Discrete logarithms of small exponents.
+
+use std::collections::HashMap;
+
+use num_bigint::BigUint;
+
+use crate::{group::MODPGroup, Element, Scalar};
+
+/// Find `m` in `[0, max]` such that `g^m = h` with the baby-step giant-step algorithm, taking
+/// about `2 * sqrt(max)` multiplications and storing `sqrt(max)` elements.
+/// Returns `None` if there is no such `m`.
+///
+/// # Example
+///
+/// ```rust
+/// use diffie_hellman_groups::{dlog, Element, group::MODPGroup5};
+///
+/// let h = Element::<MODPGroup5>::from_biguint(1234u32.into());
+/// assert_eq!(dlog::baby_step_giant_step(&h, 10_000), Some(1234));
+/// assert_eq!(dlog::baby_step_giant_step(&h, 1000), None);
+/// ```
+pub fn baby_step_giant_step<G: MODPGroup>(h: &Element<G>, max: u64) -> Option<u64> {
+    let n = (max as f64 + 1.0).sqrt().ceil() as u64;
+
+    // baby steps: g^j for j in [0, n)
+    let g = Element::<G>::from_value(G::generator());
+    let mut table = HashMap::with_capacity(n as usize);
+    let mut baby = Element::<G>::from_value(BigUint::from(1u64));
+    for j in 0..n {
+        table.entry(baby.value.clone()).or_insert(j);
+        baby *= &g;
+    }
+
+    // giant steps: h * g^(-n i) for i in [0, n]
+    let factor = Element::<G>::from_biguint((-Scalar::<G>::from_biguint(BigUint::from(n))).value);
+    let mut giant = Element::<G>::from_value(h.value.clone());
+    for i in 0..=n {
+        if let Some(j) = table.get(&giant.value) {
+            let m = i * n + j;
+            return (m <= max).then_some(m);
+        }
+        giant *= &factor;
+    }
+    None
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::group::MODPGroup14;
+
+    #[test]
+    fn test_baby_step_giant_step() {
+        for max in [0, 1, 2, 15, 16, 17, 1000] {
+            for m in [0, max / 2, max] {
+                let h = Element::<MODPGroup14>::from_biguint(BigUint::from(m));
+                assert_eq!(baby_step_giant_step(&h, max), Some(m));
+            }
+            let h = Element::<MODPGroup14>::from_biguint(BigUint::from(max + 1));
+            assert_eq!(baby_step_giant_step(&h, max), None);
+        }
+    }
+}
@@ -0,0 +1,252 @@
+//! DSA signatures over the subgroup of prime order `q` of a MODP group, as in
+//! [FIPS 186-4](https://doi.org/10.6028/NIST.FIPS.186-4) section 4.
+//!
+//! The secret key is a [Scalar] `x` and the public key is `y = g^x mod p`. A signature of `msg`
+//! is `(r, s)` with `r = (g^k mod p) mod q` and `s = k^(-1) (z + x * r) mod q` for a random
+//! nonce `k`, where `z` is the leftmost `min(N, outlen)` bits of `D(msg)` and `N` the bit length
+//! of q. A nonce giving `r = 0` or `s = 0` is rejected, and signing retries with another one.
+//!
+//! The signatures work over any [MODPGroup] whose
+//! [sophie_garmain_prime](MODPGroup::sophie_garmain_prime) is the order of the subgroup generated
+//! by its generator, e.g. the groups with 224 or 256 bit subgroups used by DSA.
+//!
+//! # Example
+//!
+//! ```rust
+//! use sha2::Sha256;
+//! use diffie_hellman_groups::{dsa, Element, Scalar, group::MODPGroup14};
+//!
+//! let rng = &mut rand::thread_rng();
+//! let sk = Scalar::<MODPGroup14>::random(rng);
+//! let pk = Element::<MODPGroup14>::from_biguint(sk.value.clone());
+//!
+//! let sig = dsa::sign::<_, Sha256, _>(&sk, b"message", rng);
+//! assert!(dsa::verify::<_, Sha256>(&pk, b"message", &sig));
+//! assert!(!dsa::verify::<_, Sha256>(&pk, b"another message", &sig));
+//! ```
+
+use num_bigint::BigUint;
+use rand_core::CryptoRngCore;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{group::MODPGroup, Element, NamedDigest, Scalar};
+
+/// A DSA signature `(r, s)`.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(bound = ""))]
+pub struct Signature<G: MODPGroup> {
+    pub r: Scalar<G>,
+    pub s: Scalar<G>,
+}
+
+impl<G: MODPGroup> PartialEq for Signature<G> {
+    fn eq(&self, other: &Self) -> bool {
+        self.r == other.r && self.s == other.s
+    }
+}
+
+impl<G: MODPGroup> Eq for Signature<G> {}
+
+/// Sign `msg` with the secret key `sk`, using random nonces from `rng`.
+pub fn sign<G: MODPGroup, D: NamedDigest, R: CryptoRngCore + ?Sized>(
+    sk: &Scalar<G>,
+    msg: &[u8],
+    rng: &mut R,
+) -> Signature<G> {
+    loop {
+        if let Some(sig) = sign_with_nonce::<G, D>(sk, msg, &Scalar::random(rng)) {
+            return sig;
+        }
+    }
+}
+
+/// Verify the signature `sig` of `msg` with the public key `pk`.
+pub fn verify<G: MODPGroup, D: NamedDigest>(
+    pk: &Element<G>,
+    msg: &[u8],
+    sig: &Signature<G>,
+) -> bool {
+    let zero = BigUint::from(0u64);
+    if sig.r.value == zero || sig.s.value == zero {
+        return false;
+    }
+    let p = G::prime_modulus();
+    if pk.value <= BigUint::from(1u64) || pk.value >= p {
+        return false;
+    }
+
+    let w = sig.s.inverse().expect("s is non-zero");
+    let u1 = digest_scalar::<G, D>(msg) * &w;
+    let u2 = &sig.r * &w;
+    let v = G::mul(&G::element(&u1.value), &G::pow(&pk.value, &u2.value));
+    Scalar::<G>::from_biguint(v) == sig.r
+}
+
+/// Sign `msg` with the nonce `k`, returning `None` if `r` or `s` is zero.
+fn sign_with_nonce<G: MODPGroup, D: NamedDigest>(
+    sk: &Scalar<G>,
+    msg: &[u8],
+    k: &Scalar<G>,
+) -> Option<Signature<G>> {
+    let zero = BigUint::from(0u64);
+    let r = Scalar::<G>::from_biguint(G::element(&k.value));
+    if r.value == zero {
+        return None;
+    }
+    let s = k.inverse()? * (digest_scalar::<G, D>(msg) + sk * &r);
+    if s.value == zero {
+        return None;
+    }
+    Some(Signature { r, s })
+}
+
+/// The leftmost `min(N, outlen)` bits of `D(msg)`, reduced modulo q.
+fn digest_scalar<G: MODPGroup, D: NamedDigest>(msg: &[u8]) -> Scalar<G> {
+    let hash = D::digest(msg);
+    let n = G::subgroup_order().bits();
+    let outlen = 8 * hash.len() as u64;
+    let z = BigUint::from_bytes_be(&hash) >> outlen.saturating_sub(n);
+    Scalar::from_biguint(z)
+}
+
+#[cfg(test)]
+mod test {
+    use lazy_static::lazy_static;
+    use sha2::{Sha256, Sha512};
+
+    use super::*;
+
+    lazy_static! {
+        static ref P: BigUint = hex(
+            "9DB6FB5951B66BB6FE1E140F1D2CE5502374161FD6538DF1648218642F0B5C48\
+             C8F7A41AADFA187324B87674FA1822B00F1ECF8136943D7C55757264E5A1A44F\
+             FE012E9936E00C1D3E9310B01C7D179805D3058B2A9F4BB6F9716BFE6117C6B5\
+             B3CC4D9BE341104AD4A80AD6C94E005F4B993E14F091EB51743BF33050C38DE2\
+             35567E1B34C3D6A5C0CEAA1A0F368213C3D19843D0B4B09DCB9FC72D39C8DE41\
+             F1BF14D4BB4563CA28371621CAD3324B6A2D392145BEBFAC748805236F5CA2FE\
+             92B871CD8F9C36D3292B5509CA8CAA77A2ADFC7BFD77DDA6F71125A7456FEA15\
+             3E433256A2261C6A06ED3693797E7995FAD5AABBCFBE3EDA2741E375404AE25B"
+        );
+        static ref Q: BigUint =
+            hex("F2C3119374CE76C9356990B465374A17F23F9ED35089BD969F61C6DDE9998C1F");
+        static ref G: BigUint = hex(
+            "5C7FF6B06F8F143FE8288433493E4769C4D988ACE5BE25A0E24809670716C613\
+             D7B0CEE6932F8FAA7C44D2CB24523DA53FBE4F6EC3595892D1AA58C4328A06C4\
+             6A15662E7EAA703A1DECF8BBB2D05DBE2EB956C142A338661D10461C0D135472\
+             085057F3494309FFA73C611F78B32ADBB5740C361C9F35BE90997DB2014E2EF5\
+             AA61782F52ABEB8BD6432C4DD097BC5423B285DAFB60DC364E8161F4A2A35ACA\
+             3A10B1C4D203CC76A470A33AFDCBDD92959859ABD8B56E1725252D78EAC66E71\
+             BA9AE3F1DD2487199874393CD4D832186800654760E1E34C09E4D155179F9EC0\
+             DC4473F996BDCE6EED1CABED8B6F116F7AD9CF505DF0F998E34AB27514B0FFE7"
+        );
+    }
+
+    fn hex(s: &str) -> BigUint {
+        BigUint::parse_bytes(s.as_bytes(), 16).unwrap()
+    }
+
+    /// The 2048/256 DSA parameters and key of RFC 6979 appendix A.2.2, whose signatures of
+    /// "sample" are the known answers.
+    ///
+    /// They stand in for a NIST CAVP SigGen vector of (L, N) = (2048, 256): RFC 6979 covers the
+    /// same sizes and lists the nonce k of each signature, so `sign_with_nonce` can reproduce it,
+    /// and its values can be checked against the published text, whereas the CAVP response files
+    /// are not vendored in this repository.
+    #[derive(Debug)]
+    struct Dsa2048;
+
+    impl MODPGroup for Dsa2048 {
+        fn prime_modulus() -> BigUint {
+            P.clone()
+        }
+
+        fn name() -> &'static str {
+            "dsa2048"
+        }
+
+        fn sophie_garmain_prime() -> BigUint {
+            Q.clone()
+        }
+
+        fn generator() -> BigUint {
+            G.clone()
+        }
+
+        fn pow(a: &BigUint, e: &BigUint) -> BigUint {
+            a.modpow(e, &P)
+        }
+    }
+
+    fn key_pair() -> (Scalar<Dsa2048>, Element<Dsa2048>) {
+        let x = Scalar::from_biguint(hex(
+            "69C7548C21D0DFEA6B9A51C9EAD4E27C33D3B3F180316E5BCAB92C933F0E4DBC",
+        ));
+        let y = Element::from_value(hex(
+            "667098C654426C78D7F8201EAC6C203EF030D43605032C2F1FA937E5237DBD94\
+             9F34A0A2564FE126DC8B715C5141802CE0979C8246463C40E6B6BDAA2513FA61\
+             1728716C2E4FD53BC95B89E69949D96512E873B9C8F8DFD499CC312882561ADE\
+             CB31F658E934C0C197F2C4D96B05CBAD67381E7B768891E4DA3843D24D94CDFB\
+             5126E9B8BF21E8358EE0E0A30EF13FD6A664C0DCE3731F7FB49A4845A4FD8254\
+             687972A2D382599C9BAC4E0ED7998193078913032558134976410B89D2C171D1\
+             23AC35FD977219597AA7D15C1A9A428E59194F75C721EBCBCFAE44696A499AFA\
+             74E04299F132026601638CB87AB79190D4A0986315DA8EEC6561C938996BEADF",
+        ));
+        (x, y)
+    }
+
+    fn known_answer<D: NamedDigest>(k: &str, r: &str, s: &str) {
+        let (x, y) = key_pair();
+        assert_eq!(Element::<Dsa2048>::from_biguint(x.value.clone()), y);
+
+        let expected = Signature {
+            r: Scalar::from_biguint(hex(r)),
+            s: Scalar::from_biguint(hex(s)),
+        };
+        let k = Scalar::from_biguint(hex(k));
+        assert_eq!(
+            sign_with_nonce::<_, D>(&x, b"sample", &k).unwrap(),
+            expected
+        );
+        assert!(verify::<_, D>(&y, b"sample", &expected));
+        assert!(!verify::<_, D>(&y, b"test", &expected));
+    }
+
+    #[test]
+    fn test_known_answer_sha256() {
+        known_answer::<Sha256>(
+            "8926A27C40484216F052F4427CFD5647338B7B3939BC6573AF4333569D597C52",
+            "EACE8BDBBE353C432A795D9EC556C6D021F7A03F42C36E9BC87E4AC7932CC809",
+            "7081E175455F9247B812B74583E9E94F9EA79BD640DC962533B0680793A38D53",
+        );
+    }
+
+    #[test]
+    fn test_known_answer_sha512() {
+        // the digest is truncated to the 256 bits of q
+        known_answer::<Sha512>(
+            "5A12994431785485B3F5F067221517791B85A597B7A9436995C89ED0374668FC",
+            "2016ED092DC5FB669B8EFB3D1F31A91EECB199879BE0CF78F02BA062CB4C942E",
+            "D0C76F84B5F091E141572A639A4FB8C230807EEA7D55C8A154A224400AFF2351",
+        );
+    }
+
+    #[test]
+    fn test_sign_verify() {
+        let rng = &mut rand::thread_rng();
+        let (x, y) = key_pair();
+        let sig = sign::<_, Sha256, _>(&x, b"message", rng);
+        assert!(verify::<_, Sha256>(&y, b"message", &sig));
+
+        let tampered = Signature {
+            r: Scalar::from_biguint(&sig.r.value + 1u64),
+            s: Scalar::from_biguint(sig.s.value.clone()),
+        };
+        assert!(!verify::<_, Sha256>(&y, b"message", &tampered));
+        let zero = Signature {
+            r: Scalar::from_biguint(sig.r.value.clone()),
+            s: Scalar::from_biguint(BigUint::from(0u64)),
+        };
+        assert!(!verify::<_, Sha256>(&y, b"message", &zero));
+    }
+}
@@ -0,0 +1,349 @@
+//! An object-safe view of the groups, to choose one at runtime and keep it behind a reference or
+//! in a `Box<dyn DhGroup>`.
+//!
+//! [MODPGroup] only has associated functions, so a group is a type parameter, e.g. of
+//! [Element]. [DhGroup] has the same operations on `&self`, and is implemented by:
+//! - [StaticGroup], a zero-sized adapter of any [MODPGroup],
+//! - [AnyModpGroup], the standard groups selected by their number,
+//! - [PrimeGroup](crate::PrimeGroup), with the feature `primegroup`.
+//!
+//! Values are passed as `BigUint`s. A public value received from a peer is checked with
+//! [DhGroup::validate_peer], whose [ValidatedPublicValue] is the only input accepted by
+//! [DhGroup::shared_secret]. [Element] and the protocols generic over [MODPGroup] are not
+//! affected.
+//!
+//! # Example
+//!
+//! ```rust
+//! use diffie_hellman_groups::{dyn_group::{DhGroup, StaticGroup}, group::MODPGroup14, AnyModpGroup};
+//!
+//! let groups: Vec<Box<dyn DhGroup>> = vec![
+//!     Box::new(StaticGroup::<MODPGroup14>::new()),
+//!     Box::new(AnyModpGroup::try_from(256u16).unwrap()),
+//! ];
+//!
+//! let rng = &mut rand::thread_rng();
+//! for group in &groups {
+//!     let a = group.random_exponent(rng, Some(256));
+//!     let b = group.random_exponent(rng, Some(256));
+//!     let ya = group.validate_peer(group.element(&a)).unwrap();
+//!     let yb = group.validate_peer(group.element(&b)).unwrap();
+//!     assert_eq!(
+//!         group.shared_secret(&a, &yb).unwrap(),
+//!         group.shared_secret(&b, &ya).unwrap()
+//!     );
+//! }
+//! ```
+
+use std::{
+    fmt::{self, Debug},
+    marker::PhantomData,
+};
+
+use num_bigint::{BigUint, RandBigInt};
+use rand_core::CryptoRngCore;
+
+use crate::{group::MODPGroup, AnyModpGroup, DhGroupsError, Element, ValidationError};
+
+/// Object-safe trait of a group of prime order q modulo a prime p, see the module documentation.
+pub trait DhGroup: Debug + Send + Sync {
+    /// prime modulus p
+    fn prime(&self) -> &BigUint;
+
+    /// the prime order q of the subgroup generated by g
+    fn order(&self) -> &BigUint;
+
+    /// generator g of the subgroup of order q
+    fn generator(&self) -> &BigUint;
+
+    /// The size of the prime modulus in bits.
+    fn bit_size(&self) -> usize {
+        self.prime().bits() as usize
+    }
+
+    /// modular exponentiation, compute a^e mod p
+    fn pow(&self, a: &BigUint, e: &BigUint) -> BigUint;
+
+    /// modular multiplication, compute a * b mod p
+    fn mul(&self, a: &BigUint, b: &BigUint) -> BigUint {
+        (a * b) % self.prime()
+    }
+
+    /// compute the element of the group from the exponent, compute g^e mod p, e.g. the public
+    /// value of a secret exponent
+    fn element(&self, exponent: &BigUint) -> BigUint {
+        self.pow(self.generator(), exponent)
+    }
+
+    /// Draw a secret exponent from `[1, min(2^exponent_bits, q))`, or from `[1, q)` if
+    /// `exponent_bits` is `None`.
+    fn random_exponent(&self, rng: &mut dyn CryptoRngCore, exponent_bits: Option<u64>) -> BigUint {
+        let q = self.order();
+        let bound = match exponent_bits {
+            Some(bits) => (BigUint::from(1u64) << bits).min(q.clone()),
+            None => q.clone(),
+        };
+        rng.gen_biguint_range(&BigUint::from(1u64), &bound)
+    }
+
+    /// Validate a public value received from a peer: it must be in `[1, p)`, not be 1 or `p - 1`,
+    /// and lie in the subgroup of order `q`.
+    fn validate(&self, value: &BigUint) -> Result<(), ValidationError> {
+        let one = BigUint::from(1u64);
+        if *value == BigUint::from(0u64) || value >= self.prime() {
+            return Err(ValidationError::OutOfRange);
+        }
+        if *value == one || *value == self.prime() - 1u64 {
+            return Err(ValidationError::Degenerate);
+        }
+        if self.pow(value, self.order()) != one {
+            return Err(ValidationError::NotInSubgroup);
+        }
+        Ok(())
+    }
+
+    /// Validate a public value received from a peer with [DhGroup::validate], to use it in
+    /// [DhGroup::shared_secret].
+    fn validate_peer(&self, value: BigUint) -> Result<ValidatedPublicValue, ValidationError> {
+        self.validate(&value)?;
+        Ok(ValidatedPublicValue {
+            value,
+            prime: self.prime().clone(),
+            order: self.order().clone(),
+        })
+    }
+
+    /// Compute the shared secret `peer^exponent mod p` with the validated public value of a peer.
+    ///
+    /// # Errors
+    /// Returns [DhGroupsError::GroupMismatch] if `peer` was validated in a group of another p or
+    /// q.
+    fn shared_secret(
+        &self,
+        exponent: &BigUint,
+        peer: &ValidatedPublicValue,
+    ) -> Result<BigUint, DhGroupsError> {
+        if peer.prime != *self.prime() || peer.order != *self.order() {
+            return Err(DhGroupsError::GroupMismatch);
+        }
+        Ok(self.pow(&peer.value, exponent))
+    }
+}
+
+/// A public value that passed [DhGroup::validate_peer], with the p and q of the group that
+/// validated it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidatedPublicValue {
+    value: BigUint,
+    prime: BigUint,
+    order: BigUint,
+}
+
+impl ValidatedPublicValue {
+    /// The validated value.
+    pub fn value(&self) -> &BigUint {
+        &self.value
+    }
+
+    /// Take the validated value.
+    pub fn into_value(self) -> BigUint {
+        self.value
+    }
+}
+
+/// The zero-sized [DhGroup] of a [MODPGroup] `G`, computing with the functions of `G` and
+/// borrowing its parameters from [MODPGroup::constants].
+pub struct StaticGroup<G: MODPGroup>(PhantomData<fn() -> G>);
+
+impl<G: MODPGroup> StaticGroup<G> {
+    /// The adapter of `G`, to be borrowed for `'static`.
+    pub(crate) const INSTANCE: Self = StaticGroup(PhantomData);
+
+    /// The adapter of `G`.
+    pub const fn new() -> Self {
+        StaticGroup(PhantomData)
+    }
+}
+
+impl<G: MODPGroup> Default for StaticGroup<G> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<G: MODPGroup> Clone for StaticGroup<G> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<G: MODPGroup> Copy for StaticGroup<G> {}
+
+impl<G: MODPGroup> Debug for StaticGroup<G> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("StaticGroup")
+            .field(&std::any::type_name::<G>())
+            .finish()
+    }
+}
+
+impl<G: MODPGroup> DhGroup for StaticGroup<G> {
+    fn prime(&self) -> &BigUint {
+        G::constants().prime_modulus()
+    }
+
+    fn order(&self) -> &BigUint {
+        G::constants().subgroup_order()
+    }
+
+    fn generator(&self) -> &BigUint {
+        G::constants().generator()
+    }
+
+    fn bit_size(&self) -> usize {
+        G::bit_size()
+    }
+
+    fn pow(&self, a: &BigUint, e: &BigUint) -> BigUint {
+        G::pow(a, e)
+    }
+
+    fn mul(&self, a: &BigUint, b: &BigUint) -> BigUint {
+        G::mul(a, b)
+    }
+
+    fn element(&self, exponent: &BigUint) -> BigUint {
+        G::element(exponent)
+    }
+
+    fn validate(&self, value: &BigUint) -> Result<(), ValidationError> {
+        if value >= self.prime() {
+            return Err(ValidationError::OutOfRange);
+        }
+        Element::<G>::from_value(value.clone()).validate()
+    }
+}
+
+impl DhGroup for AnyModpGroup {
+    fn prime(&self) -> &BigUint {
+        self.as_dyn().prime()
+    }
+
+    fn order(&self) -> &BigUint {
+        self.as_dyn().order()
+    }
+
+    fn generator(&self) -> &BigUint {
+        self.as_dyn().generator()
+    }
+
+    fn bit_size(&self) -> usize {
+        AnyModpGroup::bit_size(self)
+    }
+
+    fn pow(&self, a: &BigUint, e: &BigUint) -> BigUint {
+        AnyModpGroup::pow(self, a, e)
+    }
+
+    fn mul(&self, a: &BigUint, b: &BigUint) -> BigUint {
+        self.as_dyn().mul(a, b)
+    }
+
+    fn element(&self, exponent: &BigUint) -> BigUint {
+        AnyModpGroup::element(self, exponent)
+    }
+
+    fn validate(&self, value: &BigUint) -> Result<(), ValidationError> {
+        AnyModpGroup::validate(self, value)
+    }
+}
+
+#[cfg(feature = "primegroup")]
+impl DhGroup for crate::PrimeGroup {
+    fn prime(&self) -> &BigUint {
+        &self.p
+    }
+
+    fn order(&self) -> &BigUint {
+        &self.q
+    }
+
+    fn generator(&self) -> &BigUint {
+        &self.g
+    }
+
+    fn pow(&self, a: &BigUint, e: &BigUint) -> BigUint {
+        a.modpow(e, &self.p)
+    }
+
+    fn validate(&self, value: &BigUint) -> Result<(), ValidationError> {
+        self.validate_public_key(value)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::group::{FFDHEGroup2048, MODPGroup14, MODPGroup22, MODPGroup5};
+
+    fn test_key_exchange(group: &dyn DhGroup) {
+        let rng = &mut rand::thread_rng();
+        let a = group.random_exponent(rng, Some(256));
+        let b = group.random_exponent(rng, Some(256));
+        let (ya, yb) = (group.element(&a), group.element(&b));
+        let za = group
+            .shared_secret(&a, &group.validate_peer(yb.clone()).unwrap())
+            .unwrap();
+        let zb = group
+            .shared_secret(&b, &group.validate_peer(ya.clone()).unwrap())
+            .unwrap();
+        assert_eq!(za, zb);
+
+        assert_eq!(group.bit_size() as u64, group.prime().bits());
+        assert_eq!(
+            group.pow(group.generator(), group.order()),
+            BigUint::from(1u64)
+        );
+        assert_eq!(group.mul(&ya, &yb), (&ya * &yb) % group.prime());
+
+        let p = group.prime();
+        for (value, expected) in [
+            (BigUint::from(0u64), ValidationError::OutOfRange),
+            (BigUint::from(1u64), ValidationError::Degenerate),
+            (p - 1u64, ValidationError::Degenerate),
+            (p.clone(), ValidationError::OutOfRange),
+        ] {
+            assert_eq!(group.validate(&value), Err(expected));
+        }
+    }
+
+    #[test]
+    fn test_static_group() {
+        test_key_exchange(&StaticGroup::<MODPGroup5>::new());
+        test_key_exchange(&StaticGroup::<MODPGroup22>::new());
+        assert_eq!(std::mem::size_of::<StaticGroup<MODPGroup14>>(), 0);
+        assert_eq!(
+            StaticGroup::<MODPGroup14>::new().prime(),
+            &MODPGroup14::prime_modulus()
+        );
+    }
+
+    #[test]
+    fn test_any_group() {
+        let groups: Vec<Box<dyn DhGroup>> = vec![
+            Box::new(AnyModpGroup::try_from(14u16).unwrap()),
+            Box::new(AnyModpGroup::try_from(256u16).unwrap()),
+        ];
+        for group in &groups {
+            test_key_exchange(group.as_ref());
+        }
+        assert_eq!(groups[1].prime(), &FFDHEGroup2048::prime_modulus());
+    }
+
+    #[cfg(feature = "primegroup")]
+    #[test]
+    fn test_prime_group() {
+        let group = crate::PrimeGroup::new::<MODPGroup5>(128).unwrap();
+        test_key_exchange(&group);
+    }
+}
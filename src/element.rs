@@ -1,12 +1,15 @@
 use std::{
-    ops::{Add, Mul, Sub},
+    borrow::Borrow,
+    iter::{Product, Sum},
+    ops::{Add, AddAssign, Mul, MulAssign, Sub, SubAssign},
     str::FromStr,
 };
 
 use num_bigint::BigUint;
+#[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
-use crate::group::MODPGroup;
+use crate::{group::MODPGroup, BufferTooSmall, DhGroupsError, ValidationError};
 
 /// An element of a MODP group, implemented as a wrapper around a BigUint.
 ///
@@ -26,7 +29,8 @@ use crate::group::MODPGroup;
 /// let rhs = Element::<MODPGroup5>::from_biguint(BigUint::from(5u32)); // = g^5 mod p
 /// assert_eq!(lhs, rhs);
 /// ```
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(bound = ""))]
 pub struct Element<G: MODPGroup> {
     pub value: BigUint,
     phantom: std::marker::PhantomData<G>,
@@ -42,6 +46,136 @@ impl<G: MODPGroup> Element<G> {
         }
     }
 
+    /// Create an Element from its value, reduced modulo p. Unlike [Element::from_biguint], the value
+    /// is not used as an exponent of the generator.
+    pub fn from_value(value: BigUint) -> Self {
+        Element {
+            value: value % G::prime_modulus(),
+            phantom: std::marker::PhantomData,
+        }
+    }
+
+    /// Encode the value in big-endian bytes, left-padded with zeros to the length of p.
+    pub fn to_bytes_be(&self) -> Vec<u8> {
+        let mut out = vec![0u8; G::prime_modulus().bits().div_ceil(8) as usize];
+        self.write_bytes_be(&mut out)
+            .expect("buffer has the length of p");
+        out
+    }
+
+    /// Write the encoding of [Element::to_bytes_be] to `out` without allocating, returning the
+    /// number of bytes written. A buffer longer than p is left-padded with zeros in full, which
+    /// decodes to the same value but not with [Element::from_bytes_be], so the written length
+    /// is always `out.len()`.
+    ///
+    /// # Errors
+    /// Returns [BufferTooSmall] if `out` is shorter than p.
+    pub fn write_bytes_be(&self, out: &mut [u8]) -> Result<usize, BufferTooSmall> {
+        write_padded(
+            &self.value,
+            G::prime_modulus().bits().div_ceil(8) as usize,
+            out,
+        )
+    }
+
+    /// Decode an element from big-endian bytes of the length of p, as written by
+    /// [Element::to_bytes_be].
+    ///
+    /// # Errors
+    /// Returns [DhGroupsError::UnexpectedEof] or [DhGroupsError::InputTooLong] if `bytes` is
+    /// shorter or longer than p, or [DhGroupsError::Validation] if the value is not less than p.
+    pub fn from_bytes_be(bytes: &[u8]) -> Result<Self, DhGroupsError> {
+        let p = G::prime_modulus();
+        let value = decode_fixed(bytes, &p)?;
+        Ok(Element {
+            value,
+            phantom: std::marker::PhantomData,
+        })
+    }
+
+    /// Encode an element of the subgroup of order q in compressed form: its canonical
+    /// representative `min(x, p - x)` in `[1, (p - 1) / 2]`, in big-endian bytes left-padded to
+    /// the length of (p - 1) / 2, i.e. of q for a safe prime.
+    ///
+    /// No sign bit is needed to tell x from p - x for the elements of the subgroup. Since the
+    /// safe prime p = 2q + 1 is 3 mod 4, p - 1 is not a quadratic residue, so exactly one of x and
+    /// p - x lies in the subgroup of quadratic residues, the one [Element::from_compressed_bytes]
+    /// returns. An element outside the subgroup has the same encoding as its negation, which is
+    /// in the subgroup, so its sign is not recoverable and it is rejected. In the groups of
+    /// RFC 5114, whose subgroup has odd order, p - 1 is not in the subgroup either, so at most
+    /// one of x and p - x is.
+    ///
+    /// # Errors
+    /// Returns [ValidationError::NotInSubgroup] if the element is not in the subgroup of order q.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use num_bigint::BigUint;
+    /// use diffie_hellman_groups::{Element, group::{MODPGroup, MODPGroup5}};
+    ///
+    /// let x = Element::<MODPGroup5>::from_biguint(BigUint::from(42u32));
+    /// let bytes = x.to_compressed_bytes().unwrap();
+    /// assert_eq!(bytes.len(), MODPGroup5::sophie_garmain_prime().bits().div_ceil(8) as usize);
+    /// assert_eq!(Element::from_compressed_bytes(&bytes).unwrap(), x);
+    /// ```
+    pub fn to_compressed_bytes(&self) -> Result<Vec<u8>, ValidationError> {
+        if self.pow(&G::subgroup_order()).value != BigUint::from(1u64) {
+            return Err(ValidationError::NotInSubgroup);
+        }
+        let p = G::prime_modulus();
+        let canonical = (&p - &self.value).min(self.value.clone());
+        let mut out = vec![0u8; G::sophie_garmain_prime().bits().div_ceil(8) as usize];
+        write_padded(&canonical, out.len(), &mut out)
+            .expect("min(x, p - x) is at most (p - 1) / 2");
+        Ok(out)
+    }
+
+    /// Decode an element of the subgroup of order q written by [Element::to_compressed_bytes],
+    /// i.e. the one of `m` and `p - m` that lies in the subgroup.
+    ///
+    /// # Errors
+    /// Returns [DhGroupsError::UnexpectedEof] or [DhGroupsError::InputTooLong] if `bytes` is
+    /// shorter or longer than (p - 1) / 2, or [DhGroupsError::Validation] if the value `m` is not
+    /// in `[1, (p - 1) / 2]` or, in the groups of RFC 5114, neither `m` nor `p - m` is in the
+    /// subgroup.
+    pub fn from_compressed_bytes(bytes: &[u8]) -> Result<Self, DhGroupsError> {
+        let half = G::sophie_garmain_prime();
+        let canonical = decode_fixed(bytes, &(&half + 1u64))?;
+        if canonical == BigUint::ZERO {
+            return Err(ValidationError::OutOfRange.into());
+        }
+        let q = G::subgroup_order();
+        let one = BigUint::from(1u64);
+        let element = Element::from_value(canonical);
+        if element.pow(&q).value == one {
+            return Ok(element);
+        }
+        let negated = Element::from_value(G::prime_modulus() - element.value);
+        // for a safe prime, exactly one of m and p - m is in the subgroup
+        if q != half && negated.pow(&q).value != one {
+            return Err(ValidationError::NotInSubgroup.into());
+        }
+        Ok(negated)
+    }
+
+    /// Validate an element received from a peer: it must be in `[1, p)`, not be 1 or `p - 1`, and
+    /// lie in the subgroup of order `q`.
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        let p = G::prime_modulus();
+        let one = BigUint::from(1u64);
+        if self.value == BigUint::from(0u64) || self.value >= p {
+            return Err(ValidationError::OutOfRange);
+        }
+        if self.value == one || self.value == G::p_minus_one() {
+            return Err(ValidationError::Degenerate);
+        }
+        if self.pow(&G::subgroup_order()).value != one {
+            return Err(ValidationError::NotInSubgroup);
+        }
+        Ok(())
+    }
+
     /// Raise the element to the power of the exponent. Returns the value = self.value^exponent mod p,
     /// where p is the modulus of the group.
     ///
@@ -64,6 +198,336 @@ impl<G: MODPGroup> Element<G> {
             phantom: std::marker::PhantomData,
         }
     }
+
+    /// Compute self^2 mod p with [MODPGroup::square], which the groups of RFC 3526 implement
+    /// with a dedicated squaring instead of a multiplication of the value by itself.
+    pub fn square(&self) -> Self {
+        Element {
+            value: G::square(&self.value),
+            phantom: std::marker::PhantomData,
+        }
+    }
+
+    /// Compute the product of `base^exponent` over `terms`, with
+    /// [Element::multi_pow_straus] below [PIPPENGER_THRESHOLD] terms and with
+    /// [Element::multi_pow_pippenger] from there on. Returns 1 if `terms` is empty.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use num_bigint::BigUint;
+    /// use diffie_hellman_groups::{Element, group::MODPGroup5};
+    ///
+    /// let a = Element::<MODPGroup5>::from_biguint(BigUint::from(2u32)); // = g^2 mod p
+    /// let b = Element::<MODPGroup5>::from_biguint(BigUint::from(3u32)); // = g^3 mod p
+    ///
+    /// let lhs = Element::multi_pow(&[(&a, &BigUint::from(5u32)), (&b, &BigUint::from(7u32))]);
+    /// let rhs = Element::<MODPGroup5>::from_biguint(BigUint::from(31u32)); // = g^31 mod p
+    /// assert_eq!(lhs, rhs);
+    /// ```
+    pub fn multi_pow(terms: &[(&Element<G>, &BigUint)]) -> Self {
+        if terms.len() < PIPPENGER_THRESHOLD {
+            Self::multi_pow_straus(terms)
+        } else {
+            Self::multi_pow_pippenger(terms)
+        }
+    }
+
+    /// Compute the product of `base^exponent` over `terms` with Straus' simultaneous
+    /// exponentiation: the squarings are shared by all the terms, and each term costs a table of
+    /// 15 powers and one multiplication per nonzero 4-bit window of its exponent. Returns 1 if
+    /// `terms` is empty.
+    pub fn multi_pow_straus(terms: &[(&Element<G>, &BigUint)]) -> Self {
+        const WINDOW: u64 = 4;
+        const WINDOWS_PER_DIGIT: u64 = 64 / WINDOW;
+        let one = BigUint::from(1u64);
+
+        // the powers base^0..base^15 and the 64-bit digits of the exponent of each term
+        let terms: Vec<(Vec<BigUint>, Vec<u64>)> = terms
+            .iter()
+            .map(|(base, exponent)| {
+                let mut table = vec![one.clone(), G::fast_reduce(&base.value)];
+                for i in 2..1 << WINDOW {
+                    table.push(G::mul(&table[i - 1], &table[1]));
+                }
+                (table, exponent.to_u64_digits())
+            })
+            .collect();
+        let windows = terms
+            .iter()
+            .map(|(_, digits)| digits.len() as u64 * WINDOWS_PER_DIGIT)
+            .max()
+            .unwrap_or(0);
+
+        let mut acc = one.clone();
+        for w in (0..windows).rev() {
+            if acc != one {
+                for _ in 0..WINDOW {
+                    acc = G::square(&acc);
+                }
+            }
+            let (digit, shift) = (
+                (w / WINDOWS_PER_DIGIT) as usize,
+                (w % WINDOWS_PER_DIGIT) * WINDOW,
+            );
+            for (table, digits) in &terms {
+                let window = digits.get(digit).map_or(0, |d| (d >> shift) & 0xf);
+                if window != 0 {
+                    G::mul_assign(&mut acc, &table[window as usize]);
+                }
+            }
+        }
+        Element {
+            value: acc,
+            phantom: std::marker::PhantomData,
+        }
+    }
+
+    /// Compute the product of `base^exponent` over `terms` with Pippenger's bucket method, which
+    /// is faster than [Element::multi_pow] for large batches, e.g. in a batch verification.
+    ///
+    /// The exponents are cut into windows of `c` bits, with `c` chosen from the number of terms
+    /// and the length of the exponents. For each window, the bases are multiplied into the
+    /// bucket of their window value, and the buckets are combined with two multiplications per
+    /// bucket, so that each term costs one multiplication per window, without a table of
+    /// powers. Returns 1 if `terms` is empty.
+    pub fn multi_pow_pippenger(terms: &[(&Element<G>, &BigUint)]) -> Self {
+        let bits = terms.iter().map(|(_, e)| e.bits()).max().unwrap_or(0);
+        let c = pippenger_window(terms.len(), bits);
+        let terms: Vec<(BigUint, Vec<u64>)> = terms
+            .iter()
+            .map(|(base, exponent)| (G::fast_reduce(&base.value), exponent.to_u64_digits()))
+            .collect();
+
+        let mut acc: Option<BigUint> = None;
+        let mut buckets: Vec<Option<BigUint>> = vec![None; (1 << c) - 1];
+        for w in (0..bits.div_ceil(c)).rev() {
+            if let Some(acc) = acc.as_mut() {
+                for _ in 0..c {
+                    *acc = G::square(acc);
+                }
+            }
+            for (base, digits) in &terms {
+                let window = window_bits(digits, w * c, c);
+                if window != 0 {
+                    match &mut buckets[window - 1] {
+                        Some(bucket) => G::mul_assign(bucket, base),
+                        bucket => *bucket = Some(base.clone()),
+                    }
+                }
+            }
+            // the product of bucket_k^k, as the product of the running products of the buckets
+            // from the highest
+            let mut running: Option<BigUint> = None;
+            for bucket in buckets.iter_mut().rev() {
+                if let Some(bucket) = bucket.take() {
+                    match running.as_mut() {
+                        Some(running) => G::mul_assign(running, &bucket),
+                        None => running = Some(bucket),
+                    }
+                }
+                if let Some(running) = &running {
+                    match acc.as_mut() {
+                        Some(acc) => G::mul_assign(acc, running),
+                        None => acc = Some(running.clone()),
+                    }
+                }
+            }
+        }
+        Element {
+            value: acc.unwrap_or_else(|| BigUint::from(1u64)),
+            phantom: std::marker::PhantomData,
+        }
+    }
+
+    /// Compute `base^exponent` for each of `bases`, e.g. to raise many public keys to the order
+    /// q. The exponent is recoded once into the sliding windows of its odd digits, and the
+    /// schedule of squarings and multiplications is shared by all the bases, each of which
+    /// costs a table of odd powers. With the `parallel` feature, the bases are raised on the
+    /// threads of the global rayon pool.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use num_bigint::BigUint;
+    /// use diffie_hellman_groups::{Element, group::MODPGroup5};
+    ///
+    /// let bases: Vec<_> = (2..5u64)
+    ///     .map(|v| Element::<MODPGroup5>::from_value(BigUint::from(v)))
+    ///     .collect();
+    /// let exponent = BigUint::from(1000u64);
+    /// let powers = Element::pow_many_bases(&bases, &exponent);
+    /// assert_eq!(powers[1], bases[1].pow(&exponent));
+    /// ```
+    pub fn pow_many_bases(bases: &[Element<G>], exponent: &BigUint) -> Vec<Self> {
+        let width = sliding_window(exponent.bits());
+        let (steps, trailing) = sliding_schedule(exponent, width);
+        let pow = |base: &BigUint| {
+            // the odd powers base^1, base^3, ..., base^(2^width - 1)
+            let base = G::fast_reduce(base);
+            let square = G::square(&base);
+            let mut table = vec![base];
+            for i in 1..1 << (width - 1) {
+                table.push(G::mul(&table[i - 1], &square));
+            }
+
+            let mut acc: Option<BigUint> = None;
+            for &(squarings, digit) in &steps {
+                match acc.as_mut() {
+                    Some(acc) => {
+                        for _ in 0..squarings {
+                            *acc = G::square(acc);
+                        }
+                        G::mul_assign(acc, &table[digit >> 1]);
+                    }
+                    None => acc = Some(table[digit >> 1].clone()),
+                }
+            }
+            let mut acc = acc.unwrap_or_else(|| BigUint::from(1u64));
+            for _ in 0..trailing {
+                acc = G::square(&acc);
+            }
+            acc
+        };
+
+        // the values are raised rather than the elements, as the group marker need not be Sync
+        let values: Vec<&BigUint> = bases.iter().map(|base| &base.value).collect();
+        #[cfg(feature = "parallel")]
+        let powers: Vec<BigUint> = {
+            use rayon::prelude::*;
+            values.into_par_iter().map(pow).collect()
+        };
+        #[cfg(not(feature = "parallel"))]
+        let powers: Vec<BigUint> = values.into_iter().map(pow).collect();
+        powers
+            .into_iter()
+            .map(|value| Element {
+                value,
+                phantom: std::marker::PhantomData,
+            })
+            .collect()
+    }
+
+    /// Check for each of `elements` whether it lies in the subgroup of order q, i.e. whether
+    /// its power q is 1, with [Element::pow_many_bases].
+    pub fn batch_is_in_subgroup(elements: &[Element<G>]) -> Vec<bool> {
+        let one = BigUint::from(1u64);
+        Self::pow_many_bases(elements, &G::subgroup_order())
+            .into_iter()
+            .map(|power| power.value == one)
+            .collect()
+    }
+}
+
+/// The width of the sliding windows of [Element::pow_many_bases] minimizing the number of
+/// multiplications, about `2^(w-1) + bits / (w + 1)` for an exponent of `bits` bits.
+fn sliding_window(bits: u64) -> u64 {
+    (1..=8)
+        .min_by_key(|&w| (1 << (w - 1)) + bits / (w + 1))
+        .expect("the range is not empty")
+}
+
+/// Recode `exponent` into sliding windows of at most `width` bits, from the most significant.
+/// Returns the steps `(squarings, digit)`, raising the accumulator to `2^squarings` and then
+/// multiplying it by the power of the odd `digit`, and the number of squarings left at the end.
+fn sliding_schedule(exponent: &BigUint, width: u64) -> (Vec<(u64, usize)>, u64) {
+    let mut steps = Vec::new();
+    let mut squarings = 0;
+    let mut i = exponent.bits();
+    while i > 0 {
+        if !exponent.bit(i - 1) {
+            squarings += 1;
+            i -= 1;
+            continue;
+        }
+        // the window ends at the lowest set bit among the `width` bits from i - 1
+        let mut low = i.saturating_sub(width);
+        while !exponent.bit(low) {
+            low += 1;
+        }
+        let digit = (low..i)
+            .rev()
+            .fold(0, |d, j| d << 1 | exponent.bit(j) as usize);
+        steps.push((squarings + i - low, digit));
+        squarings = 0;
+        i = low;
+    }
+    (steps, squarings)
+}
+
+/// The number of terms from which [Element::multi_pow] uses Pippenger's bucket method, measured
+/// by the `multi_pow` benchmark of `benches/reduction.rs`.
+pub const PIPPENGER_THRESHOLD: usize = 128;
+
+/// The window width of Pippenger's bucket method minimizing the number of multiplications,
+/// about `bits / c * (n + 2^(c+1))` for `n` exponents of `bits` bits.
+fn pippenger_window(n: usize, bits: u64) -> u64 {
+    (1..=16)
+        .min_by_key(|&c| bits.div_ceil(c) * (n as u64 + (2 << c)))
+        .expect("the range is not empty")
+}
+
+/// The `width` bits of the little-endian 64-bit `digits` from bit `start`.
+fn window_bits(digits: &[u64], start: u64, width: u64) -> usize {
+    let (digit, shift) = ((start / 64) as usize, start % 64);
+    let mut window = digits.get(digit).map_or(0, |d| d >> shift);
+    if shift + width > 64 {
+        window |= digits.get(digit + 1).map_or(0, |d| d << (64 - shift));
+    }
+    (window & ((1 << width) - 1)) as usize
+}
+
+impl<G: MODPGroup> Element<G> {
+    /// Compute the sum of `elements` modulo p. The values are accumulated in a wide integer that
+    /// is reduced only when it grows a limb beyond p, instead of once per addition.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use num_bigint::BigUint;
+    /// use diffie_hellman_groups::{Element, group::MODPGroup5};
+    ///
+    /// let elements: Vec<_> = (1..=3u64)
+    ///     .map(|v| Element::<MODPGroup5>::from_value(BigUint::from(v)))
+    ///     .collect();
+    /// assert_eq!(Element::sum_mod(&elements).value, BigUint::from(6u64));
+    /// assert_eq!(Element::product_mod(&elements).value, BigUint::from(6u64));
+    /// ```
+    pub fn sum_mod<I>(elements: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Borrow<Element<G>>,
+    {
+        let limit = G::prime_modulus().bits() + 64;
+        let mut acc = BigUint::ZERO;
+        for element in elements {
+            acc += &element.borrow().value;
+            if acc.bits() > limit {
+                acc = G::fast_reduce(&acc);
+            }
+        }
+        Element {
+            value: G::fast_reduce(&acc),
+            phantom: std::marker::PhantomData,
+        }
+    }
+
+    /// Compute the product of `elements` modulo p, reducing each product with
+    /// [MODPGroup::fast_reduce]. Returns 1 if `elements` is empty.
+    pub fn product_mod<I>(elements: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Borrow<Element<G>>,
+    {
+        let mut acc = BigUint::from(1u64);
+        for element in elements {
+            G::mul_assign(&mut acc, &element.borrow().value);
+        }
+        Element {
+            value: G::fast_reduce(&acc),
+            phantom: std::marker::PhantomData,
+        }
+    }
 }
 
 impl<G: MODPGroup> PartialEq for Element<G> {
@@ -91,7 +555,49 @@ impl<G: MODPGroup> FromStr for Element<G> {
     }
 }
 
-// Implementing the Add, Sub, and Mul traits for the Element struct
+/// Write `value` to `out` in big-endian bytes, left-padded with zeros to the length of `out`,
+/// which must be at least `len` and fit the value.
+pub(crate) fn write_padded(
+    value: &BigUint,
+    len: usize,
+    out: &mut [u8],
+) -> Result<usize, BufferTooSmall> {
+    let needed = len.max(value.bits().div_ceil(8) as usize);
+    if out.len() < needed {
+        return Err(BufferTooSmall {
+            needed,
+            len: out.len(),
+        });
+    }
+    out.fill(0);
+    let bytes = value.iter_u64_digits().flat_map(u64::to_le_bytes);
+    for (byte, digit) in out.iter_mut().rev().zip(bytes) {
+        *byte = digit;
+    }
+    Ok(out.len())
+}
+
+/// Decode big-endian bytes of exactly the length of `bound` into a value less than `bound`.
+pub(crate) fn decode_fixed(bytes: &[u8], bound: &BigUint) -> Result<BigUint, DhGroupsError> {
+    let len = bound.bits().div_ceil(8) as usize;
+    if bytes.len() < len {
+        return Err(DhGroupsError::UnexpectedEof);
+    }
+    if bytes.len() > len {
+        return Err(DhGroupsError::InputTooLong {
+            len: bytes.len(),
+            max: len,
+        });
+    }
+    let value = BigUint::from_bytes_be(bytes);
+    if value >= *bound {
+        return Err(ValidationError::OutOfRange.into());
+    }
+    Ok(value)
+}
+
+// Implementing the Add, Sub, and Mul traits, their assigning variants, and Sum and Product for
+// the Element struct
 
 impl<G: MODPGroup> Add for Element<G> {
     type Output = Self;
@@ -224,3 +730,427 @@ impl<G: MODPGroup> Mul<Element<G>> for &Element<G> {
         }
     }
 }
+
+impl<G: MODPGroup> AddAssign<&Element<G>> for Element<G> {
+    fn add_assign(&mut self, rhs: &Element<G>) {
+        G::add_assign(&mut self.value, &rhs.value);
+    }
+}
+
+impl<G: MODPGroup> AddAssign for Element<G> {
+    fn add_assign(&mut self, rhs: Element<G>) {
+        G::add_assign(&mut self.value, &rhs.value);
+    }
+}
+
+impl<G: MODPGroup> SubAssign<&Element<G>> for Element<G> {
+    fn sub_assign(&mut self, rhs: &Element<G>) {
+        G::sub_assign(&mut self.value, &rhs.value);
+    }
+}
+
+impl<G: MODPGroup> SubAssign for Element<G> {
+    fn sub_assign(&mut self, rhs: Element<G>) {
+        G::sub_assign(&mut self.value, &rhs.value);
+    }
+}
+
+impl<G: MODPGroup> MulAssign<&Element<G>> for Element<G> {
+    fn mul_assign(&mut self, rhs: &Element<G>) {
+        G::mul_assign(&mut self.value, &rhs.value);
+    }
+}
+
+impl<G: MODPGroup> MulAssign for Element<G> {
+    fn mul_assign(&mut self, rhs: Element<G>) {
+        G::mul_assign(&mut self.value, &rhs.value);
+    }
+}
+
+impl<G: MODPGroup> Sum for Element<G> {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        Element::sum_mod(iter)
+    }
+}
+
+impl<'a, G: MODPGroup> Sum<&'a Element<G>> for Element<G> {
+    fn sum<I: Iterator<Item = &'a Element<G>>>(iter: I) -> Self {
+        Element::sum_mod(iter)
+    }
+}
+
+impl<G: MODPGroup> Product for Element<G> {
+    fn product<I: Iterator<Item = Self>>(iter: I) -> Self {
+        Element::product_mod(iter)
+    }
+}
+
+impl<'a, G: MODPGroup> Product<&'a Element<G>> for Element<G> {
+    fn product<I: Iterator<Item = &'a Element<G>>>(iter: I) -> Self {
+        Element::product_mod(iter)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use num_bigint::RandBigInt;
+
+    use super::*;
+    use crate::MODPGroup5;
+
+    #[test]
+    fn test_multi_pow_pippenger() {
+        let p = MODPGroup5::prime_modulus();
+        let mut rng = rand::thread_rng();
+        for n in [1, 2, 257, 1000] {
+            let bases: Vec<Element<MODPGroup5>> = (0..n)
+                .map(|_| Element::from_value(rng.gen_biguint_below(&p)))
+                .collect();
+            // short exponents with a few edge cases, as the naive product is slow
+            let mut exponents: Vec<BigUint> = (0..n).map(|_| rng.gen_biguint(256)).collect();
+            exponents[0] = BigUint::from(1u64);
+            if n > 2 {
+                exponents[1] = BigUint::ZERO;
+                exponents[2] = BigUint::from(u64::MAX) << 64;
+            }
+            let terms: Vec<_> = bases.iter().zip(&exponents).collect();
+            let expected = Element::product_mod(terms.iter().map(|(b, e)| b.pow(e)));
+            assert_eq!(Element::multi_pow_pippenger(&terms), expected, "{n} terms");
+            assert_eq!(Element::multi_pow_straus(&terms), expected, "{n} terms");
+            assert_eq!(Element::multi_pow(&terms), expected, "{n} terms");
+        }
+        assert_eq!(
+            Element::<MODPGroup5>::multi_pow_pippenger(&[]).value,
+            BigUint::from(1u64)
+        );
+        let zero = BigUint::ZERO;
+        let base = Element::<MODPGroup5>::from_value(BigUint::from(3u64));
+        assert_eq!(
+            Element::multi_pow_pippenger(&[(&base, &zero)]).value,
+            BigUint::from(1u64)
+        );
+    }
+
+    #[test]
+    fn test_pow_many_bases() {
+        let p = MODPGroup5::prime_modulus();
+        let q = MODPGroup5::sophie_garmain_prime();
+        let mut rng = rand::thread_rng();
+        let bases: Vec<Element<MODPGroup5>> = (0..8)
+            .map(|_| Element::from_value(rng.gen_biguint_below(&p)))
+            .collect();
+        let exponents = [
+            BigUint::ZERO,
+            BigUint::from(1u64),
+            BigUint::from(2u64),
+            BigUint::from(0b1011_0000u64),
+            BigUint::from(u64::MAX) << 64,
+            rng.gen_biguint(256),
+            q.clone(),
+        ];
+        for exponent in &exponents {
+            let expected: Vec<_> = bases.iter().map(|b| b.pow(exponent)).collect();
+            assert_eq!(Element::pow_many_bases(&bases, exponent), expected);
+        }
+        assert!(Element::<MODPGroup5>::pow_many_bases(&[], &q).is_empty());
+
+        // squares are in the subgroup, while 0 and p - 1 are not
+        let mut elements: Vec<_> = bases.iter().map(|b| b.square()).collect();
+        elements.push(Element::from_value(BigUint::ZERO));
+        elements.push(Element::from_value(&p - 1u64));
+        let mut expected = vec![true; bases.len()];
+        expected.extend([false, false]);
+        assert_eq!(Element::batch_is_in_subgroup(&elements), expected);
+    }
+
+    #[test]
+    fn test_sliding_schedule() {
+        for bits in [0, 1, 64, 256, 3072] {
+            let w = sliding_window(bits);
+            assert!((1..=8).contains(&w), "{bits} bits: w = {w}");
+        }
+        // 0b1011_0000 with windows of 3 bits: 0b101, then 0b1 and 4 squarings
+        let (steps, trailing) = sliding_schedule(&BigUint::from(0b1011_0000u64), 3);
+        assert_eq!(steps, vec![(3, 0b101), (1, 0b1)]);
+        assert_eq!(trailing, 4);
+        assert_eq!(sliding_schedule(&BigUint::ZERO, 4), (vec![], 0));
+    }
+
+    #[test]
+    fn test_window_bits() {
+        let digits = [0xf000_0000_0000_0001, 0x5];
+        assert_eq!(window_bits(&digits, 0, 4), 1);
+        assert_eq!(window_bits(&digits, 60, 8), 0x5f);
+        assert_eq!(window_bits(&digits, 64, 8), 0x5);
+        assert_eq!(window_bits(&digits, 128, 8), 0);
+        for n in [1, 1000] {
+            for bits in [256, 3072] {
+                let c = pippenger_window(n, bits);
+                assert!((1..=16).contains(&c), "{n} terms of {bits} bits: c = {c}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_write_bytes_be() {
+        let element = Element::<MODPGroup5>::from_value(BigUint::from(0x0102u64));
+
+        let mut exact = [0xffu8; 192];
+        assert_eq!(element.write_bytes_be(&mut exact), Ok(192));
+        assert_eq!(exact.to_vec(), element.to_bytes_be());
+        assert_eq!(exact[190..], [1, 2]);
+        assert!(exact[..190].iter().all(|&b| b == 0));
+
+        let mut oversized = [0xffu8; 200];
+        assert_eq!(element.write_bytes_be(&mut oversized), Ok(200));
+        assert!(oversized[..8].iter().all(|&b| b == 0));
+        assert_eq!(oversized[8..], exact);
+        assert_eq!(
+            Element::<MODPGroup5>::from_bytes_be(&oversized),
+            Err(DhGroupsError::InputTooLong { len: 200, max: 192 })
+        );
+
+        let mut undersized = [0xffu8; 191];
+        assert_eq!(
+            element.write_bytes_be(&mut undersized),
+            Err(BufferTooSmall {
+                needed: 192,
+                len: 191
+            })
+        );
+    }
+
+    #[test]
+    fn test_write_padded_full_width() {
+        let p_minus_one = MODPGroup5::prime_modulus() - 1u64;
+        let element = Element::<MODPGroup5>::from_value(p_minus_one.clone());
+        assert_eq!(element.to_bytes_be(), p_minus_one.to_bytes_be());
+
+        let mut out = [0u8; 4];
+        assert_eq!(
+            write_padded(&BigUint::from(0x010203u64), 0, &mut out),
+            Ok(4)
+        );
+        assert_eq!(out, [0, 1, 2, 3]);
+        assert_eq!(
+            write_padded(&BigUint::from(0x010203u64), 0, &mut out[..2]),
+            Err(BufferTooSmall { needed: 3, len: 2 })
+        );
+    }
+
+    #[test]
+    fn test_compressed_bytes() {
+        let p = MODPGroup5::prime_modulus();
+        let q = MODPGroup5::sophie_garmain_prime();
+        let mut rng = rand::thread_rng();
+        let one = Element::<MODPGroup5>::from_value(BigUint::from(1u64));
+        for x in (0..16)
+            .map(|_| Element::<MODPGroup5>::from_biguint(rng.gen_biguint_below(&q)))
+            .chain([one])
+        {
+            let bytes = x.to_compressed_bytes().unwrap();
+            assert_eq!(bytes.len(), 192);
+            assert!(BigUint::from_bytes_be(&bytes) <= q);
+            assert_eq!(Element::from_compressed_bytes(&bytes).unwrap(), x);
+
+            // the negation is outside the subgroup
+            let negated = Element::<MODPGroup5>::from_value(&p - &x.value);
+            assert_eq!(
+                negated.to_compressed_bytes(),
+                Err(ValidationError::NotInSubgroup)
+            );
+        }
+        assert_eq!(
+            Element::<MODPGroup5>::from_value(BigUint::ZERO).to_compressed_bytes(),
+            Err(ValidationError::NotInSubgroup)
+        );
+    }
+
+    #[test]
+    fn test_compressed_bytes_boundary() {
+        let p = MODPGroup5::prime_modulus();
+        let q = MODPGroup5::sophie_garmain_prime();
+        let encode = |m: &BigUint| {
+            let mut bytes = vec![0u8; 192];
+            write_padded(m, 192, &mut bytes).unwrap();
+            bytes
+        };
+
+        // (p - 1) / 2 is the largest canonical value, of q or q + 1, whichever is in the subgroup
+        let x = Element::<MODPGroup5>::from_compressed_bytes(&encode(&q)).unwrap();
+        assert!(x.value == q || x.value == &q + 1u64);
+        assert_eq!(x.pow(&q).value, BigUint::from(1u64));
+        assert_eq!(x.to_compressed_bytes().unwrap(), encode(&q));
+        assert_eq!(
+            Element::<MODPGroup5>::from_value(&p - &x.value).to_compressed_bytes(),
+            Err(ValidationError::NotInSubgroup)
+        );
+
+        for m in [BigUint::ZERO, &q + 1u64] {
+            assert_eq!(
+                Element::<MODPGroup5>::from_compressed_bytes(&encode(&m)),
+                Err(ValidationError::OutOfRange.into())
+            );
+        }
+        assert_eq!(
+            Element::<MODPGroup5>::from_compressed_bytes(&[1; 191]),
+            Err(DhGroupsError::UnexpectedEof)
+        );
+        assert_eq!(
+            Element::<MODPGroup5>::from_compressed_bytes(&[0; 193]),
+            Err(DhGroupsError::InputTooLong { len: 193, max: 192 })
+        );
+    }
+
+    #[test]
+    fn test_prime_order_subgroup() {
+        use crate::{MODPGroup22, Scalar};
+
+        type G = MODPGroup22;
+        let q = G::subgroup_order();
+        let rng = &mut rand::thread_rng();
+        let (a, b) = (Scalar::<G>::random(rng), Scalar::<G>::random(rng));
+        assert!(a.value < q && b.value < q);
+
+        // exponents are reduced modulo the order q of 160 bits
+        let ga = Element::<G>::from_biguint(a.value.clone());
+        let gb = Element::<G>::from_biguint(b.value.clone());
+        assert_eq!(&ga * &gb, Element::from_biguint((&a + &b).value));
+        assert_eq!(ga.pow(&(&q + 1u64)), ga);
+        assert_eq!(ga.validate(), Ok(()));
+
+        // with a cofactor larger than 2, most elements are outside of the subgroup
+        let outside = Element::<G>::from_value(&G::prime_modulus() - &ga.value);
+        assert_eq!(outside.validate(), Err(ValidationError::NotInSubgroup));
+        assert_eq!(
+            Element::<G>::from_value(BigUint::from(2u64)).validate(),
+            Err(ValidationError::NotInSubgroup)
+        );
+
+        let bytes = ga.to_compressed_bytes().unwrap();
+        assert_eq!(bytes.len(), 128);
+        assert_eq!(Element::<G>::from_compressed_bytes(&bytes).unwrap(), ga);
+        assert_eq!(
+            outside.to_compressed_bytes(),
+            Err(ValidationError::NotInSubgroup)
+        );
+        let mut encoded = vec![0u8; 128];
+        write_padded(&BigUint::from(2u64), 128, &mut encoded).unwrap();
+        assert_eq!(
+            Element::<G>::from_compressed_bytes(&encoded),
+            Err(ValidationError::NotInSubgroup.into())
+        );
+    }
+
+    #[test]
+    fn test_assign_ops() {
+        let p = MODPGroup5::prime_modulus();
+        let a = Element::<MODPGroup5>::from_value(&p - 3u64);
+        let b = Element::<MODPGroup5>::from_value(BigUint::from(5u64));
+
+        let mut c = a.clone();
+        c += &b;
+        assert_eq!(c, &a + &b);
+        assert_eq!(c.value, BigUint::from(2u64));
+
+        let mut c = b.clone();
+        c -= &a;
+        assert_eq!(c, &b - &a);
+        assert_eq!(c.value, BigUint::from(8u64));
+
+        let mut c = a.clone();
+        c *= b.clone();
+        assert_eq!(c, &a * &b);
+
+        // unreduced values are reduced as by the allocating operations
+        for value in [p.clone(), &p * 3u64 + 1u64] {
+            let mut x = value.clone();
+            MODPGroup5::add_assign(&mut x, &p);
+            assert_eq!(x, MODPGroup5::add(&value, &p));
+            let mut x = value.clone();
+            MODPGroup5::sub_assign(&mut x, &BigUint::from(1u64));
+            assert_eq!(x, MODPGroup5::sub(&value, &BigUint::from(1u64)));
+            let mut x = value.clone();
+            MODPGroup5::mul_assign(&mut x, &value);
+            assert_eq!(x, MODPGroup5::mul(&value, &value));
+        }
+    }
+
+    #[test]
+    fn test_sum_product() {
+        let mut rng = rand::thread_rng();
+        let p = MODPGroup5::prime_modulus();
+        for len in [0usize, 1, 2, 7, 100, 1000] {
+            let elements: Vec<Element<MODPGroup5>> = (0..len)
+                .map(|_| Element::from_value(rng.gen_biguint_below(&p)))
+                .collect();
+            let naive_sum = elements
+                .iter()
+                .fold(BigUint::ZERO, |acc, e| (acc + &e.value) % &p);
+            let naive_product = elements
+                .iter()
+                .fold(BigUint::from(1u64), |acc, e| acc * &e.value % &p);
+
+            assert_eq!(Element::sum_mod(&elements).value, naive_sum);
+            assert_eq!(Element::product_mod(&elements).value, naive_product);
+            assert_eq!(elements.iter().sum::<Element<_>>().value, naive_sum);
+            assert_eq!(elements.iter().product::<Element<_>>().value, naive_product);
+            assert_eq!(
+                elements.clone().into_iter().sum::<Element<_>>().value,
+                naive_sum
+            );
+            assert_eq!(
+                elements.into_iter().product::<Element<_>>().value,
+                naive_product
+            );
+        }
+
+        // values close to p, whose sum exceeds p many times over
+        let large = vec![Element::<MODPGroup5>::from_value(&p - 1u64); 1 << 12];
+        assert_eq!(
+            Element::sum_mod(&large).value,
+            (&p - 1u64) * BigUint::from(1u64 << 12) % &p
+        );
+    }
+
+    /// A group marker without serde impls, e.g. one defined outside this crate.
+    #[cfg(feature = "serde")]
+    #[derive(Debug)]
+    struct Toy;
+
+    #[cfg(feature = "serde")]
+    impl MODPGroup for Toy {
+        fn prime_modulus() -> BigUint {
+            BigUint::from(23u64)
+        }
+
+        fn sophie_garmain_prime() -> BigUint {
+            BigUint::from(11u64)
+        }
+
+        fn generator() -> BigUint {
+            BigUint::from(4u64)
+        }
+
+        fn pow(a: &BigUint, e: &BigUint) -> BigUint {
+            a.modpow(e, &Self::prime_modulus())
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_without_marker_bounds() {
+        let element = Element::<Toy>::from_biguint(BigUint::from(3u64));
+        let json = serde_json::to_string(&element).unwrap();
+        let decoded: Element<Toy> = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded, element);
+
+        let signature = crate::schnorr::Signature {
+            r: element,
+            s: crate::Scalar::<Toy>::from_biguint(BigUint::from(5u64)),
+        };
+        let json = serde_json::to_string(&signature).unwrap();
+        let decoded: crate::schnorr::Signature<Toy> = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded.s.value, signature.s.value);
+    }
+}
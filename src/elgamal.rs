@@ -0,0 +1,583 @@
+//! ElGamal encryption over a MODP group.
+//!
+//! The secret key is a [Scalar] `x` and the public key is the [Element] `h = g^x mod p`. A message
+//! `m`, an element of the group, is encrypted as `(g^r, m * h^r)` for a random `r`.
+//!
+//! Ciphertexts are multiplicatively homomorphic: the component-wise product of the encryptions of
+//! `a` and `b` is an encryption of `a * b`.
+//!
+//! In the exponential (or lifted) variant, a small integer `m` is encrypted as the element `g^m`
+//! by [encrypt_exp], giving an [ExpCiphertext]. These are additively homomorphic, and
+//! [decrypt_exp] recovers `m` by computing a discrete logarithm, which is only feasible for small
+//! values such as counters or tallies.
+//!
+//! A ciphertext is re-randomized into another encryption of the same message with
+//! [Ciphertext::rerandomize], e.g. by a mix-net, which proves that it did not change the message
+//! with [prove_rerandomization].
+//!
+//! Byte strings are mapped to elements of the subgroup of order `q` with [encode_message] and back
+//! with [decode_message], in the safe prime groups only, where that subgroup is the one of the
+//! quadratic residues.
+//!
+//! # Example
+//!
+//! ```rust
+//! use diffie_hellman_groups::{elgamal, Element, Scalar, group::MODPGroup5};
+//!
+//! let rng = &mut rand::thread_rng();
+//! let sk = Scalar::<MODPGroup5>::random(rng);
+//! let pk = Element::<MODPGroup5>::from_biguint(sk.value.clone());
+//!
+//! let m = Element::<MODPGroup5>::from_biguint(42u32.into());
+//! let ct = elgamal::encrypt(&pk, &m, rng);
+//! assert_eq!(elgamal::decrypt(&sk, &ct), m);
+//! ```
+
+use std::ops::{Add, Mul};
+
+use rand_core::CryptoRngCore;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use num_bigint::BigUint;
+
+use crate::{
+    dlog::baby_step_giant_step,
+    group::MODPGroup,
+    sigma::{self, DleqProof, Statement},
+    Element, EncodeError, NamedDigest, Scalar,
+};
+
+/// An ElGamal ciphertext `(c1, c2) = (g^r, m * h^r)`.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(bound = ""))]
+pub struct Ciphertext<G: MODPGroup> {
+    pub c1: Element<G>,
+    pub c2: Element<G>,
+}
+
+impl<G: MODPGroup> PartialEq for Ciphertext<G> {
+    fn eq(&self, other: &Self) -> bool {
+        self.c1 == other.c1 && self.c2 == other.c2
+    }
+}
+
+impl<G: MODPGroup> Eq for Ciphertext<G> {}
+
+impl<G: MODPGroup> Ciphertext<G> {
+    /// Re-randomize the ciphertext under the public key `pk` as `(c1 * g^s, c2 * h^s)` for a
+    /// random `s`, returning the new ciphertext, which encrypts the same message, and `s`.
+    pub fn rerandomize<R: CryptoRngCore + ?Sized>(
+        &self,
+        pk: &Element<G>,
+        rng: &mut R,
+    ) -> (Self, Scalar<G>) {
+        let s = Scalar::<G>::random(rng);
+        (self.rerandomize_with(pk, &s), s)
+    }
+
+    /// Re-randomize the ciphertext with the randomness `s`.
+    pub(crate) fn rerandomize_with(&self, pk: &Element<G>, s: &Scalar<G>) -> Self {
+        Ciphertext {
+            c1: &self.c1 * Element::from_biguint(s.value.clone()),
+            c2: &self.c2 * pk.pow(&s.value),
+        }
+    }
+}
+
+/// Encrypt the element `m` under the public key `pk`.
+pub fn encrypt<G: MODPGroup, R: CryptoRngCore + ?Sized>(
+    pk: &Element<G>,
+    m: &Element<G>,
+    rng: &mut R,
+) -> Ciphertext<G> {
+    let r = Scalar::<G>::random(rng);
+    Ciphertext {
+        c1: Element::from_biguint(r.value.clone()),
+        c2: m * pk.pow(&r.value),
+    }
+}
+
+/// Decrypt `ct` with the secret key `sk`, computing `c2 * c1^(-x)`.
+pub fn decrypt<G: MODPGroup>(sk: &Scalar<G>, ct: &Ciphertext<G>) -> Element<G> {
+    // c1 lies in the subgroup of order q, so c1^(q - x) is the inverse of c1^x
+    &ct.c2 * ct.c1.pow(&(-sk).value)
+}
+
+/// Prove that `rerandomized` is the re-randomization of `ct` under the public key `pk` with the
+/// randomness `s`, i.e. that `log_g(c1' / c1) = log_h(c2' / c2) = s`, so that both encrypt the
+/// same message.
+///
+/// # Example
+///
+/// ```rust
+/// use sha2::Sha256;
+/// use diffie_hellman_groups::{elgamal, Element, Scalar, group::MODPGroup5};
+///
+/// let rng = &mut rand::thread_rng();
+/// let sk = Scalar::<MODPGroup5>::random(rng);
+/// let pk = Element::<MODPGroup5>::from_biguint(sk.value.clone());
+///
+/// let ct = elgamal::encrypt(&pk, &Element::from_biguint(42u32.into()), rng);
+/// let (rerandomized, s) = ct.rerandomize(&pk, rng);
+/// let proof = elgamal::prove_rerandomization::<_, Sha256, _>(&pk, &ct, &rerandomized, &s, rng);
+/// assert!(elgamal::verify_rerandomization::<_, Sha256>(&pk, &ct, &rerandomized, &proof));
+/// ```
+pub fn prove_rerandomization<G: MODPGroup, D: NamedDigest, R: CryptoRngCore + ?Sized>(
+    pk: &Element<G>,
+    ct: &Ciphertext<G>,
+    rerandomized: &Ciphertext<G>,
+    s: &Scalar<G>,
+    rng: &mut R,
+) -> DleqProof<G> {
+    sigma::prove::<G, D, R>(&rerandomization_statement(pk, ct, rerandomized), s, rng)
+}
+
+/// Verify a proof that `rerandomized` is a re-randomization of `ct` under the public key `pk`.
+pub fn verify_rerandomization<G: MODPGroup, D: NamedDigest>(
+    pk: &Element<G>,
+    ct: &Ciphertext<G>,
+    rerandomized: &Ciphertext<G>,
+    proof: &DleqProof<G>,
+) -> bool {
+    sigma::verify::<G, D>(&rerandomization_statement(pk, ct, rerandomized), proof)
+}
+
+/// The statement `log_g(c1' / c1) = log_h(c2' / c2)`.
+pub(crate) fn rerandomization_statement<G: MODPGroup>(
+    pk: &Element<G>,
+    ct: &Ciphertext<G>,
+    rerandomized: &Ciphertext<G>,
+) -> Statement<G> {
+    // divide with the inverse modulo p, as the components may lie outside the subgroup
+    let p_minus_2 = G::prime_modulus() - 2u64;
+    let quotient = |a: &Element<G>, b: &Element<G>| a * b.pow(&p_minus_2);
+    Statement::dleq(
+        Element::from_value(G::generator()),
+        quotient(&rerandomized.c1, &ct.c1),
+        Element::from_value(pk.value.clone()),
+        quotient(&rerandomized.c2, &ct.c2),
+    )
+}
+
+/// An exponential ElGamal ciphertext `(c1, c2) = (g^r, g^m * h^r)` of an integer `m`.
+///
+/// Adding ciphertexts adds the plaintexts, and multiplying a ciphertext by a [Scalar] `k`
+/// multiplies its plaintext by `k`, both modulo q.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(bound = ""))]
+pub struct ExpCiphertext<G: MODPGroup> {
+    pub c1: Element<G>,
+    pub c2: Element<G>,
+}
+
+impl<G: MODPGroup> PartialEq for ExpCiphertext<G> {
+    fn eq(&self, other: &Self) -> bool {
+        self.c1 == other.c1 && self.c2 == other.c2
+    }
+}
+
+impl<G: MODPGroup> Eq for ExpCiphertext<G> {}
+
+/// Encrypt the integer `m` as the element `g^m` under the public key `pk`.
+///
+/// # Example
+///
+/// ```rust
+/// use diffie_hellman_groups::{elgamal, Element, Scalar, group::MODPGroup5};
+///
+/// let rng = &mut rand::thread_rng();
+/// let sk = Scalar::<MODPGroup5>::random(rng);
+/// let pk = Element::<MODPGroup5>::from_biguint(sk.value.clone());
+///
+/// let ct = elgamal::encrypt_exp(&pk, 20, rng) + elgamal::encrypt_exp(&pk, 22, rng);
+/// assert_eq!(elgamal::decrypt_exp(&sk, &ct, 100), Some(42));
+/// ```
+pub fn encrypt_exp<G: MODPGroup, R: CryptoRngCore + ?Sized>(
+    pk: &Element<G>,
+    m: u64,
+    rng: &mut R,
+) -> ExpCiphertext<G> {
+    let Ciphertext { c1, c2 } = encrypt(pk, &Element::from_biguint(BigUint::from(m)), rng);
+    ExpCiphertext { c1, c2 }
+}
+
+/// Decrypt `ct` with the secret key `sk` and recover the integer plaintext with
+/// [baby_step_giant_step]. Returns `None` if the plaintext is not in `[0, max]`.
+pub fn decrypt_exp<G: MODPGroup>(sk: &Scalar<G>, ct: &ExpCiphertext<G>, max: u64) -> Option<u64> {
+    let gm = &ct.c2 * ct.c1.pow(&(-sk).value);
+    baby_step_giant_step(&gm, max)
+}
+
+/// Maximum length in bytes of the messages accepted by [encode_message] for the group `G`, i.e.
+/// the largest `len` such that `2^(8 * len + 1) <= 2^(bits(q) - 1) < q`, or 0 if `G` is not a
+/// safe prime group.
+pub fn max_message_len<G: MODPGroup>() -> usize {
+    if !is_safe_prime_group::<G>() {
+        return 0;
+    }
+    ((G::sophie_garmain_prime().bits() - 2) / 8) as usize
+}
+
+/// Whether the generator of `G` has order `(p - 1) / 2`, so that its subgroup is that of the
+/// quadratic residues, on which the message encoding relies.
+fn is_safe_prime_group<G: MODPGroup>() -> bool {
+    G::subgroup_order() == G::sophie_garmain_prime()
+}
+
+/// Encode `msg` into an element of the subgroup of order `q` of a safe prime group.
+///
+/// The message is prefixed with the byte `0x01` and read as a big-endian integer `m` in `[1, q]`.
+/// Exactly one of `m` and `p - m` is a quadratic residue modulo p (since -1 is not, p being 3
+/// mod 4), and that one is the encoding. [decode_message] is its inverse.
+///
+/// # Errors
+/// Returns [EncodeError::NotSafePrimeGroup] if the subgroup of `G` is not that of the quadratic
+/// residues, e.g. for the groups of RFC 5114, or [EncodeError::MessageTooLong] if `msg` is longer
+/// than [max_message_len].
+///
+/// # Example
+///
+/// ```rust
+/// use diffie_hellman_groups::{elgamal, group::MODPGroup5};
+///
+/// let m = elgamal::encode_message::<MODPGroup5>(b"attack at dawn").unwrap();
+/// assert_eq!(elgamal::decode_message(&m).unwrap(), b"attack at dawn");
+/// ```
+pub fn encode_message<G: MODPGroup>(msg: &[u8]) -> Result<Element<G>, EncodeError> {
+    if !is_safe_prime_group::<G>() {
+        return Err(EncodeError::NotSafePrimeGroup);
+    }
+    let max = max_message_len::<G>();
+    if msg.len() > max {
+        return Err(EncodeError::MessageTooLong {
+            len: msg.len(),
+            max,
+        });
+    }
+
+    let mut padded = Vec::with_capacity(msg.len() + 1);
+    padded.push(1u8);
+    padded.extend_from_slice(msg);
+    let m = BigUint::from_bytes_be(&padded);
+
+    let p = G::prime_modulus();
+    let q = G::sophie_garmain_prime();
+    if G::pow(&m, &q) == BigUint::from(1u64) {
+        Ok(Element::from_value(m))
+    } else {
+        Ok(Element::from_value(p - m))
+    }
+}
+
+/// Decode an element created by [encode_message] back into the message.
+///
+/// # Errors
+/// Returns [EncodeError::NotSafePrimeGroup] as [encode_message], or [EncodeError::InvalidEncoding]
+/// if the element is not the encoding of a message of at most [max_message_len] bytes.
+pub fn decode_message<G: MODPGroup>(element: &Element<G>) -> Result<Vec<u8>, EncodeError> {
+    if !is_safe_prime_group::<G>() {
+        return Err(EncodeError::NotSafePrimeGroup);
+    }
+    let p = G::prime_modulus();
+    let q = G::sophie_garmain_prime();
+    let m = if element.value <= q {
+        element.value.clone()
+    } else {
+        p - &element.value
+    };
+
+    let padded = m.to_bytes_be();
+    match padded.split_first() {
+        Some((1, msg)) if msg.len() <= max_message_len::<G>() => Ok(msg.to_vec()),
+        _ => Err(EncodeError::InvalidEncoding),
+    }
+}
+
+// Implementing the Mul trait for the Ciphertext struct
+
+impl<G: MODPGroup> Mul for Ciphertext<G> {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        &self * &rhs
+    }
+}
+
+impl<G: MODPGroup> Mul for &Ciphertext<G> {
+    type Output = Ciphertext<G>;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        Ciphertext {
+            c1: &self.c1 * &rhs.c1,
+            c2: &self.c2 * &rhs.c2,
+        }
+    }
+}
+
+impl<G: MODPGroup> Mul<&Ciphertext<G>> for Ciphertext<G> {
+    type Output = Ciphertext<G>;
+
+    fn mul(self, rhs: &Ciphertext<G>) -> Self::Output {
+        &self * rhs
+    }
+}
+
+impl<G: MODPGroup> Mul<Ciphertext<G>> for &Ciphertext<G> {
+    type Output = Ciphertext<G>;
+
+    fn mul(self, rhs: Ciphertext<G>) -> Self::Output {
+        self * &rhs
+    }
+}
+
+// Implementing the Add and Mul traits for the ExpCiphertext struct
+
+impl<G: MODPGroup> Add for ExpCiphertext<G> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        &self + &rhs
+    }
+}
+
+impl<G: MODPGroup> Add for &ExpCiphertext<G> {
+    type Output = ExpCiphertext<G>;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        ExpCiphertext {
+            c1: &self.c1 * &rhs.c1,
+            c2: &self.c2 * &rhs.c2,
+        }
+    }
+}
+
+impl<G: MODPGroup> Add<&ExpCiphertext<G>> for ExpCiphertext<G> {
+    type Output = ExpCiphertext<G>;
+
+    fn add(self, rhs: &ExpCiphertext<G>) -> Self::Output {
+        &self + rhs
+    }
+}
+
+impl<G: MODPGroup> Add<ExpCiphertext<G>> for &ExpCiphertext<G> {
+    type Output = ExpCiphertext<G>;
+
+    fn add(self, rhs: ExpCiphertext<G>) -> Self::Output {
+        self + &rhs
+    }
+}
+
+impl<G: MODPGroup> Mul<Scalar<G>> for ExpCiphertext<G> {
+    type Output = Self;
+
+    fn mul(self, rhs: Scalar<G>) -> Self::Output {
+        &self * &rhs
+    }
+}
+
+impl<G: MODPGroup> Mul<&Scalar<G>> for &ExpCiphertext<G> {
+    type Output = ExpCiphertext<G>;
+
+    fn mul(self, rhs: &Scalar<G>) -> Self::Output {
+        ExpCiphertext {
+            c1: self.c1.pow(&rhs.value),
+            c2: self.c2.pow(&rhs.value),
+        }
+    }
+}
+
+impl<G: MODPGroup> Mul<&Scalar<G>> for ExpCiphertext<G> {
+    type Output = ExpCiphertext<G>;
+
+    fn mul(self, rhs: &Scalar<G>) -> Self::Output {
+        &self * rhs
+    }
+}
+
+impl<G: MODPGroup> Mul<Scalar<G>> for &ExpCiphertext<G> {
+    type Output = ExpCiphertext<G>;
+
+    fn mul(self, rhs: Scalar<G>) -> Self::Output {
+        self * &rhs
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rand::Rng;
+
+    use super::*;
+    use crate::group::{MODPGroup14, MODPGroup22, MODPGroup5};
+
+    fn random_element<G: MODPGroup>(rng: &mut impl CryptoRngCore) -> Element<G> {
+        Element::from_biguint(Scalar::<G>::random(rng).value)
+    }
+
+    fn test_round_trip<G: MODPGroup>() {
+        let rng = &mut rand::thread_rng();
+        let sk = Scalar::<G>::random(rng);
+        let pk = Element::<G>::from_biguint(sk.value.clone());
+
+        for _ in 0..3 {
+            let m = random_element::<G>(rng);
+            let ct = encrypt(&pk, &m, rng);
+            assert_ne!(ct.c2, m);
+            assert_eq!(decrypt(&sk, &ct), m);
+        }
+
+        let other_sk = Scalar::<G>::random(rng);
+        let m = random_element::<G>(rng);
+        assert_ne!(decrypt(&other_sk, &encrypt(&pk, &m, rng)), m);
+    }
+
+    fn test_homomorphism<G: MODPGroup>() {
+        let rng = &mut rand::thread_rng();
+        let sk = Scalar::<G>::random(rng);
+        let pk = Element::<G>::from_biguint(sk.value.clone());
+
+        let a = random_element::<G>(rng);
+        let b = random_element::<G>(rng);
+        let ct = encrypt(&pk, &a, rng) * encrypt(&pk, &b, rng);
+        assert_eq!(decrypt(&sk, &ct), a * b);
+    }
+
+    fn test_message_encoding<G: MODPGroup>() {
+        let max = max_message_len::<G>();
+        let q = G::sophie_garmain_prime();
+        let messages: [&[u8]; 4] = [b"", b"\0\0leading zeros", &[0xff; 64], &vec![0xff; max]];
+        for msg in messages {
+            let m = encode_message::<G>(msg).unwrap();
+            assert_eq!(G::pow(&m.value, &q), BigUint::from(1u64));
+            assert_eq!(decode_message(&m).unwrap(), msg);
+        }
+
+        assert_eq!(
+            encode_message::<G>(&vec![0; max + 1]).unwrap_err(),
+            EncodeError::MessageTooLong { len: max + 1, max }
+        );
+        assert_eq!(
+            decode_message(&Element::<G>::from_value(BigUint::from(4u64))).unwrap_err(),
+            EncodeError::InvalidEncoding
+        );
+    }
+
+    #[test]
+    fn test_message_encoding_group_5() {
+        assert_eq!(max_message_len::<MODPGroup5>(), 191);
+        test_message_encoding::<MODPGroup5>();
+    }
+
+    #[test]
+    fn test_message_encoding_group_22() {
+        // q is a 160-bit factor of p - 1, and the quadratic residues are not the subgroup
+        assert_eq!(max_message_len::<MODPGroup22>(), 0);
+        assert_eq!(
+            encode_message::<MODPGroup22>(b"attack at dawn").unwrap_err(),
+            EncodeError::NotSafePrimeGroup
+        );
+        let element = Element::<MODPGroup22>::from_value(MODPGroup22::generator());
+        assert_eq!(
+            decode_message(&element).unwrap_err(),
+            EncodeError::NotSafePrimeGroup
+        );
+    }
+
+    #[test]
+    fn test_message_encoding_group_14() {
+        assert_eq!(max_message_len::<MODPGroup14>(), 255);
+        test_message_encoding::<MODPGroup14>();
+
+        // encrypt a message of the maximum length
+        let rng = &mut rand::thread_rng();
+        let sk = Scalar::<MODPGroup14>::random(rng);
+        let pk = Element::<MODPGroup14>::from_biguint(sk.value.clone());
+        let msg = [0xa5; 255];
+        let ct = encrypt(&pk, &encode_message(&msg).unwrap(), rng);
+        assert_eq!(decode_message(&decrypt(&sk, &ct)).unwrap(), msg);
+    }
+
+    #[test]
+    fn test_exponential_sum() {
+        let rng = &mut rand::thread_rng();
+        let sk = Scalar::<MODPGroup5>::random(rng);
+        let pk = Element::<MODPGroup5>::from_biguint(sk.value.clone());
+
+        let values: Vec<u64> = (0..100).map(|_| rng.gen_range(0..1000)).collect();
+        let sum: u64 = values.iter().sum();
+        let ct = values
+            .iter()
+            .map(|&m| encrypt_exp(&pk, m, rng))
+            .reduce(|acc, ct| acc + ct)
+            .unwrap();
+
+        assert_eq!(decrypt_exp(&sk, &ct, 100_000), Some(sum));
+        assert_eq!(decrypt_exp(&sk, &ct, sum), Some(sum));
+        assert_eq!(decrypt_exp(&sk, &ct, sum - 1), None);
+
+        let tripled = ct * Scalar::from_biguint(BigUint::from(3u64));
+        assert_eq!(decrypt_exp(&sk, &tripled, 300_000), Some(3 * sum));
+    }
+
+    #[test]
+    fn test_round_trip_group_5() {
+        test_round_trip::<MODPGroup5>();
+    }
+
+    #[test]
+    fn test_round_trip_group_14() {
+        test_round_trip::<MODPGroup14>();
+    }
+
+    #[test]
+    fn test_homomorphism_group_5() {
+        test_homomorphism::<MODPGroup5>();
+    }
+
+    #[test]
+    fn test_homomorphism_group_14() {
+        test_homomorphism::<MODPGroup14>();
+    }
+
+    #[test]
+    fn test_rerandomization() {
+        use sha2::Sha256;
+
+        let rng = &mut rand::thread_rng();
+        let sk = Scalar::<MODPGroup5>::random(rng);
+        let pk = Element::<MODPGroup5>::from_biguint(sk.value.clone());
+        let m = random_element::<MODPGroup5>(rng);
+        let ct = encrypt(&pk, &m, rng);
+
+        let (rerandomized, s) = ct.rerandomize(&pk, rng);
+        assert_eq!(decrypt(&sk, &rerandomized), m);
+        let (other, _) = ct.rerandomize(&pk, rng);
+        for c in [&rerandomized, &other] {
+            assert_ne!(c.c1.to_bytes_be(), ct.c1.to_bytes_be());
+            assert_ne!(c.c2.to_bytes_be(), ct.c2.to_bytes_be());
+        }
+        assert_ne!(rerandomized, other);
+
+        let proof = prove_rerandomization::<_, Sha256, _>(&pk, &ct, &rerandomized, &s, rng);
+        assert!(verify_rerandomization::<_, Sha256>(
+            &pk,
+            &ct,
+            &rerandomized,
+            &proof
+        ));
+        assert!(!verify_rerandomization::<_, Sha256>(
+            &pk, &ct, &other, &proof
+        ));
+
+        // a re-randomization of another message, proven with the same randomness
+        let changed = Ciphertext {
+            c1: Element::from_value(rerandomized.c1.value.clone()),
+            c2: &rerandomized.c2 * random_element::<MODPGroup5>(rng),
+        };
+        assert_ne!(decrypt(&sk, &changed), m);
+        let proof = prove_rerandomization::<_, Sha256, _>(&pk, &ct, &changed, &s, rng);
+        assert!(!verify_rerandomization::<_, Sha256>(
+            &pk, &ct, &changed, &proof
+        ));
+    }
+}
@@ -0,0 +1,445 @@
+use std::fmt::{self, Display};
+
+use crate::AnyModpGroup;
+
+/// Errors returned by the fallible operations of this crate.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DhGroupsError {
+    /// The input ended before a complete value could be read.
+    UnexpectedEof,
+    /// Extra bytes remained after a complete message was decoded.
+    TrailingBytes,
+    /// The message number does not match the message being decoded.
+    UnexpectedMessageType { expected: u8, found: u8 },
+    /// The version of an encoding is neither the current nor the previous one, see
+    /// [WireVersion](crate::wire::WireVersion).
+    UnsupportedVersion(u8),
+    /// An SSH mpint is negative or not minimally encoded.
+    InvalidMpint,
+    /// An integer encoded as a TLS opaque vector is empty or not minimally encoded.
+    InvalidOpaque,
+    /// The modulus or the order of a group is not prime.
+    NotPrime,
+    /// The order of a group multiplied by its cofactor is not `p - 1`.
+    InvalidCofactor,
+    /// The generator of a group does not have the expected order.
+    InvalidGenerator,
+    /// A bit count lies outside of the range accepted by the operation.
+    BitsOutOfRange,
+    /// An encoded value is longer than the encoding of the values of its group, in bytes.
+    InputTooLong { len: usize, max: usize },
+    /// A modulus has more bits than the limit of the `GroupPolicy` it was checked against.
+    ModulusTooLarge { bits: u64, max: u64 },
+    /// An exponent has more bits than the order of its group.
+    ExponentTooLarge { bits: u64, max: u64 },
+    /// The modulus is not a safe prime, i.e. not of the form 2q + 1 with q prime.
+    NotSafePrime,
+    /// The operation was cancelled by the caller.
+    Cancelled,
+    /// The number of threads to search with is zero.
+    InvalidThreadCount,
+    /// The thread pool to search with cannot be built.
+    ThreadPool,
+    /// A line of a moduli file cannot be parsed.
+    MalformedModuli { line: usize },
+    /// An I/O error occurred while reading the input.
+    Io(std::io::ErrorKind),
+    /// A public key or group element failed validation.
+    Validation(ValidationError),
+    /// The input is not valid DER, or does not have the expected structure.
+    InvalidDer,
+    /// The input is not a PEM block with the expected label and a base64 body.
+    InvalidPem,
+    /// The input is not a JSON Web Key of the expected type with the required members.
+    InvalidJwk,
+    /// The input is not well-formed CBOR, or not a COSE_Key of the expected key type.
+    InvalidCbor,
+    /// An OpenSSL operation failed, or an OpenSSL key is not a Diffie-Hellman key.
+    OpenSsl,
+    /// A fixed-size integer type does not have the size of the modulus of the group, in bits.
+    UintSizeMismatch { expected: usize, found: usize },
+    /// Keys or elements of different groups were combined.
+    GroupMismatch,
+    /// A group was built without setting its modulus.
+    MissingModulus,
+    /// A message cannot be encoded into, or decoded from, a group element.
+    Encode(EncodeError),
+    /// The signature or MAC of the peer over a key exchange does not verify.
+    AuthenticationFailed,
+    /// A threshold is zero or greater than the number of participants.
+    InvalidThreshold,
+    /// A participant index is out of range, or a message is not addressed to its receiver.
+    InvalidParticipant,
+    /// Threshold decryption shares cannot be combined.
+    Threshold(ThresholdError),
+    /// Secret shares cannot be combined.
+    Share(ShareError),
+    /// The socialist millionaires' protocol was aborted, on a message out of order or failing
+    /// its proof.
+    SmpAborted,
+    /// A ciphertext or its additional data is not authentic.
+    DecryptionFailed,
+    /// A password authenticated key exchange failed.
+    Pake(PakeError),
+    /// A fingerprint is not of the form `ALGORITHM:base64`.
+    InvalidFingerprint,
+    /// No group of this crate has the given number, see
+    /// [AnyModpGroup](crate::group::AnyModpGroup).
+    UnknownGroup(u16),
+}
+
+impl Display for DhGroupsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DhGroupsError::UnexpectedEof => write!(f, "unexpected end of input"),
+            DhGroupsError::TrailingBytes => write!(f, "trailing bytes after message"),
+            DhGroupsError::UnexpectedMessageType { expected, found } => {
+                write!(f, "unexpected message type {found}, expected {expected}")
+            }
+            DhGroupsError::UnsupportedVersion(version) => {
+                write!(f, "unsupported encoding version {version}")
+            }
+            DhGroupsError::InvalidMpint => write!(f, "invalid mpint encoding"),
+            DhGroupsError::InvalidOpaque => write!(f, "invalid TLS integer encoding"),
+            DhGroupsError::NotPrime => write!(f, "group modulus or order is not prime"),
+            DhGroupsError::InvalidCofactor => write!(f, "order times cofactor is not p - 1"),
+            DhGroupsError::InvalidGenerator => write!(f, "generator does not have the group order"),
+            DhGroupsError::BitsOutOfRange => write!(f, "number of bits out of range"),
+            DhGroupsError::InputTooLong { len, max } => {
+                write!(f, "input of {len} bytes exceeds the maximum of {max} bytes")
+            }
+            DhGroupsError::ModulusTooLarge { bits, max } => {
+                write!(
+                    f,
+                    "modulus of {bits} bits exceeds the maximum of {max} bits"
+                )
+            }
+            DhGroupsError::ExponentTooLarge { bits, max } => {
+                write!(
+                    f,
+                    "exponent of {bits} bits exceeds the maximum of {max} bits"
+                )
+            }
+            DhGroupsError::NotSafePrime => write!(f, "modulus is not a safe prime"),
+            DhGroupsError::Cancelled => write!(f, "operation cancelled"),
+            DhGroupsError::InvalidThreadCount => write!(f, "number of threads must be at least 1"),
+            DhGroupsError::ThreadPool => write!(f, "failed to build the thread pool"),
+            DhGroupsError::MalformedModuli { line } => {
+                write!(f, "malformed moduli entry at line {line}")
+            }
+            DhGroupsError::Io(kind) => write!(f, "I/O error: {kind}"),
+            DhGroupsError::Validation(e) => write!(f, "validation failed: {e}"),
+            DhGroupsError::InvalidDer => write!(f, "invalid DER encoding"),
+            DhGroupsError::InvalidPem => write!(f, "invalid PEM encoding"),
+            DhGroupsError::InvalidJwk => write!(f, "invalid JSON Web Key"),
+            DhGroupsError::InvalidCbor => write!(f, "invalid CBOR or COSE_Key"),
+            DhGroupsError::OpenSsl => write!(f, "OpenSSL operation failed"),
+            DhGroupsError::UintSizeMismatch { expected, found } => write!(
+                f,
+                "integer of {found} bits does not match the group size of {expected} bits"
+            ),
+            DhGroupsError::GroupMismatch => write!(f, "keys belong to different groups"),
+            DhGroupsError::MissingModulus => write!(f, "modulus of the group not set"),
+            DhGroupsError::Encode(e) => write!(f, "message encoding failed: {e}"),
+            DhGroupsError::AuthenticationFailed => write!(f, "peer authentication failed"),
+            DhGroupsError::InvalidThreshold => write!(f, "invalid threshold"),
+            DhGroupsError::InvalidParticipant => write!(f, "invalid participant index"),
+            DhGroupsError::Threshold(e) => write!(f, "threshold decryption failed: {e}"),
+            DhGroupsError::Share(e) => write!(f, "secret recovery failed: {e}"),
+            DhGroupsError::SmpAborted => write!(f, "socialist millionaires' protocol aborted"),
+            DhGroupsError::DecryptionFailed => write!(f, "decryption failed"),
+            DhGroupsError::Pake(e) => write!(f, "password authenticated key exchange failed: {e}"),
+            DhGroupsError::InvalidFingerprint => write!(f, "invalid fingerprint"),
+            DhGroupsError::UnknownGroup(id) => write!(f, "unknown group number {id}"),
+        }
+    }
+}
+
+impl std::error::Error for DhGroupsError {}
+
+impl From<ValidationError> for DhGroupsError {
+    fn from(e: ValidationError) -> Self {
+        DhGroupsError::Validation(e)
+    }
+}
+
+impl From<EncodeError> for DhGroupsError {
+    fn from(e: EncodeError) -> Self {
+        DhGroupsError::Encode(e)
+    }
+}
+
+impl From<ThresholdError> for DhGroupsError {
+    fn from(e: ThresholdError) -> Self {
+        DhGroupsError::Threshold(e)
+    }
+}
+
+impl From<ShareError> for DhGroupsError {
+    fn from(e: ShareError) -> Self {
+        DhGroupsError::Share(e)
+    }
+}
+
+impl From<PakeError> for DhGroupsError {
+    fn from(e: PakeError) -> Self {
+        DhGroupsError::Pake(e)
+    }
+}
+
+impl From<AuthError> for DhGroupsError {
+    fn from(e: AuthError) -> Self {
+        match e {
+            AuthError::InvalidKey(e) => DhGroupsError::Validation(e),
+            AuthError::ContextMismatch | AuthError::InvalidSignature => {
+                DhGroupsError::AuthenticationFailed
+            }
+        }
+    }
+}
+
+/// Reasons for rejecting a public key or group element.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValidationError {
+    /// The value is not in `[1, p - 1]`.
+    OutOfRange,
+    /// The value is 1 or `p - 1`, which would leak the shared secret.
+    Degenerate,
+    /// The value is not in the subgroup of order `q`.
+    NotInSubgroup,
+}
+
+impl Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValidationError::OutOfRange => write!(f, "value out of range"),
+            ValidationError::Degenerate => write!(f, "degenerate value"),
+            ValidationError::NotInSubgroup => write!(f, "value not in the prime order subgroup"),
+        }
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+/// Reasons for failing to map a message to or from a group element.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EncodeError {
+    /// The message is longer than the maximum length of the group.
+    MessageTooLong { len: usize, max: usize },
+    /// The element is not the encoding of any message.
+    InvalidEncoding,
+    /// The group is not a safe prime group, whose subgroup of order q is that of the quadratic
+    /// residues, e.g. a group of RFC 5114.
+    NotSafePrimeGroup,
+}
+
+impl Display for EncodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EncodeError::MessageTooLong { len, max } => {
+                write!(
+                    f,
+                    "message of {len} bytes exceeds the maximum of {max} bytes"
+                )
+            }
+            EncodeError::InvalidEncoding => write!(f, "element does not encode a message"),
+            EncodeError::NotSafePrimeGroup => {
+                write!(f, "messages are only encoded in safe prime groups")
+            }
+        }
+    }
+}
+
+impl std::error::Error for EncodeError {}
+
+/// Reasons for failing to combine partial decryptions or signature shares.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ThresholdError {
+    /// Fewer partial decryptions than the threshold were given.
+    NotEnoughShares { needed: usize, got: usize },
+    /// Two partial decryptions have the same participant index.
+    DuplicateIndex(u32),
+    /// A participant index is zero.
+    InvalidIndex,
+    /// The proof of a partial decryption does not verify.
+    InvalidProof(u32),
+    /// A participant has no nonce commitment in the signing package, or a signer of the package
+    /// gave no signature share.
+    MissingCommitment(u32),
+    /// The signature share of a participant does not verify.
+    InvalidShare(u32),
+}
+
+impl Display for ThresholdError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ThresholdError::NotEnoughShares { needed, got } => {
+                write!(f, "{got} partial decryptions given, {needed} needed")
+            }
+            ThresholdError::DuplicateIndex(index) => {
+                write!(f, "duplicate partial decryption of participant {index}")
+            }
+            ThresholdError::InvalidIndex => write!(f, "participant index is zero"),
+            ThresholdError::InvalidProof(index) => {
+                write!(f, "invalid proof of participant {index}")
+            }
+            ThresholdError::MissingCommitment(index) => {
+                write!(f, "missing commitment or share of participant {index}")
+            }
+            ThresholdError::InvalidShare(index) => {
+                write!(f, "invalid signature share of participant {index}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ThresholdError {}
+
+/// Reasons for failing to combine secret shares.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShareError {
+    /// Fewer shares than the threshold were given.
+    NotEnoughShares { needed: usize, got: usize },
+    /// Two shares have the same index.
+    DuplicateIndex(u32),
+    /// A share index is zero.
+    InvalidIndex,
+}
+
+impl Display for ShareError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ShareError::NotEnoughShares { needed, got } => {
+                write!(f, "{got} shares given, {needed} needed")
+            }
+            ShareError::DuplicateIndex(index) => write!(f, "duplicate share of index {index}"),
+            ShareError::InvalidIndex => write!(f, "share index is zero"),
+        }
+    }
+}
+
+impl std::error::Error for ShareError {}
+
+/// Reasons for aborting a password authenticated key exchange.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PakeError {
+    /// The element of the peer is 0, 1 or `p - 1`, not in `[0, p)`, or a reflection of ours.
+    InvalidElement,
+    /// The element of the peer is not in the subgroup of order `q`.
+    NotInSubgroup,
+    /// The key confirmation of the peer does not verify.
+    ConfirmationFailed,
+}
+
+impl Display for PakeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PakeError::InvalidElement => write!(f, "invalid peer element"),
+            PakeError::NotInSubgroup => write!(f, "peer element not in the prime order subgroup"),
+            PakeError::ConfirmationFailed => write!(f, "key confirmation failed"),
+        }
+    }
+}
+
+impl std::error::Error for PakeError {}
+
+/// Reasons for rejecting a signed public key, see [signed_dh](crate::signed_dh).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuthError {
+    /// The context of the key is not the expected one.
+    ContextMismatch,
+    /// The signature over the key and its context does not verify.
+    InvalidSignature,
+    /// The key is not a valid element.
+    InvalidKey(ValidationError),
+}
+
+impl Display for AuthError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AuthError::ContextMismatch => write!(f, "unexpected key context"),
+            AuthError::InvalidSignature => write!(f, "invalid signature of the key"),
+            AuthError::InvalidKey(e) => write!(f, "invalid key: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for AuthError {}
+
+/// Reasons for rejecting a number as written in an RFC, see [util](crate::util).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseError {
+    /// The input has no digits.
+    Empty,
+    /// The input has a character that is neither a digit nor ASCII whitespace, at the given byte
+    /// offset.
+    InvalidDigit { position: usize, found: char },
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::Empty => write!(f, "no digits"),
+            ParseError::InvalidDigit { position, found } => {
+                write!(f, "invalid digit {found:?} at offset {position}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Reasons for failing the known-answer self-test, see [self_test](crate::self_test).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SelfTestError {
+    /// A constant of the group does not have the expected structure.
+    GroupConstant(AnyModpGroup),
+    /// A key exchange does not give the pinned public keys or shared secret.
+    KnownAnswer(AnyModpGroup),
+}
+
+impl Display for SelfTestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SelfTestError::GroupConstant(id) => write!(f, "invalid constant of group {id:?}"),
+            SelfTestError::KnownAnswer(id) => {
+                write!(f, "known-answer key exchange failed in group {id:?}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for SelfTestError {}
+
+/// The opening of a commitment does not match it, see [cointoss](crate::cointoss).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CheatDetected;
+
+impl Display for CheatDetected {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "opening does not match the commitment")
+    }
+}
+
+impl std::error::Error for CheatDetected {}
+
+/// A buffer is shorter than the encoding written to it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BufferTooSmall {
+    /// The length of the encoding.
+    pub needed: usize,
+    /// The length of the buffer.
+    pub len: usize,
+}
+
+impl Display for BufferTooSmall {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "buffer of {} bytes is too small for {} bytes",
+            self.len, self.needed
+        )
+    }
+}
+
+impl std::error::Error for BufferTooSmall {}
@@ -0,0 +1,363 @@
+//! C interface to the key exchange over the MODP groups, enabled by the `capi` feature.
+//!
+//! Groups are identified by their number in RFC 3526: 5, 14, 15, 16, 17 and 18. Keys and shared
+//! secrets are exchanged in big-endian buffers of exactly [dhg_element_len] bytes, the length of
+//! the modulus. The functions return [DHG_OK] or one of the negative `DHG_ERR_*` codes, and never
+//! unwind into the caller: a panic is caught and reported as [DHG_ERR_PANIC].
+//!
+//! The declarations are in `include/diffie_hellman_groups.h`. To link the crate into a C program,
+//! build it as a static or dynamic library:
+//!
+//! ```text
+//! cargo rustc --release --features capi --crate-type staticlib
+//! ```
+
+use std::{
+    panic::{catch_unwind, AssertUnwindSafe},
+    slice,
+};
+
+use num_bigint::BigUint;
+
+use crate::{
+    element,
+    group::{with_any_group, MODPGroup},
+    AnyModpGroup, Element, PublicKey, Scalar, UnvalidatedPublicKey,
+};
+
+/// The operation succeeded.
+pub const DHG_OK: i32 = 0;
+/// A required pointer is null.
+pub const DHG_ERR_NULL_POINTER: i32 = -1;
+/// The group number is not one of the MODP groups of RFC 3526.
+pub const DHG_ERR_UNKNOWN_GROUP: i32 = -2;
+/// A buffer does not have the length returned by [dhg_element_len].
+pub const DHG_ERR_LENGTH: i32 = -3;
+/// A private key is not in `[1, q)`, or a public key fails [Element::validate].
+pub const DHG_ERR_INVALID_KEY: i32 = -4;
+/// The library panicked. This is a bug.
+pub const DHG_ERR_PANIC: i32 = -99;
+
+/// Return the length in bytes of the keys and shared secrets of the group numbered `group_id`,
+/// or 0 if the group is unknown.
+#[no_mangle]
+pub extern "C" fn dhg_element_len(group_id: u32) -> usize {
+    AnyModpGroup::from_rfc3526_number(group_id)
+        .map_or(0, |id| with_any_group!(id, G => element_len::<G>()))
+}
+
+/// Generate a key pair in the group numbered `group_id`, writing the private exponent to
+/// `out_priv` and the public key to `out_pub`.
+///
+/// # Safety
+/// `out_priv` and `out_pub` must be valid for writes of [dhg_element_len] bytes.
+#[no_mangle]
+pub unsafe extern "C" fn dhg_keypair_generate(
+    group_id: u32,
+    out_priv: *mut u8,
+    out_pub: *mut u8,
+) -> i32 {
+    guard(|| {
+        let id = group(group_id)?;
+        let len = with_any_group!(id, G => element_len::<G>());
+        let out_priv = slice_mut(out_priv, len)?;
+        let out_pub = slice_mut(out_pub, len)?;
+        with_any_group!(id, G => keypair_generate::<G>(out_priv, out_pub))
+    })
+}
+
+/// Compute the shared secret of the private key `priv` and the public key `peer` of the group
+/// numbered `group_id`, writing it to `out`. The peer key is validated first.
+///
+/// # Safety
+/// `priv` and `peer` must be valid for reads of `priv_len` and `peer_len` bytes, and `out` for
+/// writes of `out_len` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn dhg_shared_secret(
+    group_id: u32,
+    r#priv: *const u8,
+    priv_len: usize,
+    peer: *const u8,
+    peer_len: usize,
+    out: *mut u8,
+    out_len: usize,
+) -> i32 {
+    guard(|| {
+        let secret = slice_ref(r#priv, priv_len)?;
+        let peer = slice_ref(peer, peer_len)?;
+        let out = slice_mut(out, out_len)?;
+        with_any_group!(group(group_id)?, G => shared_secret::<G>(secret, peer, out))
+    })
+}
+
+/// Validate the public key in `buf` of the group numbered `group_id` with [Element::validate].
+/// Returns [DHG_OK] if the key is valid, or [DHG_ERR_INVALID_KEY] if it is not.
+///
+/// # Safety
+/// `buf` must be valid for reads of `len` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn dhg_validate_public(group_id: u32, buf: *const u8, len: usize) -> i32 {
+    guard(|| {
+        let public = slice_ref(buf, len)?;
+        with_any_group!(group(group_id)?, G => validate_public::<G>(public))
+    })
+}
+
+fn group(group_id: u32) -> Result<AnyModpGroup, i32> {
+    AnyModpGroup::from_rfc3526_number(group_id).ok_or(DHG_ERR_UNKNOWN_GROUP)
+}
+
+fn element_len<G: MODPGroup>() -> usize {
+    G::prime_modulus().bits().div_ceil(8) as usize
+}
+
+fn keypair_generate<G: MODPGroup>(out_priv: &mut [u8], out_pub: &mut [u8]) -> Result<(), i32> {
+    let x = Scalar::<G>::random(&mut rand::thread_rng());
+    write_padded(&x.value, out_priv)?;
+    write_padded(&Element::<G>::from_biguint(x.value).value, out_pub)
+}
+
+fn shared_secret<G: MODPGroup>(secret: &[u8], peer: &[u8], out: &mut [u8]) -> Result<(), i32> {
+    let len = element_len::<G>();
+    if secret.len() != len || out.len() != len {
+        return Err(DHG_ERR_LENGTH);
+    }
+    let x = BigUint::from_bytes_be(secret);
+    if x == BigUint::from(0u64) || x >= G::subgroup_order() {
+        return Err(DHG_ERR_INVALID_KEY);
+    }
+    let peer = decode_public::<G>(peer)?;
+    write_padded(&peer.as_element().pow(&x).value, out)
+}
+
+fn validate_public<G: MODPGroup>(public: &[u8]) -> Result<(), i32> {
+    decode_public::<G>(public).map(|_| ())
+}
+
+fn decode_public<G: MODPGroup>(bytes: &[u8]) -> Result<PublicKey<G>, i32> {
+    if bytes.len() != element_len::<G>() {
+        return Err(DHG_ERR_LENGTH);
+    }
+    UnvalidatedPublicKey::<G>::from_bytes_be(bytes)
+        .map_err(|_| DHG_ERR_INVALID_KEY)?
+        .validate()
+        .map_err(|_| DHG_ERR_INVALID_KEY)
+}
+
+/// Write `value` to `out` in big-endian bytes, left-padded with zeros to the length of `out`,
+/// which must be that of p.
+fn write_padded(value: &BigUint, out: &mut [u8]) -> Result<(), i32> {
+    element::write_padded(value, 0, out)
+        .map(|_| ())
+        .map_err(|_| DHG_ERR_LENGTH)
+}
+
+/// Run `f`, mapping its result to a status code and catching panics.
+fn guard<F: FnOnce() -> Result<(), i32>>(f: F) -> i32 {
+    match catch_unwind(AssertUnwindSafe(f)) {
+        Ok(Ok(())) => DHG_OK,
+        Ok(Err(code)) => code,
+        Err(_) => DHG_ERR_PANIC,
+    }
+}
+
+/// # Safety
+/// `ptr` must be null or valid for reads of `len` bytes.
+unsafe fn slice_ref<'a>(ptr: *const u8, len: usize) -> Result<&'a [u8], i32> {
+    if ptr.is_null() {
+        return Err(DHG_ERR_NULL_POINTER);
+    }
+    Ok(slice::from_raw_parts(ptr, len))
+}
+
+/// # Safety
+/// `ptr` must be null or valid for writes of `len` bytes.
+unsafe fn slice_mut<'a>(ptr: *mut u8, len: usize) -> Result<&'a mut [u8], i32> {
+    if ptr.is_null() {
+        return Err(DHG_ERR_NULL_POINTER);
+    }
+    Ok(slice::from_raw_parts_mut(ptr, len))
+}
+
+#[cfg(test)]
+mod test {
+    use std::ptr;
+
+    use super::*;
+    use crate::group::MODPGroup14;
+
+    const HEADER: &str = include_str!("../include/diffie_hellman_groups.h");
+    const SOURCE: &str = include_str!("ffi.rs");
+
+    #[test]
+    fn test_key_exchange() {
+        for group_id in [5, 14] {
+            let len = dhg_element_len(group_id);
+            let (mut a_priv, mut a_pub) = (vec![0u8; len], vec![0u8; len]);
+            let (mut b_priv, mut b_pub) = (vec![0u8; len], vec![0u8; len]);
+            let (mut a_secret, mut b_secret) = (vec![0u8; len], vec![0u8; len]);
+            unsafe {
+                assert_eq!(
+                    dhg_keypair_generate(group_id, a_priv.as_mut_ptr(), a_pub.as_mut_ptr()),
+                    DHG_OK
+                );
+                assert_eq!(
+                    dhg_keypair_generate(group_id, b_priv.as_mut_ptr(), b_pub.as_mut_ptr()),
+                    DHG_OK
+                );
+                assert_eq!(dhg_validate_public(group_id, a_pub.as_ptr(), len), DHG_OK);
+                assert_eq!(
+                    dhg_shared_secret(
+                        group_id,
+                        a_priv.as_ptr(),
+                        len,
+                        b_pub.as_ptr(),
+                        len,
+                        a_secret.as_mut_ptr(),
+                        len
+                    ),
+                    DHG_OK
+                );
+                assert_eq!(
+                    dhg_shared_secret(
+                        group_id,
+                        b_priv.as_ptr(),
+                        len,
+                        a_pub.as_ptr(),
+                        len,
+                        b_secret.as_mut_ptr(),
+                        len
+                    ),
+                    DHG_OK
+                );
+            }
+            assert_eq!(a_secret, b_secret);
+            assert_ne!(a_secret, vec![0u8; len]);
+        }
+    }
+
+    #[test]
+    fn test_errors() {
+        let len = dhg_element_len(14);
+        assert_eq!(len, 256);
+        assert_eq!(dhg_element_len(1), 0);
+        let mut key = vec![0u8; len];
+        let mut out = vec![0u8; len];
+        unsafe {
+            assert_eq!(
+                dhg_keypair_generate(1, key.as_mut_ptr(), out.as_mut_ptr()),
+                DHG_ERR_UNKNOWN_GROUP
+            );
+            assert_eq!(
+                dhg_keypair_generate(14, ptr::null_mut(), out.as_mut_ptr()),
+                DHG_ERR_NULL_POINTER
+            );
+            assert_eq!(
+                dhg_validate_public(14, ptr::null(), len),
+                DHG_ERR_NULL_POINTER
+            );
+            assert_eq!(
+                dhg_validate_public(14, key.as_ptr(), len - 1),
+                DHG_ERR_LENGTH
+            );
+            // 0, 1 and p - 1 are not valid public keys
+            assert_eq!(
+                dhg_validate_public(14, key.as_ptr(), len),
+                DHG_ERR_INVALID_KEY
+            );
+            key[len - 1] = 1;
+            assert_eq!(
+                dhg_validate_public(14, key.as_ptr(), len),
+                DHG_ERR_INVALID_KEY
+            );
+            let p_minus_one = (MODPGroup14::prime_modulus() - 1u64).to_bytes_be();
+            assert_eq!(
+                dhg_validate_public(14, p_minus_one.as_ptr(), len),
+                DHG_ERR_INVALID_KEY
+            );
+
+            let mut public = vec![0u8; len];
+            assert_eq!(
+                dhg_keypair_generate(14, key.as_mut_ptr(), public.as_mut_ptr()),
+                DHG_OK
+            );
+            let shared = |secret: &[u8], out: &mut [u8]| {
+                dhg_shared_secret(
+                    14,
+                    secret.as_ptr(),
+                    secret.len(),
+                    public.as_ptr(),
+                    public.len(),
+                    out.as_mut_ptr(),
+                    out.len(),
+                )
+            };
+            assert_eq!(shared(&key, &mut out[..len - 1]), DHG_ERR_LENGTH);
+            assert_eq!(shared(&key[1..], &mut out), DHG_ERR_LENGTH);
+            assert_eq!(shared(&vec![0u8; len], &mut out), DHG_ERR_INVALID_KEY);
+            assert_eq!(shared(&[0xff; 256], &mut out), DHG_ERR_INVALID_KEY);
+            assert_eq!(shared(&key, &mut out), DHG_OK);
+        }
+    }
+
+    /// Collapse runs of whitespace into single spaces, and remove those after `(` and `*` or
+    /// before `)` and `,`, as cbindgen wraps long declarations.
+    fn normalize(s: &str) -> String {
+        s.split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ")
+            .replace("( ", "(")
+            .replace(" )", ")")
+            .replace(" ,", ",")
+            .replace("* ", "*")
+    }
+
+    /// Translate the argument or return type of an extern function to the type cbindgen emits.
+    fn c_type(rust: &str) -> String {
+        match rust.trim() {
+            "u32" => "uint32_t".to_string(),
+            "i32" => "int32_t".to_string(),
+            "usize" => "uintptr_t".to_string(),
+            "*const u8" => "const uint8_t *".to_string(),
+            "*mut u8" => "uint8_t *".to_string(),
+            other => panic!("no C type for {other}"),
+        }
+    }
+
+    #[test]
+    fn test_header() {
+        let header = normalize(HEADER);
+
+        let mut constants = 0;
+        for line in SOURCE.lines() {
+            if let Some(rest) = line.strip_prefix("pub const ") {
+                let (name, value) = rest.split_once(": i32 = ").unwrap();
+                let define = format!("#define {name} {}", value.trim_end_matches(';'));
+                assert!(header.contains(&define), "missing `{define}`");
+                constants += 1;
+            }
+        }
+        assert_eq!(constants, 6);
+
+        // the signature of each extern function, from `fn` to the opening brace
+        let mut functions = 0;
+        for (i, _) in SOURCE.match_indices("extern \"C\" fn ") {
+            let signature = &SOURCE[i + "extern \"C\" fn ".len()..];
+            let signature = normalize(&signature[..signature.find('{').unwrap()]);
+            let (name, rest) = signature.split_once('(').unwrap();
+            let (args, ret) = rest.rsplit_once(") -> ").unwrap();
+            let args: Vec<String> = args
+                .split(',')
+                .filter(|arg| !arg.trim().is_empty())
+                .map(|arg| {
+                    let (arg, ty) = arg.split_once(':').unwrap();
+                    format!("{} {}", c_type(ty), arg.trim().trim_start_matches("r#"))
+                })
+                .collect();
+            let declaration = normalize(&format!("{} {name}({});", c_type(ret), args.join(", ")));
+            assert!(header.contains(&declaration), "missing `{declaration}`");
+            functions += 1;
+        }
+        assert_eq!(functions, 4);
+    }
+}
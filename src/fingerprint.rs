@@ -0,0 +1,301 @@
+//! Fingerprints of public keys, to log them or pin the key of a peer the way SSH pins host keys,
+//! enabled by the `fingerprint` feature.
+//!
+//! A [Fingerprint] is the hash of the tagged encoding of a key: the header of
+//! [wire](crate::wire) with the number of the group, followed by the value in the fixed length
+//! of p, as in [Element::to_tagged_bytes]. The same value in two groups has different
+//! fingerprints. It is displayed as in OpenSSH, e.g. `SHA256:` followed by the digest in base64
+//! without padding, and parsed back from that form with [str::parse].
+//!
+//! # Example
+//!
+//! ```rust
+//! use diffie_hellman_groups::{fingerprint::Fingerprint, group::MODPGroup14, Element, Scalar};
+//! use sha2::Sha256;
+//!
+//! let public = Element::<MODPGroup14>::from_biguint(Scalar::<MODPGroup14>::random(&mut rand::thread_rng()).value);
+//! let pinned = public.fingerprint::<Sha256>().to_string();
+//! assert!(pinned.starts_with("SHA256:"));
+//!
+//! // later, checking the key received from the peer
+//! let pinned: Fingerprint = pinned.parse().unwrap();
+//! assert!(public.matches_fingerprint::<Sha256>(&pinned));
+//! ```
+
+use std::{fmt, str::FromStr};
+
+use base64::{engine::general_purpose::STANDARD_NO_PAD, Engine};
+#[cfg(feature = "serde")]
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{group::MODPGroup, hash::algorithm_name, DhGroupsError, Element, NamedDigest};
+
+#[cfg(feature = "primegroup")]
+use crate::{
+    wire::{header_number, WireVersion},
+    RuntimePublicKey,
+};
+
+/// The digest of the encoding of a public key, with the name of the hash function.
+///
+/// Fingerprints are compared in constant time, as the digest of a pinned key may be matched
+/// against the fingerprints of keys chosen by an attacker.
+#[derive(Debug, Clone)]
+pub struct Fingerprint {
+    algorithm: String,
+    digest: Vec<u8>,
+}
+
+impl Fingerprint {
+    /// The fingerprint of `encoding` with the hash function `D`.
+    pub(crate) fn of<D: NamedDigest>(encoding: &[u8]) -> Self {
+        Fingerprint {
+            algorithm: label::<D>(),
+            digest: D::digest(encoding).to_vec(),
+        }
+    }
+
+    /// The name of the hash function, e.g. `SHA256`.
+    pub fn algorithm(&self) -> &str {
+        &self.algorithm
+    }
+
+    /// The digest of the encoding of the key.
+    pub fn digest(&self) -> &[u8] {
+        &self.digest
+    }
+}
+
+/// The label of `D` in the displayed form, i.e. its algorithm name in upper case without the
+/// output size in bytes appended by the wrappers of RustCrypto, e.g. `SHA256` for
+/// `sha2::Sha256`, named `Sha256_32`.
+fn label<D: NamedDigest>() -> String {
+    let name = algorithm_name::<D>();
+    let suffix = format!("_{}", <D as digest::Digest>::output_size());
+    name.strip_suffix(&suffix)
+        .unwrap_or(&name)
+        .to_ascii_uppercase()
+}
+
+impl PartialEq for Fingerprint {
+    fn eq(&self, other: &Self) -> bool {
+        self.algorithm == other.algorithm
+            && self.digest.len() == other.digest.len()
+            && self
+                .digest
+                .iter()
+                .zip(&other.digest)
+                .fold(0u8, |acc, (a, b)| acc | (a ^ b))
+                == 0
+    }
+}
+
+impl Eq for Fingerprint {}
+
+impl fmt::Display for Fingerprint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}:{}",
+            self.algorithm,
+            STANDARD_NO_PAD.encode(&self.digest)
+        )
+    }
+}
+
+impl FromStr for Fingerprint {
+    type Err = DhGroupsError;
+
+    /// Parse the displayed form of a fingerprint, `ALGORITHM:base64`.
+    ///
+    /// # Errors
+    /// Returns [DhGroupsError::InvalidFingerprint] if the name is empty or the digest is not
+    /// base64 without padding.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (algorithm, digest) = s.split_once(':').ok_or(DhGroupsError::InvalidFingerprint)?;
+        let digest = STANDARD_NO_PAD
+            .decode(digest)
+            .map_err(|_| DhGroupsError::InvalidFingerprint)?;
+        if algorithm.is_empty() || digest.is_empty() {
+            return Err(DhGroupsError::InvalidFingerprint);
+        }
+        Ok(Fingerprint {
+            algorithm: algorithm.to_string(),
+            digest,
+        })
+    }
+}
+
+/// Serialized as its displayed form.
+#[cfg(feature = "serde")]
+impl Serialize for Fingerprint {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for Fingerprint {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(de::Error::custom)
+    }
+}
+
+impl<G: MODPGroup> Element<G> {
+    /// The fingerprint of the element as a public key, the hash with `D` of
+    /// [Element::to_tagged_bytes].
+    pub fn fingerprint<D: NamedDigest>(&self) -> Fingerprint {
+        Fingerprint::of::<D>(&self.to_tagged_bytes())
+    }
+
+    /// Whether the fingerprint of the element with `D` is `fingerprint`, e.g. a pinned key.
+    pub fn matches_fingerprint<D: NamedDigest>(&self, fingerprint: &Fingerprint) -> bool {
+        self.fingerprint::<D>() == *fingerprint
+    }
+}
+
+#[cfg(feature = "primegroup")]
+impl RuntimePublicKey {
+    /// The fingerprint of the key with `D`. A key of a group of RFC 3526 with its standard
+    /// generator has the fingerprint of the same [Element]. For other groups, the group number 0
+    /// of the header is followed by p, g and the key, each in the length of p.
+    pub fn fingerprint<D: NamedDigest>(&self) -> Fingerprint {
+        let group = self.group();
+        let len = group.p.bits().div_ceil(8) as usize;
+        let number = header_number(group.identify_exact());
+        let mut encoding = vec![WireVersion::CURRENT as u8, number];
+        let values = if number == 0 {
+            vec![&group.p, &group.g, self.value()]
+        } else {
+            vec![self.value()]
+        };
+        for value in values {
+            let start = encoding.len();
+            encoding.resize(start + len, 0);
+            crate::element::write_padded(value, len, &mut encoding[start..])
+                .expect("values are less than p");
+        }
+        Fingerprint::of::<D>(&encoding)
+    }
+
+    /// Whether the fingerprint of the key with `D` is `fingerprint`, e.g. a pinned key.
+    pub fn matches_fingerprint<D: NamedDigest>(&self, fingerprint: &Fingerprint) -> bool {
+        self.fingerprint::<D>() == *fingerprint
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use num_bigint::BigUint;
+    use sha2::{Sha256, Sha512};
+
+    use super::*;
+    use crate::{MODPGroup14, MODPGroup5};
+
+    #[test]
+    fn test_pinned_fingerprint() {
+        let public = Element::<MODPGroup14>::from_biguint(BigUint::from(42u64));
+        let fingerprint = public.fingerprint::<Sha256>();
+        assert_eq!(fingerprint.to_string(), PINNED);
+        assert_eq!(fingerprint.algorithm(), "SHA256");
+        assert!(public.matches_fingerprint::<Sha256>(&PINNED.parse().unwrap()));
+        assert!(public
+            .fingerprint::<Sha512>()
+            .to_string()
+            .starts_with("SHA512:"));
+
+        let other = Element::<MODPGroup14>::from_biguint(BigUint::from(43u64));
+        assert!(!other.matches_fingerprint::<Sha256>(&fingerprint));
+    }
+
+    /// The fingerprint of g^42 in MODPGroup14.
+    const PINNED: &str = "SHA256:9mzDsDtcwZv9BtvMWbf1mnGaedfPzvEn0Ok03qSX5xA";
+
+    #[test]
+    fn test_group_number_changes_fingerprint() {
+        let public = Element::<MODPGroup5>::from_biguint(BigUint::from(42u64));
+        let mut encoding = public.to_tagged_bytes();
+        assert_eq!(
+            Fingerprint::of::<Sha256>(&encoding),
+            public.fingerprint::<Sha256>()
+        );
+        encoding[1] = 14;
+        assert_ne!(
+            Fingerprint::of::<Sha256>(&encoding),
+            public.fingerprint::<Sha256>()
+        );
+    }
+
+    #[test]
+    fn test_parse() {
+        let fingerprint =
+            Element::<MODPGroup5>::from_biguint(BigUint::from(7u64)).fingerprint::<Sha256>();
+        assert_eq!(
+            fingerprint.to_string().parse::<Fingerprint>().unwrap(),
+            fingerprint
+        );
+        for invalid in [
+            "",
+            "SHA256",
+            ":AAAA",
+            "SHA256:",
+            "SHA256:AAAA=",
+            "SHA256:@@",
+        ] {
+            assert_eq!(
+                invalid.parse::<Fingerprint>(),
+                Err(DhGroupsError::InvalidFingerprint),
+                "{invalid}"
+            );
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        let fingerprint =
+            Element::<MODPGroup5>::from_biguint(BigUint::from(7u64)).fingerprint::<Sha256>();
+        let json = serde_json::to_string(&fingerprint).unwrap();
+        assert_eq!(json, format!("\"{fingerprint}\""));
+        assert_eq!(
+            serde_json::from_str::<Fingerprint>(&json).unwrap(),
+            fingerprint
+        );
+    }
+
+    #[cfg(feature = "primegroup")]
+    #[test]
+    fn test_runtime_public_key() {
+        use std::sync::Arc;
+
+        use crate::{MODPGroup, PrimeGroup, UnvalidatedRuntimePublicKey};
+
+        let group = Arc::new(PrimeGroup {
+            p: MODPGroup14::prime_modulus(),
+            q: MODPGroup14::sophie_garmain_prime(),
+            g: MODPGroup14::generator(),
+            cofactor: BigUint::from(2u64),
+        });
+        let public = Element::<MODPGroup14>::from_biguint(BigUint::from(42u64));
+        let runtime =
+            UnvalidatedRuntimePublicKey::from_biguint(group.clone(), public.value.clone())
+                .validate()
+                .unwrap();
+        assert_eq!(
+            runtime.fingerprint::<Sha256>(),
+            public.fingerprint::<Sha256>()
+        );
+
+        // the same key with another generator is not identified
+        let other = Arc::new(PrimeGroup {
+            g: BigUint::from(4u64),
+            ..(*group).clone()
+        });
+        let runtime_other = UnvalidatedRuntimePublicKey::from_biguint(other, public.value.clone())
+            .validate()
+            .unwrap();
+        assert!(!runtime_other.matches_fingerprint::<Sha256>(&runtime.fingerprint::<Sha256>()));
+    }
+}
@@ -0,0 +1,495 @@
+//! Two-round threshold Schnorr signatures in the style of FROST (Komlo and Goldberg): any `t`
+//! participants holding shares `x_i` of a secret key `x`, e.g. from the [dkg](crate::dkg),
+//! jointly produce a signature that verifies with [schnorr::verify] under `y = g^x`.
+//!
+//! 1. Each signer `i` picks two nonces `d_i, e_i` and publishes the commitments
+//!    `(D_i, E_i) = (g^d_i, g^e_i)` ([commit]).
+//! 2. The aggregator chooses the message and the signers, and sends their commitments to them as
+//!    a [SigningPackage]. Each signer computes the binding factors
+//!    `rho_j = D(D(dst || y || msg || commitments) || j)`, where `dst` names `D`, the group
+//!    commitment `R = prod_j D_j * E_j^rho_j`, the challenge `c` of [schnorr] for `R` and the
+//!    signature share `z_i = d_i + e_i rho_i + lambda_i x_i c`, where `lambda_i` is the Lagrange
+//!    coefficient of `i` in the set of signers ([sign]).
+//! 3. The aggregator sums the shares into the signature `(R, sum_i z_i)` ([aggregate]).
+//!
+//! Each share can be verified against the public share `y_i = g^x_i` of its signer as
+//! `g^z_i = D_i * E_i^rho_i * y_i^(lambda_i c)` ([verify_share]), so that a misbehaving signer is
+//! identified. The binding factors tie each nonce to the message and the whole set of
+//! commitments, which prevents the concurrent forgeries of plain two-round schemes.
+//!
+//! The nonces are consumed by [sign]: they must never be used for two signatures.
+//!
+//! # Example
+//!
+//! ```rust
+//! use sha2::Sha256;
+//! use diffie_hellman_groups::{
+//!     frost::{self, SigningPackage}, group::MODPGroup5, schnorr, Element, Scalar,
+//! };
+//!
+//! type G = MODPGroup5;
+//! let rng = &mut rand::thread_rng();
+//!
+//! // a dealer shares x with threshold 2: x_j = x + a * j
+//! let (x, a) = (Scalar::<G>::random(rng), Scalar::<G>::random(rng));
+//! let share = |j: u64| &x + &a * Scalar::from_biguint(j.into());
+//! let pk = Element::<G>::from_biguint(x.value.clone());
+//!
+//! let (nonces1, commitment1) = frost::commit::<G, _>(1, rng);
+//! let (nonces3, commitment3) = frost::commit::<G, _>(3, rng);
+//! let package = SigningPackage::new(b"message", vec![commitment1, commitment3]).unwrap();
+//!
+//! let shares = vec![
+//!     frost::sign::<_, Sha256>(nonces1, &share(1), &pk, &package).unwrap(),
+//!     frost::sign::<_, Sha256>(nonces3, &share(3), &pk, &package).unwrap(),
+//! ];
+//! let public_share = |j: u32| Element::<G>::from_biguint(share(j.into()).value);
+//! let sig = frost::aggregate::<_, Sha256, _>(&package, &shares, &pk, public_share).unwrap();
+//! assert!(schnorr::verify::<_, Sha256>(&pk, b"message", &sig));
+//! ```
+
+use std::{collections::BTreeSet, fmt};
+
+use num_bigint::BigUint;
+use rand_core::CryptoRngCore;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    group::MODPGroup,
+    hash::domain_tag,
+    schnorr::{self, Signature},
+    threshold::lagrange_coefficient,
+    Element, NamedDigest, Scalar, ThresholdError,
+};
+
+/// Domain separation tag of the binding factors.
+const BINDING_DST: &[u8] = b"diffie-hellman-groups frost binding";
+
+/// The commitments `(D_i, E_i) = (g^d_i, g^e_i)` to the nonces of the signer `i`.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(bound = ""))]
+pub struct NonceCommitment<G: MODPGroup> {
+    pub index: u32,
+    pub hiding: Element<G>,
+    pub binding: Element<G>,
+}
+
+/// The secret nonces `(d_i, e_i)` of a signer for a single signature.
+pub struct SigningNonces<G: MODPGroup> {
+    index: u32,
+    hiding: Scalar<G>,
+    binding: Scalar<G>,
+}
+
+/// The message to sign and the nonce commitments of the signers, in increasing order of index.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(bound = ""))]
+pub struct SigningPackage<G: MODPGroup> {
+    message: Vec<u8>,
+    commitments: Vec<NonceCommitment<G>>,
+}
+
+/// The signature share `z_i` of the signer `i`.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(bound = ""))]
+pub struct SignatureShare<G: MODPGroup> {
+    pub index: u32,
+    pub z: Scalar<G>,
+}
+
+impl<G: MODPGroup> SigningNonces<G> {
+    /// The index of the signer.
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+}
+
+impl<G: MODPGroup> fmt::Debug for SigningNonces<G> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SigningNonces")
+            .field("index", &self.index)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<G: MODPGroup> SigningPackage<G> {
+    /// Gather the commitments of the signers of `message`, sorting them by index.
+    ///
+    /// # Errors
+    /// Returns [ThresholdError::InvalidIndex] or [ThresholdError::DuplicateIndex] if an index is
+    /// zero or repeated.
+    pub fn new(
+        message: &[u8],
+        mut commitments: Vec<NonceCommitment<G>>,
+    ) -> Result<Self, ThresholdError> {
+        commitments.sort_by_key(|commitment| commitment.index);
+        let mut indices = BTreeSet::new();
+        for commitment in &commitments {
+            if commitment.index == 0 {
+                return Err(ThresholdError::InvalidIndex);
+            }
+            if !indices.insert(commitment.index) {
+                return Err(ThresholdError::DuplicateIndex(commitment.index));
+            }
+        }
+        Ok(SigningPackage {
+            message: message.to_vec(),
+            commitments,
+        })
+    }
+
+    /// The message to sign.
+    pub fn message(&self) -> &[u8] {
+        &self.message
+    }
+
+    /// The nonce commitments of the signers, in increasing order of index.
+    pub fn commitments(&self) -> &[NonceCommitment<G>] {
+        &self.commitments
+    }
+
+    /// The indices of the signers, in increasing order.
+    fn indices(&self) -> Vec<u32> {
+        self.commitments.iter().map(|c| c.index).collect()
+    }
+
+    fn commitment(&self, index: u32) -> Result<&NonceCommitment<G>, ThresholdError> {
+        self.commitments
+            .iter()
+            .find(|c| c.index == index)
+            .ok_or(ThresholdError::MissingCommitment(index))
+    }
+
+    /// Compute the binding factor of each signer and the group commitment `R`.
+    fn binding_factors<D: NamedDigest>(
+        &self,
+        public_key: &Element<G>,
+    ) -> (Vec<Scalar<G>>, Element<G>) {
+        let dst = domain_tag::<D>(BINDING_DST);
+        let mut hasher = D::new()
+            .chain_update((dst.len() as u32).to_be_bytes())
+            .chain_update(&dst)
+            .chain_update(public_key.to_bytes_be())
+            .chain_update((self.message.len() as u32).to_be_bytes())
+            .chain_update(&self.message)
+            .chain_update((self.commitments.len() as u32).to_be_bytes());
+        for commitment in &self.commitments {
+            hasher.update(commitment.index.to_be_bytes());
+            hasher.update(commitment.hiding.to_bytes_be());
+            hasher.update(commitment.binding.to_bytes_be());
+        }
+        let prefix = hasher.finalize();
+
+        let rhos: Vec<Scalar<G>> = self
+            .commitments
+            .iter()
+            .map(|commitment| {
+                let digest = D::new()
+                    .chain_update(&prefix)
+                    .chain_update(commitment.index.to_be_bytes())
+                    .finalize();
+                Scalar::from_biguint(BigUint::from_bytes_be(&digest))
+            })
+            .collect();
+        let r = self.commitments.iter().zip(&rhos).fold(
+            Element::from_value(BigUint::from(1u64)),
+            |acc, (commitment, rho)| acc * &commitment.hiding * commitment.binding.pow(&rho.value),
+        );
+        (rhos, r)
+    }
+}
+
+/// Round one: pick the nonces of the signer `index` for one signature, returning them and their
+/// commitments to publish.
+pub fn commit<G: MODPGroup, R: CryptoRngCore + ?Sized>(
+    index: u32,
+    rng: &mut R,
+) -> (SigningNonces<G>, NonceCommitment<G>) {
+    let nonces = SigningNonces {
+        index,
+        hiding: Scalar::random(rng),
+        binding: Scalar::random(rng),
+    };
+    let commitment = NonceCommitment {
+        index,
+        hiding: Element::from_biguint(nonces.hiding.value.clone()),
+        binding: Element::from_biguint(nonces.binding.value.clone()),
+    };
+    (nonces, commitment)
+}
+
+/// Round two: compute the signature share of the message of `package` with the secret share
+/// `share` of the key `public_key`, consuming the nonces.
+///
+/// # Errors
+/// Returns [ThresholdError::MissingCommitment] if the package does not hold the commitments to
+/// `nonces`.
+pub fn sign<G: MODPGroup, D: NamedDigest>(
+    nonces: SigningNonces<G>,
+    share: &Scalar<G>,
+    public_key: &Element<G>,
+    package: &SigningPackage<G>,
+) -> Result<SignatureShare<G>, ThresholdError> {
+    let commitment = package.commitment(nonces.index)?;
+    if commitment.hiding != Element::from_biguint(nonces.hiding.value.clone())
+        || commitment.binding != Element::from_biguint(nonces.binding.value.clone())
+    {
+        return Err(ThresholdError::MissingCommitment(nonces.index));
+    }
+
+    let (rhos, r) = package.binding_factors::<D>(public_key);
+    let rho = &rhos[position(package, nonces.index)];
+    let c = schnorr::challenge::<G, D>(&r, public_key, &package.message);
+    let lambda = lagrange_coefficient::<G>(&package.indices(), nonces.index);
+    Ok(SignatureShare {
+        index: nonces.index,
+        z: &nonces.hiding + &nonces.binding * rho + lambda * share * c,
+    })
+}
+
+/// Verify the signature share `share` against the public share `public_share` of its signer.
+///
+/// # Errors
+/// Returns [ThresholdError::MissingCommitment] if the signer is not in the package, or
+/// [ThresholdError::InvalidShare] if the share does not verify.
+pub fn verify_share<G: MODPGroup, D: NamedDigest>(
+    share: &SignatureShare<G>,
+    public_share: &Element<G>,
+    public_key: &Element<G>,
+    package: &SigningPackage<G>,
+) -> Result<(), ThresholdError> {
+    let commitment = package.commitment(share.index)?;
+    let (rhos, r) = package.binding_factors::<D>(public_key);
+    let rho = &rhos[position(package, share.index)];
+    let c = schnorr::challenge::<G, D>(&r, public_key, &package.message);
+    let lambda = lagrange_coefficient::<G>(&package.indices(), share.index);
+
+    let expected = &commitment.hiding
+        * commitment.binding.pow(&rho.value)
+        * public_share.pow(&(lambda * c).value);
+    if Element::<G>::from_biguint(share.z.value.clone()) == expected {
+        Ok(())
+    } else {
+        Err(ThresholdError::InvalidShare(share.index))
+    }
+}
+
+/// Sum the signature shares of all the signers of `package` into a signature of its message
+/// under `public_key`. If the signature does not verify, the shares are verified with
+/// [verify_share] against the public shares given by `public_share` to identify the culprit.
+///
+/// # Errors
+/// Returns
+/// - [ThresholdError::DuplicateIndex] if two shares have the same index.
+/// - [ThresholdError::MissingCommitment] if a share is not from a signer of the package, or a
+///   signer gave no share.
+/// - [ThresholdError::InvalidShare] with the index of the first invalid share.
+pub fn aggregate<G, D, F>(
+    package: &SigningPackage<G>,
+    shares: &[SignatureShare<G>],
+    public_key: &Element<G>,
+    public_share: F,
+) -> Result<Signature<G>, ThresholdError>
+where
+    G: MODPGroup,
+    D: NamedDigest,
+    F: Fn(u32) -> Element<G>,
+{
+    let mut indices = BTreeSet::new();
+    for share in shares {
+        package.commitment(share.index)?;
+        if !indices.insert(share.index) {
+            return Err(ThresholdError::DuplicateIndex(share.index));
+        }
+    }
+    if let Some(index) = package.indices().into_iter().find(|i| !indices.contains(i)) {
+        return Err(ThresholdError::MissingCommitment(index));
+    }
+
+    let (_, r) = package.binding_factors::<D>(public_key);
+    let s = shares
+        .iter()
+        .fold(Scalar::from_biguint(BigUint::from(0u64)), |acc, share| {
+            acc + &share.z
+        });
+    let sig = Signature { r, s };
+    if schnorr::verify::<G, D>(public_key, &package.message, &sig) {
+        return Ok(sig);
+    }
+    for share in shares {
+        verify_share::<G, D>(share, &public_share(share.index), public_key, package)?;
+    }
+    // all shares are valid against the given public shares, which are thus not those of the key
+    Err(ThresholdError::InvalidShare(0))
+}
+
+/// The position of the signer `index`, which is in the package, in its commitments.
+fn position<G: MODPGroup>(package: &SigningPackage<G>, index: u32) -> usize {
+    package
+        .commitments
+        .iter()
+        .position(|c| c.index == index)
+        .expect("signer is in the package")
+}
+
+#[cfg(test)]
+mod test {
+    use sha2::Sha256;
+
+    use super::*;
+    use crate::{
+        dkg::{DkgOutput, Parameters, Participant},
+        group::MODPGroup5,
+    };
+
+    type G = MODPGroup5;
+
+    /// Run the DKG with 5 participants and threshold 3.
+    fn dkg() -> Vec<DkgOutput<G>> {
+        let rng = &mut rand::thread_rng();
+        let params = Parameters::new(5, 3).unwrap();
+        let (mut participants, mut broadcasts, mut shares) = (Vec::new(), Vec::new(), Vec::new());
+        for index in 1..=5 {
+            let (participant, broadcast, messages) =
+                Participant::<G>::new(index, params, rng).unwrap();
+            participants.push(participant);
+            broadcasts.push(broadcast);
+            shares.extend(messages);
+        }
+        for participant in participants.iter_mut() {
+            for broadcast in &broadcasts {
+                participant.receive_broadcast(broadcast.clone()).unwrap();
+            }
+            let index = participant.index();
+            for msg in shares.iter().filter(|m| m.recipient == index) {
+                participant.receive_share(msg.clone()).unwrap();
+            }
+        }
+        participants
+            .into_iter()
+            .map(|p| p.finish().unwrap())
+            .collect()
+    }
+
+    /// Run both rounds with the participants at `signers`.
+    fn sign_with(
+        outputs: &[DkgOutput<G>],
+        signers: &[usize],
+        message: &[u8],
+    ) -> (SigningPackage<G>, Vec<SignatureShare<G>>) {
+        let rng = &mut rand::thread_rng();
+        let (nonces, commitments): (Vec<_>, Vec<_>) = signers
+            .iter()
+            .map(|&i| commit::<G, _>(outputs[i].index, rng))
+            .unzip();
+        // the messages go over the wire
+        #[cfg(feature = "serde")]
+        let commitments =
+            serde_json::from_str(&serde_json::to_string(&commitments).unwrap()).unwrap();
+        let package = SigningPackage::new(message, commitments).unwrap();
+        let shares = signers
+            .iter()
+            .zip(nonces)
+            .map(|(&i, nonces)| {
+                let output = &outputs[i];
+                sign::<_, Sha256>(nonces, &output.share, &output.public_key, &package).unwrap()
+            })
+            .collect();
+        (package, shares)
+    }
+
+    #[test]
+    fn test_three_of_five() {
+        let outputs = dkg();
+        let pk = &outputs[0].public_key;
+        let public_share = |j: u32| outputs[0].public_share(j);
+
+        for signers in [[0, 1, 2], [4, 0, 2], [1, 3, 4]] {
+            let (package, shares) = sign_with(&outputs, &signers, b"message");
+            for share in &shares {
+                assert!(
+                    verify_share::<_, Sha256>(share, &public_share(share.index), pk, &package)
+                        .is_ok()
+                );
+            }
+            let sig = aggregate::<_, Sha256, _>(&package, &shares, pk, public_share).unwrap();
+            assert!(schnorr::verify::<_, Sha256>(pk, b"message", &sig));
+            assert!(!schnorr::verify::<_, Sha256>(pk, b"another message", &sig));
+        }
+
+        // 2 signers with threshold 3 do not produce a valid signature
+        let (package, shares) = sign_with(&outputs, &[0, 1], b"message");
+        assert!(aggregate::<_, Sha256, _>(&package, &shares, pk, public_share).is_err());
+    }
+
+    #[test]
+    fn test_misbehaving_share() {
+        let outputs = dkg();
+        let pk = &outputs[0].public_key;
+        let public_share = |j: u32| outputs[0].public_share(j);
+
+        let (package, mut shares) = sign_with(&outputs, &[0, 2, 3], b"message");
+        shares[1].z = &shares[1].z + Scalar::from_biguint(BigUint::from(1u64));
+        assert_eq!(
+            verify_share::<_, Sha256>(&shares[1], &public_share(3), pk, &package),
+            Err(ThresholdError::InvalidShare(3))
+        );
+        assert_eq!(
+            aggregate::<_, Sha256, _>(&package, &shares, pk, public_share).unwrap_err(),
+            ThresholdError::InvalidShare(3)
+        );
+
+        // a share computed for another package
+        let (_, other) = sign_with(&outputs, &[0, 2, 3], b"message");
+        shares[1] = other[1].clone();
+        assert_eq!(
+            aggregate::<_, Sha256, _>(&package, &shares, pk, public_share).unwrap_err(),
+            ThresholdError::InvalidShare(3)
+        );
+    }
+
+    #[test]
+    fn test_invalid_packages() {
+        let rng = &mut rand::thread_rng();
+        let outputs = dkg();
+        let pk = &outputs[0].public_key;
+        let public_share = |j: u32| outputs[0].public_share(j);
+
+        let (_, c0) = commit::<G, _>(0, rng);
+        assert_eq!(
+            SigningPackage::new(b"message", vec![c0]).unwrap_err(),
+            ThresholdError::InvalidIndex
+        );
+        let (_, c1) = commit::<G, _>(1, rng);
+        assert_eq!(
+            SigningPackage::new(b"message", vec![c1.clone(), c1.clone()]).unwrap_err(),
+            ThresholdError::DuplicateIndex(1)
+        );
+
+        // nonces whose commitments are not in the package
+        let (nonces, _) = commit::<G, _>(2, rng);
+        let package = SigningPackage::new(b"message", vec![c1]).unwrap();
+        assert_eq!(
+            sign::<_, Sha256>(nonces, &outputs[1].share, pk, &package).unwrap_err(),
+            ThresholdError::MissingCommitment(2)
+        );
+        let (nonces, _) = commit::<G, _>(1, rng);
+        assert_eq!(
+            sign::<_, Sha256>(nonces, &outputs[0].share, pk, &package).unwrap_err(),
+            ThresholdError::MissingCommitment(1)
+        );
+
+        // missing and duplicated shares
+        let (package, shares) = sign_with(&outputs, &[0, 1, 2], b"message");
+        assert_eq!(
+            aggregate::<_, Sha256, _>(&package, &shares[..2], pk, public_share).unwrap_err(),
+            ThresholdError::MissingCommitment(3)
+        );
+        let duplicated = [shares[0].clone(), shares[1].clone(), shares[1].clone()];
+        assert_eq!(
+            aggregate::<_, Sha256, _>(&package, &duplicated, pk, public_share).unwrap_err(),
+            ThresholdError::DuplicateIndex(2)
+        );
+    }
+}
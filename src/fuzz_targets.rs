@@ -0,0 +1,152 @@
+//! The bodies of the fuzz targets under `fuzz/`, shared with the test below that runs them over
+//! the committed corpus, so that `cargo test` covers the decoders without `cargo fuzz`.
+//!
+//! Each target decodes arbitrary bytes and either gets an error or re-encodes the decoded value,
+//! panicking only if the encoding differs from the input. Formats with several encodings of the
+//! same value are compared where the encoding is unique: the DER inside a PEM block, and the
+//! entries of a moduli file.
+
+use crate::{moduli, Element, MODPGroup14, MODPGroup5, Scalar};
+
+#[cfg(feature = "primegroup")]
+use crate::{
+    der::{decode_pem, read_integer, write_integer, write_tlv, TAG_SEQUENCE},
+    dhparam,
+};
+#[cfg(feature = "primegroup")]
+use num_bigint::BigUint;
+
+/// Decode an element of groups 5 and 14 with [Element::from_bytes_be].
+pub fn element_from_bytes_be(data: &[u8]) {
+    if let Ok(element) = Element::<MODPGroup5>::from_bytes_be(data) {
+        assert_eq!(element.to_bytes_be(), data);
+    }
+    if let Ok(element) = Element::<MODPGroup14>::from_bytes_be(data) {
+        assert_eq!(element.to_bytes_be(), data);
+    }
+}
+
+/// Decode a DER INTEGER, which is re-encoded as the bytes it was read from.
+#[cfg(feature = "primegroup")]
+pub fn der_integer(data: &[u8]) {
+    if let Ok((x, rest)) = read_integer(data) {
+        let mut encoded = Vec::new();
+        write_integer(&mut encoded, &x);
+        assert_eq!(encoded, &data[..data.len() - rest.len()]);
+    }
+}
+
+/// Decode PKCS #3 parameters with [dhparam::parse_pem], comparing the DER inside the PEM block.
+#[cfg(feature = "primegroup")]
+pub fn dhparam_pem(data: &[u8]) {
+    let Ok(pem) = std::str::from_utf8(data) else {
+        return;
+    };
+    let Ok(params) = dhparam::parse_pem(pem) else {
+        return;
+    };
+    let der = decode_pem(pem, dhparam::PEM_LABEL).expect("parsed PEM decodes");
+    let mut content = Vec::new();
+    write_integer(&mut content, &params.group.p);
+    write_integer(&mut content, &params.group.g);
+    if let Some(length) = params.private_value_length {
+        write_integer(&mut content, &BigUint::from(length));
+    }
+    let mut encoded = Vec::new();
+    write_tlv(&mut encoded, TAG_SEQUENCE, &content);
+    assert_eq!(encoded, der);
+
+    let reparsed = dhparam::parse_pem(&dhparam::to_pem(&params.group)).expect("encoding parses");
+    assert_eq!(reparsed.group, params.group);
+}
+
+/// Parse a moduli file with [moduli::parse]. Comments, spacing and the case and leading zeros of
+/// the hexadecimal fields are not kept, so the written entries must parse back to the same ones.
+pub fn moduli_parse(data: &[u8]) {
+    let Ok(entries) = moduli::parse(data) else {
+        return;
+    };
+    let mut written = Vec::new();
+    moduli::write(&mut written, &entries).expect("writing to a Vec does not fail");
+    assert_eq!(
+        moduli::parse(&written[..]).expect("written entries parse"),
+        entries
+    );
+}
+
+/// Decode an element and a scalar of group 5 with [Element::from_tagged_bytes] and
+/// [Scalar::from_tagged_bytes]. An input of the previous version is re-encoded in the current one,
+/// so only the bodies are compared then.
+pub fn tagged_bytes(data: &[u8]) {
+    if let Ok(element) = Element::<MODPGroup5>::from_tagged_bytes(data) {
+        assert_tagged_eq(&element.to_tagged_bytes(), data);
+    }
+    if let Ok(scalar) = Scalar::<MODPGroup5>::from_tagged_bytes(data) {
+        assert_tagged_eq(&scalar.to_tagged_bytes(), data);
+    }
+}
+
+fn assert_tagged_eq(encoded: &[u8], data: &[u8]) {
+    match data[0] {
+        1 => assert_eq!(encoded[2..], data[1..]),
+        _ => assert_eq!(encoded, data),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// The inputs of the corpus of a fuzz target, with every prefix of each, which are mostly
+    /// invalid.
+    fn corpus(target: &str) -> Vec<Vec<u8>> {
+        let dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("fuzz/corpus")
+            .join(target);
+        let mut inputs = Vec::new();
+        for entry in std::fs::read_dir(dir).unwrap() {
+            let input = std::fs::read(entry.unwrap().path()).unwrap();
+            inputs.extend((0..input.len()).map(|len| input[..len].to_vec()));
+            inputs.push(input);
+        }
+        assert!(inputs.len() > 1, "the corpus of {target} is empty");
+        inputs
+    }
+
+    #[test]
+    fn test_element_from_bytes_be() {
+        corpus("element_from_bytes_be")
+            .iter()
+            .for_each(|input| element_from_bytes_be(input));
+    }
+
+    #[cfg(feature = "primegroup")]
+    #[test]
+    fn test_der_integer() {
+        corpus("der_integer")
+            .iter()
+            .for_each(|input| der_integer(input));
+    }
+
+    #[cfg(feature = "primegroup")]
+    #[test]
+    fn test_dhparam_pem() {
+        corpus("dhparam_pem")
+            .iter()
+            .for_each(|input| dhparam_pem(input));
+    }
+
+    #[test]
+    fn test_moduli_parse() {
+        corpus("moduli_parse")
+            .iter()
+            .for_each(|input| moduli_parse(input));
+    }
+
+    #[test]
+    fn test_tagged_bytes() {
+        corpus("tagged_bytes")
+            .iter()
+            .for_each(|input| tagged_bytes(input));
+    }
+}
@@ -1,17 +1,77 @@
-use std::fmt::Debug;
+use std::{
+    any::TypeId,
+    collections::HashMap,
+    fmt::Debug,
+    sync::{Mutex, OnceLock, PoisonError},
+};
 
 use lazy_static::lazy_static;
-use num_bigint::BigUint;
+use num_bigint::{BigUint, RandBigInt};
+use rand_core::CryptoRngCore;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    dyn_group::{DhGroup, StaticGroup, ValidatedPublicValue},
+    reduce::SpecialForm,
+    text::write_dhparam_text,
+    util::from_rfc_hex,
+    DhGroupsError, Element, ValidationError,
+};
 
 /// Trait of the Modular Exponential (MODP) Groups for the Internet Key Exchange (IKE) protocol.
-pub trait MODPGroup: Debug {
+///
+/// A group defined outside this crate implements [MODPGroup::prime_modulus],
+/// [MODPGroup::generator] and [MODPGroup::pow], and [MODPGroup::sophie_garmain_prime] or
+/// [MODPGroup::subgroup_order] if its modulus is not a safe prime.
+pub trait MODPGroup: Debug + 'static {
     /// prime modulus
     fn prime_modulus() -> BigUint;
 
-    /// The Sophie Germain Prime, i.e. q
-    fn sophie_garmain_prime() -> BigUint;
+    /// The number of the group in the IANA registry of IKE Diffie-Hellman groups, e.g. 14 for the
+    /// 2048-bit MODP group of RFC 3526. The groups of RFC 7919, not numbered there, return their
+    /// code point of the TLS Supported Groups registry, e.g. 256 for ffdhe2048. Groups in neither
+    /// registry return 0, the default.
+    fn group_id() -> u16 {
+        0
+    }
+
+    /// The name of the group, e.g. "modp2048" or "ffdhe2048", as used in IKE and TLS
+    /// configurations. By default, the name of the implementing type.
+    fn name() -> &'static str {
+        std::any::type_name::<Self>()
+    }
+
+    /// The size of the prime modulus in bits, i.e. `prime_modulus().bits()`, e.g. to size the
+    /// buffers of encoded elements with `bit_size() / 8` bytes.
+    fn bit_size() -> usize {
+        Self::prime_modulus().bits() as usize
+    }
+
+    /// The estimated security strength of the group in bits, i.e. the size of a symmetric key
+    /// as hard to attack, after table 2 of NIST SP 800-57 part 1: 80 for 1024-bit moduli, 112
+    /// for 2048 bits, 128 for 3072 bits, 192 for 7680 bits and 256 for 15360 bits. The sizes of
+    /// the built-in groups missing from the table are interpolated, e.g. 152 for 4096 bits. It is
+    /// the strength of the largest size not above the modulus, or 0 below 768 bits, limited to
+    /// half of the bits of the order q, e.g. 80 for the 160-bit q of [MODPGroup22].
+    fn security_strength() -> u32 {
+        security_strength(Self::prime_modulus().bits(), Self::subgroup_order().bits())
+    }
+
+    /// The Sophie Germain Prime, i.e. q = (p - 1) / 2 for a safe prime p = 2q + 1. For the groups
+    /// of RFC 5114, whose modulus is not a safe prime, (p - 1) / 2 is not a prime and the order of
+    /// the subgroup is given by [MODPGroup::subgroup_order].
+    fn sophie_garmain_prime() -> BigUint {
+        (Self::prime_modulus() - 1u64) >> 1
+    }
 
-    /// generator of the subgroup of order 2q
+    /// the prime order q of the subgroup generated by g, in which the elements and exponents are
+    /// computed. It is the Sophie Germain prime for the safe primes of RFC 3526 and RFC 7919.
+    fn subgroup_order() -> BigUint {
+        Self::sophie_garmain_prime()
+    }
+
+    /// generator of the subgroup of order `subgroup_order()`
     fn generator() -> BigUint;
 
     /// modular addition, compute a + b mod p
@@ -26,16 +86,313 @@ pub trait MODPGroup: Debug {
 
     /// modular multiplication, compute a * b mod p
     fn mul(a: &BigUint, b: &BigUint) -> BigUint {
-        (a * b) % Self::prime_modulus()
+        record!(Multiplication);
+        Self::fast_reduce(&(a * b))
+    }
+
+    /// modular squaring, compute a^2 mod p
+    fn square(a: &BigUint) -> BigUint {
+        record!(Squaring);
+        Self::fast_reduce(&(a * a))
+    }
+
+    /// compute x mod p, e.g. of a double-width product. The groups of RFC 3526 and RFC 7919
+    /// override it with a reduction specialized to the form of their primes.
+    fn fast_reduce(x: &BigUint) -> BigUint {
+        record!(Reduction);
+        x % Self::prime_modulus()
+    }
+
+    /// modular addition in place, compute a = a + b mod p, reusing the storage of a
+    fn add_assign(a: &mut BigUint, b: &BigUint) {
+        let p = Self::prime_modulus();
+        *a += b;
+        if *a >= p {
+            *a -= &p;
+            reduce_assign(a, p);
+        }
+    }
+
+    /// modular subtraction in place, compute a = a - b mod p, reusing the storage of a
+    fn sub_assign(a: &mut BigUint, b: &BigUint) {
+        let p = Self::prime_modulus();
+        if *a < *b {
+            *a += &p;
+        }
+        *a -= b;
+        reduce_assign(a, p);
+    }
+
+    /// modular multiplication in place, compute a = a * b mod p. The product needs a buffer of
+    /// its own, which is reduced with [MODPGroup::fast_reduce].
+    fn mul_assign(a: &mut BigUint, b: &BigUint) {
+        record!(Multiplication);
+        *a *= b;
+        *a = Self::fast_reduce(a);
     }
 
     /// modular exponentiation, compute a^e mod p
     fn pow(a: &BigUint, e: &BigUint) -> BigUint;
 
+    /// The parameters of the group and the constants derived from them, computed once on first
+    /// use. By default, they are kept in a map shared by all groups, locked on every call. The
+    /// built-in groups override it to keep them in a `static CONSTANTS: OnceLock<GroupConstants>`
+    /// of their own, returning `GroupConstants::get::<Self>(&CONSTANTS)`.
+    fn constants() -> &'static GroupConstants {
+        GroupConstants::shared::<Self>()
+    }
+
+    /// p - 1, the largest value of an element, cached in [MODPGroup::constants].
+    fn p_minus_one() -> BigUint {
+        Self::constants().p_minus_one().clone()
+    }
+
+    /// the inverse of the generator, g^-1 mod p, cached in [MODPGroup::constants].
+    fn generator_inverse() -> BigUint {
+        Self::constants().generator_inverse().clone()
+    }
+
     /// compute the element of the group from the exponent, compute g^e mod p
     fn element(exponent: &BigUint) -> BigUint {
         Self::pow(&Self::generator(), exponent)
     }
+
+    /// textual dump of the group parameters in the layout of `openssl dhparam -text`
+    fn to_text() -> String {
+        let mut text = String::new();
+        write_dhparam_text(&mut text, &Self::prime_modulus(), &Self::generator())
+            .expect("writing to a String cannot fail");
+        text
+    }
+}
+
+/// The parameters of a group and the constants derived from them, computed once per group on
+/// first use and kept in a static `OnceLock` of the group, see [MODPGroup::constants].
+#[derive(Debug)]
+pub struct GroupConstants {
+    p: BigUint,
+    q: BigUint,
+    g: BigUint,
+    p_minus_one: BigUint,
+    generator_inverse: BigUint,
+}
+
+/// The names of the groups whose constants were computed, once each.
+#[cfg(test)]
+static CONSTANTS_INITIALIZED: std::sync::Mutex<Vec<&'static str>> =
+    std::sync::Mutex::new(Vec::new());
+
+impl GroupConstants {
+    fn new<G: MODPGroup + ?Sized>() -> Self {
+        #[cfg(test)]
+        CONSTANTS_INITIALIZED.lock().unwrap().push(G::name());
+        let p = G::prime_modulus();
+        let g = G::generator();
+        GroupConstants {
+            generator_inverse: g
+                .modinv(&p)
+                .expect("the generator is invertible modulo the prime p"),
+            p_minus_one: &p - 1u64,
+            q: G::subgroup_order(),
+            g,
+            p,
+        }
+    }
+
+    /// The constants of `G` in `cell`, computed by the first caller. `cell` is the static
+    /// `OnceLock` read by [MODPGroup::constants] of `G`.
+    pub fn get<G: MODPGroup>(cell: &'static OnceLock<GroupConstants>) -> &'static GroupConstants {
+        cell.get_or_init(GroupConstants::new::<G>)
+    }
+
+    /// The constants of `G` in a map shared by all groups, computed by the first caller, see
+    /// [MODPGroup::constants].
+    fn shared<G: MODPGroup + ?Sized>() -> &'static GroupConstants {
+        static SHARED: OnceLock<Mutex<HashMap<TypeId, &'static GroupConstants>>> = OnceLock::new();
+        let mut shared = SHARED
+            .get_or_init(Default::default)
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        shared
+            .entry(TypeId::of::<G>())
+            .or_insert_with(|| Box::leak(Box::new(GroupConstants::new::<G>())))
+    }
+
+    /// see [MODPGroup::prime_modulus]
+    pub fn prime_modulus(&self) -> &BigUint {
+        &self.p
+    }
+
+    /// see [MODPGroup::subgroup_order]
+    pub fn subgroup_order(&self) -> &BigUint {
+        &self.q
+    }
+
+    /// see [MODPGroup::generator]
+    pub fn generator(&self) -> &BigUint {
+        &self.g
+    }
+
+    /// see [MODPGroup::p_minus_one]
+    pub fn p_minus_one(&self) -> &BigUint {
+        &self.p_minus_one
+    }
+
+    /// see [MODPGroup::generator_inverse]
+    pub fn generator_inverse(&self) -> &BigUint {
+        &self.generator_inverse
+    }
+}
+
+/// The security strength of a group whose modulus and order have the given numbers of bits, see
+/// [MODPGroup::security_strength].
+pub(crate) fn security_strength(modulus_bits: u64, order_bits: u64) -> u32 {
+    let strength = match modulus_bits {
+        15360.. => 256,
+        8192.. => 200,
+        7680.. => 192,
+        6144.. => 176,
+        4096.. => 152,
+        3072.. => 128,
+        2048.. => 112,
+        1536.. => 90,
+        1024.. => 80,
+        768.. => 60,
+        _ => 0,
+    };
+    strength.min(order_bits as u32 / 2)
+}
+
+/// Reduce `x` modulo `p` in place. Dividing an owned value leaves the remainder in its buffer,
+/// where `x % &p` would divide a copy.
+fn reduce_assign(x: &mut BigUint, p: BigUint) {
+    if *x >= p {
+        *x = std::mem::take(x) % p;
+    }
+}
+
+/// Implementations of the MODPGroup trait for the Oakley groups defined in RFC 2409, enabled by
+/// the `legacy-groups` feature.
+///
+/// # Warning
+/// This group is weak and deprecated. Its 768-bit modulus offers about 60 bits of security,
+/// discrete logarithms in groups of this size are within reach of precomputation (the Logjam
+/// attack), and RFC 8247 says that IKEv2 implementations MUST NOT use it. Only use it to
+/// interoperate with legacy IKEv1 peers.
+///
+/// First Oakley Default Group
+/// This group is assigned id 1.
+///
+/// The prime is: 2^768 - 2^704 - 1 + 2^64 * { \[2^638 pi\] + 149686 }
+#[cfg(feature = "legacy-groups")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct MODPGroup1;
+
+#[cfg(feature = "legacy-groups")]
+impl MODPGroup for MODPGroup1 {
+    fn prime_modulus() -> BigUint {
+        PRIME_GROUP_1.clone()
+    }
+
+    fn group_id() -> u16 {
+        1
+    }
+
+    fn name() -> &'static str {
+        "modp768"
+    }
+
+    fn bit_size() -> usize {
+        768
+    }
+
+    fn sophie_garmain_prime() -> BigUint {
+        Q_GROUP_1.clone()
+    }
+
+    fn generator() -> BigUint {
+        BigUint::from(2u32)
+    }
+
+    fn pow(a: &BigUint, e: &BigUint) -> BigUint {
+        record!(Exponentiation);
+        a.modpow(e, &PRIME_GROUP_1)
+    }
+
+    fn fast_reduce(x: &BigUint) -> BigUint {
+        REDUCE_GROUP_1.reduce(x)
+    }
+
+    fn square(a: &BigUint) -> BigUint {
+        REDUCE_GROUP_1.sqr_mod(a)
+    }
+
+    fn constants() -> &'static GroupConstants {
+        GroupConstants::get::<Self>(&CONSTANTS_GROUP_1)
+    }
+}
+
+/// Implementations of the MODPGroup trait for the Oakley groups defined in RFC 2409, enabled by
+/// the `legacy-groups` feature.
+///
+/// # Warning
+/// This group is weak and deprecated. Its 1024-bit modulus offers about 80 bits of security,
+/// discrete logarithms in groups of this size are within reach of precomputation (the Logjam
+/// attack), and RFC 8247 says that IKEv2 implementations SHOULD NOT use it. Only use it to
+/// interoperate with legacy IKEv1 peers.
+///
+/// Second Oakley Default Group
+/// This group is assigned id 2.
+///
+/// The prime is: 2^1024 - 2^960 - 1 + 2^64 * { \[2^894 pi\] + 129093 }
+#[cfg(feature = "legacy-groups")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct MODPGroup2;
+
+#[cfg(feature = "legacy-groups")]
+impl MODPGroup for MODPGroup2 {
+    fn prime_modulus() -> BigUint {
+        PRIME_GROUP_2.clone()
+    }
+
+    fn group_id() -> u16 {
+        2
+    }
+
+    fn name() -> &'static str {
+        "modp1024"
+    }
+
+    fn bit_size() -> usize {
+        1024
+    }
+
+    fn sophie_garmain_prime() -> BigUint {
+        Q_GROUP_2.clone()
+    }
+
+    fn generator() -> BigUint {
+        BigUint::from(2u32)
+    }
+
+    fn pow(a: &BigUint, e: &BigUint) -> BigUint {
+        record!(Exponentiation);
+        a.modpow(e, &PRIME_GROUP_2)
+    }
+
+    fn fast_reduce(x: &BigUint) -> BigUint {
+        REDUCE_GROUP_2.reduce(x)
+    }
+
+    fn square(a: &BigUint) -> BigUint {
+        REDUCE_GROUP_2.sqr_mod(a)
+    }
+
+    fn constants() -> &'static GroupConstants {
+        GroupConstants::get::<Self>(&CONSTANTS_GROUP_2)
+    }
 }
 
 /// Implementations of the MODPGroup trait for the MODP groups defined in RFC 3526.
@@ -46,7 +403,8 @@ pub trait MODPGroup: Debug {
 /// standardize that practice here.
 ///
 /// The prime is: 2^1536 - 2^1472 - 1 + 2^64 * { \[2^1406 pi\] + 741804 }
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct MODPGroup5;
 
 impl MODPGroup for MODPGroup5 {
@@ -54,6 +412,18 @@ impl MODPGroup for MODPGroup5 {
         PRIME_GROUP_5.clone()
     }
 
+    fn group_id() -> u16 {
+        5
+    }
+
+    fn name() -> &'static str {
+        "modp1536"
+    }
+
+    fn bit_size() -> usize {
+        1536
+    }
+
     fn sophie_garmain_prime() -> BigUint {
         Q_GROUP_5.clone()
     }
@@ -63,8 +433,21 @@ impl MODPGroup for MODPGroup5 {
     }
 
     fn pow(a: &BigUint, e: &BigUint) -> BigUint {
+        record!(Exponentiation);
         a.modpow(e, &PRIME_GROUP_5)
     }
+
+    fn fast_reduce(x: &BigUint) -> BigUint {
+        REDUCE_GROUP_5.reduce(x)
+    }
+
+    fn square(a: &BigUint) -> BigUint {
+        REDUCE_GROUP_5.sqr_mod(a)
+    }
+
+    fn constants() -> &'static GroupConstants {
+        GroupConstants::get::<Self>(&CONSTANTS_GROUP_5)
+    }
 }
 
 /// Implementations of the MODPGroup trait for the MODP groups defined in RFC 3526.
@@ -73,7 +456,8 @@ impl MODPGroup for MODPGroup5 {
 /// This group is assigned id 14.
 ///
 /// This prime is: 2^2048 - 2^1984 - 1 + 2^64 * { \[2^1918 pi\] + 124476 }
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct MODPGroup14;
 
 impl MODPGroup for MODPGroup14 {
@@ -81,6 +465,18 @@ impl MODPGroup for MODPGroup14 {
         PRIME_GROUP_14.clone()
     }
 
+    fn group_id() -> u16 {
+        14
+    }
+
+    fn name() -> &'static str {
+        "modp2048"
+    }
+
+    fn bit_size() -> usize {
+        2048
+    }
+
     fn sophie_garmain_prime() -> BigUint {
         Q_GROUP_14.clone()
     }
@@ -90,8 +486,21 @@ impl MODPGroup for MODPGroup14 {
     }
 
     fn pow(a: &BigUint, e: &BigUint) -> BigUint {
+        record!(Exponentiation);
         a.modpow(e, &PRIME_GROUP_14)
     }
+
+    fn fast_reduce(x: &BigUint) -> BigUint {
+        REDUCE_GROUP_14.reduce(x)
+    }
+
+    fn square(a: &BigUint) -> BigUint {
+        REDUCE_GROUP_14.sqr_mod(a)
+    }
+
+    fn constants() -> &'static GroupConstants {
+        GroupConstants::get::<Self>(&CONSTANTS_GROUP_14)
+    }
 }
 
 /// Implementations of the MODPGroup trait for the MODP groups defined in RFC 3526.
@@ -100,7 +509,8 @@ impl MODPGroup for MODPGroup14 {
 /// This group is assigned id 15.
 ///
 /// This prime is: 2^3072 - 2^3008 - 1 + 2^64 * { \[2^2942 pi\] + 1690314 }
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct MODPGroup15;
 
 impl MODPGroup for MODPGroup15 {
@@ -108,6 +518,18 @@ impl MODPGroup for MODPGroup15 {
         PRIME_GROUP_15.clone()
     }
 
+    fn group_id() -> u16 {
+        15
+    }
+
+    fn name() -> &'static str {
+        "modp3072"
+    }
+
+    fn bit_size() -> usize {
+        3072
+    }
+
     fn sophie_garmain_prime() -> BigUint {
         Q_GROUP_15.clone()
     }
@@ -117,8 +539,21 @@ impl MODPGroup for MODPGroup15 {
     }
 
     fn pow(a: &BigUint, e: &BigUint) -> BigUint {
+        record!(Exponentiation);
         a.modpow(e, &PRIME_GROUP_15)
     }
+
+    fn fast_reduce(x: &BigUint) -> BigUint {
+        REDUCE_GROUP_15.reduce(x)
+    }
+
+    fn square(a: &BigUint) -> BigUint {
+        REDUCE_GROUP_15.sqr_mod(a)
+    }
+
+    fn constants() -> &'static GroupConstants {
+        GroupConstants::get::<Self>(&CONSTANTS_GROUP_15)
+    }
 }
 
 /// Implementations of the MODPGroup trait for the MODP groups defined in RFC 3526.
@@ -127,7 +562,8 @@ impl MODPGroup for MODPGroup15 {
 /// This group is assigned id 16.
 ///
 /// This prime is: 2^4096 - 2^4032 - 1 + 2^64 * { \[2^3966 pi\] + 240904 }
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct MODPGroup16;
 
 impl MODPGroup for MODPGroup16 {
@@ -135,6 +571,18 @@ impl MODPGroup for MODPGroup16 {
         PRIME_GROUP_16.clone()
     }
 
+    fn group_id() -> u16 {
+        16
+    }
+
+    fn name() -> &'static str {
+        "modp4096"
+    }
+
+    fn bit_size() -> usize {
+        4096
+    }
+
     fn sophie_garmain_prime() -> BigUint {
         Q_GROUP_16.clone()
     }
@@ -144,8 +592,21 @@ impl MODPGroup for MODPGroup16 {
     }
 
     fn pow(a: &BigUint, e: &BigUint) -> BigUint {
+        record!(Exponentiation);
         a.modpow(e, &PRIME_GROUP_16)
     }
+
+    fn fast_reduce(x: &BigUint) -> BigUint {
+        REDUCE_GROUP_16.reduce(x)
+    }
+
+    fn square(a: &BigUint) -> BigUint {
+        REDUCE_GROUP_16.sqr_mod(a)
+    }
+
+    fn constants() -> &'static GroupConstants {
+        GroupConstants::get::<Self>(&CONSTANTS_GROUP_16)
+    }
 }
 
 /// Implementations of the MODPGroup trait for the MODP groups defined in RFC 3526.
@@ -154,7 +615,8 @@ impl MODPGroup for MODPGroup16 {
 /// This group is assigned id 17.
 ///
 /// This prime is: 2^6144 - 2^6080 - 1 + 2^64 * { \[2^6014 pi\] + 929484 }
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct MODPGroup17;
 
 impl MODPGroup for MODPGroup17 {
@@ -162,6 +624,18 @@ impl MODPGroup for MODPGroup17 {
         PRIME_GROUP_17.clone()
     }
 
+    fn group_id() -> u16 {
+        17
+    }
+
+    fn name() -> &'static str {
+        "modp6144"
+    }
+
+    fn bit_size() -> usize {
+        6144
+    }
+
     fn sophie_garmain_prime() -> BigUint {
         Q_GROUP_17.clone()
     }
@@ -171,8 +645,21 @@ impl MODPGroup for MODPGroup17 {
     }
 
     fn pow(a: &BigUint, e: &BigUint) -> BigUint {
+        record!(Exponentiation);
         a.modpow(e, &PRIME_GROUP_17)
     }
+
+    fn fast_reduce(x: &BigUint) -> BigUint {
+        REDUCE_GROUP_17.reduce(x)
+    }
+
+    fn square(a: &BigUint) -> BigUint {
+        REDUCE_GROUP_17.sqr_mod(a)
+    }
+
+    fn constants() -> &'static GroupConstants {
+        GroupConstants::get::<Self>(&CONSTANTS_GROUP_17)
+    }
 }
 
 /// Implementations of the MODPGroup trait for the MODP groups defined in RFC 3526.
@@ -181,7 +668,8 @@ impl MODPGroup for MODPGroup17 {
 /// This group is assigned id 18.
 ///
 /// This prime is: 2^8192 - 2^8128 - 1 + 2^64 * { \[2^8062 pi\] + 4743158 }
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct MODPGroup18;
 
 impl MODPGroup for MODPGroup18 {
@@ -189,6 +677,18 @@ impl MODPGroup for MODPGroup18 {
         PRIME_GROUP_18.clone()
     }
 
+    fn group_id() -> u16 {
+        18
+    }
+
+    fn name() -> &'static str {
+        "modp8192"
+    }
+
+    fn bit_size() -> usize {
+        8192
+    }
+
     fn sophie_garmain_prime() -> BigUint {
         Q_GROUP_18.clone()
     }
@@ -198,331 +698,1899 @@ impl MODPGroup for MODPGroup18 {
     }
 
     fn pow(a: &BigUint, e: &BigUint) -> BigUint {
+        record!(Exponentiation);
         a.modpow(e, &PRIME_GROUP_18)
     }
-}
 
-lazy_static! {
-    static ref PRIME_GROUP_5: BigUint = BigUint::parse_bytes(
-        b"FFFFFFFFFFFFFFFFC90FDAA22168C234C4C6628B80DC1CD1\
-        29024E088A67CC74020BBEA63B139B22514A08798E3404DD\
-        EF9519B3CD3A431B302B0A6DF25F14374FE1356D6D51C245\
-        E485B576625E7EC6F44C42E9A637ED6B0BFF5CB6F406B7ED\
-        EE386BFB5A899FA5AE9F24117C4B1FE649286651ECE45B3D\
-        C2007CB8A163BF0598DA48361C55D39A69163FA8FD24CF5F\
-        83655D23DCA3AD961C62F356208552BB9ED529077096966D\
-        670C354E4ABC9804F1746C08CA237327FFFFFFFFFFFFFFFF",
-        16,
-    )
-    .unwrap();
-    static ref Q_GROUP_5: BigUint = BigUint::parse_bytes(
-        b"7FFFFFFFFFFFFFFFE487ED5110B4611A62633145C06E0E68\
-        948127044533E63A0105DF531D89CD9128A5043CC71A026E\
-        F7CA8CD9E69D218D98158536F92F8A1BA7F09AB6B6A8E122\
-        F242DABB312F3F637A262174D31BF6B585FFAE5B7A035BF6\
-        F71C35FDAD44CFD2D74F9208BE258FF324943328F6722D9E\
-        E1003E5C50B1DF82CC6D241B0E2AE9CD348B1FD47E9267AF\
-        C1B2AE91EE51D6CB0E3179AB1042A95DCF6A9483B84B4B36\
-        B3861AA7255E4C0278BA36046511B993FFFFFFFFFFFFFFFF",
-        16
-    )
-    .unwrap();
-    static ref PRIME_GROUP_14: BigUint = BigUint::parse_bytes(
-        b"FFFFFFFFFFFFFFFFC90FDAA22168C234C4C6628B80DC1CD1\
-        29024E088A67CC74020BBEA63B139B22514A08798E3404DD\
-        EF9519B3CD3A431B302B0A6DF25F14374FE1356D6D51C245\
-        E485B576625E7EC6F44C42E9A637ED6B0BFF5CB6F406B7ED\
-        EE386BFB5A899FA5AE9F24117C4B1FE649286651ECE45B3D\
-        C2007CB8A163BF0598DA48361C55D39A69163FA8FD24CF5F\
-        83655D23DCA3AD961C62F356208552BB9ED529077096966D\
-        670C354E4ABC9804F1746C08CA18217C32905E462E36CE3B\
-        E39E772C180E86039B2783A2EC07A28FB5C55DF06F4C52C9\
-        DE2BCBF6955817183995497CEA956AE515D2261898FA0510\
-        15728E5A8AACAA68FFFFFFFFFFFFFFFF",
-        16,
-    )
-    .unwrap();
-    static ref Q_GROUP_14: BigUint = BigUint::parse_bytes(
-        b"7FFFFFFFFFFFFFFFE487ED5110B4611A62633145C06E0E68\
-        948127044533E63A0105DF531D89CD9128A5043CC71A026E\
-        F7CA8CD9E69D218D98158536F92F8A1BA7F09AB6B6A8E122\
-        F242DABB312F3F637A262174D31BF6B585FFAE5B7A035BF6\
-        F71C35FDAD44CFD2D74F9208BE258FF324943328F6722D9E\
-        E1003E5C50B1DF82CC6D241B0E2AE9CD348B1FD47E9267AF\
-        C1B2AE91EE51D6CB0E3179AB1042A95DCF6A9483B84B4B36\
-        B3861AA7255E4C0278BA3604650C10BE19482F23171B671D\
-        F1CF3B960C074301CD93C1D17603D147DAE2AEF837A62964\
-        EF15E5FB4AAC0B8C1CCAA4BE754AB5728AE9130C4C7D0288\
-        0AB9472D455655347FFFFFFFFFFFFFFF",
-        16
-    )
-    .unwrap();
-    static ref PRIME_GROUP_15: BigUint = BigUint::parse_bytes(
-        b"FFFFFFFFFFFFFFFFC90FDAA22168C234C4C6628B80DC1CD1\
-        29024E088A67CC74020BBEA63B139B22514A08798E3404DD\
-        EF9519B3CD3A431B302B0A6DF25F14374FE1356D6D51C245\
-        E485B576625E7EC6F44C42E9A637ED6B0BFF5CB6F406B7ED\
-        EE386BFB5A899FA5AE9F24117C4B1FE649286651ECE45B3D\
-        C2007CB8A163BF0598DA48361C55D39A69163FA8FD24CF5F\
-        83655D23DCA3AD961C62F356208552BB9ED529077096966D\
-        670C354E4ABC9804F1746C08CA18217C32905E462E36CE3B\
-        E39E772C180E86039B2783A2EC07A28FB5C55DF06F4C52C9\
-        DE2BCBF6955817183995497CEA956AE515D2261898FA0510\
-        15728E5A8AAAC42DAD33170D04507A33A85521ABDF1CBA64\
-        ECFB850458DBEF0A8AEA71575D060C7DB3970F85A6E1E4C7\
-        ABF5AE8CDB0933D71E8C94E04A25619DCEE3D2261AD2EE6B\
-        F12FFA06D98A0864D87602733EC86A64521F2B18177B200C\
-        BBE117577A615D6C770988C0BAD946E208E24FA074E5AB31\
-        43DB5BFCE0FD108E4B82D120A93AD2CAFFFFFFFFFFFFFFFF",
-        16,
-    )
-    .unwrap();
-    static ref PRIME_GROUP_16: BigUint = BigUint::parse_bytes(
-        b"FFFFFFFFFFFFFFFFC90FDAA22168C234C4C6628B80DC1CD1\
-        29024E088A67CC74020BBEA63B139B22514A08798E3404DD\
-        EF9519B3CD3A431B302B0A6DF25F14374FE1356D6D51C245\
-        E485B576625E7EC6F44C42E9A637ED6B0BFF5CB6F406B7ED\
-        EE386BFB5A899FA5AE9F24117C4B1FE649286651ECE45B3D\
-        C2007CB8A163BF0598DA48361C55D39A69163FA8FD24CF5F\
-        83655D23DCA3AD961C62F356208552BB9ED529077096966D\
-        670C354E4ABC9804F1746C08CA18217C32905E462E36CE3B\
-        E39E772C180E86039B2783A2EC07A28FB5C55DF06F4C52C9\
-        DE2BCBF6955817183995497CEA956AE515D2261898FA0510\
-        15728E5A8AAAC42DAD33170D04507A33A85521ABDF1CBA64\
-        ECFB850458DBEF0A8AEA71575D060C7DB3970F85A6E1E4C7\
-        ABF5AE8CDB0933D71E8C94E04A25619DCEE3D2261AD2EE6B\
-        F12FFA06D98A0864D87602733EC86A64521F2B18177B200C\
-        BBE117577A615D6C770988C0BAD946E208E24FA074E5AB31\
-        43DB5BFCE0FD108E4B82D120A92108011A723C12A787E6D7\
-        88719A10BDBA5B2699C327186AF4E23C1A946834B6150BDA\
-        2583E9CA2AD44CE8DBBBC2DB04DE8EF92E8EFC141FBECAA6\
-        287C59474E6BC05D99B2964FA090C3A2233BA186515BE7ED\
-        1F612970CEE2D7AFB81BDD762170481CD0069127D5B05AA9\
-        93B4EA988D8FDDC186FFB7DC90A6C08F4DF435C934063199\
-        FFFFFFFFFFFFFFFF",
-        16,
-    )
-    .unwrap();
-    static ref Q_GROUP_15: BigUint = BigUint::parse_bytes(
-        b"7FFFFFFFFFFFFFFFE487ED5110B4611A62633145C06E0E68\
-        948127044533E63A0105DF531D89CD9128A5043CC71A026E\
-        F7CA8CD9E69D218D98158536F92F8A1BA7F09AB6B6A8E122\
-        F242DABB312F3F637A262174D31BF6B585FFAE5B7A035BF6\
-        F71C35FDAD44CFD2D74F9208BE258FF324943328F6722D9E\
-        E1003E5C50B1DF82CC6D241B0E2AE9CD348B1FD47E9267AF\
-        C1B2AE91EE51D6CB0E3179AB1042A95DCF6A9483B84B4B36\
-        B3861AA7255E4C0278BA3604650C10BE19482F23171B671D\
-        F1CF3B960C074301CD93C1D17603D147DAE2AEF837A62964\
-        EF15E5FB4AAC0B8C1CCAA4BE754AB5728AE9130C4C7D0288\
-        0AB9472D45556216D6998B8682283D19D42A90D5EF8E5D32\
-        767DC2822C6DF785457538ABAE83063ED9CB87C2D370F263\
-        D5FAD7466D8499EB8F464A702512B0CEE771E9130D697735\
-        F897FD036CC504326C3B01399F643532290F958C0BBD9006\
-        5DF08BABBD30AEB63B84C4605D6CA371047127D03A72D598\
-        A1EDADFE707E884725C16890549D69657FFFFFFFFFFFFFFF",
-        16
-    )
-    .unwrap();
-    static ref Q_GROUP_16: BigUint = BigUint::parse_bytes(
-        b"7FFFFFFFFFFFFFFFE487ED5110B4611A62633145C06E0E68\
-        948127044533E63A0105DF531D89CD9128A5043CC71A026E\
-        F7CA8CD9E69D218D98158536F92F8A1BA7F09AB6B6A8E122\
-        F242DABB312F3F637A262174D31BF6B585FFAE5B7A035BF6\
-        F71C35FDAD44CFD2D74F9208BE258FF324943328F6722D9E\
-        E1003E5C50B1DF82CC6D241B0E2AE9CD348B1FD47E9267AF\
-        C1B2AE91EE51D6CB0E3179AB1042A95DCF6A9483B84B4B36\
-        B3861AA7255E4C0278BA3604650C10BE19482F23171B671D\
-        F1CF3B960C074301CD93C1D17603D147DAE2AEF837A62964\
-        EF15E5FB4AAC0B8C1CCAA4BE754AB5728AE9130C4C7D0288\
-        0AB9472D45556216D6998B8682283D19D42A90D5EF8E5D32\
-        767DC2822C6DF785457538ABAE83063ED9CB87C2D370F263\
-        D5FAD7466D8499EB8F464A702512B0CEE771E9130D697735\
-        F897FD036CC504326C3B01399F643532290F958C0BBD9006\
-        5DF08BABBD30AEB63B84C4605D6CA371047127D03A72D598\
-        A1EDADFE707E884725C16890549084008D391E0953C3F36B\
-        C438CD085EDD2D934CE1938C357A711E0D4A341A5B0A85ED\
-        12C1F4E5156A26746DDDE16D826F477C97477E0A0FDF6553\
-        143E2CA3A735E02ECCD94B27D04861D1119DD0C328ADF3F6\
-        8FB094B867716BD7DC0DEEBB10B8240E68034893EAD82D54\
-        C9DA754C46C7EEE0C37FDBEE48536047A6FA1AE49A0318CC\
-        FFFFFFFFFFFFFFFF",
-        16
-    )
-    .unwrap();
-    static ref PRIME_GROUP_17: BigUint = BigUint::parse_bytes(
-        b"FFFFFFFFFFFFFFFFC90FDAA22168C234C4C6628B80DC1CD129024E08\
-        8A67CC74020BBEA63B139B22514A08798E3404DDEF9519B3CD3A431B\
-        302B0A6DF25F14374FE1356D6D51C245E485B576625E7EC6F44C42E9\
-        A637ED6B0BFF5CB6F406B7EDEE386BFB5A899FA5AE9F24117C4B1FE6\
-        49286651ECE45B3DC2007CB8A163BF0598DA48361C55D39A69163FA8\
-        FD24CF5F83655D23DCA3AD961C62F356208552BB9ED529077096966D\
-        670C354E4ABC9804F1746C08CA18217C32905E462E36CE3BE39E772C\
-        180E86039B2783A2EC07A28FB5C55DF06F4C52C9DE2BCBF695581718\
-        3995497CEA956AE515D2261898FA051015728E5A8AAAC42DAD33170D\
-        04507A33A85521ABDF1CBA64ECFB850458DBEF0A8AEA71575D060C7D\
-        B3970F85A6E1E4C7ABF5AE8CDB0933D71E8C94E04A25619DCEE3D226\
-        1AD2EE6BF12FFA06D98A0864D87602733EC86A64521F2B18177B200C\
-        BBE117577A615D6C770988C0BAD946E208E24FA074E5AB3143DB5BFC\
-        E0FD108E4B82D120A92108011A723C12A787E6D788719A10BDBA5B26\
-        99C327186AF4E23C1A946834B6150BDA2583E9CA2AD44CE8DBBBC2DB\
-        04DE8EF92E8EFC141FBECAA6287C59474E6BC05D99B2964FA090C3A2\
-        233BA186515BE7ED1F612970CEE2D7AFB81BDD762170481CD0069127\
-        D5B05AA993B4EA988D8FDDC186FFB7DC90A6C08F4DF435C934028492\
-        36C3FAB4D27C7026C1D4DCB2602646DEC9751E763DBA37BDF8FF9406\
-        AD9E530EE5DB382F413001AEB06A53ED9027D831179727B0865A8918\
-        DA3EDBEBCF9B14ED44CE6CBACED4BB1BDB7F1447E6CC254B33205151\
-        2BD7AF426FB8F401378CD2BF5983CA01C64B92ECF032EA15D1721D03\
-        F482D7CE6E74FEF6D55E702F46980C82B5A84031900B1C9E59E7C97F\
-        BEC7E8F323A97A7E36CC88BE0F1D45B7FF585AC54BD407B22B4154AA\
-        CC8F6D7EBF48E1D814CC5ED20F8037E0A79715EEF29BE32806A1D58B\
-        B7C5DA76F550AA3D8A1FBFF0EB19CCB1A313D55CDA56C9EC2EF29632\
-        387FE8D76E3C0468043E8F663F4860EE12BF2D5B0B7474D6E694F91E\
-        6DCC4024FFFFFFFFFFFFFFFF",
-        16,
-    )
-    .unwrap();
-    static ref Q_GROUP_17: BigUint = BigUint::parse_bytes(
-        b"7FFFFFFFFFFFFFFFE487ED5110B4611A62633145C06E0E68\
-        948127044533E63A0105DF531D89CD9128A5043CC71A026E\
-        F7CA8CD9E69D218D98158536F92F8A1BA7F09AB6B6A8E122\
-        F242DABB312F3F637A262174D31BF6B585FFAE5B7A035BF6\
-        F71C35FDAD44CFD2D74F9208BE258FF324943328F6722D9E\
-        E1003E5C50B1DF82CC6D241B0E2AE9CD348B1FD47E9267AF\
-        C1B2AE91EE51D6CB0E3179AB1042A95DCF6A9483B84B4B36\
-        B3861AA7255E4C0278BA3604650C10BE19482F23171B671D\
-        F1CF3B960C074301CD93C1D17603D147DAE2AEF837A62964\
-        EF15E5FB4AAC0B8C1CCAA4BE754AB5728AE9130C4C7D0288\
-        0AB9472D45556216D6998B8682283D19D42A90D5EF8E5D32\
-        767DC2822C6DF785457538ABAE83063ED9CB87C2D370F263\
-        D5FAD7466D8499EB8F464A702512B0CEE771E9130D697735\
-        F897FD036CC504326C3B01399F643532290F958C0BBD9006\
-        5DF08BABBD30AEB63B84C4605D6CA371047127D03A72D598\
-        A1EDADFE707E884725C16890549084008D391E0953C3F36B\
-        C438CD085EDD2D934CE1938C357A711E0D4A341A5B0A85ED\
-        12C1F4E5156A26746DDDE16D826F477C97477E0A0FDF6553\
-        143E2CA3A735E02ECCD94B27D04861D1119DD0C328ADF3F6\
-        8FB094B867716BD7DC0DEEBB10B8240E68034893EAD82D54\
-        C9DA754C46C7EEE0C37FDBEE48536047A6FA1AE49A014249\
-        1B61FD5A693E381360EA6E593013236F64BA8F3B1EDD1BDE\
-        FC7FCA0356CF298772ED9C17A09800D7583529F6C813EC18\
-        8BCB93D8432D448C6D1F6DF5E7CD8A76A267365D676A5D8D\
-        EDBF8A23F36612A5999028A895EBD7A137DC7A009BC6695F\
-        ACC1E500E325C9767819750AE8B90E81FA416BE7373A7F7B\
-        6AAF3817A34C06415AD42018C8058E4F2CF3E4BFDF63F479\
-        91D4BD3F1B66445F078EA2DBFFAC2D62A5EA03D915A0AA55\
-        6647B6BF5FA470EC0A662F6907C01BF053CB8AF7794DF194\
-        0350EAC5DBE2ED3B7AA8551EC50FDFF8758CE658D189EAAE\
-        6D2B64F617794B191C3FF46BB71E0234021F47B31FA43077\
-        095F96AD85BA3A6B734A7C8F36E620127FFFFFFFFFFFFFFF",
-        16
-    )
-    .unwrap();
-    static ref PRIME_GROUP_18: BigUint = BigUint::parse_bytes(
-        b"FFFFFFFFFFFFFFFFC90FDAA22168C234C4C6628B80DC1CD1\
-        29024E088A67CC74020BBEA63B139B22514A08798E3404DD\
-        EF9519B3CD3A431B302B0A6DF25F14374FE1356D6D51C245\
-        E485B576625E7EC6F44C42E9A637ED6B0BFF5CB6F406B7ED\
-        EE386BFB5A899FA5AE9F24117C4B1FE649286651ECE45B3D\
-        C2007CB8A163BF0598DA48361C55D39A69163FA8FD24CF5F\
-        83655D23DCA3AD961C62F356208552BB9ED529077096966D\
-        670C354E4ABC9804F1746C08CA18217C32905E462E36CE3B\
-        E39E772C180E86039B2783A2EC07A28FB5C55DF06F4C52C9\
-        DE2BCBF6955817183995497CEA956AE515D2261898FA0510\
-        15728E5A8AAAC42DAD33170D04507A33A85521ABDF1CBA64\
-        ECFB850458DBEF0A8AEA71575D060C7DB3970F85A6E1E4C7\
-        ABF5AE8CDB0933D71E8C94E04A25619DCEE3D2261AD2EE6B\
-        F12FFA06D98A0864D87602733EC86A64521F2B18177B200C\
-        BBE117577A615D6C770988C0BAD946E208E24FA074E5AB31\
-        43DB5BFCE0FD108E4B82D120A92108011A723C12A787E6D7\
-        88719A10BDBA5B2699C327186AF4E23C1A946834B6150BDA\
-        2583E9CA2AD44CE8DBBBC2DB04DE8EF92E8EFC141FBECAA6\
-        287C59474E6BC05D99B2964FA090C3A2233BA186515BE7ED\
-        1F612970CEE2D7AFB81BDD762170481CD0069127D5B05AA9\
-        93B4EA988D8FDDC186FFB7DC90A6C08F4DF435C934028492\
-        36C3FAB4D27C7026C1D4DCB2602646DEC9751E763DBA37BD\
-        F8FF9406AD9E530EE5DB382F413001AEB06A53ED9027D831\
-        179727B0865A8918DA3EDBEBCF9B14ED44CE6CBACED4BB1B\
-        DB7F1447E6CC254B332051512BD7AF426FB8F401378CD2BF\
-        5983CA01C64B92ECF032EA15D1721D03F482D7CE6E74FEF6\
-        D55E702F46980C82B5A84031900B1C9E59E7C97FBEC7E8F3\
-        23A97A7E36CC88BE0F1D45B7FF585AC54BD407B22B4154AA\
-        CC8F6D7EBF48E1D814CC5ED20F8037E0A79715EEF29BE328\
-        06A1D58BB7C5DA76F550AA3D8A1FBFF0EB19CCB1A313D55C\
-        DA56C9EC2EF29632387FE8D76E3C0468043E8F663F4860EE\
-        12BF2D5B0B7474D6E694F91E6DBE115974A3926F12FEE5E4\
-        38777CB6A932DF8CD8BEC4D073B931BA3BC832B68D9DD300\
-        741FA7BF8AFC47ED2576F6936BA424663AAB639C5AE4F568\
-        3423B4742BF1C978238F16CBE39D652DE3FDB8BEFC848AD9\
-        22222E04A4037C0713EB57A81A23F0C73473FC646CEA306B\
-        4BCBC8862F8385DDFA9D4B7FA2C087E879683303ED5BDD3A\
-        062B3CF5B3A278A66D2A13F83F44F82DDF310EE074AB6A36\
-        4597E899A0255DC164F31CC50846851DF9AB48195DED7EA1\
-        B1D510BD7EE74D73FAF36BC31ECFA268359046F4EB879F92\
-        4009438B481C6CD7889A002ED5EE382BC9190DA6FC026E47\
-        9558E4475677E9AA9E3050E2765694DFC81F56E880B96E71\
-        60C980DD98EDD3DFFFFFFFFFFFFFFFFF",
-        16,
-    )
-    .unwrap();
-    static ref Q_GROUP_18: BigUint = BigUint::parse_bytes(
-        b"7FFFFFFFFFFFFFFFE487ED5110B4611A62633145C06E0E68\
-        948127044533E63A0105DF531D89CD9128A5043CC71A026E\
-        F7CA8CD9E69D218D98158536F92F8A1BA7F09AB6B6A8E122\
-        F242DABB312F3F637A262174D31BF6B585FFAE5B7A035BF6\
-        F71C35FDAD44CFD2D74F9208BE258FF324943328F6722D9E\
-        E1003E5C50B1DF82CC6D241B0E2AE9CD348B1FD47E9267AF\
-        C1B2AE91EE51D6CB0E3179AB1042A95DCF6A9483B84B4B36\
-        B3861AA7255E4C0278BA3604650C10BE19482F23171B671D\
-        F1CF3B960C074301CD93C1D17603D147DAE2AEF837A62964\
-        EF15E5FB4AAC0B8C1CCAA4BE754AB5728AE9130C4C7D0288\
-        0AB9472D45556216D6998B8682283D19D42A90D5EF8E5D32\
-        767DC2822C6DF785457538ABAE83063ED9CB87C2D370F263\
-        D5FAD7466D8499EB8F464A702512B0CEE771E9130D697735\
-        F897FD036CC504326C3B01399F643532290F958C0BBD9006\
-        5DF08BABBD30AEB63B84C4605D6CA371047127D03A72D598\
-        A1EDADFE707E884725C16890549084008D391E0953C3F36B\
-        C438CD085EDD2D934CE1938C357A711E0D4A341A5B0A85ED\
-        12C1F4E5156A26746DDDE16D826F477C97477E0A0FDF6553\
-        143E2CA3A735E02ECCD94B27D04861D1119DD0C328ADF3F6\
-        8FB094B867716BD7DC0DEEBB10B8240E68034893EAD82D54\
-        C9DA754C46C7EEE0C37FDBEE48536047A6FA1AE49A014249\
-        1B61FD5A693E381360EA6E593013236F64BA8F3B1EDD1BDE\
-        FC7FCA0356CF298772ED9C17A09800D7583529F6C813EC18\
-        8BCB93D8432D448C6D1F6DF5E7CD8A76A267365D676A5D8D\
-        EDBF8A23F36612A5999028A895EBD7A137DC7A009BC6695F\
-        ACC1E500E325C9767819750AE8B90E81FA416BE7373A7F7B\
-        6AAF3817A34C06415AD42018C8058E4F2CF3E4BFDF63F479\
-        91D4BD3F1B66445F078EA2DBFFAC2D62A5EA03D915A0AA55\
-        6647B6BF5FA470EC0A662F6907C01BF053CB8AF7794DF194\
-        0350EAC5DBE2ED3B7AA8551EC50FDFF8758CE658D189EAAE\
-        6D2B64F617794B191C3FF46BB71E0234021F47B31FA43077\
-        095F96AD85BA3A6B734A7C8F36DF08ACBA51C937897F72F2\
-        1C3BBE5B54996FC66C5F626839DC98DD1DE4195B46CEE980\
-        3A0FD3DFC57E23F692BB7B49B5D212331D55B1CE2D727AB4\
-        1A11DA3A15F8E4BC11C78B65F1CEB296F1FEDC5F7E42456C\
-        911117025201BE0389F5ABD40D11F8639A39FE3236751835\
-        A5E5E44317C1C2EEFD4EA5BFD16043F43CB41981F6ADEE9D\
-        03159E7AD9D13C53369509FC1FA27C16EF9887703A55B51B\
-        22CBF44CD012AEE0B2798E628423428EFCD5A40CAEF6BF50\
-        D8EA885EBF73A6B9FD79B5E18F67D1341AC8237A75C3CFC9\
-        2004A1C5A40E366BC44D00176AF71C15E48C86D37E013723\
-        CAAC7223AB3BF4D54F1828713B2B4A6FE40FAB74405CB738\
-        B064C06ECC76E9EFFFFFFFFFFFFFFFFF",
-        16,
-    )
-    .unwrap();
+    fn fast_reduce(x: &BigUint) -> BigUint {
+        REDUCE_GROUP_18.reduce(x)
+    }
+
+    fn square(a: &BigUint) -> BigUint {
+        REDUCE_GROUP_18.sqr_mod(a)
+    }
+
+    fn constants() -> &'static GroupConstants {
+        GroupConstants::get::<Self>(&CONSTANTS_GROUP_18)
+    }
 }
 
-#[cfg(test)]
-mod test {
-    use num_bigint::BigUint;
+/// Implementations of the MODPGroup trait for the finite field Diffie-Hellman groups defined in
+/// RFC 7919.
+///
+/// ffdhe2048
+/// This group is assigned the TLS NamedGroup 256.
+///
+/// The prime is: 2^2048 - 2^1984 + { \[2^1918 e\] + 560316 } * 2^64 - 1
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct FFDHEGroup2048;
 
-    use super::*;
+impl MODPGroup for FFDHEGroup2048 {
+    fn prime_modulus() -> BigUint {
+        PRIME_FFDHE_2048.clone()
+    }
 
-    fn test_order<G: MODPGroup>() {
-        let lhs = G::sophie_garmain_prime();
-        let rhs = (G::prime_modulus() - BigUint::from(1u32)) / BigUint::from(2u32);
-        assert_eq!(lhs, rhs);
+    fn group_id() -> u16 {
+        256
+    }
+
+    fn name() -> &'static str {
+        "ffdhe2048"
+    }
+
+    fn bit_size() -> usize {
+        2048
+    }
+
+    fn sophie_garmain_prime() -> BigUint {
+        Q_FFDHE_2048.clone()
+    }
+
+    fn generator() -> BigUint {
+        BigUint::from(2u32)
+    }
+
+    fn pow(a: &BigUint, e: &BigUint) -> BigUint {
+        record!(Exponentiation);
+        a.modpow(e, &PRIME_FFDHE_2048)
+    }
+
+    fn fast_reduce(x: &BigUint) -> BigUint {
+        REDUCE_FFDHE_2048.reduce(x)
+    }
+
+    fn square(a: &BigUint) -> BigUint {
+        REDUCE_FFDHE_2048.sqr_mod(a)
+    }
+
+    fn constants() -> &'static GroupConstants {
+        GroupConstants::get::<Self>(&CONSTANTS_FFDHE_2048)
+    }
+}
+
+/// Implementations of the MODPGroup trait for the finite field Diffie-Hellman groups defined in
+/// RFC 7919.
+///
+/// ffdhe3072
+/// This group is assigned the TLS NamedGroup 257.
+///
+/// The prime is: 2^3072 - 2^3008 + { \[2^2942 e\] + 2625351 } * 2^64 - 1
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct FFDHEGroup3072;
+
+impl MODPGroup for FFDHEGroup3072 {
+    fn prime_modulus() -> BigUint {
+        PRIME_FFDHE_3072.clone()
+    }
+
+    fn group_id() -> u16 {
+        257
+    }
+
+    fn name() -> &'static str {
+        "ffdhe3072"
+    }
+
+    fn bit_size() -> usize {
+        3072
+    }
+
+    fn sophie_garmain_prime() -> BigUint {
+        Q_FFDHE_3072.clone()
+    }
+
+    fn generator() -> BigUint {
+        BigUint::from(2u32)
+    }
+
+    fn pow(a: &BigUint, e: &BigUint) -> BigUint {
+        record!(Exponentiation);
+        a.modpow(e, &PRIME_FFDHE_3072)
+    }
+
+    fn fast_reduce(x: &BigUint) -> BigUint {
+        REDUCE_FFDHE_3072.reduce(x)
+    }
+
+    fn square(a: &BigUint) -> BigUint {
+        REDUCE_FFDHE_3072.sqr_mod(a)
+    }
+
+    fn constants() -> &'static GroupConstants {
+        GroupConstants::get::<Self>(&CONSTANTS_FFDHE_3072)
+    }
+}
+
+/// Implementations of the MODPGroup trait for the finite field Diffie-Hellman groups defined in
+/// RFC 7919.
+///
+/// ffdhe4096
+/// This group is assigned the TLS NamedGroup 258.
+///
+/// The prime is: 2^4096 - 2^4032 + { \[2^3966 e\] + 5736041 } * 2^64 - 1
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct FFDHEGroup4096;
+
+impl MODPGroup for FFDHEGroup4096 {
+    fn prime_modulus() -> BigUint {
+        PRIME_FFDHE_4096.clone()
+    }
+
+    fn group_id() -> u16 {
+        258
+    }
+
+    fn name() -> &'static str {
+        "ffdhe4096"
+    }
+
+    fn bit_size() -> usize {
+        4096
+    }
+
+    fn sophie_garmain_prime() -> BigUint {
+        Q_FFDHE_4096.clone()
+    }
+
+    fn generator() -> BigUint {
+        BigUint::from(2u32)
+    }
+
+    fn pow(a: &BigUint, e: &BigUint) -> BigUint {
+        record!(Exponentiation);
+        a.modpow(e, &PRIME_FFDHE_4096)
+    }
+
+    fn fast_reduce(x: &BigUint) -> BigUint {
+        REDUCE_FFDHE_4096.reduce(x)
+    }
+
+    fn square(a: &BigUint) -> BigUint {
+        REDUCE_FFDHE_4096.sqr_mod(a)
+    }
+
+    fn constants() -> &'static GroupConstants {
+        GroupConstants::get::<Self>(&CONSTANTS_FFDHE_4096)
+    }
+}
+
+/// Implementations of the MODPGroup trait for the finite field Diffie-Hellman groups defined in
+/// RFC 7919.
+///
+/// ffdhe6144
+/// This group is assigned the TLS NamedGroup 259.
+///
+/// The prime is: 2^6144 - 2^6080 + { \[2^6014 e\] + 15705020 } * 2^64 - 1
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct FFDHEGroup6144;
+
+impl MODPGroup for FFDHEGroup6144 {
+    fn prime_modulus() -> BigUint {
+        PRIME_FFDHE_6144.clone()
+    }
+
+    fn group_id() -> u16 {
+        259
+    }
+
+    fn name() -> &'static str {
+        "ffdhe6144"
+    }
+
+    fn bit_size() -> usize {
+        6144
+    }
+
+    fn sophie_garmain_prime() -> BigUint {
+        Q_FFDHE_6144.clone()
+    }
+
+    fn generator() -> BigUint {
+        BigUint::from(2u32)
+    }
+
+    fn pow(a: &BigUint, e: &BigUint) -> BigUint {
+        record!(Exponentiation);
+        a.modpow(e, &PRIME_FFDHE_6144)
+    }
+
+    fn fast_reduce(x: &BigUint) -> BigUint {
+        REDUCE_FFDHE_6144.reduce(x)
+    }
+
+    fn square(a: &BigUint) -> BigUint {
+        REDUCE_FFDHE_6144.sqr_mod(a)
+    }
+
+    fn constants() -> &'static GroupConstants {
+        GroupConstants::get::<Self>(&CONSTANTS_FFDHE_6144)
+    }
+}
+
+/// Implementations of the MODPGroup trait for the finite field Diffie-Hellman groups defined in
+/// RFC 7919.
+///
+/// ffdhe8192
+/// This group is assigned the TLS NamedGroup 260.
+///
+/// The prime is: 2^8192 - 2^8128 + { \[2^8062 e\] + 10965728 } * 2^64 - 1
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct FFDHEGroup8192;
+
+impl MODPGroup for FFDHEGroup8192 {
+    fn prime_modulus() -> BigUint {
+        PRIME_FFDHE_8192.clone()
+    }
+
+    fn group_id() -> u16 {
+        260
+    }
+
+    fn name() -> &'static str {
+        "ffdhe8192"
+    }
+
+    fn bit_size() -> usize {
+        8192
+    }
+
+    fn sophie_garmain_prime() -> BigUint {
+        Q_FFDHE_8192.clone()
+    }
+
+    fn generator() -> BigUint {
+        BigUint::from(2u32)
+    }
+
+    fn pow(a: &BigUint, e: &BigUint) -> BigUint {
+        record!(Exponentiation);
+        a.modpow(e, &PRIME_FFDHE_8192)
+    }
+
+    fn fast_reduce(x: &BigUint) -> BigUint {
+        REDUCE_FFDHE_8192.reduce(x)
+    }
+
+    fn square(a: &BigUint) -> BigUint {
+        REDUCE_FFDHE_8192.sqr_mod(a)
+    }
+
+    fn constants() -> &'static GroupConstants {
+        GroupConstants::get::<Self>(&CONSTANTS_FFDHE_8192)
+    }
+}
+
+/// Implementations of the MODPGroup trait for the MODP groups with a prime order subgroup
+/// defined in RFC 5114.
+///
+/// 1024-bit MODP Group with 160-bit Prime Order Subgroup (section 2.1)
+/// This group is assigned id 22.
+///
+/// The modulus is not a safe prime: the generator has the prime order q of 160 bits, given by
+/// [MODPGroup::subgroup_order], and [MODPGroup::sophie_garmain_prime] is not a prime.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct MODPGroup22;
+
+impl MODPGroup for MODPGroup22 {
+    fn prime_modulus() -> BigUint {
+        PRIME_GROUP_22.clone()
+    }
+
+    fn group_id() -> u16 {
+        22
+    }
+
+    fn name() -> &'static str {
+        "modp1024s160"
+    }
+
+    fn bit_size() -> usize {
+        1024
+    }
+
+    fn subgroup_order() -> BigUint {
+        Q_GROUP_22.clone()
+    }
+
+    fn generator() -> BigUint {
+        GENERATOR_GROUP_22.clone()
+    }
+
+    fn pow(a: &BigUint, e: &BigUint) -> BigUint {
+        record!(Exponentiation);
+        a.modpow(e, &PRIME_GROUP_22)
+    }
+
+    fn constants() -> &'static GroupConstants {
+        GroupConstants::get::<Self>(&CONSTANTS_GROUP_22)
+    }
+}
+
+/// Implementations of the MODPGroup trait for the MODP groups with a prime order subgroup
+/// defined in RFC 5114.
+///
+/// 2048-bit MODP Group with 224-bit Prime Order Subgroup (section 2.2)
+/// This group is assigned id 23.
+///
+/// The modulus is not a safe prime: the generator has the prime order q of 224 bits, given by
+/// [MODPGroup::subgroup_order], and [MODPGroup::sophie_garmain_prime] is not a prime.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct MODPGroup23;
+
+impl MODPGroup for MODPGroup23 {
+    fn prime_modulus() -> BigUint {
+        PRIME_GROUP_23.clone()
+    }
+
+    fn group_id() -> u16 {
+        23
+    }
+
+    fn name() -> &'static str {
+        "modp2048s224"
+    }
+
+    fn bit_size() -> usize {
+        2048
+    }
+
+    fn subgroup_order() -> BigUint {
+        Q_GROUP_23.clone()
+    }
+
+    fn generator() -> BigUint {
+        GENERATOR_GROUP_23.clone()
+    }
+
+    fn pow(a: &BigUint, e: &BigUint) -> BigUint {
+        record!(Exponentiation);
+        a.modpow(e, &PRIME_GROUP_23)
+    }
+
+    fn constants() -> &'static GroupConstants {
+        GroupConstants::get::<Self>(&CONSTANTS_GROUP_23)
+    }
+}
+
+/// Implementations of the MODPGroup trait for the MODP groups with a prime order subgroup
+/// defined in RFC 5114.
+///
+/// 2048-bit MODP Group with 256-bit Prime Order Subgroup (section 2.3)
+/// This group is assigned id 24.
+///
+/// The modulus is not a safe prime: the generator has the prime order q of 256 bits, given by
+/// [MODPGroup::subgroup_order], and [MODPGroup::sophie_garmain_prime] is not a prime.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct MODPGroup24;
+
+impl MODPGroup for MODPGroup24 {
+    fn prime_modulus() -> BigUint {
+        PRIME_GROUP_24.clone()
+    }
+
+    fn group_id() -> u16 {
+        24
+    }
+
+    fn name() -> &'static str {
+        "modp2048s256"
+    }
+
+    fn bit_size() -> usize {
+        2048
+    }
+
+    fn subgroup_order() -> BigUint {
+        Q_GROUP_24.clone()
+    }
+
+    fn generator() -> BigUint {
+        GENERATOR_GROUP_24.clone()
+    }
+
+    fn pow(a: &BigUint, e: &BigUint) -> BigUint {
+        record!(Exponentiation);
+        a.modpow(e, &PRIME_GROUP_24)
+    }
+
+    fn constants() -> &'static GroupConstants {
+        GroupConstants::get::<Self>(&CONSTANTS_GROUP_24)
+    }
+}
+
+/// Identifier of the MODP groups defined in RFC 3526, named after the size of their modulus.
+///
+/// Superseded by [AnyModpGroup], which has a variant of the same name for each of these groups and
+/// also covers the other groups of this crate. `AnyModpGroup::from(id)` converts an identifier.
+#[deprecated(note = "use AnyModpGroup, e.g. AnyModpGroup::from(id)")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ModpGroupId {
+    /// 1536-bit MODP Group, see [MODPGroup5].
+    Modp1536,
+    /// 2048-bit MODP Group, see [MODPGroup14].
+    Modp2048,
+    /// 3072-bit MODP Group, see [MODPGroup15].
+    Modp3072,
+    /// 4096-bit MODP Group, see [MODPGroup16].
+    Modp4096,
+    /// 6144-bit MODP Group, see [MODPGroup17].
+    Modp6144,
+    /// 8192-bit MODP Group, see [MODPGroup18].
+    Modp8192,
+}
+
+#[allow(deprecated)]
+impl ModpGroupId {
+    /// All the identifiers, in increasing size of modulus.
+    pub const ALL: [ModpGroupId; 6] = [
+        ModpGroupId::Modp1536,
+        ModpGroupId::Modp2048,
+        ModpGroupId::Modp3072,
+        ModpGroupId::Modp4096,
+        ModpGroupId::Modp6144,
+        ModpGroupId::Modp8192,
+    ];
+
+    /// prime modulus of the identified group
+    pub fn prime_modulus(&self) -> BigUint {
+        match self {
+            ModpGroupId::Modp1536 => MODPGroup5::prime_modulus(),
+            ModpGroupId::Modp2048 => MODPGroup14::prime_modulus(),
+            ModpGroupId::Modp3072 => MODPGroup15::prime_modulus(),
+            ModpGroupId::Modp4096 => MODPGroup16::prime_modulus(),
+            ModpGroupId::Modp6144 => MODPGroup17::prime_modulus(),
+            ModpGroupId::Modp8192 => MODPGroup18::prime_modulus(),
+        }
+    }
+
+    /// generator of the identified group
+    pub fn generator(&self) -> BigUint {
+        BigUint::from(2u32)
+    }
+
+    /// The number of the group in RFC 3526, e.g. 14 for [ModpGroupId::Modp2048].
+    pub fn number(&self) -> u32 {
+        match self {
+            ModpGroupId::Modp1536 => 5,
+            ModpGroupId::Modp2048 => 14,
+            ModpGroupId::Modp3072 => 15,
+            ModpGroupId::Modp4096 => 16,
+            ModpGroupId::Modp6144 => 17,
+            ModpGroupId::Modp8192 => 18,
+        }
+    }
+
+    /// The identifier of the group `G`, or `None` if it is not one of the MODP groups of this
+    /// crate.
+    pub fn of<G: MODPGroup>() -> Option<Self> {
+        let p = G::prime_modulus();
+        ModpGroupId::ALL
+            .into_iter()
+            .find(|id| id.prime_modulus() == p)
+    }
+
+    /// The group numbered `number` in RFC 3526, or `None` if it is not one of the MODP groups of
+    /// this crate.
+    pub fn from_number(number: u32) -> Option<Self> {
+        ModpGroupId::ALL
+            .into_iter()
+            .find(|id| id.number() == number)
+    }
+}
+
+/// A standard group chosen at runtime, e.g. from the group number received in an IKE or SSH
+/// negotiation, whose methods dispatch to the corresponding [MODPGroup]. Values are passed as
+/// `BigUint`s instead of [Element]s, which carry the group in their type.
+///
+/// # Example
+///
+/// ```rust
+/// use diffie_hellman_groups::group::AnyModpGroup;
+///
+/// let rng = &mut rand::thread_rng();
+/// let group = AnyModpGroup::try_from(14u16).unwrap();
+/// let a = group.random_exponent(rng, Some(256));
+/// let b = group.random_exponent(rng, Some(256));
+///
+/// // the public values, sent to the peer and validated on receipt
+/// let ya = group.validate_peer(group.element(&a)).unwrap();
+/// let yb = group.validate_peer(group.element(&b)).unwrap();
+/// assert_eq!(
+///     group.shared_secret(&a, &yb).unwrap(),
+///     group.shared_secret(&b, &ya).unwrap()
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AnyModpGroup {
+    /// 768-bit MODP Group, see [MODPGroup1].
+    #[cfg(feature = "legacy-groups")]
+    Modp768,
+    /// 1024-bit MODP Group, see [MODPGroup2].
+    #[cfg(feature = "legacy-groups")]
+    Modp1024,
+    /// 1536-bit MODP Group, see [MODPGroup5].
+    Modp1536,
+    /// 2048-bit MODP Group, see [MODPGroup14].
+    Modp2048,
+    /// 3072-bit MODP Group, see [MODPGroup15].
+    Modp3072,
+    /// 4096-bit MODP Group, see [MODPGroup16].
+    Modp4096,
+    /// 6144-bit MODP Group, see [MODPGroup17].
+    Modp6144,
+    /// 8192-bit MODP Group, see [MODPGroup18].
+    Modp8192,
+    /// 2048-bit Finite Field Group, see [FFDHEGroup2048].
+    Ffdhe2048,
+    /// 3072-bit Finite Field Group, see [FFDHEGroup3072].
+    Ffdhe3072,
+    /// 4096-bit Finite Field Group, see [FFDHEGroup4096].
+    Ffdhe4096,
+    /// 6144-bit Finite Field Group, see [FFDHEGroup6144].
+    Ffdhe6144,
+    /// 8192-bit Finite Field Group, see [FFDHEGroup8192].
+    Ffdhe8192,
+    /// 1024-bit MODP Group with 160-bit Prime Order Subgroup, see [MODPGroup22].
+    Modp1024S160,
+    /// 2048-bit MODP Group with 224-bit Prime Order Subgroup, see [MODPGroup23].
+    Modp2048S224,
+    /// 2048-bit MODP Group with 256-bit Prime Order Subgroup, see [MODPGroup24].
+    Modp2048S256,
+}
+
+/// Evaluate `$body` with the type alias `$g` set to the [MODPGroup] of the [AnyModpGroup]
+/// `$group`, to call generic code on a group chosen at runtime.
+macro_rules! with_any_group {
+    ($group:expr, $g:ident => $body:expr) => {
+        match $group {
+            #[cfg(feature = "legacy-groups")]
+            $crate::group::AnyModpGroup::Modp768 => {
+                type $g = $crate::group::MODPGroup1;
+                $body
+            }
+            #[cfg(feature = "legacy-groups")]
+            $crate::group::AnyModpGroup::Modp1024 => {
+                type $g = $crate::group::MODPGroup2;
+                $body
+            }
+            $crate::group::AnyModpGroup::Modp1536 => {
+                type $g = $crate::group::MODPGroup5;
+                $body
+            }
+            $crate::group::AnyModpGroup::Modp2048 => {
+                type $g = $crate::group::MODPGroup14;
+                $body
+            }
+            $crate::group::AnyModpGroup::Modp3072 => {
+                type $g = $crate::group::MODPGroup15;
+                $body
+            }
+            $crate::group::AnyModpGroup::Modp4096 => {
+                type $g = $crate::group::MODPGroup16;
+                $body
+            }
+            $crate::group::AnyModpGroup::Modp6144 => {
+                type $g = $crate::group::MODPGroup17;
+                $body
+            }
+            $crate::group::AnyModpGroup::Modp8192 => {
+                type $g = $crate::group::MODPGroup18;
+                $body
+            }
+            $crate::group::AnyModpGroup::Ffdhe2048 => {
+                type $g = $crate::group::FFDHEGroup2048;
+                $body
+            }
+            $crate::group::AnyModpGroup::Ffdhe3072 => {
+                type $g = $crate::group::FFDHEGroup3072;
+                $body
+            }
+            $crate::group::AnyModpGroup::Ffdhe4096 => {
+                type $g = $crate::group::FFDHEGroup4096;
+                $body
+            }
+            $crate::group::AnyModpGroup::Ffdhe6144 => {
+                type $g = $crate::group::FFDHEGroup6144;
+                $body
+            }
+            $crate::group::AnyModpGroup::Ffdhe8192 => {
+                type $g = $crate::group::FFDHEGroup8192;
+                $body
+            }
+            $crate::group::AnyModpGroup::Modp1024S160 => {
+                type $g = $crate::group::MODPGroup22;
+                $body
+            }
+            $crate::group::AnyModpGroup::Modp2048S224 => {
+                type $g = $crate::group::MODPGroup23;
+                $body
+            }
+            $crate::group::AnyModpGroup::Modp2048S256 => {
+                type $g = $crate::group::MODPGroup24;
+                $body
+            }
+        }
+    };
+}
+#[cfg(any(feature = "capi", feature = "python", feature = "wasm"))]
+pub(crate) use with_any_group;
+
+impl AnyModpGroup {
+    /// All the groups, in the order of their number.
+    pub const ALL: &'static [AnyModpGroup] = &[
+        #[cfg(feature = "legacy-groups")]
+        AnyModpGroup::Modp768,
+        #[cfg(feature = "legacy-groups")]
+        AnyModpGroup::Modp1024,
+        AnyModpGroup::Modp1536,
+        AnyModpGroup::Modp2048,
+        AnyModpGroup::Modp3072,
+        AnyModpGroup::Modp4096,
+        AnyModpGroup::Modp6144,
+        AnyModpGroup::Modp8192,
+        AnyModpGroup::Modp1024S160,
+        AnyModpGroup::Modp2048S224,
+        AnyModpGroup::Modp2048S256,
+        AnyModpGroup::Ffdhe2048,
+        AnyModpGroup::Ffdhe3072,
+        AnyModpGroup::Ffdhe4096,
+        AnyModpGroup::Ffdhe6144,
+        AnyModpGroup::Ffdhe8192,
+    ];
+
+    /// The groups of RFC 3526, in increasing size of modulus.
+    pub const RFC3526: [AnyModpGroup; 6] = [
+        AnyModpGroup::Modp1536,
+        AnyModpGroup::Modp2048,
+        AnyModpGroup::Modp3072,
+        AnyModpGroup::Modp4096,
+        AnyModpGroup::Modp6144,
+        AnyModpGroup::Modp8192,
+    ];
+
+    /// The group `G`, or `None` if it is not one of the groups of this crate.
+    pub fn of<G: MODPGroup>() -> Option<Self> {
+        let group = AnyModpGroup::try_from(G::group_id()).ok()?;
+        (*group.as_dyn().prime() == G::prime_modulus()).then_some(group)
+    }
+
+    /// The group of RFC 3526 numbered `number`, e.g. 14, to which the C, Python and wasm bindings
+    /// are limited.
+    #[cfg(any(feature = "capi", feature = "python", feature = "wasm"))]
+    pub(crate) fn from_rfc3526_number(number: u32) -> Option<Self> {
+        AnyModpGroup::RFC3526
+            .into_iter()
+            .find(|group| u32::from(group.group_id()) == number)
+    }
+
+    /// The reduction context of a group of RFC 3526.
+    #[cfg(feature = "zeroize")]
+    pub(crate) fn special_form(&self) -> Option<&'static SpecialForm> {
+        match self {
+            AnyModpGroup::Modp1536 => Some(&REDUCE_GROUP_5),
+            AnyModpGroup::Modp2048 => Some(&REDUCE_GROUP_14),
+            AnyModpGroup::Modp3072 => Some(&REDUCE_GROUP_15),
+            AnyModpGroup::Modp4096 => Some(&REDUCE_GROUP_16),
+            AnyModpGroup::Modp6144 => Some(&REDUCE_GROUP_17),
+            AnyModpGroup::Modp8192 => Some(&REDUCE_GROUP_18),
+            _ => None,
+        }
+    }
+
+    /// Whether the modulus is a safe prime p = 2q + 1 with a generator of order q, i.e. any group
+    /// but those of RFC 5114, whose subgroup order is much smaller than (p - 1) / 2.
+    pub fn is_safe_prime(&self) -> bool {
+        !matches!(
+            self,
+            AnyModpGroup::Modp1024S160 | AnyModpGroup::Modp2048S224 | AnyModpGroup::Modp2048S256
+        )
+    }
+
+    /// see [MODPGroup::group_id]
+    pub fn group_id(&self) -> u16 {
+        with_any_group!(self, G => G::group_id())
+    }
+
+    /// see [MODPGroup::name]
+    pub fn name(&self) -> &'static str {
+        with_any_group!(self, G => G::name())
+    }
+
+    /// see [MODPGroup::bit_size]
+    pub fn bit_size(&self) -> usize {
+        with_any_group!(self, G => G::bit_size())
+    }
+
+    /// see [MODPGroup::security_strength]
+    pub fn security_strength(&self) -> u32 {
+        with_any_group!(self, G => G::security_strength())
+    }
+
+    /// prime modulus of the group
+    pub fn prime_modulus(&self) -> BigUint {
+        with_any_group!(self, G => G::prime_modulus())
+    }
+
+    /// generator of the group
+    pub fn generator(&self) -> BigUint {
+        with_any_group!(self, G => G::generator())
+    }
+
+    /// the prime order q of the subgroup generated by g, see [MODPGroup::subgroup_order]
+    pub fn order(&self) -> BigUint {
+        with_any_group!(self, G => G::subgroup_order())
+    }
+
+    /// modular exponentiation, compute a^e mod p
+    pub fn pow(&self, a: &BigUint, e: &BigUint) -> BigUint {
+        with_any_group!(self, G => G::pow(a, e))
+    }
+
+    /// compute the element of the group from the exponent, compute g^e mod p, e.g. the public
+    /// value of a secret exponent
+    pub fn element(&self, exponent: &BigUint) -> BigUint {
+        with_any_group!(self, G => G::element(exponent))
+    }
+
+    /// Draw a secret exponent from `[1, min(2^exponent_bits, q))`, or from `[1, q)` if
+    /// `exponent_bits` is `None`, as [EphemeralSecret::generate](crate::pool::EphemeralSecret::generate).
+    pub fn random_exponent<R: CryptoRngCore + ?Sized>(
+        &self,
+        rng: &mut R,
+        exponent_bits: Option<u64>,
+    ) -> BigUint {
+        let q = self.order();
+        let bound = match exponent_bits {
+            Some(bits) => (BigUint::from(1u64) << bits).min(q),
+            None => q,
+        };
+        rng.gen_biguint_range(&BigUint::from(1u64), &bound)
+    }
+
+    /// Validate a public value received from a peer, see [Element::validate].
+    pub fn validate(&self, value: &BigUint) -> Result<(), ValidationError> {
+        if *value >= self.prime_modulus() {
+            return Err(ValidationError::OutOfRange);
+        }
+        with_any_group!(self, G => Element::<G>::from_value(value.clone()).validate())
+    }
+
+    /// Validate a public value received from a peer with [AnyModpGroup::validate], to use it in
+    /// [AnyModpGroup::shared_secret].
+    pub fn validate_peer(&self, value: BigUint) -> Result<ValidatedPublicValue, ValidationError> {
+        DhGroup::validate_peer(self, value)
+    }
+
+    /// Compute the shared secret `peer^exponent mod p` with the validated public value of a
+    /// peer.
+    ///
+    /// # Errors
+    /// Returns [DhGroupsError::GroupMismatch] if `peer` was validated in another group.
+    pub fn shared_secret(
+        &self,
+        exponent: &BigUint,
+        peer: &ValidatedPublicValue,
+    ) -> Result<BigUint, DhGroupsError> {
+        DhGroup::shared_secret(self, exponent, peer)
+    }
+
+    /// Encode a value in big-endian bytes, left-padded with zeros to the length of p, see
+    /// [Element::to_bytes_be].
+    pub fn to_bytes_be(&self, value: &BigUint) -> Vec<u8> {
+        with_any_group!(self, G => Element::<G>::from_value(value.clone()).to_bytes_be())
+    }
+
+    /// The [StaticGroup] of the group, borrowed by the [DhGroup] implementation.
+    pub(crate) fn as_dyn(&self) -> &'static dyn DhGroup {
+        with_any_group!(self, G => &StaticGroup::<G>::INSTANCE)
+    }
+
+    /// Decode a value from big-endian bytes of the length of p, see [Element::from_bytes_be].
+    ///
+    /// # Errors
+    /// Returns the errors of [Element::from_bytes_be].
+    pub fn from_bytes_be(&self, bytes: &[u8]) -> Result<BigUint, DhGroupsError> {
+        with_any_group!(self, G => Element::<G>::from_bytes_be(bytes).map(|e| e.value))
+    }
+}
+
+/// The smallest group of RFC 3526 whose [MODPGroup::security_strength] is at least `bits`, e.g.
+/// [AnyModpGroup::Modp3072] for 128 bits, or `None` if no group is strong enough. The groups of
+/// RFC 7919 of the same sizes are as strong, and the legacy groups and the groups of RFC 5114 are
+/// never chosen.
+pub fn strongest_group_at_least(bits: u32) -> Option<AnyModpGroup> {
+    AnyModpGroup::RFC3526
+        .into_iter()
+        .find(|group| group.security_strength() >= bits)
+}
+
+/// Look up a group by its number, see [MODPGroup::group_id].
+impl TryFrom<u16> for AnyModpGroup {
+    type Error = DhGroupsError;
+
+    /// # Errors
+    /// Returns [DhGroupsError::UnknownGroup] if no group of this crate has the number `id`.
+    fn try_from(id: u16) -> Result<Self, Self::Error> {
+        AnyModpGroup::ALL
+            .iter()
+            .copied()
+            .find(|group| group.group_id() == id)
+            .ok_or(DhGroupsError::UnknownGroup(id))
+    }
+}
+
+#[allow(deprecated)]
+impl From<ModpGroupId> for AnyModpGroup {
+    fn from(id: ModpGroupId) -> Self {
+        match id {
+            ModpGroupId::Modp1536 => AnyModpGroup::Modp1536,
+            ModpGroupId::Modp2048 => AnyModpGroup::Modp2048,
+            ModpGroupId::Modp3072 => AnyModpGroup::Modp3072,
+            ModpGroupId::Modp4096 => AnyModpGroup::Modp4096,
+            ModpGroupId::Modp6144 => AnyModpGroup::Modp6144,
+            ModpGroupId::Modp8192 => AnyModpGroup::Modp8192,
+        }
+    }
+}
+
+#[cfg(feature = "legacy-groups")]
+static CONSTANTS_GROUP_1: OnceLock<GroupConstants> = OnceLock::new();
+#[cfg(feature = "legacy-groups")]
+static CONSTANTS_GROUP_2: OnceLock<GroupConstants> = OnceLock::new();
+static CONSTANTS_GROUP_5: OnceLock<GroupConstants> = OnceLock::new();
+static CONSTANTS_GROUP_14: OnceLock<GroupConstants> = OnceLock::new();
+static CONSTANTS_GROUP_15: OnceLock<GroupConstants> = OnceLock::new();
+static CONSTANTS_GROUP_16: OnceLock<GroupConstants> = OnceLock::new();
+static CONSTANTS_GROUP_17: OnceLock<GroupConstants> = OnceLock::new();
+static CONSTANTS_GROUP_18: OnceLock<GroupConstants> = OnceLock::new();
+static CONSTANTS_FFDHE_2048: OnceLock<GroupConstants> = OnceLock::new();
+static CONSTANTS_FFDHE_3072: OnceLock<GroupConstants> = OnceLock::new();
+static CONSTANTS_FFDHE_4096: OnceLock<GroupConstants> = OnceLock::new();
+static CONSTANTS_FFDHE_6144: OnceLock<GroupConstants> = OnceLock::new();
+static CONSTANTS_FFDHE_8192: OnceLock<GroupConstants> = OnceLock::new();
+static CONSTANTS_GROUP_22: OnceLock<GroupConstants> = OnceLock::new();
+static CONSTANTS_GROUP_23: OnceLock<GroupConstants> = OnceLock::new();
+static CONSTANTS_GROUP_24: OnceLock<GroupConstants> = OnceLock::new();
+
+lazy_static! {
+    static ref PRIME_GROUP_5: BigUint = from_rfc_hex(
+        "FFFFFFFF FFFFFFFF C90FDAA2 2168C234 C4C6628B 80DC1CD1
+        29024E08 8A67CC74 020BBEA6 3B139B22 514A0879 8E3404DD
+        EF9519B3 CD3A431B 302B0A6D F25F1437 4FE1356D 6D51C245
+        E485B576 625E7EC6 F44C42E9 A637ED6B 0BFF5CB6 F406B7ED
+        EE386BFB 5A899FA5 AE9F2411 7C4B1FE6 49286651 ECE45B3D
+        C2007CB8 A163BF05 98DA4836 1C55D39A 69163FA8 FD24CF5F
+        83655D23 DCA3AD96 1C62F356 208552BB 9ED52907 7096966D
+        670C354E 4ABC9804 F1746C08 CA237327 FFFFFFFF FFFFFFFF",
+    )
+    .unwrap();
+    static ref Q_GROUP_5: BigUint = from_rfc_hex(Q_HEX_GROUP_5).unwrap();
+    pub(crate) static ref REDUCE_GROUP_5: SpecialForm = SpecialForm::new(&PRIME_GROUP_5);
+    static ref PRIME_GROUP_14: BigUint = from_rfc_hex(
+        "FFFFFFFF FFFFFFFF C90FDAA2 2168C234 C4C6628B 80DC1CD1
+        29024E08 8A67CC74 020BBEA6 3B139B22 514A0879 8E3404DD
+        EF9519B3 CD3A431B 302B0A6D F25F1437 4FE1356D 6D51C245
+        E485B576 625E7EC6 F44C42E9 A637ED6B 0BFF5CB6 F406B7ED
+        EE386BFB 5A899FA5 AE9F2411 7C4B1FE6 49286651 ECE45B3D
+        C2007CB8 A163BF05 98DA4836 1C55D39A 69163FA8 FD24CF5F
+        83655D23 DCA3AD96 1C62F356 208552BB 9ED52907 7096966D
+        670C354E 4ABC9804 F1746C08 CA18217C 32905E46 2E36CE3B
+        E39E772C 180E8603 9B2783A2 EC07A28F B5C55DF0 6F4C52C9
+        DE2BCBF6 95581718 3995497C EA956AE5 15D22618 98FA0510
+        15728E5A 8AACAA68 FFFFFFFF FFFFFFFF",
+    )
+    .unwrap();
+    static ref Q_GROUP_14: BigUint = from_rfc_hex(Q_HEX_GROUP_14).unwrap();
+    pub(crate) static ref REDUCE_GROUP_14: SpecialForm = SpecialForm::new(&PRIME_GROUP_14);
+    static ref PRIME_GROUP_15: BigUint = from_rfc_hex(
+        "FFFFFFFF FFFFFFFF C90FDAA2 2168C234 C4C6628B 80DC1CD1
+        29024E08 8A67CC74 020BBEA6 3B139B22 514A0879 8E3404DD
+        EF9519B3 CD3A431B 302B0A6D F25F1437 4FE1356D 6D51C245
+        E485B576 625E7EC6 F44C42E9 A637ED6B 0BFF5CB6 F406B7ED
+        EE386BFB 5A899FA5 AE9F2411 7C4B1FE6 49286651 ECE45B3D
+        C2007CB8 A163BF05 98DA4836 1C55D39A 69163FA8 FD24CF5F
+        83655D23 DCA3AD96 1C62F356 208552BB 9ED52907 7096966D
+        670C354E 4ABC9804 F1746C08 CA18217C 32905E46 2E36CE3B
+        E39E772C 180E8603 9B2783A2 EC07A28F B5C55DF0 6F4C52C9
+        DE2BCBF6 95581718 3995497C EA956AE5 15D22618 98FA0510
+        15728E5A 8AAAC42D AD33170D 04507A33 A85521AB DF1CBA64
+        ECFB8504 58DBEF0A 8AEA7157 5D060C7D B3970F85 A6E1E4C7
+        ABF5AE8C DB0933D7 1E8C94E0 4A25619D CEE3D226 1AD2EE6B
+        F12FFA06 D98A0864 D8760273 3EC86A64 521F2B18 177B200C
+        BBE11757 7A615D6C 770988C0 BAD946E2 08E24FA0 74E5AB31
+        43DB5BFC E0FD108E 4B82D120 A93AD2CA FFFFFFFF FFFFFFFF",
+    )
+    .unwrap();
+    static ref PRIME_GROUP_16: BigUint = from_rfc_hex(
+        "FFFFFFFF FFFFFFFF C90FDAA2 2168C234 C4C6628B 80DC1CD1
+        29024E08 8A67CC74 020BBEA6 3B139B22 514A0879 8E3404DD
+        EF9519B3 CD3A431B 302B0A6D F25F1437 4FE1356D 6D51C245
+        E485B576 625E7EC6 F44C42E9 A637ED6B 0BFF5CB6 F406B7ED
+        EE386BFB 5A899FA5 AE9F2411 7C4B1FE6 49286651 ECE45B3D
+        C2007CB8 A163BF05 98DA4836 1C55D39A 69163FA8 FD24CF5F
+        83655D23 DCA3AD96 1C62F356 208552BB 9ED52907 7096966D
+        670C354E 4ABC9804 F1746C08 CA18217C 32905E46 2E36CE3B
+        E39E772C 180E8603 9B2783A2 EC07A28F B5C55DF0 6F4C52C9
+        DE2BCBF6 95581718 3995497C EA956AE5 15D22618 98FA0510
+        15728E5A 8AAAC42D AD33170D 04507A33 A85521AB DF1CBA64
+        ECFB8504 58DBEF0A 8AEA7157 5D060C7D B3970F85 A6E1E4C7
+        ABF5AE8C DB0933D7 1E8C94E0 4A25619D CEE3D226 1AD2EE6B
+        F12FFA06 D98A0864 D8760273 3EC86A64 521F2B18 177B200C
+        BBE11757 7A615D6C 770988C0 BAD946E2 08E24FA0 74E5AB31
+        43DB5BFC E0FD108E 4B82D120 A9210801 1A723C12 A787E6D7
+        88719A10 BDBA5B26 99C32718 6AF4E23C 1A946834 B6150BDA
+        2583E9CA 2AD44CE8 DBBBC2DB 04DE8EF9 2E8EFC14 1FBECAA6
+        287C5947 4E6BC05D 99B2964F A090C3A2 233BA186 515BE7ED
+        1F612970 CEE2D7AF B81BDD76 2170481C D0069127 D5B05AA9
+        93B4EA98 8D8FDDC1 86FFB7DC 90A6C08F 4DF435C9 34063199
+        FFFFFFFF FFFFFFFF",
+    )
+    .unwrap();
+    static ref Q_GROUP_15: BigUint = from_rfc_hex(Q_HEX_GROUP_15).unwrap();
+    pub(crate) static ref REDUCE_GROUP_15: SpecialForm = SpecialForm::new(&PRIME_GROUP_15);
+    static ref Q_GROUP_16: BigUint = from_rfc_hex(Q_HEX_GROUP_16).unwrap();
+    pub(crate) static ref REDUCE_GROUP_16: SpecialForm = SpecialForm::new(&PRIME_GROUP_16);
+    static ref PRIME_GROUP_17: BigUint = from_rfc_hex(
+        "FFFFFFFF FFFFFFFF C90FDAA2 2168C234 C4C6628B 80DC1CD1
+        29024E08 8A67CC74 020BBEA6 3B139B22 514A0879 8E3404DD
+        EF9519B3 CD3A431B 302B0A6D F25F1437 4FE1356D 6D51C245
+        E485B576 625E7EC6 F44C42E9 A637ED6B 0BFF5CB6 F406B7ED
+        EE386BFB 5A899FA5 AE9F2411 7C4B1FE6 49286651 ECE45B3D
+        C2007CB8 A163BF05 98DA4836 1C55D39A 69163FA8 FD24CF5F
+        83655D23 DCA3AD96 1C62F356 208552BB 9ED52907 7096966D
+        670C354E 4ABC9804 F1746C08 CA18217C 32905E46 2E36CE3B
+        E39E772C 180E8603 9B2783A2 EC07A28F B5C55DF0 6F4C52C9
+        DE2BCBF6 95581718 3995497C EA956AE5 15D22618 98FA0510
+        15728E5A 8AAAC42D AD33170D 04507A33 A85521AB DF1CBA64
+        ECFB8504 58DBEF0A 8AEA7157 5D060C7D B3970F85 A6E1E4C7
+        ABF5AE8C DB0933D7 1E8C94E0 4A25619D CEE3D226 1AD2EE6B
+        F12FFA06 D98A0864 D8760273 3EC86A64 521F2B18 177B200C
+        BBE11757 7A615D6C 770988C0 BAD946E2 08E24FA0 74E5AB31
+        43DB5BFC E0FD108E 4B82D120 A9210801 1A723C12 A787E6D7
+        88719A10 BDBA5B26 99C32718 6AF4E23C 1A946834 B6150BDA
+        2583E9CA 2AD44CE8 DBBBC2DB 04DE8EF9 2E8EFC14 1FBECAA6
+        287C5947 4E6BC05D 99B2964F A090C3A2 233BA186 515BE7ED
+        1F612970 CEE2D7AF B81BDD76 2170481C D0069127 D5B05AA9
+        93B4EA98 8D8FDDC1 86FFB7DC 90A6C08F 4DF435C9 34028492
+        36C3FAB4 D27C7026 C1D4DCB2 602646DE C9751E76 3DBA37BD
+        F8FF9406 AD9E530E E5DB382F 413001AE B06A53ED 9027D831
+        179727B0 865A8918 DA3EDBEB CF9B14ED 44CE6CBA CED4BB1B
+        DB7F1447 E6CC254B 33205151 2BD7AF42 6FB8F401 378CD2BF
+        5983CA01 C64B92EC F032EA15 D1721D03 F482D7CE 6E74FEF6
+        D55E702F 46980C82 B5A84031 900B1C9E 59E7C97F BEC7E8F3
+        23A97A7E 36CC88BE 0F1D45B7 FF585AC5 4BD407B2 2B4154AA
+        CC8F6D7E BF48E1D8 14CC5ED2 0F8037E0 A79715EE F29BE328
+        06A1D58B B7C5DA76 F550AA3D 8A1FBFF0 EB19CCB1 A313D55C
+        DA56C9EC 2EF29632 387FE8D7 6E3C0468 043E8F66 3F4860EE
+        12BF2D5B 0B7474D6 E694F91E 6DCC4024 FFFFFFFF FFFFFFFF",
+    )
+    .unwrap();
+    static ref Q_GROUP_17: BigUint = from_rfc_hex(Q_HEX_GROUP_17).unwrap();
+    pub(crate) static ref REDUCE_GROUP_17: SpecialForm = SpecialForm::new(&PRIME_GROUP_17);
+    static ref PRIME_GROUP_18: BigUint = from_rfc_hex(
+        "FFFFFFFF FFFFFFFF C90FDAA2 2168C234 C4C6628B 80DC1CD1
+        29024E08 8A67CC74 020BBEA6 3B139B22 514A0879 8E3404DD
+        EF9519B3 CD3A431B 302B0A6D F25F1437 4FE1356D 6D51C245
+        E485B576 625E7EC6 F44C42E9 A637ED6B 0BFF5CB6 F406B7ED
+        EE386BFB 5A899FA5 AE9F2411 7C4B1FE6 49286651 ECE45B3D
+        C2007CB8 A163BF05 98DA4836 1C55D39A 69163FA8 FD24CF5F
+        83655D23 DCA3AD96 1C62F356 208552BB 9ED52907 7096966D
+        670C354E 4ABC9804 F1746C08 CA18217C 32905E46 2E36CE3B
+        E39E772C 180E8603 9B2783A2 EC07A28F B5C55DF0 6F4C52C9
+        DE2BCBF6 95581718 3995497C EA956AE5 15D22618 98FA0510
+        15728E5A 8AAAC42D AD33170D 04507A33 A85521AB DF1CBA64
+        ECFB8504 58DBEF0A 8AEA7157 5D060C7D B3970F85 A6E1E4C7
+        ABF5AE8C DB0933D7 1E8C94E0 4A25619D CEE3D226 1AD2EE6B
+        F12FFA06 D98A0864 D8760273 3EC86A64 521F2B18 177B200C
+        BBE11757 7A615D6C 770988C0 BAD946E2 08E24FA0 74E5AB31
+        43DB5BFC E0FD108E 4B82D120 A9210801 1A723C12 A787E6D7
+        88719A10 BDBA5B26 99C32718 6AF4E23C 1A946834 B6150BDA
+        2583E9CA 2AD44CE8 DBBBC2DB 04DE8EF9 2E8EFC14 1FBECAA6
+        287C5947 4E6BC05D 99B2964F A090C3A2 233BA186 515BE7ED
+        1F612970 CEE2D7AF B81BDD76 2170481C D0069127 D5B05AA9
+        93B4EA98 8D8FDDC1 86FFB7DC 90A6C08F 4DF435C9 34028492
+        36C3FAB4 D27C7026 C1D4DCB2 602646DE C9751E76 3DBA37BD
+        F8FF9406 AD9E530E E5DB382F 413001AE B06A53ED 9027D831
+        179727B0 865A8918 DA3EDBEB CF9B14ED 44CE6CBA CED4BB1B
+        DB7F1447 E6CC254B 33205151 2BD7AF42 6FB8F401 378CD2BF
+        5983CA01 C64B92EC F032EA15 D1721D03 F482D7CE 6E74FEF6
+        D55E702F 46980C82 B5A84031 900B1C9E 59E7C97F BEC7E8F3
+        23A97A7E 36CC88BE 0F1D45B7 FF585AC5 4BD407B2 2B4154AA
+        CC8F6D7E BF48E1D8 14CC5ED2 0F8037E0 A79715EE F29BE328
+        06A1D58B B7C5DA76 F550AA3D 8A1FBFF0 EB19CCB1 A313D55C
+        DA56C9EC 2EF29632 387FE8D7 6E3C0468 043E8F66 3F4860EE
+        12BF2D5B 0B7474D6 E694F91E 6DBE1159 74A3926F 12FEE5E4
+        38777CB6 A932DF8C D8BEC4D0 73B931BA 3BC832B6 8D9DD300
+        741FA7BF 8AFC47ED 2576F693 6BA42466 3AAB639C 5AE4F568
+        3423B474 2BF1C978 238F16CB E39D652D E3FDB8BE FC848AD9
+        22222E04 A4037C07 13EB57A8 1A23F0C7 3473FC64 6CEA306B
+        4BCBC886 2F8385DD FA9D4B7F A2C087E8 79683303 ED5BDD3A
+        062B3CF5 B3A278A6 6D2A13F8 3F44F82D DF310EE0 74AB6A36
+        4597E899 A0255DC1 64F31CC5 0846851D F9AB4819 5DED7EA1
+        B1D510BD 7EE74D73 FAF36BC3 1ECFA268 359046F4 EB879F92
+        4009438B 481C6CD7 889A002E D5EE382B C9190DA6 FC026E47
+        9558E447 5677E9AA 9E3050E2 765694DF C81F56E8 80B96E71
+        60C980DD 98EDD3DF FFFFFFFF FFFFFFFF",
+    )
+    .unwrap();
+    static ref Q_GROUP_18: BigUint = from_rfc_hex(Q_HEX_GROUP_18).unwrap();
+    pub(crate) static ref REDUCE_GROUP_18: SpecialForm = SpecialForm::new(&PRIME_GROUP_18);
+    static ref PRIME_FFDHE_2048: BigUint = from_rfc_hex(
+        "FFFFFFFF FFFFFFFF ADF85458 A2BB4A9A AFDC5620 273D3CF1
+        D8B9C583 CE2D3695 A9E13641 146433FB CC939DCE 249B3EF9
+        7D2FE363 630C75D8 F681B202 AEC4617A D3DF1ED5 D5FD6561
+        2433F51F 5F066ED0 85636555 3DED1AF3 B557135E 7F57C935
+        984F0C70 E0E68B77 E2A689DA F3EFE872 1DF158A1 36ADE735
+        30ACCA4F 483A797A BC0AB182 B324FB61 D108A94B B2C8E3FB
+        B96ADAB7 60D7F468 1D4F42A3 DE394DF4 AE56EDE7 6372BB19
+        0B07A7C8 EE0A6D70 9E02FCE1 CDF7E2EC C03404CD 28342F61
+        9172FE9C E98583FF 8E4F1232 EEF28183 C3FE3B1B 4C6FAD73
+        3BB5FCBC 2EC22005 C58EF183 7D1683B2 C6F34A26 C1B2EFFA
+        886B4238 61285C97 FFFFFFFF FFFFFFFF",
+    )
+    .unwrap();
+    static ref Q_FFDHE_2048: BigUint = from_rfc_hex(
+        "7FFFFFFF FFFFFFFF D6FC2A2C 515DA54D 57EE2B10 139E9E78
+        EC5CE2C1 E7169B4A D4F09B20 8A3219FD E649CEE7 124D9F7C
+        BE97F1B1 B1863AEC 7B40D901 576230BD 69EF8F6A EAFEB2B0
+        9219FA8F AF833768 42B1B2AA 9EF68D79 DAAB89AF 3FABE49A
+        CC278638 707345BB F15344ED 79F7F439 0EF8AC50 9B56F39A
+        98566527 A41D3CBD 5E0558C1 59927DB0 E88454A5 D96471FD
+        DCB56D5B B06BFA34 0EA7A151 EF1CA6FA 572B76F3 B1B95D8C
+        8583D3E4 770536B8 4F017E70 E6FBF176 601A0266 941A17B0
+        C8B97F4E 74C2C1FF C7278919 777940C1 E1FF1D8D A637D6B9
+        9DDAFE5E 17611002 E2C778C1 BE8B41D9 6379A513 60D977FD
+        4435A11C 30942E4B FFFFFFFF FFFFFFFF",
+    )
+    .unwrap();
+    static ref REDUCE_FFDHE_2048: SpecialForm = SpecialForm::new(&PRIME_FFDHE_2048);
+    static ref PRIME_FFDHE_3072: BigUint = from_rfc_hex(
+        "FFFFFFFF FFFFFFFF ADF85458 A2BB4A9A AFDC5620 273D3CF1
+        D8B9C583 CE2D3695 A9E13641 146433FB CC939DCE 249B3EF9
+        7D2FE363 630C75D8 F681B202 AEC4617A D3DF1ED5 D5FD6561
+        2433F51F 5F066ED0 85636555 3DED1AF3 B557135E 7F57C935
+        984F0C70 E0E68B77 E2A689DA F3EFE872 1DF158A1 36ADE735
+        30ACCA4F 483A797A BC0AB182 B324FB61 D108A94B B2C8E3FB
+        B96ADAB7 60D7F468 1D4F42A3 DE394DF4 AE56EDE7 6372BB19
+        0B07A7C8 EE0A6D70 9E02FCE1 CDF7E2EC C03404CD 28342F61
+        9172FE9C E98583FF 8E4F1232 EEF28183 C3FE3B1B 4C6FAD73
+        3BB5FCBC 2EC22005 C58EF183 7D1683B2 C6F34A26 C1B2EFFA
+        886B4238 611FCFDC DE355B3B 6519035B BC34F4DE F99C0238
+        61B46FC9 D6E6C907 7AD91D26 91F7F7EE 598CB0FA C186D91C
+        AEFE1309 85139270 B4130C93 BC437944 F4FD4452 E2D74DD3
+        64F2E21E 71F54BFF 5CAE82AB 9C9DF69E E86D2BC5 22363A0D
+        ABC52197 9B0DEADA 1DBF9A42 D5C4484E 0ABCD06B FA53DDEF
+        3C1B20EE 3FD59D7C 25E41D2B 66C62E37 FFFFFFFF FFFFFFFF",
+    )
+    .unwrap();
+    static ref Q_FFDHE_3072: BigUint = from_rfc_hex(
+        "7FFFFFFF FFFFFFFF D6FC2A2C 515DA54D 57EE2B10 139E9E78
+        EC5CE2C1 E7169B4A D4F09B20 8A3219FD E649CEE7 124D9F7C
+        BE97F1B1 B1863AEC 7B40D901 576230BD 69EF8F6A EAFEB2B0
+        9219FA8F AF833768 42B1B2AA 9EF68D79 DAAB89AF 3FABE49A
+        CC278638 707345BB F15344ED 79F7F439 0EF8AC50 9B56F39A
+        98566527 A41D3CBD 5E0558C1 59927DB0 E88454A5 D96471FD
+        DCB56D5B B06BFA34 0EA7A151 EF1CA6FA 572B76F3 B1B95D8C
+        8583D3E4 770536B8 4F017E70 E6FBF176 601A0266 941A17B0
+        C8B97F4E 74C2C1FF C7278919 777940C1 E1FF1D8D A637D6B9
+        9DDAFE5E 17611002 E2C778C1 BE8B41D9 6379A513 60D977FD
+        4435A11C 308FE7EE 6F1AAD9D B28C81AD DE1A7A6F 7CCE011C
+        30DA37E4 EB736483 BD6C8E93 48FBFBF7 2CC6587D 60C36C8E
+        577F0984 C289C938 5A098649 DE21BCA2 7A7EA229 716BA6E9
+        B279710F 38FAA5FF AE574155 CE4EFB4F 743695E2 911B1D06
+        D5E290CB CD86F56D 0EDFCD21 6AE22427 055E6835 FD29EEF7
+        9E0D9077 1FEACEBE 12F20E95 B363171B FFFFFFFF FFFFFFFF",
+    )
+    .unwrap();
+    static ref REDUCE_FFDHE_3072: SpecialForm = SpecialForm::new(&PRIME_FFDHE_3072);
+    static ref PRIME_FFDHE_4096: BigUint = from_rfc_hex(
+        "FFFFFFFF FFFFFFFF ADF85458 A2BB4A9A AFDC5620 273D3CF1
+        D8B9C583 CE2D3695 A9E13641 146433FB CC939DCE 249B3EF9
+        7D2FE363 630C75D8 F681B202 AEC4617A D3DF1ED5 D5FD6561
+        2433F51F 5F066ED0 85636555 3DED1AF3 B557135E 7F57C935
+        984F0C70 E0E68B77 E2A689DA F3EFE872 1DF158A1 36ADE735
+        30ACCA4F 483A797A BC0AB182 B324FB61 D108A94B B2C8E3FB
+        B96ADAB7 60D7F468 1D4F42A3 DE394DF4 AE56EDE7 6372BB19
+        0B07A7C8 EE0A6D70 9E02FCE1 CDF7E2EC C03404CD 28342F61
+        9172FE9C E98583FF 8E4F1232 EEF28183 C3FE3B1B 4C6FAD73
+        3BB5FCBC 2EC22005 C58EF183 7D1683B2 C6F34A26 C1B2EFFA
+        886B4238 611FCFDC DE355B3B 6519035B BC34F4DE F99C0238
+        61B46FC9 D6E6C907 7AD91D26 91F7F7EE 598CB0FA C186D91C
+        AEFE1309 85139270 B4130C93 BC437944 F4FD4452 E2D74DD3
+        64F2E21E 71F54BFF 5CAE82AB 9C9DF69E E86D2BC5 22363A0D
+        ABC52197 9B0DEADA 1DBF9A42 D5C4484E 0ABCD06B FA53DDEF
+        3C1B20EE 3FD59D7C 25E41D2B 669E1EF1 6E6F52C3 164DF4FB
+        7930E9E4 E58857B6 AC7D5F42 D69F6D18 7763CF1D 55034004
+        87F55BA5 7E31CC7A 7135C886 EFB4318A ED6A1E01 2D9E6832
+        A907600A 918130C4 6DC778F9 71AD0038 092999A3 33CB8B7A
+        1A1DB93D 7140003C 2A4ECEA9 F98D0ACC 0A8291CD CEC97DCF
+        8EC9B55A 7F88A46B 4DB5A851 F44182E1 C68A007E 5E655F6A
+        FFFFFFFF FFFFFFFF",
+    )
+    .unwrap();
+    static ref Q_FFDHE_4096: BigUint = from_rfc_hex(
+        "7FFFFFFF FFFFFFFF D6FC2A2C 515DA54D 57EE2B10 139E9E78
+        EC5CE2C1 E7169B4A D4F09B20 8A3219FD E649CEE7 124D9F7C
+        BE97F1B1 B1863AEC 7B40D901 576230BD 69EF8F6A EAFEB2B0
+        9219FA8F AF833768 42B1B2AA 9EF68D79 DAAB89AF 3FABE49A
+        CC278638 707345BB F15344ED 79F7F439 0EF8AC50 9B56F39A
+        98566527 A41D3CBD 5E0558C1 59927DB0 E88454A5 D96471FD
+        DCB56D5B B06BFA34 0EA7A151 EF1CA6FA 572B76F3 B1B95D8C
+        8583D3E4 770536B8 4F017E70 E6FBF176 601A0266 941A17B0
+        C8B97F4E 74C2C1FF C7278919 777940C1 E1FF1D8D A637D6B9
+        9DDAFE5E 17611002 E2C778C1 BE8B41D9 6379A513 60D977FD
+        4435A11C 308FE7EE 6F1AAD9D B28C81AD DE1A7A6F 7CCE011C
+        30DA37E4 EB736483 BD6C8E93 48FBFBF7 2CC6587D 60C36C8E
+        577F0984 C289C938 5A098649 DE21BCA2 7A7EA229 716BA6E9
+        B279710F 38FAA5FF AE574155 CE4EFB4F 743695E2 911B1D06
+        D5E290CB CD86F56D 0EDFCD21 6AE22427 055E6835 FD29EEF7
+        9E0D9077 1FEACEBE 12F20E95 B34F0F78 B737A961 8B26FA7D
+        BC9874F2 72C42BDB 563EAFA1 6B4FB68C 3BB1E78E AA81A002
+        43FAADD2 BF18E63D 389AE443 77DA18C5 76B50F00 96CF3419
+        5483B005 48C09862 36E3BC7C B8D6801C 0494CCD1 99E5C5BD
+        0D0EDC9E B8A0001E 15276754 FCC68566 054148E6 E764BEE7
+        C764DAAD 3FC45235 A6DAD428 FA20C170 E345003F 2F32AFB5
+        7FFFFFFF FFFFFFFF",
+    )
+    .unwrap();
+    static ref REDUCE_FFDHE_4096: SpecialForm = SpecialForm::new(&PRIME_FFDHE_4096);
+    static ref PRIME_FFDHE_6144: BigUint = from_rfc_hex(
+        "FFFFFFFF FFFFFFFF ADF85458 A2BB4A9A AFDC5620 273D3CF1
+        D8B9C583 CE2D3695 A9E13641 146433FB CC939DCE 249B3EF9
+        7D2FE363 630C75D8 F681B202 AEC4617A D3DF1ED5 D5FD6561
+        2433F51F 5F066ED0 85636555 3DED1AF3 B557135E 7F57C935
+        984F0C70 E0E68B77 E2A689DA F3EFE872 1DF158A1 36ADE735
+        30ACCA4F 483A797A BC0AB182 B324FB61 D108A94B B2C8E3FB
+        B96ADAB7 60D7F468 1D4F42A3 DE394DF4 AE56EDE7 6372BB19
+        0B07A7C8 EE0A6D70 9E02FCE1 CDF7E2EC C03404CD 28342F61
+        9172FE9C E98583FF 8E4F1232 EEF28183 C3FE3B1B 4C6FAD73
+        3BB5FCBC 2EC22005 C58EF183 7D1683B2 C6F34A26 C1B2EFFA
+        886B4238 611FCFDC DE355B3B 6519035B BC34F4DE F99C0238
+        61B46FC9 D6E6C907 7AD91D26 91F7F7EE 598CB0FA C186D91C
+        AEFE1309 85139270 B4130C93 BC437944 F4FD4452 E2D74DD3
+        64F2E21E 71F54BFF 5CAE82AB 9C9DF69E E86D2BC5 22363A0D
+        ABC52197 9B0DEADA 1DBF9A42 D5C4484E 0ABCD06B FA53DDEF
+        3C1B20EE 3FD59D7C 25E41D2B 669E1EF1 6E6F52C3 164DF4FB
+        7930E9E4 E58857B6 AC7D5F42 D69F6D18 7763CF1D 55034004
+        87F55BA5 7E31CC7A 7135C886 EFB4318A ED6A1E01 2D9E6832
+        A907600A 918130C4 6DC778F9 71AD0038 092999A3 33CB8B7A
+        1A1DB93D 7140003C 2A4ECEA9 F98D0ACC 0A8291CD CEC97DCF
+        8EC9B55A 7F88A46B 4DB5A851 F44182E1 C68A007E 5E0DD902
+        0BFD64B6 45036C7A 4E677D2C 38532A3A 23BA4442 CAF53EA6
+        3BB45432 9B7624C8 917BDD64 B1C0FD4C B38E8C33 4C701C3A
+        CDAD0657 FCCFEC71 9B1F5C3E 4E46041F 388147FB 4CFDB477
+        A52471F7 A9A96910 B855322E DB6340D8 A00EF092 350511E3
+        0ABEC1FF F9E3A26E 7FB29F8C 183023C3 587E38DA 0077D9B4
+        763E4E4B 94B2BBC1 94C6651E 77CAF992 EEAAC023 2A281BF6
+        B3A739C1 22611682 0AE8DB58 47A67CBE F9C9091B 462D538C
+        D72B0374 6AE77F5E 62292C31 1562A846 505DC82D B854338A
+        E49F5235 C95B9117 8CCF2DD5 CACEF403 EC9D1810 C6272B04
+        5B3B71F9 DC6B80D6 3FDD4A8E 9ADB1E69 62A69526 D43161C1
+        A41D570D 7938DAD4 A40E329C D0E40E65 FFFFFFFF FFFFFFFF",
+    )
+    .unwrap();
+    static ref Q_FFDHE_6144: BigUint = from_rfc_hex(
+        "7FFFFFFF FFFFFFFF D6FC2A2C 515DA54D 57EE2B10 139E9E78
+        EC5CE2C1 E7169B4A D4F09B20 8A3219FD E649CEE7 124D9F7C
+        BE97F1B1 B1863AEC 7B40D901 576230BD 69EF8F6A EAFEB2B0
+        9219FA8F AF833768 42B1B2AA 9EF68D79 DAAB89AF 3FABE49A
+        CC278638 707345BB F15344ED 79F7F439 0EF8AC50 9B56F39A
+        98566527 A41D3CBD 5E0558C1 59927DB0 E88454A5 D96471FD
+        DCB56D5B B06BFA34 0EA7A151 EF1CA6FA 572B76F3 B1B95D8C
+        8583D3E4 770536B8 4F017E70 E6FBF176 601A0266 941A17B0
+        C8B97F4E 74C2C1FF C7278919 777940C1 E1FF1D8D A637D6B9
+        9DDAFE5E 17611002 E2C778C1 BE8B41D9 6379A513 60D977FD
+        4435A11C 308FE7EE 6F1AAD9D B28C81AD DE1A7A6F 7CCE011C
+        30DA37E4 EB736483 BD6C8E93 48FBFBF7 2CC6587D 60C36C8E
+        577F0984 C289C938 5A098649 DE21BCA2 7A7EA229 716BA6E9
+        B279710F 38FAA5FF AE574155 CE4EFB4F 743695E2 911B1D06
+        D5E290CB CD86F56D 0EDFCD21 6AE22427 055E6835 FD29EEF7
+        9E0D9077 1FEACEBE 12F20E95 B34F0F78 B737A961 8B26FA7D
+        BC9874F2 72C42BDB 563EAFA1 6B4FB68C 3BB1E78E AA81A002
+        43FAADD2 BF18E63D 389AE443 77DA18C5 76B50F00 96CF3419
+        5483B005 48C09862 36E3BC7C B8D6801C 0494CCD1 99E5C5BD
+        0D0EDC9E B8A0001E 15276754 FCC68566 054148E6 E764BEE7
+        C764DAAD 3FC45235 A6DAD428 FA20C170 E345003F 2F06EC81
+        05FEB25B 2281B63D 2733BE96 1C29951D 11DD2221 657A9F53
+        1DDA2A19 4DBB1264 48BDEEB2 58E07EA6 59C74619 A6380E1D
+        66D6832B FE67F638 CD8FAE1F 2723020F 9C40A3FD A67EDA3B
+        D29238FB D4D4B488 5C2A9917 6DB1A06C 50077849 1A8288F1
+        855F60FF FCF1D137 3FD94FC6 0C1811E1 AC3F1C6D 003BECDA
+        3B1F2725 CA595DE0 CA63328F 3BE57CC9 77556011 95140DFB
+        59D39CE0 91308B41 05746DAC 23D33E5F 7CE4848D A316A9C6
+        6B9581BA 3573BFAF 31149618 8AB15423 282EE416 DC2A19C5
+        724FA91A E4ADC88B C66796EA E5677A01 F64E8C08 63139582
+        2D9DB8FC EE35C06B 1FEEA547 4D6D8F34 B1534A93 6A18B0E0
+        D20EAB86 BC9C6D6A 5207194E 68720732 FFFFFFFF FFFFFFFF",
+    )
+    .unwrap();
+    static ref REDUCE_FFDHE_6144: SpecialForm = SpecialForm::new(&PRIME_FFDHE_6144);
+    static ref PRIME_FFDHE_8192: BigUint = from_rfc_hex(
+        "FFFFFFFF FFFFFFFF ADF85458 A2BB4A9A AFDC5620 273D3CF1
+        D8B9C583 CE2D3695 A9E13641 146433FB CC939DCE 249B3EF9
+        7D2FE363 630C75D8 F681B202 AEC4617A D3DF1ED5 D5FD6561
+        2433F51F 5F066ED0 85636555 3DED1AF3 B557135E 7F57C935
+        984F0C70 E0E68B77 E2A689DA F3EFE872 1DF158A1 36ADE735
+        30ACCA4F 483A797A BC0AB182 B324FB61 D108A94B B2C8E3FB
+        B96ADAB7 60D7F468 1D4F42A3 DE394DF4 AE56EDE7 6372BB19
+        0B07A7C8 EE0A6D70 9E02FCE1 CDF7E2EC C03404CD 28342F61
+        9172FE9C E98583FF 8E4F1232 EEF28183 C3FE3B1B 4C6FAD73
+        3BB5FCBC 2EC22005 C58EF183 7D1683B2 C6F34A26 C1B2EFFA
+        886B4238 611FCFDC DE355B3B 6519035B BC34F4DE F99C0238
+        61B46FC9 D6E6C907 7AD91D26 91F7F7EE 598CB0FA C186D91C
+        AEFE1309 85139270 B4130C93 BC437944 F4FD4452 E2D74DD3
+        64F2E21E 71F54BFF 5CAE82AB 9C9DF69E E86D2BC5 22363A0D
+        ABC52197 9B0DEADA 1DBF9A42 D5C4484E 0ABCD06B FA53DDEF
+        3C1B20EE 3FD59D7C 25E41D2B 669E1EF1 6E6F52C3 164DF4FB
+        7930E9E4 E58857B6 AC7D5F42 D69F6D18 7763CF1D 55034004
+        87F55BA5 7E31CC7A 7135C886 EFB4318A ED6A1E01 2D9E6832
+        A907600A 918130C4 6DC778F9 71AD0038 092999A3 33CB8B7A
+        1A1DB93D 7140003C 2A4ECEA9 F98D0ACC 0A8291CD CEC97DCF
+        8EC9B55A 7F88A46B 4DB5A851 F44182E1 C68A007E 5E0DD902
+        0BFD64B6 45036C7A 4E677D2C 38532A3A 23BA4442 CAF53EA6
+        3BB45432 9B7624C8 917BDD64 B1C0FD4C B38E8C33 4C701C3A
+        CDAD0657 FCCFEC71 9B1F5C3E 4E46041F 388147FB 4CFDB477
+        A52471F7 A9A96910 B855322E DB6340D8 A00EF092 350511E3
+        0ABEC1FF F9E3A26E 7FB29F8C 183023C3 587E38DA 0077D9B4
+        763E4E4B 94B2BBC1 94C6651E 77CAF992 EEAAC023 2A281BF6
+        B3A739C1 22611682 0AE8DB58 47A67CBE F9C9091B 462D538C
+        D72B0374 6AE77F5E 62292C31 1562A846 505DC82D B854338A
+        E49F5235 C95B9117 8CCF2DD5 CACEF403 EC9D1810 C6272B04
+        5B3B71F9 DC6B80D6 3FDD4A8E 9ADB1E69 62A69526 D43161C1
+        A41D570D 7938DAD4 A40E329C CFF46AAA 36AD004C F600C838
+        1E425A31 D951AE64 FDB23FCE C9509D43 687FEB69 EDD1CC5E
+        0B8CC3BD F64B10EF 86B63142 A3AB8829 555B2F74 7C932665
+        CB2C0F1C C01BD702 29388839 D2AF05E4 54504AC7 8B758282
+        2846C0BA 35C35F5C 59160CC0 46FD8251 541FC68C 9C86B022
+        BB709987 6A460E74 51A8A931 09703FEE 1C217E6C 3826E52C
+        51AA691E 0E423CFC 99E9E316 50C1217B 624816CD AD9A95F9
+        D5B80194 88D9C0A0 A1FE3075 A577E231 83F81D4A 3F2FA457
+        1EFC8CE0 BA8A4FE8 B6855DFE 72B0A66E DED2FBAB FBE58A30
+        FAFABE1C 5D71A87E 2F741EF8 C1FE86FE A6BBFDE5 30677F0D
+        97D11D49 F7A8443D 0822E506 A9F4614E 011E2A94 838FF88C
+        D68C8BB7 C5C6424C FFFFFFFF FFFFFFFF",
+    )
+    .unwrap();
+    static ref Q_FFDHE_8192: BigUint = from_rfc_hex(
+        "7FFFFFFF FFFFFFFF D6FC2A2C 515DA54D 57EE2B10 139E9E78
+        EC5CE2C1 E7169B4A D4F09B20 8A3219FD E649CEE7 124D9F7C
+        BE97F1B1 B1863AEC 7B40D901 576230BD 69EF8F6A EAFEB2B0
+        9219FA8F AF833768 42B1B2AA 9EF68D79 DAAB89AF 3FABE49A
+        CC278638 707345BB F15344ED 79F7F439 0EF8AC50 9B56F39A
+        98566527 A41D3CBD 5E0558C1 59927DB0 E88454A5 D96471FD
+        DCB56D5B B06BFA34 0EA7A151 EF1CA6FA 572B76F3 B1B95D8C
+        8583D3E4 770536B8 4F017E70 E6FBF176 601A0266 941A17B0
+        C8B97F4E 74C2C1FF C7278919 777940C1 E1FF1D8D A637D6B9
+        9DDAFE5E 17611002 E2C778C1 BE8B41D9 6379A513 60D977FD
+        4435A11C 308FE7EE 6F1AAD9D B28C81AD DE1A7A6F 7CCE011C
+        30DA37E4 EB736483 BD6C8E93 48FBFBF7 2CC6587D 60C36C8E
+        577F0984 C289C938 5A098649 DE21BCA2 7A7EA229 716BA6E9
+        B279710F 38FAA5FF AE574155 CE4EFB4F 743695E2 911B1D06
+        D5E290CB CD86F56D 0EDFCD21 6AE22427 055E6835 FD29EEF7
+        9E0D9077 1FEACEBE 12F20E95 B34F0F78 B737A961 8B26FA7D
+        BC9874F2 72C42BDB 563EAFA1 6B4FB68C 3BB1E78E AA81A002
+        43FAADD2 BF18E63D 389AE443 77DA18C5 76B50F00 96CF3419
+        5483B005 48C09862 36E3BC7C B8D6801C 0494CCD1 99E5C5BD
+        0D0EDC9E B8A0001E 15276754 FCC68566 054148E6 E764BEE7
+        C764DAAD 3FC45235 A6DAD428 FA20C170 E345003F 2F06EC81
+        05FEB25B 2281B63D 2733BE96 1C29951D 11DD2221 657A9F53
+        1DDA2A19 4DBB1264 48BDEEB2 58E07EA6 59C74619 A6380E1D
+        66D6832B FE67F638 CD8FAE1F 2723020F 9C40A3FD A67EDA3B
+        D29238FB D4D4B488 5C2A9917 6DB1A06C 50077849 1A8288F1
+        855F60FF FCF1D137 3FD94FC6 0C1811E1 AC3F1C6D 003BECDA
+        3B1F2725 CA595DE0 CA63328F 3BE57CC9 77556011 95140DFB
+        59D39CE0 91308B41 05746DAC 23D33E5F 7CE4848D A316A9C6
+        6B9581BA 3573BFAF 31149618 8AB15423 282EE416 DC2A19C5
+        724FA91A E4ADC88B C66796EA E5677A01 F64E8C08 63139582
+        2D9DB8FC EE35C06B 1FEEA547 4D6D8F34 B1534A93 6A18B0E0
+        D20EAB86 BC9C6D6A 5207194E 67FA3555 1B568026 7B00641C
+        0F212D18 ECA8D732 7ED91FE7 64A84EA1 B43FF5B4 F6E8E62F
+        05C661DE FB258877 C35B18A1 51D5C414 AAAD97BA 3E499332
+        E596078E 600DEB81 149C441C E95782F2 2A282563 C5BAC141
+        1423605D 1AE1AFAE 2C8B0660 237EC128 AA0FE346 4E435811
+        5DB84CC3 B523073A 28D45498 84B81FF7 0E10BF36 1C137296
+        28D5348F 07211E7E 4CF4F18B 286090BD B1240B66 D6CD4AFC
+        EADC00CA 446CE050 50FF183A D2BBF118 C1FC0EA5 1F97D22B
+        8F7E4670 5D4527F4 5B42AEFF 39585337 6F697DD5 FDF2C518
+        7D7D5F0E 2EB8D43F 17BA0F7C 60FF437F 535DFEF2 9833BF86
+        CBE88EA4 FBD4221E 84117283 54FA30A7 008F154A 41C7FC46
+        6B4645DB E2E32126 7FFFFFFF FFFFFFFF",
+    )
+    .unwrap();
+    static ref REDUCE_FFDHE_8192: SpecialForm = SpecialForm::new(&PRIME_FFDHE_8192);
+    static ref PRIME_GROUP_22: BigUint = from_rfc_hex(
+        "B10B8F96 A080E01D DE92DE5E AE5D54EC 52C99FBC FB06A3C6
+        9A6A9DCA 52D23B61 6073E286 75A23D18 9838EF1E 2EE652C0
+        13ECB4AE A9061123 24975C3C D49B83BF ACCBDD7D 90C4BD70
+        98488E9C 219A7372 4EFFD6FA E5644738 FAA31A4F F55BCCC0
+        A151AF5F 0DC8B4BD 45BF37DF 365C1A65 E68CFDA7 6D4DA708
+        DF1FB2BC 2E4A4371",
+    )
+    .unwrap();
+    static ref GENERATOR_GROUP_22: BigUint = from_rfc_hex(
+        "A4D1CBD5 C3FD3412 6765A442 EFB99905 F8104DD2 58AC507F
+        D6406CFF 14266D31 266FEA1E 5C41564B 777E690F 5504F213
+        160217B4 B01B886A 5E91547F 9E2749F4 D7FBD7D3 B9A92EE1
+        909D0D22 63F80A76 A6A24C08 7A091F53 1DBF0A01 69B6A28A
+        D662A4D1 8E73AFA3 2D779D59 18D08BC8 858F4DCE F97C2A24
+        855E6EEB 22B3B2E5",
+    )
+    .unwrap();
+    static ref Q_GROUP_22: BigUint =
+        from_rfc_hex("F518AA87 81A8DF27 8ABA4E7D 64B7CB9D 49462353",).unwrap();
+    static ref PRIME_GROUP_23: BigUint = from_rfc_hex(
+        "AD107E1E 9123A9D0 D660FAA7 9559C51F A20D64E5 683B9FD1
+        B54B1597 B61D0A75 E6FA141D F95A56DB AF9A3C40 7BA1DF15
+        EB3D688A 309C180E 1DE6B85A 1274A0A6 6D3F8152 AD6AC212
+        9037C9ED EFDA4DF8 D91E8FEF 55B7394B 7AD5B7D0 B6C12207
+        C9F98D11 ED34DBF6 C6BA0B2C 8BBC27BE 6A00E0A0 B9C49708
+        B3BF8A31 70918836 81286130 BC8985DB 1602E714 415D9330
+        278273C7 DE31EFDC 7310F712 1FD5A074 15987D9A DC0A486D
+        CDF93ACC 44328387 315D75E1 98C641A4 80CD86A1 B9E587E8
+        BE60E69C C928B2B9 C52172E4 13042E9B 23F10B0E 16E79763
+        C9B53DCF 4BA80A29 E3FB73C1 6B8E75B9 7EF363E2 FFA31F71
+        CF9DE538 4E71B81C 0AC4DFFE 0C10E64F",
+    )
+    .unwrap();
+    static ref GENERATOR_GROUP_23: BigUint = from_rfc_hex(
+        "AC4032EF 4F2D9AE3 9DF30B5C 8FFDAC50 6CDEBE7B 89998CAF
+        74866A08 CFE4FFE3 A6824A4E 10B9A6F0 DD921F01 A70C4AFA
+        AB739D77 00C29F52 C57DB17C 620A8652 BE5E9001 A8D66AD7
+        C1766910 1999024A F4D02727 5AC1348B B8A762D0 521BC98A
+        E2471504 22EA1ED4 09939D54 DA7460CD B5F6C6B2 50717CBE
+        F180EB34 118E98D1 19529A45 D6F83456 6E3025E3 16A330EF
+        BB77A86F 0C1AB15B 051AE3D4 28C8F8AC B70A8137 150B8EEB
+        10E183ED D19963DD D9E263E4 770589EF 6AA21E7F 5F2FF381
+        B539CCE3 409D13CD 566AFBB4 8D6C0191 81E1BCFE 94B30269
+        EDFE72FE 9B6AA4BD 7B5A0F1C 71CFFF4C 19C418E1 F6EC0179
+        81BC087F 2A7065B3 84B890D3 191F2BFA",
+    )
+    .unwrap();
+    static ref Q_GROUP_23: BigUint = from_rfc_hex(
+        "801C0D34 C58D93FE 99717710 1F80535A 4738CEBC BF389A99
+        B36371EB",
+    )
+    .unwrap();
+    static ref PRIME_GROUP_24: BigUint = from_rfc_hex(
+        "87A8E61D B4B6663C FFBBD19C 65195999 8CEEF608 660DD0F2
+        5D2CEED4 435E3B00 E00DF8F1 D61957D4 FAF7DF45 61B2AA30
+        16C3D911 34096FAA 3BF4296D 830E9A7C 209E0C64 97517ABD
+        5A8A9D30 6BCF67ED 91F9E672 5B4758C0 22E0B1EF 4275BF7B
+        6C5BFC11 D45F9088 B941F54E B1E59BB8 BC39A0BF 12307F5C
+        4FDB70C5 81B23F76 B63ACAE1 CAA6B790 2D525267 35488A0E
+        F13C6D9A 51BFA4AB 3AD83477 96524D8E F6A167B5 A41825D9
+        67E144E5 14056425 1CCACB83 E6B486F6 B3CA3F79 71506026
+        C0B857F6 89962856 DED4010A BD0BE621 C3A3960A 54E710C3
+        75F26375 D7014103 A4B54330 C198AF12 6116D227 6E11715F
+        693877FA D7EF09CA DB094AE9 1E1A1597",
+    )
+    .unwrap();
+    static ref GENERATOR_GROUP_24: BigUint = from_rfc_hex(
+        "3FB32C9B 73134D0B 2E775066 60EDBD48 4CA7B18F 21EF2054
+        07F4793A 1A0BA125 10DBC150 77BE463F FF4FED4A AC0BB555
+        BE3A6C1B 0C6B47B1 BC3773BF 7E8C6F62 901228F8 C28CBB18
+        A55AE313 41000A65 0196F931 C77A57F2 DDF463E5 E9EC144B
+        777DE62A AAB8A862 8AC376D2 82D6ED38 64E67982 428EBC83
+        1D14348F 6F2F9193 B5045AF2 767164E1 DFC967C1 FB3F2E55
+        A4BD1BFF E83B9C80 D052B985 D182EA0A DB2A3B73 13D3FE14
+        C8484B1E 052588B9 B7D2BBD2 DF016199 ECD06E15 57CD0915
+        B3353BBB 64E0EC37 7FD02837 0DF92B52 C7891428 CDC67EB6
+        184B523D 1DB246C3 2F630784 90F00EF8 D647D148 D4795451
+        5E2327CF EF98C582 664B4C0F 6CC41659",
+    )
+    .unwrap();
+    static ref Q_GROUP_24: BigUint = from_rfc_hex(
+        "8CF83642 A709A097 B4479976 40129DA2 99B1A47D 1EB3750B
+        A308B0FE 64F5FBD3",
+    )
+    .unwrap();
+}
+
+#[cfg(feature = "legacy-groups")]
+lazy_static! {
+    static ref PRIME_GROUP_1: BigUint = from_rfc_hex(
+        "FFFFFFFF FFFFFFFF C90FDAA2 2168C234 C4C6628B 80DC1CD1
+        29024E08 8A67CC74 020BBEA6 3B139B22 514A0879 8E3404DD
+        EF9519B3 CD3A431B 302B0A6D F25F1437 4FE1356D 6D51C245
+        E485B576 625E7EC6 F44C42E9 A63A3620 FFFFFFFF FFFFFFFF",
+    )
+    .unwrap();
+    static ref Q_GROUP_1: BigUint = from_rfc_hex(
+        "7FFFFFFF FFFFFFFF E487ED51 10B4611A 62633145 C06E0E68
+        94812704 4533E63A 0105DF53 1D89CD91 28A5043C C71A026E
+        F7CA8CD9 E69D218D 98158536 F92F8A1B A7F09AB6 B6A8E122
+        F242DABB 312F3F63 7A262174 D31D1B10 7FFFFFFF FFFFFFFF",
+    )
+    .unwrap();
+    static ref REDUCE_GROUP_1: SpecialForm = SpecialForm::new(&PRIME_GROUP_1);
+    static ref PRIME_GROUP_2: BigUint = from_rfc_hex(
+        "FFFFFFFF FFFFFFFF C90FDAA2 2168C234 C4C6628B 80DC1CD1
+        29024E08 8A67CC74 020BBEA6 3B139B22 514A0879 8E3404DD
+        EF9519B3 CD3A431B 302B0A6D F25F1437 4FE1356D 6D51C245
+        E485B576 625E7EC6 F44C42E9 A637ED6B 0BFF5CB6 F406B7ED
+        EE386BFB 5A899FA5 AE9F2411 7C4B1FE6 49286651 ECE65381
+        FFFFFFFF FFFFFFFF",
+    )
+    .unwrap();
+    static ref Q_GROUP_2: BigUint = from_rfc_hex(
+        "7FFFFFFF FFFFFFFF E487ED51 10B4611A 62633145 C06E0E68
+        94812704 4533E63A 0105DF53 1D89CD91 28A5043C C71A026E
+        F7CA8CD9 E69D218D 98158536 F92F8A1B A7F09AB6 B6A8E122
+        F242DABB 312F3F63 7A262174 D31BF6B5 85FFAE5B 7A035BF6
+        F71C35FD AD44CFD2 D74F9208 BE258FF3 24943328 F67329C0
+        FFFFFFFF FFFFFFFF",
+    )
+    .unwrap();
+    static ref REDUCE_GROUP_2: SpecialForm = SpecialForm::new(&PRIME_GROUP_2);
+}
+
+/// The Sophie Germain prime q of [MODPGroup5], in the hexadecimal layout of RFC 3526.
+pub(crate) const Q_HEX_GROUP_5: &str = "7FFFFFFF FFFFFFFF E487ED51 10B4611A 62633145 C06E0E68
+    94812704 4533E63A 0105DF53 1D89CD91 28A5043C C71A026E
+    F7CA8CD9 E69D218D 98158536 F92F8A1B A7F09AB6 B6A8E122
+    F242DABB 312F3F63 7A262174 D31BF6B5 85FFAE5B 7A035BF6
+    F71C35FD AD44CFD2 D74F9208 BE258FF3 24943328 F6722D9E
+    E1003E5C 50B1DF82 CC6D241B 0E2AE9CD 348B1FD4 7E9267AF
+    C1B2AE91 EE51D6CB 0E3179AB 1042A95D CF6A9483 B84B4B36
+    B3861AA7 255E4C02 78BA3604 6511B993 FFFFFFFF FFFFFFFF";
+
+/// The Sophie Germain prime q of [MODPGroup14], in the hexadecimal layout of RFC 3526.
+pub(crate) const Q_HEX_GROUP_14: &str = "7FFFFFFF FFFFFFFF E487ED51 10B4611A 62633145 C06E0E68
+    94812704 4533E63A 0105DF53 1D89CD91 28A5043C C71A026E
+    F7CA8CD9 E69D218D 98158536 F92F8A1B A7F09AB6 B6A8E122
+    F242DABB 312F3F63 7A262174 D31BF6B5 85FFAE5B 7A035BF6
+    F71C35FD AD44CFD2 D74F9208 BE258FF3 24943328 F6722D9E
+    E1003E5C 50B1DF82 CC6D241B 0E2AE9CD 348B1FD4 7E9267AF
+    C1B2AE91 EE51D6CB 0E3179AB 1042A95D CF6A9483 B84B4B36
+    B3861AA7 255E4C02 78BA3604 650C10BE 19482F23 171B671D
+    F1CF3B96 0C074301 CD93C1D1 7603D147 DAE2AEF8 37A62964
+    EF15E5FB 4AAC0B8C 1CCAA4BE 754AB572 8AE9130C 4C7D0288
+    0AB9472D 45565534 7FFFFFFF FFFFFFFF";
+
+/// The Sophie Germain prime q of [MODPGroup15], in the hexadecimal layout of RFC 3526.
+pub(crate) const Q_HEX_GROUP_15: &str = "7FFFFFFF FFFFFFFF E487ED51 10B4611A 62633145 C06E0E68
+    94812704 4533E63A 0105DF53 1D89CD91 28A5043C C71A026E
+    F7CA8CD9 E69D218D 98158536 F92F8A1B A7F09AB6 B6A8E122
+    F242DABB 312F3F63 7A262174 D31BF6B5 85FFAE5B 7A035BF6
+    F71C35FD AD44CFD2 D74F9208 BE258FF3 24943328 F6722D9E
+    E1003E5C 50B1DF82 CC6D241B 0E2AE9CD 348B1FD4 7E9267AF
+    C1B2AE91 EE51D6CB 0E3179AB 1042A95D CF6A9483 B84B4B36
+    B3861AA7 255E4C02 78BA3604 650C10BE 19482F23 171B671D
+    F1CF3B96 0C074301 CD93C1D1 7603D147 DAE2AEF8 37A62964
+    EF15E5FB 4AAC0B8C 1CCAA4BE 754AB572 8AE9130C 4C7D0288
+    0AB9472D 45556216 D6998B86 82283D19 D42A90D5 EF8E5D32
+    767DC282 2C6DF785 457538AB AE83063E D9CB87C2 D370F263
+    D5FAD746 6D8499EB 8F464A70 2512B0CE E771E913 0D697735
+    F897FD03 6CC50432 6C3B0139 9F643532 290F958C 0BBD9006
+    5DF08BAB BD30AEB6 3B84C460 5D6CA371 047127D0 3A72D598
+    A1EDADFE 707E8847 25C16890 549D6965 7FFFFFFF FFFFFFFF";
+
+/// The Sophie Germain prime q of [MODPGroup16], in the hexadecimal layout of RFC 3526.
+pub(crate) const Q_HEX_GROUP_16: &str = "7FFFFFFF FFFFFFFF E487ED51 10B4611A 62633145 C06E0E68
+    94812704 4533E63A 0105DF53 1D89CD91 28A5043C C71A026E
+    F7CA8CD9 E69D218D 98158536 F92F8A1B A7F09AB6 B6A8E122
+    F242DABB 312F3F63 7A262174 D31BF6B5 85FFAE5B 7A035BF6
+    F71C35FD AD44CFD2 D74F9208 BE258FF3 24943328 F6722D9E
+    E1003E5C 50B1DF82 CC6D241B 0E2AE9CD 348B1FD4 7E9267AF
+    C1B2AE91 EE51D6CB 0E3179AB 1042A95D CF6A9483 B84B4B36
+    B3861AA7 255E4C02 78BA3604 650C10BE 19482F23 171B671D
+    F1CF3B96 0C074301 CD93C1D1 7603D147 DAE2AEF8 37A62964
+    EF15E5FB 4AAC0B8C 1CCAA4BE 754AB572 8AE9130C 4C7D0288
+    0AB9472D 45556216 D6998B86 82283D19 D42A90D5 EF8E5D32
+    767DC282 2C6DF785 457538AB AE83063E D9CB87C2 D370F263
+    D5FAD746 6D8499EB 8F464A70 2512B0CE E771E913 0D697735
+    F897FD03 6CC50432 6C3B0139 9F643532 290F958C 0BBD9006
+    5DF08BAB BD30AEB6 3B84C460 5D6CA371 047127D0 3A72D598
+    A1EDADFE 707E8847 25C16890 54908400 8D391E09 53C3F36B
+    C438CD08 5EDD2D93 4CE1938C 357A711E 0D4A341A 5B0A85ED
+    12C1F4E5 156A2674 6DDDE16D 826F477C 97477E0A 0FDF6553
+    143E2CA3 A735E02E CCD94B27 D04861D1 119DD0C3 28ADF3F6
+    8FB094B8 67716BD7 DC0DEEBB 10B8240E 68034893 EAD82D54
+    C9DA754C 46C7EEE0 C37FDBEE 48536047 A6FA1AE4 9A0318CC
+    FFFFFFFF FFFFFFFF";
+
+/// The Sophie Germain prime q of [MODPGroup17], in the hexadecimal layout of RFC 3526.
+pub(crate) const Q_HEX_GROUP_17: &str = "7FFFFFFF FFFFFFFF E487ED51 10B4611A 62633145 C06E0E68
+    94812704 4533E63A 0105DF53 1D89CD91 28A5043C C71A026E
+    F7CA8CD9 E69D218D 98158536 F92F8A1B A7F09AB6 B6A8E122
+    F242DABB 312F3F63 7A262174 D31BF6B5 85FFAE5B 7A035BF6
+    F71C35FD AD44CFD2 D74F9208 BE258FF3 24943328 F6722D9E
+    E1003E5C 50B1DF82 CC6D241B 0E2AE9CD 348B1FD4 7E9267AF
+    C1B2AE91 EE51D6CB 0E3179AB 1042A95D CF6A9483 B84B4B36
+    B3861AA7 255E4C02 78BA3604 650C10BE 19482F23 171B671D
+    F1CF3B96 0C074301 CD93C1D1 7603D147 DAE2AEF8 37A62964
+    EF15E5FB 4AAC0B8C 1CCAA4BE 754AB572 8AE9130C 4C7D0288
+    0AB9472D 45556216 D6998B86 82283D19 D42A90D5 EF8E5D32
+    767DC282 2C6DF785 457538AB AE83063E D9CB87C2 D370F263
+    D5FAD746 6D8499EB 8F464A70 2512B0CE E771E913 0D697735
+    F897FD03 6CC50432 6C3B0139 9F643532 290F958C 0BBD9006
+    5DF08BAB BD30AEB6 3B84C460 5D6CA371 047127D0 3A72D598
+    A1EDADFE 707E8847 25C16890 54908400 8D391E09 53C3F36B
+    C438CD08 5EDD2D93 4CE1938C 357A711E 0D4A341A 5B0A85ED
+    12C1F4E5 156A2674 6DDDE16D 826F477C 97477E0A 0FDF6553
+    143E2CA3 A735E02E CCD94B27 D04861D1 119DD0C3 28ADF3F6
+    8FB094B8 67716BD7 DC0DEEBB 10B8240E 68034893 EAD82D54
+    C9DA754C 46C7EEE0 C37FDBEE 48536047 A6FA1AE4 9A014249
+    1B61FD5A 693E3813 60EA6E59 3013236F 64BA8F3B 1EDD1BDE
+    FC7FCA03 56CF2987 72ED9C17 A09800D7 583529F6 C813EC18
+    8BCB93D8 432D448C 6D1F6DF5 E7CD8A76 A267365D 676A5D8D
+    EDBF8A23 F36612A5 999028A8 95EBD7A1 37DC7A00 9BC6695F
+    ACC1E500 E325C976 7819750A E8B90E81 FA416BE7 373A7F7B
+    6AAF3817 A34C0641 5AD42018 C8058E4F 2CF3E4BF DF63F479
+    91D4BD3F 1B66445F 078EA2DB FFAC2D62 A5EA03D9 15A0AA55
+    6647B6BF 5FA470EC 0A662F69 07C01BF0 53CB8AF7 794DF194
+    0350EAC5 DBE2ED3B 7AA8551E C50FDFF8 758CE658 D189EAAE
+    6D2B64F6 17794B19 1C3FF46B B71E0234 021F47B3 1FA43077
+    095F96AD 85BA3A6B 734A7C8F 36E62012 7FFFFFFF FFFFFFFF";
+
+/// The Sophie Germain prime q of [MODPGroup18], in the hexadecimal layout of RFC 3526.
+pub(crate) const Q_HEX_GROUP_18: &str = "7FFFFFFF FFFFFFFF E487ED51 10B4611A 62633145 C06E0E68
+    94812704 4533E63A 0105DF53 1D89CD91 28A5043C C71A026E
+    F7CA8CD9 E69D218D 98158536 F92F8A1B A7F09AB6 B6A8E122
+    F242DABB 312F3F63 7A262174 D31BF6B5 85FFAE5B 7A035BF6
+    F71C35FD AD44CFD2 D74F9208 BE258FF3 24943328 F6722D9E
+    E1003E5C 50B1DF82 CC6D241B 0E2AE9CD 348B1FD4 7E9267AF
+    C1B2AE91 EE51D6CB 0E3179AB 1042A95D CF6A9483 B84B4B36
+    B3861AA7 255E4C02 78BA3604 650C10BE 19482F23 171B671D
+    F1CF3B96 0C074301 CD93C1D1 7603D147 DAE2AEF8 37A62964
+    EF15E5FB 4AAC0B8C 1CCAA4BE 754AB572 8AE9130C 4C7D0288
+    0AB9472D 45556216 D6998B86 82283D19 D42A90D5 EF8E5D32
+    767DC282 2C6DF785 457538AB AE83063E D9CB87C2 D370F263
+    D5FAD746 6D8499EB 8F464A70 2512B0CE E771E913 0D697735
+    F897FD03 6CC50432 6C3B0139 9F643532 290F958C 0BBD9006
+    5DF08BAB BD30AEB6 3B84C460 5D6CA371 047127D0 3A72D598
+    A1EDADFE 707E8847 25C16890 54908400 8D391E09 53C3F36B
+    C438CD08 5EDD2D93 4CE1938C 357A711E 0D4A341A 5B0A85ED
+    12C1F4E5 156A2674 6DDDE16D 826F477C 97477E0A 0FDF6553
+    143E2CA3 A735E02E CCD94B27 D04861D1 119DD0C3 28ADF3F6
+    8FB094B8 67716BD7 DC0DEEBB 10B8240E 68034893 EAD82D54
+    C9DA754C 46C7EEE0 C37FDBEE 48536047 A6FA1AE4 9A014249
+    1B61FD5A 693E3813 60EA6E59 3013236F 64BA8F3B 1EDD1BDE
+    FC7FCA03 56CF2987 72ED9C17 A09800D7 583529F6 C813EC18
+    8BCB93D8 432D448C 6D1F6DF5 E7CD8A76 A267365D 676A5D8D
+    EDBF8A23 F36612A5 999028A8 95EBD7A1 37DC7A00 9BC6695F
+    ACC1E500 E325C976 7819750A E8B90E81 FA416BE7 373A7F7B
+    6AAF3817 A34C0641 5AD42018 C8058E4F 2CF3E4BF DF63F479
+    91D4BD3F 1B66445F 078EA2DB FFAC2D62 A5EA03D9 15A0AA55
+    6647B6BF 5FA470EC 0A662F69 07C01BF0 53CB8AF7 794DF194
+    0350EAC5 DBE2ED3B 7AA8551E C50FDFF8 758CE658 D189EAAE
+    6D2B64F6 17794B19 1C3FF46B B71E0234 021F47B3 1FA43077
+    095F96AD 85BA3A6B 734A7C8F 36DF08AC BA51C937 897F72F2
+    1C3BBE5B 54996FC6 6C5F6268 39DC98DD 1DE4195B 46CEE980
+    3A0FD3DF C57E23F6 92BB7B49 B5D21233 1D55B1CE 2D727AB4
+    1A11DA3A 15F8E4BC 11C78B65 F1CEB296 F1FEDC5F 7E42456C
+    91111702 5201BE03 89F5ABD4 0D11F863 9A39FE32 36751835
+    A5E5E443 17C1C2EE FD4EA5BF D16043F4 3CB41981 F6ADEE9D
+    03159E7A D9D13C53 369509FC 1FA27C16 EF988770 3A55B51B
+    22CBF44C D012AEE0 B2798E62 8423428E FCD5A40C AEF6BF50
+    D8EA885E BF73A6B9 FD79B5E1 8F67D134 1AC8237A 75C3CFC9
+    2004A1C5 A40E366B C44D0017 6AF71C15 E48C86D3 7E013723
+    CAAC7223 AB3BF4D5 4F182871 3B2B4A6F E40FAB74 405CB738
+    B064C06E CC76E9EF FFFFFFFF FFFFFFFF";
+
+#[cfg(test)]
+mod test {
+    use num_bigint::BigUint;
+
+    use super::*;
+
+    fn test_order<G: MODPGroup>() {
+        let lhs = G::sophie_garmain_prime();
+        let rhs = (G::prime_modulus() - BigUint::from(1u32)) / BigUint::from(2u32);
+        assert_eq!(lhs, rhs);
+    }
+
+    #[test]
+    fn test_to_text() {
+        // dump of group 14 by `openssl dhparam -text`, with generator 4 to prevent OpenSSL
+        // from printing the group name instead of the parameters
+        let expected = include_str!("../tests/fixtures/group14_dhparam.txt")
+            .replace("G:    4 (0x4)", "G:    2 (0x2)");
+        assert_eq!(MODPGroup14::to_text(), expected);
+    }
+
+    fn test_constants<G: MODPGroup>() {
+        let p = G::prime_modulus();
+        assert_eq!(G::p_minus_one(), &p - 1u64);
+        assert_eq!(
+            G::mul(&G::generator(), &G::generator_inverse()),
+            BigUint::from(1u64)
+        );
+    }
+
+    #[test]
+    fn test_generator_inverse() {
+        test_constants::<MODPGroup5>();
+        test_constants::<MODPGroup14>();
+        test_constants::<MODPGroup15>();
+        test_constants::<MODPGroup16>();
+        test_constants::<MODPGroup17>();
+        test_constants::<MODPGroup18>();
+        test_constants::<FFDHEGroup2048>();
+        test_constants::<FFDHEGroup3072>();
+        test_constants::<FFDHEGroup4096>();
+        test_constants::<FFDHEGroup6144>();
+        test_constants::<FFDHEGroup8192>();
+        test_constants::<MODPGroup22>();
+        test_constants::<MODPGroup23>();
+        test_constants::<MODPGroup24>();
+        #[cfg(feature = "legacy-groups")]
+        test_constants::<MODPGroup1>();
+        #[cfg(feature = "legacy-groups")]
+        test_constants::<MODPGroup2>();
+    }
+
+    #[test]
+    fn test_constants_initialized_once() {
+        let barrier = std::sync::Barrier::new(8);
+        std::thread::scope(|scope| {
+            for _ in 0..8 {
+                scope.spawn(|| {
+                    barrier.wait();
+                    test_constants::<MODPGroup5>();
+                    test_constants::<MODPGroup14>();
+                    test_constants::<MODPGroup15>();
+                    test_constants::<MODPGroup16>();
+                    test_constants::<MODPGroup17>();
+                    test_constants::<MODPGroup18>();
+                    test_constants::<FFDHEGroup2048>();
+                    test_constants::<FFDHEGroup3072>();
+                    test_constants::<FFDHEGroup4096>();
+                    test_constants::<FFDHEGroup6144>();
+                    test_constants::<FFDHEGroup8192>();
+                    test_constants::<MODPGroup22>();
+                    test_constants::<MODPGroup23>();
+                    test_constants::<MODPGroup24>();
+                    #[cfg(feature = "legacy-groups")]
+                    test_constants::<MODPGroup1>();
+                    #[cfg(feature = "legacy-groups")]
+                    test_constants::<MODPGroup2>();
+                });
+            }
+        });
+        // once per group, whichever thread or test got there first
+        let initialized = CONSTANTS_INITIALIZED.lock().unwrap();
+        for group in AnyModpGroup::ALL {
+            let count = initialized.iter().filter(|&&name| name == group.name());
+            assert_eq!(count.count(), 1, "{}", group.name());
+        }
+    }
+
+    /// A group defined outside this crate, implementing the required methods only.
+    #[derive(Debug)]
+    struct Custom;
+
+    impl MODPGroup for Custom {
+        fn prime_modulus() -> BigUint {
+            BigUint::from(23u64)
+        }
+
+        fn generator() -> BigUint {
+            BigUint::from(4u64)
+        }
+
+        fn pow(a: &BigUint, e: &BigUint) -> BigUint {
+            a.modpow(e, &Self::prime_modulus())
+        }
+    }
+
+    #[test]
+    fn test_default_methods() {
+        assert_eq!(Custom::group_id(), 0);
+        assert!(Custom::name().ends_with("Custom"));
+        assert_eq!(Custom::subgroup_order(), BigUint::from(11u64));
+
+        let barrier = std::sync::Barrier::new(8);
+        std::thread::scope(|scope| {
+            for _ in 0..8 {
+                scope.spawn(|| {
+                    barrier.wait();
+                    test_constants::<Custom>();
+                    assert!(std::ptr::eq(Custom::constants(), Custom::constants()));
+                });
+            }
+        });
+        let initialized = CONSTANTS_INITIALIZED.lock().unwrap();
+        let count = initialized.iter().filter(|&&name| name == Custom::name());
+        assert_eq!(count.count(), 1);
+    }
+
+    fn test_prime_order_subgroup<G: MODPGroup>(p_bits: u64, q_bits: u64) {
+        let p = G::prime_modulus();
+        let q = G::subgroup_order();
+        assert_eq!((p.bits(), q.bits()), (p_bits, q_bits));
+        assert_eq!((&p - 1u64) % &q, BigUint::from(0u64));
+        assert_eq!(G::pow(&G::generator(), &q), BigUint::from(1u64));
+        assert_ne!(G::generator(), BigUint::from(1u64));
+        assert_ne!(G::sophie_garmain_prime(), q);
+    }
+
+    #[test]
+    fn test_rfc5114_orders() {
+        test_prime_order_subgroup::<MODPGroup22>(1024, 160);
+        test_prime_order_subgroup::<MODPGroup23>(2048, 224);
+        test_prime_order_subgroup::<MODPGroup24>(2048, 256);
+    }
+
+    fn test_metadata<G: MODPGroup>(group_id: u16, name: &str) {
+        assert_eq!(G::group_id(), group_id);
+        assert_eq!(G::name(), name);
+        assert_eq!(G::bit_size() as u64, G::prime_modulus().bits());
+        let group = AnyModpGroup::of::<G>().unwrap();
+        assert_eq!(group.group_id(), group_id);
+        assert_eq!(group.name(), name);
+    }
+
+    #[test]
+    fn test_group_metadata() {
+        #[cfg(feature = "legacy-groups")]
+        test_metadata::<MODPGroup1>(1, "modp768");
+        #[cfg(feature = "legacy-groups")]
+        test_metadata::<MODPGroup2>(2, "modp1024");
+        test_metadata::<MODPGroup5>(5, "modp1536");
+        test_metadata::<MODPGroup14>(14, "modp2048");
+        test_metadata::<MODPGroup15>(15, "modp3072");
+        test_metadata::<MODPGroup16>(16, "modp4096");
+        test_metadata::<MODPGroup17>(17, "modp6144");
+        test_metadata::<MODPGroup18>(18, "modp8192");
+        test_metadata::<FFDHEGroup2048>(256, "ffdhe2048");
+        test_metadata::<FFDHEGroup3072>(257, "ffdhe3072");
+        test_metadata::<FFDHEGroup4096>(258, "ffdhe4096");
+        test_metadata::<FFDHEGroup6144>(259, "ffdhe6144");
+        test_metadata::<FFDHEGroup8192>(260, "ffdhe8192");
+        test_metadata::<MODPGroup22>(22, "modp1024s160");
+        test_metadata::<MODPGroup23>(23, "modp2048s224");
+        test_metadata::<MODPGroup24>(24, "modp2048s256");
+    }
+
+    #[test]
+    fn test_security_strength() {
+        #[cfg(feature = "legacy-groups")]
+        assert_eq!(
+            (
+                MODPGroup1::security_strength(),
+                MODPGroup2::security_strength()
+            ),
+            (60, 80)
+        );
+        let strengths = [
+            (MODPGroup5::security_strength(), 90),
+            (MODPGroup14::security_strength(), 112),
+            (MODPGroup15::security_strength(), 128),
+            (MODPGroup16::security_strength(), 152),
+            (MODPGroup17::security_strength(), 176),
+            (MODPGroup18::security_strength(), 200),
+            (FFDHEGroup2048::security_strength(), 112),
+            (FFDHEGroup3072::security_strength(), 128),
+            (FFDHEGroup4096::security_strength(), 152),
+            (FFDHEGroup6144::security_strength(), 176),
+            (FFDHEGroup8192::security_strength(), 200),
+            (MODPGroup22::security_strength(), 80),
+            (MODPGroup23::security_strength(), 112),
+            (MODPGroup24::security_strength(), 112),
+        ];
+        for (strength, expected) in strengths {
+            assert_eq!(strength, expected);
+        }
+        for group in AnyModpGroup::ALL {
+            assert!(group.security_strength() <= group.order().bits() as u32 / 2);
+        }
+
+        for (bits, expected) in [
+            (0, Some(AnyModpGroup::Modp1536)),
+            (90, Some(AnyModpGroup::Modp1536)),
+            (91, Some(AnyModpGroup::Modp2048)),
+            (112, Some(AnyModpGroup::Modp2048)),
+            (128, Some(AnyModpGroup::Modp3072)),
+            (129, Some(AnyModpGroup::Modp4096)),
+            (176, Some(AnyModpGroup::Modp6144)),
+            (200, Some(AnyModpGroup::Modp8192)),
+            (201, None),
+        ] {
+            assert_eq!(strongest_group_at_least(bits), expected);
+        }
+    }
+
+    #[test]
+    fn test_any_group_key_exchange() {
+        let rng = &mut rand::thread_rng();
+        let ids: &[u16] = &[5, 14, 15, 16, 17, 18, 22, 23, 24, 256, 257, 258, 259, 260];
+        #[cfg(feature = "legacy-groups")]
+        let ids = &[&[1u16, 2][..], ids].concat();
+        for &id in ids {
+            let group = AnyModpGroup::try_from(id).unwrap();
+            assert_eq!(group.group_id(), id);
+            assert_eq!(
+                group.is_safe_prime(),
+                group.order() == (group.prime_modulus() - 1u64) >> 1
+            );
+
+            let a = group.random_exponent(rng, Some(256));
+            let b = group.random_exponent(rng, Some(256));
+            let ya = group.from_bytes_be(&group.to_bytes_be(&group.element(&a)));
+            let yb = group.from_bytes_be(&group.to_bytes_be(&group.element(&b)));
+            let ya = group.validate_peer(ya.unwrap()).unwrap();
+            let yb = group.validate_peer(yb.unwrap()).unwrap();
+            let za = group.shared_secret(&a, &yb).unwrap();
+            let zb = group.shared_secret(&b, &ya).unwrap();
+            assert_eq!(za, zb);
+            let other = AnyModpGroup::try_from(if id == 14 { 15u16 } else { 14 }).unwrap();
+            assert_eq!(
+                other.shared_secret(&a, &yb),
+                Err(DhGroupsError::GroupMismatch)
+            );
+            assert_eq!(
+                group.pow(&group.generator(), &group.order()),
+                BigUint::from(1u64)
+            );
+        }
+        assert_eq!(AnyModpGroup::ALL.len(), ids.len());
+    }
+
+    #[test]
+    fn test_any_group_errors() {
+        for id in [0u16, 3, 19, 255, 261, u16::MAX] {
+            assert_eq!(
+                AnyModpGroup::try_from(id),
+                Err(DhGroupsError::UnknownGroup(id))
+            );
+        }
+        #[cfg(not(feature = "legacy-groups"))]
+        assert_eq!(
+            AnyModpGroup::try_from(1u16),
+            Err(DhGroupsError::UnknownGroup(1))
+        );
+
+        #[allow(deprecated)]
+        for id in ModpGroupId::ALL {
+            let group = AnyModpGroup::from(id);
+            assert_eq!(u32::from(group.group_id()), id.number());
+            assert!(AnyModpGroup::RFC3526.contains(&group));
+        }
+        #[allow(deprecated)]
+        let id = ModpGroupId::of::<MODPGroup14>();
+        assert_eq!(
+            id.map(AnyModpGroup::from),
+            AnyModpGroup::of::<MODPGroup14>()
+        );
+
+        let group = AnyModpGroup::Modp2048;
+        let p = group.prime_modulus();
+        for (value, expected) in [
+            (BigUint::from(1u64), ValidationError::Degenerate),
+            (&p - 2u64, ValidationError::NotInSubgroup),
+            (p.clone(), ValidationError::OutOfRange),
+        ] {
+            assert_eq!(group.validate_peer(value), Err(expected));
+        }
     }
 
     #[test]
@@ -533,5 +2601,14 @@ mod test {
         test_order::<MODPGroup16>();
         test_order::<MODPGroup17>();
         test_order::<MODPGroup18>();
+        test_order::<FFDHEGroup2048>();
+        test_order::<FFDHEGroup3072>();
+        test_order::<FFDHEGroup4096>();
+        test_order::<FFDHEGroup6144>();
+        test_order::<FFDHEGroup8192>();
+        #[cfg(feature = "legacy-groups")]
+        test_order::<MODPGroup1>();
+        #[cfg(feature = "legacy-groups")]
+        test_order::<MODPGroup2>();
     }
 }
@@ -0,0 +1,770 @@
+//! Implementations of the `Group` trait of [group](https://crates.io/crates/group) and the
+//! `PrimeField` trait of [ff](https://crates.io/crates/ff), so that the generic protocols of the
+//! RustCrypto ecosystem run over the MODP groups, enabled by the `group` feature.
+//!
+//! [QrElement] is an element of the subgroup of order q, the quadratic residues modulo p, written
+//! additively as `group` expects: addition is the multiplication modulo p, negation the inverse
+//! and the multiplication by a [ScalarField] the exponentiation. [ScalarField] is the field of
+//! the integers modulo q.
+//!
+//! # Limitations
+//!
+//! The traits need the modulus of the field at compile time, e.g. in `PrimeField::MODULUS`, and
+//! values that are `Copy`, while this crate computes with [BigUint]s of the size of the group:
+//! - They are implemented for the groups of RFC 3526 only, through [QrGroup], and not for the
+//!   groups chosen at runtime, e.g. `PrimeGroup`.
+//! - Values are stored in 8192-bit fixed-size arrays whatever the group, and the arithmetic
+//!   converts them to [BigUint], so it is neither fast nor constant time.
+//! - `PrimeField::MULTIPLICATIVE_GENERATOR` is `-2`, a quadratic non-residue modulo q. As q - 1
+//!   is not factored, it is not known to generate the whole multiplicative group. `S` is 1 for
+//!   all the groups, with `ROOT_OF_UNITY` equal to `-1`.
+//! - The representation of `PrimeField::Repr` is the little-endian encoding of the value in 1024
+//!   bytes, as `group::Wnaf` expects.
+//! - The encodings and curve traits of `group`, e.g. `GroupEncoding` or `PrimeCurve`, are not
+//!   implemented, so the test suites of `group::tests` do not apply.
+//!
+//! # Example
+//!
+//! ```rust
+//! use diffie_hellman_groups::{
+//!     group::MODPGroup5,
+//!     group_traits::{QrElement, ScalarField},
+//! };
+//! use ff::Field;
+//! use group::Group;
+//!
+//! // a key exchange written against the `group` traits
+//! fn exchange<G: Group>() -> bool {
+//!     let rng = &mut rand::thread_rng();
+//!     let (a, b) = (G::Scalar::random(&mut *rng), G::Scalar::random(&mut *rng));
+//!     let (public_a, public_b) = (G::generator() * a, G::generator() * b);
+//!     public_b * a == public_a * b
+//! }
+//!
+//! assert!(exchange::<QrElement<MODPGroup5>>());
+//! ```
+
+use std::{
+    fmt,
+    iter::{Product, Sum},
+    marker::PhantomData,
+    ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign},
+};
+
+use ff::{Field, PrimeField};
+use group::Group;
+use num_bigint::{BigUint, RandBigInt};
+use rand::RngCore;
+use subtle::{Choice, ConditionallySelectable, ConstantTimeEq, CtOption};
+
+use crate::{
+    group::{
+        MODPGroup, MODPGroup14, MODPGroup15, MODPGroup16, MODPGroup17, MODPGroup18, MODPGroup5,
+        Q_HEX_GROUP_14, Q_HEX_GROUP_15, Q_HEX_GROUP_16, Q_HEX_GROUP_17, Q_HEX_GROUP_18,
+        Q_HEX_GROUP_5,
+    },
+    scalar::ct_less_than,
+    Element, Scalar, ValidationError,
+};
+
+/// Number of 64-bit limbs of the values, enough for the largest group of RFC 3526.
+const LIMBS: usize = 128;
+
+/// Length of `0x` followed by the hexadecimal digits of [LIMBS] limbs.
+const HEX_LEN: usize = 2 + 16 * LIMBS;
+
+/// A MODP group whose order q of the subgroup is known at compile time, implemented for the
+/// groups of RFC 3526.
+pub trait QrGroup: MODPGroup + Copy + Eq + Send + Sync + 'static {
+    /// The order q of the subgroup, in little-endian 64-bit limbs.
+    const Q: [u64; LIMBS];
+
+    /// q as `0x` followed by its hexadecimal digits, right-aligned in a buffer padded with `0`.
+    #[doc(hidden)]
+    const Q_HEX: [u8; HEX_LEN] = hex_digits(&Self::Q);
+}
+
+macro_rules! impl_qr_group {
+    ($($group:ident => $q:ident),*) => {
+        $(
+            impl QrGroup for $group {
+                const Q: [u64; LIMBS] = parse_rfc_hex($q);
+            }
+        )*
+    };
+}
+
+impl_qr_group!(
+    MODPGroup5 => Q_HEX_GROUP_5,
+    MODPGroup14 => Q_HEX_GROUP_14,
+    MODPGroup15 => Q_HEX_GROUP_15,
+    MODPGroup16 => Q_HEX_GROUP_16,
+    MODPGroup17 => Q_HEX_GROUP_17,
+    MODPGroup18 => Q_HEX_GROUP_18
+);
+
+/// An integer modulo the order q of the subgroup, implementing `ff::PrimeField`.
+#[derive(Clone, Copy)]
+pub struct ScalarField<G: QrGroup> {
+    limbs: [u64; LIMBS],
+    phantom: PhantomData<G>,
+}
+
+/// The little-endian encoding of a [ScalarField] in 1024 bytes.
+#[derive(Clone, Copy)]
+pub struct ScalarRepr(pub [u8; 8 * LIMBS]);
+
+impl Default for ScalarRepr {
+    fn default() -> Self {
+        ScalarRepr([0; 8 * LIMBS])
+    }
+}
+
+impl AsRef<[u8]> for ScalarRepr {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl AsMut<[u8]> for ScalarRepr {
+    fn as_mut(&mut self) -> &mut [u8] {
+        &mut self.0
+    }
+}
+
+impl<G: QrGroup> ScalarField<G> {
+    const fn from_limbs(limbs: [u64; LIMBS]) -> Self {
+        ScalarField {
+            limbs,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Create a scalar from a BigUint, reduced modulo q.
+    pub fn from_biguint(value: BigUint) -> Self {
+        Self::from_limbs(to_limbs(&(value % G::sophie_garmain_prime())))
+    }
+
+    /// The value of the scalar, in `[0, q)`.
+    pub fn to_biguint(&self) -> BigUint {
+        from_limbs(&self.limbs)
+    }
+
+    fn pow_biguint(&self, exponent: &BigUint) -> Self {
+        Self::from_limbs(to_limbs(
+            &self
+                .to_biguint()
+                .modpow(exponent, &G::sophie_garmain_prime()),
+        ))
+    }
+}
+
+impl<G: QrGroup> From<Scalar<G>> for ScalarField<G> {
+    fn from(scalar: Scalar<G>) -> Self {
+        Self::from_limbs(to_limbs(&scalar.value))
+    }
+}
+
+impl<G: QrGroup> From<ScalarField<G>> for Scalar<G> {
+    fn from(scalar: ScalarField<G>) -> Self {
+        Scalar::from_biguint(scalar.to_biguint())
+    }
+}
+
+impl<G: QrGroup> From<u64> for ScalarField<G> {
+    fn from(value: u64) -> Self {
+        Self::from_biguint(BigUint::from(value))
+    }
+}
+
+impl<G: QrGroup> fmt::Debug for ScalarField<G> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "ScalarField(0x{:x})", self.to_biguint())
+    }
+}
+
+impl<G: QrGroup> Default for ScalarField<G> {
+    fn default() -> Self {
+        Self::ZERO
+    }
+}
+
+impl<G: QrGroup> ConstantTimeEq for ScalarField<G> {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.limbs.ct_eq(&other.limbs)
+    }
+}
+
+impl<G: QrGroup> ConditionallySelectable for ScalarField<G> {
+    fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
+        let mut limbs = [0; LIMBS];
+        for (limb, (a, b)) in limbs.iter_mut().zip(a.limbs.iter().zip(&b.limbs)) {
+            *limb = u64::conditional_select(a, b, choice);
+        }
+        Self::from_limbs(limbs)
+    }
+}
+
+impl<G: QrGroup> PartialEq for ScalarField<G> {
+    fn eq(&self, other: &Self) -> bool {
+        self.ct_eq(other).into()
+    }
+}
+
+impl<G: QrGroup> Eq for ScalarField<G> {}
+
+impl<G: QrGroup> Add<&ScalarField<G>> for ScalarField<G> {
+    type Output = Self;
+
+    fn add(self, rhs: &Self) -> Self::Output {
+        Self::from_biguint(self.to_biguint() + rhs.to_biguint())
+    }
+}
+
+impl<G: QrGroup> Sub<&ScalarField<G>> for ScalarField<G> {
+    type Output = Self;
+
+    fn sub(self, rhs: &Self) -> Self::Output {
+        Self::from_biguint(self.to_biguint() + G::sophie_garmain_prime() - rhs.to_biguint())
+    }
+}
+
+impl<G: QrGroup> Mul<&ScalarField<G>> for ScalarField<G> {
+    type Output = Self;
+
+    fn mul(self, rhs: &Self) -> Self::Output {
+        Self::from_biguint(self.to_biguint() * rhs.to_biguint())
+    }
+}
+
+impl<G: QrGroup> Neg for ScalarField<G> {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self::ZERO - self
+    }
+}
+
+/// Implement the operator on owned right-hand sides and the assigning operators, from the
+/// implementation on references.
+macro_rules! impl_owned_ops {
+    ($ty:ident, $rhs:ident, $($op:ident $method:ident $assign_op:ident $assign_method:ident),*) => {
+        $(
+            impl<G: QrGroup> $op<$rhs<G>> for $ty<G> {
+                type Output = Self;
+
+                fn $method(self, rhs: $rhs<G>) -> Self::Output {
+                    self.$method(&rhs)
+                }
+            }
+
+            impl<G: QrGroup> $assign_op<$rhs<G>> for $ty<G> {
+                fn $assign_method(&mut self, rhs: $rhs<G>) {
+                    *self = self.$method(&rhs);
+                }
+            }
+
+            impl<G: QrGroup> $assign_op<&$rhs<G>> for $ty<G> {
+                fn $assign_method(&mut self, rhs: &$rhs<G>) {
+                    *self = self.$method(rhs);
+                }
+            }
+        )*
+    };
+}
+
+impl_owned_ops!(
+    ScalarField, ScalarField,
+    Add add AddAssign add_assign,
+    Sub sub SubAssign sub_assign,
+    Mul mul MulAssign mul_assign
+);
+
+impl<G: QrGroup> Sum for ScalarField<G> {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::ZERO, |acc, x| acc + x)
+    }
+}
+
+impl<'a, G: QrGroup> Sum<&'a ScalarField<G>> for ScalarField<G> {
+    fn sum<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+        iter.fold(Self::ZERO, |acc, x| acc + x)
+    }
+}
+
+impl<G: QrGroup> Product for ScalarField<G> {
+    fn product<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::ONE, |acc, x| acc * x)
+    }
+}
+
+impl<'a, G: QrGroup> Product<&'a ScalarField<G>> for ScalarField<G> {
+    fn product<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+        iter.fold(Self::ONE, |acc, x| acc * x)
+    }
+}
+
+impl<G: QrGroup> Field for ScalarField<G> {
+    const ZERO: Self = Self::from_limbs([0; LIMBS]);
+    const ONE: Self = Self::from_limbs(small(1));
+
+    fn random(mut rng: impl RngCore) -> Self {
+        Self::from_limbs(to_limbs(&rng.gen_biguint_below(&G::sophie_garmain_prime())))
+    }
+
+    fn square(&self) -> Self {
+        *self * self
+    }
+
+    fn double(&self) -> Self {
+        *self + self
+    }
+
+    fn invert(&self) -> CtOption<Self> {
+        // q is prime, so x^(q - 2) is the inverse of a non-zero x
+        let inverse = self.pow_biguint(&(G::sophie_garmain_prime() - 2u64));
+        CtOption::new(inverse, !self.is_zero())
+    }
+
+    fn sqrt_ratio(num: &Self, div: &Self) -> (Choice, Self) {
+        ff::helpers::sqrt_ratio_generic(num, div)
+    }
+
+    fn sqrt(&self) -> CtOption<Self> {
+        // q = 3 mod 4, so x^((q + 1) / 4) is a square root of a quadratic residue x
+        let root = self.pow_biguint(&((G::sophie_garmain_prime() + 1u64) >> 2));
+        CtOption::new(root, root.square().ct_eq(self))
+    }
+}
+
+impl<G: QrGroup> PrimeField for ScalarField<G> {
+    type Repr = ScalarRepr;
+
+    fn from_repr(repr: Self::Repr) -> CtOption<Self> {
+        let mut limbs = [0; LIMBS];
+        for (limb, chunk) in limbs.iter_mut().zip(repr.0.chunks_exact(8)) {
+            *limb = u64::from_le_bytes(chunk.try_into().unwrap());
+        }
+        let is_some = ct_less_than(&limbs, &G::Q);
+        CtOption::new(Self::from_limbs(limbs), is_some)
+    }
+
+    fn to_repr(&self) -> Self::Repr {
+        let mut repr = ScalarRepr::default();
+        for (chunk, limb) in repr.0.chunks_exact_mut(8).zip(&self.limbs) {
+            chunk.copy_from_slice(&limb.to_le_bytes());
+        }
+        repr
+    }
+
+    fn is_odd(&self) -> Choice {
+        Choice::from((self.limbs[0] & 1) as u8)
+    }
+
+    const MODULUS: &'static str = hex_str(&G::Q_HEX);
+    const NUM_BITS: u32 = bits(&G::Q);
+    const CAPACITY: u32 = Self::NUM_BITS - 1;
+    const TWO_INV: Self = Self::from_limbs(add_small(shr1(G::Q), 1));
+    const MULTIPLICATIVE_GENERATOR: Self = Self::from_limbs(sub_small(G::Q, 2));
+    const S: u32 = 1;
+    const ROOT_OF_UNITY: Self = Self::from_limbs(sub_small(G::Q, 1));
+    const ROOT_OF_UNITY_INV: Self = Self::ROOT_OF_UNITY;
+    const DELTA: Self = Self::from_limbs(small(4));
+}
+
+/// An element of the subgroup of order q, implementing `group::Group` with the group operation
+/// written additively.
+#[derive(Clone, Copy)]
+pub struct QrElement<G: QrGroup> {
+    limbs: [u64; LIMBS],
+    phantom: PhantomData<G>,
+}
+
+impl<G: QrGroup> QrElement<G> {
+    fn from_biguint(value: &BigUint) -> Self {
+        QrElement {
+            limbs: to_limbs(value),
+            phantom: PhantomData,
+        }
+    }
+
+    /// The value of the element modulo p.
+    pub fn to_biguint(&self) -> BigUint {
+        from_limbs(&self.limbs)
+    }
+}
+
+impl<G: QrGroup> TryFrom<Element<G>> for QrElement<G> {
+    type Error = ValidationError;
+
+    /// Convert an element in the subgroup of order q, including 1, the identity.
+    ///
+    /// # Errors
+    /// Returns [ValidationError::OutOfRange] if the value is 0, or
+    /// [ValidationError::NotInSubgroup] if it is not in the subgroup of order q.
+    fn try_from(element: Element<G>) -> Result<Self, Self::Error> {
+        if element.value == BigUint::from(0u64) {
+            return Err(ValidationError::OutOfRange);
+        }
+        if element.pow(&G::sophie_garmain_prime()).value != BigUint::from(1u64) {
+            return Err(ValidationError::NotInSubgroup);
+        }
+        Ok(Self::from_biguint(&element.value))
+    }
+}
+
+impl<G: QrGroup> From<QrElement<G>> for Element<G> {
+    fn from(element: QrElement<G>) -> Self {
+        Element::from_value(element.to_biguint())
+    }
+}
+
+impl<G: QrGroup> fmt::Debug for QrElement<G> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "QrElement(0x{:x})", self.to_biguint())
+    }
+}
+
+impl<G: QrGroup> ConstantTimeEq for QrElement<G> {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.limbs.ct_eq(&other.limbs)
+    }
+}
+
+impl<G: QrGroup> PartialEq for QrElement<G> {
+    fn eq(&self, other: &Self) -> bool {
+        self.ct_eq(other).into()
+    }
+}
+
+impl<G: QrGroup> Eq for QrElement<G> {}
+
+impl<G: QrGroup> Add<&QrElement<G>> for QrElement<G> {
+    type Output = Self;
+
+    fn add(self, rhs: &Self) -> Self::Output {
+        Self::from_biguint(&G::mul(&self.to_biguint(), &rhs.to_biguint()))
+    }
+}
+
+impl<G: QrGroup> Sub<&QrElement<G>> for QrElement<G> {
+    type Output = Self;
+
+    fn sub(self, rhs: &Self) -> Self::Output {
+        self + -*rhs
+    }
+}
+
+impl<G: QrGroup> Mul<&ScalarField<G>> for QrElement<G> {
+    type Output = Self;
+
+    fn mul(self, rhs: &ScalarField<G>) -> Self::Output {
+        Self::from_biguint(&G::pow(&self.to_biguint(), &rhs.to_biguint()))
+    }
+}
+
+impl<G: QrGroup> Neg for QrElement<G> {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        // the element has order q, so x^(q - 1) is its inverse
+        Self::from_biguint(&G::pow(
+            &self.to_biguint(),
+            &(G::sophie_garmain_prime() - 1u64),
+        ))
+    }
+}
+
+impl_owned_ops!(
+    QrElement, QrElement,
+    Add add AddAssign add_assign,
+    Sub sub SubAssign sub_assign
+);
+
+impl_owned_ops!(QrElement, ScalarField, Mul mul MulAssign mul_assign);
+
+impl<G: QrGroup> Sum for QrElement<G> {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::identity(), |acc, x| acc + x)
+    }
+}
+
+impl<'a, G: QrGroup> Sum<&'a QrElement<G>> for QrElement<G> {
+    fn sum<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+        iter.fold(Self::identity(), |acc, x| acc + x)
+    }
+}
+
+impl<G: QrGroup> Group for QrElement<G> {
+    type Scalar = ScalarField<G>;
+
+    fn random(mut rng: impl RngCore) -> Self {
+        let exponent = rng.gen_biguint_range(&BigUint::from(1u64), &G::sophie_garmain_prime());
+        Self::from_biguint(&G::element(&exponent))
+    }
+
+    fn identity() -> Self {
+        Self::from_biguint(&BigUint::from(1u64))
+    }
+
+    /// The generator of the group, which has order q in the groups of RFC 3526.
+    fn generator() -> Self {
+        Self::from_biguint(&G::generator())
+    }
+
+    fn is_identity(&self) -> Choice {
+        self.limbs.ct_eq(&small(1))
+    }
+
+    fn double(&self) -> Self {
+        *self + self
+    }
+}
+
+/// Split a value less than 2^8192 into little-endian limbs.
+fn to_limbs(value: &BigUint) -> [u64; LIMBS] {
+    let mut limbs = [0; LIMBS];
+    for (limb, digit) in limbs.iter_mut().zip(value.iter_u64_digits()) {
+        *limb = digit;
+    }
+    limbs
+}
+
+fn from_limbs(limbs: &[u64; LIMBS]) -> BigUint {
+    let digits: Vec<u32> = limbs
+        .iter()
+        .flat_map(|limb| [*limb as u32, (limb >> 32) as u32])
+        .collect();
+    BigUint::new(digits)
+}
+
+/// Parse the hexadecimal layout of RFC 3526 into limbs, ignoring ASCII whitespace.
+const fn parse_rfc_hex(text: &str) -> [u64; LIMBS] {
+    let bytes = text.as_bytes();
+    let mut limbs = [0; LIMBS];
+    let mut digit = 0;
+    let mut i = bytes.len();
+    while i > 0 {
+        i -= 1;
+        let nibble = match bytes[i] {
+            b'0'..=b'9' => bytes[i] - b'0',
+            b'a'..=b'f' => bytes[i] - b'a' + 10,
+            b'A'..=b'F' => bytes[i] - b'A' + 10,
+            b' ' | b'\t' | b'\n' | b'\r' => continue,
+            _ => panic!("invalid hexadecimal digit"),
+        };
+        limbs[digit / 16] |= (nibble as u64) << (4 * (digit % 16));
+        digit += 1;
+    }
+    limbs
+}
+
+/// Write `0x` and the hexadecimal digits of the limbs, without leading zeros, at the end of a
+/// buffer padded with `0`.
+const fn hex_digits(limbs: &[u64; LIMBS]) -> [u8; HEX_LEN] {
+    let mut buf = [b'0'; HEX_LEN];
+    let mut first = HEX_LEN - 1;
+    let mut digit = 0;
+    while digit < 16 * LIMBS {
+        let nibble = ((limbs[digit / 16] >> (4 * (digit % 16))) & 0xf) as u8;
+        let pos = HEX_LEN - 1 - digit;
+        buf[pos] = if nibble < 10 {
+            b'0' + nibble
+        } else {
+            b'a' + nibble - 10
+        };
+        if nibble != 0 {
+            first = pos;
+        }
+        digit += 1;
+    }
+    buf[first - 1] = b'x';
+    buf
+}
+
+/// The `0x`-prefixed string written in a buffer by [hex_digits].
+const fn hex_str(buf: &'static [u8; HEX_LEN]) -> &'static str {
+    let mut start = 0;
+    while buf[start + 1] != b'x' {
+        start += 1;
+    }
+    match std::str::from_utf8(buf.split_at(start).1) {
+        Ok(text) => text,
+        Err(_) => panic!("hexadecimal digits are ASCII"),
+    }
+}
+
+const fn small(value: u64) -> [u64; LIMBS] {
+    let mut limbs = [0; LIMBS];
+    limbs[0] = value;
+    limbs
+}
+
+const fn bits(limbs: &[u64; LIMBS]) -> u32 {
+    let mut i = LIMBS;
+    while i > 0 {
+        i -= 1;
+        if limbs[i] != 0 {
+            return 64 * i as u32 + 64 - limbs[i].leading_zeros();
+        }
+    }
+    0
+}
+
+const fn shr1(mut limbs: [u64; LIMBS]) -> [u64; LIMBS] {
+    let mut i = 0;
+    while i < LIMBS {
+        limbs[i] >>= 1;
+        if i + 1 < LIMBS {
+            limbs[i] |= limbs[i + 1] << 63;
+        }
+        i += 1;
+    }
+    limbs
+}
+
+const fn add_small(mut limbs: [u64; LIMBS], value: u64) -> [u64; LIMBS] {
+    let (sum, mut carry) = limbs[0].overflowing_add(value);
+    limbs[0] = sum;
+    let mut i = 1;
+    while carry && i < LIMBS {
+        (limbs[i], carry) = limbs[i].overflowing_add(1);
+        i += 1;
+    }
+    limbs
+}
+
+const fn sub_small(mut limbs: [u64; LIMBS], value: u64) -> [u64; LIMBS] {
+    let (difference, mut borrow) = limbs[0].overflowing_sub(value);
+    limbs[0] = difference;
+    let mut i = 1;
+    while borrow && i < LIMBS {
+        (limbs[i], borrow) = limbs[i].overflowing_sub(1);
+        i += 1;
+    }
+    limbs
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    type G = MODPGroup5;
+
+    /// Laws of a prime order group, written against the `group` traits only.
+    fn check_group<E: Group>() {
+        let rng = &mut rand::thread_rng();
+        let (a, b) = (E::Scalar::random(&mut *rng), E::Scalar::random(&mut *rng));
+        let (x, y) = (E::random(&mut *rng), E::random(&mut *rng));
+
+        assert!(bool::from(E::identity().is_identity()));
+        assert!(!bool::from(x.is_identity()));
+        assert_eq!(x + E::identity(), x);
+        assert_eq!(x + y, y + x);
+        assert_eq!((x + y) - y, x);
+        assert_eq!(x + -x, E::identity());
+        assert_eq!(x.double(), x + x);
+        assert_eq!((x + y) * a, x * a + y * a);
+        assert_eq!(x * (a + b), x * a + x * b);
+        assert_eq!(x * (a * b), (x * a) * b);
+        assert_eq!(x * E::Scalar::ZERO, E::identity());
+        assert_eq!(x * -E::Scalar::ONE, -x);
+        assert_eq!([x, y, x].iter().sum::<E>(), x.double() + y);
+
+        // the order of the group is the modulus of the scalar field
+        let order_minus_one = -E::Scalar::ONE;
+        assert_eq!(
+            E::generator() * order_minus_one + E::generator(),
+            E::identity()
+        );
+    }
+
+    /// Consistency of the constants and operations of a prime field.
+    fn check_field<F: PrimeField>() {
+        let rng = &mut rand::thread_rng();
+        let x = F::random(&mut *rng);
+
+        assert_eq!(x * x.invert().unwrap(), F::ONE);
+        assert!(bool::from(F::ZERO.invert().is_none()));
+        assert_eq!(x.square().sqrt().unwrap().square(), x.square());
+        assert_eq!(F::from(2) * F::TWO_INV, F::ONE);
+        assert_eq!(F::ROOT_OF_UNITY * F::ROOT_OF_UNITY_INV, F::ONE);
+        assert_eq!(F::ROOT_OF_UNITY.pow_vartime([1u64 << F::S]), F::ONE);
+        assert_eq!(
+            F::MULTIPLICATIVE_GENERATOR.pow_vartime([1u64 << F::S]),
+            F::DELTA
+        );
+        // the generator is a quadratic non-residue
+        assert!(bool::from(F::MULTIPLICATIVE_GENERATOR.sqrt().is_none()));
+        assert_eq!(F::from_repr(x.to_repr()).unwrap(), x);
+        assert_eq!(F::from_str_vartime("12").unwrap(), F::from(12));
+        assert_eq!(F::from(3).is_odd().unwrap_u8(), 1);
+    }
+
+    #[test]
+    fn test_group_laws() {
+        check_group::<QrElement<MODPGroup5>>();
+        check_group::<QrElement<MODPGroup14>>();
+    }
+
+    #[test]
+    fn test_field() {
+        check_field::<ScalarField<MODPGroup5>>();
+        check_field::<ScalarField<MODPGroup14>>();
+    }
+
+    #[test]
+    fn test_constants() {
+        fn check<G: QrGroup>() {
+            let q = G::sophie_garmain_prime();
+            assert_eq!(from_limbs(&G::Q), q);
+            assert_eq!(ScalarField::<G>::MODULUS, format!("0x{q:x}"));
+            assert_eq!(ScalarField::<G>::NUM_BITS, q.bits() as u32);
+            // q = 3 mod 4, so that S = 1
+            assert_eq!(&q % 4u64, BigUint::from(3u64));
+        }
+        check::<MODPGroup5>();
+        check::<MODPGroup14>();
+        check::<MODPGroup15>();
+        check::<MODPGroup16>();
+        check::<MODPGroup17>();
+        check::<MODPGroup18>();
+    }
+
+    #[test]
+    fn test_conversions() {
+        let rng = &mut rand::thread_rng();
+        let scalar = Scalar::<G>::random(rng);
+        let field = ScalarField::from(scalar.clone());
+        assert_eq!(Scalar::from(field), scalar);
+
+        let element = Element::<G>::from_biguint(scalar.value.clone());
+        let qr = QrElement::try_from(element.clone()).unwrap();
+        assert_eq!(qr, QrElement::generator() * field);
+        assert_eq!(Element::from(qr), element);
+
+        // p - 1 has order 2
+        let minus_one = Element::<G>::from_value(G::prime_modulus() - 1u64);
+        assert_eq!(
+            QrElement::try_from(minus_one),
+            Err(ValidationError::NotInSubgroup)
+        );
+        assert_eq!(
+            QrElement::try_from(Element::<G>::from_value(BigUint::from(0u64))),
+            Err(ValidationError::OutOfRange)
+        );
+        assert_eq!(
+            QrElement::try_from(Element::<G>::from_value(BigUint::from(1u64))).unwrap(),
+            QrElement::identity()
+        );
+    }
+
+    #[test]
+    fn test_repr_out_of_range() {
+        let q = G::sophie_garmain_prime();
+        let mut repr = ScalarRepr::default();
+        let bytes = q.to_bytes_le();
+        repr.0[..bytes.len()].copy_from_slice(&bytes);
+        assert!(bool::from(ScalarField::<G>::from_repr(repr).is_none()));
+    }
+}
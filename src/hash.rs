@@ -0,0 +1,119 @@
+use std::{fmt, marker::PhantomData};
+
+use digest::{
+    core_api::{AlgorithmName, CoreProxy},
+    Digest,
+};
+use num_bigint::BigUint;
+
+use crate::{group::MODPGroup, Element};
+
+/// A hash function of the protocols of this crate: a [Digest] that also names its algorithm, e.g.
+/// `sha2::Sha256`, the default used in the examples and tests, `sha3::Sha3_256` or
+/// `sha2::Sha512`. It is implemented for all the hash functions of
+/// [RustCrypto](https://github.com/RustCrypto/hashes) that name their algorithm.
+///
+/// The name is mixed into the domain separation tags of the hashes of this crate, so that the
+/// same protocol run with two hash functions never produces related outputs.
+pub trait NamedDigest: Digest {
+    /// Write the name of the algorithm, e.g. `Sha256_32` for `sha2::Sha256`.
+    fn write_alg_name(f: &mut fmt::Formatter<'_>) -> fmt::Result;
+}
+
+impl<D> NamedDigest for D
+where
+    D: Digest + CoreProxy,
+    D::Core: AlgorithmName,
+{
+    fn write_alg_name(f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        D::Core::write_alg_name(f)
+    }
+}
+
+/// The name of the algorithm of `D`, as written by [NamedDigest::write_alg_name].
+pub(crate) fn algorithm_name<D: NamedDigest>() -> String {
+    struct Name<D>(PhantomData<D>);
+
+    impl<D: NamedDigest> fmt::Display for Name<D> {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            D::write_alg_name(f)
+        }
+    }
+
+    Name::<D>(PhantomData).to_string()
+}
+
+/// Bind the domain separation tag `dst` to the hash function `D`, as `len || name || dst` where
+/// `name` is the [algorithm name](algorithm_name) of `D` and `len` its length as a byte.
+pub(crate) fn domain_tag<D: NamedDigest>(dst: &[u8]) -> Vec<u8> {
+    let name = algorithm_name::<D>();
+    let mut tag = vec![u8::try_from(name.len()).expect("algorithm name too long")];
+    tag.extend_from_slice(name.as_bytes());
+    tag.extend_from_slice(dst);
+    tag
+}
+
+/// Expand `msg` into `len` pseudo-random bytes with the hash function `D`, under the domain
+/// separation tag `dst` bound to `D` by [domain_tag]. The output is the concatenation of the
+/// blocks `D(len(tag) || tag || counter || block_index || msg)` truncated to `len` bytes, where
+/// the lengths and indices are encoded as big-endian `u32`.
+pub(crate) fn expand_message<D: NamedDigest>(
+    msg: &[u8],
+    dst: &[u8],
+    counter: u32,
+    len: usize,
+) -> Vec<u8> {
+    let dst = domain_tag::<D>(dst);
+    let mut out = Vec::with_capacity(len);
+    let mut block_index = 0u32;
+    while out.len() < len {
+        let block = D::new()
+            .chain_update((dst.len() as u32).to_be_bytes())
+            .chain_update(&dst)
+            .chain_update(counter.to_be_bytes())
+            .chain_update(block_index.to_be_bytes())
+            .chain_update(msg)
+            .finalize();
+        out.extend_from_slice(&block);
+        block_index += 1;
+    }
+    out.truncate(len);
+    out
+}
+
+/// Hash `msg` to a generator of the subgroup of order q, as [expand_message] modulo p raised to
+/// the cofactor (p - 1) / q, i.e. squared for a safe prime. The output is 128 bits longer than p
+/// so that its reduction is close to uniform. A power equal to 0 or 1 is discarded and `msg`
+/// hashed again with the next counter.
+pub(crate) fn hash_to_subgroup<G: MODPGroup, D: NamedDigest>(msg: &[u8], dst: &[u8]) -> Element<G> {
+    let p = G::prime_modulus();
+    let len = p.bits().div_ceil(8) as usize + 16;
+    let one = BigUint::from(1u64);
+    let cofactor = (&p - 1u64) / G::subgroup_order();
+    let mut counter = 0u32;
+    loop {
+        let value = BigUint::from_bytes_be(&expand_message::<D>(msg, dst, counter, len)) % &p;
+        let generator = value.modpow(&cofactor, &p);
+        if generator > one {
+            return Element::from_value(generator);
+        }
+        counter += 1;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use sha2::{Sha256, Sha512};
+
+    use super::*;
+
+    #[test]
+    fn test_domain_tag() {
+        assert_eq!(algorithm_name::<Sha256>(), "Sha256_32");
+        assert_eq!(domain_tag::<Sha256>(b"dst"), b"\x09Sha256_32dst");
+        assert_ne!(
+            expand_message::<Sha256>(b"msg", b"dst", 0, 32),
+            expand_message::<Sha512>(b"msg", b"dst", 0, 32)
+        );
+    }
+}
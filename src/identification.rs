@@ -0,0 +1,243 @@
+//! The interactive Schnorr identification protocol, by which a prover convinces a verifier that
+//! it knows the secret key `x` of the public key `y = g^x mod p`:
+//!
+//! 1. The prover picks a random `k` and sends the commitment `t = g^k` ([Prover::new]).
+//! 2. The verifier replies with a random challenge `c` ([Verifier::challenge]).
+//! 3. The prover sends the response `s = k + c * x mod q` ([Prover::respond]).
+//! 4. The verifier accepts if `g^s = t * y^c` ([Verifier::verify]).
+//!
+//! Responding to two challenges with the same commitment reveals the secret key, so
+//! [Prover::respond] consumes the prover:
+//!
+//! ```compile_fail
+//! use diffie_hellman_groups::{identification::{Prover, Verifier}, Scalar, group::MODPGroup5};
+//!
+//! let rng = &mut rand::thread_rng();
+//! let sk = Scalar::<MODPGroup5>::random(rng);
+//! let (prover, _commitment) = Prover::new(&sk, rng);
+//! let first = prover.respond(&Verifier::challenge(rng));
+//! let second = prover.respond(&Verifier::challenge(rng)); // error: use of moved value
+//! ```
+//!
+//! Each message can be encoded into fixed-length bytes with `to_bytes` and decoded with
+//! `from_bytes`.
+//!
+//! # Example
+//!
+//! ```rust
+//! use diffie_hellman_groups::{
+//!     identification::{Prover, Verifier}, Element, Scalar, group::MODPGroup5,
+//! };
+//!
+//! let rng = &mut rand::thread_rng();
+//! let sk = Scalar::<MODPGroup5>::random(rng);
+//! let pk = Element::<MODPGroup5>::from_biguint(sk.value.clone());
+//!
+//! let (prover, commitment) = Prover::new(&sk, rng);
+//! let challenge = Verifier::challenge(rng);
+//! let response = prover.respond(&challenge);
+//! assert!(Verifier::verify(&pk, &commitment, &challenge, &response));
+//! ```
+
+use std::marker::PhantomData;
+
+use num_bigint::BigUint;
+use rand_core::CryptoRngCore;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{group::MODPGroup, DhGroupsError, Element, Scalar};
+
+/// The first message, `t = g^k`, sent by the prover.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(bound = ""))]
+pub struct ProverCommitment<G: MODPGroup> {
+    pub t: Element<G>,
+}
+
+/// The second message, a random challenge `c`, sent by the verifier.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(bound = ""))]
+pub struct VerifierChallenge<G: MODPGroup> {
+    pub c: Scalar<G>,
+}
+
+/// The third message, `s = k + c * x mod q`, sent by the prover.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(bound = ""))]
+pub struct ProverResponse<G: MODPGroup> {
+    pub s: Scalar<G>,
+}
+
+/// The prover after sending its commitment, holding the secret key and the nonce `k`.
+pub struct Prover<G: MODPGroup> {
+    x: Scalar<G>,
+    k: Scalar<G>,
+}
+
+/// The verifier of the protocol.
+pub struct Verifier<G: MODPGroup> {
+    phantom: PhantomData<G>,
+}
+
+impl<G: MODPGroup> Prover<G> {
+    /// Start the protocol with the secret key `sk`, returning the prover and the commitment to
+    /// send to the verifier.
+    pub fn new<R: CryptoRngCore + ?Sized>(
+        sk: &Scalar<G>,
+        rng: &mut R,
+    ) -> (Self, ProverCommitment<G>) {
+        let k = Scalar::<G>::random(rng);
+        let commitment = ProverCommitment {
+            t: Element::from_biguint(k.value.clone()),
+        };
+        let prover = Prover {
+            x: Scalar::from_biguint(sk.value.clone()),
+            k,
+        };
+        (prover, commitment)
+    }
+
+    /// Answer the challenge of the verifier. The prover is consumed, so that the nonce cannot be
+    /// used for a second challenge.
+    pub fn respond(self, challenge: &VerifierChallenge<G>) -> ProverResponse<G> {
+        ProverResponse {
+            s: &self.k + &challenge.c * &self.x,
+        }
+    }
+}
+
+impl<G: MODPGroup> Verifier<G> {
+    /// Pick a random challenge in `[1, q)`.
+    pub fn challenge<R: CryptoRngCore + ?Sized>(rng: &mut R) -> VerifierChallenge<G> {
+        VerifierChallenge {
+            c: Scalar::random(rng),
+        }
+    }
+
+    /// Check the response of the prover to `challenge`, i.e. `g^s = t * y^c`. The commitment and
+    /// the public key must be elements in `[1, p)`.
+    pub fn verify(
+        pk: &Element<G>,
+        commitment: &ProverCommitment<G>,
+        challenge: &VerifierChallenge<G>,
+        response: &ProverResponse<G>,
+    ) -> bool {
+        let zero = BigUint::from(0u64);
+        if pk.value == zero || commitment.t.value == zero {
+            return false;
+        }
+        Element::<G>::from_biguint(response.s.value.clone())
+            == &commitment.t * pk.pow(&challenge.c.value)
+    }
+}
+
+impl<G: MODPGroup> ProverCommitment<G> {
+    /// Encode the commitment as in [Element::to_bytes_be].
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.t.to_bytes_be()
+    }
+
+    /// Decode the commitment as in [Element::from_bytes_be].
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, DhGroupsError> {
+        Ok(ProverCommitment {
+            t: Element::from_bytes_be(bytes)?,
+        })
+    }
+}
+
+impl<G: MODPGroup> VerifierChallenge<G> {
+    /// Encode the challenge as in [Scalar::to_bytes_be].
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.c.to_bytes_be()
+    }
+
+    /// Decode the challenge as in [Scalar::from_bytes].
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, DhGroupsError> {
+        Ok(VerifierChallenge {
+            c: Scalar::from_bytes(bytes)?,
+        })
+    }
+}
+
+impl<G: MODPGroup> ProverResponse<G> {
+    /// Encode the response as in [Scalar::to_bytes_be].
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.s.to_bytes_be()
+    }
+
+    /// Decode the response as in [Scalar::from_bytes].
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, DhGroupsError> {
+        Ok(ProverResponse {
+            s: Scalar::from_bytes(bytes)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        group::{MODPGroup14, MODPGroup5},
+        ValidationError,
+    };
+
+    fn run<G: MODPGroup>(prover_sk: &Scalar<G>, pk: &Element<G>) -> bool {
+        let rng = &mut rand::thread_rng();
+        let (prover, commitment) = Prover::new(prover_sk, rng);
+        let challenge = Verifier::challenge(rng);
+        let response = prover.respond(&challenge);
+
+        // the messages go over the wire
+        let commitment = ProverCommitment::from_bytes(&commitment.to_bytes()).unwrap();
+        let challenge = VerifierChallenge::from_bytes(&challenge.to_bytes()).unwrap();
+        let response = ProverResponse::from_bytes(&response.to_bytes()).unwrap();
+        Verifier::verify(pk, &commitment, &challenge, &response)
+    }
+
+    fn test_executions<G: MODPGroup>() {
+        let rng = &mut rand::thread_rng();
+        let sk = Scalar::<G>::random(rng);
+        let pk = Element::<G>::from_biguint(sk.value.clone());
+        assert!(run(&sk, &pk));
+
+        // a cheating prover not knowing the secret key
+        let wrong_sk = Scalar::<G>::random(rng);
+        assert!(!run(&wrong_sk, &pk));
+    }
+
+    #[test]
+    fn test_executions_group_5() {
+        test_executions::<MODPGroup5>();
+    }
+
+    #[test]
+    fn test_executions_group_14() {
+        test_executions::<MODPGroup14>();
+    }
+
+    #[test]
+    fn test_decoding_errors() {
+        let q = MODPGroup5::sophie_garmain_prime();
+        let bytes = Scalar::<MODPGroup5>::from_biguint(q.clone() - 1u64).to_bytes_be();
+        assert_eq!(bytes.len(), 192);
+
+        assert_eq!(
+            VerifierChallenge::<MODPGroup5>::from_bytes(&bytes[1..]).unwrap_err(),
+            DhGroupsError::UnexpectedEof
+        );
+        assert_eq!(
+            ProverResponse::<MODPGroup5>::from_bytes(&[bytes.as_slice(), &[0]].concat())
+                .unwrap_err(),
+            DhGroupsError::InputTooLong { len: 193, max: 192 }
+        );
+        assert_eq!(
+            ProverResponse::<MODPGroup5>::from_bytes(&q.to_bytes_be()).unwrap_err(),
+            DhGroupsError::Validation(ValidationError::OutOfRange)
+        );
+        assert_eq!(
+            ProverCommitment::<MODPGroup5>::from_bytes(&[0xff; 192]).unwrap_err(),
+            DhGroupsError::Validation(ValidationError::OutOfRange)
+        );
+    }
+}
@@ -0,0 +1,310 @@
+//! JSON Web Key (RFC 7517) export and import of [PrimeGroup] parameters and
+//! [RuntimePublicKey]s, enabled by the `jwk` feature.
+//!
+//! JOSE registers no key type for finite field Diffie-Hellman, so the keys use the custom key type
+//! `"kty": "DH"` with the following members, each the base64url encoding without padding of the
+//! big-endian value, as for the RSA members of RFC 7518:
+//! - `"p"`: the prime modulus,
+//! - `"g"`: the generator,
+//! - `"q"`: the order of the subgroup, optional, `(p - 1) / 2` if absent,
+//! - `"y"`: the public key, absent from parameters.
+//!
+//! Other members, e.g. `"kid"` or `"use"`, are ignored when parsing. The parsed group is checked
+//! with [PrimeGroup::validate]. The public key is imported as an [UnvalidatedRuntimePublicKey], to
+//! be checked with [UnvalidatedRuntimePublicKey::validate].
+//!
+//! # Example
+//!
+//! ```rust
+//! use std::sync::Arc;
+//! use diffie_hellman_groups::{group::MODPGroup14, PrimeGroup, UnvalidatedRuntimePublicKey};
+//!
+//! let group = Arc::new(PrimeGroup::new::<MODPGroup14>(256).unwrap());
+//! let (_, pk) = group.generate_keypair(&mut rand::thread_rng());
+//!
+//! let jwk = pk.to_jwk();
+//! assert!(jwk.contains(r#""kty":"DH""#));
+//! let imported = UnvalidatedRuntimePublicKey::from_jwk(&jwk).unwrap();
+//! assert_eq!(imported.validate().unwrap(), pk);
+//! let imported = UnvalidatedRuntimePublicKey::from_jwk_in(&group, &jwk).unwrap();
+//! assert_eq!(imported.validate().unwrap(), pk);
+//! ```
+
+use std::sync::Arc;
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use num_bigint::BigUint;
+use serde_json::{json, Map, Value};
+
+use crate::{DhGroupsError, PrimeGroup, RuntimePublicKey, UnvalidatedRuntimePublicKey};
+
+/// Key type of the JSON Web Keys.
+pub const KEY_TYPE: &str = "DH";
+
+impl PrimeGroup {
+    /// Export the parameters as a JSON Web Key with the members `"p"`, `"g"` and `"q"`.
+    pub fn to_jwk(&self) -> String {
+        Value::Object(group_members(self)).to_string()
+    }
+
+    /// Import parameters from a JSON Web Key.
+    ///
+    /// # Errors
+    /// Returns [DhGroupsError::InvalidJwk] if the input is not a JSON object of the key type
+    /// [KEY_TYPE] with base64url members `"p"` and `"g"`, and optionally `"q"`, or the error of
+    /// [PrimeGroup::validate] if the parameters are invalid.
+    pub fn from_jwk(jwk: &str) -> Result<Self, DhGroupsError> {
+        parse_group(&parse_object(jwk)?)
+    }
+}
+
+impl RuntimePublicKey {
+    /// Export the key and its group as a JSON Web Key with the members `"p"`, `"g"`, `"q"` and
+    /// `"y"`.
+    pub fn to_jwk(&self) -> String {
+        let mut members = group_members(self.group());
+        members.insert("y".to_string(), json!(encode(self.value())));
+        Value::Object(members).to_string()
+    }
+}
+
+impl UnvalidatedRuntimePublicKey {
+    /// Import a public key and its group from a JSON Web Key.
+    ///
+    /// # Errors
+    /// Returns the errors of [PrimeGroup::from_jwk], [DhGroupsError::InvalidJwk] if the member
+    /// `"y"` is missing or malformed, or [DhGroupsError::InputTooLong] if it is longer than p.
+    pub fn from_jwk(jwk: &str) -> Result<Self, DhGroupsError> {
+        let members = parse_object(jwk)?;
+        let group = Arc::new(parse_group(&members)?);
+        parse_key(group, &members)
+    }
+
+    /// Import a public key of `group` from a JSON Web Key. The group of the key is compared to
+    /// `group` instead of being validated.
+    ///
+    /// # Errors
+    /// Returns [DhGroupsError::InvalidJwk] if the input is malformed, [DhGroupsError::InputTooLong]
+    /// if the key is longer than p, or [DhGroupsError::GroupMismatch] if the parameters of the key
+    /// are not those of `group`.
+    pub fn from_jwk_in(group: &Arc<PrimeGroup>, jwk: &str) -> Result<Self, DhGroupsError> {
+        let members = parse_object(jwk)?;
+        let (p, g, q) = group_values(&members)?;
+        let q = q.unwrap_or_else(|| (&p - 1u64) >> 1);
+        if p != group.p || g != group.g || q != group.q {
+            return Err(DhGroupsError::GroupMismatch);
+        }
+        parse_key(group.clone(), &members)
+    }
+}
+
+/// The members `"kty"`, `"p"`, `"g"` and `"q"` of `group`.
+fn group_members(group: &PrimeGroup) -> Map<String, Value> {
+    let mut members = Map::new();
+    members.insert("kty".to_string(), json!(KEY_TYPE));
+    members.insert("p".to_string(), json!(encode(&group.p)));
+    members.insert("g".to_string(), json!(encode(&group.g)));
+    members.insert("q".to_string(), json!(encode(&group.q)));
+    members
+}
+
+/// Parse a JSON object of the key type [KEY_TYPE].
+fn parse_object(jwk: &str) -> Result<Map<String, Value>, DhGroupsError> {
+    match serde_json::from_str(jwk) {
+        Ok(Value::Object(members))
+            if members.get("kty").and_then(Value::as_str) == Some(KEY_TYPE) =>
+        {
+            Ok(members)
+        }
+        _ => Err(DhGroupsError::InvalidJwk),
+    }
+}
+
+/// Decode the members `"p"`, `"g"` and the optional `"q"`.
+fn group_values(
+    members: &Map<String, Value>,
+) -> Result<(BigUint, BigUint, Option<BigUint>), DhGroupsError> {
+    let p = decode_member(members, "p")?;
+    let g = decode_member(members, "g")?;
+    let q = match members.get("q") {
+        Some(_) => Some(decode_member(members, "q")?),
+        None => None,
+    };
+    Ok((p, g, q))
+}
+
+/// Build and validate the group of the members.
+fn parse_group(members: &Map<String, Value>) -> Result<PrimeGroup, DhGroupsError> {
+    let (p, g, q) = group_values(members)?;
+    PrimeGroup::from_parameters(p, g, q)
+}
+
+/// Decode the member `"y"` as a public key of `group`.
+fn parse_key(
+    group: Arc<PrimeGroup>,
+    members: &Map<String, Value>,
+) -> Result<UnvalidatedRuntimePublicKey, DhGroupsError> {
+    let y = decode_member(members, "y")?;
+    group.check_encoded_len(y.bits().div_ceil(8) as usize)?;
+    Ok(UnvalidatedRuntimePublicKey::from_biguint(group, y))
+}
+
+/// Decode the non-empty base64url member `name`.
+fn decode_member(members: &Map<String, Value>, name: &str) -> Result<BigUint, DhGroupsError> {
+    let encoded = members
+        .get(name)
+        .and_then(Value::as_str)
+        .ok_or(DhGroupsError::InvalidJwk)?;
+    match URL_SAFE_NO_PAD.decode(encoded) {
+        Ok(bytes) if !bytes.is_empty() => Ok(BigUint::from_bytes_be(&bytes)),
+        _ => Err(DhGroupsError::InvalidJwk),
+    }
+}
+
+/// Encode the minimal big-endian bytes of `value` in base64url without padding.
+fn encode(value: &BigUint) -> String {
+    URL_SAFE_NO_PAD.encode(value.to_bytes_be())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{MODPGroup, MODPGroup14, ValidationError};
+
+    /// Import and validate a public key.
+    fn import(jwk: &str) -> RuntimePublicKey {
+        UnvalidatedRuntimePublicKey::from_jwk(jwk)
+            .unwrap()
+            .validate()
+            .unwrap()
+    }
+
+    const GROUP_14_JWK: &str = include_str!("../tests/fixtures/jwk/group14_public_key.json");
+
+    /// The fixture with the member `name` replaced, or removed if `value` is `None`.
+    fn with_member(name: &str, value: Option<Value>) -> String {
+        let mut members = parse_object(GROUP_14_JWK).unwrap();
+        match value {
+            Some(value) => members.insert(name.to_string(), value),
+            None => members.remove(name),
+        };
+        Value::Object(members).to_string()
+    }
+
+    #[test]
+    fn test_javascript_fixture() {
+        // written by tests/fixtures/jwk/generate.js, with unknown members
+        let pk = import(GROUP_14_JWK);
+        assert_eq!(pk.group().p, MODPGroup14::prime_modulus());
+        assert_eq!(pk.group().g, MODPGroup14::generator());
+        assert_eq!(pk.group().q, MODPGroup14::sophie_garmain_prime());
+        assert_eq!(pk.value().bits(), 2046);
+
+        let group = Arc::new(PrimeGroup {
+            p: MODPGroup14::prime_modulus(),
+            q: MODPGroup14::sophie_garmain_prime(),
+            g: MODPGroup14::generator(),
+            cofactor: 2u64.into(),
+        });
+        assert_eq!(
+            UnvalidatedRuntimePublicKey::from_jwk_in(&group, GROUP_14_JWK).unwrap(),
+            pk.clone().into()
+        );
+        assert_eq!(PrimeGroup::from_jwk(GROUP_14_JWK).unwrap(), *group);
+
+        // "q" is optional
+        let without_q = with_member("q", None);
+        assert_eq!(import(&without_q), pk);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let rng = &mut rand::thread_rng();
+        // a group whose order is not (p - 1) / 2
+        let group = Arc::new(PrimeGroup::new_schnorr(3571u64.into(), 17u64.into()).unwrap());
+        let (_, pk) = group.generate_keypair(rng);
+        assert_eq!(import(&pk.to_jwk()), pk);
+        assert_eq!(PrimeGroup::from_jwk(&group.to_jwk()).unwrap(), *group);
+        // parameters are not a public key
+        assert_eq!(
+            UnvalidatedRuntimePublicKey::from_jwk(&group.to_jwk()).unwrap_err(),
+            DhGroupsError::InvalidJwk
+        );
+    }
+
+    #[test]
+    fn test_invalid_members() {
+        for jwk in [
+            "",
+            "[]",
+            r#"{"kty":"RSA"}"#,
+            &with_member("kty", None),
+            &with_member("p", None),
+            &with_member("g", Some(json!(2))),
+            &with_member("y", Some(json!(""))),
+            &with_member("y", Some(json!("AQ=="))),
+            &with_member("y", Some(json!("+/8"))),
+        ] {
+            assert_eq!(
+                UnvalidatedRuntimePublicKey::from_jwk(jwk).unwrap_err(),
+                DhGroupsError::InvalidJwk,
+                "{jwk}"
+            );
+        }
+
+        // values out of range or not in the group are imported, but not validated
+        let p_minus_one = MODPGroup14::prime_modulus() - 1u64;
+        let imported = UnvalidatedRuntimePublicKey::from_jwk(&with_member(
+            "y",
+            Some(json!(encode(&p_minus_one))),
+        ))
+        .unwrap();
+        assert_eq!(imported.validate(), Err(ValidationError::Degenerate));
+        let p = MODPGroup14::prime_modulus();
+        let imported =
+            UnvalidatedRuntimePublicKey::from_jwk(&with_member("y", Some(json!(encode(&p)))))
+                .unwrap();
+        assert_eq!(imported.validate(), Err(ValidationError::OutOfRange));
+        let too_long = BigUint::from(1u64) << 2048;
+        assert_eq!(
+            UnvalidatedRuntimePublicKey::from_jwk(&with_member(
+                "y",
+                Some(json!(encode(&too_long)))
+            ))
+            .unwrap_err(),
+            DhGroupsError::InputTooLong { len: 257, max: 256 }
+        );
+        assert_eq!(
+            UnvalidatedRuntimePublicKey::from_jwk(&with_member("g", Some(json!("AQ"))))
+                .unwrap_err(),
+            DhGroupsError::InvalidGenerator
+        );
+        assert_eq!(
+            UnvalidatedRuntimePublicKey::from_jwk(&with_member("q", Some(json!("Aw"))))
+                .unwrap_err(),
+            DhGroupsError::InvalidCofactor
+        );
+    }
+
+    #[test]
+    fn test_group_mismatch() {
+        let rng = &mut rand::thread_rng();
+        let group = Arc::new(PrimeGroup::new_schnorr(3571u64.into(), 17u64.into()).unwrap());
+        assert_eq!(
+            UnvalidatedRuntimePublicKey::from_jwk_in(&group, GROUP_14_JWK).unwrap_err(),
+            DhGroupsError::GroupMismatch
+        );
+        let (_, pk) = group.generate_keypair(rng);
+        let (_, other) = PrimeGroup::new_schnorr(3571u64.into(), 7u64.into())
+            .unwrap()
+            .generate_keypair(rng);
+        assert_eq!(
+            UnvalidatedRuntimePublicKey::from_jwk_in(&group, &other.to_jwk()).unwrap_err(),
+            DhGroupsError::GroupMismatch
+        );
+        assert_eq!(
+            UnvalidatedRuntimePublicKey::from_jwk_in(&group, &pk.to_jwk()).unwrap(),
+            pk.into()
+        );
+    }
+}
@@ -0,0 +1,388 @@
+//! Derivation of session keys from a Diffie-Hellman shared secret with HKDF and structured,
+//! length-prefixed labels.
+//!
+//! The pseudo-random key is extracted from the encoded shared secret `g^(xy)` with an empty
+//! salt, and each key is expanded with the info
+//!
+//! ```text
+//! length (u16) || len (u8) || "dhgroups " || label || len (u8) || transcript_hash
+//! ```
+//!
+//! in the style of the `HkdfLabel` of TLS 1.3, where `length` is the length of the key in bytes
+//! and `len` the length of the field that follows. As every field is length-prefixed, no two
+//! distinct labels, e.g. "client write key" and "server write key", or key lengths give the same
+//! info. The transcript hash binds the keys to the messages of the exchange.
+//!
+//! [SessionKeys] also hold a chaining key for rekeying: [SessionKeys::rekey] extracts a new
+//! pseudo-random key with the chaining key as salt and the output of a fresh exchange as input
+//! key material, and expands the keys of the next epoch with the epoch number, as a big-endian
+//! `u64`, in place of the transcript hash. An attacker thus needs both the previous keys and
+//! the new shared secret to compute the new keys.
+//!
+//! # Example
+//!
+//! ```rust
+//! use num_bigint::BigUint;
+//! use sha2::{Digest, Sha256};
+//! use diffie_hellman_groups::{kdf::{SessionKeys, SharedSecret}, Element, group::MODPGroup5};
+//!
+//! let secret = SharedSecret::new(Element::<MODPGroup5>::from_biguint(BigUint::from(42u64)));
+//! let transcript_hash = Sha256::digest(b"transcript");
+//!
+//! let keys = secret.derive_session_keys::<Sha256>(&transcript_hash, &["exporter"], &[64]);
+//! assert_eq!(keys[0].len(), 64);
+//!
+//! let keys = SessionKeys::derive::<_, Sha256>(&secret, &transcript_hash);
+//! assert_ne!(keys.client_write_key, keys.server_write_key);
+//!
+//! let fresh = SharedSecret::new(Element::<MODPGroup5>::from_biguint(BigUint::from(7u64)));
+//! let rekeyed = keys.rekey::<_, Sha256>(&fresh);
+//! assert_eq!(rekeyed.epoch(), 1);
+//! ```
+
+use std::fmt;
+
+use digest::core_api::BlockSizeUser;
+use hkdf::SimpleHkdf;
+
+use crate::{group::MODPGroup, BufferTooSmall, Element, NamedDigest};
+
+/// Prefix of all labels.
+const LABEL_PREFIX: &[u8] = b"dhgroups ";
+
+/// Length in bytes of the write keys of [SessionKeys].
+pub const KEY_LEN: usize = 32;
+
+/// Length in bytes of the write IVs of [SessionKeys].
+pub const IV_LEN: usize = 12;
+
+/// Labels of the keys of [SessionKeys], in the order of [SESSION_KEY_LENGTHS].
+const SESSION_KEY_LABELS: [&str; 5] = [
+    "client write key",
+    "server write key",
+    "client write iv",
+    "server write iv",
+    "chaining key",
+];
+
+/// Lengths of the keys of [SessionKeys].
+const SESSION_KEY_LENGTHS: [usize; 5] = [KEY_LEN, KEY_LEN, IV_LEN, IV_LEN, KEY_LEN];
+
+/// A shared secret `g^(xy)` of a Diffie-Hellman exchange.
+pub struct SharedSecret<G: MODPGroup>(Element<G>);
+
+/// The keys of both directions of a session, e.g. for an AEAD with 96-bit nonces, and the
+/// chaining key of their epoch. They are zeroized on drop.
+pub struct SessionKeys {
+    pub client_write_key: Vec<u8>,
+    pub server_write_key: Vec<u8>,
+    pub client_write_iv: Vec<u8>,
+    pub server_write_iv: Vec<u8>,
+    chaining_key: Vec<u8>,
+    epoch: u64,
+}
+
+impl<G: MODPGroup> SharedSecret<G> {
+    /// Wrap the shared element computed by an exchange.
+    pub fn new(shared: Element<G>) -> Self {
+        SharedSecret(shared)
+    }
+
+    pub fn as_element(&self) -> &Element<G> {
+        &self.0
+    }
+
+    /// Encode the secret as in [Element::to_bytes_be].
+    pub fn to_bytes_be(&self) -> Vec<u8> {
+        self.0.to_bytes_be()
+    }
+
+    /// Write the encoding of [SharedSecret::to_bytes_be] to `out` without allocating, as in
+    /// [Element::write_bytes_be].
+    ///
+    /// # Errors
+    /// Returns [BufferTooSmall] if `out` is shorter than p.
+    pub fn write_bytes(&self, out: &mut [u8]) -> Result<usize, BufferTooSmall> {
+        self.0.write_bytes_be(out)
+    }
+
+    /// Derive a key of `lengths[i]` bytes for each `labels[i]`, bound to `transcript_hash`.
+    ///
+    /// # Panics
+    /// Panics if `labels` and `lengths` have different lengths, a label is longer than 246 bytes,
+    /// `transcript_hash` is longer than 255 bytes, or a length exceeds 255 times the output size
+    /// of `D`.
+    pub fn derive_session_keys<D: NamedDigest + BlockSizeUser + Clone>(
+        &self,
+        transcript_hash: &[u8],
+        labels: &[&str],
+        lengths: &[usize],
+    ) -> Vec<Vec<u8>> {
+        assert_eq!(labels.len(), lengths.len(), "one length per label");
+        expand_labels(&self.extract::<D>(None), transcript_hash, labels, lengths)
+    }
+
+    /// Extract the pseudo-random key from the encoded secret.
+    fn extract<D: NamedDigest + BlockSizeUser + Clone>(
+        &self,
+        salt: Option<&[u8]>,
+    ) -> SimpleHkdf<D> {
+        let mut ikm = self.to_bytes_be();
+        let hkdf = SimpleHkdf::<D>::new(salt, &ikm);
+        ikm.fill(0);
+        std::hint::black_box(&ikm);
+        hkdf
+    }
+}
+
+impl<G: MODPGroup> fmt::Debug for SharedSecret<G> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("SharedSecret").finish_non_exhaustive()
+    }
+}
+
+impl SessionKeys {
+    /// Derive the keys of epoch 0, of [KEY_LEN] and [IV_LEN] bytes, under the labels
+    /// "client write key", "server write key", "client write iv", "server write iv" and
+    /// "chaining key".
+    pub fn derive<G: MODPGroup, D: NamedDigest + BlockSizeUser + Clone>(
+        secret: &SharedSecret<G>,
+        transcript_hash: &[u8],
+    ) -> Self {
+        let keys = secret.derive_session_keys::<D>(
+            transcript_hash,
+            &SESSION_KEY_LABELS,
+            &SESSION_KEY_LENGTHS,
+        );
+        Self::from_keys(keys, 0)
+    }
+
+    /// Derive the keys of the next epoch from the chaining key and the shared secret of a fresh
+    /// exchange.
+    ///
+    /// # Panics
+    /// Panics if the epoch would overflow a `u64`.
+    pub fn rekey<G: MODPGroup, D: NamedDigest + BlockSizeUser + Clone>(
+        &self,
+        new_secret: &SharedSecret<G>,
+    ) -> Self {
+        let epoch = self.epoch.checked_add(1).expect("epoch overflow");
+        let keys = expand_labels(
+            &new_secret.extract::<D>(Some(&self.chaining_key)),
+            &epoch.to_be_bytes(),
+            &SESSION_KEY_LABELS,
+            &SESSION_KEY_LENGTHS,
+        );
+        Self::from_keys(keys, epoch)
+    }
+
+    /// The number of rekeyings since [SessionKeys::derive].
+    pub fn epoch(&self) -> u64 {
+        self.epoch
+    }
+
+    fn from_keys(keys: Vec<Vec<u8>>, epoch: u64) -> Self {
+        let mut keys = keys.into_iter();
+        let mut next = || keys.next().expect("one key per label");
+        SessionKeys {
+            client_write_key: next(),
+            server_write_key: next(),
+            client_write_iv: next(),
+            server_write_iv: next(),
+            chaining_key: next(),
+            epoch,
+        }
+    }
+}
+
+impl Drop for SessionKeys {
+    fn drop(&mut self) {
+        self.client_write_key.fill(0);
+        self.server_write_key.fill(0);
+        self.client_write_iv.fill(0);
+        self.server_write_iv.fill(0);
+        self.chaining_key.fill(0);
+        std::hint::black_box(self);
+    }
+}
+
+impl fmt::Debug for SessionKeys {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SessionKeys")
+            .field("epoch", &self.epoch)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Expand a key of `lengths[i]` bytes for each `labels[i]` in `context`.
+fn expand_labels<D: NamedDigest + BlockSizeUser + Clone>(
+    hkdf: &SimpleHkdf<D>,
+    context: &[u8],
+    labels: &[&str],
+    lengths: &[usize],
+) -> Vec<Vec<u8>> {
+    labels
+        .iter()
+        .zip(lengths)
+        .map(|(label, &len)| {
+            let mut key = vec![0u8; len];
+            hkdf.expand(&hkdf_label(label.as_bytes(), len, context), &mut key)
+                .expect("key length is valid for HKDF");
+            key
+        })
+        .collect()
+}
+
+/// Encode `length || len || "dhgroups " || label || len || context`.
+fn hkdf_label(label: &[u8], length: usize, context: &[u8]) -> Vec<u8> {
+    let full_len = u8::try_from(LABEL_PREFIX.len() + label.len()).expect("label too long");
+    let context_len = u8::try_from(context.len()).expect("context too long");
+    let mut info = u16::try_from(length)
+        .expect("key too long")
+        .to_be_bytes()
+        .to_vec();
+    info.push(full_len);
+    info.extend_from_slice(LABEL_PREFIX);
+    info.extend_from_slice(label);
+    info.push(context_len);
+    info.extend_from_slice(context);
+    info
+}
+
+#[cfg(test)]
+mod test {
+    use num_bigint::BigUint;
+    use sha2::{Digest, Sha256, Sha512};
+
+    use super::*;
+    use crate::group::MODPGroup5;
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{b:02x}")).collect()
+    }
+
+    fn secret() -> SharedSecret<MODPGroup5> {
+        SharedSecret::new(Element::from_biguint(BigUint::from(42u64)))
+    }
+
+    #[test]
+    fn test_pinned_session_keys() {
+        let transcript_hash = Sha256::digest(b"transcript");
+        let keys = SessionKeys::derive::<_, Sha256>(&secret(), &transcript_hash);
+        assert_eq!(
+            hex(&keys.client_write_key),
+            "e9d9dec5d6ca0dbdf5cd67110d85c434d912bef51faffffa3971add709a36b49"
+        );
+        assert_eq!(
+            hex(&keys.server_write_key),
+            "1dbbe415b0794e622aa3620fde69c9a906023eadddd83fea9daacd2eadc563ad"
+        );
+        assert_eq!(hex(&keys.client_write_iv), "e3d34fb420e3890488a9175f");
+        assert_eq!(hex(&keys.server_write_iv), "596680c9bbac40d258a36f1e");
+
+        let exported =
+            secret().derive_session_keys::<Sha256>(&transcript_hash, &["exporter"], &[100]);
+        assert_eq!(
+            hex(&exported[0]),
+            "693bd9091e06fedf34ace5e11d046a4da600708add922d50605bb2e029aec9f2\
+            3641a07d1fa1ca104786314eff503bdfa32bb5568871b066cd74b443bf57d43d\
+            76f554e351e84fc5ac85b7e3972ecb900e03e31955695d57071d32a7ed60b51e\
+            5fea9151"
+        );
+    }
+
+    #[test]
+    fn test_labels_are_separated() {
+        let transcript_hash = Sha256::digest(b"transcript");
+        let keys = secret().derive_session_keys::<Sha256>(
+            &transcript_hash,
+            &["key", "key ", "key", "ke"],
+            &[32, 32, 16, 32],
+        );
+        // a different label, or the same label with another length, gives an unrelated key
+        assert_ne!(keys[0], keys[1]);
+        assert_ne!(&keys[0][..16], &keys[2][..]);
+        assert_ne!(keys[0], keys[3]);
+
+        // bound to the transcript
+        let other = secret().derive_session_keys::<Sha256>(b"other", &["key"], &[32]);
+        assert_ne!(keys[0], other[0]);
+    }
+
+    #[test]
+    fn test_rekey_in_sync() {
+        let transcript_hash = Sha256::digest(b"transcript");
+        let fresh = |x: u64| SharedSecret::<MODPGroup5>::new(Element::from_biguint(x.into()));
+        let mut client = SessionKeys::derive::<_, Sha256>(&secret(), &transcript_hash);
+        let mut server = SessionKeys::derive::<_, Sha256>(&secret(), &transcript_hash);
+        let initial = client.client_write_key.clone();
+
+        for x in [7, 8, 9] {
+            client = client.rekey::<_, Sha256>(&fresh(x));
+            server = server.rekey::<_, Sha256>(&fresh(x));
+            assert_eq!(client.client_write_key, server.client_write_key);
+            assert_eq!(client.server_write_iv, server.server_write_iv);
+            assert_eq!(client.chaining_key, server.chaining_key);
+            assert_ne!(client.client_write_key, initial);
+        }
+        assert_eq!(client.epoch(), 3);
+        assert_eq!(format!("{client:?}"), "SessionKeys { epoch: 3, .. }");
+    }
+
+    #[test]
+    fn test_write_bytes() {
+        let secret = secret();
+        let mut out = [0u8; 192];
+        assert_eq!(secret.write_bytes(&mut out), Ok(192));
+        assert_eq!(out.to_vec(), secret.to_bytes_be());
+        assert_eq!(
+            secret.write_bytes(&mut out[..100]),
+            Err(BufferTooSmall {
+                needed: 192,
+                len: 100
+            })
+        );
+    }
+
+    #[test]
+    fn test_digests() {
+        let transcript_hash = Sha256::digest(b"transcript");
+        let derive_256 = || SessionKeys::derive::<_, Sha256>(&secret(), &transcript_hash);
+        let derive_512 = || SessionKeys::derive::<_, Sha512>(&secret(), &transcript_hash);
+        // both parties agree under each hash function, on different keys
+        assert_eq!(derive_256().client_write_key, derive_256().client_write_key);
+        assert_eq!(derive_512().client_write_key, derive_512().client_write_key);
+        assert_ne!(derive_256().client_write_key, derive_512().client_write_key);
+        assert_ne!(derive_256().chaining_key, derive_512().chaining_key);
+    }
+
+    #[test]
+    fn test_rekey_diverges() {
+        let transcript_hash = Sha256::digest(b"transcript");
+        let fresh = |x: u64| SharedSecret::<MODPGroup5>::new(Element::from_biguint(x.into()));
+        let keys = SessionKeys::derive::<_, Sha256>(&secret(), &transcript_hash);
+
+        // skipping an epoch
+        let skipped = keys.rekey::<_, Sha256>(&fresh(8));
+        let chained = keys
+            .rekey::<_, Sha256>(&fresh(7))
+            .rekey::<_, Sha256>(&fresh(8));
+        assert_ne!(skipped.client_write_key, chained.client_write_key);
+
+        // the same fresh secret on top of other previous keys
+        let other = SessionKeys::derive::<_, Sha256>(&secret(), b"other transcript");
+        assert_ne!(
+            other.rekey::<_, Sha256>(&fresh(8)).client_write_key,
+            skipped.client_write_key
+        );
+        // the new keys are not the initial keys of the fresh secret
+        let direct = SessionKeys::derive::<_, Sha256>(&fresh(8), &1u64.to_be_bytes());
+        assert_ne!(direct.client_write_key, skipped.client_write_key);
+    }
+
+    #[test]
+    #[should_panic(expected = "one length per label")]
+    fn test_mismatched_lengths() {
+        secret().derive_session_keys::<Sha256>(b"", &["a", "b"], &[32]);
+    }
+}
@@ -0,0 +1,271 @@
+//! Key pairs for Diffie-Hellman key exchange over a [PrimeGroup] chosen at runtime.
+//!
+//! Keys keep a reference to their group, so that combining keys of different groups is detected
+//! instead of silently producing a meaningless result.
+
+use std::{fmt, sync::Arc};
+
+use num_bigint::BigUint;
+
+use crate::{
+    element::write_padded, scalar::wipe, BufferTooSmall, DhGroupsError, PrimeGroup, ValidationError,
+};
+
+/// A secret exponent `x` of a [PrimeGroup]. The exponent is overwritten with zeros when dropped.
+pub struct RuntimeSecretKey {
+    group: Arc<PrimeGroup>,
+    x: BigUint,
+}
+
+/// A public key `y = g^x mod p` of a [PrimeGroup], either computed from a [RuntimeSecretKey] or
+/// validated with [UnvalidatedRuntimePublicKey::validate].
+///
+/// Only this type is accepted by [RuntimeSecretKey::diffie_hellman], so a key received from a
+/// peer cannot be used before it is validated:
+///
+/// ```compile_fail
+/// # use std::sync::Arc;
+/// # use num_bigint::BigUint;
+/// use diffie_hellman_groups::{group::MODPGroup14, PrimeGroup, UnvalidatedRuntimePublicKey};
+///
+/// let group = Arc::new(PrimeGroup::new::<MODPGroup14>());
+/// let (secret, _) = group.generate_keypair(&mut rand::thread_rng());
+/// let peer = UnvalidatedRuntimePublicKey::from_biguint(group, BigUint::from(1u64));
+/// let shared = secret.diffie_hellman(&peer);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RuntimePublicKey {
+    group: Arc<PrimeGroup>,
+    y: BigUint,
+}
+
+/// A public key `y` of a [PrimeGroup] received from a peer, which has not been validated yet.
+/// Call [UnvalidatedRuntimePublicKey::validate] to obtain a [RuntimePublicKey].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UnvalidatedRuntimePublicKey {
+    group: Arc<PrimeGroup>,
+    y: BigUint,
+}
+
+/// The result `peer_y^x mod p` of a key exchange. The value is overwritten with zeros when
+/// dropped.
+pub struct RuntimeSharedSecret {
+    z: BigUint,
+    len: usize,
+}
+
+impl RuntimeSecretKey {
+    pub(crate) fn new(group: Arc<PrimeGroup>, x: BigUint) -> Self {
+        Self { group, x }
+    }
+
+    /// Copy the key, e.g. to move it into another thread. Both copies are wiped when dropped.
+    #[cfg(feature = "async")]
+    pub(crate) fn duplicate(&self) -> Self {
+        Self::new(self.group.clone(), self.x.clone())
+    }
+
+    /// The secret exponent x.
+    #[cfg(any(feature = "openssl", feature = "secrecy"))]
+    pub(crate) fn exponent(&self) -> &BigUint {
+        &self.x
+    }
+
+    /// The group of the key.
+    pub fn group(&self) -> &PrimeGroup {
+        &self.group
+    }
+
+    /// Compute the public key g^x mod p.
+    pub fn public_key(&self) -> RuntimePublicKey {
+        RuntimePublicKey {
+            group: self.group.clone(),
+            // x is drawn below q by PrimeGroup::generate_keypair
+            y: self.group.public_key_allow_oversized(&self.x),
+        }
+    }
+
+    /// Compute the shared secret with the validated public key of a peer.
+    ///
+    /// # Errors
+    /// Returns the errors of [PrimeGroup::shared_secret], e.g. [DhGroupsError::GroupMismatch] if
+    /// `peer` belongs to another group.
+    pub fn diffie_hellman(
+        &self,
+        peer: &RuntimePublicKey,
+    ) -> Result<RuntimeSharedSecret, DhGroupsError> {
+        let z = self.group.shared_secret(&self.x, peer)?;
+        Ok(RuntimeSharedSecret {
+            z,
+            len: self.group.p.bits().div_ceil(8) as usize,
+        })
+    }
+}
+
+impl Drop for RuntimeSecretKey {
+    fn drop(&mut self) {
+        wipe(&mut self.x);
+    }
+}
+
+impl fmt::Debug for RuntimeSecretKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RuntimeSecretKey")
+            .field("group", &self.group)
+            .finish_non_exhaustive()
+    }
+}
+
+impl RuntimePublicKey {
+    /// The group of the key.
+    pub fn group(&self) -> &PrimeGroup {
+        &self.group
+    }
+
+    /// The value g^x mod p.
+    pub fn value(&self) -> &BigUint {
+        &self.y
+    }
+}
+
+impl UnvalidatedRuntimePublicKey {
+    /// Wrap the public key `y` of `group`, e.g. one received from a peer.
+    pub fn from_biguint(group: Arc<PrimeGroup>, y: BigUint) -> Self {
+        Self { group, y }
+    }
+
+    /// The group of the key.
+    pub fn group(&self) -> &PrimeGroup {
+        &self.group
+    }
+
+    /// The value y, which may be invalid.
+    pub fn value(&self) -> &BigUint {
+        &self.y
+    }
+
+    /// Validate the key with [PrimeGroup::validate_public_key].
+    ///
+    /// # Errors
+    /// Returns the [ValidationError] of [PrimeGroup::validate_public_key].
+    pub fn validate(self) -> Result<RuntimePublicKey, ValidationError> {
+        self.group.validate_public_key(&self.y)?;
+        Ok(RuntimePublicKey {
+            group: self.group,
+            y: self.y,
+        })
+    }
+
+    /// Use the key without validating it, e.g. when it was already validated by a trusted
+    /// party. Using an invalid key in a key exchange can leak bits of the secret exponent.
+    pub fn assume_valid_dangerous(self) -> RuntimePublicKey {
+        RuntimePublicKey {
+            group: self.group,
+            y: self.y,
+        }
+    }
+}
+
+impl From<RuntimePublicKey> for UnvalidatedRuntimePublicKey {
+    fn from(key: RuntimePublicKey) -> Self {
+        Self {
+            group: key.group,
+            y: key.y,
+        }
+    }
+}
+
+impl RuntimeSharedSecret {
+    /// The shared secret as an integer.
+    pub fn as_biguint(&self) -> &BigUint {
+        &self.z
+    }
+
+    /// The shared secret in big-endian bytes, left-padded with zeros to the length of the
+    /// modulus as required by RFC 2631 and TLS.
+    pub fn to_bytes_be(&self) -> Vec<u8> {
+        let mut out = vec![0u8; self.len];
+        self.write_bytes(&mut out)
+            .expect("buffer has the length of p");
+        out
+    }
+
+    /// Write the encoding of [RuntimeSharedSecret::to_bytes_be] to `out` without allocating,
+    /// returning the number of bytes written. A longer buffer is left-padded with zeros in full.
+    ///
+    /// # Errors
+    /// Returns [BufferTooSmall] if `out` is shorter than p.
+    pub fn write_bytes(&self, out: &mut [u8]) -> Result<usize, BufferTooSmall> {
+        write_padded(&self.z, self.len, out)
+    }
+}
+
+impl Drop for RuntimeSharedSecret {
+    fn drop(&mut self) {
+        wipe(&mut self.z);
+    }
+}
+
+impl fmt::Debug for RuntimeSharedSecret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RuntimeSharedSecret")
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{dhparam, AnyModpGroup, ValidationError};
+
+    #[test]
+    fn test_exchange_imported_group() {
+        let params =
+            dhparam::parse_pem(include_str!("../tests/fixtures/group14_dhparam.pem")).unwrap();
+        assert_eq!(params.builtin, Some(AnyModpGroup::Modp2048));
+        let group = params.group;
+
+        let mut rng = rand::thread_rng();
+        let (alice_secret, alice_public) = group.generate_keypair(&mut rng);
+        let (bob_secret, bob_public) = group.generate_keypair(&mut rng);
+        assert!(alice_secret.x.bits() <= group.private_exponent_bits());
+
+        let alice_shared = alice_secret.diffie_hellman(&bob_public).unwrap();
+        let bob_shared = bob_secret.diffie_hellman(&alice_public).unwrap();
+        assert_eq!(alice_shared.as_biguint(), bob_shared.as_biguint());
+        assert_eq!(alice_shared.to_bytes_be().len(), 256);
+
+        // a public key received over the wire
+        let received = UnvalidatedRuntimePublicKey::from_biguint(
+            Arc::new(group.clone()),
+            bob_public.value().clone(),
+        )
+        .validate()
+        .unwrap();
+        assert_eq!(
+            alice_secret
+                .diffie_hellman(&received)
+                .unwrap()
+                .to_bytes_be(),
+            alice_shared.to_bytes_be()
+        );
+        let invalid =
+            UnvalidatedRuntimePublicKey::from_biguint(Arc::new(group), BigUint::from(1u64));
+        assert_eq!(invalid.validate(), Err(ValidationError::Degenerate));
+    }
+
+    #[test]
+    fn test_group_mismatch() {
+        let group = PrimeGroup::new_with(BigUint::from(1623299u64), 15).unwrap();
+        let other = PrimeGroup::new_with(BigUint::from(1000667u64), 15).unwrap();
+
+        let mut rng = rand::thread_rng();
+        let (secret, _) = group.generate_keypair(&mut rng);
+        let (_, other_public) = other.generate_keypair(&mut rng);
+        assert_eq!(
+            secret.diffie_hellman(&other_public).unwrap_err(),
+            DhGroupsError::GroupMismatch
+        );
+        assert!(format!("{secret:?}").ends_with(", .. }"));
+    }
+}
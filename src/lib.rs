@@ -1,17 +1,200 @@
 //! Defines data structures representing Diffie-Hellman Groups stated in [RFC3526](https://datatracker.ietf.org/doc/rfc3526/)
 
+/// Count an operation in the counters of the module `stats`, or nothing without the `stats`
+/// feature.
+macro_rules! record {
+    ($operation:ident) => {
+        #[cfg(feature = "stats")]
+        $crate::stats::record($crate::stats::Operation::$operation);
+    };
+}
+
+#[cfg(feature = "serde_with")]
+pub mod adapters;
+
 pub mod element;
 pub use element::Element;
 
+#[cfg(feature = "bigint-dig")]
+pub mod bigint_dig;
+#[cfg(feature = "cbor")]
+pub mod cbor;
+
+pub mod cointoss;
+
+pub mod commitment;
+
+pub mod dhies;
+
+pub mod dkg;
+
+pub mod dyn_group;
+pub use dyn_group::{DhGroup, StaticGroup, ValidatedPublicValue};
+
+pub mod dlog;
+
+pub mod dsa;
+
+pub mod elgamal;
+
+#[cfg(feature = "capi")]
+pub mod ffi;
+
+#[cfg(feature = "fingerprint")]
+pub mod fingerprint;
+
+pub mod frost;
+
+#[cfg(any(test, feature = "fuzzing"))]
+#[doc(hidden)]
+pub mod fuzz_targets;
+
+pub mod error;
+pub use error::{
+    AuthError, BufferTooSmall, CheatDetected, DhGroupsError, EncodeError, PakeError, ParseError,
+    SelfTestError, ShareError, ThresholdError, ValidationError,
+};
+
 pub mod group;
+
+#[cfg(feature = "group")]
+pub mod group_traits;
+
+pub mod nr_prf;
+
+#[cfg(feature = "async")]
+pub mod offload;
+#[cfg(feature = "async")]
+pub use offload::{BlockingExecutor, TokioExecutor};
+
+pub mod pep;
+
+pub mod pok_dlog;
+
+pub mod pool;
+
+pub mod pre;
+
+pub mod public_key;
+pub use public_key::{PublicKey, UnvalidatedPublicKey};
+
+#[cfg(feature = "python")]
+pub mod python;
+
+pub mod scalar;
+pub use scalar::Scalar;
+
+pub mod schnorr;
+
+#[cfg(feature = "primegroup")]
+pub mod builder;
+#[cfg(feature = "primegroup")]
+pub use builder::{GeneratorSpec, PrimeGroupBuilder};
+
+#[cfg(feature = "primegroup")]
+mod der;
+
+#[cfg(feature = "primegroup")]
+pub mod dhparam;
+#[cfg(feature = "primegroup")]
+pub mod x942;
+
+mod hash;
+pub use hash::NamedDigest;
+
+mod reduce;
+
+pub mod identification;
+
+#[cfg(feature = "jwk")]
+pub mod jwk;
+
+pub mod kdf;
+
+#[cfg(feature = "primegroup")]
+pub mod keys;
+#[cfg(feature = "primegroup")]
+pub use keys::{
+    RuntimePublicKey, RuntimeSecretKey, RuntimeSharedSecret, UnvalidatedRuntimePublicKey,
+};
+
+#[cfg(feature = "fixed-limbs")]
+pub mod limbs;
+
+#[cfg(feature = "primegroup")]
+pub mod primes;
+
+mod text;
+#[allow(deprecated)]
 pub use group::{
-    MODPGroup, MODPGroup14, MODPGroup15, MODPGroup16, MODPGroup17, MODPGroup18, MODPGroup5,
+    strongest_group_at_least, AnyModpGroup, FFDHEGroup2048, FFDHEGroup3072, FFDHEGroup4096,
+    FFDHEGroup6144, FFDHEGroup8192, MODPGroup, MODPGroup14, MODPGroup15, MODPGroup16, MODPGroup17,
+    MODPGroup18, MODPGroup22, MODPGroup23, MODPGroup24, MODPGroup5, ModpGroupId,
 };
+#[cfg(feature = "legacy-groups")]
+pub use group::{MODPGroup1, MODPGroup2};
 
 #[cfg(feature = "primegroup")]
 pub mod primegroup;
 #[cfg(feature = "primegroup")]
-pub use primegroup::PrimeGroup;
+pub use primegroup::{GeneratorOrder, GroupPolicy, PrimeGroup, ProgressHook};
+
+pub mod mix;
+
+pub mod moduli;
+
+#[cfg(feature = "openssl")]
+pub mod openssl_interop;
+
+pub mod sae;
+
+pub mod selftest;
+pub use selftest::self_test;
+
+pub mod session;
+
+#[cfg(feature = "secrecy")]
+pub mod secret;
+
+pub mod shamir;
+
+pub mod sigma;
+
+pub mod signed_dh;
+
+pub mod smp;
+
+pub mod speke;
+
+pub mod ssh;
+
+#[cfg(feature = "stats")]
+pub mod stats;
+
+pub mod sts;
+
+#[cfg(any(test, feature = "test-utils"))]
+pub mod testing;
+
+pub mod threshold;
+
+pub mod tls;
+
+#[cfg(feature = "crypto-bigint")]
+pub mod uint;
+
+pub mod util;
+
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+pub mod wire;
+
+#[cfg(feature = "zeroize")]
+pub mod zeroizing;
+
+#[cfg(all(test, feature = "wycheproof-tests"))]
+mod wycheproof;
 
 #[cfg(test)]
 mod test {
@@ -41,6 +224,33 @@ mod test {
         assert_eq!(s, z)
     }
 
+    #[test]
+    fn test_multi_pow() {
+        use crate::Scalar;
+
+        let rng = &mut rand::thread_rng();
+        let bases: Vec<Element<MODPGroup5>> = (0..5)
+            .map(|_| Element::from_biguint(Scalar::<MODPGroup5>::random(rng).value))
+            .collect();
+        let exponents: Vec<BigUint> = [0u64, 1, 15, 16]
+            .into_iter()
+            .map(BigUint::from)
+            .chain([Scalar::<MODPGroup5>::random(rng).value])
+            .collect();
+        let terms: Vec<_> = bases.iter().zip(&exponents).collect();
+        let expected = bases
+            .iter()
+            .zip(&exponents)
+            .fold(Element::from_value(BigUint::from(1u64)), |acc, (b, e)| {
+                acc * b.pow(e)
+            });
+        assert_eq!(Element::multi_pow(&terms), expected);
+        assert_eq!(
+            Element::<MODPGroup5>::multi_pow(&[]).value,
+            BigUint::from(1u64)
+        );
+    }
+
     #[test]
 
     fn test_key_exchange_group_1() {
@@ -71,4 +281,56 @@ mod test {
     fn test_key_exchange_group_18() {
         test_key_exchange::<crate::group::MODPGroup18>();
     }
+
+    #[test]
+    fn test_key_exchange_ffdhe2048() {
+        test_key_exchange::<crate::group::FFDHEGroup2048>();
+    }
+
+    #[test]
+    fn test_key_exchange_ffdhe3072() {
+        test_key_exchange::<crate::group::FFDHEGroup3072>();
+    }
+
+    #[test]
+    fn test_key_exchange_ffdhe4096() {
+        test_key_exchange::<crate::group::FFDHEGroup4096>();
+    }
+
+    #[test]
+    fn test_key_exchange_ffdhe6144() {
+        test_key_exchange::<crate::group::FFDHEGroup6144>();
+    }
+
+    #[test]
+    fn test_key_exchange_ffdhe8192() {
+        test_key_exchange::<crate::group::FFDHEGroup8192>();
+    }
+
+    #[test]
+    fn test_key_exchange_group_22() {
+        test_key_exchange::<crate::group::MODPGroup22>();
+    }
+
+    #[test]
+    fn test_key_exchange_group_23() {
+        test_key_exchange::<crate::group::MODPGroup23>();
+    }
+
+    #[test]
+    fn test_key_exchange_group_24() {
+        test_key_exchange::<crate::group::MODPGroup24>();
+    }
+
+    #[cfg(feature = "legacy-groups")]
+    #[test]
+    fn test_key_exchange_legacy_group_1() {
+        test_key_exchange::<crate::group::MODPGroup1>();
+    }
+
+    #[cfg(feature = "legacy-groups")]
+    #[test]
+    fn test_key_exchange_legacy_group_2() {
+        test_key_exchange::<crate::group::MODPGroup2>();
+    }
 }
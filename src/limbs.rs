@@ -0,0 +1,240 @@
+//! Elements of the groups of RFC 3526 stored in fixed-size arrays of 64-bit limbs, enabled by the
+//! `fixed-limbs` feature.
+//!
+//! [Element] keeps its value in a public [BigUint], whose digits live on the heap, so that each
+//! clone allocates and each operation follows a pointer. A [FixedElement] stores the limbs
+//! inline, in an array of the length of p, so that it is `Copy` and a chain of multiplications
+//! only touches the stack and the scratch space of the reductions. Values are converted to and
+//! from [Element] at the boundaries, e.g. to exponentiate or encode them.
+//!
+//! # Example
+//!
+//! ```rust
+//! use num_bigint::BigUint;
+//! use diffie_hellman_groups::{group::MODPGroup14, limbs::FixedElement, Element};
+//!
+//! let a = Element::<MODPGroup14>::from_biguint(BigUint::from(2u32)); // = g^2 mod p
+//! let b = Element::<MODPGroup14>::from_biguint(BigUint::from(3u32)); // = g^3 mod p
+//!
+//! let product = FixedElement::from(&a) * FixedElement::from(&b);
+//! assert_eq!(Element::from(product), &a * &b);
+//! ```
+
+use std::{
+    fmt,
+    ops::{Mul, MulAssign},
+};
+
+use num_bigint::BigUint;
+
+use crate::{
+    group::{
+        MODPGroup, MODPGroup14, MODPGroup15, MODPGroup16, MODPGroup17, MODPGroup18, MODPGroup5,
+        REDUCE_GROUP_14, REDUCE_GROUP_15, REDUCE_GROUP_16, REDUCE_GROUP_17, REDUCE_GROUP_18,
+        REDUCE_GROUP_5,
+    },
+    Element,
+};
+
+/// A group whose elements can be stored in [FixedElement], implemented for the groups of
+/// RFC 3526.
+pub trait FixedLimbs: MODPGroup {
+    /// The 64-bit limbs of an element, least significant first, as many as p has.
+    type Limbs: AsRef<[u64]> + AsMut<[u64]> + Copy + Eq + fmt::Debug;
+
+    /// The limbs of zero.
+    const ZERO: Self::Limbs;
+
+    /// Compute `a * b mod p`.
+    fn mul_limbs(a: &Self::Limbs, b: &Self::Limbs) -> Self::Limbs;
+}
+
+macro_rules! impl_fixed_limbs {
+    ($($group:ident => $limbs:literal, $reduce:ident),*) => {
+        $(
+            impl FixedLimbs for $group {
+                type Limbs = [u64; $limbs];
+
+                const ZERO: Self::Limbs = [0; $limbs];
+
+                fn mul_limbs(a: &Self::Limbs, b: &Self::Limbs) -> Self::Limbs {
+                    let mut out = Self::ZERO;
+                    $reduce.mul_limbs(a, b, &mut out);
+                    out
+                }
+            }
+        )*
+    };
+}
+
+impl_fixed_limbs!(
+    MODPGroup5 => 24, REDUCE_GROUP_5,
+    MODPGroup14 => 32, REDUCE_GROUP_14,
+    MODPGroup15 => 48, REDUCE_GROUP_15,
+    MODPGroup16 => 64, REDUCE_GROUP_16,
+    MODPGroup17 => 96, REDUCE_GROUP_17,
+    MODPGroup18 => 128, REDUCE_GROUP_18
+);
+
+/// An element of a MODP group stored in a fixed-size array of limbs, always less than p.
+pub struct FixedElement<G: FixedLimbs> {
+    limbs: G::Limbs,
+}
+
+impl<G: FixedLimbs> FixedElement<G> {
+    /// The value as an integer.
+    pub fn to_biguint(&self) -> BigUint {
+        let words: Vec<u32> = self
+            .limbs
+            .as_ref()
+            .iter()
+            .flat_map(|&d| [d as u32, (d >> 32) as u32])
+            .collect();
+        BigUint::from_slice(&words)
+    }
+
+    /// Compute self^2 mod p.
+    pub fn square(&self) -> Self {
+        self * self
+    }
+}
+
+impl<G: FixedLimbs> From<&Element<G>> for FixedElement<G> {
+    fn from(element: &Element<G>) -> Self {
+        let mut limbs = G::ZERO;
+        let value = if element.value < G::prime_modulus() {
+            element.value.clone()
+        } else {
+            G::fast_reduce(&element.value)
+        };
+        for (limb, digit) in limbs.as_mut().iter_mut().zip(value.iter_u64_digits()) {
+            *limb = digit;
+        }
+        FixedElement { limbs }
+    }
+}
+
+impl<G: FixedLimbs> From<Element<G>> for FixedElement<G> {
+    fn from(element: Element<G>) -> Self {
+        FixedElement::from(&element)
+    }
+}
+
+impl<G: FixedLimbs> From<FixedElement<G>> for Element<G> {
+    fn from(element: FixedElement<G>) -> Self {
+        Element::from_value(element.to_biguint())
+    }
+}
+
+impl<G: FixedLimbs> Clone for FixedElement<G> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<G: FixedLimbs> Copy for FixedElement<G> {}
+
+impl<G: FixedLimbs> PartialEq for FixedElement<G> {
+    fn eq(&self, other: &Self) -> bool {
+        self.limbs == other.limbs
+    }
+}
+
+impl<G: FixedLimbs> Eq for FixedElement<G> {}
+
+impl<G: FixedLimbs> fmt::Debug for FixedElement<G> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FixedElement")
+            .field("value", &self.to_biguint())
+            .finish()
+    }
+}
+
+impl<G: FixedLimbs> Mul for FixedElement<G> {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        FixedElement {
+            limbs: G::mul_limbs(&self.limbs, &rhs.limbs),
+        }
+    }
+}
+
+impl<G: FixedLimbs> Mul for &FixedElement<G> {
+    type Output = FixedElement<G>;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        FixedElement {
+            limbs: G::mul_limbs(&self.limbs, &rhs.limbs),
+        }
+    }
+}
+
+impl<G: FixedLimbs> MulAssign for FixedElement<G> {
+    fn mul_assign(&mut self, rhs: Self) {
+        self.limbs = G::mul_limbs(&self.limbs, &rhs.limbs);
+    }
+}
+
+impl<G: FixedLimbs> MulAssign<&FixedElement<G>> for FixedElement<G> {
+    fn mul_assign(&mut self, rhs: &FixedElement<G>) {
+        self.limbs = G::mul_limbs(&self.limbs, &rhs.limbs);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use num_bigint::RandBigInt;
+
+    use super::*;
+
+    fn check<G: FixedLimbs>() {
+        let p = G::prime_modulus();
+        let mut rng = rand::thread_rng();
+        let mut values: Vec<BigUint> = (0..32).map(|_| rng.gen_biguint_below(&p)).collect();
+        values.extend([BigUint::ZERO, BigUint::from(1u64), &p - 1u64, &p - 2u64]);
+        for pair in values.windows(2) {
+            let (a, b) = (
+                Element::<G>::from_value(pair[0].clone()),
+                Element::<G>::from_value(pair[1].clone()),
+            );
+            let (fa, fb) = (FixedElement::from(&a), FixedElement::from(&b));
+            assert_eq!(Element::from(fa), a);
+            assert_eq!(Element::from(fa * fb), &a * &b);
+            assert_eq!(Element::from(fa.square()), a.square());
+
+            let mut acc = fa;
+            acc *= &fb;
+            acc *= fb;
+            assert_eq!(
+                acc.to_biguint(),
+                G::mul(&G::mul(&a.value, &b.value), &b.value)
+            );
+        }
+    }
+
+    #[test]
+    fn test_matches_element() {
+        check::<MODPGroup5>();
+        check::<MODPGroup14>();
+        check::<MODPGroup15>();
+        check::<MODPGroup16>();
+        check::<MODPGroup17>();
+        check::<MODPGroup18>();
+    }
+
+    #[test]
+    fn test_unreduced_element() {
+        let p = MODPGroup5::prime_modulus();
+        let mut element = Element::<MODPGroup5>::from_value(BigUint::from(3u64));
+        element.value += &p;
+        assert_eq!(
+            FixedElement::from(&element).to_biguint(),
+            BigUint::from(3u64)
+        );
+        assert_eq!(
+            format!("{:?}", FixedElement::from(&element)),
+            "FixedElement { value: 3 }"
+        );
+    }
+}
@@ -0,0 +1,268 @@
+//! Re-encryption mixing of [ElGamal](crate::elgamal) ciphertexts, the building block of mix-nets:
+//! a mix server permutes a batch of ciphertexts and re-randomizes each of them, so that the
+//! outputs cannot be linked to the inputs without the secret key ([shuffle]).
+//!
+//! The mix proves with [prove_shuffle] that each output is a re-randomization of one of the
+//! inputs, by an OR proof per output over all the inputs.
+//!
+//! # Limitations
+//!
+//! This proof is not a proof of shuffle: it does not show that the outputs come from distinct
+//! inputs, so a dishonest mix could replace an input by a copy of another one. A full proof of
+//! shuffle, e.g. by Bayer and Groth, is out of scope. The proof is also quadratic in the size of
+//! the batch. An auditor can check the [ShuffleWitness] kept by the mix with
+//! [ShuffleWitness::verify], or, with the secret key, check that the plaintexts are preserved
+//! with [audit].
+//!
+//! # Example
+//!
+//! ```rust
+//! use sha2::Sha256;
+//! use diffie_hellman_groups::{elgamal, mix, Element, Scalar, group::MODPGroup5};
+//!
+//! let rng = &mut rand::thread_rng();
+//! let sk = Scalar::<MODPGroup5>::random(rng);
+//! let pk = Element::<MODPGroup5>::from_biguint(sk.value.clone());
+//!
+//! let inputs: Vec<_> = (1..=4u32)
+//!     .map(|m| elgamal::encrypt(&pk, &Element::from_biguint(m.into()), rng))
+//!     .collect();
+//! let (outputs, witness) = mix::shuffle(&inputs, &pk, rng);
+//!
+//! let proof = mix::prove_shuffle::<_, Sha256, _>(&pk, &inputs, &outputs, &witness, rng);
+//! assert!(mix::verify_shuffle::<_, Sha256>(&pk, &inputs, &outputs, &proof));
+//! assert!(mix::audit(&sk, &inputs, &outputs));
+//! ```
+
+use std::collections::BTreeMap;
+
+use num_bigint::BigUint;
+use rand::seq::SliceRandom;
+use rand_core::CryptoRngCore;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    elgamal::{self, rerandomization_statement, Ciphertext},
+    group::MODPGroup,
+    sigma::{self, OrProof},
+    Element, NamedDigest, Scalar,
+};
+
+/// The secret permutation and randomness of a [shuffle]: the output `i` is the re-randomization
+/// of the input `permutation[i]` with `randomness[i]`.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(bound = ""))]
+pub struct ShuffleWitness<G: MODPGroup> {
+    pub permutation: Vec<usize>,
+    pub randomness: Vec<Scalar<G>>,
+}
+
+/// A proof that each output of a shuffle is a re-randomization of one of its inputs, with an
+/// OR proof per output.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(bound = ""))]
+pub struct ShuffleProof<G: MODPGroup> {
+    pub proofs: Vec<OrProof<G>>,
+}
+
+impl<G: MODPGroup> ShuffleWitness<G> {
+    /// Check that the witness is a permutation of the inputs, and that it maps `inputs` to
+    /// `outputs` under the public key `pk`.
+    pub fn verify(
+        &self,
+        pk: &Element<G>,
+        inputs: &[Ciphertext<G>],
+        outputs: &[Ciphertext<G>],
+    ) -> bool {
+        let n = inputs.len();
+        if outputs.len() != n || self.permutation.len() != n || self.randomness.len() != n {
+            return false;
+        }
+        let mut seen = vec![false; n];
+        for &j in &self.permutation {
+            if j >= n || std::mem::replace(&mut seen[j], true) {
+                return false;
+            }
+        }
+        outputs
+            .iter()
+            .zip(self.permutation.iter().zip(&self.randomness))
+            .all(|(output, (&j, s))| inputs[j].rerandomize_with(pk, s) == *output)
+    }
+}
+
+/// Shuffle the ciphertexts `cts` under the public key `pk`: re-randomize them and output them in
+/// a random order, returning the outputs and the witness of the shuffle.
+pub fn shuffle<G: MODPGroup, R: CryptoRngCore + ?Sized>(
+    cts: &[Ciphertext<G>],
+    pk: &Element<G>,
+    rng: &mut R,
+) -> (Vec<Ciphertext<G>>, ShuffleWitness<G>) {
+    let mut permutation: Vec<usize> = (0..cts.len()).collect();
+    permutation.shuffle(rng);
+    let randomness: Vec<Scalar<G>> = (0..cts.len()).map(|_| Scalar::random(rng)).collect();
+    let outputs = permutation
+        .iter()
+        .zip(&randomness)
+        .map(|(&j, s)| cts[j].rerandomize_with(pk, s))
+        .collect();
+    (
+        outputs,
+        ShuffleWitness {
+            permutation,
+            randomness,
+        },
+    )
+}
+
+/// Prove that each of `outputs` is a re-randomization of one of `inputs` under the public key
+/// `pk`, with the witness of the [shuffle].
+///
+/// # Panics
+/// Panics if the witness does not match the lengths of `inputs` and `outputs`.
+pub fn prove_shuffle<G: MODPGroup, D: NamedDigest, R: CryptoRngCore + ?Sized>(
+    pk: &Element<G>,
+    inputs: &[Ciphertext<G>],
+    outputs: &[Ciphertext<G>],
+    witness: &ShuffleWitness<G>,
+    rng: &mut R,
+) -> ShuffleProof<G> {
+    assert!(
+        outputs.len() == inputs.len()
+            && witness.permutation.len() == inputs.len()
+            && witness.randomness.len() == inputs.len(),
+        "witness does not match the shuffle"
+    );
+    let proofs = outputs
+        .iter()
+        .zip(witness.permutation.iter().zip(&witness.randomness))
+        .map(|(output, (&j, s))| {
+            let statements: Vec<_> = inputs
+                .iter()
+                .map(|input| rerandomization_statement(pk, input, output))
+                .collect();
+            sigma::or_prove::<G, D, R>(&statements, j, s, rng)
+        })
+        .collect();
+    ShuffleProof { proofs }
+}
+
+/// Verify a proof that each of `outputs` is a re-randomization of one of `inputs` under the
+/// public key `pk`. See the [limitations](self#limitations) of the proof.
+pub fn verify_shuffle<G: MODPGroup, D: NamedDigest>(
+    pk: &Element<G>,
+    inputs: &[Ciphertext<G>],
+    outputs: &[Ciphertext<G>],
+    proof: &ShuffleProof<G>,
+) -> bool {
+    outputs.len() == inputs.len()
+        && proof.proofs.len() == outputs.len()
+        && outputs.iter().zip(&proof.proofs).all(|(output, proof)| {
+            let statements: Vec<_> = inputs
+                .iter()
+                .map(|input| rerandomization_statement(pk, input, output))
+                .collect();
+            sigma::or_verify::<G, D>(&statements, proof)
+        })
+}
+
+/// Check with the secret key `sk` that `inputs` and `outputs` decrypt to the same multiset of
+/// messages.
+pub fn audit<G: MODPGroup>(
+    sk: &Scalar<G>,
+    inputs: &[Ciphertext<G>],
+    outputs: &[Ciphertext<G>],
+) -> bool {
+    let plaintexts = |cts: &[Ciphertext<G>]| {
+        cts.iter()
+            .fold(BTreeMap::<BigUint, usize>::new(), |mut acc, ct| {
+                *acc.entry(elgamal::decrypt(sk, ct).value).or_default() += 1;
+                acc
+            })
+    };
+    inputs.len() == outputs.len() && plaintexts(inputs) == plaintexts(outputs)
+}
+
+#[cfg(test)]
+mod test {
+    use sha2::Sha256;
+
+    use super::*;
+    use crate::group::MODPGroup5;
+
+    type G = MODPGroup5;
+
+    fn setup(n: u64) -> (Scalar<G>, Element<G>, Vec<Ciphertext<G>>) {
+        let rng = &mut rand::thread_rng();
+        let sk = Scalar::<G>::random(rng);
+        let pk = Element::<G>::from_biguint(sk.value.clone());
+        let inputs = (1..=n)
+            .map(|m| elgamal::encrypt(&pk, &Element::from_biguint(BigUint::from(m)), rng))
+            .collect();
+        (sk, pk, inputs)
+    }
+
+    #[test]
+    fn test_shuffle() {
+        let rng = &mut rand::thread_rng();
+        let (sk, pk, inputs) = setup(6);
+        let (outputs, witness) = shuffle(&inputs, &pk, rng);
+
+        let mut plaintexts: Vec<_> = outputs
+            .iter()
+            .map(|ct| elgamal::decrypt(&sk, ct).value)
+            .collect();
+        plaintexts.sort();
+        let expected: Vec<_> = (1..=6u64)
+            .map(|m| Element::<G>::from_biguint(BigUint::from(m)).value)
+            .collect();
+        let mut expected_sorted = expected.clone();
+        expected_sorted.sort();
+        assert_eq!(plaintexts, expected_sorted);
+
+        // no output is an input, and outputs follow the secret permutation
+        assert!(outputs.iter().all(|output| !inputs.contains(output)));
+        for (output, &j) in outputs.iter().zip(&witness.permutation) {
+            assert_eq!(elgamal::decrypt(&sk, output).value, expected[j]);
+        }
+        assert!(witness.verify(&pk, &inputs, &outputs));
+        assert!(audit(&sk, &inputs, &outputs));
+
+        // the order is not always kept
+        let identity: Vec<usize> = (0..6).collect();
+        assert!((0..10).any(|_| shuffle(&inputs, &pk, rng).1.permutation != identity));
+    }
+
+    #[test]
+    fn test_shuffle_proof() {
+        let rng = &mut rand::thread_rng();
+        let (sk, pk, inputs) = setup(3);
+        let (outputs, witness) = shuffle(&inputs, &pk, rng);
+        let proof = prove_shuffle::<_, Sha256, _>(&pk, &inputs, &outputs, &witness, rng);
+        assert!(verify_shuffle::<_, Sha256>(&pk, &inputs, &outputs, &proof));
+
+        // replace an output by an encryption of another message
+        let mut tampered = outputs.clone();
+        tampered[1] = elgamal::encrypt(&pk, &Element::from_biguint(BigUint::from(9u64)), rng);
+        assert!(!verify_shuffle::<_, Sha256>(
+            &pk, &inputs, &tampered, &proof
+        ));
+        assert!(!witness.verify(&pk, &inputs, &tampered));
+        assert!(!audit(&sk, &inputs, &tampered));
+
+        // a duplicated input passes the proof, but not the audit
+        let mut duplicated = witness.clone();
+        duplicated.permutation[0] = duplicated.permutation[1];
+        let outputs: Vec<_> = duplicated
+            .permutation
+            .iter()
+            .zip(&duplicated.randomness)
+            .map(|(&j, s)| inputs[j].rerandomize_with(&pk, s))
+            .collect();
+        let proof = prove_shuffle::<_, Sha256, _>(&pk, &inputs, &outputs, &duplicated, rng);
+        assert!(verify_shuffle::<_, Sha256>(&pk, &inputs, &outputs, &proof));
+        assert!(!duplicated.verify(&pk, &inputs, &outputs));
+        assert!(!audit(&sk, &inputs, &outputs));
+    }
+}
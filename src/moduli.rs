@@ -0,0 +1,292 @@
+//! Reading and writing of the OpenSSH moduli file format (see `moduli(5)`), as found in `/etc/ssh/moduli`.
+//!
+//! Each non-comment line holds the fields
+//!
+//! ```text
+//! timestamp type tests trials size generator modulus
+//! ```
+//!
+//! where `size` is the number of bits of the modulus minus one, the generator is in hexadecimal and
+//! the modulus is in upper case hexadecimal. Lines starting with `#` and empty lines are ignored.
+
+use std::io::{BufRead, Write};
+
+use num_bigint::BigUint;
+
+use crate::DhGroupsError;
+
+#[cfg(feature = "primegroup")]
+use crate::PrimeGroup;
+
+/// Modulus type of a safe prime, p = 2q + 1.
+pub const MODULI_TYPE_SAFE: u32 = 2;
+
+/// Bit of the `tests` field set when the modulus was sieved by small primes.
+pub const MODULI_TESTS_SIEVE: u32 = 0x02;
+/// Bit of the `tests` field set when the modulus passed Miller-Rabin tests.
+pub const MODULI_TESTS_MILLER_RABIN: u32 = 0x04;
+
+/// An entry of a moduli file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModuliEntry {
+    /// Time of the last test, in the form `YYYYMMDDHHMMSS`.
+    pub timestamp: u64,
+    /// Type of the modulus, e.g. [MODULI_TYPE_SAFE].
+    pub modulus_type: u32,
+    /// Bit mask of the tests the modulus passed.
+    pub tests: u32,
+    /// Number of primality trials performed.
+    pub trials: u32,
+    /// Size of the modulus in bits, minus one.
+    pub size: u32,
+    /// Generator of the multiplicative group modulo `modulus`, in hexadecimal in the file. It
+    /// usually has order 2q, see `ModuliEntry::to_prime_group`.
+    pub generator: BigUint,
+    /// The safe prime p = 2q + 1, in upper case hexadecimal in the file.
+    pub modulus: BigUint,
+}
+
+/// Parse the entries of a moduli file, skipping comments and empty lines.
+///
+/// # Errors
+/// Returns [DhGroupsError::MalformedModuli] with the (1-based) line number of the first line
+/// that cannot be parsed, or [DhGroupsError::Io] if reading fails.
+///
+/// # Example
+///
+/// ```rust
+/// use diffie_hellman_groups::moduli;
+///
+/// let file = "# comment\n20240106081745 2 6 100 4 5 17\n";
+/// let entries = moduli::parse(file.as_bytes()).unwrap();
+/// assert_eq!(entries[0].modulus, 23u32.into());
+/// ```
+pub fn parse<R: BufRead>(reader: R) -> Result<Vec<ModuliEntry>, DhGroupsError> {
+    let mut entries = Vec::new();
+    for (index, line) in reader.lines().enumerate() {
+        let line = line.map_err(|e| DhGroupsError::Io(e.kind()))?;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let entry = parse_line(line).ok_or(DhGroupsError::MalformedModuli { line: index + 1 })?;
+        entries.push(entry);
+    }
+    Ok(entries)
+}
+
+/// Write the entries in the moduli file format, one per line.
+pub fn write<W: Write>(mut writer: W, entries: &[ModuliEntry]) -> std::io::Result<()> {
+    for entry in entries {
+        writeln!(
+            writer,
+            "{} {} {} {} {} {:x} {:X}",
+            entry.timestamp,
+            entry.modulus_type,
+            entry.tests,
+            entry.trials,
+            entry.size,
+            entry.generator,
+            entry.modulus
+        )?;
+    }
+    Ok(())
+}
+
+fn parse_line(line: &str) -> Option<ModuliEntry> {
+    let fields: Vec<&str> = line.split_whitespace().collect();
+    let [timestamp, modulus_type, tests, trials, size, generator, modulus] = fields[..] else {
+        return None;
+    };
+    Some(ModuliEntry {
+        timestamp: timestamp.parse().ok()?,
+        modulus_type: modulus_type.parse().ok()?,
+        tests: tests.parse().ok()?,
+        trials: trials.parse().ok()?,
+        size: size.parse().ok()?,
+        generator: BigUint::parse_bytes(generator.as_bytes(), 16)?,
+        modulus: BigUint::parse_bytes(modulus.as_bytes(), 16)?,
+    })
+}
+
+#[cfg(feature = "primegroup")]
+impl ModuliEntry {
+    /// Create an entry for a safe prime group, e.g. one created by [PrimeGroup::generate].
+    ///
+    /// The entry is marked as having passed Miller-Rabin tests, with `trials` left at 0 since the
+    /// number of rounds is chosen by the primality test of `num-prime`.
+    pub fn from_prime_group(group: &PrimeGroup, timestamp: u64) -> Self {
+        Self {
+            timestamp,
+            modulus_type: MODULI_TYPE_SAFE,
+            tests: MODULI_TESTS_MILLER_RABIN,
+            trials: 0,
+            size: (group.p.bits() - 1) as u32,
+            generator: group.g.clone(),
+            modulus: group.p.clone(),
+        }
+    }
+
+    /// Convert the entry to a [PrimeGroup] after validating it.
+    ///
+    /// OpenSSH usually picks a generator of the whole multiplicative group (of order 2q). Since
+    /// [PrimeGroup] represents the subgroup of prime order q, such a generator is squared.
+    ///
+    /// # Errors
+    /// Returns
+    /// - [DhGroupsError::BitsOutOfRange] if `size` does not match the size of the modulus.
+    /// - [DhGroupsError::NotSafePrime] if the entry is not of safe type or the modulus is not a safe prime.
+    /// - [DhGroupsError::InvalidGenerator] if the generator has order less than q.
+    pub fn to_prime_group(&self) -> Result<PrimeGroup, DhGroupsError> {
+        if u64::from(self.size) + 1 != self.modulus.bits() {
+            return Err(DhGroupsError::BitsOutOfRange);
+        }
+        if self.modulus_type != MODULI_TYPE_SAFE {
+            return Err(DhGroupsError::NotSafePrime);
+        }
+
+        let one = BigUint::from(1u64);
+        let p = self.modulus.clone();
+        let q = (&p - &one) >> 1;
+        let g = if self.generator.modpow(&q, &p) == one {
+            self.generator.clone()
+        } else {
+            self.generator.modpow(&BigUint::from(2u64), &p)
+        };
+        let group = PrimeGroup {
+            p,
+            q,
+            g,
+            cofactor: BigUint::from(2u64),
+        };
+        group.validate().map_err(|e| match e {
+            DhGroupsError::NotPrime => DhGroupsError::NotSafePrime,
+            e => e,
+        })?;
+        Ok(group)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const FIXTURE: &str = include_str!("../tests/fixtures/moduli");
+
+    /// An entry of a safe prime small enough to check by hand, 23 = 2 * 11 + 1.
+    const SMALL: &str = "20240106081745 2 6 100 4 5 17\n";
+
+    #[test]
+    fn test_parse_fixture() {
+        let entries = parse(FIXTURE.as_bytes()).unwrap();
+        assert_eq!(entries.len(), 4);
+        assert_eq!(entries[0].timestamp, 20261017065903);
+        assert_eq!(entries[0].generator, BigUint::from(5u64));
+        assert_eq!(entries[1].generator, BigUint::from(2u64));
+        for (entry, size) in entries.iter().zip([1023, 1023, 2047, 2047]) {
+            assert_eq!(entry.modulus_type, MODULI_TYPE_SAFE);
+            assert_eq!(entry.tests, MODULI_TESTS_SIEVE | MODULI_TESTS_MILLER_RABIN);
+            assert_eq!(entry.trials, 100);
+            assert_eq!(entry.size, size);
+            assert_eq!(entry.modulus.bits(), u64::from(size) + 1);
+        }
+
+        assert_eq!(
+            parse(SMALL.as_bytes()).unwrap(),
+            vec![ModuliEntry {
+                timestamp: 20240106081745,
+                modulus_type: MODULI_TYPE_SAFE,
+                tests: MODULI_TESTS_SIEVE | MODULI_TESTS_MILLER_RABIN,
+                trials: 100,
+                size: 4,
+                generator: BigUint::from(5u64),
+                modulus: BigUint::from(23u64),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let entries = parse(FIXTURE.as_bytes()).unwrap();
+        let mut out = Vec::new();
+        write(&mut out, &entries).unwrap();
+
+        let expected: String = FIXTURE
+            .lines()
+            .filter(|l| !l.starts_with('#'))
+            .map(|l| format!("{l}\n"))
+            .collect();
+        assert_eq!(String::from_utf8(out).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_malformed_lines() {
+        let cases = [
+            "20240106081745 2 6 100 4 5\n",
+            "20240106081745 2 6 100 4 5 17 1\n",
+            "20240106081745 x 6 100 4 5 17\n",
+            "20240106081745 2 6 100 4 5 1G\n",
+            "20240106081745 2 6 -1 4 5 17\n",
+        ];
+        for case in cases {
+            let input = format!("# header\n\n{case}");
+            assert_eq!(
+                parse(input.as_bytes()),
+                Err(DhGroupsError::MalformedModuli { line: 3 })
+            );
+        }
+    }
+
+    #[cfg(feature = "primegroup")]
+    #[test]
+    fn test_to_prime_group() {
+        let entries = parse(FIXTURE.as_bytes()).unwrap();
+        for entry in &entries {
+            let group = entry.to_prime_group().unwrap();
+            assert_eq!(group.p, entry.modulus);
+        }
+        // ssh-keygen picks generators of order 2q, which are squared
+        assert_eq!(entries[0].to_prime_group().unwrap().g, BigUint::from(25u64));
+        assert_eq!(entries[1].to_prime_group().unwrap().g, BigUint::from(4u64));
+
+        let small = parse(SMALL.as_bytes()).unwrap().remove(0);
+        assert_eq!(small.to_prime_group().unwrap().g, BigUint::from(2u64));
+
+        let mut invalid = small.clone();
+        invalid.size = 5;
+        assert_eq!(
+            invalid.to_prime_group().unwrap_err(),
+            DhGroupsError::BitsOutOfRange
+        );
+
+        let mut invalid = small.clone();
+        invalid.modulus = BigUint::from(29u64);
+        assert_eq!(
+            invalid.to_prime_group().unwrap_err(),
+            DhGroupsError::NotSafePrime
+        );
+
+        let mut invalid = small;
+        invalid.generator = BigUint::from(22u64);
+        assert_eq!(
+            invalid.to_prime_group().unwrap_err(),
+            DhGroupsError::InvalidGenerator
+        );
+    }
+
+    #[cfg(feature = "primegroup")]
+    #[test]
+    fn test_from_prime_group() {
+        let group = PrimeGroup::generate(64, &mut rand::thread_rng(), None).unwrap();
+        let entry = ModuliEntry::from_prime_group(&group, 20240101000000);
+
+        let mut out = Vec::new();
+        write(&mut out, std::slice::from_ref(&entry)).unwrap();
+        let parsed = parse(out.as_slice()).unwrap();
+        assert_eq!(parsed, vec![entry]);
+
+        let restored = parsed[0].to_prime_group().unwrap();
+        assert_eq!(restored.p, group.p);
+        assert_eq!(restored.g, group.g);
+    }
+}
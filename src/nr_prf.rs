@@ -0,0 +1,196 @@
+//! The Naor-Reingold pseudo-random function, whose security reduces to the decisional
+//! Diffie-Hellman assumption in the group.
+//!
+//! The key is `n + 1` scalars `(a_0, a_1, ..., a_n)` ([Key]), and the value at the `n`-bit
+//! input `x` is `g^(a_0 * prod_{x_i = 1} a_i)` ([evaluate]).
+//!
+//! [Evaluator] evaluates many inputs with one key, keeping the partial products of the exponent
+//! for the prefix of the last input, so that an input sharing a prefix with it only multiplies
+//! the remaining scalars.
+//!
+//! # Example
+//!
+//! ```rust
+//! use diffie_hellman_groups::{nr_prf::{self, Evaluator, Key}, group::MODPGroup5};
+//!
+//! let key = Key::<MODPGroup5>::generate(8, &mut rand::thread_rng());
+//! let x = [true, false, true, true, false, false, true, false];
+//! let y = nr_prf::evaluate(&key, &x);
+//!
+//! let mut evaluator = Evaluator::new(&key);
+//! assert_eq!(evaluator.evaluate(&x), y);
+//! ```
+
+use std::fmt;
+
+use rand_core::CryptoRngCore;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{group::MODPGroup, Element, Scalar};
+
+/// A key `(a_0, a_1, ..., a_n)` of the PRF on `n`-bit inputs.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(bound = ""))]
+pub struct Key<G: MODPGroup> {
+    a0: Scalar<G>,
+    a: Vec<Scalar<G>>,
+}
+
+impl<G: MODPGroup> Key<G> {
+    /// Generate a random key for `n`-bit inputs.
+    pub fn generate<R: CryptoRngCore + ?Sized>(n: usize, rng: &mut R) -> Self {
+        Key {
+            a0: Scalar::random(rng),
+            a: (0..n).map(|_| Scalar::random(rng)).collect(),
+        }
+    }
+
+    /// The number of bits of the inputs.
+    pub fn input_len(&self) -> usize {
+        self.a.len()
+    }
+}
+
+impl<G: MODPGroup> fmt::Debug for Key<G> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Key")
+            .field("input_len", &self.input_len())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Evaluate the PRF with `key` at `input`, the bits `x_1, ..., x_n`.
+///
+/// # Panics
+/// Panics if `input` does not have [Key::input_len] bits.
+pub fn evaluate<G: MODPGroup>(key: &Key<G>, input: &[bool]) -> Element<G> {
+    check_len(key, input);
+    let exponent = key
+        .a
+        .iter()
+        .zip(input)
+        .filter(|(_, &bit)| bit)
+        .fold(copy(&key.a0), |acc, (a, _)| acc * a);
+    Element::from_biguint(exponent.value)
+}
+
+/// An evaluator of the PRF reusing the partial products of the prefix of the last input.
+pub struct Evaluator<'a, G: MODPGroup> {
+    key: &'a Key<G>,
+    /// The bits of the last input, and the products `a_0 * prod_{j <= i, x_j = 1} a_j` for each
+    /// of its prefixes.
+    bits: Vec<bool>,
+    products: Vec<Scalar<G>>,
+}
+
+impl<'a, G: MODPGroup> Evaluator<'a, G> {
+    pub fn new(key: &'a Key<G>) -> Self {
+        Evaluator {
+            key,
+            bits: Vec::with_capacity(key.input_len()),
+            products: Vec::with_capacity(key.input_len()),
+        }
+    }
+
+    /// Evaluate the PRF at `input`, as [evaluate].
+    ///
+    /// # Panics
+    /// Panics if `input` does not have [Key::input_len] bits.
+    pub fn evaluate(&mut self, input: &[bool]) -> Element<G> {
+        check_len(self.key, input);
+        let shared = self
+            .bits
+            .iter()
+            .zip(input)
+            .take_while(|(a, b)| a == b)
+            .count();
+        self.bits.truncate(shared);
+        self.products.truncate(shared);
+
+        for (i, &bit) in input.iter().enumerate().skip(shared) {
+            let last = self.products.last().unwrap_or(&self.key.a0);
+            let product = if bit {
+                last * &self.key.a[i]
+            } else {
+                copy(last)
+            };
+            self.bits.push(bit);
+            self.products.push(product);
+        }
+        let exponent = self.products.last().unwrap_or(&self.key.a0);
+        Element::from_biguint(exponent.value.clone())
+    }
+}
+
+fn check_len<G: MODPGroup>(key: &Key<G>, input: &[bool]) {
+    assert_eq!(
+        input.len(),
+        key.input_len(),
+        "input length does not match the key"
+    );
+}
+
+fn copy<G: MODPGroup>(s: &Scalar<G>) -> Scalar<G> {
+    Scalar::from_biguint(s.value.clone())
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashSet;
+
+    use rand::Rng;
+
+    use super::*;
+    use crate::group::MODPGroup5;
+
+    type G = MODPGroup5;
+
+    fn bits(x: u32, n: usize) -> Vec<bool> {
+        (0..n).map(|i| (x >> (n - 1 - i)) & 1 == 1).collect()
+    }
+
+    #[test]
+    fn test_incremental_evaluation() {
+        let rng = &mut rand::thread_rng();
+        let key = Key::<G>::generate(6, rng);
+        let mut evaluator = Evaluator::new(&key);
+
+        // in order, consecutive inputs share long prefixes; then in a random order
+        let mut inputs: Vec<u32> = (0..64).collect();
+        inputs.extend((0..32).map(|_| rng.gen_range(0..64)));
+        for x in inputs {
+            let input = bits(x, 6);
+            assert_eq!(evaluator.evaluate(&input), evaluate(&key, &input));
+        }
+
+        // the all-zero input gives g^a_0
+        assert_eq!(
+            evaluate(&key, &[false; 6]),
+            Element::from_biguint(key.a0.value.clone())
+        );
+    }
+
+    #[test]
+    fn test_keys_decorrelate() {
+        let rng = &mut rand::thread_rng();
+        let (k1, k2) = (Key::<G>::generate(5, rng), Key::<G>::generate(5, rng));
+
+        let outputs: HashSet<Vec<u8>> = (0..32)
+            .flat_map(|x| {
+                let input = bits(x, 5);
+                [
+                    evaluate(&k1, &input).to_bytes_be(),
+                    evaluate(&k2, &input).to_bytes_be(),
+                ]
+            })
+            .collect();
+        assert_eq!(outputs.len(), 64);
+    }
+
+    #[test]
+    #[should_panic(expected = "input length does not match the key")]
+    fn test_input_length() {
+        let key = Key::<G>::generate(4, &mut rand::thread_rng());
+        evaluate(&key, &[true; 3]);
+    }
+}
@@ -0,0 +1,207 @@
+//! Offloading of expensive exponentiations from an async runtime, enabled by the `async` feature.
+//!
+//! A modular exponentiation in a large group, e.g. group 18, takes long enough to stall every
+//! other task of a reactor thread. The `async` methods below move the computation onto a thread
+//! for blocking work with a [BlockingExecutor], [TokioExecutor] by default, and return the same
+//! results as their synchronous counterparts:
+//! - [Element::pow_blocking] for [Element::pow],
+//! - `RuntimeSecretKey::diffie_hellman_async` for `RuntimeSecretKey::diffie_hellman`,
+//! - `PrimeGroup::generate_async` for `PrimeGroup::generate`,
+//!
+//! the last two with the `primegroup` feature. The returned futures are [Send], so that they can
+//! be awaited in spawned tasks.
+//!
+//! # Example
+//!
+//! ```rust
+//! use num_bigint::BigUint;
+//! use diffie_hellman_groups::{Element, group::MODPGroup18};
+//!
+//! # tokio::runtime::Runtime::new().unwrap().block_on(async {
+//! let g = Element::<MODPGroup18>::from_biguint(BigUint::from(1u64));
+//! let y = g.pow_blocking(BigUint::from(12345u64)).await;
+//! assert_eq!(y, Element::from_biguint(BigUint::from(12345u64)));
+//! # });
+//! ```
+
+use std::{future::Future, pin::Pin};
+
+use num_bigint::BigUint;
+
+use crate::{group::MODPGroup, Element};
+
+#[cfg(feature = "primegroup")]
+use rand_core::CryptoRngCore;
+
+#[cfg(feature = "primegroup")]
+use crate::{DhGroupsError, PrimeGroup, RuntimePublicKey, RuntimeSecretKey, RuntimeSharedSecret};
+
+/// The future of the result of a closure run by a [BlockingExecutor].
+pub type BlockingFuture<T> = Pin<Box<dyn Future<Output = T> + Send>>;
+
+/// An executor of blocking closures, e.g. the thread pool of an async runtime.
+pub trait BlockingExecutor {
+    /// Run `f` on a thread where blocking is allowed, returning a future of its result.
+    fn spawn_blocking<F, T>(&self, f: F) -> BlockingFuture<T>
+    where
+        F: FnOnce() -> T + Send + 'static,
+        T: Send + 'static;
+}
+
+/// A [BlockingExecutor] running the closures with [tokio::task::spawn_blocking].
+///
+/// # Panics
+/// The futures must be awaited within a tokio runtime. A panic of the closure is resumed when
+/// its future is awaited.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TokioExecutor;
+
+impl BlockingExecutor for TokioExecutor {
+    fn spawn_blocking<F, T>(&self, f: F) -> BlockingFuture<T>
+    where
+        F: FnOnce() -> T + Send + 'static,
+        T: Send + 'static,
+    {
+        let handle = tokio::task::spawn_blocking(f);
+        Box::pin(async move {
+            match handle.await {
+                Ok(value) => value,
+                Err(e) => std::panic::resume_unwind(e.into_panic()),
+            }
+        })
+    }
+}
+
+impl<G: MODPGroup + Send + 'static> Element<G> {
+    /// Compute [Element::pow] on the blocking thread pool of tokio.
+    pub fn pow_blocking(self, exponent: BigUint) -> impl Future<Output = Self> + Send {
+        self.pow_blocking_with(exponent, &TokioExecutor)
+    }
+
+    /// Compute [Element::pow] with `executor`.
+    pub fn pow_blocking_with<E: BlockingExecutor>(
+        self,
+        exponent: BigUint,
+        executor: &E,
+    ) -> impl Future<Output = Self> + Send {
+        executor.spawn_blocking(move || self.pow(&exponent))
+    }
+}
+
+#[cfg(feature = "primegroup")]
+impl RuntimeSecretKey {
+    /// Compute [RuntimeSecretKey::diffie_hellman] on the blocking thread pool of tokio.
+    pub fn diffie_hellman_async(
+        &self,
+        peer: &RuntimePublicKey,
+    ) -> impl Future<Output = Result<RuntimeSharedSecret, DhGroupsError>> + Send {
+        self.diffie_hellman_async_with(peer, &TokioExecutor)
+    }
+
+    /// Compute [RuntimeSecretKey::diffie_hellman] with `executor`. The key is copied to the
+    /// blocking task, and the copy is wiped when dropped.
+    pub fn diffie_hellman_async_with<E: BlockingExecutor>(
+        &self,
+        peer: &RuntimePublicKey,
+        executor: &E,
+    ) -> impl Future<Output = Result<RuntimeSharedSecret, DhGroupsError>> + Send {
+        let secret = self.duplicate();
+        let peer = peer.clone();
+        executor.spawn_blocking(move || secret.diffie_hellman(&peer))
+    }
+}
+
+#[cfg(feature = "primegroup")]
+impl PrimeGroup {
+    /// Run [PrimeGroup::generate] without progress hook on the blocking thread pool of tokio.
+    pub fn generate_async<R: CryptoRngCore + Send + 'static>(
+        bits: u64,
+        rng: R,
+    ) -> impl Future<Output = Result<Self, DhGroupsError>> + Send {
+        Self::generate_async_with(bits, rng, &TokioExecutor)
+    }
+
+    /// Run [PrimeGroup::generate] without progress hook with `executor`.
+    pub fn generate_async_with<R: CryptoRngCore + Send + 'static, E: BlockingExecutor>(
+        bits: u64,
+        mut rng: R,
+        executor: &E,
+    ) -> impl Future<Output = Result<Self, DhGroupsError>> + Send {
+        executor.spawn_blocking(move || Self::generate(bits, &mut rng, None))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::group::MODPGroup14;
+
+    fn assert_send<T: Send>(value: T) -> T {
+        value
+    }
+
+    /// An executor running the closures on new threads.
+    struct ThreadExecutor;
+
+    impl BlockingExecutor for ThreadExecutor {
+        fn spawn_blocking<F, T>(&self, f: F) -> BlockingFuture<T>
+        where
+            F: FnOnce() -> T + Send + 'static,
+            T: Send + 'static,
+        {
+            let handle = std::thread::spawn(f);
+            Box::pin(async move { handle.join().unwrap() })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_pow_blocking() {
+        let g = Element::<MODPGroup14>::from_biguint(BigUint::from(3u64));
+        let e = BigUint::from(0xdeadbeefu64);
+        let expected = g.pow(&e);
+
+        let future = assert_send(g.clone().pow_blocking(e.clone()));
+        assert_eq!(future.await, expected);
+        assert_eq!(g.pow_blocking_with(e, &ThreadExecutor).await, expected);
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "blocking panic")]
+    async fn test_panic_resumed() {
+        TokioExecutor
+            .spawn_blocking(|| panic!("blocking panic"))
+            .await
+    }
+
+    #[cfg(feature = "primegroup")]
+    #[tokio::test]
+    async fn test_diffie_hellman_async() {
+        let pg = PrimeGroup::new::<MODPGroup14>(64).unwrap();
+        let rng = &mut rand::thread_rng();
+        let (alice_secret, alice_public) = pg.generate_keypair(rng);
+        let (bob_secret, bob_public) = pg.generate_keypair(rng);
+
+        let expected = alice_secret.diffie_hellman(&bob_public).unwrap();
+        let future = assert_send(alice_secret.diffie_hellman_async(&bob_public));
+        assert_eq!(future.await.unwrap().as_biguint(), expected.as_biguint());
+        let shared = bob_secret
+            .diffie_hellman_async_with(&alice_public, &ThreadExecutor)
+            .await
+            .unwrap();
+        assert_eq!(shared.as_biguint(), expected.as_biguint());
+    }
+
+    #[cfg(feature = "primegroup")]
+    #[tokio::test]
+    async fn test_generate_async() {
+        use rand::{rngs::StdRng, SeedableRng};
+
+        let expected = PrimeGroup::generate(64, &mut StdRng::seed_from_u64(7), None).unwrap();
+        let future = assert_send(PrimeGroup::generate_async(64, StdRng::seed_from_u64(7)));
+        assert_eq!(future.await.unwrap(), expected);
+        assert_eq!(
+            PrimeGroup::generate_async(2, StdRng::seed_from_u64(7)).await,
+            Err(DhGroupsError::BitsOutOfRange)
+        );
+    }
+}
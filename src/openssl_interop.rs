@@ -0,0 +1,256 @@
+//! Conversions between [PrimeGroup] parameters, runtime keys and their OpenSSL counterparts,
+//! enabled by the `openssl` feature.
+//!
+//! Parameters map to [Dh]`<Params>` with `p`, `q` and `g`, and keys to [PKey]s wrapping a [Dh],
+//! so that a key exchange can be run with one side in this crate and the other in OpenSSL. Since
+//! the parameters include `q`, OpenSSL reports the exported keys as X9.42 keys of type
+//! [Id::DHX]; keys of type [Id::DH] and [Id::DHX] are both imported. Imported parameters are checked with [PrimeGroup::validate], imported public keys
+//! are returned as [UnvalidatedRuntimePublicKey]s to be checked with
+//! [UnvalidatedRuntimePublicKey::validate], and imported secret keys must lie in `[1, q)`. A
+//! missing `q` is taken as `(p - 1) / 2`.
+//!
+//! # Example
+//!
+//! ```rust
+//! use std::sync::Arc;
+//! use diffie_hellman_groups::{PrimeGroup, UnvalidatedRuntimePublicKey};
+//! use num_bigint::BigUint;
+//! use openssl::{derive::Deriver, dh::Dh, pkey::PKey};
+//!
+//! // the 2048-bit group with a 256-bit subgroup of RFC 5114
+//! let params = Dh::get_2048_256().unwrap();
+//! let group = Arc::new(PrimeGroup::from_openssl_dh(&params).unwrap());
+//! let (secret, public) = group.generate_keypair(&mut rand::thread_rng());
+//!
+//! // the peer generates its key with OpenSSL
+//! let peer = PKey::from_dh(params.generate_key().unwrap()).unwrap();
+//! let peer_public = UnvalidatedRuntimePublicKey::from_openssl_pkey_in(&group, &peer)
+//!     .unwrap()
+//!     .validate()
+//!     .unwrap();
+//!
+//! let public = public.to_openssl_pkey().unwrap();
+//! let mut deriver = Deriver::new(&peer).unwrap();
+//! deriver.set_peer(&public).unwrap();
+//! assert_eq!(
+//!     BigUint::from_bytes_be(&deriver.derive_to_vec().unwrap()),
+//!     *secret.diffie_hellman(&peer_public).unwrap().as_biguint()
+//! );
+//! ```
+
+use std::sync::Arc;
+
+use num_bigint::BigUint;
+use openssl::{
+    bn::{BigNum, BigNumRef},
+    dh::Dh,
+    error::ErrorStack,
+    pkey::{HasParams, HasPrivate, HasPublic, Id, PKey, PKeyRef, Params, Private, Public},
+};
+
+use crate::{
+    DhGroupsError, PrimeGroup, RuntimePublicKey, RuntimeSecretKey, UnvalidatedRuntimePublicKey,
+    ValidationError,
+};
+
+impl From<ErrorStack> for DhGroupsError {
+    fn from(_: ErrorStack) -> Self {
+        DhGroupsError::OpenSsl
+    }
+}
+
+impl PrimeGroup {
+    /// Export the parameters `p`, `q` and `g` as OpenSSL Diffie-Hellman parameters.
+    ///
+    /// # Errors
+    /// Returns [DhGroupsError::OpenSsl] if OpenSSL fails to allocate the parameters.
+    pub fn to_openssl_dh(&self) -> Result<Dh<Params>, DhGroupsError> {
+        Ok(Dh::from_pqg(
+            to_bignum(&self.p)?,
+            Some(to_bignum(&self.q)?),
+            to_bignum(&self.g)?,
+        )?)
+    }
+
+    /// Import the parameters of an OpenSSL Diffie-Hellman object, e.g. a key or parameters read
+    /// by OpenSSL from a PEM file.
+    ///
+    /// # Errors
+    /// Returns [DhGroupsError::NotPrime] if the modulus or the order is too small, or the error of
+    /// [PrimeGroup::validate] if the parameters are invalid.
+    pub fn from_openssl_dh<T: HasParams>(dh: &Dh<T>) -> Result<Self, DhGroupsError> {
+        PrimeGroup::from_parameters(
+            to_biguint(dh.prime_p()),
+            to_biguint(dh.generator()),
+            dh.prime_q().map(to_biguint),
+        )
+    }
+}
+
+impl RuntimeSecretKey {
+    /// Export the key as an OpenSSL Diffie-Hellman private key, with its group and public key.
+    ///
+    /// # Errors
+    /// Returns [DhGroupsError::OpenSsl] if OpenSSL fails to build the key.
+    pub fn to_openssl_pkey(&self) -> Result<PKey<Private>, DhGroupsError> {
+        let dh = self.group().to_openssl_dh()?.set_key(
+            to_bignum(self.public_key().value())?,
+            to_bignum(self.exponent())?,
+        )?;
+        Ok(PKey::from_dh(dh)?)
+    }
+
+    /// Import an OpenSSL Diffie-Hellman private key and its group.
+    ///
+    /// # Errors
+    /// Returns [DhGroupsError::OpenSsl] if `pkey` is not a Diffie-Hellman key, the errors of
+    /// [PrimeGroup::from_openssl_dh], or [DhGroupsError::Validation] if the exponent is not in
+    /// `[1, q)`.
+    pub fn from_openssl_pkey<T: HasPrivate>(pkey: &PKeyRef<T>) -> Result<Self, DhGroupsError> {
+        let dh = dh_of(pkey)?;
+        let group = PrimeGroup::from_openssl_dh(&dh)?;
+        let x = to_biguint(dh.private_key());
+        if x == BigUint::from(0u64) || x >= group.q {
+            return Err(ValidationError::OutOfRange.into());
+        }
+        Ok(RuntimeSecretKey::new(Arc::new(group), x))
+    }
+}
+
+impl RuntimePublicKey {
+    /// Export the key as an OpenSSL Diffie-Hellman public key, with its group.
+    ///
+    /// # Errors
+    /// Returns [DhGroupsError::OpenSsl] if OpenSSL fails to build the key.
+    pub fn to_openssl_pkey(&self) -> Result<PKey<Public>, DhGroupsError> {
+        let dh = self
+            .group()
+            .to_openssl_dh()?
+            .set_public_key(to_bignum(self.value())?)?;
+        Ok(PKey::from_dh(dh)?)
+    }
+}
+
+impl UnvalidatedRuntimePublicKey {
+    /// Import an OpenSSL Diffie-Hellman public key, or the public part of a private key, and its
+    /// group.
+    ///
+    /// # Errors
+    /// Returns [DhGroupsError::OpenSsl] if `pkey` is not a Diffie-Hellman key, or the errors of
+    /// [PrimeGroup::from_openssl_dh].
+    pub fn from_openssl_pkey<T: HasPublic>(pkey: &PKeyRef<T>) -> Result<Self, DhGroupsError> {
+        let dh = dh_of(pkey)?;
+        let group = Arc::new(PrimeGroup::from_openssl_dh(&dh)?);
+        Ok(import_public(group, &dh))
+    }
+
+    /// Import an OpenSSL Diffie-Hellman public key of `group`. The parameters of the key are
+    /// compared to `group` instead of being validated.
+    ///
+    /// # Errors
+    /// Returns [DhGroupsError::OpenSsl] if `pkey` is not a Diffie-Hellman key, or
+    /// [DhGroupsError::GroupMismatch] if the parameters of the key are not those of `group`.
+    pub fn from_openssl_pkey_in<T: HasPublic>(
+        group: &Arc<PrimeGroup>,
+        pkey: &PKeyRef<T>,
+    ) -> Result<Self, DhGroupsError> {
+        let dh = dh_of(pkey)?;
+        let p = to_biguint(dh.prime_p());
+        let q = dh.prime_q().map_or_else(|| (&p - 1u64) >> 1, to_biguint);
+        if p != group.p || q != group.q || to_biguint(dh.generator()) != group.g {
+            return Err(DhGroupsError::GroupMismatch);
+        }
+        Ok(import_public(group.clone(), &dh))
+    }
+}
+
+/// The Diffie-Hellman object of `pkey`, of type DH or X9.42 DHX.
+fn dh_of<T>(pkey: &PKeyRef<T>) -> Result<Dh<T>, DhGroupsError> {
+    if pkey.id() != Id::DH && pkey.id() != Id::DHX {
+        return Err(DhGroupsError::OpenSsl);
+    }
+    Ok(pkey.dh()?)
+}
+
+/// The public key of `dh` in `group`.
+fn import_public<T: HasPublic>(group: Arc<PrimeGroup>, dh: &Dh<T>) -> UnvalidatedRuntimePublicKey {
+    UnvalidatedRuntimePublicKey::from_biguint(group, to_biguint(dh.public_key()))
+}
+
+fn to_bignum(value: &BigUint) -> Result<BigNum, ErrorStack> {
+    BigNum::from_slice(&value.to_bytes_be())
+}
+
+fn to_biguint(value: &BigNumRef) -> BigUint {
+    BigUint::from_bytes_be(&value.to_vec())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::MODPGroup5;
+
+    /// Import and validate a public key.
+    fn import<T: HasPublic>(pkey: &PKeyRef<T>) -> RuntimePublicKey {
+        UnvalidatedRuntimePublicKey::from_openssl_pkey(pkey)
+            .unwrap()
+            .validate()
+            .unwrap()
+    }
+
+    fn group() -> Arc<PrimeGroup> {
+        Arc::new(PrimeGroup::new::<MODPGroup5>(256).unwrap())
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let group = group();
+        assert_eq!(
+            PrimeGroup::from_openssl_dh(&group.to_openssl_dh().unwrap()).unwrap(),
+            *group
+        );
+
+        let (secret, public) = group.generate_keypair(&mut rand::thread_rng());
+        let pkey = secret.to_openssl_pkey().unwrap();
+        assert_eq!(pkey.id(), Id::DHX);
+        let imported = RuntimeSecretKey::from_openssl_pkey(&pkey).unwrap();
+        assert_eq!(imported.exponent(), secret.exponent());
+        assert_eq!(import(&pkey), public);
+        assert_eq!(import(&public.to_openssl_pkey().unwrap()), public);
+    }
+
+    #[test]
+    fn test_group_mismatch() {
+        let (group, other) = (group(), group());
+        let (_, public) = other.generate_keypair(&mut rand::thread_rng());
+        assert_eq!(
+            UnvalidatedRuntimePublicKey::from_openssl_pkey_in(
+                &group,
+                &public.to_openssl_pkey().unwrap()
+            ),
+            Err(DhGroupsError::GroupMismatch)
+        );
+    }
+
+    #[test]
+    fn test_invalid_keys() {
+        let group = group();
+        // 1 is a degenerate public key, imported but not validated
+        let dh = group
+            .to_openssl_dh()
+            .unwrap()
+            .set_public_key(BigNum::from_u32(1).unwrap())
+            .unwrap();
+        let imported = UnvalidatedRuntimePublicKey::from_openssl_pkey(&PKey::from_dh(dh).unwrap());
+        assert_eq!(
+            imported.unwrap().validate(),
+            Err(ValidationError::Degenerate)
+        );
+        // not a Diffie-Hellman key
+        let x25519 = PKey::generate_x25519().unwrap();
+        assert_eq!(
+            RuntimeSecretKey::from_openssl_pkey(&x25519).err(),
+            Some(DhGroupsError::OpenSsl)
+        );
+    }
+}
@@ -0,0 +1,131 @@
+//! Plaintext-equivalence proofs (PEP): a proof that two [ElGamal](crate::elgamal) ciphertexts
+//! under the same public key `h` encrypt the same plaintext, without decrypting them.
+//!
+//! If `ct2 = (c1 * g^delta, c2 * h^delta)`, e.g. when `ct2` is a re-randomization of `ct1`, the
+//! component-wise quotient `ct2 / ct1 = (g^delta, h^delta)` is an encryption of 1, and the proof
+//! is the [DLEQ proof](crate::sigma::DleqProof) of `log_g(c1' / c1) = log_h(c2' / c2)` with
+//! the witness `delta`. If the plaintexts differ, the quotient is not of this form and no proof
+//! exists.
+//!
+//! # Example
+//!
+//! ```rust
+//! use sha2::Sha256;
+//! use diffie_hellman_groups::{elgamal, pep, Element, Scalar, group::MODPGroup5};
+//!
+//! let rng = &mut rand::thread_rng();
+//! let sk = Scalar::<MODPGroup5>::random(rng);
+//! let pk = Element::<MODPGroup5>::from_biguint(sk.value.clone());
+//!
+//! let ct1 = elgamal::encrypt(&pk, &Element::from_biguint(42u32.into()), rng);
+//! let (ct2, delta) = ct1.rerandomize(&pk, rng);
+//!
+//! let proof = pep::prove::<_, Sha256, _>(&delta, &ct1, &ct2, &pk, rng);
+//! assert!(pep::verify::<_, Sha256>(&ct1, &ct2, &pk, &proof));
+//! ```
+
+use rand_core::CryptoRngCore;
+
+use crate::{
+    elgamal::{rerandomization_statement, Ciphertext},
+    group::MODPGroup,
+    sigma::{self, DleqProof},
+    Element, NamedDigest, Scalar,
+};
+
+/// A proof that two ciphertexts encrypt the same plaintext.
+pub type PepProof<G> = DleqProof<G>;
+
+/// Prove that `ct1` and `ct2` encrypt the same plaintext under the public key `pk`, knowing
+/// `r_delta` such that `ct2 / ct1 = (g^r_delta, pk^r_delta)`.
+pub fn prove<G: MODPGroup, D: NamedDigest, R: CryptoRngCore + ?Sized>(
+    r_delta: &Scalar<G>,
+    ct1: &Ciphertext<G>,
+    ct2: &Ciphertext<G>,
+    pk: &Element<G>,
+    rng: &mut R,
+) -> PepProof<G> {
+    sigma::prove::<G, D, R>(&rerandomization_statement(pk, ct1, ct2), r_delta, rng)
+}
+
+/// Verify a proof that `ct1` and `ct2` encrypt the same plaintext under the public key `pk`.
+pub fn verify<G: MODPGroup, D: NamedDigest>(
+    ct1: &Ciphertext<G>,
+    ct2: &Ciphertext<G>,
+    pk: &Element<G>,
+    proof: &PepProof<G>,
+) -> bool {
+    sigma::verify::<G, D>(&rerandomization_statement(pk, ct1, ct2), proof)
+}
+
+#[cfg(test)]
+mod test {
+    use num_bigint::BigUint;
+    use sha2::Sha256;
+
+    use super::*;
+    use crate::{elgamal, group::MODPGroup5};
+
+    type G = MODPGroup5;
+
+    fn key_pair() -> (Scalar<G>, Element<G>) {
+        let sk = Scalar::<G>::random(&mut rand::thread_rng());
+        let pk = Element::from_biguint(sk.value.clone());
+        (sk, pk)
+    }
+
+    #[test]
+    fn test_equal_plaintexts() {
+        let rng = &mut rand::thread_rng();
+        let (_, pk) = key_pair();
+        let m = Element::<G>::from_biguint(BigUint::from(7u64));
+
+        // two independent encryptions: delta is the difference of their randomness
+        let (r1, r2) = (Scalar::<G>::random(rng), Scalar::<G>::random(rng));
+        let encrypt = |r: &Scalar<G>| Ciphertext {
+            c1: Element::from_biguint(r.value.clone()),
+            c2: &m * pk.pow(&r.value),
+        };
+        let (ct1, ct2) = (encrypt(&r1), encrypt(&r2));
+        let proof = prove::<_, Sha256, _>(&(r2 - r1), &ct1, &ct2, &pk, rng);
+        assert!(verify::<_, Sha256>(&ct1, &ct2, &pk, &proof));
+
+        // the proof is bound to the ciphertexts and the key
+        assert!(!verify::<_, Sha256>(&ct2, &ct1, &pk, &proof));
+        let (_, other_pk) = key_pair();
+        assert!(!verify::<_, Sha256>(&ct1, &ct2, &other_pk, &proof));
+    }
+
+    #[test]
+    fn test_different_plaintexts() {
+        let rng = &mut rand::thread_rng();
+        let (sk, pk) = key_pair();
+        let ct1 = elgamal::encrypt(&pk, &Element::from_biguint(BigUint::from(7u64)), rng);
+        let (rerandomized, delta) = ct1.rerandomize(&pk, rng);
+
+        // the same randomness, but the plaintext multiplied by g
+        let ct2 = Ciphertext {
+            c1: rerandomized.c1,
+            c2: rerandomized.c2 * Element::from_biguint(BigUint::from(1u64)),
+        };
+        assert_ne!(elgamal::decrypt(&sk, &ct1), elgamal::decrypt(&sk, &ct2));
+        let proof = prove::<_, Sha256, _>(&delta, &ct1, &ct2, &pk, rng);
+        assert!(!verify::<_, Sha256>(&ct1, &ct2, &pk, &proof));
+    }
+
+    #[test]
+    fn test_serialized_proof() {
+        let rng = &mut rand::thread_rng();
+        let (_, pk) = key_pair();
+        let ct1 = elgamal::encrypt(&pk, &Element::from_biguint(BigUint::from(7u64)), rng);
+        let (ct2, delta) = ct1.rerandomize(&pk, rng);
+        let proof = prove::<_, Sha256, _>(&delta, &ct1, &ct2, &pk, rng);
+
+        let bytes = proof.to_bytes();
+        assert_eq!(bytes.len(), 2 + 2 * 192);
+        let decoded = PepProof::<G>::from_bytes(&bytes).unwrap();
+        assert_eq!(decoded, proof);
+        assert!(verify::<_, Sha256>(&ct1, &ct2, &pk, &decoded));
+        assert!(PepProof::<G>::from_bytes(&bytes[1..]).is_err());
+    }
+}
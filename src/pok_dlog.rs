@@ -0,0 +1,129 @@
+//! Non-interactive proof of knowledge of a discrete logarithm: the Schnorr protocol made
+//! non-interactive with the Fiat-Shamir transform.
+//!
+//! The prover of `y = g^x mod p` picks a random `k`, and computes the challenge
+//! `c = D(len(context) || context || y || g^k) mod q` and the response `s = k + c * x mod q`. The
+//! proof `(c, s)` is verified by recomputing `g^k = g^s * y^(-c)` and the challenge.
+//!
+//! The caller-supplied context, e.g. a protocol name and session identifier, binds the proof to
+//! its use so that it cannot be replayed elsewhere. Attaching such a proof to a broadcast public
+//! key prevents rogue key attacks.
+//!
+//! # Example
+//!
+//! ```rust
+//! use sha2::Sha256;
+//! use diffie_hellman_groups::{pok_dlog, Element, Scalar, group::MODPGroup5};
+//!
+//! let x = Scalar::<MODPGroup5>::random(&mut rand::thread_rng());
+//! let y = Element::<MODPGroup5>::from_biguint(x.value.clone());
+//!
+//! let proof = pok_dlog::prove::<_, Sha256, _>(&x, b"my-dkg session 1", &mut rand::thread_rng());
+//! assert!(pok_dlog::verify::<_, Sha256>(&y, b"my-dkg session 1", &proof));
+//! assert!(!pok_dlog::verify::<_, Sha256>(&y, b"my-dkg session 2", &proof));
+//! ```
+
+use num_bigint::BigUint;
+use rand_core::CryptoRngCore;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{group::MODPGroup, Element, NamedDigest, Scalar};
+
+/// A proof of knowledge of the discrete logarithm of an element, as the challenge `c` and the
+/// response `s`.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(bound = ""))]
+pub struct DlogProof<G: MODPGroup> {
+    pub c: Scalar<G>,
+    pub s: Scalar<G>,
+}
+
+impl<G: MODPGroup> PartialEq for DlogProof<G> {
+    fn eq(&self, other: &Self) -> bool {
+        self.c == other.c && self.s == other.s
+    }
+}
+
+impl<G: MODPGroup> Eq for DlogProof<G> {}
+
+/// Prove knowledge of `x`, the discrete logarithm of `y = g^x mod p`, under `context`.
+pub fn prove<G: MODPGroup, D: NamedDigest, R: CryptoRngCore + ?Sized>(
+    x: &Scalar<G>,
+    context: &[u8],
+    rng: &mut R,
+) -> DlogProof<G> {
+    let y = Element::<G>::from_biguint(x.value.clone());
+    let k = Scalar::<G>::random(rng);
+    let t = Element::<G>::from_biguint(k.value.clone());
+    let c = challenge::<G, D>(context, &y, &t);
+    let s = k + &c * x;
+    DlogProof { c, s }
+}
+
+/// Verify a proof of knowledge of the discrete logarithm of `y`, made under `context`.
+pub fn verify<G: MODPGroup, D: NamedDigest>(
+    y: &Element<G>,
+    context: &[u8],
+    proof: &DlogProof<G>,
+) -> bool {
+    if y.value == BigUint::from(0u64) || y.value >= G::prime_modulus() {
+        return false;
+    }
+    let t = Element::<G>::from_biguint(proof.s.value.clone()) * y.pow(&(-&proof.c).value);
+    challenge::<G, D>(context, y, &t) == proof.c
+}
+
+/// Compute the challenge `c = D(len(context) || context || y || t) mod q`, with the length as a
+/// big-endian `u64` and the elements encoded as in [Element::to_bytes_be].
+fn challenge<G: MODPGroup, D: NamedDigest>(
+    context: &[u8],
+    y: &Element<G>,
+    t: &Element<G>,
+) -> Scalar<G> {
+    let digest = D::new()
+        .chain_update((context.len() as u64).to_be_bytes())
+        .chain_update(context)
+        .chain_update(y.to_bytes_be())
+        .chain_update(t.to_bytes_be())
+        .finalize();
+    Scalar::from_biguint(BigUint::from_bytes_be(&digest))
+}
+
+#[cfg(test)]
+mod test {
+    use sha2::Sha256;
+
+    use super::*;
+    use crate::group::{MODPGroup14, MODPGroup5};
+
+    fn test_prove_verify<G: MODPGroup>() {
+        let rng = &mut rand::thread_rng();
+        let x = Scalar::<G>::random(rng);
+        let y = Element::<G>::from_biguint(x.value.clone());
+        let other_y = Element::<G>::from_biguint(Scalar::<G>::random(rng).value);
+
+        let proof = prove::<G, Sha256, _>(&x, b"context", rng);
+        assert!(verify::<G, Sha256>(&y, b"context", &proof));
+
+        assert!(!verify::<G, Sha256>(&y, b"other context", &proof));
+        assert!(!verify::<G, Sha256>(&y, b"", &proof));
+        assert!(!verify::<G, Sha256>(&other_y, b"context", &proof));
+
+        let forged = DlogProof {
+            c: Scalar::from_biguint(proof.c.value.clone()),
+            s: &proof.s + Scalar::from_biguint(BigUint::from(1u64)),
+        };
+        assert!(!verify::<G, Sha256>(&y, b"context", &forged));
+    }
+
+    #[test]
+    fn test_prove_verify_group_5() {
+        test_prove_verify::<MODPGroup5>();
+    }
+
+    #[test]
+    fn test_prove_verify_group_14() {
+        test_prove_verify::<MODPGroup14>();
+    }
+}
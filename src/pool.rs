@@ -0,0 +1,500 @@
+//! A pool of ephemeral key pairs generated ahead of time on background threads, so that a server
+//! running many Diffie-Hellman handshakes does not pay for the exponentiation `g^x` in the
+//! handshake itself.
+//!
+//! The workers of a [KeyPool] fill it up to [PoolConfig::target] keys, then sleep until it falls
+//! below [PoolConfig::low_watermark]. [KeyPool::take] hands out each key once, as an
+//! [EphemeralSecret] consumed by its key exchange; if the pool is empty, the key is generated in
+//! the calling thread and counted as a miss. With the feature `parallel`, each worker generates
+//! its keys in batches on the rayon thread pool.
+//!
+//! The secret exponents are overwritten with zeros when the keys are dropped, so that dropping
+//! the pool wipes the keys it still holds.
+//!
+//! # Example
+//!
+//! ```rust
+//! use diffie_hellman_groups::{
+//!     group::MODPGroup5,
+//!     pool::{KeyPool, PoolConfig},
+//! };
+//!
+//! let pool = KeyPool::<MODPGroup5>::new(PoolConfig {
+//!     target: 16,
+//!     low_watermark: 4,
+//!     ..PoolConfig::default()
+//! });
+//!
+//! let alice = pool.take();
+//! let bob = pool.take();
+//! let alice_public = alice.public_key().clone();
+//! let shared = alice.diffie_hellman(bob.public_key());
+//! assert_eq!(
+//!     shared.to_bytes_be(),
+//!     bob.diffie_hellman(&alice_public).to_bytes_be()
+//! );
+//! ```
+
+use std::{
+    collections::VecDeque,
+    fmt,
+    sync::{Arc, Condvar, Mutex, MutexGuard},
+    thread::JoinHandle,
+};
+
+use num_bigint::{BigUint, RandBigInt};
+use rand_core::CryptoRngCore;
+
+use crate::{group::MODPGroup, kdf::SharedSecret, scalar::wipe, Element, PublicKey};
+
+/// Sizes and exponents of a [KeyPool].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PoolConfig {
+    /// Number of keys the workers fill the pool up to.
+    pub target: usize,
+    /// The workers start refilling the pool when it holds fewer keys than this.
+    pub low_watermark: usize,
+    /// Bit length of the secret exponents, drawn from `[1, min(2^bits, q))`, or `None` for
+    /// exponents drawn from `[1, q)`.
+    pub exponent_bits: Option<u64>,
+    /// Number of background threads generating the keys.
+    pub threads: usize,
+}
+
+impl Default for PoolConfig {
+    fn default() -> Self {
+        Self {
+            target: 64,
+            low_watermark: 16,
+            exponent_bits: None,
+            threads: 1,
+        }
+    }
+}
+
+/// Hooks notified by a [KeyPool], e.g. to export its state as metrics. They are called from the
+/// threads taking keys and from the workers, so they should return quickly.
+pub trait PoolMetrics: Send + Sync {
+    /// A key was taken from the pool, which now holds `size` keys.
+    fn taken(&self, size: usize) {
+        let _ = size;
+    }
+
+    /// A key was requested from the empty pool and generated by the caller.
+    fn missed(&self) {}
+
+    /// A worker added keys to the pool, which now holds `size` keys.
+    fn refilled(&self, size: usize) {
+        let _ = size;
+    }
+}
+
+/// An ephemeral key pair `(x, g^x)`, used for a single key exchange. The secret exponent is
+/// overwritten with zeros when dropped.
+pub struct EphemeralSecret<G: MODPGroup> {
+    x: BigUint,
+    public: PublicKey<G>,
+}
+
+impl<G: MODPGroup> EphemeralSecret<G> {
+    /// Generate a key pair with a secret exponent drawn from `[1, min(2^exponent_bits, q))`, or
+    /// from `[1, q)` if `exponent_bits` is `None`.
+    pub fn generate<R: CryptoRngCore + ?Sized>(rng: &mut R, exponent_bits: Option<u64>) -> Self {
+        let q = G::subgroup_order();
+        let bound = match exponent_bits {
+            Some(bits) => (BigUint::from(1u64) << bits).min(q),
+            None => q,
+        };
+        let x = rng.gen_biguint_range(&BigUint::from(1u64), &bound);
+        let public = pow_secret(&Element::from_value(G::generator()), &x);
+        Self {
+            x,
+            public: PublicKey::from_secret_power(public),
+        }
+    }
+
+    /// The public key g^x, to send to the peer.
+    pub fn public_key(&self) -> &PublicKey<G> {
+        &self.public
+    }
+
+    /// Compute the shared secret with the validated public key of a peer, consuming the key so
+    /// that it is used only once.
+    pub fn diffie_hellman(self, peer: &PublicKey<G>) -> SharedSecret<G> {
+        SharedSecret::new(pow_secret(peer.as_element(), &self.x))
+    }
+}
+
+impl<G: MODPGroup> Drop for EphemeralSecret<G> {
+    fn drop(&mut self) {
+        wipe(&mut self.x);
+    }
+}
+
+impl<G: MODPGroup> fmt::Debug for EphemeralSecret<G> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EphemeralSecret")
+            .field("public", &self.public)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Compute base^secret mod p.
+#[cfg(not(feature = "zeroize"))]
+fn pow_secret<G: MODPGroup>(base: &Element<G>, secret: &BigUint) -> Element<G> {
+    base.pow(secret)
+}
+
+/// Compute base^secret mod p, wiping the temporaries.
+#[cfg(feature = "zeroize")]
+fn pow_secret<G: MODPGroup>(base: &Element<G>, secret: &BigUint) -> Element<G> {
+    base.pow_zeroizing(secret)
+}
+
+/// A pool of [EphemeralSecret]s refilled by background threads, see the module documentation.
+pub struct KeyPool<G: MODPGroup + Send + 'static> {
+    shared: Arc<Shared<G>>,
+    workers: Vec<JoinHandle<()>>,
+}
+
+/// The state shared by the pool and its workers.
+struct Shared<G: MODPGroup> {
+    config: PoolConfig,
+    metrics: Option<Arc<dyn PoolMetrics>>,
+    state: Mutex<State<G>>,
+    /// Wakes up the workers when the pool falls below the low watermark or is dropped.
+    refill: Condvar,
+}
+
+struct State<G: MODPGroup> {
+    keys: VecDeque<EphemeralSecret<G>>,
+    /// Number of keys being generated by the workers.
+    in_flight: usize,
+    /// Whether the workers fill the pool, from falling below the low watermark until reaching
+    /// the target.
+    filling: bool,
+    misses: u64,
+    shutdown: bool,
+}
+
+impl<G: MODPGroup + Send + 'static> KeyPool<G> {
+    /// Create a pool and start its workers.
+    ///
+    /// # Panics
+    /// Panics if `config.threads` is 0 or `config.low_watermark` is greater than
+    /// `config.target`.
+    pub fn new(config: PoolConfig) -> Self {
+        Self::build(config, None)
+    }
+
+    /// Create a pool as in [KeyPool::new], notifying `metrics`.
+    pub fn with_metrics(config: PoolConfig, metrics: Arc<dyn PoolMetrics>) -> Self {
+        Self::build(config, Some(metrics))
+    }
+
+    fn build(config: PoolConfig, metrics: Option<Arc<dyn PoolMetrics>>) -> Self {
+        assert!(config.threads > 0, "a key pool needs a worker thread");
+        assert!(
+            config.low_watermark <= config.target,
+            "the low watermark of a key pool exceeds its target"
+        );
+        let shared = Arc::new(Shared {
+            state: Mutex::new(State {
+                keys: VecDeque::with_capacity(config.target),
+                in_flight: 0,
+                filling: true,
+                misses: 0,
+                shutdown: false,
+            }),
+            config,
+            metrics,
+            refill: Condvar::new(),
+        });
+        let workers = (0..shared.config.threads)
+            .map(|_| {
+                let shared = shared.clone();
+                std::thread::spawn(move || shared.work())
+            })
+            .collect();
+        Self { shared, workers }
+    }
+
+    /// Take a key from the pool, or generate one if the pool is empty. Each key is handed out
+    /// once.
+    pub fn take(&self) -> EphemeralSecret<G> {
+        let mut state = self.shared.lock();
+        let key = state.keys.pop_front();
+        let size = state.keys.len();
+        if size < self.shared.config.low_watermark && !state.filling {
+            state.filling = true;
+            self.shared.refill.notify_all();
+        }
+        if key.is_none() {
+            state.misses += 1;
+        }
+        drop(state);
+
+        let metrics = self.shared.metrics.as_deref();
+        match key {
+            Some(key) => {
+                if let Some(metrics) = metrics {
+                    metrics.taken(size);
+                }
+                key
+            }
+            None => {
+                if let Some(metrics) = metrics {
+                    metrics.missed();
+                }
+                EphemeralSecret::generate(&mut rand::thread_rng(), self.shared.config.exponent_bits)
+            }
+        }
+    }
+
+    /// The number of keys in the pool.
+    pub fn len(&self) -> usize {
+        self.shared.lock().keys.len()
+    }
+
+    /// Whether the pool holds no key.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The number of keys taken from the empty pool since its creation.
+    pub fn misses(&self) -> u64 {
+        self.shared.lock().misses
+    }
+
+    /// The configuration of the pool.
+    pub fn config(&self) -> &PoolConfig {
+        &self.shared.config
+    }
+}
+
+/// Stops the workers and wipes the keys left in the pool.
+impl<G: MODPGroup + Send + 'static> Drop for KeyPool<G> {
+    fn drop(&mut self) {
+        self.shared.lock().shutdown = true;
+        self.shared.refill.notify_all();
+        for worker in self.workers.drain(..) {
+            let _ = worker.join();
+        }
+        self.shared.lock().keys.clear();
+    }
+}
+
+impl<G: MODPGroup + Send + 'static> fmt::Debug for KeyPool<G> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("KeyPool")
+            .field("config", &self.shared.config)
+            .field("len", &self.len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<G: MODPGroup + Send> Shared<G> {
+    fn lock(&self) -> MutexGuard<'_, State<G>> {
+        // the state stays consistent if a thread panics while holding the lock
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Generate keys until the pool is dropped.
+    fn work(&self) {
+        let mut rng = rand::thread_rng();
+        while let Some(count) = self.claim() {
+            let keys = self.generate(count, &mut rng);
+            let mut state = self.lock();
+            state.in_flight -= count;
+            state.keys.extend(keys);
+            let size = state.keys.len();
+            if size >= self.config.target {
+                state.filling = false;
+            }
+            drop(state);
+            if let Some(metrics) = &self.metrics {
+                metrics.refilled(size);
+            }
+        }
+    }
+
+    /// Wait until the pool needs keys and reserve the number of keys to generate, or return `None`
+    /// when the pool is dropped.
+    fn claim(&self) -> Option<usize> {
+        let mut state = self.lock();
+        loop {
+            if state.shutdown {
+                return None;
+            }
+            let missing = self
+                .config
+                .target
+                .saturating_sub(state.keys.len() + state.in_flight);
+            if state.filling && missing > 0 {
+                let count = missing.min(Self::batch_size());
+                state.in_flight += count;
+                return Some(count);
+            }
+            state = self.refill.wait(state).unwrap_or_else(|e| e.into_inner());
+        }
+    }
+
+    /// The number of keys generated at once by a worker.
+    #[cfg(not(feature = "parallel"))]
+    fn batch_size() -> usize {
+        1
+    }
+
+    #[cfg(feature = "parallel")]
+    fn batch_size() -> usize {
+        rayon::current_num_threads()
+    }
+
+    #[cfg(not(feature = "parallel"))]
+    fn generate<R: CryptoRngCore>(&self, count: usize, rng: &mut R) -> Vec<EphemeralSecret<G>> {
+        (0..count)
+            .map(|_| EphemeralSecret::generate(rng, self.config.exponent_bits))
+            .collect()
+    }
+
+    #[cfg(feature = "parallel")]
+    fn generate<R: CryptoRngCore>(&self, count: usize, _rng: &mut R) -> Vec<EphemeralSecret<G>> {
+        use rayon::prelude::*;
+
+        (0..count)
+            .into_par_iter()
+            .map(|_| EphemeralSecret::generate(&mut rand::thread_rng(), self.config.exponent_bits))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        collections::HashSet,
+        sync::atomic::{AtomicU64, AtomicUsize, Ordering},
+        time::{Duration, Instant},
+    };
+
+    use super::*;
+    use crate::{MODPGroup5, UnvalidatedPublicKey};
+
+    #[derive(Default)]
+    struct Counters {
+        taken: AtomicU64,
+        missed: AtomicU64,
+        max_size: AtomicUsize,
+    }
+
+    impl PoolMetrics for Counters {
+        fn taken(&self, _size: usize) {
+            self.taken.fetch_add(1, Ordering::Relaxed);
+        }
+
+        fn missed(&self) {
+            self.missed.fetch_add(1, Ordering::Relaxed);
+        }
+
+        fn refilled(&self, size: usize) {
+            self.max_size.fetch_max(size, Ordering::Relaxed);
+        }
+    }
+
+    fn wait_for(pool: &KeyPool<MODPGroup5>, len: usize) {
+        let start = Instant::now();
+        while pool.len() < len {
+            assert!(
+                start.elapsed() < Duration::from_secs(60),
+                "the pool is not refilled"
+            );
+            std::thread::sleep(Duration::from_millis(5));
+        }
+    }
+
+    #[test]
+    fn test_refill() {
+        let metrics = Arc::new(Counters::default());
+        let pool = KeyPool::<MODPGroup5>::with_metrics(
+            PoolConfig {
+                target: 8,
+                low_watermark: 4,
+                exponent_bits: Some(64),
+                threads: 2,
+            },
+            metrics.clone(),
+        );
+        wait_for(&pool, 8);
+
+        // above the low watermark, the pool is not refilled
+        for _ in 0..4 {
+            let key = pool.take();
+            assert!(key.x.bits() <= 64);
+        }
+        std::thread::sleep(Duration::from_millis(50));
+        assert_eq!(pool.len(), 4);
+
+        // below it, the pool is filled up to the target
+        drop(pool.take());
+        wait_for(&pool, 8);
+        std::thread::sleep(Duration::from_millis(50));
+        assert_eq!(pool.len(), 8);
+        assert_eq!(metrics.max_size.load(Ordering::Relaxed), 8);
+        assert_eq!(pool.misses(), 0);
+        assert_eq!(metrics.taken.load(Ordering::Relaxed), 5);
+    }
+
+    #[test]
+    fn test_stress() {
+        let metrics = Arc::new(Counters::default());
+        let pool = KeyPool::<MODPGroup5>::with_metrics(
+            PoolConfig {
+                target: 16,
+                low_watermark: 8,
+                exponent_bits: Some(128),
+                threads: 2,
+            },
+            metrics.clone(),
+        );
+
+        // keys taken from several threads while the pool refills are all distinct
+        let publics: Vec<BigUint> = std::thread::scope(|s| {
+            let takers: Vec<_> = (0..8)
+                .map(|_| {
+                    s.spawn(|| {
+                        (0..40)
+                            .map(|_| pool.take().public_key().as_element().value.clone())
+                            .collect::<Vec<_>>()
+                    })
+                })
+                .collect();
+            takers
+                .into_iter()
+                .flat_map(|taker| taker.join().unwrap())
+                .collect()
+        });
+        assert_eq!(publics.len(), 320);
+        assert_eq!(publics.iter().collect::<HashSet<_>>().len(), 320);
+
+        let taken = metrics.taken.load(Ordering::Relaxed);
+        let missed = metrics.missed.load(Ordering::Relaxed);
+        assert_eq!(taken + missed, 320);
+        assert_eq!(pool.misses(), missed);
+    }
+
+    #[test]
+    fn test_key_exchange() {
+        let rng = &mut rand::thread_rng();
+        let alice = EphemeralSecret::<MODPGroup5>::generate(rng, None);
+        let bob = EphemeralSecret::<MODPGroup5>::generate(rng, Some(256));
+        assert_eq!(alice.public_key().as_element().validate(), Ok(()));
+        let alice_public = UnvalidatedPublicKey::from_bytes_be(&alice.public_key().to_bytes_be())
+            .unwrap()
+            .validate()
+            .unwrap();
+        let bob_public = bob.public_key().clone();
+        assert_eq!(
+            alice.diffie_hellman(&bob_public).to_bytes_be(),
+            bob.diffie_hellman(&alice_public).to_bytes_be()
+        );
+    }
+}
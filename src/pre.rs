@@ -0,0 +1,127 @@
+//! Proxy re-encryption of [ElGamal](crate::elgamal) ciphertexts in the style of Blaze, Bleumer
+//! and Strauss (BBS98): a proxy holding a re-encryption key from A to B turns ciphertexts for A
+//! into ciphertexts for B, without learning the messages nor either secret key.
+//!
+//! With the secret keys `a` and `b`, the re-encryption key is `rk = a / b mod q` ([rekey]), and a
+//! ciphertext `(g^r, m * g^(ar))` for A is re-encrypted as `((g^r)^rk, m * g^(ar))`
+//! ([reencrypt]), i.e. `(g^(ar/b), m * (g^(ar/b))^b)`, a ciphertext for B that
+//! [decrypt](crate::elgamal::decrypt) accepts.
+//!
+//! The scheme requires trust in both directions: the key is computed from both secret keys, so
+//! A and B must cooperate (or share with a trusted party) to create it, and its inverse
+//! ([ReKey::invert]) re-encrypts from B to A. A proxy colluding with either party learns the
+//! secret key of the other.
+//!
+//! # Example
+//!
+//! ```rust
+//! use diffie_hellman_groups::{elgamal, pre, Element, Scalar, group::MODPGroup5};
+//!
+//! let rng = &mut rand::thread_rng();
+//! let (sk_a, sk_b) = (Scalar::<MODPGroup5>::random(rng), Scalar::<MODPGroup5>::random(rng));
+//! let pk_a = Element::<MODPGroup5>::from_biguint(sk_a.value.clone());
+//!
+//! let m = Element::<MODPGroup5>::from_biguint(42u32.into());
+//! let ct = elgamal::encrypt(&pk_a, &m, rng);
+//!
+//! let rk = pre::rekey(&sk_a, &sk_b);
+//! let ct_b = pre::reencrypt(&rk, &ct);
+//! assert_eq!(elgamal::decrypt(&sk_b, &ct_b), m);
+//! ```
+
+use std::fmt;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{elgamal::Ciphertext, group::MODPGroup, Element, Scalar};
+
+/// A re-encryption key `a / b mod q` from A to B, given to the proxy.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(bound = ""))]
+pub struct ReKey<G: MODPGroup> {
+    rk: Scalar<G>,
+}
+
+impl<G: MODPGroup> ReKey<G> {
+    /// The re-encryption key from B to A.
+    pub fn invert(&self) -> Self {
+        ReKey {
+            rk: self.rk.inverse().expect("re-encryption key is non-zero"),
+        }
+    }
+}
+
+impl<G: MODPGroup> fmt::Debug for ReKey<G> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ReKey").finish_non_exhaustive()
+    }
+}
+
+/// Create the re-encryption key from A, with the secret key `sk_a`, to B, with the secret key
+/// `sk_b`.
+///
+/// # Panics
+/// Panics if `sk_b` is zero.
+pub fn rekey<G: MODPGroup>(sk_a: &Scalar<G>, sk_b: &Scalar<G>) -> ReKey<G> {
+    let inv_b = sk_b.inverse().expect("secret key is non-zero");
+    ReKey { rk: sk_a * inv_b }
+}
+
+/// Re-encrypt `ct`, a ciphertext for A, into a ciphertext of the same message for B.
+pub fn reencrypt<G: MODPGroup>(rk: &ReKey<G>, ct: &Ciphertext<G>) -> Ciphertext<G> {
+    Ciphertext {
+        c1: ct.c1.pow(&rk.rk.value),
+        c2: Element::from_value(ct.c2.value.clone()),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use num_bigint::BigUint;
+
+    use super::*;
+    use crate::{elgamal, group::MODPGroup5};
+
+    type G = MODPGroup5;
+
+    #[test]
+    fn test_reencryption() {
+        let rng = &mut rand::thread_rng();
+        let (sk_a, sk_b, sk_c) = (
+            Scalar::<G>::random(rng),
+            Scalar::<G>::random(rng),
+            Scalar::<G>::random(rng),
+        );
+        let pk_a = Element::<G>::from_biguint(sk_a.value.clone());
+
+        let m = Element::<G>::from_biguint(BigUint::from(1234u64));
+        let ct = elgamal::encrypt(&pk_a, &m, rng);
+        assert_ne!(elgamal::decrypt(&sk_b, &ct), m);
+
+        let rk = rekey(&sk_a, &sk_b);
+        let ct_b = reencrypt(&rk, &ct);
+        assert_eq!(elgamal::decrypt(&sk_b, &ct_b), m);
+        assert_ne!(elgamal::decrypt(&sk_a, &ct_b), m);
+        assert_ne!(elgamal::decrypt(&sk_c, &ct_b), m);
+
+        // and back to A
+        assert_eq!(reencrypt(&rk.invert(), &ct_b), ct);
+    }
+
+    #[test]
+    fn test_homomorphism() {
+        let rng = &mut rand::thread_rng();
+        let (sk_a, sk_b) = (Scalar::<G>::random(rng), Scalar::<G>::random(rng));
+        let pk_a = Element::<G>::from_biguint(sk_a.value.clone());
+        let rk = rekey(&sk_a, &sk_b);
+
+        let m1 = Element::<G>::from_biguint(BigUint::from(6u64));
+        let m2 = Element::<G>::from_biguint(BigUint::from(7u64));
+        let ct1 = elgamal::encrypt(&pk_a, &m1, rng);
+        let ct2 = elgamal::encrypt(&pk_a, &m2, rng);
+
+        let product = reencrypt(&rk, &ct1) * reencrypt(&rk, &ct2);
+        assert_eq!(elgamal::decrypt(&sk_b, &product), &m1 * &m2);
+        assert_eq!(reencrypt(&rk, &(ct1 * ct2)), product);
+    }
+}
@@ -1,12 +1,101 @@
-use num_bigint::{BigUint, RandomBits};
-use num_prime::{nt_funcs, Primality};
-use rand::Rng;
+use std::{fmt, ops::ControlFlow, sync::Arc};
 
-use crate::MODPGroup;
+use num_bigint::{BigUint, RandBigInt};
+use num_prime::nt_funcs;
+use rand_core::CryptoRngCore;
+
+use crate::{
+    hash::expand_message, text::write_dhparam_text, AnyModpGroup, DhGroupsError, GeneratorSpec,
+    MODPGroup, NamedDigest, PrimeGroupBuilder, RuntimePublicKey, RuntimeSecretKey, ValidationError,
+};
+
+/// Number of candidates tested by [PrimeGroup::generate] between two calls of the [ProgressHook].
+/// Candidates sieved out by small primes are not counted.
+pub const PROGRESS_INTERVAL: u64 = 16;
+
+/// Hook notified of the progress of [PrimeGroup::generate].
+///
+/// It is also implemented for closures `Fn(u64) -> ControlFlow<()>`. The hook must be `Sync`
+/// since it may be called from several threads with the `parallel` feature.
+pub trait ProgressHook: Sync {
+    /// Called every [PROGRESS_INTERVAL] candidates with the number of candidates tested so far.
+    /// Returning `ControlFlow::Break(())` cancels the generation.
+    fn on_candidate(&self, attempts: u64) -> ControlFlow<()>;
+}
+
+impl<F: Fn(u64) -> ControlFlow<()> + Sync> ProgressHook for F {
+    fn on_candidate(&self, attempts: u64) -> ControlFlow<()> {
+        self(attempts)
+    }
+}
+
+/// Default of [GroupPolicy::max_modulus_bits], twice the size of the largest group of RFC 3526.
+pub const DEFAULT_MAX_MODULUS_BITS: u64 = 16384;
+
+/// Limits on the parameters of a group received from a peer or imported, checked before the
+/// primality tests so that a huge modulus cannot turn the validation into a denial of service.
+///
+/// # Example
+///
+/// ```rust
+/// use num_bigint::BigUint;
+/// use diffie_hellman_groups::{DhGroupsError, GroupPolicy, PrimeGroup};
+///
+/// let pg = PrimeGroup::new_with(BigUint::from(1623299u64), 15).unwrap();
+/// let policy = GroupPolicy { max_modulus_bits: 16 };
+/// assert_eq!(
+///     pg.validate_with_policy(&policy),
+///     Err(DhGroupsError::ModulusTooLarge { bits: 21, max: 16 })
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GroupPolicy {
+    /// Largest number of bits of the modulus, [DEFAULT_MAX_MODULUS_BITS] by default.
+    pub max_modulus_bits: u64,
+}
+
+impl Default for GroupPolicy {
+    fn default() -> Self {
+        Self {
+            max_modulus_bits: DEFAULT_MAX_MODULUS_BITS,
+        }
+    }
+}
+
+impl GroupPolicy {
+    /// Check that the modulus `p` has at most [GroupPolicy::max_modulus_bits] bits.
+    ///
+    /// # Errors
+    /// Returns [DhGroupsError::ModulusTooLarge] otherwise.
+    pub fn check_modulus(&self, p: &BigUint) -> Result<(), DhGroupsError> {
+        if p.bits() > self.max_modulus_bits {
+            return Err(DhGroupsError::ModulusTooLarge {
+                bits: p.bits(),
+                max: self.max_modulus_bits,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Order of the generator of a [PrimeGroup], as reported by [PrimeGroup::generator_order].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GeneratorOrder {
+    /// The generator is not in `[1, p)`, so it is not an element of the group.
+    OutOfRange,
+    /// The generator is 1.
+    One,
+    /// The generator is `p - 1`, of order 2.
+    Two,
+    /// The generator has the prime order `q`.
+    Q,
+    /// Any other order, e.g. 2q for a generator of the whole group modulo a safe prime.
+    Other,
+}
 
 /// PrimeGroup represents a group of a prime order `q` of a group with a prime modulus `p`,
 /// and a generator `g` such that g^q mod p = 1.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct PrimeGroup {
     /// Prime modulus.
     pub p: BigUint,
@@ -14,52 +103,65 @@ pub struct PrimeGroup {
     pub q: BigUint,
     /// Generator of the group, g^q mod p = 1.
     pub g: BigUint,
+    /// Cofactor of the group, such that q * cofactor = p - 1. It is 2 for safe primes.
+    pub cofactor: BigUint,
 }
 
 impl PrimeGroup {
-    /// Create a new group from `MODPGroup` with a different generator of `num_bits` bits.
+    /// Start building a group with [PrimeGroupBuilder].
+    pub fn builder<'a>() -> PrimeGroupBuilder<'a> {
+        PrimeGroupBuilder::new()
+    }
+
+    /// Create a new group from `MODPGroup` with a different generator.
     ///
-    /// # Panics
-    /// Panics if `num_bits` is less than 2 or greater than the number of bits in the prime modulus `p`.
+    /// The generator is `g = h^2 mod p` for `h` sampled uniformly from `[2, min(2^num_bits, p - 1))`,
+    /// which always lies in the subgroup of order `q`. It is resampled only if it equals the
+    /// default generator of `G`.
+    ///
+    /// # Errors
+    /// Returns [DhGroupsError::BitsOutOfRange] if `num_bits` is less than 2 or greater than the number
+    /// of bits in the prime modulus `p`.
     ///
     /// # Example
     ///
     /// ```rust
     /// use diffie_hellman_groups::{PrimeGroup, group::{MODPGroup, MODPGroup5}};
     ///
-    /// let pg = PrimeGroup::new::<MODPGroup5>(128);
+    /// let pg = PrimeGroup::new::<MODPGroup5>(128).unwrap();
     /// println!("{:?}", pg);
     /// assert!(pg.g != MODPGroup5::generator());
     /// ```
-    pub fn new<G: MODPGroup>(num_bits: usize) -> Self {
-        let p = G::prime_modulus();
-        let q = G::sophie_garmain_prime();
-        assert!(num_bits >= 2 && num_bits <= p.bits() as usize);
-        let g;
-
-        let rng = &mut rand::thread_rng();
-        loop {
-            let a = rng.sample::<BigUint, _>(RandomBits::new(num_bits as u64));
-            if a != G::generator() {
-                let res = a.modpow(&q, &p);
-                if res == BigUint::from(1u64) {
-                    g = a;
-                    break;
-                }
-            }
-        }
+    pub fn new<G: MODPGroup>(num_bits: u64) -> Result<Self, DhGroupsError> {
+        Self::new_from_rng::<G, _>(num_bits, &mut rand::thread_rng())
+    }
 
-        Self { p, q, g }
+    /// Create a new group from `MODPGroup` with a different generator sampled from `rng`, as in
+    /// [PrimeGroup::new], e.g. to reproduce a group with a seeded generator.
+    ///
+    /// # Errors
+    /// Returns [DhGroupsError::BitsOutOfRange] as [PrimeGroup::new].
+    pub fn new_from_rng<G: MODPGroup, R: CryptoRngCore + ?Sized>(
+        num_bits: u64,
+        rng: &mut R,
+    ) -> Result<Self, DhGroupsError> {
+        Self::builder()
+            .trusted(G::prime_modulus(), G::subgroup_order())
+            .generator(GeneratorSpec::RandomBits(num_bits))
+            .exclude_generator(G::generator())
+            .build(rng)
     }
 
-    /// Create a new prime group with a generator of `generator_num_bits` bits and order of `order_num_bits` bits.
-    /// The prime modulus `q` is set such that p = 2q + 1 . The generator `g` is generated randomly such that
-    /// g^q mod p = 1.
+    /// Create a new prime group from the safe prime `p`.
+    /// The order `q` is set such that p = 2q + 1 . The generator is `g = h^2 mod p` for `h` sampled
+    /// uniformly from `[2, min(2^generator_num_bits, p - 1))`, so that g^q mod p = 1. It is sampled
+    /// with `rand::thread_rng()`; [PrimeGroup::builder] takes the random number generator instead.
     ///
-    /// # Panics
-    /// Panics if
-    /// - `generator_num_bits` is less than 2 or greater than the number of bits in the prime modulus `p`.
-    /// - `p` is not a safe prime.
+    /// # Errors
+    /// Returns
+    /// - [DhGroupsError::BitsOutOfRange] if `generator_num_bits` is less than 2 or greater than the
+    ///   number of bits in the prime modulus `p`.
+    /// - [DhGroupsError::NotSafePrime] if `p` is not a safe prime.
     ///
     /// # Example
     ///
@@ -68,26 +170,910 @@ impl PrimeGroup {
     /// use diffie_hellman_groups::PrimeGroup;
     ///
     /// let p = BigUint::from(1623299u64);
-    /// let pg = PrimeGroup::new_with(p, 15);
+    /// let pg = PrimeGroup::new_with(p, 15).unwrap();
     /// println!("{:?}", pg);
     /// ```
-    pub fn new_with(p: BigUint, generator_num_bits: usize) -> Self {
-        assert!(generator_num_bits >= 2);
-        assert!(generator_num_bits <= p.bits() as usize);
-        assert!(nt_funcs::is_safe_prime(&p) == Primality::Yes);
-
-        // q is a sophie germain prime
-        let q = (&p - BigUint::from(1u64)) / BigUint::from(2u64);
-        let g;
-        let rng = &mut rand::thread_rng();
-        loop {
-            let a = rng.sample::<BigUint, _>(RandomBits::new(generator_num_bits as u64));
-            let res = a.modpow(&q, &p);
-            if res == BigUint::from(1u64) {
-                g = a;
-                break;
+    pub fn new_with(p: BigUint, generator_num_bits: u64) -> Result<Self, DhGroupsError> {
+        Self::builder()
+            .from_existing_prime(p)
+            .generator(GeneratorSpec::RandomBits(generator_num_bits))
+            .build(&mut rand::thread_rng())
+    }
+
+    /// Create a Schnorr group, i.e. the subgroup of prime order `q` of the multiplicative group
+    /// modulo the prime `p`, where `q` divides `p - 1`. The cofactor is `(p - 1) / q`, and the
+    /// generator is `g = h^cofactor mod p` for `h` sampled uniformly from `[2, p - 1)` with
+    /// `rand::thread_rng()`; [PrimeGroup::builder] takes the random number generator instead.
+    ///
+    /// # Errors
+    /// Returns [DhGroupsError::NotPrime] if `p` or `q` is not prime, or [DhGroupsError::InvalidCofactor]
+    /// if `q` does not divide `p - 1`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use num_bigint::BigUint;
+    /// use diffie_hellman_groups::PrimeGroup;
+    ///
+    /// // p - 1 = 2 * 3 * 5 * 7 * 17
+    /// let pg = PrimeGroup::new_schnorr(BigUint::from(3571u64), BigUint::from(17u64)).unwrap();
+    /// assert_eq!(pg.cofactor, BigUint::from(210u64));
+    /// assert!(pg.validate().is_ok());
+    /// ```
+    pub fn new_schnorr(p: BigUint, q: BigUint) -> Result<Self, DhGroupsError> {
+        Self::builder()
+            .from_schnorr(p, q)
+            .build(&mut rand::thread_rng())
+    }
+
+    /// Generate a new prime group with a random safe prime modulus `p` of exactly `bits` bits.
+    ///
+    /// Candidates `q` of `bits - 1` bits are tested until both `q` and `p = 2q + 1` are prime, see
+    /// [find_sophie_germain](crate::primes::find_sophie_germain). They are scanned incrementally
+    /// from a random odd starting point, and a window of candidates is first sieved by a few
+    /// thousand small primes (for both `q` and `2q + 1`), so that only the survivors go through
+    /// the probabilistic primality tests. If `progress` is given, it is
+    /// notified every [PROGRESS_INTERVAL] tested candidates and may cancel the search.
+    ///
+    /// With the `parallel` feature, the search runs on all threads of the global rayon pool, each
+    /// worker being seeded from `rng`. The first worker finding a safe prime stops the others, so
+    /// the result is not deterministic for a given `rng`. The generator is derived as in [PrimeGroup::new_with].
+    ///
+    /// # Errors
+    /// Returns [DhGroupsError::BitsOutOfRange] if `bits` is less than 3, or
+    /// [DhGroupsError::Cancelled] if the search is cancelled by `progress`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use diffie_hellman_groups::PrimeGroup;
+    ///
+    /// let pg = PrimeGroup::generate(64, &mut rand::thread_rng(), None).unwrap();
+    /// assert_eq!(pg.p.bits(), 64);
+    /// assert!(pg.validate().is_ok());
+    /// ```
+    pub fn generate<R: CryptoRngCore + ?Sized>(
+        bits: u64,
+        rng: &mut R,
+        progress: Option<&dyn ProgressHook>,
+    ) -> Result<Self, DhGroupsError> {
+        let builder = Self::builder()
+            .modulus_bits(bits)
+            .generator(GeneratorSpec::RandomBits(bits));
+        match progress {
+            Some(hook) => builder.progress(hook).build(rng),
+            None => builder.build(rng),
+        }
+    }
+
+    /// Check that `x` is an element of the multiplicative group modulo `p`, i.e. 0 < x < p.
+    pub fn is_valid_element(&self, x: &BigUint) -> bool {
+        *x > BigUint::from(0u64) && *x < self.p
+    }
+
+    /// Check that `x` is in the subgroup of order `q`, i.e. x^q mod p = 1.
+    pub fn is_subgroup_member(&self, x: &BigUint) -> bool {
+        self.is_valid_element(x) && x.modpow(&self.q, &self.p) == BigUint::from(1u64)
+    }
+
+    /// Validate a public key `y` received from a peer: it must be in range, not be 1 or `p - 1`,
+    /// and lie in the subgroup of order `q`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use num_bigint::BigUint;
+    /// use diffie_hellman_groups::{PrimeGroup, ValidationError};
+    ///
+    /// let pg = PrimeGroup::new_with(BigUint::from(1623299u64), 15).unwrap();
+    /// assert!(pg.validate_public_key(&pg.g).is_ok());
+    /// assert_eq!(pg.validate_public_key(&BigUint::from(1u64)), Err(ValidationError::Degenerate));
+    /// ```
+    pub fn validate_public_key(&self, y: &BigUint) -> Result<(), ValidationError> {
+        if !self.is_valid_element(y) {
+            return Err(ValidationError::OutOfRange);
+        }
+        if *y == BigUint::from(1u64) || *y == &self.p - 1u64 {
+            return Err(ValidationError::Degenerate);
+        }
+        if !self.is_subgroup_member(y) {
+            return Err(ValidationError::NotInSubgroup);
+        }
+        Ok(())
+    }
+
+    /// Compute the public key g^secret mod p.
+    ///
+    /// With the `zeroize` feature, the temporaries of the exponentiation are wiped, see the
+    /// module [zeroizing](crate::zeroizing).
+    ///
+    /// # Errors
+    /// Returns [DhGroupsError::ExponentTooLarge] if `secret` has more bits than q, see
+    /// [PrimeGroup::public_key_allow_oversized].
+    pub fn public_key(&self, secret: &BigUint) -> Result<BigUint, DhGroupsError> {
+        self.check_exponent(secret)?;
+        Ok(self.public_key_allow_oversized(secret))
+    }
+
+    /// Compute the public key g^secret mod p like [PrimeGroup::public_key], for an exponent of
+    /// any size.
+    pub fn public_key_allow_oversized(&self, secret: &BigUint) -> BigUint {
+        self.pow_secret(&self.g, secret)
+    }
+
+    /// Compute the shared secret peer_public^secret mod p with a public key validated by
+    /// [UnvalidatedRuntimePublicKey::validate](crate::UnvalidatedRuntimePublicKey::validate).
+    ///
+    /// With the `zeroize` feature, the temporaries of the exponentiation are wiped, see the
+    /// module [zeroizing](crate::zeroizing).
+    ///
+    /// # Errors
+    /// Returns [DhGroupsError::ExponentTooLarge] if `secret` has more bits than q, see
+    /// [PrimeGroup::shared_secret_allow_oversized], or [DhGroupsError::GroupMismatch] if
+    /// `peer_public` belongs to another group.
+    pub fn shared_secret(
+        &self,
+        secret: &BigUint,
+        peer_public: &RuntimePublicKey,
+    ) -> Result<BigUint, DhGroupsError> {
+        self.check_exponent(secret)?;
+        self.shared_secret_allow_oversized(secret, peer_public)
+    }
+
+    /// Compute the shared secret peer_public^secret mod p like [PrimeGroup::shared_secret], for
+    /// an exponent of any size.
+    ///
+    /// # Errors
+    /// Returns [DhGroupsError::GroupMismatch] if `peer_public` belongs to another group.
+    pub fn shared_secret_allow_oversized(
+        &self,
+        secret: &BigUint,
+        peer_public: &RuntimePublicKey,
+    ) -> Result<BigUint, DhGroupsError> {
+        if !std::ptr::eq(peer_public.group(), self) && peer_public.group() != self {
+            return Err(DhGroupsError::GroupMismatch);
+        }
+        Ok(self.pow_secret(peer_public.value(), secret))
+    }
+
+    /// Check that a value decoded from `len` bytes is not longer than p.
+    #[cfg(any(feature = "jwk", feature = "cbor"))]
+    pub(crate) fn check_encoded_len(&self, len: usize) -> Result<(), DhGroupsError> {
+        let max = self.p.bits().div_ceil(8) as usize;
+        if len > max {
+            return Err(DhGroupsError::InputTooLong { len, max });
+        }
+        Ok(())
+    }
+
+    /// Check that `exponent` has at most as many bits as q.
+    fn check_exponent(&self, exponent: &BigUint) -> Result<(), DhGroupsError> {
+        if exponent.bits() > self.q.bits() {
+            return Err(DhGroupsError::ExponentTooLarge {
+                bits: exponent.bits(),
+                max: self.q.bits(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Compute base^secret mod p.
+    #[cfg(not(feature = "zeroize"))]
+    fn pow_secret(&self, base: &BigUint, secret: &BigUint) -> BigUint {
+        base.modpow(secret, &self.p)
+    }
+
+    /// Compute base^secret mod p, wiping the temporaries.
+    #[cfg(feature = "zeroize")]
+    fn pow_secret(&self, base: &BigUint, secret: &BigUint) -> BigUint {
+        use crate::zeroizing::{pow_mod, Modulus};
+
+        pow_mod(base, secret, Modulus::new(&self.p, self.identify()))
+    }
+
+    /// Number of bits of the private exponents chosen by [PrimeGroup::generate_keypair].
+    ///
+    /// It is twice the security strength of the group, estimated as for
+    /// [MODPGroup::security_strength](crate::MODPGroup::security_strength) (e.g. 224 bits for a
+    /// 2048-bit modulus), and at most the number of bits of `q`. Groups of less than 768 bits,
+    /// which have no security strength, use exponents of the size of `q`.
+    pub fn private_exponent_bits(&self) -> u64 {
+        match crate::group::security_strength(self.p.bits(), self.q.bits()) {
+            0 => self.q.bits(),
+            strength => (2 * u64::from(strength)).min(self.q.bits()),
+        }
+    }
+
+    /// Generate a key pair with a secret exponent sampled uniformly from
+    /// `[1, min(2^private_exponent_bits, q))`. The keys share a copy of the group.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use num_bigint::BigUint;
+    /// use diffie_hellman_groups::PrimeGroup;
+    ///
+    /// let pg = PrimeGroup::new_with(BigUint::from(1623299u64), 15).unwrap();
+    /// let mut rng = rand::thread_rng();
+    /// let (alice_secret, alice_public) = pg.generate_keypair(&mut rng);
+    /// let (bob_secret, bob_public) = pg.generate_keypair(&mut rng);
+    /// assert_eq!(
+    ///     alice_secret.diffie_hellman(&bob_public).unwrap().as_biguint(),
+    ///     bob_secret.diffie_hellman(&alice_public).unwrap().as_biguint()
+    /// );
+    /// ```
+    pub fn generate_keypair<R: CryptoRngCore + ?Sized>(
+        &self,
+        rng: &mut R,
+    ) -> (RuntimeSecretKey, RuntimePublicKey) {
+        let bound = (BigUint::from(1u64) << self.private_exponent_bits()).min(self.q.clone());
+        let x = rng.gen_biguint_range(&BigUint::from(1u64), &bound);
+        let secret = RuntimeSecretKey::new(Arc::new(self.clone()), x);
+        let public = secret.public_key();
+        (secret, public)
+    }
+
+    /// Generate a new prime group as in [PrimeGroup::generate], searching on `threads` worker
+    /// threads seeded from `rng_seed`.
+    ///
+    /// The first worker finding a safe prime stops the others. Since this depends on thread
+    /// scheduling, the result is not deterministic, even for a fixed `rng_seed`.
+    ///
+    /// # Errors
+    /// Returns [DhGroupsError::BitsOutOfRange] if `bits` is less than 3,
+    /// [DhGroupsError::InvalidThreadCount] if `threads` is 0, or [DhGroupsError::ThreadPool] if
+    /// the pool of threads cannot be built.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use diffie_hellman_groups::PrimeGroup;
+    ///
+    /// let pg = PrimeGroup::generate_parallel(128, 4, 42).unwrap();
+    /// assert_eq!(pg.p.bits(), 128);
+    /// ```
+    #[cfg(feature = "parallel")]
+    pub fn generate_parallel(
+        bits: u64,
+        threads: usize,
+        rng_seed: u64,
+    ) -> Result<Self, DhGroupsError> {
+        use rand::{rngs::StdRng, SeedableRng};
+
+        Self::builder()
+            .modulus_bits(bits)
+            .generator(GeneratorSpec::RandomBits(bits))
+            .threads(threads)
+            .build(&mut StdRng::seed_from_u64(rng_seed))
+    }
+
+    /// Identify the built-in group with the same prime modulus, if any.
+    ///
+    /// The subgroup of order `q` is the same for any generator, so `g` is not compared; see
+    /// [PrimeGroup::identify_exact] to also require the standard generator. The modulus is first
+    /// compared by a cheap fingerprint (its size and lowest 64 bits) before the full comparison.
+    ///
+    /// All the groups of [AnyModpGroup::ALL] are matched, including those of RFC 5114, whose
+    /// modulus is not a safe prime and whose subgroup order is not `(p - 1) / 2`. A match only
+    /// vouches for the primality of `p` and `(p - 1) / 2` if [AnyModpGroup::is_safe_prime] holds.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use diffie_hellman_groups::{AnyModpGroup, PrimeGroup, MODPGroup14};
+    ///
+    /// let pg = PrimeGroup::new::<MODPGroup14>(64).unwrap();
+    /// assert_eq!(pg.identify(), Some(AnyModpGroup::Modp2048));
+    /// ```
+    pub fn identify(&self) -> Option<AnyModpGroup> {
+        let fingerprint = |p: &BigUint| (p.bits(), p.iter_u64_digits().next());
+        let target = fingerprint(&self.p);
+        AnyModpGroup::ALL.iter().copied().find(|group| {
+            let p = group.prime_modulus();
+            fingerprint(&p) == target && p == self.p
+        })
+    }
+
+    /// Identify the built-in group with the same prime modulus and generator, if any.
+    pub fn identify_exact(&self) -> Option<AnyModpGroup> {
+        self.identify().filter(|group| group.generator() == self.g)
+    }
+
+    /// Raise `x` to the cofactor, mapping any element modulo `p` into the subgroup of order `q`
+    /// (or to 1).
+    pub fn clear_cofactor(&self, x: &BigUint) -> BigUint {
+        x.modpow(&self.cofactor, &self.p)
+    }
+
+    /// Build and validate the group of imported parameters, where a missing `q` is taken as
+    /// `(p - 1) / 2`.
+    pub(crate) fn from_parameters(
+        p: BigUint,
+        g: BigUint,
+        q: Option<BigUint>,
+    ) -> Result<Self, DhGroupsError> {
+        let one = BigUint::from(1u64);
+        if p.bits() < 3 {
+            return Err(DhGroupsError::NotPrime);
+        }
+        let q = q.unwrap_or_else(|| (&p - &one) >> 1);
+        if q < BigUint::from(2u64) {
+            return Err(DhGroupsError::NotPrime);
+        }
+        let group = PrimeGroup {
+            cofactor: (&p - &one) / &q,
+            p,
+            q,
+            g,
+        };
+        group.validate()?;
+        Ok(group)
+    }
+
+    /// Check that the parameters describe a subgroup of prime order: `p` and `q` are prime,
+    /// q * cofactor = p - 1, and `g` is an element of order `q`. The modulus is first checked
+    /// against the default [GroupPolicy].
+    pub fn validate(&self) -> Result<(), DhGroupsError> {
+        self.validate_with_policy(&GroupPolicy::default())
+    }
+
+    /// Check the parameters like [PrimeGroup::validate], with the limits of `policy`.
+    ///
+    /// # Errors
+    /// Returns [DhGroupsError::ModulusTooLarge] if the modulus exceeds the limit of `policy`,
+    /// before testing its primality.
+    pub fn validate_with_policy(&self, policy: &GroupPolicy) -> Result<(), DhGroupsError> {
+        policy.check_modulus(&self.p)?;
+        if !nt_funcs::is_prime(&self.p, None).probably()
+            || !nt_funcs::is_prime(&self.q, None).probably()
+        {
+            return Err(DhGroupsError::NotPrime);
+        }
+        let one = BigUint::from(1u64);
+        if &self.q * &self.cofactor != &self.p - &one {
+            return Err(DhGroupsError::InvalidCofactor);
+        }
+        self.verify()
+    }
+
+    /// Determine the order of the generator `g`. Only the orders 1, 2 and `q` are told apart,
+    /// which takes a single exponentiation.
+    pub fn generator_order(&self) -> GeneratorOrder {
+        let one = BigUint::from(1u64);
+        if !self.is_valid_element(&self.g) {
+            GeneratorOrder::OutOfRange
+        } else if self.g == one {
+            GeneratorOrder::One
+        } else if self.g.modpow(&self.q, &self.p) == one {
+            GeneratorOrder::Q
+        } else if self.g == &self.p - &one {
+            GeneratorOrder::Two
+        } else {
+            GeneratorOrder::Other
+        }
+    }
+
+    /// Check that the generator has order `q`, i.e. g^q mod p = 1 and g != 1. Unlike
+    /// [PrimeGroup::validate], `p` and `q` are assumed to be prime. All constructors verify the
+    /// group before returning it.
+    ///
+    /// # Errors
+    /// Returns [DhGroupsError::InvalidGenerator] if [PrimeGroup::generator_order] is not
+    /// [GeneratorOrder::Q].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use num_bigint::BigUint;
+    /// use diffie_hellman_groups::{DhGroupsError, PrimeGroup};
+    ///
+    /// let mut pg = PrimeGroup::new_with(BigUint::from(23u64), 5).unwrap();
+    /// assert!(pg.verify().is_ok());
+    /// pg.g = BigUint::from(1u64);
+    /// assert_eq!(pg.verify(), Err(DhGroupsError::InvalidGenerator));
+    /// ```
+    pub fn verify(&self) -> Result<(), DhGroupsError> {
+        match self.generator_order() {
+            GeneratorOrder::Q => Ok(()),
+            _ => Err(DhGroupsError::InvalidGenerator),
+        }
+    }
+
+    /// Hash `msg` to an element of the subgroup of order `q`, under the domain separation tag `dst`.
+    ///
+    /// The message is expanded with `D` to 128 bits more than the size of `p`, reduced modulo `p`
+    /// and raised to the cofactor (i.e. squared for safe primes), so that nobody knows the
+    /// discrete log of the result with respect to `g`.
+    /// The output is never 0 or 1; the expansion is repeated with an incremented counter in the
+    /// (negligibly likely) case it would be.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use num_bigint::BigUint;
+    /// use sha2::Sha256;
+    /// use diffie_hellman_groups::{PrimeGroup, group::MODPGroup14};
+    ///
+    /// let pg = PrimeGroup::new::<MODPGroup14>(64).unwrap();
+    /// let h = pg.hash_to_element::<Sha256>(b"pedersen h", b"my-protocol-v1");
+    /// assert_eq!(h.modpow(&pg.q, &pg.p), BigUint::from(1u64));
+    /// ```
+    pub fn hash_to_element<D: NamedDigest>(&self, msg: &[u8], dst: &[u8]) -> BigUint {
+        let one = BigUint::from(1u64);
+        let len = (self.p.bits() as usize + 128).div_ceil(8);
+        for counter in 0u32.. {
+            let h = BigUint::from_bytes_be(&expand_message::<D>(msg, dst, counter, len)) % &self.p;
+            let x = self.clear_cofactor(&h);
+            if x > one {
+                return x;
             }
         }
-        Self { p, q, g }
+        unreachable!("hash_to_element exhausted the counter space")
+    }
+}
+
+/// Formats the parameters `p` and `g` in the layout of `openssl dhparam -text`.
+impl fmt::Display for PrimeGroup {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_dhparam_text(f, &self.p, &self.g)
+    }
+}
+
+/// Check that a generator bit budget lies in `[2, p.bits()]`.
+pub(crate) fn check_bits(num_bits: u64, modulus_bits: u64) -> Result<(), DhGroupsError> {
+    if num_bits < 2 || num_bits > modulus_bits {
+        return Err(DhGroupsError::BitsOutOfRange);
+    }
+    Ok(())
+}
+
+/// Sample a generator of the subgroup of order `q` by raising to the cofactor. Since
+/// `h^cofactor mod p` lies in the subgroup of order `q` for any `h`, a single exponentiation
+/// suffices; for safe primes this is a squaring, and the result is never 1 for `h` not in
+/// {0, 1, p - 1}. `h` is drawn uniformly from `[2, min(2^max_bits, p - 1))`, and the sampling
+/// is only repeated if the result is rejected by `accept` (or is the identity).
+pub(crate) fn sample_generator<R: CryptoRngCore + ?Sized>(
+    p: &BigUint,
+    cofactor: &BigUint,
+    max_bits: u64,
+    rng: &mut R,
+    accept: impl Fn(&BigUint) -> bool,
+) -> BigUint {
+    let one = BigUint::from(1u64);
+    let lower = BigUint::from(2u64);
+    let upper = std::cmp::min(&one << max_bits, p - &one);
+
+    loop {
+        let h = rng.gen_biguint_range(&lower, &upper);
+        let g = h.modpow(cofactor, p);
+        if g != one && accept(&g) {
+            return g;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        collections::BTreeSet,
+        sync::atomic::{AtomicU64, Ordering},
+    };
+
+    use num_bigint::BigUint;
+    use sha2::Sha256;
+
+    use super::*;
+    use crate::{
+        FFDHEGroup2048, MODPGroup14, MODPGroup18, MODPGroup22, MODPGroup5,
+        UnvalidatedRuntimePublicKey,
+    };
+
+    #[test]
+    fn test_generator_full_range_coverage() {
+        // p = 23, q = 11. The subgroup of order q is the set of quadratic residues mod p.
+        let p = BigUint::from(23u64);
+        let expected: BTreeSet<BigUint> = (2u64..=21)
+            .map(BigUint::from)
+            .filter(|a| a.modpow(&BigUint::from(11u64), &p) == BigUint::from(1u64))
+            .collect();
+
+        let mut seen = BTreeSet::new();
+        for _ in 0..1000 {
+            let pg = PrimeGroup::new_with(p.clone(), 5).unwrap();
+            assert!(pg.g >= BigUint::from(2u64) && pg.g < BigUint::from(22u64));
+            seen.insert(pg.g);
+        }
+        assert_eq!(seen, expected);
+    }
+
+    #[test]
+    fn test_generator_max_bits() {
+        let p = BigUint::from(1623299u64);
+        let squares: BTreeSet<BigUint> = (2u64..16).map(|h| BigUint::from(h * h) % &p).collect();
+        for _ in 0..100 {
+            let pg = PrimeGroup::new_with(p.clone(), 4).unwrap();
+            assert!(squares.contains(&pg.g));
+            assert_eq!(pg.g.modpow(&pg.q, &pg.p), BigUint::from(1u64));
+        }
+    }
+
+    #[test]
+    fn test_hash_to_element() {
+        let p = BigUint::from(1623299u64);
+        let pg = PrimeGroup {
+            q: (&p - 1u64) / 2u64,
+            g: BigUint::from(4u64),
+            cofactor: BigUint::from(2u64),
+            p,
+        };
+        let h1 = pg.hash_to_element::<Sha256>(b"message", b"dst");
+        let h2 = pg.hash_to_element::<Sha256>(b"message", b"other dst");
+        let h3 = pg.hash_to_element::<Sha256>(b"other message", b"dst");
+        assert_eq!(h1, BigUint::from(1588637u64));
+        assert_eq!(h2, BigUint::from(868585u64));
+        assert_eq!(h3, BigUint::from(304446u64));
+        for h in [h1, h2, h3] {
+            assert!(h > BigUint::from(1u64));
+            assert_eq!(h.modpow(&pg.q, &pg.p), BigUint::from(1u64));
+        }
+    }
+
+    #[test]
+    fn test_cofactor_safe_prime() {
+        let pg = PrimeGroup::new_with(BigUint::from(1623299u64), 15).unwrap();
+        assert_eq!(pg.cofactor, BigUint::from(2u64));
+        assert!(pg.validate().is_ok());
+
+        let pg = PrimeGroup::new::<MODPGroup5>(64).unwrap();
+        assert_eq!(pg.cofactor, BigUint::from(2u64));
+        assert!(pg.validate().is_ok());
+    }
+
+    #[test]
+    fn test_cofactor_schnorr_group() {
+        // p - 1 = 2 * 3 * 5 * 7 * 17
+        let pg = PrimeGroup::new_schnorr(BigUint::from(3571u64), BigUint::from(17u64)).unwrap();
+        assert_eq!(pg.cofactor, BigUint::from(210u64));
+        assert!(pg.validate().is_ok());
+
+        for x in 2u64..100 {
+            let y = pg.clear_cofactor(&BigUint::from(x));
+            assert_eq!(y.modpow(&pg.q, &pg.p), BigUint::from(1u64));
+        }
+
+        let mut invalid = pg.clone();
+        invalid.cofactor = BigUint::from(2u64);
+        assert_eq!(invalid.validate(), Err(DhGroupsError::InvalidCofactor));
+
+        let mut invalid = pg.clone();
+        invalid.g = BigUint::from(1u64);
+        assert_eq!(invalid.validate(), Err(DhGroupsError::InvalidGenerator));
+
+        let mut invalid = pg;
+        invalid.q = BigUint::from(210u64);
+        assert_eq!(invalid.validate(), Err(DhGroupsError::NotPrime));
+    }
+
+    #[test]
+    fn test_constructor_errors() {
+        assert_eq!(
+            PrimeGroup::new::<MODPGroup5>(1).unwrap_err(),
+            DhGroupsError::BitsOutOfRange
+        );
+        assert_eq!(
+            PrimeGroup::new::<MODPGroup5>(1537).unwrap_err(),
+            DhGroupsError::BitsOutOfRange
+        );
+        assert!(PrimeGroup::new::<MODPGroup5>(1536).is_ok());
+
+        let p = BigUint::from(1623299u64);
+        assert_eq!(
+            PrimeGroup::new_with(p.clone(), 1).unwrap_err(),
+            DhGroupsError::BitsOutOfRange
+        );
+        assert_eq!(
+            PrimeGroup::new_with(p.clone(), p.bits() + 1).unwrap_err(),
+            DhGroupsError::BitsOutOfRange
+        );
+        // 29 is prime but 14 is not
+        assert_eq!(
+            PrimeGroup::new_with(BigUint::from(29u64), 4).unwrap_err(),
+            DhGroupsError::NotSafePrime
+        );
+        assert_eq!(
+            PrimeGroup::new_with(BigUint::from(21u64), 4).unwrap_err(),
+            DhGroupsError::NotSafePrime
+        );
+
+        assert_eq!(
+            PrimeGroup::new_schnorr(BigUint::from(3570u64), BigUint::from(17u64)).unwrap_err(),
+            DhGroupsError::NotPrime
+        );
+        assert_eq!(
+            PrimeGroup::new_schnorr(BigUint::from(3571u64), BigUint::from(13u64)).unwrap_err(),
+            DhGroupsError::InvalidCofactor
+        );
+    }
+
+    #[test]
+    fn test_generate() {
+        for bits in [3, 16, 64] {
+            let pg = PrimeGroup::generate(bits, &mut rand::thread_rng(), None).unwrap();
+            assert_eq!(pg.p.bits(), bits);
+            assert!(pg.validate().is_ok());
+        }
+        assert_eq!(
+            PrimeGroup::generate(2, &mut rand::thread_rng(), None).unwrap_err(),
+            DhGroupsError::BitsOutOfRange
+        );
+    }
+
+    #[test]
+    fn test_generate_progress() {
+        let calls = AtomicU64::new(0);
+        let hook = |_attempts: u64| {
+            calls.fetch_add(1, Ordering::Relaxed);
+            ControlFlow::Continue(())
+        };
+        let pg = PrimeGroup::generate(64, &mut rand::thread_rng(), Some(&hook)).unwrap();
+        assert!(pg.validate().is_ok());
+        assert!(calls.load(Ordering::Relaxed) >= 1);
+    }
+
+    #[test]
+    fn test_generate_cancelled() {
+        struct CancelAfter {
+            last_attempts: AtomicU64,
+            limit: u64,
+        }
+        impl ProgressHook for CancelAfter {
+            fn on_candidate(&self, attempts: u64) -> ControlFlow<()> {
+                self.last_attempts.store(attempts, Ordering::Relaxed);
+                if attempts >= self.limit {
+                    ControlFlow::Break(())
+                } else {
+                    ControlFlow::Continue(())
+                }
+            }
+        }
+
+        let hook = CancelAfter {
+            last_attempts: AtomicU64::new(0),
+            limit: 3 * PROGRESS_INTERVAL,
+        };
+        // a 2048-bit safe prime would not be found within a few candidates
+        let res = PrimeGroup::generate(2048, &mut rand::thread_rng(), Some(&hook));
+        assert_eq!(res.unwrap_err(), DhGroupsError::Cancelled);
+        assert_eq!(
+            hook.last_attempts.load(Ordering::Relaxed),
+            3 * PROGRESS_INTERVAL
+        );
+    }
+
+    #[test]
+    fn test_generator_order() {
+        let pg = PrimeGroup::new_with(BigUint::from(23u64), 5).unwrap();
+        assert_eq!(pg.generator_order(), GeneratorOrder::Q);
+        assert!(pg.verify().is_ok());
+
+        let cases = [
+            (0u64, GeneratorOrder::OutOfRange),
+            (23, GeneratorOrder::OutOfRange),
+            (1, GeneratorOrder::One),
+            (22, GeneratorOrder::Two),
+            (5, GeneratorOrder::Other),
+            (4, GeneratorOrder::Q),
+        ];
+        for (g, order) in cases {
+            let invalid = PrimeGroup {
+                g: BigUint::from(g),
+                ..pg.clone()
+            };
+            assert_eq!(invalid.generator_order(), order);
+            assert_eq!(invalid.verify().is_ok(), order == GeneratorOrder::Q);
+        }
+        let invalid = PrimeGroup {
+            g: BigUint::from(1u64),
+            ..pg
+        };
+        assert_eq!(invalid.validate(), Err(DhGroupsError::InvalidGenerator));
+    }
+
+    #[test]
+    fn test_display() {
+        let pg = PrimeGroup {
+            p: MODPGroup14::prime_modulus(),
+            q: MODPGroup14::sophie_garmain_prime(),
+            g: BigUint::from(4u64),
+            cofactor: BigUint::from(2u64),
+        };
+        let expected = include_str!("../tests/fixtures/group14_dhparam.txt");
+        assert_eq!(pg.to_string(), expected);
+
+        let pg = PrimeGroup::new_with(BigUint::from(1623299u64), 15).unwrap();
+        assert_eq!(
+            pg.to_string(),
+            format!(
+                "    DH Parameters: (21 bit)\n    P:    1623299 (0x18c503)\n    G:    {} (0x{:x})\n",
+                pg.g, pg.g
+            )
+        );
+    }
+
+    #[test]
+    fn test_validate_public_key() {
+        let pg = PrimeGroup::new_with(BigUint::from(1623299u64), 15).unwrap();
+        let p_minus_one = &pg.p - 1u64;
+        // 2 is a quadratic non-residue modulo p as p = 3 mod 8
+        let non_residue = BigUint::from(2u64);
+        let y = pg.public_key(&BigUint::from(12345u64)).unwrap();
+
+        assert!(!pg.is_valid_element(&BigUint::from(0u64)));
+        assert!(!pg.is_valid_element(&pg.p));
+        assert!(pg.is_valid_element(&p_minus_one));
+        assert!(pg.is_subgroup_member(&y));
+        assert!(!pg.is_subgroup_member(&p_minus_one));
+        assert!(!pg.is_subgroup_member(&non_residue));
+
+        let cases = [
+            (BigUint::from(0u64), Err(ValidationError::OutOfRange)),
+            (pg.p.clone(), Err(ValidationError::OutOfRange)),
+            (BigUint::from(1u64), Err(ValidationError::Degenerate)),
+            (p_minus_one, Err(ValidationError::Degenerate)),
+            (non_residue, Err(ValidationError::NotInSubgroup)),
+            (y.clone(), Ok(())),
+        ];
+        for (key, expected) in cases {
+            assert_eq!(pg.validate_public_key(&key), expected);
+        }
+
+        let key = |y: BigUint| UnvalidatedRuntimePublicKey::from_biguint(Arc::new(pg.clone()), y);
+        let a = BigUint::from(777u64);
+        let b = BigUint::from(12345u64);
+        let ya = key(pg.public_key(&a).unwrap()).validate().unwrap();
+        assert_eq!(
+            pg.shared_secret(&a, &key(y).validate().unwrap()).unwrap(),
+            pg.shared_secret(&b, &ya).unwrap()
+        );
+        assert_eq!(
+            key(BigUint::from(1u64)).validate(),
+            Err(ValidationError::Degenerate)
+        );
+        let other = PrimeGroup::new_with(BigUint::from(1000667u64), 15).unwrap();
+        let (_, other_public) = other.generate_keypair(&mut rand::thread_rng());
+        assert_eq!(
+            pg.shared_secret(&a, &other_public),
+            Err(DhGroupsError::GroupMismatch)
+        );
+    }
+
+    #[test]
+    fn test_oversized_inputs() {
+        let pg = PrimeGroup::new_with(BigUint::from(1623299u64), 15).unwrap();
+        let y = pg.public_key(&BigUint::from(12345u64)).unwrap();
+        let key = UnvalidatedRuntimePublicKey::from_biguint(Arc::new(pg.clone()), y.clone())
+            .validate()
+            .unwrap();
+
+        // q has 20 bits, and an exponent of q bits is still accepted
+        let oversized = BigUint::from(1u64) << 20;
+        let error = DhGroupsError::ExponentTooLarge { bits: 21, max: 20 };
+        assert!(pg.public_key(&(&oversized - 1u64)).is_ok());
+        assert_eq!(pg.public_key(&oversized), Err(error.clone()));
+        assert_eq!(pg.shared_secret(&oversized, &key), Err(error));
+        assert_eq!(
+            pg.public_key_allow_oversized(&oversized),
+            pg.g.modpow(&oversized, &pg.p)
+        );
+        assert_eq!(
+            pg.shared_secret_allow_oversized(&oversized, &key),
+            Ok(y.modpow(&oversized, &pg.p))
+        );
+
+        assert!(pg.validate().is_ok());
+        assert_eq!(
+            pg.validate_with_policy(&GroupPolicy {
+                max_modulus_bits: 20
+            }),
+            Err(DhGroupsError::ModulusTooLarge { bits: 21, max: 20 })
+        );
+        let huge = PrimeGroup {
+            p: BigUint::from(1u64) << DEFAULT_MAX_MODULUS_BITS,
+            ..pg
+        };
+        assert_eq!(
+            huge.validate(),
+            Err(DhGroupsError::ModulusTooLarge {
+                bits: DEFAULT_MAX_MODULUS_BITS + 1,
+                max: DEFAULT_MAX_MODULUS_BITS
+            })
+        );
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn test_generate_parallel() {
+        for threads in [1, 4] {
+            let pg = PrimeGroup::generate_parallel(64, threads, 7).unwrap();
+            assert_eq!(pg.p.bits(), 64);
+            assert!(pg.validate().is_ok());
+        }
+        assert_eq!(
+            PrimeGroup::generate_parallel(64, 0, 7).unwrap_err(),
+            DhGroupsError::InvalidThreadCount
+        );
+    }
+
+    #[test]
+    fn test_identify() {
+        let pg = PrimeGroup::new::<MODPGroup14>(64).unwrap();
+        assert_eq!(pg.identify(), Some(AnyModpGroup::Modp2048));
+        assert_eq!(pg.identify_exact(), None);
+
+        let pg = PrimeGroup {
+            g: BigUint::from(2u64),
+            ..PrimeGroup::new::<MODPGroup18>(64).unwrap()
+        };
+        assert_eq!(pg.identify_exact(), Some(AnyModpGroup::Modp8192));
+
+        let pg = PrimeGroup {
+            g: MODPGroup22::generator(),
+            ..PrimeGroup::new::<MODPGroup22>(64).unwrap()
+        };
+        assert_eq!(pg.identify_exact(), Some(AnyModpGroup::Modp1024S160));
+
+        let pg = PrimeGroup::generate(64, &mut rand::thread_rng(), None).unwrap();
+        assert_eq!(pg.identify(), None);
+    }
+
+    #[test]
+    fn test_new_group_18() {
+        let pg = PrimeGroup::new::<MODPGroup18>(8192).unwrap();
+        assert_ne!(pg.g, BigUint::from(1u64));
+        assert_ne!(pg.g, MODPGroup18::generator());
+        assert_eq!(pg.g.modpow(&pg.q, &pg.p), BigUint::from(1u64));
+    }
+
+    #[test]
+    fn test_new_rfc5114() {
+        let pg = PrimeGroup::new::<MODPGroup22>(64).unwrap();
+        assert_eq!(pg.q, MODPGroup22::subgroup_order());
+        assert_eq!(pg.cofactor, (&pg.p - 1u64) / &pg.q);
+        assert_eq!(pg.g.modpow(&pg.q, &pg.p), BigUint::from(1u64));
+        assert_ne!(pg.g, BigUint::from(1u64));
+        assert!(pg.validate().is_ok());
+    }
+
+    #[test]
+    fn test_new_ffdhe() {
+        let pg = PrimeGroup::new::<FFDHEGroup2048>(64).unwrap();
+        assert_eq!(pg.p, FFDHEGroup2048::prime_modulus());
+        assert_eq!(pg.q, FFDHEGroup2048::sophie_garmain_prime());
+        assert_eq!(pg.g.modpow(&pg.q, &pg.p), BigUint::from(1u64));
+        // the primes of RFC 7919 are not the primes of RFC 3526
+        assert_eq!(pg.identify(), Some(AnyModpGroup::Ffdhe2048));
+        assert!(!AnyModpGroup::RFC3526.contains(&AnyModpGroup::Ffdhe2048));
+
+        let rng = &mut rand::thread_rng();
+        let (secret, public) = pg.generate_keypair(rng);
+        let (peer_secret, peer_public) = pg.generate_keypair(rng);
+        assert_eq!(
+            secret.diffie_hellman(&peer_public).unwrap().to_bytes_be(),
+            peer_secret.diffie_hellman(&public).unwrap().to_bytes_be()
+        );
+    }
+
+    #[test]
+    fn test_private_exponent_bits() {
+        let pg = PrimeGroup::new::<MODPGroup14>(64).unwrap();
+        assert_eq!(
+            pg.private_exponent_bits(),
+            2 * u64::from(MODPGroup14::security_strength())
+        );
+        let pg = PrimeGroup::new::<MODPGroup22>(64).unwrap();
+        assert_eq!(pg.private_exponent_bits(), 160);
+
+        let pg = PrimeGroup::generate(64, &mut rand::thread_rng(), None).unwrap();
+        assert_eq!(pg.private_exponent_bits(), 63);
     }
 }
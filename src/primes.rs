@@ -0,0 +1,360 @@
+//! Search of safe primes and Sophie Germain primes.
+
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+
+use lazy_static::lazy_static;
+use num_bigint::{BigUint, RandBigInt};
+use num_prime::{nt_funcs, PrimalityTestConfig};
+use rand_core::CryptoRngCore;
+
+use crate::{primegroup::PROGRESS_INTERVAL, DhGroupsError, ProgressHook};
+
+/// Configuration of the probabilistic primality tests used by the prime searches.
+///
+/// The default configuration runs strong probable prime tests to the bases 2 and 3, and to 3
+/// random bases.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PrimalityConfig {
+    /// Number of strong probable prime tests with the fixed bases 2, 3, 5, ...
+    pub sprp_trials: usize,
+    /// Number of strong probable prime tests with random bases.
+    pub sprp_random_trials: usize,
+    /// Whether to run the strong Lucas probable prime test, which with the base 2 test makes up
+    /// the Baillie-PSW test.
+    pub lucas_test: bool,
+}
+
+impl Default for PrimalityConfig {
+    fn default() -> Self {
+        let config = PrimalityTestConfig::default();
+        Self {
+            sprp_trials: config.sprp_trials,
+            sprp_random_trials: config.sprp_random_trials,
+            lucas_test: config.slprp_test,
+        }
+    }
+}
+
+impl PrimalityConfig {
+    /// The Baillie-PSW test followed by a strong probable prime test to a random base.
+    pub fn strict() -> Self {
+        Self {
+            sprp_trials: 1,
+            sprp_random_trials: 1,
+            lucas_test: true,
+        }
+    }
+
+    /// Test whether `n` is a probable prime under this configuration.
+    pub fn is_probable_prime(&self, n: &BigUint) -> bool {
+        let mut config = PrimalityTestConfig::default();
+        config.sprp_trials = self.sprp_trials;
+        config.sprp_random_trials = self.sprp_random_trials;
+        config.slprp_test = self.lucas_test;
+        nt_funcs::is_prime(n, Some(config)).probably()
+    }
+}
+
+/// Find a random Sophie Germain prime `q` of exactly `bits` bits, returning `(q, p)` where
+/// `p = 2q + 1` is the associated safe prime.
+///
+/// The candidates are scanned incrementally from random odd starting points, and sieved by small
+/// primes before the probabilistic tests of `config` are run on both `q` and `p`.
+///
+/// # Panics
+/// Panics if `bits` is less than 2.
+///
+/// # Example
+///
+/// ```rust
+/// use diffie_hellman_groups::primes::{find_sophie_germain, PrimalityConfig};
+///
+/// let (q, p) = find_sophie_germain(64, &mut rand::thread_rng(), &PrimalityConfig::default());
+/// assert_eq!(q.bits(), 64);
+/// assert_eq!(p, 2u32 * q + 1u32);
+/// ```
+pub fn find_sophie_germain<R: CryptoRngCore + ?Sized>(
+    bits: u64,
+    rng: &mut R,
+    config: &PrimalityConfig,
+) -> (BigUint, BigUint) {
+    assert!(bits >= 2);
+    search_sophie_germain(bits, rng, config, None).expect("search without hook is not cancelled")
+}
+
+/// Find the smallest safe prime `p >= start`, i.e. such that `(p - 1) / 2` is also prime.
+///
+/// # Example
+///
+/// ```rust
+/// use num_bigint::BigUint;
+/// use diffie_hellman_groups::primes::next_safe_prime;
+///
+/// assert_eq!(next_safe_prime(&BigUint::from(12u32)), BigUint::from(23u32));
+/// ```
+pub fn next_safe_prime(start: &BigUint) -> BigUint {
+    let one = BigUint::from(1u64);
+    if *start <= BigUint::from(5u64) {
+        return BigUint::from(5u64);
+    }
+    let config = PrimalityConfig::default();
+
+    // smallest odd q such that 2q + 1 >= start
+    let mut q = (start - &one + &one) >> 1 | &one;
+    loop {
+        for i in sieve_safe_prime_candidates(&q, SIEVE_WINDOW) {
+            let candidate = &q + BigUint::from(2 * i);
+            if config.is_probable_prime(&candidate) {
+                let p = (&candidate << 1) + &one;
+                if config.is_probable_prime(&p) {
+                    return p;
+                }
+            }
+        }
+        q += BigUint::from(2 * SIEVE_WINDOW);
+    }
+}
+
+/// Search a Sophie Germain prime of `bits` bits as in [find_sophie_germain], notifying
+/// `progress` every [PROGRESS_INTERVAL] tested candidates.
+pub(crate) fn search_sophie_germain<R: CryptoRngCore + ?Sized>(
+    bits: u64,
+    rng: &mut R,
+    config: &PrimalityConfig,
+    progress: Option<&dyn ProgressHook>,
+) -> Result<(BigUint, BigUint), DhGroupsError> {
+    let attempts = AtomicU64::new(0);
+    let stop = AtomicBool::new(false);
+    search_worker(bits, rng, config, progress, &attempts, &stop)
+        .expect("search is only stopped by itself")
+}
+
+/// Search a Sophie Germain prime of `bits` bits with one worker per seed, running in `pool`
+/// (or in the global rayon pool). The first result found (or the cancellation by `progress`)
+/// stops all workers, so the result is not deterministic even for fixed seeds.
+#[cfg(feature = "parallel")]
+pub(crate) fn search_sophie_germain_parallel(
+    bits: u64,
+    seeds: &[u64],
+    config: &PrimalityConfig,
+    progress: Option<&dyn ProgressHook>,
+    pool: Option<&rayon::ThreadPool>,
+) -> Result<(BigUint, BigUint), DhGroupsError> {
+    use std::sync::Mutex;
+
+    let attempts = AtomicU64::new(0);
+    let stop = AtomicBool::new(false);
+    let result = Mutex::new(None);
+    let state = (&attempts, &stop, &result);
+    match pool {
+        Some(pool) => {
+            pool.scope(|scope| spawn_workers(scope, bits, seeds, config, progress, state))
+        }
+        None => rayon::scope(|scope| spawn_workers(scope, bits, seeds, config, progress, state)),
+    }
+    result
+        .into_inner()
+        .unwrap()
+        .expect("search is only stopped by a worker")
+}
+
+/// Shared state of the parallel search: the number of attempts, the stop flag and the result.
+#[cfg(feature = "parallel")]
+type SearchState<'a> = (
+    &'a AtomicU64,
+    &'a AtomicBool,
+    &'a std::sync::Mutex<Option<Result<(BigUint, BigUint), DhGroupsError>>>,
+);
+
+#[cfg(feature = "parallel")]
+fn spawn_workers<'s>(
+    scope: &rayon::Scope<'s>,
+    bits: u64,
+    seeds: &[u64],
+    config: &'s PrimalityConfig,
+    progress: Option<&'s dyn ProgressHook>,
+    (attempts, stop, result): SearchState<'s>,
+) {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    for &seed in seeds {
+        scope.spawn(move |_| {
+            let rng = &mut StdRng::seed_from_u64(seed);
+            if let Some(res) = search_worker(bits, rng, config, progress, attempts, stop) {
+                result.lock().unwrap().get_or_insert(res);
+            }
+        });
+    }
+}
+
+/// Search loop shared by the sequential and parallel searches. The number of tested candidates is
+/// counted in `attempts`, and the loop returns `None` as soon as `stop` is set by another worker.
+/// It sets `stop` itself when it finds a result or is cancelled by `progress`.
+fn search_worker<R: CryptoRngCore + ?Sized>(
+    bits: u64,
+    rng: &mut R,
+    config: &PrimalityConfig,
+    progress: Option<&dyn ProgressHook>,
+    attempts: &AtomicU64,
+    stop: &AtomicBool,
+) -> Option<Result<(BigUint, BigUint), DhGroupsError>> {
+    let one = BigUint::from(1u64);
+    let lower = &one << (bits - 1);
+    let upper = &one << bits;
+    loop {
+        let start = rng.gen_biguint_range(&lower, &upper) | &one;
+        for i in sieve_safe_prime_candidates(&start, SIEVE_WINDOW) {
+            let q = &start + BigUint::from(2 * i);
+            if q >= upper {
+                break;
+            }
+            if stop.load(Ordering::Relaxed) {
+                return None;
+            }
+
+            let attempt = attempts.fetch_add(1, Ordering::Relaxed);
+            if let Some(hook) = progress {
+                if attempt.is_multiple_of(PROGRESS_INTERVAL)
+                    && hook.on_candidate(attempt).is_break()
+                {
+                    stop.store(true, Ordering::Relaxed);
+                    return Some(Err(DhGroupsError::Cancelled));
+                }
+            }
+
+            if !config.is_probable_prime(&q) {
+                continue;
+            }
+            let p = (&q << 1) + &one;
+            if config.is_probable_prime(&p) {
+                stop.store(true, Ordering::Relaxed);
+                return Some(Ok((q, p)));
+            }
+        }
+    }
+}
+
+/// Bound of the odd primes used to sieve safe prime candidates.
+const SIEVE_PRIME_LIMIT: u32 = 1 << 15;
+
+/// Number of consecutive odd candidates sieved at once.
+const SIEVE_WINDOW: usize = 4096;
+
+lazy_static! {
+    /// Odd primes below [SIEVE_PRIME_LIMIT] (a few thousand of them).
+    static ref SMALL_PRIMES: Vec<u32> = {
+        let limit = SIEVE_PRIME_LIMIT as usize;
+        let mut composite = vec![false; limit];
+        let mut primes = Vec::new();
+        for n in 3..limit {
+            if !composite[n] && n % 2 == 1 {
+                primes.push(n as u32);
+                for m in (n * n..limit).step_by(2 * n) {
+                    composite[m] = true;
+                }
+            }
+        }
+        primes
+    };
+}
+
+/// Sieve the odd candidates `q = start + 2i` for `i` in `[0, window)`, where `start` is odd, and
+/// return the indices `i` such that neither `q` nor `2q + 1` is divisible by a small prime.
+///
+/// Only primes smaller than `start` are used, so that small primes are never sieved out themselves.
+fn sieve_safe_prime_candidates(start: &BigUint, window: usize) -> Vec<usize> {
+    let mut sieved = vec![false; window];
+    for &r in SMALL_PRIMES.iter() {
+        if BigUint::from(r) >= *start {
+            break;
+        }
+        let m = u32::try_from(start % r).unwrap() as usize;
+        let r = r as usize;
+        // inverse of 2 modulo the odd prime r
+        let half = r / 2 + 1;
+        // q = start + 2i is divisible by r iff i = -m / 2 mod r
+        let i_q = (r - m) % r * half % r;
+        // 2q + 1 is divisible by r iff q = (r - 1) / 2 mod r, i.e. i = ((r - 1) / 2 - m) / 2 mod r
+        let i_p = ((r - 1) / 2 + r - m) % r * half % r;
+        for i in [i_q, i_p] {
+            for s in sieved.iter_mut().skip(i).step_by(r) {
+                *s = true;
+            }
+        }
+    }
+    (0..window).filter(|&i| !sieved[i]).collect()
+}
+
+#[cfg(test)]
+mod test {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+
+    #[test]
+    fn test_small_primes() {
+        assert_eq!(SMALL_PRIMES[..5], [3, 5, 7, 11, 13]);
+        assert!(SMALL_PRIMES.len() > 3000);
+    }
+
+    #[test]
+    fn test_sieve_keeps_safe_primes() {
+        let start = BigUint::from(1_000_001u64);
+        let survivors = sieve_safe_prime_candidates(&start, SIEVE_WINDOW);
+        for i in 0..SIEVE_WINDOW {
+            let q = &start + BigUint::from(2 * i);
+            let p = &q * 2u64 + 1u64;
+            let safe =
+                nt_funcs::is_prime(&q, None).probably() && nt_funcs::is_prime(&p, None).probably();
+            if safe {
+                assert!(survivors.contains(&i));
+            }
+            if survivors.contains(&i) {
+                for &r in SMALL_PRIMES.iter().take(100) {
+                    assert_ne!(&q % r, BigUint::from(0u32));
+                    assert_ne!(&p % r, BigUint::from(0u32));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_find_sophie_germain() {
+        let config = PrimalityConfig::strict();
+        for bits in [2, 3, 16, 64, 128] {
+            let (q, p) = find_sophie_germain(bits, &mut rand::thread_rng(), &config);
+            assert_eq!(q.bits(), bits);
+            assert_eq!(p, &q * 2u32 + 1u32);
+            assert!(config.is_probable_prime(&q));
+            assert!(config.is_probable_prime(&p));
+        }
+    }
+
+    #[test]
+    fn test_find_sophie_germain_seeded() {
+        let config = PrimalityConfig::default();
+        let (q1, p1) = find_sophie_germain(32, &mut StdRng::seed_from_u64(42), &config);
+        let (q2, p2) = find_sophie_germain(32, &mut StdRng::seed_from_u64(42), &config);
+        assert_eq!((&q1, &p1), (&q2, &p2));
+        assert_eq!(q1, BigUint::from(2720474423u64));
+    }
+
+    #[test]
+    fn test_next_safe_prime() {
+        let cases = [
+            (0u64, 5u64),
+            (5, 5),
+            (6, 7),
+            (8, 11),
+            (12, 23),
+            (1_000_000, 1_000_667),
+            (1 << 20, 1_048_703),
+        ];
+        for (start, expected) in cases {
+            assert_eq!(
+                next_safe_prime(&BigUint::from(start)),
+                BigUint::from(expected)
+            );
+        }
+    }
+}
@@ -0,0 +1,240 @@
+//! Public keys of a peer, whose validation cannot be skipped.
+//!
+//! Every decoder of a public key received from a peer returns an [UnvalidatedPublicKey]. Its
+//! only routes to a [PublicKey], the type accepted by the shared-secret APIs, are
+//! [UnvalidatedPublicKey::validate], with the checks of section 5.6.2.3.1 of NIST SP 800-56A
+//! ([Element::validate]), and [UnvalidatedPublicKey::assume_valid_dangerous], for keys already
+//! validated elsewhere.
+//!
+//! # Example
+//!
+//! ```rust
+//! use diffie_hellman_groups::{group::MODPGroup14, pool::EphemeralSecret, UnvalidatedPublicKey};
+//!
+//! let rng = &mut rand::thread_rng();
+//! let alice = EphemeralSecret::<MODPGroup14>::generate(rng, Some(256));
+//! let bob = EphemeralSecret::<MODPGroup14>::generate(rng, Some(256));
+//!
+//! // the bytes received from bob
+//! let peer = UnvalidatedPublicKey::<MODPGroup14>::from_bytes_be(&bob.public_key().to_bytes_be()).unwrap();
+//! let peer = peer.validate().unwrap();
+//! let shared = alice.diffie_hellman(&peer);
+//! ```
+//!
+//! A key that was not validated does not compile where a [PublicKey] is expected:
+//!
+//! ```compile_fail
+//! use diffie_hellman_groups::{group::MODPGroup14, pool::EphemeralSecret, UnvalidatedPublicKey};
+//!
+//! let rng = &mut rand::thread_rng();
+//! let alice = EphemeralSecret::<MODPGroup14>::generate(rng, Some(256));
+//! let bob = EphemeralSecret::<MODPGroup14>::generate(rng, Some(256));
+//! let peer = UnvalidatedPublicKey::<MODPGroup14>::from_bytes_be(&bob.public_key().to_bytes_be()).unwrap();
+//! let shared = alice.diffie_hellman(&peer);
+//! ```
+//!
+//! and neither does an [Element]:
+//!
+//! ```compile_fail
+//! use num_bigint::BigUint;
+//! use diffie_hellman_groups::{group::MODPGroup14, pool::EphemeralSecret, Element};
+//!
+//! let rng = &mut rand::thread_rng();
+//! let alice = EphemeralSecret::<MODPGroup14>::generate(rng, Some(256));
+//! let peer = Element::<MODPGroup14>::from_value(BigUint::from(1u64));
+//! let shared = alice.diffie_hellman(&peer);
+//! ```
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{group::MODPGroup, DhGroupsError, Element, ValidationError};
+
+/// A public key received from a peer, not yet validated.
+#[derive(Debug)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(bound = "", transparent)
+)]
+pub struct UnvalidatedPublicKey<G: MODPGroup>(Element<G>);
+
+/// A public key that passed [UnvalidatedPublicKey::validate], or was computed from a secret
+/// exponent. It is serialized as its element, and deserialized as an [UnvalidatedPublicKey].
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize), serde(bound = "", transparent))]
+pub struct PublicKey<G: MODPGroup>(Element<G>);
+
+impl<G: MODPGroup> UnvalidatedPublicKey<G> {
+    /// Decode a public key with [Element::from_bytes_be].
+    ///
+    /// # Errors
+    /// Returns the errors of [Element::from_bytes_be].
+    pub fn from_bytes_be(bytes: &[u8]) -> Result<Self, DhGroupsError> {
+        Element::from_bytes_be(bytes).map(UnvalidatedPublicKey)
+    }
+
+    /// Decode a public key with [Element::from_compressed_bytes].
+    ///
+    /// # Errors
+    /// Returns the errors of [Element::from_compressed_bytes].
+    pub fn from_compressed_bytes(bytes: &[u8]) -> Result<Self, DhGroupsError> {
+        Element::from_compressed_bytes(bytes).map(UnvalidatedPublicKey)
+    }
+
+    /// Decode a public key with [Element::from_tagged_bytes].
+    ///
+    /// # Errors
+    /// Returns the errors of [Element::from_tagged_bytes].
+    pub fn from_tagged_bytes(bytes: &[u8]) -> Result<Self, DhGroupsError> {
+        Element::from_tagged_bytes(bytes).map(UnvalidatedPublicKey)
+    }
+
+    /// The element of the key, e.g. to hash it into a transcript.
+    pub fn as_element(&self) -> &Element<G> {
+        &self.0
+    }
+
+    /// Validate the key with [Element::validate].
+    ///
+    /// # Errors
+    /// Returns the [ValidationError] of [Element::validate].
+    pub fn validate(self) -> Result<PublicKey<G>, ValidationError> {
+        self.0.validate()?;
+        Ok(PublicKey(self.0))
+    }
+
+    /// Accept the key without validating it.
+    ///
+    /// Computing a shared secret with a key outside of the subgroup of order q leaks bits of the
+    /// secret exponent. Only use it for keys validated before, e.g. read back from storage
+    /// written by this process.
+    pub fn assume_valid_dangerous(self) -> PublicKey<G> {
+        PublicKey(self.0)
+    }
+}
+
+impl<G: MODPGroup> From<Element<G>> for UnvalidatedPublicKey<G> {
+    fn from(element: Element<G>) -> Self {
+        UnvalidatedPublicKey(element)
+    }
+}
+
+/// A validated key, e.g. our own key, sent in a message.
+impl<G: MODPGroup> From<PublicKey<G>> for UnvalidatedPublicKey<G> {
+    fn from(key: PublicKey<G>) -> Self {
+        UnvalidatedPublicKey(key.0)
+    }
+}
+
+impl<G: MODPGroup> PublicKey<G> {
+    /// The public key of a secret exponent, `g^x`, computed by its owner.
+    pub(crate) fn from_secret_power(element: Element<G>) -> Self {
+        PublicKey(element)
+    }
+
+    /// The element of the key.
+    pub fn as_element(&self) -> &Element<G> {
+        &self.0
+    }
+
+    /// The element of the key.
+    pub fn into_element(self) -> Element<G> {
+        self.0
+    }
+
+    /// Encode the key with [Element::to_bytes_be].
+    pub fn to_bytes_be(&self) -> Vec<u8> {
+        self.0.to_bytes_be()
+    }
+}
+
+impl<G: MODPGroup> Clone for UnvalidatedPublicKey<G> {
+    fn clone(&self) -> Self {
+        UnvalidatedPublicKey(Element::from_value(self.0.value.clone()))
+    }
+}
+
+impl<G: MODPGroup> PartialEq for UnvalidatedPublicKey<G> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl<G: MODPGroup> Eq for UnvalidatedPublicKey<G> {}
+
+impl<G: MODPGroup> Clone for PublicKey<G> {
+    fn clone(&self) -> Self {
+        PublicKey(Element::from_value(self.0.value.clone()))
+    }
+}
+
+impl<G: MODPGroup> PartialEq for PublicKey<G> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl<G: MODPGroup> Eq for PublicKey<G> {}
+
+impl<G: MODPGroup> AsRef<Element<G>> for PublicKey<G> {
+    fn as_ref(&self) -> &Element<G> {
+        &self.0
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use num_bigint::BigUint;
+
+    use super::*;
+    use crate::group::MODPGroup5;
+
+    type G = MODPGroup5;
+
+    #[test]
+    fn test_validate() {
+        let key = Element::<G>::from_biguint(BigUint::from(42u64));
+        let decoded = UnvalidatedPublicKey::<G>::from_bytes_be(&key.to_bytes_be()).unwrap();
+        assert_eq!(decoded.as_element(), &key);
+        assert_eq!(decoded.validate().unwrap().into_element(), key);
+
+        let tagged = UnvalidatedPublicKey::<G>::from_tagged_bytes(&key.to_tagged_bytes()).unwrap();
+        assert!(tagged.validate().is_ok());
+        let compressed =
+            UnvalidatedPublicKey::<G>::from_compressed_bytes(&key.to_compressed_bytes().unwrap())
+                .unwrap();
+        assert_eq!(compressed.validate().unwrap().as_element(), &key);
+
+        for (value, expected) in [
+            (BigUint::from(0u64), ValidationError::OutOfRange),
+            (BigUint::from(1u64), ValidationError::Degenerate),
+            (G::p_minus_one(), ValidationError::Degenerate),
+            (G::prime_modulus() - 2u64, ValidationError::NotInSubgroup),
+        ] {
+            let key = UnvalidatedPublicKey::from(Element::<G>::from_value(value));
+            assert_eq!(key.validate(), Err(expected));
+        }
+    }
+
+    #[test]
+    fn test_assume_valid_dangerous() {
+        let invalid = UnvalidatedPublicKey::from(Element::<G>::from_value(BigUint::from(1u64)));
+        assert_eq!(
+            invalid.clone().assume_valid_dangerous().as_element(),
+            invalid.as_element()
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        let key = UnvalidatedPublicKey::from(Element::<G>::from_biguint(BigUint::from(7u64)))
+            .validate()
+            .unwrap();
+        let json = serde_json::to_string(&key).unwrap();
+        assert_eq!(json, serde_json::to_string(key.as_element()).unwrap());
+        let decoded: UnvalidatedPublicKey<G> = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded.validate().unwrap(), key);
+    }
+}
@@ -0,0 +1,295 @@
+//! Python bindings of the MODP groups with [PyO3](https://pyo3.rs), enabled by the `python`
+//! feature.
+//!
+//! The module `diffie_hellman_groups` exposes:
+//! - `ModpGroup(id)`, the group numbered `id` in RFC 3526, i.e. 5, 14, 15, 16, 17 or 18, with
+//!   `generator()`, `element(x)` computing `g^x`, `element_from_bytes(data)`,
+//!   `generate_keypair()` returning a secret exponent and its public key, and
+//!   `shared_secret(x, peer)`,
+//! - `Element`, supporting `*`, `**` with an integer exponent, `to_bytes()`, `validate()` and
+//!   `int()`.
+//!
+//! Exponents are Python integers, reduced modulo q, so that negative exponents invert elements.
+//! Errors raise `DhGroupsError`, a subclass of `ValueError`. The arithmetic is that of [Element]
+//! and [Scalar] over the [MODPGroup] of each group.
+//!
+//! The bindings are exercised from Python by `tests/python/test_key_exchange.py`, run on an
+//! embedded interpreter by `cargo test --features python`.
+
+use num_bigint::{BigInt, BigUint, Sign};
+use pyo3::{create_exception, exceptions::PyValueError, prelude::*, types::PyBytes};
+
+use crate::{
+    group::{with_any_group, MODPGroup},
+    AnyModpGroup, DhGroupsError, Element, Scalar, UnvalidatedPublicKey, ValidationError,
+};
+
+create_exception!(
+    diffie_hellman_groups,
+    PyDhGroupsError,
+    PyValueError,
+    "An error of the diffie-hellman-groups crate."
+);
+
+impl From<DhGroupsError> for PyErr {
+    fn from(err: DhGroupsError) -> Self {
+        PyDhGroupsError::new_err(err.to_string())
+    }
+}
+
+/// A MODP group of RFC 3526.
+#[pyclass(name = "ModpGroup", module = "diffie_hellman_groups", frozen, eq)]
+#[derive(Clone, PartialEq, Eq)]
+pub struct PyModpGroup {
+    id: AnyModpGroup,
+}
+
+/// An element of a MODP group.
+#[pyclass(name = "Element", module = "diffie_hellman_groups", frozen, eq)]
+#[derive(Clone, PartialEq, Eq)]
+pub struct PyElement {
+    id: AnyModpGroup,
+    value: BigUint,
+}
+
+#[pymethods]
+impl PyModpGroup {
+    #[new]
+    fn new(id: u32) -> PyResult<Self> {
+        let id = AnyModpGroup::from_rfc3526_number(id)
+            .ok_or_else(|| PyDhGroupsError::new_err(format!("unknown MODP group {id}")))?;
+        Ok(PyModpGroup { id })
+    }
+
+    /// The number of the group in RFC 3526.
+    #[getter]
+    fn id(&self) -> u32 {
+        u32::from(self.id.group_id())
+    }
+
+    /// The prime modulus p.
+    #[getter]
+    fn modulus(&self) -> BigUint {
+        self.id.prime_modulus()
+    }
+
+    /// The order q of the generator.
+    #[getter]
+    fn order(&self) -> BigUint {
+        with_any_group!(self.id, G => order::<G>())
+    }
+
+    fn generator(&self) -> PyElement {
+        self.element_of(self.id.generator())
+    }
+
+    /// Compute `g^exponent`.
+    fn element(&self, exponent: BigInt) -> PyElement {
+        self.element_of(with_any_group!(self.id, G => exp::<G>(&exponent)))
+    }
+
+    /// Decode an element from big-endian bytes of the length of p.
+    fn element_from_bytes(&self, data: &[u8]) -> PyResult<PyElement> {
+        Ok(self.element_of(with_any_group!(self.id, G => from_bytes::<G>(data))?))
+    }
+
+    /// Generate a secret exponent in `[1, q)` and its public key.
+    fn generate_keypair(&self) -> (BigUint, PyElement) {
+        let (x, y) = with_any_group!(self.id, G => generate_keypair::<G>());
+        (x, self.element_of(y))
+    }
+
+    /// Validate the public key `peer` and compute `peer^secret`, in big-endian bytes of the
+    /// length of p.
+    fn shared_secret<'py>(
+        &self,
+        py: Python<'py>,
+        secret: BigUint,
+        peer: &PyElement,
+    ) -> PyResult<Bound<'py, PyBytes>> {
+        self.check(peer)?;
+        let z = with_any_group!(self.id, G => shared_secret::<G>(&secret, &peer.value))?;
+        Ok(PyBytes::new(py, &z))
+    }
+
+    fn __repr__(&self) -> String {
+        format!("ModpGroup({})", self.id())
+    }
+}
+
+impl PyModpGroup {
+    fn element_of(&self, value: BigUint) -> PyElement {
+        PyElement { id: self.id, value }
+    }
+
+    fn check(&self, element: &PyElement) -> Result<(), DhGroupsError> {
+        if element.id != self.id {
+            return Err(DhGroupsError::GroupMismatch);
+        }
+        Ok(())
+    }
+}
+
+#[pymethods]
+impl PyElement {
+    #[getter]
+    fn group(&self) -> PyModpGroup {
+        PyModpGroup { id: self.id }
+    }
+
+    fn __mul__(&self, other: &PyElement) -> PyResult<PyElement> {
+        self.group().check(other)?;
+        Ok(PyElement {
+            id: self.id,
+            value: with_any_group!(self.id, G => mul::<G>(&self.value, &other.value)),
+        })
+    }
+
+    fn __pow__(&self, exponent: BigInt, modulo: Option<&Bound<'_, PyAny>>) -> PyResult<PyElement> {
+        if modulo.is_some_and(|m| !m.is_none()) {
+            return Err(PyDhGroupsError::new_err(
+                "pow() of an element takes no modulus",
+            ));
+        }
+        Ok(PyElement {
+            id: self.id,
+            value: with_any_group!(self.id, G => pow::<G>(&self.value, &exponent)),
+        })
+    }
+
+    /// Encode the element in big-endian bytes of the length of p.
+    fn to_bytes<'py>(&self, py: Python<'py>) -> Bound<'py, PyBytes> {
+        PyBytes::new(
+            py,
+            &with_any_group!(self.id, G => to_bytes::<G>(&self.value)),
+        )
+    }
+
+    /// Check that the element is a valid public key, raising `DhGroupsError` otherwise.
+    fn validate(&self) -> PyResult<()> {
+        with_any_group!(self.id, G => validate::<G>(&self.value))
+            .map_err(|e| DhGroupsError::from(e).into())
+    }
+
+    fn __int__(&self) -> BigUint {
+        self.value.clone()
+    }
+
+    fn __hash__(&self) -> u64 {
+        self.value.iter_u64_digits().next().unwrap_or(0)
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "Element(group={}, value={:#x})",
+            self.group().id(),
+            self.value
+        )
+    }
+}
+
+/// The Python module `diffie_hellman_groups`.
+#[pymodule]
+pub fn diffie_hellman_groups(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyModpGroup>()?;
+    m.add_class::<PyElement>()?;
+    m.add("DhGroupsError", m.py().get_type::<PyDhGroupsError>())?;
+    Ok(())
+}
+
+fn order<G: MODPGroup>() -> BigUint {
+    G::subgroup_order()
+}
+
+/// Reduce `exponent` modulo q.
+fn scalar<G: MODPGroup>(exponent: &BigInt) -> Scalar<G> {
+    let scalar = Scalar::from_biguint(exponent.magnitude().clone());
+    match exponent.sign() {
+        Sign::Minus => -scalar,
+        _ => scalar,
+    }
+}
+
+fn exp<G: MODPGroup>(exponent: &BigInt) -> BigUint {
+    Element::<G>::from_biguint(scalar::<G>(exponent).value).value
+}
+
+fn pow<G: MODPGroup>(base: &BigUint, exponent: &BigInt) -> BigUint {
+    Element::<G>::from_value(base.clone())
+        .pow(&scalar::<G>(exponent).value)
+        .value
+}
+
+fn mul<G: MODPGroup>(a: &BigUint, b: &BigUint) -> BigUint {
+    (Element::<G>::from_value(a.clone()) * Element::from_value(b.clone())).value
+}
+
+fn from_bytes<G: MODPGroup>(data: &[u8]) -> Result<BigUint, DhGroupsError> {
+    Ok(Element::<G>::from_bytes_be(data)?.value)
+}
+
+fn to_bytes<G: MODPGroup>(value: &BigUint) -> Vec<u8> {
+    Element::<G>::from_value(value.clone()).to_bytes_be()
+}
+
+fn validate<G: MODPGroup>(value: &BigUint) -> Result<(), ValidationError> {
+    Element::<G>::from_value(value.clone()).validate()
+}
+
+fn generate_keypair<G: MODPGroup>() -> (BigUint, BigUint) {
+    let x = Scalar::<G>::random(&mut rand::thread_rng());
+    let y = Element::<G>::from_biguint(x.value.clone()).value;
+    (x.value, y)
+}
+
+fn shared_secret<G: MODPGroup>(secret: &BigUint, peer: &BigUint) -> Result<Vec<u8>, DhGroupsError> {
+    if *secret == BigUint::from(0u64) || *secret >= G::subgroup_order() {
+        return Err(ValidationError::OutOfRange.into());
+    }
+    let peer = UnvalidatedPublicKey::from(Element::<G>::from_value(peer.clone())).validate()?;
+    Ok(peer.as_element().pow(secret).to_bytes_be())
+}
+
+#[cfg(test)]
+mod test {
+    use std::ffi::CString;
+
+    use pyo3::types::{PyDict, PyModule};
+
+    use super::*;
+
+    const SCRIPT: &str = include_str!("../tests/python/test_key_exchange.py");
+
+    /// Run the pytest-style script on an embedded interpreter, calling each of its `test_`
+    /// functions.
+    #[test]
+    fn test_python_script() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let module = PyModule::new(py, "diffie_hellman_groups").unwrap();
+            diffie_hellman_groups(&module).unwrap();
+            py.import("sys")
+                .unwrap()
+                .getattr("modules")
+                .unwrap()
+                .set_item("diffie_hellman_groups", module)
+                .unwrap();
+
+            let globals = PyDict::new(py);
+            let script = CString::new(SCRIPT).unwrap();
+            py.run(&script, Some(&globals), None).unwrap();
+            let mut tests = 0;
+            for (name, function) in globals.iter() {
+                let name: String = name.extract().unwrap();
+                if name.starts_with("test_") {
+                    if let Err(err) = function.call0() {
+                        err.display(py);
+                        panic!("{name} failed");
+                    }
+                    tests += 1;
+                }
+            }
+            assert!(tests > 0);
+        });
+    }
+}
@@ -0,0 +1,364 @@
+//! Reduction modulo the primes of RFC 3526, which have the form
+//! `p = 2^n - 2^(n-64) - 1 + 2^64 * c` with `c < 2^(n-130)`.
+//!
+//! Their 64 most significant bits are all ones, so `d = 2^n - p` is shorter than p by more than a
+//! 64-bit limb. A limb `t` at position `j >= n/64` then folds back as `t * 2^(64j) = t * d *
+//! 2^(64j - n) mod p`, a multiplication by a single limb without any quotient to estimate, where
+//! the generic long division spends a 128-bit division and a correction step on each limb.
+
+use std::cell::RefCell;
+
+use num_bigint::BigUint;
+
+thread_local! {
+    static SCRATCH: RefCell<Scratch> = RefCell::new(Scratch::default());
+}
+
+/// Working space of the reductions of a thread, kept between calls so that a chain of
+/// multiplications does not allocate and free the same temporaries on each step, which contends
+/// on the allocator when many threads exponentiate at once.
+#[derive(Default)]
+struct Scratch {
+    /// The 64-bit limbs of the value being reduced.
+    digits: Vec<u64>,
+    /// The 64-bit limbs of the value being squared.
+    operand: Vec<u64>,
+    /// The 32-bit digits of the result, as taken by [BigUint::assign_from_slice].
+    words: Vec<u32>,
+}
+
+#[cfg(feature = "zeroize")]
+impl Scratch {
+    /// Grow the buffers to the sizes used for a modulus of `limbs` 64-bit limbs, overwriting a
+    /// buffer with zeros before it is moved to a larger allocation.
+    fn reserve(&mut self, limbs: usize) {
+        fn reserve<T: Copy + Default>(buffer: &mut Vec<T>, len: usize) {
+            if buffer.capacity() < len {
+                wipe_vec(buffer);
+                buffer.reserve(len);
+            }
+        }
+        reserve(&mut self.digits, 2 * limbs + 1);
+        reserve(&mut self.operand, limbs);
+        reserve(&mut self.words, 2 * limbs);
+    }
+
+    fn wipe(&mut self) {
+        wipe_vec(&mut self.digits);
+        wipe_vec(&mut self.operand);
+        wipe_vec(&mut self.words);
+    }
+}
+
+/// The buffers of a thread being torn down, or of a fresh scratch space, hold the limbs of its
+/// last reductions.
+#[cfg(feature = "zeroize")]
+impl Drop for Scratch {
+    fn drop(&mut self) {
+        self.wipe();
+    }
+}
+
+/// Overwrite the whole allocation of `buffer` with zeros, leaving it empty.
+#[cfg(feature = "zeroize")]
+fn wipe_vec<T: Copy + Default>(buffer: &mut Vec<T>) {
+    buffer.clear();
+    buffer.resize(buffer.capacity(), T::default());
+    std::hint::black_box(&*buffer);
+    buffer.clear();
+}
+
+/// Overwrite the scratch space of the thread with zeros, e.g. after an exponentiation by a
+/// secret exponent.
+#[cfg(feature = "zeroize")]
+pub(crate) fn wipe_scratch() {
+    with_scratch(Scratch::wipe);
+}
+
+/// Run `f` with the scratch space of the thread, or with a fresh one if it is already borrowed
+/// or the thread is being torn down.
+fn with_scratch<T>(f: impl FnOnce(&mut Scratch) -> T) -> T {
+    let mut f = Some(f);
+    SCRATCH
+        .try_with(|scratch| {
+            let mut scratch = scratch.try_borrow_mut().ok()?;
+            f.take().map(|f| f(&mut scratch))
+        })
+        .ok()
+        .flatten()
+        .unwrap_or_else(|| (f.take().expect("f is called once"))(&mut Scratch::default()))
+}
+
+/// Reduction modulo a prime `p = 2^n - d` with `n` a multiple of 64 and `d < 2^(n-64)`.
+pub(crate) struct SpecialForm {
+    p: BigUint,
+    /// The 64-bit limbs of `d = 2^n - p`, least significant first.
+    d: Vec<u64>,
+    /// The number of 64-bit limbs of p.
+    limbs: usize,
+    /// The 64-bit limbs of p, least significant first.
+    #[cfg(feature = "fixed-limbs")]
+    p_limbs: Vec<u64>,
+}
+
+impl SpecialForm {
+    /// # Panics
+    /// Panics if `p` does not have the required form.
+    pub(crate) fn new(p: &BigUint) -> Self {
+        let bits = p.bits();
+        assert!(
+            bits.is_multiple_of(64),
+            "the bit length of p is a multiple of 64"
+        );
+        let limbs = (bits / 64) as usize;
+        let d = (BigUint::from(1u64) << bits) - p;
+        assert!(d.bits() < bits - 64, "the top limb of p is all ones");
+        SpecialForm {
+            p: p.clone(),
+            d: d.to_u64_digits(),
+            limbs,
+            #[cfg(feature = "fixed-limbs")]
+            p_limbs: p.to_u64_digits(),
+        }
+    }
+
+    /// Compute `x mod p`.
+    pub(crate) fn reduce(&self, x: &BigUint) -> BigUint {
+        record!(Reduction);
+        if *x < self.p {
+            return x.clone();
+        }
+        with_scratch(|scratch| {
+            scratch.digits.clear();
+            // sized for a double-width product on first use, then reused
+            scratch.digits.reserve(2 * self.limbs + 1);
+            scratch.digits.extend(x.iter_u64_digits());
+            self.finish(scratch)
+        })
+    }
+
+    /// Compute `a^2 mod p`, squaring in the scratch space with half the limb multiplications of
+    /// a product of distinct values: each cross product `a_i * a_j` is computed once and doubled.
+    pub(crate) fn sqr_mod(&self, value: &BigUint) -> BigUint {
+        record!(Squaring);
+        record!(Reduction);
+        with_scratch(|scratch| {
+            let a = &mut scratch.operand;
+            a.clear();
+            a.extend(value.iter_u64_digits());
+            let n = a.len();
+
+            let digits = &mut scratch.digits;
+            digits.clear();
+            digits.resize((2 * n).max(self.limbs), 0);
+            // the cross products a_i * a_j for i < j
+            for (i, &ai) in a.iter().enumerate() {
+                digits[i + n] = mul_add(&mut digits[2 * i + 1..i + n], &a[i + 1..], ai);
+            }
+            // doubled, plus the squares a_i^2 on the diagonal
+            let mut shifted = 0u64;
+            let mut carry = 0u128;
+            for (i, &ai) in a.iter().enumerate() {
+                let square = ai as u128 * ai as u128;
+                for (k, half) in [(2 * i, square as u64), (2 * i + 1, (square >> 64) as u64)] {
+                    let doubled = digits[k] << 1 | shifted;
+                    shifted = digits[k] >> 63;
+                    let v = doubled as u128 + half as u128 + carry;
+                    digits[k] = v as u64;
+                    carry = v >> 64;
+                }
+            }
+            self.finish(scratch)
+        })
+    }
+
+    /// Compute `a * b mod p` with a schoolbook multiplication in the scratch space, for `a` and
+    /// `b` of at most as many limbs as p. Unlike [MODPGroup::mul](crate::group::MODPGroup::mul),
+    /// no temporary is allocated outside of the scratch space, once sized by
+    /// [SpecialForm::reserve_scratch].
+    #[cfg(feature = "zeroize")]
+    pub(crate) fn mul_mod(&self, a: &BigUint, b: &BigUint) -> BigUint {
+        record!(Multiplication);
+        record!(Reduction);
+        with_scratch(|scratch| {
+            let a_limbs = &mut scratch.operand;
+            a_limbs.clear();
+            a_limbs.extend(a.iter_u64_digits());
+            let n = a_limbs.len();
+
+            let digits = &mut scratch.digits;
+            digits.clear();
+            digits.resize((n + b.iter_u64_digits().len()).max(self.limbs), 0);
+            for (j, bj) in b.iter_u64_digits().enumerate() {
+                digits[j + n] = mul_add(&mut digits[j..j + n], a_limbs, bj);
+            }
+            self.finish(scratch)
+        })
+    }
+
+    /// Size the scratch space of the thread for the arithmetic modulo p, so that it is not moved
+    /// to a larger allocation in the middle of an exponentiation.
+    #[cfg(feature = "zeroize")]
+    pub(crate) fn reserve_scratch(&self) {
+        with_scratch(|scratch| scratch.reserve(self.limbs));
+    }
+
+    /// Compute `a * b mod p` on limbs of the length of p, writing the result to `out`.
+    #[cfg(feature = "fixed-limbs")]
+    pub(crate) fn mul_limbs(&self, a: &[u64], b: &[u64], out: &mut [u64]) {
+        record!(Multiplication);
+        record!(Reduction);
+        let n = self.limbs;
+        with_scratch(|scratch| {
+            let digits = &mut scratch.digits;
+            digits.clear();
+            digits.resize(2 * n, 0);
+            for (i, &ai) in a.iter().enumerate() {
+                digits[i + n] = mul_add(&mut digits[i..i + n], b, ai);
+            }
+            self.fold(digits);
+            out.copy_from_slice(&digits[..n]);
+        });
+        // out < 2^n = p + d < 2p
+        if out.iter().rev().cmp(self.p_limbs.iter().rev()).is_ge() {
+            let mut borrow = false;
+            for (o, &p) in out.iter_mut().zip(&self.p_limbs) {
+                let (v, b1) = o.overflowing_sub(p);
+                let (v, b2) = v.overflowing_sub(borrow as u64);
+                *o = v;
+                borrow = b1 || b2;
+            }
+        }
+    }
+
+    /// Reduce the value in `scratch.digits`, which has at least as many limbs as p, and return
+    /// it.
+    fn finish(&self, scratch: &mut Scratch) -> BigUint {
+        let digits = &mut scratch.digits;
+        self.fold(digits);
+
+        let words = &mut scratch.words;
+        words.clear();
+        words.extend(
+            digits[..self.limbs]
+                .iter()
+                .flat_map(|&d| [d as u32, (d >> 32) as u32]),
+        );
+        let mut r = BigUint::ZERO;
+        r.assign_from_slice(words);
+        // r < 2^n = p + d < 2p
+        if r >= self.p {
+            r -= &self.p;
+        }
+        r
+    }
+
+    /// Fold the limbs of `digits` above p from the top, until at most a carry into limb j is
+    /// left over, leaving a value less than 2^n in the lower limbs.
+    fn fold(&self, digits: &mut [u64]) {
+        let mut j = digits.len() - 1;
+        while j >= self.limbs {
+            let t = std::mem::take(&mut digits[j]);
+            if t == 0 {
+                j -= 1;
+                continue;
+            }
+            let offset = j - self.limbs;
+            let mut carry = mul_add(&mut digits[offset..offset + self.d.len()], &self.d, t) as u128;
+            // t * d < 2^(n-64) * t, so the carry does not pass limb j
+            let mut k = offset + self.d.len();
+            while carry != 0 {
+                let v = digits[k] as u128 + carry;
+                digits[k] = v as u64;
+                carry = v >> 64;
+                k += 1;
+            }
+        }
+    }
+}
+
+/// Add `b * t` to `acc`, which has the length of `b`, returning the carry out of it.
+fn mul_add(acc: &mut [u64], b: &[u64], t: u64) -> u64 {
+    let mut carry = 0u128;
+    for (a, &b) in acc.iter_mut().zip(b) {
+        let v = *a as u128 + t as u128 * b as u128 + carry;
+        *a = v as u64;
+        carry = v >> 64;
+    }
+    carry as u64
+}
+
+#[cfg(test)]
+mod test {
+    use num_bigint::RandBigInt;
+
+    use super::*;
+    use crate::{
+        group::{MODPGroup, MODPGroup14, MODPGroup15, MODPGroup16, MODPGroup17, MODPGroup18},
+        MODPGroup5,
+    };
+
+    fn check<G: MODPGroup>() {
+        let p = G::prime_modulus();
+        let reducer = SpecialForm::new(&p);
+        let mut rng = rand::thread_rng();
+        for _ in 0..64 {
+            let x = rng.gen_biguint(2 * p.bits());
+            assert_eq!(reducer.reduce(&x), &x % &p);
+        }
+        let edges = [
+            BigUint::ZERO,
+            &p - 1u64,
+            p.clone(),
+            &p * &p - 1u64,
+            (BigUint::from(1u64) << (2 * p.bits())) - 1u64,
+            BigUint::from(1u64) << (3 * p.bits()),
+        ];
+        for x in edges {
+            assert_eq!(reducer.reduce(&x), &x % &p);
+        }
+
+        for _ in 0..64 {
+            let a = rng.gen_biguint_below(&p);
+            assert_eq!(reducer.sqr_mod(&a), &a * &a % &p);
+        }
+        let edges = [
+            BigUint::ZERO,
+            BigUint::from(1u64),
+            BigUint::from(u64::MAX),
+            &p - 1u64,
+            p.clone(),
+            &p * 5u64 + 3u64,
+        ];
+        for a in edges {
+            assert_eq!(reducer.sqr_mod(&a), &a * &a % &p);
+        }
+    }
+
+    #[test]
+    fn test_matches_remainder() {
+        check::<MODPGroup5>();
+        check::<MODPGroup14>();
+        check::<MODPGroup15>();
+        check::<MODPGroup16>();
+        check::<MODPGroup17>();
+        check::<MODPGroup18>();
+    }
+
+    #[test]
+    fn test_scratch_fallback() {
+        let p = MODPGroup14::prime_modulus();
+        let reducer = SpecialForm::new(&p);
+        let x = &p * 3u64 + 7u64;
+        // a reduction while the scratch space is borrowed uses a fresh one
+        let nested = with_scratch(|_| reducer.reduce(&x));
+        assert_eq!(nested, BigUint::from(7u64));
+        assert_eq!(reducer.reduce(&x), BigUint::from(7u64));
+    }
+
+    #[test]
+    #[should_panic(expected = "the top limb of p is all ones")]
+    fn test_other_form() {
+        SpecialForm::new(&((BigUint::from(1u64) << 1024) - 159u64 - (BigUint::from(1u64) << 960)));
+    }
+}
@@ -0,0 +1,387 @@
+//! The Dragonfly key exchange (RFC 7664), as used by WPA3-SAE, over MODP groups: a password
+//! authenticated key exchange in which both parties derive a password element from a shared
+//! password and their identities, exchange commits, and confirm the derived key.
+//!
+//! 1. Both parties derive the password element `PE` with [derive_password_element].
+//! 2. Each party picks `rand` and `mask` and sends the commit `(scalar, element)` with
+//!    `scalar = rand + mask mod q` and `element = PE^(-mask)` ([Sae::new]).
+//! 3. Each party checks the peer's commit and computes the shared secret
+//!    `ss = (PE^peer_scalar * peer_element)^rand`, from which the key confirmation key `kck` and
+//!    the master key `mk` are derived ([Sae::process_commit]).
+//! 4. Each party sends its confirm ([SaeKeys::confirm]) and checks the peer's
+//!    ([SaeKeys::verify_confirm]).
+//!
+//! The password element is found by "hunting and pecking": for `counter = 1, 2, ...`, a candidate
+//! is derived from `D(max(id_a, id_b) || min(id_a, id_b) || password || counter)` and accepted if
+//! it is less than p and raises to an element greater than 1. The loop always runs
+//! [HUNT_AND_PECK_ITERATIONS] times, whatever the iteration of the first success, so that the
+//! number of iterations does not leak information about the password. Note that the arithmetic
+//! of `num-bigint` is not constant time.
+//!
+//! # Interoperability
+//!
+//! The key derivations use [`Digest`](digest::Digest) based expansions instead of the HMAC
+//! based KDF of RFC 7664 and IEEE 802.11, so this implementation only interoperates with itself.
+//! The groups of RFC 3526 are safe prime groups (group 14 is also the IKE group 14 of RFC 3526
+//! and SAE group 14), for which `(p - 1) / q = 2`: the password element is the square of the
+//! candidate, and a candidate is rejected only if it is 1 or `p - 1`. As candidates have the
+//! length of p and p is close to a power of two, the first iteration succeeds with overwhelming
+//! probability.
+//!
+//! # Example
+//!
+//! ```rust
+//! use sha2::Sha256;
+//! use diffie_hellman_groups::{group::MODPGroup14, sae::{self, Sae}};
+//!
+//! let rng = &mut rand::thread_rng();
+//! let pe = sae::derive_password_element::<MODPGroup14, Sha256>(b"password", b"alice", b"bob");
+//!
+//! let (alice, alice_commit) = Sae::new(pe.clone(), rng);
+//! let (bob, bob_commit) = Sae::new(pe, rng);
+//!
+//! let alice_keys = alice.process_commit::<Sha256>(&bob_commit).unwrap();
+//! let bob_keys = bob.process_commit::<Sha256>(&alice_commit).unwrap();
+//!
+//! assert!(bob_keys.verify_confirm::<Sha256>(&alice_keys.confirm::<Sha256>()));
+//! assert!(alice_keys.verify_confirm::<Sha256>(&bob_keys.confirm::<Sha256>()));
+//! assert_eq!(alice_keys.master_key(), bob_keys.master_key());
+//! ```
+
+use std::fmt;
+
+use digest::Digest;
+use num_bigint::BigUint;
+use rand_core::CryptoRngCore;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    group::MODPGroup, hash::expand_message, DhGroupsError, Element, NamedDigest, Scalar,
+    ValidationError,
+};
+
+/// The minimum number of iterations of the hunting and pecking loop.
+pub const HUNT_AND_PECK_ITERATIONS: u8 = 40;
+
+/// Domain separation tag of the password element candidates.
+const HUNT_AND_PECK_DST: &[u8] = b"Dragonfly Hunting And Pecking";
+
+/// Domain separation tag of the key confirmation key and the master key.
+const KEY_DST: &[u8] = b"Dragonfly Key Confirm Key and Master Key";
+
+/// Derive the password element of `password` shared by the parties `id_a` and `id_b`. The
+/// result does not depend on the order of the identities.
+pub fn derive_password_element<G: MODPGroup, D: NamedDigest>(
+    password: &[u8],
+    id_a: &[u8],
+    id_b: &[u8],
+) -> Element<G> {
+    let p = G::prime_modulus();
+    let p_len = p.bits().div_ceil(8) as usize;
+    let exponent = (&p - 1u64) / G::subgroup_order();
+    let one = BigUint::from(1u64);
+    let (max, min) = if id_a >= id_b {
+        (id_a, id_b)
+    } else {
+        (id_b, id_a)
+    };
+
+    let mut found = None;
+    let mut counter = 1u8;
+    // the loop continues past the fixed number of iterations only if no candidate was found
+    while counter <= HUNT_AND_PECK_ITERATIONS || found.is_none() {
+        let seed = D::new()
+            .chain_update(max)
+            .chain_update(min)
+            .chain_update(password)
+            .chain_update([counter])
+            .finalize();
+        let value =
+            BigUint::from_bytes_be(&expand_message::<D>(&seed, HUNT_AND_PECK_DST, 0, p_len));
+        if value < p {
+            let candidate = value.modpow(&exponent, &p);
+            if candidate > one && found.is_none() {
+                found = Some(candidate);
+            }
+        }
+        counter = counter.wrapping_add(1);
+    }
+
+    Element::from_value(found.expect("loop ends with an element"))
+}
+
+/// A commit message `(scalar, element)`.
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(bound = ""))]
+pub struct Commit<G: MODPGroup> {
+    pub scalar: Scalar<G>,
+    pub element: Element<G>,
+}
+
+impl<G: MODPGroup> Clone for Commit<G> {
+    fn clone(&self) -> Self {
+        Commit {
+            scalar: Scalar::from_biguint(self.scalar.value.clone()),
+            element: Element::from_value(self.element.value.clone()),
+        }
+    }
+}
+
+impl<G: MODPGroup> PartialEq for Commit<G> {
+    fn eq(&self, other: &Self) -> bool {
+        self.scalar == other.scalar && self.element == other.element
+    }
+}
+
+impl<G: MODPGroup> Eq for Commit<G> {}
+
+/// A party after sending its commit, holding the password element and its `rand`.
+pub struct Sae<G: MODPGroup> {
+    pe: Element<G>,
+    rand: Scalar<G>,
+    commit: Commit<G>,
+}
+
+impl<G: MODPGroup> Sae<G> {
+    /// Start the exchange with the password element `pe`, returning the party and the commit to
+    /// send to the peer.
+    pub fn new<R: CryptoRngCore + ?Sized>(pe: Element<G>, rng: &mut R) -> (Self, Commit<G>) {
+        let one = BigUint::from(1u64);
+        let (rand, mask, scalar) = loop {
+            let rand = Scalar::<G>::random(rng);
+            let mask = Scalar::<G>::random(rng);
+            let scalar = &rand + &mask;
+            if scalar.value > one {
+                break (rand, mask, scalar);
+            }
+        };
+        let commit = Commit {
+            scalar,
+            element: pe.pow(&(-mask).value),
+        };
+        let sae = Sae {
+            pe,
+            rand,
+            commit: commit.clone(),
+        };
+        (sae, commit)
+    }
+
+    /// Check the commit of the peer and derive the keys.
+    ///
+    /// # Errors
+    /// Returns [DhGroupsError::Validation] if the peer scalar is not in `[2, q)`, the peer element
+    /// is not in `[2, p - 1)` or not in the subgroup of order q, the peer reflected our commit,
+    /// or the shared secret is 1.
+    pub fn process_commit<D: NamedDigest>(
+        self,
+        peer: &Commit<G>,
+    ) -> Result<SaeKeys<G>, DhGroupsError> {
+        let one = BigUint::from(1u64);
+        if peer.scalar.value <= one {
+            return Err(ValidationError::OutOfRange.into());
+        }
+        if peer.element.value <= one || peer.element.value >= G::p_minus_one() {
+            return Err(ValidationError::OutOfRange.into());
+        }
+        if peer.element.pow(&G::subgroup_order()).value != one {
+            return Err(ValidationError::NotInSubgroup.into());
+        }
+        if *peer == self.commit {
+            return Err(ValidationError::Degenerate.into());
+        }
+
+        let ss = (self.pe.pow(&peer.scalar.value) * &peer.element).pow(&self.rand.value);
+        if ss.value == one {
+            return Err(ValidationError::Degenerate.into());
+        }
+
+        let len = <D as Digest>::output_size();
+        let mut input = ss.to_bytes_be();
+        input.extend_from_slice(&(&self.commit.scalar + &peer.scalar).to_bytes_be());
+        let mut okm = expand_message::<D>(&input, KEY_DST, 0, 2 * len);
+        input.fill(0);
+        std::hint::black_box(&input);
+        let mk = okm.split_off(len);
+
+        Ok(SaeKeys {
+            kck: okm,
+            mk,
+            own: self.commit,
+            peer: peer.clone(),
+        })
+    }
+}
+
+/// The keys derived from a completed commit exchange: the key confirmation key and the master
+/// key. They are zeroized on drop.
+pub struct SaeKeys<G: MODPGroup> {
+    kck: Vec<u8>,
+    mk: Vec<u8>,
+    own: Commit<G>,
+    peer: Commit<G>,
+}
+
+impl<G: MODPGroup> SaeKeys<G> {
+    /// The master key, for use as the key of the session.
+    pub fn master_key(&self) -> &[u8] {
+        &self.mk
+    }
+
+    /// Compute our confirm `D(kck || scalar || element || peer_scalar || peer_element)`.
+    pub fn confirm<D: NamedDigest>(&self) -> Vec<u8> {
+        confirm::<G, D>(&self.kck, &self.own, &self.peer)
+    }
+
+    /// Check the confirm of the peer, computed with the commits in the peer's order.
+    pub fn verify_confirm<D: NamedDigest>(&self, peer_confirm: &[u8]) -> bool {
+        let expected = confirm::<G, D>(&self.kck, &self.peer, &self.own);
+        expected.len() == peer_confirm.len()
+            && expected
+                .iter()
+                .zip(peer_confirm)
+                .fold(0u8, |acc, (a, b)| acc | (a ^ b))
+                == 0
+    }
+}
+
+impl<G: MODPGroup> Drop for SaeKeys<G> {
+    fn drop(&mut self) {
+        self.kck.fill(0);
+        self.mk.fill(0);
+        std::hint::black_box((&self.kck, &self.mk));
+    }
+}
+
+impl<G: MODPGroup> fmt::Debug for SaeKeys<G> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SaeKeys").finish_non_exhaustive()
+    }
+}
+
+fn confirm<G: MODPGroup, D: NamedDigest>(
+    kck: &[u8],
+    first: &Commit<G>,
+    second: &Commit<G>,
+) -> Vec<u8> {
+    D::new()
+        .chain_update(kck)
+        .chain_update(first.scalar.to_bytes_be())
+        .chain_update(first.element.to_bytes_be())
+        .chain_update(second.scalar.to_bytes_be())
+        .chain_update(second.element.to_bytes_be())
+        .finalize()
+        .to_vec()
+}
+
+#[cfg(test)]
+mod test {
+    use sha2::Sha256;
+
+    use super::*;
+    use crate::group::{MODPGroup14, MODPGroup5};
+
+    type G = MODPGroup5;
+
+    fn exchange(pe_a: Element<G>, pe_b: Element<G>) -> (SaeKeys<G>, SaeKeys<G>) {
+        let rng = &mut rand::thread_rng();
+        let (a, commit_a) = Sae::new(pe_a, rng);
+        let (b, commit_b) = Sae::new(pe_b, rng);
+        (
+            a.process_commit::<Sha256>(&commit_b).unwrap(),
+            b.process_commit::<Sha256>(&commit_a).unwrap(),
+        )
+    }
+
+    #[test]
+    fn test_password_element() {
+        let pe = derive_password_element::<MODPGroup14, Sha256>(b"password", b"alice", b"bob");
+        assert_eq!(
+            pe,
+            derive_password_element::<MODPGroup14, Sha256>(b"password", b"bob", b"alice")
+        );
+        assert_ne!(
+            pe,
+            derive_password_element::<MODPGroup14, Sha256>(b"passwore", b"alice", b"bob")
+        );
+        assert_ne!(
+            pe,
+            derive_password_element::<MODPGroup14, Sha256>(b"password", b"alice", b"carol")
+        );
+        assert!(pe.value > BigUint::from(1u64));
+        assert_eq!(
+            pe.pow(&MODPGroup14::sophie_garmain_prime()).value,
+            BigUint::from(1u64)
+        );
+    }
+
+    #[test]
+    fn test_same_password() {
+        let pe_a = derive_password_element::<G, Sha256>(b"password", b"alice", b"bob");
+        let pe_b = derive_password_element::<G, Sha256>(b"password", b"bob", b"alice");
+        let (keys_a, keys_b) = exchange(pe_a, pe_b);
+
+        assert_eq!(keys_a.master_key(), keys_b.master_key());
+        assert_eq!(keys_a.master_key().len(), 32);
+        assert!(keys_b.verify_confirm::<Sha256>(&keys_a.confirm::<Sha256>()));
+        assert!(keys_a.verify_confirm::<Sha256>(&keys_b.confirm::<Sha256>()));
+        // a confirm is not accepted back by its sender
+        assert!(!keys_a.verify_confirm::<Sha256>(&keys_a.confirm::<Sha256>()));
+    }
+
+    #[test]
+    fn test_wrong_password() {
+        let pe_a = derive_password_element::<G, Sha256>(b"password", b"alice", b"bob");
+        let pe_b = derive_password_element::<G, Sha256>(b"wrong password", b"bob", b"alice");
+        let (keys_a, keys_b) = exchange(pe_a, pe_b);
+
+        assert_ne!(keys_a.master_key(), keys_b.master_key());
+        assert!(!keys_b.verify_confirm::<Sha256>(&keys_a.confirm::<Sha256>()));
+        assert!(!keys_a.verify_confirm::<Sha256>(&keys_b.confirm::<Sha256>()));
+    }
+
+    #[test]
+    fn test_invalid_commits() {
+        let rng = &mut rand::thread_rng();
+        let pe = derive_password_element::<G, Sha256>(b"password", b"alice", b"bob");
+        let p = G::prime_modulus();
+        let new = || Sae::new(pe.clone(), &mut rand::thread_rng());
+        let (_, valid) = new();
+
+        let commit = Commit {
+            scalar: Scalar::from_biguint(BigUint::from(1u64)),
+            element: valid.element.clone(),
+        };
+        assert_eq!(
+            new().0.process_commit::<Sha256>(&commit).unwrap_err(),
+            DhGroupsError::Validation(ValidationError::OutOfRange)
+        );
+        for element in [BigUint::from(1u64), &p - 1u64] {
+            let commit = Commit {
+                scalar: valid.scalar.clone(),
+                element: Element::from_value(element),
+            };
+            assert_eq!(
+                new().0.process_commit::<Sha256>(&commit).unwrap_err(),
+                DhGroupsError::Validation(ValidationError::OutOfRange)
+            );
+        }
+        // -2 is not a quadratic residue modulo p, as p = 7 mod 8
+        let commit = Commit {
+            scalar: valid.scalar.clone(),
+            element: Element::from_value(&p - 2u64),
+        };
+        assert_eq!(
+            new().0.process_commit::<Sha256>(&commit).unwrap_err(),
+            DhGroupsError::Validation(ValidationError::NotInSubgroup)
+        );
+
+        // reflection of our own commit
+        let (sae, commit) = Sae::new(pe.clone(), rng);
+        assert_eq!(
+            sae.process_commit::<Sha256>(&commit).unwrap_err(),
+            DhGroupsError::Validation(ValidationError::Degenerate)
+        );
+    }
+}
@@ -0,0 +1,371 @@
+use std::ops::{Add, Mul, Neg, Sub};
+
+use num_bigint::{BigUint, RandBigInt};
+use rand_core::CryptoRngCore;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "subtle")]
+use subtle::{Choice, CtOption};
+
+use crate::{
+    element::{decode_fixed, write_padded},
+    group::MODPGroup,
+    BufferTooSmall, DhGroupsError,
+};
+
+/// An exponent of a MODP group, i.e. an integer modulo the order `q` of the subgroup generated
+/// by `g`.
+///
+/// This struct implements the Add, Sub, Mul and Neg traits, computing modulo q.
+///
+/// # Example
+///
+/// ```rust
+/// use num_bigint::BigUint;
+/// use diffie_hellman_groups::{Element, Scalar, group::MODPGroup5};
+///
+/// let a = Scalar::<MODPGroup5>::from_biguint(BigUint::from(2u32));
+/// let b = Scalar::<MODPGroup5>::from_biguint(BigUint::from(3u32));
+///
+/// // g^2 * g^3 = g^(2 + 3)
+/// let lhs = Element::<MODPGroup5>::from_biguint(a.value.clone())
+///     * Element::<MODPGroup5>::from_biguint(b.value.clone());
+/// let rhs = Element::<MODPGroup5>::from_biguint((a + b).value);
+/// assert_eq!(lhs, rhs);
+/// ```
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(bound = ""))]
+pub struct Scalar<G: MODPGroup> {
+    pub value: BigUint,
+    phantom: std::marker::PhantomData<G>,
+}
+
+impl<G: MODPGroup> Scalar<G> {
+    /// Create a Scalar from a BigUint, reduced modulo q.
+    pub fn from_biguint(value: BigUint) -> Self {
+        Scalar {
+            value: value % G::subgroup_order(),
+            phantom: std::marker::PhantomData,
+        }
+    }
+
+    /// Sample a non-zero scalar uniformly from `[1, q)`, e.g. a secret key or an ephemeral
+    /// exponent.
+    pub fn random<R: CryptoRngCore + ?Sized>(rng: &mut R) -> Self {
+        Scalar {
+            value: rng.gen_biguint_range(&BigUint::from(1u64), &G::subgroup_order()),
+            phantom: std::marker::PhantomData,
+        }
+    }
+
+    /// Encode the value in big-endian bytes, left-padded with zeros to the length of q.
+    pub fn to_bytes_be(&self) -> Vec<u8> {
+        let mut out = vec![0u8; G::subgroup_order().bits().div_ceil(8) as usize];
+        self.write_bytes_be(&mut out)
+            .expect("buffer has the length of q");
+        out
+    }
+
+    /// Write the encoding of [Scalar::to_bytes_be] to `out` without allocating, returning the
+    /// number of bytes written. As in [Element::write_bytes_be](crate::Element::write_bytes_be), a longer buffer is left-padded
+    /// with zeros in full.
+    ///
+    /// # Errors
+    /// Returns [BufferTooSmall] if `out` is shorter than q.
+    pub fn write_bytes_be(&self, out: &mut [u8]) -> Result<usize, BufferTooSmall> {
+        write_padded(
+            &self.value,
+            G::subgroup_order().bits().div_ceil(8) as usize,
+            out,
+        )
+    }
+
+    /// Decode a scalar from big-endian bytes of the length of q, as written by
+    /// [Scalar::to_bytes_be].
+    ///
+    /// # Errors
+    /// Returns [DhGroupsError::UnexpectedEof] or [DhGroupsError::InputTooLong] if `bytes` is
+    /// shorter or longer than q, or [DhGroupsError::Validation] if the value is not less than q.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, DhGroupsError> {
+        let value = decode_fixed(bytes, &G::subgroup_order())?;
+        Ok(Scalar {
+            value,
+            phantom: std::marker::PhantomData,
+        })
+    }
+
+    /// Decode a scalar like [Scalar::from_bytes], in a time independent of the secret value: all
+    /// of `bytes` is read, and the value is compared to q by subtracting their 64-bit limbs with
+    /// a borrow chain, so only the final validity flag is branched on. The result is none if
+    /// `bytes` does not have the length of q, which is not secret, or if the value is not less
+    /// than q.
+    ///
+    /// The value is still stored in a [BigUint], whose normalization strips the leading zero
+    /// limbs of the value.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use diffie_hellman_groups::{MODPGroup, Scalar, group::MODPGroup5};
+    ///
+    /// let x = Scalar::<MODPGroup5>::from_biguint(12345u64.into());
+    /// assert_eq!(Scalar::<MODPGroup5>::from_bytes_ct(&x.to_bytes_be()).unwrap(), x);
+    ///
+    /// let q = MODPGroup5::sophie_garmain_prime().to_bytes_be();
+    /// assert!(bool::from(Scalar::<MODPGroup5>::from_bytes_ct(&q).is_none()));
+    /// ```
+    #[cfg(feature = "subtle")]
+    pub fn from_bytes_ct(bytes: &[u8]) -> CtOption<Self> {
+        let q = G::subgroup_order();
+        let len = q.bits().div_ceil(8) as usize;
+        let value = Scalar {
+            value: BigUint::from_bytes_be(bytes),
+            phantom: std::marker::PhantomData,
+        };
+        if bytes.len() != len {
+            return CtOption::new(value, Choice::from(0));
+        }
+        let mut limbs = vec![0u64; len.div_ceil(8)];
+        for (i, byte) in bytes.iter().rev().enumerate() {
+            limbs[i / 8] |= u64::from(*byte) << (8 * (i % 8));
+        }
+        let mut bound = q.to_u64_digits();
+        bound.resize(limbs.len(), 0);
+        let is_less = ct_less_than(&limbs, &bound);
+        limbs.fill(0);
+        std::hint::black_box(&limbs);
+        CtOption::new(value, is_less)
+    }
+
+    /// Compute the multiplicative inverse modulo q. Returns `None` for zero.
+    pub fn inverse(&self) -> Option<Self> {
+        self.value.modinv(&G::subgroup_order()).map(|value| Scalar {
+            value,
+            phantom: std::marker::PhantomData,
+        })
+    }
+}
+
+impl<G: MODPGroup> PartialEq for Scalar<G> {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+impl<G: MODPGroup> Eq for Scalar<G> {}
+
+impl<G: MODPGroup> AsRef<BigUint> for Scalar<G> {
+    fn as_ref(&self) -> &BigUint {
+        &self.value
+    }
+}
+
+// Implementing the Add, Sub, Mul and Neg traits for the Scalar struct
+
+impl<G: MODPGroup> Add for Scalar<G> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        &self + &rhs
+    }
+}
+
+impl<G: MODPGroup> Add for &Scalar<G> {
+    type Output = Scalar<G>;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Scalar::from_biguint(&self.value + &rhs.value)
+    }
+}
+
+impl<G: MODPGroup> Add<&Scalar<G>> for Scalar<G> {
+    type Output = Scalar<G>;
+
+    fn add(self, rhs: &Scalar<G>) -> Self::Output {
+        &self + rhs
+    }
+}
+
+impl<G: MODPGroup> Add<Scalar<G>> for &Scalar<G> {
+    type Output = Scalar<G>;
+
+    fn add(self, rhs: Scalar<G>) -> Self::Output {
+        self + &rhs
+    }
+}
+
+impl<G: MODPGroup> Sub for Scalar<G> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        &self - &rhs
+    }
+}
+
+impl<G: MODPGroup> Sub for &Scalar<G> {
+    type Output = Scalar<G>;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Scalar::from_biguint(&self.value + G::subgroup_order() - &rhs.value)
+    }
+}
+
+impl<G: MODPGroup> Sub<&Scalar<G>> for Scalar<G> {
+    type Output = Scalar<G>;
+
+    fn sub(self, rhs: &Scalar<G>) -> Self::Output {
+        &self - rhs
+    }
+}
+
+impl<G: MODPGroup> Sub<Scalar<G>> for &Scalar<G> {
+    type Output = Scalar<G>;
+
+    fn sub(self, rhs: Scalar<G>) -> Self::Output {
+        self - &rhs
+    }
+}
+
+impl<G: MODPGroup> Mul for Scalar<G> {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        &self * &rhs
+    }
+}
+
+impl<G: MODPGroup> Mul for &Scalar<G> {
+    type Output = Scalar<G>;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        Scalar::from_biguint(&self.value * &rhs.value)
+    }
+}
+
+impl<G: MODPGroup> Mul<&Scalar<G>> for Scalar<G> {
+    type Output = Scalar<G>;
+
+    fn mul(self, rhs: &Scalar<G>) -> Self::Output {
+        &self * rhs
+    }
+}
+
+impl<G: MODPGroup> Mul<Scalar<G>> for &Scalar<G> {
+    type Output = Scalar<G>;
+
+    fn mul(self, rhs: Scalar<G>) -> Self::Output {
+        self * &rhs
+    }
+}
+
+impl<G: MODPGroup> Neg for Scalar<G> {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        -&self
+    }
+}
+
+impl<G: MODPGroup> Neg for &Scalar<G> {
+    type Output = Scalar<G>;
+
+    fn neg(self) -> Self::Output {
+        Scalar::from_biguint(G::subgroup_order() - &self.value)
+    }
+}
+
+/// Whether `a < b`, for little-endian 64-bit limbs of the same length, computed from the final
+/// borrow of `a - b` without branching on the limbs.
+#[cfg(feature = "subtle")]
+pub(crate) fn ct_less_than(a: &[u64], b: &[u64]) -> Choice {
+    debug_assert_eq!(a.len(), b.len());
+    let mut borrow = 0u64;
+    for (a, b) in a.iter().zip(b) {
+        let (difference, borrow_1) = a.overflowing_sub(*b);
+        let (_, borrow_2) = difference.overflowing_sub(borrow);
+        borrow = u64::from(borrow_1 | borrow_2);
+    }
+    Choice::from(borrow as u8)
+}
+
+/// Overwrite the digits of `x` with zeros in place, keeping its allocation.
+pub(crate) fn wipe(x: &mut BigUint) {
+    let zeros = vec![0u32; x.iter_u32_digits().len()];
+    x.assign_from_slice(&zeros);
+    std::hint::black_box(&*x);
+}
+
+#[cfg(test)]
+mod test {
+    #[cfg(feature = "subtle")]
+    #[test]
+    fn test_from_bytes_ct() {
+        use num_bigint::RandBigInt;
+        use rand::Rng;
+
+        use super::*;
+        use crate::MODPGroup5;
+
+        type S = Scalar<MODPGroup5>;
+
+        let q = MODPGroup5::sophie_garmain_prime();
+        let mut rng = rand::thread_rng();
+        let mut inputs: Vec<Vec<u8>> = [
+            BigUint::ZERO,
+            BigUint::from(1u64),
+            rng.gen_biguint_below(&q),
+            &q - 1u64,
+            q.clone(),
+            &q + 1u64,
+            // differs from q in its lowest limb only
+            &q + (1u64 << 63),
+        ]
+        .iter()
+        .map(|x| {
+            let mut bytes = vec![0u8; 192];
+            let be = x.to_bytes_be();
+            bytes[192 - be.len()..].copy_from_slice(&be);
+            bytes
+        })
+        .collect();
+        inputs.extend([vec![0xff; 192], vec![0; 191], vec![0; 193], vec![]]);
+        for _ in 0..64 {
+            let mut bytes = vec![0u8; 192];
+            rng.fill(&mut bytes[..]);
+            inputs.push(bytes);
+        }
+
+        for bytes in inputs {
+            let ct: Option<S> = S::from_bytes_ct(&bytes).into();
+            assert_eq!(ct, S::from_bytes(&bytes).ok(), "{bytes:02x?}");
+        }
+    }
+
+    #[cfg(feature = "subtle")]
+    #[test]
+    fn test_ct_less_than() {
+        use super::ct_less_than;
+
+        let cases = [
+            ([0, 0], [0, 0], false),
+            ([1, 0], [2, 0], true),
+            ([2, 0], [1, 0], false),
+            ([u64::MAX, 0], [0, 1], true),
+            ([0, 1], [u64::MAX, 0], false),
+            ([5, 7], [5, 7], false),
+            ([4, 7], [5, 7], true),
+        ];
+        for (a, b, expected) in cases {
+            assert_eq!(bool::from(ct_less_than(&a, &b)), expected, "{a:?} < {b:?}");
+        }
+    }
+
+    #[test]
+    fn test_wipe() {
+        use super::*;
+
+        let mut x = BigUint::from(u128::MAX);
+        wipe(&mut x);
+        assert_eq!(x, BigUint::from(0u64));
+    }
+}
@@ -0,0 +1,521 @@
+//! Schnorr signatures over the subgroup of prime order `q` of a MODP group.
+//!
+//! The secret key is a [Scalar] `x` and the public key is `y = g^x mod p`. A signature of `msg` is
+//! `(R, s) = (g^k, k + e * x mod q)` for a nonce `k`, where the challenge is
+//! `e = D(R || y || msg) mod q` with the elements encoded as in [Element::to_bytes_be]. It is
+//! valid if `g^s = R * y^e`.
+//!
+//! Signatures are also available in the shorter form `(e, s)` ([CompactSignature]), which is
+//! verified by recomputing `R = g^s * y^(-e)` and the challenge.
+//!
+//! Many signatures `(R_i, s_i)` under the keys `y_i` can be verified at once ([batch_verify]) by
+//! checking `g^(-sum z_i s_i) * prod R_i^z_i * y_i^(z_i e_i) = 1` for random 128-bit weights
+//! `z_i`, with a single multi-exponentiation ([Element::multi_pow]). An invalid signature passes
+//! with probability at most `2^-128`, provided that `R_i` and `y_i` are in the subgroup of order
+//! q, i.e. are quadratic residues. The signatures whose elements are not are verified one by one.
+//! [batch_verify_identify] falls back to verifying each signature to find the invalid ones.
+//!
+//! # Example
+//!
+//! ```rust
+//! use sha2::Sha256;
+//! use diffie_hellman_groups::{schnorr, Element, Scalar, group::MODPGroup14};
+//!
+//! let sk = Scalar::<MODPGroup14>::random(&mut rand::thread_rng());
+//! let pk = Element::<MODPGroup14>::from_biguint(sk.value.clone());
+//!
+//! let sig = schnorr::sign::<_, Sha256, _>(&sk, b"message", &mut rand::thread_rng());
+//! assert!(schnorr::verify::<_, Sha256>(&pk, b"message", &sig));
+//! ```
+
+use num_bigint::{BigUint, RandBigInt};
+use rand_core::CryptoRngCore;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{group::MODPGroup, hash::expand_message, DhGroupsError, Element, NamedDigest, Scalar};
+
+/// Domain separation tag of the deterministic nonces.
+const NONCE_DST: &[u8] = b"diffie-hellman-groups schnorr nonce";
+
+/// A Schnorr signature in the form `(R, s)`.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(bound = ""))]
+pub struct Signature<G: MODPGroup> {
+    pub r: Element<G>,
+    pub s: Scalar<G>,
+}
+
+/// A Schnorr signature in the form `(e, s)`, where `e` is the challenge.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(bound = ""))]
+pub struct CompactSignature<G: MODPGroup> {
+    pub e: Scalar<G>,
+    pub s: Scalar<G>,
+}
+
+impl<G: MODPGroup> PartialEq for Signature<G> {
+    fn eq(&self, other: &Self) -> bool {
+        self.r == other.r && self.s == other.s
+    }
+}
+
+impl<G: MODPGroup> Eq for Signature<G> {}
+
+impl<G: MODPGroup> PartialEq for CompactSignature<G> {
+    fn eq(&self, other: &Self) -> bool {
+        self.e == other.e && self.s == other.s
+    }
+}
+
+impl<G: MODPGroup> Eq for CompactSignature<G> {}
+
+impl<G: MODPGroup> Signature<G> {
+    /// Convert the signature to the form `(e, s)`, recomputing the challenge from the public key
+    /// and the message.
+    pub fn to_compact<D: NamedDigest>(&self, pk: &Element<G>, msg: &[u8]) -> CompactSignature<G> {
+        CompactSignature {
+            e: challenge::<G, D>(&self.r, pk, msg),
+            s: Scalar::from_biguint(self.s.value.clone()),
+        }
+    }
+}
+
+impl<G: MODPGroup> CompactSignature<G> {
+    /// Encode the signature as `e || s`, each as in [Scalar::to_bytes_be].
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = self.e.to_bytes_be();
+        bytes.extend_from_slice(&self.s.to_bytes_be());
+        bytes
+    }
+
+    /// Decode a signature written by [CompactSignature::to_bytes].
+    ///
+    /// # Errors
+    /// Returns [DhGroupsError::UnexpectedEof] or [DhGroupsError::InputTooLong] if `bytes` is not
+    /// twice the length of q, or [DhGroupsError::Validation] if a value is not less than q.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, DhGroupsError> {
+        let len = G::subgroup_order().bits().div_ceil(8) as usize;
+        let (e, s) = bytes.split_at(len.min(bytes.len()));
+        Ok(CompactSignature {
+            e: Scalar::from_bytes(e)?,
+            s: Scalar::from_bytes(s)?,
+        })
+    }
+}
+
+/// Sign `msg` with the secret key `sk`, using a random nonce from `rng`.
+pub fn sign<G: MODPGroup, D: NamedDigest, R: CryptoRngCore + ?Sized>(
+    sk: &Scalar<G>,
+    msg: &[u8],
+    rng: &mut R,
+) -> Signature<G> {
+    sign_with_nonce::<G, D>(sk, msg, &Scalar::random(rng))
+}
+
+/// Sign `msg` with the secret key `sk`, using a nonce derived deterministically from the secret
+/// key and the message, in the spirit of RFC 6979.
+///
+/// The nonce is `D`-expanded from `x || msg` (see [deterministic_nonce]) so that signing the same
+/// message twice gives the same signature, and no randomness is needed.
+pub fn sign_deterministic<G: MODPGroup, D: NamedDigest>(
+    sk: &Scalar<G>,
+    msg: &[u8],
+) -> Signature<G> {
+    sign_with_nonce::<G, D>(sk, msg, &deterministic_nonce::<G, D>(sk, msg))
+}
+
+/// Sign `msg` as in [sign], returning the signature in the form `(e, s)`.
+pub fn sign_compact<G: MODPGroup, D: NamedDigest, R: CryptoRngCore + ?Sized>(
+    sk: &Scalar<G>,
+    msg: &[u8],
+    rng: &mut R,
+) -> CompactSignature<G> {
+    let k = Scalar::random(rng);
+    let (e, s) = sign_inner::<G, D>(sk, msg, &k);
+    CompactSignature { e, s }
+}
+
+/// Verify the signature `sig` of `msg` under the public key `pk`.
+pub fn verify<G: MODPGroup, D: NamedDigest>(
+    pk: &Element<G>,
+    msg: &[u8],
+    sig: &Signature<G>,
+) -> bool {
+    if !is_element(pk) || !is_element(&sig.r) {
+        return false;
+    }
+    let e = challenge::<G, D>(&sig.r, pk, msg);
+    Element::<G>::from_biguint(sig.s.value.clone()) == &sig.r * pk.pow(&e.value)
+}
+
+/// Verify the signature `sig` in the form `(e, s)` of `msg` under the public key `pk`.
+pub fn verify_compact<G: MODPGroup, D: NamedDigest>(
+    pk: &Element<G>,
+    msg: &[u8],
+    sig: &CompactSignature<G>,
+) -> bool {
+    if !is_element(pk) {
+        return false;
+    }
+    let r = Element::<G>::from_biguint(sig.s.value.clone()) * pk.pow(&(-&sig.e).value);
+    challenge::<G, D>(&r, pk, msg) == sig.e
+}
+
+/// Verify the signatures `(pk, msg, sig)` of `items` together. Returns `true` if and only if
+/// every signature verifies with [verify], except with probability at most `2^-128` over the
+/// weights drawn from `rng`.
+pub fn batch_verify<G: MODPGroup, D: NamedDigest, R: CryptoRngCore + ?Sized>(
+    items: &[(Element<G>, &[u8], Signature<G>)],
+    rng: &mut R,
+) -> bool {
+    let q = G::subgroup_order();
+    let mut sum = BigUint::from(0u64);
+    let mut exponents = Vec::with_capacity(2 * items.len());
+    for (pk, msg, sig) in items {
+        if !is_element(pk) || !is_element(&sig.r) {
+            return false;
+        }
+        if !is_quadratic_residue(pk) || !is_quadratic_residue(&sig.r) {
+            if !verify::<G, D>(pk, msg, sig) {
+                return false;
+            }
+            continue;
+        }
+        let z = rng.gen_biguint(128);
+        let e = challenge::<G, D>(&sig.r, pk, msg);
+        sum += &z * &sig.s.value;
+        exponents.push((&sig.r, z.clone()));
+        exponents.push((pk, z * e.value % &q));
+    }
+
+    let g = Element::<G>::from_value(G::generator());
+    let minus_sum = &q - sum % &q;
+    let mut terms: Vec<_> = exponents.iter().map(|(base, z)| (*base, z)).collect();
+    terms.push((&g, &minus_sum));
+    Element::multi_pow(&terms).value == BigUint::from(1u64)
+}
+
+/// Verify the signatures of `items` as in [batch_verify], and if the batch fails, verify each
+/// signature to identify the invalid ones. Returns the indices of the invalid signatures, which
+/// is empty if all are valid.
+pub fn batch_verify_identify<G: MODPGroup, D: NamedDigest, R: CryptoRngCore + ?Sized>(
+    items: &[(Element<G>, &[u8], Signature<G>)],
+    rng: &mut R,
+) -> Vec<usize> {
+    if batch_verify::<G, D, R>(items, rng) {
+        return Vec::new();
+    }
+    items
+        .iter()
+        .enumerate()
+        .filter(|(_, (pk, msg, sig))| !verify::<G, D>(pk, msg, sig))
+        .map(|(i, _)| i)
+        .collect()
+}
+
+/// Derive the nonce used by [sign_deterministic]: the bytes `D`-expanded from the secret key
+/// (padded to the length of q) and the message, reduced modulo q. The expansion produces 128
+/// bits more than the size of q, so the bias of the reduction is negligible. It is repeated with
+/// an incremented counter in the (negligibly likely) case the nonce would be zero.
+pub fn deterministic_nonce<G: MODPGroup, D: NamedDigest>(sk: &Scalar<G>, msg: &[u8]) -> Scalar<G> {
+    let q = G::subgroup_order();
+    let q_len = q.bits().div_ceil(8) as usize;
+
+    let key = sk.value.to_bytes_be();
+    let mut input = vec![0u8; q_len - key.len()];
+    input.extend_from_slice(&key);
+    input.extend_from_slice(msg);
+
+    let mut counter = 0u32;
+    loop {
+        let bytes = expand_message::<D>(&input, NONCE_DST, counter, q_len + 16);
+        let k = Scalar::<G>::from_biguint(BigUint::from_bytes_be(&bytes));
+        if k.value != BigUint::from(0u64) {
+            return k;
+        }
+        counter += 1;
+    }
+}
+
+fn sign_with_nonce<G: MODPGroup, D: NamedDigest>(
+    sk: &Scalar<G>,
+    msg: &[u8],
+    k: &Scalar<G>,
+) -> Signature<G> {
+    let (_, s) = sign_inner::<G, D>(sk, msg, k);
+    Signature {
+        r: Element::from_biguint(k.value.clone()),
+        s,
+    }
+}
+
+/// Compute the challenge `e` and the response `s = k + e * x mod q`.
+fn sign_inner<G: MODPGroup, D: NamedDigest>(
+    sk: &Scalar<G>,
+    msg: &[u8],
+    k: &Scalar<G>,
+) -> (Scalar<G>, Scalar<G>) {
+    let r = Element::<G>::from_biguint(k.value.clone());
+    let pk = Element::<G>::from_biguint(sk.value.clone());
+    let e = challenge::<G, D>(&r, &pk, msg);
+    let s = k + &e * sk;
+    (e, s)
+}
+
+/// Compute the challenge `e = D(R || y || msg) mod q`.
+pub(crate) fn challenge<G: MODPGroup, D: NamedDigest>(
+    r: &Element<G>,
+    pk: &Element<G>,
+    msg: &[u8],
+) -> Scalar<G> {
+    let digest = D::new()
+        .chain_update(r.to_bytes_be())
+        .chain_update(pk.to_bytes_be())
+        .chain_update(msg)
+        .finalize();
+    Scalar::from_biguint(BigUint::from_bytes_be(&digest))
+}
+
+/// Check that the element is in `[1, p)`.
+fn is_element<G: MODPGroup>(x: &Element<G>) -> bool {
+    x.value > BigUint::from(0u64) && x.value < G::prime_modulus()
+}
+
+/// Check that the element is a quadratic residue modulo p, i.e. in the subgroup of order q,
+/// by computing its Jacobi symbol, which is much cheaper than the exponentiation `x^q`.
+fn is_quadratic_residue<G: MODPGroup>(x: &Element<G>) -> bool {
+    let (mut a, mut n) = (x.value.clone(), G::prime_modulus());
+    let mut symbol = true;
+    while a != BigUint::from(0u64) {
+        let zeros = a.trailing_zeros().unwrap_or(0);
+        a >>= zeros;
+        // (2/n) = -1 iff n = 3 or 5 mod 8
+        let n_mod_8 = n.iter_u64_digits().next().unwrap_or(0) & 7;
+        if zeros % 2 == 1 && (n_mod_8 == 3 || n_mod_8 == 5) {
+            symbol = !symbol;
+        }
+        // quadratic reciprocity
+        let a_mod_4 = a.iter_u64_digits().next().unwrap_or(0) & 3;
+        if a_mod_4 == 3 && n_mod_8 & 3 == 3 {
+            symbol = !symbol;
+        }
+        std::mem::swap(&mut a, &mut n);
+        a %= &n;
+    }
+    symbol && n == BigUint::from(1u64)
+}
+
+#[cfg(test)]
+mod test {
+    use sha2::{Sha256, Sha512};
+
+    use super::*;
+    use crate::group::{MODPGroup14, MODPGroup15, MODPGroup5};
+
+    fn keypair<G: MODPGroup>() -> (Scalar<G>, Element<G>) {
+        let sk = Scalar::<G>::random(&mut rand::thread_rng());
+        let pk = Element::<G>::from_biguint(sk.value.clone());
+        (sk, pk)
+    }
+
+    fn test_sign_verify<G: MODPGroup>() {
+        let rng = &mut rand::thread_rng();
+        let (sk, pk) = keypair::<G>();
+        let (_, other_pk) = keypair::<G>();
+        let msg = b"message";
+
+        let sig = sign::<G, Sha256, _>(&sk, msg, rng);
+        assert!(verify::<G, Sha256>(&pk, msg, &sig));
+        assert!(!verify::<G, Sha512>(&pk, msg, &sig));
+        assert!(!verify::<G, Sha256>(&other_pk, msg, &sig));
+
+        // flip a bit of the message
+        assert!(!verify::<G, Sha256>(&pk, b"messagf", &sig));
+        // flip a bit of each part of the signature
+        let flipped = Signature {
+            r: Element::from_value(&sig.r.value ^ BigUint::from(1u64)),
+            s: Scalar::from_biguint(sig.s.value.clone()),
+        };
+        assert!(!verify::<G, Sha256>(&pk, msg, &flipped));
+        let flipped = Signature {
+            r: Element::from_value(sig.r.value.clone()),
+            s: Scalar::from_biguint(&sig.s.value ^ BigUint::from(1u64)),
+        };
+        assert!(!verify::<G, Sha256>(&pk, msg, &flipped));
+
+        let compact = sig.to_compact::<Sha256>(&pk, msg);
+        assert!(verify_compact::<G, Sha256>(&pk, msg, &compact));
+        assert!(!verify_compact::<G, Sha256>(&other_pk, msg, &compact));
+        assert!(!verify_compact::<G, Sha256>(&pk, b"messagf", &compact));
+
+        let compact = sign_compact::<G, Sha256, _>(&sk, msg, rng);
+        assert!(verify_compact::<G, Sha256>(&pk, msg, &compact));
+        assert_eq!(
+            CompactSignature::from_bytes(&compact.to_bytes()).unwrap(),
+            compact
+        );
+    }
+
+    #[test]
+    fn test_sign_verify_group_5() {
+        test_sign_verify::<MODPGroup5>();
+    }
+
+    #[test]
+    fn test_sign_verify_group_14() {
+        test_sign_verify::<MODPGroup14>();
+    }
+
+    #[test]
+    fn test_sign_verify_group_15() {
+        test_sign_verify::<MODPGroup15>();
+    }
+
+    #[test]
+    fn test_deterministic() {
+        let (sk, pk) = keypair::<MODPGroup5>();
+        let sig = sign_deterministic::<_, Sha256>(&sk, b"message");
+        assert!(verify::<_, Sha256>(&pk, b"message", &sig));
+        assert_eq!(sig, sign_deterministic::<_, Sha256>(&sk, b"message"));
+        assert_ne!(sig, sign_deterministic::<_, Sha256>(&sk, b"other message"));
+        assert_ne!(
+            deterministic_nonce::<_, Sha256>(&sk, b"message"),
+            deterministic_nonce::<_, Sha512>(&sk, b"message")
+        );
+
+        // pinned nonce for a fixed key
+        let sk = Scalar::<MODPGroup5>::from_biguint(BigUint::from(42u64));
+        let k = deterministic_nonce::<_, Sha256>(&sk, b"message");
+        assert_eq!(k.value.iter_u64_digits().next(), Some(0xf4c544ac1c0196f4));
+    }
+
+    #[test]
+    fn test_digests() {
+        let (sk, pk) = keypair::<MODPGroup5>();
+        let sig_256 = sign_deterministic::<_, Sha256>(&sk, b"message");
+        let sig_512 = sign_deterministic::<_, Sha512>(&sk, b"message");
+        assert_ne!(sig_256, sig_512);
+        assert!(verify::<_, Sha256>(&pk, b"message", &sig_256));
+        assert!(verify::<_, Sha512>(&pk, b"message", &sig_512));
+        assert!(!verify::<_, Sha512>(&pk, b"message", &sig_256));
+        assert!(!verify::<_, Sha256>(&pk, b"message", &sig_512));
+    }
+
+    fn batch<G: MODPGroup>(n: usize) -> Vec<(Element<G>, &'static [u8], Signature<G>)> {
+        let rng = &mut rand::thread_rng();
+        (0..n)
+            .map(|i| {
+                let (sk, pk) = keypair::<G>();
+                let msg: &[u8] = [b"message a".as_slice(), b"message b"][i % 2];
+                let sig = sign::<G, Sha256, _>(&sk, msg, rng);
+                (pk, msg, sig)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_batch_verify() {
+        let rng = &mut rand::thread_rng();
+        let mut items = batch::<MODPGroup5>(16);
+        assert!(batch_verify::<_, Sha256, _>(&items, rng));
+        assert!(batch_verify_identify::<_, Sha256, _>(&items, rng).is_empty());
+        assert!(batch_verify::<MODPGroup5, Sha256, _>(&[], rng));
+
+        // a corrupted signature fails the batch and is identified
+        items[5].2.s = &items[5].2.s + Scalar::from_biguint(BigUint::from(1u64));
+        assert!(!batch_verify::<_, Sha256, _>(&items, rng));
+        assert_eq!(batch_verify_identify::<_, Sha256, _>(&items, rng), vec![5]);
+
+        // a signature moved to another message
+        let mut items = batch::<MODPGroup5>(4);
+        items[2].1 = b"message c";
+        items[3].2 = Signature {
+            r: Element::from_value(items[0].2.r.value.clone()),
+            s: Scalar::from_biguint(items[0].2.s.value.clone()),
+        };
+        assert_eq!(
+            batch_verify_identify::<_, Sha256, _>(&items, rng),
+            vec![2, 3]
+        );
+    }
+
+    #[test]
+    fn test_batch_verify_outside_subgroup() {
+        type G = MODPGroup5;
+        let rng = &mut rand::thread_rng();
+        let p = G::prime_modulus();
+        let minus = |x: &Element<G>| Element::<G>::from_value(&p - &x.value);
+
+        // two signatures whose verification equations are off by -1 would cancel out with even
+        // weights, so they are verified one by one
+        let mut items = batch::<G>(4);
+        for item in &mut items[..2] {
+            item.2.r = minus(&item.2.r);
+        }
+        assert!(!batch_verify::<_, Sha256, _>(&items, rng));
+        assert_eq!(
+            batch_verify_identify::<_, Sha256, _>(&items, rng),
+            vec![0, 1]
+        );
+
+        // a signature under a key outside the subgroup which verifies individually, as
+        // (-y)^e = y^e for an even challenge e
+        let (sk, pk) = keypair::<G>();
+        let pk = minus(&pk);
+        let sig = std::iter::repeat_with(|| Scalar::<G>::random(rng))
+            .find_map(|k| {
+                let r = Element::<G>::from_biguint(k.value.clone());
+                let e = challenge::<G, Sha256>(&r, &pk, b"message");
+                (!e.value.bit(0)).then(|| Signature { r, s: k + &e * &sk })
+            })
+            .unwrap();
+        assert!(verify::<_, Sha256>(&pk, b"message", &sig));
+        let mut items = batch::<G>(3);
+        items.push((pk, b"message", sig));
+        assert!(batch_verify::<_, Sha256, _>(&items, rng));
+    }
+
+    #[test]
+    fn test_is_quadratic_residue() {
+        type G = MODPGroup5;
+        let g = Element::<G>::from_value(G::generator());
+        assert!(is_quadratic_residue(&g));
+        assert!(is_quadratic_residue(&Element::<G>::from_biguint(
+            Scalar::<G>::random(&mut rand::thread_rng()).value
+        )));
+        // -1 is not a quadratic residue, as p = 3 mod 4
+        assert!(!is_quadratic_residue(&Element::<G>::from_value(
+            G::prime_modulus() - 1u64
+        )));
+        assert!(!is_quadratic_residue(&Element::<G>::from_value(
+            G::prime_modulus() - 2u64
+        )));
+    }
+
+    #[test]
+    #[ignore = "timing, run in release mode"]
+    #[cfg(not(target_arch = "wasm32"))]
+    fn test_batch_verify_faster() {
+        use std::time::Instant;
+
+        let rng = &mut rand::thread_rng();
+        let items = batch::<MODPGroup14>(64);
+        let start = Instant::now();
+        assert!(items
+            .iter()
+            .all(|(pk, msg, sig)| verify::<_, Sha256>(pk, msg, sig)));
+        let individual = start.elapsed();
+        let start = Instant::now();
+        assert!(batch_verify::<_, Sha256, _>(&items, rng));
+        assert!(start.elapsed() < individual);
+    }
+
+    #[test]
+    fn test_invalid_public_key() {
+        let (sk, _) = keypair::<MODPGroup5>();
+        let sig = sign::<_, Sha256, _>(&sk, b"message", &mut rand::thread_rng());
+        let zero = Element::<MODPGroup5>::from_value(BigUint::from(0u64));
+        assert!(!verify::<_, Sha256>(&zero, b"message", &sig));
+    }
+}
@@ -0,0 +1,183 @@
+//! Wrapping of secret exponents and shared secrets in the types of
+//! [secrecy](https://crates.io/crates/secrecy), so that they are zeroized when dropped, redacted in
+//! `Debug` output, never serialized, and only read through [ExposeSecret::expose_secret].
+//!
+//! # Example
+//!
+//! ```rust
+//! use diffie_hellman_groups::{
+//!     kdf::SharedSecret, secret::ExposeSecret, Element, MODPGroup5, Scalar,
+//! };
+//!
+//! let mut rng = rand::thread_rng();
+//! let x = Scalar::<MODPGroup5>::random(&mut rng);
+//! let exponent = x.expose_exponent();
+//! assert!(format!("{exponent:?}").contains("[REDACTED]"));
+//!
+//! let restored = Scalar::<MODPGroup5>::from_secret(&exponent).unwrap();
+//! assert_eq!(restored.value, x.value);
+//!
+//! let shared = SharedSecret::new(Element::<MODPGroup5>::from_biguint(x.value.clone()));
+//! let bytes = shared.into_secret_bytes();
+//! assert_eq!(bytes.expose_secret().len(), 192);
+//! ```
+
+#[cfg(feature = "primegroup")]
+use std::sync::Arc;
+
+use num_bigint::BigUint;
+use secrecy::zeroize::Zeroize;
+pub use secrecy::{ExposeSecret, SecretBox, SecretSlice};
+
+use crate::{
+    group::MODPGroup, kdf::SharedSecret, scalar::wipe, DhGroupsError, Scalar, ValidationError,
+};
+#[cfg(feature = "primegroup")]
+use crate::{PrimeGroup, RuntimeSecretKey, RuntimeSharedSecret};
+
+/// A secret exponent, e.g. a private key. It implements neither `Debug` nor `Serialize`, and its
+/// digits are overwritten with zeros when the [SecretBox] holding it is dropped.
+pub struct SecretExponent(BigUint);
+
+impl SecretExponent {
+    /// Wrap the exponent `x` in a [SecretBox].
+    pub fn new_secret(x: BigUint) -> SecretBox<Self> {
+        SecretBox::new(Box::new(SecretExponent(x)))
+    }
+
+    /// The exponent as an integer.
+    pub fn as_biguint(&self) -> &BigUint {
+        &self.0
+    }
+}
+
+impl Zeroize for SecretExponent {
+    fn zeroize(&mut self) {
+        wipe(&mut self.0);
+    }
+}
+
+impl<G: MODPGroup> Scalar<G> {
+    /// Copy the value into a [SecretBox], e.g. to hand a private key to code that should only
+    /// read it through [ExposeSecret].
+    pub fn expose_exponent(&self) -> SecretBox<SecretExponent> {
+        SecretExponent::new_secret(self.value.clone())
+    }
+
+    /// Create a scalar from a secret exponent, as returned by [Scalar::expose_exponent].
+    ///
+    /// # Errors
+    /// Returns [DhGroupsError::Validation] if the exponent is not less than q.
+    pub fn from_secret(secret: &SecretBox<SecretExponent>) -> Result<Self, DhGroupsError> {
+        let x = secret.expose_secret().as_biguint();
+        if *x >= G::subgroup_order() {
+            return Err(ValidationError::OutOfRange.into());
+        }
+        Ok(Scalar::from_biguint(x.clone()))
+    }
+}
+
+impl<G: MODPGroup> SharedSecret<G> {
+    /// Consume the secret, returning its encoding as in [SharedSecret::to_bytes_be] in a
+    /// [SecretSlice].
+    pub fn into_secret_bytes(self) -> SecretSlice<u8> {
+        SecretSlice::from(self.to_bytes_be())
+    }
+}
+
+#[cfg(feature = "primegroup")]
+impl RuntimeSecretKey {
+    /// Copy the secret exponent x into a [SecretBox].
+    pub fn expose_exponent(&self) -> SecretBox<SecretExponent> {
+        SecretExponent::new_secret(self.exponent().clone())
+    }
+
+    /// Create the secret key of `group` from a secret exponent, e.g. one returned by
+    /// [RuntimeSecretKey::expose_exponent].
+    ///
+    /// # Errors
+    /// Returns [DhGroupsError::Validation] if the exponent is not in `[1, q)`.
+    pub fn from_secret(
+        group: Arc<PrimeGroup>,
+        secret: &SecretBox<SecretExponent>,
+    ) -> Result<Self, DhGroupsError> {
+        let x = secret.expose_secret().as_biguint();
+        if *x == BigUint::ZERO || *x >= group.q {
+            return Err(ValidationError::OutOfRange.into());
+        }
+        Ok(RuntimeSecretKey::new(group, x.clone()))
+    }
+}
+
+#[cfg(feature = "primegroup")]
+impl RuntimeSharedSecret {
+    /// Consume the secret, returning its padded encoding as in [RuntimeSharedSecret::to_bytes_be]
+    /// in a [SecretSlice].
+    pub fn into_secret_bytes(self) -> SecretSlice<u8> {
+        SecretSlice::from(self.to_bytes_be())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{Element, MODPGroup5};
+
+    #[test]
+    fn test_scalar_round_trip() {
+        let mut rng = rand::thread_rng();
+        let x = Scalar::<MODPGroup5>::random(&mut rng);
+        let secret = x.expose_exponent();
+        assert_eq!(
+            format!("{secret:?}"),
+            "SecretBox<diffie_hellman_groups::secret::SecretExponent>([REDACTED])"
+        );
+        assert_eq!(
+            Scalar::<MODPGroup5>::from_secret(&secret).unwrap().value,
+            x.value
+        );
+
+        let q = SecretExponent::new_secret(MODPGroup5::sophie_garmain_prime());
+        assert_eq!(
+            Scalar::<MODPGroup5>::from_secret(&q).unwrap_err(),
+            ValidationError::OutOfRange.into()
+        );
+    }
+
+    #[test]
+    fn test_shared_secret_bytes() {
+        let shared = SharedSecret::new(Element::<MODPGroup5>::from_biguint(BigUint::from(42u64)));
+        let expected = shared.to_bytes_be();
+        let bytes = shared.into_secret_bytes();
+        assert_eq!(format!("{bytes:?}"), "SecretBox<[u8]>([REDACTED])");
+        assert_eq!(bytes.expose_secret(), &expected[..]);
+    }
+
+    #[test]
+    fn test_zeroize() {
+        let mut secret = SecretExponent(BigUint::from(u128::MAX));
+        secret.zeroize();
+        assert_eq!(secret.0, BigUint::ZERO);
+    }
+
+    #[cfg(feature = "primegroup")]
+    #[test]
+    fn test_runtime_keys() {
+        let mut rng = rand::thread_rng();
+        let group = Arc::new(PrimeGroup::new_with(BigUint::from(1623299u64), 15).unwrap());
+        let (secret, public) = group.generate_keypair(&mut rng);
+        let exponent = secret.expose_exponent();
+        assert!(format!("{exponent:?}").ends_with("([REDACTED])"));
+
+        let restored = RuntimeSecretKey::from_secret(group.clone(), &exponent).unwrap();
+        assert_eq!(restored.public_key(), public);
+        let zero = SecretExponent::new_secret(BigUint::ZERO);
+        assert!(RuntimeSecretKey::from_secret(group.clone(), &zero).is_err());
+
+        let shared = secret.diffie_hellman(&public).unwrap();
+        let expected = shared.to_bytes_be();
+        let bytes = shared.into_secret_bytes();
+        assert_eq!(format!("{bytes:?}"), "SecretBox<[u8]>([REDACTED])");
+        assert_eq!(bytes.expose_secret(), &expected[..]);
+    }
+}
@@ -0,0 +1,277 @@
+//! A known-answer self-test of the MODP groups, to be run e.g. at application startup as the
+//! power-on self-test of FIPS 140 style deployments.
+//!
+//! [self_test] checks the structure of the constants of all groups of RFC 3526 and RFC 5114, and
+//! runs a key exchange in group 14 with fixed private keys against public keys and a shared secret
+//! computed independently and pinned below, and the key exchanges of the test data of RFC 5114
+//! Appendix A in groups 22 and 24. It takes a few milliseconds.
+//!
+//! # Example
+//!
+//! ```rust
+//! diffie_hellman_groups::self_test().expect("self-test");
+//! ```
+
+use num_bigint::BigUint;
+
+use crate::{
+    group::{
+        MODPGroup, MODPGroup14, MODPGroup15, MODPGroup16, MODPGroup17, MODPGroup18, MODPGroup22,
+        MODPGroup23, MODPGroup24, MODPGroup5,
+    },
+    AnyModpGroup, Element, SelfTestError,
+};
+
+/// Private key of A, `SHA-256("diffie-hellman-groups self test A")`.
+const XA: &[u8] = b"5EEB5623C395916FCCC68C1F2047F2C30882C78357AA6011044E114B4E2D8F";
+
+/// Private key of B, `SHA-256("diffie-hellman-groups self test B")`.
+const XB: &[u8] = b"C4C33C45AF0326B3DE931FF910733ADC25DFF1A25D161520BEDA4DBDF8F83659";
+
+/// Public key of A, `2^XA mod p` in group 14.
+const YA: &[u8] = b"98B36AEF288CEF36EFEFEBF485EAD1E04A7F15E69EBD414D\
+    26ED5469332491EBD7E2A75E0C73280A986916C83DFBE82F\
+    7AB1965404281F9B599F7337596DCACF67080606E74D94C4\
+    934730F45F782A893ECEBE4CC23E0AD40649C286B8391E10\
+    5096B5D2E4FCB82277E83ED15A8241DF3979DC5513E0834B\
+    ED2B2D500F45E129970D0B5731A159FEA5BE9C87C54E73CE\
+    A18DB6CDA6F5D5072716A5535A4192BD9765B97488163A64\
+    023377EC78BF85DDDD14CD746B675823AE59615C3C75ABF6\
+    25E1A047A7F2418A3BD2794156768EED20EB274E034A9609\
+    E889ECC62F59A4ED0E253AA21AB49CA28621B796C71B479A\
+    D7EF04D4B67EDAA3A817DE2BEF32E416";
+
+/// Public key of B, `2^XB mod p` in group 14.
+const YB: &[u8] = b"5279D08F9BE046931899EE7DF250F54C18F7F93688DF6FAB\
+    845D22A6DD29D079700E12E80F9B01C423BE5B13E81A2BD3\
+    36672E87FEFDA2CF5C6AB3F10A61F45F843E433E3F8D6645\
+    B4C8C423DA5AF501FDD2B6900A0E79557A902E3255DC3620\
+    8A02D456D81DFE62AF1B01FB835078F9383061BB31061744\
+    32688643E1F1A9FBEEDD555CBA73939C5D6B47CC54056F3A\
+    21F7609BC5EEC07D6C96833C6F032E2B7E2544E10F2B7709\
+    E68F65845062385A21E24EAB5BDC7463A199E836073AB9DB\
+    444EC35CB8E3E7EC516F87BF34F497981790CB263F6DE0E0\
+    F99512C80DD9DEA492B3558A6F5090E79CDB19D11E64570E\
+    DC6B26620A30F9200ABBA3AFEB454B4D";
+
+/// Shared secret `YB^XA = YA^XB mod p` in group 14.
+const Z: &[u8] = b"605AFA679F9FB5F21365D1C5FA9A12ECF7C116A764280D95\
+    B027D1B5C266F130CD80E1B09D1E95E8378458917BB4A0A0\
+    94CC01B7B34B1B5508120968BC1EEF3911CE65B0B474BDFC\
+    18F57ECBADD69031299727C30615A686CDF8890164CC7E91\
+    034C536EEFC276AD2AB517304B6520B161E665317FD54007\
+    C1D79697B56B2CFAF698AF61EA6283C55631307CABB142D6\
+    DD92B10998D68C6E9D3F6355FE7A05C66AFD2C2A582DB66E\
+    BD6773338C3C8100B7313F1FE59F82096C04478EE7C98687\
+    07BD61A216BC061E75E625E19AD04935F92FCA959893A313\
+    3E7CFEC7F3596CF82889595555C44E7FEE0DA24C0ABAA8C4\
+    C7ADB656154CB90F04933C4E8722A9B4";
+
+/// Private key of A in group 22, from RFC 5114 Appendix A.1.
+const XA_22: &[u8] = b"B9A3B3AE8FEFC1A2930496507086F8455D48943E";
+
+/// Private key of B in group 22, from RFC 5114 Appendix A.1.
+const XB_22: &[u8] = b"9392C9F9EB6A7A6A9022F7D83E7223C6835BBDDA";
+
+/// Public key of A, `g^XA_22 mod p` in group 22, from RFC 5114 Appendix A.1.
+const YA_22: &[u8] = b"2A853B3D92197501B9015B2DEB3ED84F5E021DCC3E52F109\
+    D3273D2B7521281CBABE0E76FF5727FA8ACCE26956BA9A1F\
+    CA26F20228D8693FEB10841D84A7360054ECE5A7F5B7A61A\
+    D3DFB3C60D2E43106D8727DA37DF9CCE95B478755D06BCEA\
+    8F9D45965F75A5F3D1DF3701165FC9E50C4279CEB07F9895\
+    40AE96D5D88ED776";
+
+/// Public key of B, `g^XB_22 mod p` in group 22, from RFC 5114 Appendix A.1.
+const YB_22: &[u8] = b"717A6CB053371FF4A3B932941C1E5663F861A1D6AD34AE66\
+    576DFB98F6C6CBF9DDD5A56C7833F6BCFDFF095582AD868E\
+    440E8D09FD769E3CECCDC3D3B1E4CFA057776CAAF9739B6A\
+    9FEE8E7411F8D6DAC09D6A4EDB46CC2B5D5203090EAE6126\
+    311E53FD2C14B574E6A3109A3DA1BE41BDCEAA186F5CE067\
+    16A2B6A07B3C33FE";
+
+/// Shared secret in group 22, from RFC 5114 Appendix A.1.
+const Z_22: &[u8] = b"5C804F454D30D9C4DF85271F93528C91DF6B48AB5F80B3B5\
+    9CAAC1B28F8ACBA9CD3E39F3CB614525D9521D2E644C53B8\
+    07B810F340062F257D7D6FBFE8D5E8F072E9B6E9AFDA9413\
+    EAFB2E8B0699B1FB5A0CACEDDEAEAD7E9CFBB36AE2B42083\
+    5BD83A19FB0B5E96BF8FA4D09E345525167ECD9155416F46\
+    F408ED31B63C6E6D";
+
+/// Private key of A in group 24, from RFC 5114 Appendix A.3.
+const XA_24: &[u8] = b"0881382CDB87660C6DC13E614938D5B9C8B2F248581CC5E3\
+    1B35454397FCE50E";
+
+/// Private key of B in group 24, from RFC 5114 Appendix A.3.
+const XB_24: &[u8] = b"7D62A7E3EF36DE617B13D1AFB82C780D83A23BD4EE670564\
+    5121F371F546A53D";
+
+/// Public key of A, `g^XA_24 mod p` in group 24, from RFC 5114 Appendix A.3.
+const YA_24: &[u8] = b"2E9380C8323AF97545BC4941DEB0EC3742C62FE0ECE824A6\
+    ABDBE66C59BEE0242911BFB967235CEBA35AE13E4EC752BE\
+    630B92DC4BDE2847A9C62CB8152745421FB7EB60A63C0FE9\
+    159FCCE726CE7CD8523D7450667EF840E4919121EB5F01C8\
+    C9B0D3D648A93BFB75689E8244AC134AF544711CE79A02DC\
+    C34226684780DDDCB498594106C37F5BC79856487AF5AB02\
+    2A2E5E42F09897C1A85A11EA0212AF04D9B4CEBC937C3C1A\
+    3E15A8A0342E337615C84E7FE3B8B9B87FB1E73A15AF12A3\
+    0D746E06DFC34F290D797CE51AA13AA785BF6658AFF5E4B0\
+    93003CBEAF665B3C2E113A3A4E905269341DC0711426685F\
+    4EF37E868A8126FF3F2279B57CA67E29";
+
+/// Public key of B, `g^XB_24 mod p` in group 24, from RFC 5114 Appendix A.3.
+const YB_24: &[u8] = b"575F0351BD2B1B817448BDF87A6C362C1E289D3903A30B98\
+    32C5741FA250363E7ACBC7F77F3DACBC1F131ADD8E03367E\
+    FF8FBBB3E1C5784424809B25AFE4D2262A1A6FD2FAB64105\
+    CA30A674E07F7809852088632FC049233791AD4EDD083A97\
+    8B883EE618BC5E0DD047415F2D95E683CF14826B5FBE10D3\
+    CE41C6C120C78AB20008C698BF7F0BCAB9D7F407BED0F43A\
+    FB2970F57F8D12043963E66DDD320D599AD9936C8F44137C\
+    08B180EC5E985CEBE186F3D549677E80607331EE17AF3380\
+    A725B0782317D7DD43F59D7AF9568A9BB63A84D365F92244\
+    ED120988219302F42924C7CA90B89D24F71B0AB697823D7D\
+    EB1AFF5B0E8E4A45D49F7F53757E1913";
+
+/// Shared secret in group 24, from RFC 5114 Appendix A.3.
+const Z_24: &[u8] = b"86C70BF8D0BB81BB01078A17219CB7D27203DB2A19C877F1\
+    D1F19FD7D77EF22546A68F005AD52DC84553B78FC60330BE\
+    51EA7C0672CAC1515E4B35C047B9A551B88F39DC26DA14A0\
+    9EF74774D47C762DD177F9ED5BC2F11E52C879BD95098504\
+    CD9EECD8A8F9B3EFBD1F008AC5853097D9D1837F2B18F77C\
+    D7BE01AF80A7C7B5EA3CA54CC02D0C116FEE3F95BB873993\
+    85875D7E86747E676E728938ACBFF7098E05BE4DCFB24052\
+    B83AEFFB14783F029ADBDE7F53FAE92084224090E007CEE9\
+    4D4BF2BACE9FFD4B57D2AF7C724D0CAA19BF0501F6F17B4A\
+    A10F425E3EA76080B4B9D6B3CEFEA115B2CEB8789BB8A3B0\
+    EA87FEBE63B6C8F846EC6DB0C26C5D7C";
+
+/// Run the known-answer self-test.
+///
+/// # Errors
+/// - [SelfTestError::GroupConstant] if the modulus of a group of RFC 3526 does not have the size
+///   of its identifier and 64 leading and trailing one bits, is not `2q + 1`, or its generator is
+///   not 2, or if p, q or g of a group of RFC 5114 do not have the sizes and lowest 64 bits of the
+///   RFC, q does not divide `p - 1`, or g does not have order q.
+/// - [SelfTestError::KnownAnswer] if a key exchange does not give the pinned values, or the
+///   generator of its group does not have order q.
+pub fn self_test() -> Result<(), SelfTestError> {
+    check_constants::<MODPGroup5>(AnyModpGroup::Modp1536, 1536)?;
+    check_constants::<MODPGroup14>(AnyModpGroup::Modp2048, 2048)?;
+    check_constants::<MODPGroup15>(AnyModpGroup::Modp3072, 3072)?;
+    check_constants::<MODPGroup16>(AnyModpGroup::Modp4096, 4096)?;
+    check_constants::<MODPGroup17>(AnyModpGroup::Modp6144, 6144)?;
+    check_constants::<MODPGroup18>(AnyModpGroup::Modp8192, 8192)?;
+    check_subgroup_constants::<MODPGroup22>(
+        AnyModpGroup::Modp1024S160,
+        (1024, 160),
+        [0xDF1FB2BC2E4A4371, 0x64B7CB9D49462353, 0x855E6EEB22B3B2E5],
+    )?;
+    check_subgroup_constants::<MODPGroup23>(
+        AnyModpGroup::Modp2048S224,
+        (2048, 224),
+        [0x0AC4DFFE0C10E64F, 0xBF389A99B36371EB, 0x84B890D3191F2BFA],
+    )?;
+    check_subgroup_constants::<MODPGroup24>(
+        AnyModpGroup::Modp2048S256,
+        (2048, 256),
+        [0xDB094AE91E1A1597, 0xA308B0FE64F5FBD3, 0x664B4C0F6CC41659],
+    )?;
+    check_exchange::<MODPGroup14>(AnyModpGroup::Modp2048, [XA, XB, YA, YB, Z])?;
+    check_exchange::<MODPGroup22>(
+        AnyModpGroup::Modp1024S160,
+        [XA_22, XB_22, YA_22, YB_22, Z_22],
+    )?;
+    check_exchange::<MODPGroup24>(
+        AnyModpGroup::Modp2048S256,
+        [XA_24, XB_24, YA_24, YB_24, Z_24],
+    )
+}
+
+fn check_constants<G: MODPGroup>(id: AnyModpGroup, bits: u64) -> Result<(), SelfTestError> {
+    let p = G::prime_modulus();
+    let ones = BigUint::from(u64::MAX);
+    let valid = p.bits() == bits
+        && (&p >> (bits - 64)) == ones
+        && (&p & &ones) == ones
+        && p == G::sophie_garmain_prime() * 2u64 + 1u64
+        && G::generator() == BigUint::from(2u64)
+        && id.prime_modulus() == p;
+    if valid {
+        Ok(())
+    } else {
+        Err(SelfTestError::GroupConstant(id))
+    }
+}
+
+/// Check the parameters of a group of RFC 5114 against the sizes in bits of p and q and the
+/// lowest 64 bits of p, q and g.
+fn check_subgroup_constants<G: MODPGroup>(
+    id: AnyModpGroup,
+    (bits, q_bits): (u64, u64),
+    lowest: [u64; 3],
+) -> Result<(), SelfTestError> {
+    let (p, q, g) = (G::prime_modulus(), G::subgroup_order(), G::generator());
+    let low = |x: &BigUint| x.iter_u64_digits().next().unwrap_or(0);
+    let valid = p.bits() == bits
+        && q.bits() == q_bits
+        && [low(&p), low(&q), low(&g)] == lowest
+        && (&p - 1u64) % &q == BigUint::from(0u64)
+        && g > BigUint::from(1u64)
+        && g < p
+        && G::pow(&g, &q) == BigUint::from(1u64)
+        && id.prime_modulus() == p;
+    if valid {
+        Ok(())
+    } else {
+        Err(SelfTestError::GroupConstant(id))
+    }
+}
+
+/// Run the key exchange of the private keys `[XA, XB]` in `G` and compare it with the pinned
+/// `[YA, YB, Z]`, all in hexadecimal.
+fn check_exchange<G: MODPGroup>(
+    id: AnyModpGroup,
+    [xa, xb, ya, yb, z]: [&[u8]; 5],
+) -> Result<(), SelfTestError> {
+    let pinned = |hex: &[u8]| BigUint::parse_bytes(hex, 16).expect("pinned hex value");
+    let (xa, xb, z) = (pinned(xa), pinned(xb), pinned(z));
+    let public_a = Element::<G>::from_biguint(xa.clone());
+    let public_b = Element::<G>::from_biguint(xb.clone());
+    let (za, zb) = (public_b.pow(&xa), public_a.pow(&xb));
+    let g = Element::<G>::from_value(G::generator());
+
+    let valid = public_a.value == pinned(ya)
+        && public_b.value == pinned(yb)
+        && za.value == z
+        && zb.value == z
+        && public_a.validate().is_ok()
+        && public_b.validate().is_ok()
+        && g.pow(&G::subgroup_order()).value == BigUint::from(1u64);
+    if valid {
+        Ok(())
+    } else {
+        Err(SelfTestError::KnownAnswer(id))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_self_test() {
+        assert_eq!(self_test(), Ok(()));
+    }
+
+    #[test]
+    fn test_mismatch() {
+        let id = AnyModpGroup::Modp1024S160;
+        assert_eq!(
+            check_exchange::<MODPGroup22>(id, [XA_22, XB_22, YA_22, YB_22, XA_22]),
+            Err(SelfTestError::KnownAnswer(id))
+        );
+        assert_eq!(
+            check_subgroup_constants::<MODPGroup22>(id, (1024, 160), [0, 0, 0]),
+            Err(SelfTestError::GroupConstant(id))
+        );
+    }
+}
@@ -0,0 +1,581 @@
+//! An ephemeral Diffie-Hellman handshake with key confirmation, as state machines of the
+//! initiator and the responder whose steps consume the previous state.
+//!
+//! 1. The initiator sends a nonce and its ephemeral public key `g^x` ([Initiator::start]).
+//! 2. The responder validates `g^x`, answers with its nonce, `g^y` and its confirm
+//!    ([Responder::respond]).
+//! 3. The initiator validates `g^y`, checks the confirm of the responder and sends its own
+//!    ([Initiator::finish]), which the responder checks ([Responder::finish]).
+//!
+//! The keys are derived with HKDF over `D`, with the hash of the transcript `hello || reply` (the
+//! reply without its confirm) as salt and `g^(xy)` as input key material. The confirm key and the
+//! [SessionKeys] are expanded under distinct labels, and each confirm is the HMAC of the label of
+//! its sender's role, so that a confirm cannot be reflected to its sender. As the transcript
+//! includes fresh nonces of both parties, messages replayed from another session fail the
+//! confirmation.
+//!
+//! Messages have a serde and a binary encoding: the versioned header of [wire](crate::wire), a
+//! message type byte, then each field prefixed by its length as a big-endian `u32`. Decoding a
+//! message of another type fails with [DhGroupsError::UnexpectedMessageType].
+//!
+//! # Example
+//!
+//! ```rust
+//! use sha2::Sha256;
+//! use diffie_hellman_groups::{
+//!     group::MODPGroup5,
+//!     session::{ConfirmMsg, HelloMsg, Initiator, ReplyMsg, Responder},
+//! };
+//!
+//! let rng = &mut rand::thread_rng();
+//! let (initiator, hello) = Initiator::<MODPGroup5>::start(rng);
+//! let hello = HelloMsg::<MODPGroup5>::decode(&hello.encode()).unwrap();
+//! let (responder, reply) = Responder::respond::<Sha256, _>(&hello, rng).unwrap();
+//! let reply = ReplyMsg::<MODPGroup5>::decode(&reply.encode()).unwrap();
+//! let (keys_a, confirm) = initiator.finish::<Sha256>(&reply).unwrap();
+//! let confirm = ConfirmMsg::decode(&confirm.encode()).unwrap();
+//! let keys_b = responder.finish::<Sha256>(&confirm).unwrap();
+//! assert_eq!(keys_a.encryption, keys_b.encryption);
+//! assert_eq!(keys_a.mac, keys_b.mac);
+//! ```
+
+use std::fmt;
+
+use digest::{core_api::BlockSizeUser, Digest};
+use hkdf::SimpleHkdf;
+use hmac::{Mac, SimpleHmac};
+use rand::Rng;
+use rand_core::CryptoRngCore;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    group::MODPGroup,
+    ssh::{read_string, write_string},
+    wire::{read_header, read_version, write_header, WireVersion},
+    DhGroupsError, Element, NamedDigest, PublicKey, Scalar, UnvalidatedPublicKey, ValidationError,
+};
+
+/// Message type of [HelloMsg].
+pub const MSG_HELLO: u8 = 1;
+/// Message type of [ReplyMsg].
+pub const MSG_REPLY: u8 = 2;
+/// Message type of [ConfirmMsg].
+pub const MSG_CONFIRM: u8 = 3;
+
+/// HKDF info of the confirm key.
+const CONFIRM_KEY_LABEL: &[u8] = b"diffie-hellman-groups session confirm";
+/// HKDF info of the encryption key.
+const ENCRYPTION_KEY_LABEL: &[u8] = b"diffie-hellman-groups session encryption";
+/// HKDF info of the MAC key.
+const MAC_KEY_LABEL: &[u8] = b"diffie-hellman-groups session mac";
+/// Label of the confirm of the initiator.
+const INITIATOR_LABEL: &[u8] = b"initiator";
+/// Label of the confirm of the responder.
+const RESPONDER_LABEL: &[u8] = b"responder";
+
+/// The first message, sent by the initiator.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(bound = ""))]
+pub struct HelloMsg<G: MODPGroup> {
+    pub nonce: [u8; 32],
+    pub public: UnvalidatedPublicKey<G>,
+}
+
+/// The second message, sent by the responder.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(bound = ""))]
+pub struct ReplyMsg<G: MODPGroup> {
+    pub nonce: [u8; 32],
+    pub public: UnvalidatedPublicKey<G>,
+    /// Confirm of the responder.
+    pub confirm: Vec<u8>,
+}
+
+/// The third message, the confirm of the initiator.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ConfirmMsg {
+    pub confirm: Vec<u8>,
+}
+
+/// The initiator after sending [HelloMsg], holding its exponent `x`.
+pub struct Initiator<G: MODPGroup> {
+    x: Scalar<G>,
+    hello: HelloMsg<G>,
+}
+
+/// The responder after sending [ReplyMsg], holding the derived keys.
+pub struct Responder<G: MODPGroup> {
+    keys: Keys,
+    phantom: std::marker::PhantomData<G>,
+}
+
+/// The keys agreed by a completed handshake, of the output size of the digest. They are
+/// zeroized on drop.
+pub struct SessionKeys {
+    pub encryption: Vec<u8>,
+    pub mac: Vec<u8>,
+}
+
+/// The confirm key and the session keys.
+struct Keys {
+    confirm: Vec<u8>,
+    session: SessionKeys,
+}
+
+impl<G: MODPGroup> Initiator<G> {
+    /// Start the handshake, returning the initiator and the message to send to the responder.
+    pub fn start<R: CryptoRngCore + ?Sized>(rng: &mut R) -> (Self, HelloMsg<G>) {
+        let x = Scalar::<G>::random(rng);
+        let public = PublicKey::from_secret_power(Element::from_biguint(x.value.clone()));
+        let hello = HelloMsg {
+            nonce: rng.gen(),
+            public: public.into(),
+        };
+        let msg = HelloMsg {
+            nonce: hello.nonce,
+            public: hello.public.clone(),
+        };
+        (Initiator { x, hello }, msg)
+    }
+
+    /// Check the reply of the responder, returning the session keys and the confirm to send.
+    ///
+    /// # Errors
+    /// Returns [DhGroupsError::Validation] if `g^y` is not a valid element or is a reflection of
+    /// `g^x`, or [DhGroupsError::AuthenticationFailed] if the confirm does not verify.
+    pub fn finish<D: NamedDigest + BlockSizeUser + Clone>(
+        self,
+        reply: &ReplyMsg<G>,
+    ) -> Result<(SessionKeys, ConfirmMsg), DhGroupsError> {
+        let peer = reply.public.clone().validate()?;
+        if peer.as_element() == self.hello.public.as_element() {
+            return Err(ValidationError::Degenerate.into());
+        }
+        let keys = Keys::derive::<G, D>(&peer.as_element().pow(&self.x.value), &self.hello, reply);
+        keys.verify::<D>(RESPONDER_LABEL, &reply.confirm)?;
+        let confirm = ConfirmMsg {
+            confirm: keys.confirm::<D>(INITIATOR_LABEL),
+        };
+        Ok((keys.into_session(), confirm))
+    }
+}
+
+impl<G: MODPGroup> Responder<G> {
+    /// Answer the hello of the initiator, returning the responder and the message to send.
+    ///
+    /// # Errors
+    /// Returns [DhGroupsError::Validation] if `g^x` is not a valid element.
+    pub fn respond<D: NamedDigest + BlockSizeUser + Clone, R: CryptoRngCore + ?Sized>(
+        hello: &HelloMsg<G>,
+        rng: &mut R,
+    ) -> Result<(Self, ReplyMsg<G>), DhGroupsError> {
+        let peer = hello.public.clone().validate()?;
+        let y = Scalar::<G>::random(rng);
+        let public = PublicKey::from_secret_power(Element::from_biguint(y.value.clone()));
+        let mut reply = ReplyMsg {
+            nonce: rng.gen(),
+            public: public.into(),
+            confirm: Vec::new(),
+        };
+        let keys = Keys::derive::<G, D>(&peer.as_element().pow(&y.value), hello, &reply);
+        reply.confirm = keys.confirm::<D>(RESPONDER_LABEL);
+        let responder = Responder {
+            keys,
+            phantom: std::marker::PhantomData,
+        };
+        Ok((responder, reply))
+    }
+
+    /// Check the confirm of the initiator, returning the session keys.
+    ///
+    /// # Errors
+    /// Returns [DhGroupsError::AuthenticationFailed] if the confirm does not verify.
+    pub fn finish<D: NamedDigest + BlockSizeUser + Clone>(
+        self,
+        msg: &ConfirmMsg,
+    ) -> Result<SessionKeys, DhGroupsError> {
+        self.keys.verify::<D>(INITIATOR_LABEL, &msg.confirm)?;
+        Ok(self.keys.into_session())
+    }
+}
+
+impl Keys {
+    /// Derive the keys from the shared element and the transcript.
+    fn derive<G: MODPGroup, D: NamedDigest + BlockSizeUser + Clone>(
+        shared: &Element<G>,
+        hello: &HelloMsg<G>,
+        reply: &ReplyMsg<G>,
+    ) -> Self {
+        let transcript = D::new()
+            .chain_update(hello.encode())
+            .chain_update(reply.nonce)
+            .chain_update(reply.public.as_element().to_bytes_be())
+            .finalize();
+        let hkdf = SimpleHkdf::<D>::new(Some(&transcript), &shared.to_bytes_be());
+        let expand = |label: &[u8]| {
+            let mut key = vec![0u8; <D as Digest>::output_size()];
+            hkdf.expand(label, &mut key)
+                .expect("key length is valid for HKDF");
+            key
+        };
+        Keys {
+            confirm: expand(CONFIRM_KEY_LABEL),
+            session: SessionKeys {
+                encryption: expand(ENCRYPTION_KEY_LABEL),
+                mac: expand(MAC_KEY_LABEL),
+            },
+        }
+    }
+
+    fn mac<D: NamedDigest + BlockSizeUser>(&self, role: &[u8]) -> SimpleHmac<D> {
+        let mut mac = SimpleHmac::<D>::new_from_slice(&self.confirm).expect("any key length");
+        mac.update(role);
+        mac
+    }
+
+    fn confirm<D: NamedDigest + BlockSizeUser>(&self, role: &[u8]) -> Vec<u8> {
+        self.mac::<D>(role).finalize().into_bytes().to_vec()
+    }
+
+    fn verify<D: NamedDigest + BlockSizeUser>(
+        &self,
+        role: &[u8],
+        tag: &[u8],
+    ) -> Result<(), DhGroupsError> {
+        self.mac::<D>(role)
+            .verify_slice(tag)
+            .map_err(|_| DhGroupsError::AuthenticationFailed)
+    }
+
+    fn into_session(mut self) -> SessionKeys {
+        std::mem::replace(
+            &mut self.session,
+            SessionKeys {
+                encryption: Vec::new(),
+                mac: Vec::new(),
+            },
+        )
+    }
+}
+
+impl Drop for Keys {
+    fn drop(&mut self) {
+        self.confirm.fill(0);
+        std::hint::black_box(&self.confirm);
+    }
+}
+
+impl Drop for SessionKeys {
+    fn drop(&mut self) {
+        self.encryption.fill(0);
+        self.mac.fill(0);
+        std::hint::black_box((&self.encryption, &self.mac));
+    }
+}
+
+impl fmt::Debug for SessionKeys {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SessionKeys").finish_non_exhaustive()
+    }
+}
+
+impl<G: MODPGroup> HelloMsg<G> {
+    /// Encode the message as `header || MSG_HELLO || nonce || public`, each field prefixed by its
+    /// length.
+    pub fn encode(&self) -> Vec<u8> {
+        let mut buf = Vec::new();
+        write_header::<G>(&mut buf);
+        buf.push(MSG_HELLO);
+        write_string(&mut buf, &self.nonce);
+        write_string(&mut buf, &self.public.as_element().to_bytes_be());
+        buf
+    }
+
+    /// Decode a message written by [HelloMsg::encode].
+    ///
+    /// # Errors
+    /// Returns [DhGroupsError::UnsupportedVersion] or [DhGroupsError::GroupMismatch] if the
+    /// header is not accepted, [DhGroupsError::UnexpectedMessageType] for another message,
+    /// [DhGroupsError::UnexpectedEof] or [DhGroupsError::TrailingBytes] if a field does not have
+    /// its length, [DhGroupsError::InputTooLong] if the public key is longer than p, or
+    /// [DhGroupsError::Validation] if it is not less than p.
+    pub fn decode(bytes: &[u8]) -> Result<Self, DhGroupsError> {
+        let mut reader = Reader::new(read_header::<G>(bytes)?, MSG_HELLO)?;
+        let msg = HelloMsg {
+            nonce: reader.read_nonce()?,
+            public: UnvalidatedPublicKey::from_bytes_be(reader.read()?)?,
+        };
+        reader.finish()?;
+        Ok(msg)
+    }
+}
+
+impl<G: MODPGroup> ReplyMsg<G> {
+    /// Encode the message as `header || MSG_REPLY || nonce || public || confirm`, each field
+    /// prefixed by its length.
+    pub fn encode(&self) -> Vec<u8> {
+        let mut buf = Vec::new();
+        write_header::<G>(&mut buf);
+        buf.push(MSG_REPLY);
+        write_string(&mut buf, &self.nonce);
+        write_string(&mut buf, &self.public.as_element().to_bytes_be());
+        write_string(&mut buf, &self.confirm);
+        buf
+    }
+
+    /// Decode a message written by [ReplyMsg::encode], with the errors of [HelloMsg::decode].
+    pub fn decode(bytes: &[u8]) -> Result<Self, DhGroupsError> {
+        let mut reader = Reader::new(read_header::<G>(bytes)?, MSG_REPLY)?;
+        let msg = ReplyMsg {
+            nonce: reader.read_nonce()?,
+            public: UnvalidatedPublicKey::from_bytes_be(reader.read()?)?,
+            confirm: reader.read()?.to_vec(),
+        };
+        reader.finish()?;
+        Ok(msg)
+    }
+}
+
+impl ConfirmMsg {
+    /// Encode the message as `version || MSG_CONFIRM || confirm`, prefixed by its length. As
+    /// the message holds no group values, its header has no group number.
+    pub fn encode(&self) -> Vec<u8> {
+        let mut buf = vec![WireVersion::CURRENT as u8, MSG_CONFIRM];
+        write_string(&mut buf, &self.confirm);
+        buf
+    }
+
+    /// Decode a message written by [ConfirmMsg::encode], with the errors of [HelloMsg::decode].
+    pub fn decode(bytes: &[u8]) -> Result<Self, DhGroupsError> {
+        let (_, bytes) = read_version(bytes)?;
+        let mut reader = Reader::new(bytes, MSG_CONFIRM)?;
+        let msg = ConfirmMsg {
+            confirm: reader.read()?.to_vec(),
+        };
+        reader.finish()?;
+        Ok(msg)
+    }
+}
+
+/// Cursor over a message, used by the decoders above.
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn new(bytes: &'a [u8], msg_type: u8) -> Result<Self, DhGroupsError> {
+        match bytes.split_first() {
+            None => Err(DhGroupsError::UnexpectedEof),
+            Some((&found, rest)) if found == msg_type => Ok(Self { bytes: rest }),
+            Some((&found, _)) => Err(DhGroupsError::UnexpectedMessageType {
+                expected: msg_type,
+                found,
+            }),
+        }
+    }
+
+    fn read(&mut self) -> Result<&'a [u8], DhGroupsError> {
+        let (data, rest) = read_string(self.bytes)?;
+        self.bytes = rest;
+        Ok(data)
+    }
+
+    fn read_nonce(&mut self) -> Result<[u8; 32], DhGroupsError> {
+        let data = self.read()?;
+        match data.len() {
+            len if len < 32 => Err(DhGroupsError::UnexpectedEof),
+            32 => Ok(data.try_into().unwrap()),
+            _ => Err(DhGroupsError::TrailingBytes),
+        }
+    }
+
+    fn finish(self) -> Result<(), DhGroupsError> {
+        if self.bytes.is_empty() {
+            Ok(())
+        } else {
+            Err(DhGroupsError::TrailingBytes)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::VecDeque;
+
+    use sha2::Sha256;
+
+    use super::*;
+    use crate::group::MODPGroup5;
+
+    type G = MODPGroup5;
+
+    /// An in-memory channel of encoded messages in one direction.
+    #[derive(Default)]
+    struct Channel(VecDeque<Vec<u8>>);
+
+    impl Channel {
+        fn send(&mut self, msg: Vec<u8>) {
+            self.0.push_back(msg);
+        }
+
+        fn recv(&mut self) -> Vec<u8> {
+            self.0.pop_front().expect("message in channel")
+        }
+    }
+
+    #[test]
+    fn test_handshake_over_channel() {
+        let rng = &mut rand::thread_rng();
+        let (mut to_responder, mut to_initiator) = (Channel::default(), Channel::default());
+
+        let (initiator, hello) = Initiator::<G>::start(rng);
+        to_responder.send(hello.encode());
+
+        let hello = HelloMsg::<G>::decode(&to_responder.recv()).unwrap();
+        let (responder, reply) = Responder::respond::<Sha256, _>(&hello, rng).unwrap();
+        to_initiator.send(reply.encode());
+
+        let reply = ReplyMsg::<G>::decode(&to_initiator.recv()).unwrap();
+        let (keys_a, confirm) = initiator.finish::<Sha256>(&reply).unwrap();
+        to_responder.send(confirm.encode());
+
+        let confirm = ConfirmMsg::decode(&to_responder.recv()).unwrap();
+        let keys_b = responder.finish::<Sha256>(&confirm).unwrap();
+
+        assert_eq!(keys_a.encryption, keys_b.encryption);
+        assert_eq!(keys_a.mac, keys_b.mac);
+        assert_eq!(keys_a.encryption.len(), 32);
+        assert_ne!(keys_a.encryption, keys_a.mac);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_serde_messages() {
+        let rng = &mut rand::thread_rng();
+        let (_, hello) = Initiator::<G>::start(rng);
+        let json = serde_json::to_string(&hello).unwrap();
+        let decoded: HelloMsg<G> = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded.encode(), hello.encode());
+    }
+
+    #[test]
+    fn test_misordered_messages() {
+        let rng = &mut rand::thread_rng();
+        let (initiator, hello) = Initiator::<G>::start(rng);
+        let (responder, reply) = Responder::respond::<Sha256, _>(&hello, rng).unwrap();
+
+        // a hello where a reply is expected
+        assert_eq!(
+            ReplyMsg::<G>::decode(&hello.encode()).unwrap_err(),
+            DhGroupsError::UnexpectedMessageType {
+                expected: MSG_REPLY,
+                found: MSG_HELLO
+            }
+        );
+        // the responder's confirm sent back as a confirm of the initiator
+        let reflected = ConfirmMsg {
+            confirm: reply.confirm.clone(),
+        };
+        assert_eq!(
+            responder.finish::<Sha256>(&reflected).unwrap_err(),
+            DhGroupsError::AuthenticationFailed
+        );
+        // the hello reflected as a reply
+        let reflected = ReplyMsg {
+            nonce: hello.nonce,
+            public: hello.public.clone(),
+            confirm: reply.confirm,
+        };
+        assert_eq!(
+            initiator.finish::<Sha256>(&reflected).unwrap_err(),
+            DhGroupsError::Validation(ValidationError::Degenerate)
+        );
+    }
+
+    #[test]
+    fn test_invalid_public_key() {
+        let rng = &mut rand::thread_rng();
+        let hello = HelloMsg {
+            nonce: [0; 32],
+            public: Element::<G>::from_value(1u64.into()).into(),
+        };
+        assert_eq!(
+            Responder::respond::<Sha256, _>(&hello, rng).err(),
+            Some(DhGroupsError::Validation(ValidationError::Degenerate))
+        );
+    }
+
+    #[test]
+    fn test_replayed_messages() {
+        let rng = &mut rand::thread_rng();
+        let (first, hello) = Initiator::<G>::start(rng);
+        let (responder, reply) = Responder::respond::<Sha256, _>(&hello, rng).unwrap();
+        let (_, confirm) = first.finish::<Sha256>(&reply).unwrap();
+        responder.finish::<Sha256>(&confirm).unwrap();
+
+        // the reply replayed to a new initiator
+        let (second, hello) = Initiator::<G>::start(rng);
+        assert_eq!(
+            second.finish::<Sha256>(&reply).unwrap_err(),
+            DhGroupsError::AuthenticationFailed
+        );
+        // the confirm replayed to a new responder of the same hello
+        let (responder, _) = Responder::respond::<Sha256, _>(&hello, rng).unwrap();
+        assert_eq!(
+            responder.finish::<Sha256>(&confirm).unwrap_err(),
+            DhGroupsError::AuthenticationFailed
+        );
+    }
+
+    #[test]
+    fn test_decoding_errors() {
+        let rng = &mut rand::thread_rng();
+        let (_, hello) = Initiator::<G>::start(rng);
+        let encoded = hello.encode();
+        assert_eq!(
+            HelloMsg::<G>::decode(&encoded[..encoded.len() - 1]).unwrap_err(),
+            DhGroupsError::UnexpectedEof
+        );
+        let mut trailing = encoded.clone();
+        trailing.push(0);
+        assert_eq!(
+            HelloMsg::<G>::decode(&trailing).unwrap_err(),
+            DhGroupsError::TrailingBytes
+        );
+        assert_eq!(
+            ConfirmMsg::decode(&[]).unwrap_err(),
+            DhGroupsError::UnexpectedEof
+        );
+    }
+
+    #[test]
+    fn test_versions() {
+        let rng = &mut rand::thread_rng();
+        let (_, hello) = Initiator::<G>::start(rng);
+        let encoded = hello.encode();
+        assert_eq!(encoded[..3], [WireVersion::V2 as u8, 5, MSG_HELLO]);
+
+        // the previous version has no group number
+        let v1 = [&[WireVersion::V1 as u8][..], &encoded[2..]].concat();
+        assert_eq!(HelloMsg::<G>::decode(&v1).unwrap().encode(), encoded);
+        let confirm = ConfirmMsg {
+            confirm: vec![1; 32],
+        };
+        let mut v1 = confirm.encode();
+        v1[0] = WireVersion::V1 as u8;
+        assert_eq!(ConfirmMsg::decode(&v1).unwrap().confirm, confirm.confirm);
+
+        let mut v9 = encoded.clone();
+        v9[0] = 9;
+        assert_eq!(
+            HelloMsg::<G>::decode(&v9).unwrap_err(),
+            DhGroupsError::UnsupportedVersion(9)
+        );
+        assert_eq!(
+            HelloMsg::<crate::group::MODPGroup14>::decode(&encoded).unwrap_err(),
+            DhGroupsError::GroupMismatch
+        );
+    }
+}
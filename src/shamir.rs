@@ -0,0 +1,167 @@
+//! Shamir secret sharing of scalars, e.g. to back up a secret key: the secret is split into `n`
+//! shares so that any `threshold` of them recover it, while fewer reveal nothing about it.
+//!
+//! The secret is the constant term of a random polynomial `f` of degree `threshold - 1`, and the
+//! share `j` is `(j, f(j))` for `j` in `[1, n]`. The shares are not verifiable, see the
+//! [dkg](crate::dkg) for verifiable sharing.
+//!
+//! # Example
+//!
+//! ```rust
+//! use diffie_hellman_groups::{shamir, Scalar, group::MODPGroup5};
+//!
+//! let rng = &mut rand::thread_rng();
+//! let secret = Scalar::<MODPGroup5>::random(rng);
+//! let shares = shamir::split(&secret, 3, 5, rng);
+//!
+//! let subset = [shares[4].clone(), shares[1].clone(), shares[2].clone()];
+//! assert_eq!(shamir::combine(&subset, 3).unwrap(), secret);
+//! assert!(shamir::combine(&shares[..2], 3).is_err());
+//! ```
+
+use std::collections::BTreeSet;
+
+use num_bigint::BigUint;
+use rand_core::CryptoRngCore;
+
+use crate::{group::MODPGroup, threshold::lagrange_coefficient, Scalar, ShareError};
+
+/// Split `secret` into `n` shares `(j, f(j))`, any `threshold` of which recover it.
+///
+/// # Panics
+/// Panics unless `1 <= threshold <= n`.
+pub fn split<G: MODPGroup, R: CryptoRngCore + ?Sized>(
+    secret: &Scalar<G>,
+    threshold: u32,
+    n: u32,
+    rng: &mut R,
+) -> Vec<(u32, Scalar<G>)> {
+    assert!(
+        threshold >= 1 && threshold <= n,
+        "threshold must be in [1, n]"
+    );
+    let coefficients: Vec<Scalar<G>> = std::iter::once(Scalar::from_biguint(secret.value.clone()))
+        .chain((1..threshold).map(|_| Scalar::random(rng)))
+        .collect();
+    (1..=n).map(|j| (j, evaluate(&coefficients, j))).collect()
+}
+
+/// Recover the secret from the first `threshold` of `shares`.
+///
+/// The threshold must be the one used to [split] the secret: fewer shares interpolate a
+/// different polynomial and give a wrong secret, without any error.
+///
+/// # Errors
+/// Returns
+/// - [ShareError::NotEnoughShares] if less than `threshold` shares are given.
+/// - [ShareError::InvalidIndex] or [ShareError::DuplicateIndex] if an index of the combined
+///   shares is zero or repeated.
+pub fn combine<G: MODPGroup>(
+    shares: &[(u32, Scalar<G>)],
+    threshold: usize,
+) -> Result<Scalar<G>, ShareError> {
+    if threshold == 0 || shares.len() < threshold {
+        return Err(ShareError::NotEnoughShares {
+            needed: threshold.max(1),
+            got: shares.len(),
+        });
+    }
+    let shares = &shares[..threshold];
+    let mut indices = BTreeSet::new();
+    for &(index, _) in shares {
+        if index == 0 {
+            return Err(ShareError::InvalidIndex);
+        }
+        if !indices.insert(index) {
+            return Err(ShareError::DuplicateIndex(index));
+        }
+    }
+    let indices: Vec<u32> = indices.into_iter().collect();
+
+    Ok(shares
+        .iter()
+        .fold(Scalar::from_biguint(BigUint::from(0u64)), |acc, (j, y)| {
+            acc + lagrange_coefficient::<G>(&indices, *j) * y
+        }))
+}
+
+/// Evaluate the polynomial with the given coefficients at `x`, with Horner's method.
+pub(crate) fn evaluate<G: MODPGroup>(coefficients: &[Scalar<G>], x: u32) -> Scalar<G> {
+    let x = Scalar::<G>::from_biguint(BigUint::from(x));
+    coefficients
+        .iter()
+        .rev()
+        .fold(Scalar::from_biguint(BigUint::from(0u64)), |acc, a| {
+            acc * &x + a
+        })
+}
+
+#[cfg(test)]
+mod test {
+    use proptest::prelude::*;
+    use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+
+    use super::*;
+    use crate::{group::MODPGroup5, testing::any_scalar};
+
+    type G = MODPGroup5;
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(32))]
+
+        #[test]
+        fn test_any_subset_recovers(
+            secret in any_scalar::<G>(),
+            seed: u64,
+            n in 1u32..8,
+            t in 1u32..8,
+            extra in 0u32..8,
+        ) {
+            let threshold = t.min(n);
+            let rng = &mut StdRng::seed_from_u64(seed);
+            let mut shares = split(&secret, threshold, n, rng);
+            prop_assert_eq!(shares.len(), n as usize);
+
+            shares.shuffle(rng);
+            let size = (threshold + extra).min(n) as usize;
+            prop_assert_eq!(combine(&shares[..size], threshold as usize).unwrap(), secret);
+        }
+
+        #[test]
+        fn test_fewer_shares_rejected(
+            secret in any_scalar::<G>(),
+            seed: u64,
+            n in 2u32..8,
+            t in 2u32..8,
+        ) {
+            let threshold = t.min(n);
+            let rng = &mut StdRng::seed_from_u64(seed);
+            let mut shares = split(&secret, threshold, n, rng);
+
+            shares.shuffle(rng);
+            let size = threshold as usize - 1;
+            prop_assert_eq!(
+                combine(&shares[..size], threshold as usize),
+                Err(ShareError::NotEnoughShares { needed: threshold as usize, got: size })
+            );
+            // with a lower threshold, the shares interpolate another polynomial
+            prop_assert_ne!(combine(&shares[..size], size).unwrap(), secret);
+        }
+    }
+
+    #[test]
+    fn test_invalid_indices() {
+        let rng = &mut rand::thread_rng();
+        let secret = Scalar::<G>::random(rng);
+        let shares = split(&secret, 2, 3, rng);
+
+        let duplicated = [shares[1].clone(), shares[1].clone()];
+        assert_eq!(combine(&duplicated, 2), Err(ShareError::DuplicateIndex(2)));
+        let zero = [shares[0].clone(), (0, secret.clone())];
+        assert_eq!(combine(&zero, 2), Err(ShareError::InvalidIndex));
+        assert_eq!(
+            combine(&shares, 0),
+            Err(ShareError::NotEnoughShares { needed: 1, got: 3 })
+        );
+    }
+}
@@ -0,0 +1,456 @@
+//! Sigma protocols proving knowledge of a discrete logarithm common to several pairs of
+//! elements, made non-interactive with the Fiat-Shamir transform, and their OR-composition by
+//! Cramer, Damgård and Schoenmakers (CDS).
+//!
+//! A [Statement] is a list of pairs `(b_j, y_j)`, and its witness is a [Scalar] `x` such that
+//! `y_j = b_j^x` for all `j`. With a single pair whose base is `g`, this is the Schnorr proof of
+//! knowledge of a discrete logarithm; with two pairs, it is the Chaum-Pedersen proof of equality of
+//! discrete logarithms (DLEQ).
+//!
+//! An OR proof shows that the prover knows the witness of one of several statements without
+//! revealing which one: the branches without a witness are simulated with challenges chosen in
+//! advance, and the challenge of the real branch is fixed so that all challenges sum to the
+//! Fiat-Shamir challenge.
+//!
+//! # Example
+//!
+//! Prove that an exponential ElGamal ciphertext `(c1, c2) = (g^r, g^m * h^r)` encrypts 0 or 1.
+//!
+//! ```rust
+//! use num_bigint::BigUint;
+//! use sha2::Sha256;
+//! use diffie_hellman_groups::{
+//!     group::{MODPGroup, MODPGroup5}, sigma::{self, Statement}, Element, Scalar,
+//! };
+//!
+//! type G = MODPGroup5;
+//! let rng = &mut rand::thread_rng();
+//! let g = Element::<G>::from_value(G::generator());
+//! let h = Element::<G>::from_biguint(Scalar::<G>::random(rng).value);
+//!
+//! // encrypt m = 1
+//! let r = Scalar::<G>::random(rng);
+//! let c1 = g.pow(&r.value);
+//! let c2 = &g * h.pow(&r.value);
+//!
+//! let g_inv = g.pow(&(-Scalar::<G>::from_biguint(BigUint::from(1u64))).value);
+//! let statements = [
+//!     // m = 0: (c1, c2) = (g^r, h^r)
+//!     Statement::dleq(g.clone(), c1.clone(), h.clone(), c2.clone()),
+//!     // m = 1: (c1, c2 / g) = (g^r, h^r)
+//!     Statement::dleq(g, c1, h, c2 * g_inv),
+//! ];
+//! let proof = sigma::or_prove::<G, Sha256, _>(&statements, 1, &r, rng);
+//! assert!(sigma::or_verify::<G, Sha256>(&statements, &proof));
+//! ```
+//!
+//! [Proof::to_bytes] and [OrProof::to_bytes] start with the versioned header of
+//! [wire](crate::wire).
+
+use num_bigint::{BigUint, RandBigInt};
+use rand_core::CryptoRngCore;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    group::MODPGroup,
+    wire::{read_header, write_header},
+    DhGroupsError, Element, NamedDigest, Scalar,
+};
+
+/// A statement `y_j = b_j^x` for all pairs `(b_j, y_j)`, for an unknown `x`.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(bound = ""))]
+pub struct Statement<G: MODPGroup> {
+    pub pairs: Vec<(Element<G>, Element<G>)>,
+}
+
+/// A non-interactive proof of a [Statement], as the challenge `c` and the response `s`.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(bound = ""))]
+pub struct Proof<G: MODPGroup> {
+    pub c: Scalar<G>,
+    pub s: Scalar<G>,
+}
+
+/// A proof of a statement built by [Statement::dleq].
+pub type DleqProof<G> = Proof<G>;
+
+/// A non-interactive proof of one of several statements, as a challenge and a response per
+/// statement.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(bound = ""))]
+pub struct OrProof<G: MODPGroup> {
+    pub branches: Vec<Proof<G>>,
+}
+
+impl<G: MODPGroup> Statement<G> {
+    /// The statement `y = g^x`.
+    pub fn dlog(y: Element<G>) -> Self {
+        Statement {
+            pairs: vec![(Element::from_value(G::generator()), y)],
+        }
+    }
+
+    /// The statement `y1 = b1^x` and `y2 = b2^x`.
+    pub fn dleq(b1: Element<G>, y1: Element<G>, b2: Element<G>, y2: Element<G>) -> Self {
+        Statement {
+            pairs: vec![(b1, y1), (b2, y2)],
+        }
+    }
+
+    /// Compute the commitments `t_j = b_j^k`.
+    fn commit(&self, k: &Scalar<G>) -> Vec<Element<G>> {
+        self.pairs.iter().map(|(b, _)| b.pow(&k.value)).collect()
+    }
+
+    /// Recompute the commitments `t_j = b_j^s * y_j^(-c)` of a proof.
+    fn recompute(&self, proof: &Proof<G>) -> Vec<Element<G>> {
+        let minus_c = -&proof.c;
+        self.pairs
+            .iter()
+            .map(|(b, y)| b.pow(&proof.s.value) * y.pow(&minus_c.value))
+            .collect()
+    }
+
+    /// Check that all elements are in `[1, p)`.
+    fn is_valid(&self) -> bool {
+        let zero = BigUint::from(0u64);
+        let p = G::prime_modulus();
+        !self.pairs.is_empty()
+            && self
+                .pairs
+                .iter()
+                .all(|(b, y)| b.value != zero && b.value < p && y.value != zero && y.value < p)
+    }
+}
+
+impl<G: MODPGroup> PartialEq for Proof<G> {
+    fn eq(&self, other: &Self) -> bool {
+        self.c == other.c && self.s == other.s
+    }
+}
+
+impl<G: MODPGroup> Eq for Proof<G> {}
+
+impl<G: MODPGroup> Proof<G> {
+    /// Encode the proof as `header || c || s`, with the header of [wire](crate::wire) and each
+    /// value as in [Scalar::to_bytes_be].
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        write_header::<G>(&mut bytes);
+        bytes.extend_from_slice(&self.c.to_bytes_be());
+        bytes.extend_from_slice(&self.s.to_bytes_be());
+        bytes
+    }
+
+    /// Decode a proof written by [Proof::to_bytes], in the current or the previous version of
+    /// [wire](crate::wire).
+    ///
+    /// # Errors
+    /// Returns [DhGroupsError::UnsupportedVersion] or [DhGroupsError::GroupMismatch] if the header
+    /// is not accepted, [DhGroupsError::UnexpectedEof] or [DhGroupsError::InputTooLong] if the
+    /// body is not twice the length of q, or [DhGroupsError::Validation] if a value is not less
+    /// than q.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, DhGroupsError> {
+        let bytes = read_header::<G>(bytes)?;
+        let len = G::subgroup_order().bits().div_ceil(8) as usize;
+        let (c, s) = bytes.split_at(len.min(bytes.len()));
+        Ok(Proof {
+            c: Scalar::from_bytes(c)?,
+            s: Scalar::from_bytes(s)?,
+        })
+    }
+}
+
+impl<G: MODPGroup> PartialEq for OrProof<G> {
+    fn eq(&self, other: &Self) -> bool {
+        self.branches == other.branches
+    }
+}
+
+impl<G: MODPGroup> Eq for OrProof<G> {}
+
+impl<G: MODPGroup> OrProof<G> {
+    /// Encode the proof as the header of [wire](crate::wire) followed by the concatenation of
+    /// the challenges and responses of the branches, each as in [Scalar::to_bytes_be].
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        write_header::<G>(&mut bytes);
+        for proof in &self.branches {
+            bytes.extend_from_slice(&proof.c.to_bytes_be());
+            bytes.extend_from_slice(&proof.s.to_bytes_be());
+        }
+        bytes
+    }
+
+    /// Decode a proof written by [OrProof::to_bytes], in the current or the previous version of
+    /// [wire](crate::wire).
+    ///
+    /// # Errors
+    /// Returns [DhGroupsError::UnsupportedVersion] or [DhGroupsError::GroupMismatch] if the header
+    /// is not accepted, [DhGroupsError::UnexpectedEof] if the length of the body is not a
+    /// positive multiple of the length of a branch, or [DhGroupsError::Validation] if a value is
+    /// not less than q.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, DhGroupsError> {
+        let bytes = read_header::<G>(bytes)?;
+        let len = G::subgroup_order().bits().div_ceil(8) as usize;
+        if bytes.is_empty() || !bytes.len().is_multiple_of(2 * len) {
+            return Err(DhGroupsError::UnexpectedEof);
+        }
+        let branches = bytes
+            .chunks(2 * len)
+            .map(|chunk| {
+                Ok(Proof {
+                    c: Scalar::from_bytes(&chunk[..len])?,
+                    s: Scalar::from_bytes(&chunk[len..])?,
+                })
+            })
+            .collect::<Result<_, DhGroupsError>>()?;
+        Ok(OrProof { branches })
+    }
+}
+
+/// Prove `statement` with the witness `x`.
+pub fn prove<G: MODPGroup, D: NamedDigest, R: CryptoRngCore + ?Sized>(
+    statement: &Statement<G>,
+    x: &Scalar<G>,
+    rng: &mut R,
+) -> Proof<G> {
+    let k = Scalar::<G>::random(rng);
+    let c = challenge::<G, D>(std::slice::from_ref(statement), &[statement.commit(&k)]);
+    let s = k + &c * x;
+    Proof { c, s }
+}
+
+/// Verify a proof of `statement`.
+pub fn verify<G: MODPGroup, D: NamedDigest>(statement: &Statement<G>, proof: &Proof<G>) -> bool {
+    statement.is_valid()
+        && challenge::<G, D>(
+            std::slice::from_ref(statement),
+            &[statement.recompute(proof)],
+        ) == proof.c
+}
+
+/// Prove that one of `statements` holds, knowing the witness `witness` of the statement at
+/// `witness_index`.
+///
+/// # Panics
+/// Panics if `witness_index` is out of the bounds of `statements`.
+pub fn or_prove<G: MODPGroup, D: NamedDigest, R: CryptoRngCore + ?Sized>(
+    statements: &[Statement<G>],
+    witness_index: usize,
+    witness: &Scalar<G>,
+    rng: &mut R,
+) -> OrProof<G> {
+    assert!(
+        witness_index < statements.len(),
+        "witness index out of bounds"
+    );
+
+    // simulate the other branches with random challenges and responses
+    let k = Scalar::<G>::random(rng);
+    let mut branches = Vec::with_capacity(statements.len());
+    let mut commitments = Vec::with_capacity(statements.len());
+    for (i, statement) in statements.iter().enumerate() {
+        if i == witness_index {
+            branches.push(None);
+            commitments.push(statement.commit(&k));
+        } else {
+            let proof = Proof {
+                c: Scalar::from_biguint(rng.gen_biguint_below(&G::subgroup_order())),
+                s: Scalar::from_biguint(rng.gen_biguint_below(&G::subgroup_order())),
+            };
+            commitments.push(statement.recompute(&proof));
+            branches.push(Some(proof));
+        }
+    }
+
+    // the real challenge completes the sum of the simulated ones to the Fiat-Shamir challenge
+    let c = challenge::<G, D>(statements, &commitments);
+    let c_real = branches
+        .iter()
+        .flatten()
+        .fold(c, |acc, proof| acc - &proof.c);
+    let s_real = k + &c_real * witness;
+    branches[witness_index] = Some(Proof {
+        c: c_real,
+        s: s_real,
+    });
+
+    OrProof {
+        branches: branches.into_iter().flatten().collect(),
+    }
+}
+
+/// Verify a proof that one of `statements` holds.
+pub fn or_verify<G: MODPGroup, D: NamedDigest>(
+    statements: &[Statement<G>],
+    proof: &OrProof<G>,
+) -> bool {
+    if statements.is_empty()
+        || statements.len() != proof.branches.len()
+        || !statements.iter().all(Statement::is_valid)
+    {
+        return false;
+    }
+    let commitments: Vec<_> = statements
+        .iter()
+        .zip(&proof.branches)
+        .map(|(statement, branch)| statement.recompute(branch))
+        .collect();
+    let sum = proof
+        .branches
+        .iter()
+        .fold(Scalar::from_biguint(BigUint::from(0u64)), |acc, branch| {
+            acc + &branch.c
+        });
+    challenge::<G, D>(statements, &commitments) == sum
+}
+
+/// Compute the Fiat-Shamir challenge over the statements and the commitments, with the counts as
+/// big-endian `u32` and the elements encoded as in [Element::to_bytes_be].
+fn challenge<G: MODPGroup, D: NamedDigest>(
+    statements: &[Statement<G>],
+    commitments: &[Vec<Element<G>>],
+) -> Scalar<G> {
+    let mut hasher = D::new();
+    hasher.update((statements.len() as u32).to_be_bytes());
+    for (statement, commitment) in statements.iter().zip(commitments) {
+        hasher.update((statement.pairs.len() as u32).to_be_bytes());
+        for ((b, y), t) in statement.pairs.iter().zip(commitment) {
+            hasher.update(b.to_bytes_be());
+            hasher.update(y.to_bytes_be());
+            hasher.update(t.to_bytes_be());
+        }
+    }
+    Scalar::from_biguint(BigUint::from_bytes_be(&hasher.finalize()))
+}
+
+#[cfg(test)]
+mod test {
+    use sha2::Sha256;
+
+    use super::*;
+    use crate::{
+        group::{MODPGroup14, MODPGroup5},
+        wire::WireVersion,
+    };
+
+    type G = MODPGroup5;
+
+    fn element(x: &Scalar<G>) -> Element<G> {
+        Element::from_biguint(x.value.clone())
+    }
+
+    /// Statements that the exponential ElGamal ciphertext (c1, c2) under h encrypts 0 or 1.
+    fn ballot_statements(h: &Element<G>, c1: &Element<G>, c2: &Element<G>) -> [Statement<G>; 2] {
+        let g = Element::<G>::from_value(G::generator());
+        let g_inv = g.pow(&(-Scalar::<G>::from_biguint(BigUint::from(1u64))).value);
+        [
+            Statement::dleq(g.clone(), c1.clone(), h.clone(), c2.clone()),
+            Statement::dleq(g, c1.clone(), h.clone(), c2 * g_inv),
+        ]
+    }
+
+    #[test]
+    fn test_prove_verify() {
+        let rng = &mut rand::thread_rng();
+        let x = Scalar::<MODPGroup14>::random(rng);
+        let statement = Statement::dlog(Element::from_biguint(x.value.clone()));
+        let proof = prove::<_, Sha256, _>(&statement, &x, rng);
+        assert!(verify::<_, Sha256>(&statement, &proof));
+
+        let other = Statement::dlog(Element::from_biguint(
+            Scalar::<MODPGroup14>::random(rng).value,
+        ));
+        assert!(!verify::<_, Sha256>(&other, &proof));
+    }
+
+    #[test]
+    fn test_two_way_or() {
+        let rng = &mut rand::thread_rng();
+        let h = element(&Scalar::random(rng));
+
+        for m in [0u64, 1] {
+            let r = Scalar::<G>::random(rng);
+            let c1 = element(&r);
+            let c2 = element(&Scalar::from_biguint(BigUint::from(m))) * h.pow(&r.value);
+            let statements = ballot_statements(&h, &c1, &c2);
+
+            let proof = or_prove::<G, Sha256, _>(&statements, m as usize, &r, rng);
+            assert!(or_verify::<G, Sha256>(&statements, &proof));
+            assert_eq!(OrProof::from_bytes(&proof.to_bytes()).unwrap(), proof);
+
+            // claiming the wrong branch does not verify
+            let proof = or_prove::<G, Sha256, _>(&statements, 1 - m as usize, &r, rng);
+            assert!(!or_verify::<G, Sha256>(&statements, &proof));
+        }
+
+        // a ciphertext of 2 cannot be proven to encrypt 0 or 1
+        let r = Scalar::<G>::random(rng);
+        let c1 = element(&r);
+        let c2 = element(&Scalar::from_biguint(BigUint::from(2u64))) * h.pow(&r.value);
+        let statements = ballot_statements(&h, &c1, &c2);
+        for index in [0, 1] {
+            let proof = or_prove::<G, Sha256, _>(&statements, index, &r, rng);
+            assert!(!or_verify::<G, Sha256>(&statements, &proof));
+        }
+    }
+
+    #[test]
+    fn test_three_way_or() {
+        let rng = &mut rand::thread_rng();
+        let secrets: Vec<Scalar<G>> = (0..3).map(|_| Scalar::random(rng)).collect();
+        let statements: Vec<Statement<G>> = secrets
+            .iter()
+            .map(|x| Statement::dlog(element(x)))
+            .collect();
+
+        for (index, x) in secrets.iter().enumerate() {
+            let proof = or_prove::<G, Sha256, _>(&statements, index, x, rng);
+            assert!(or_verify::<G, Sha256>(&statements, &proof));
+
+            // the proof is bound to the statements, in order
+            let mut reordered = statements.clone();
+            reordered.swap(0, 2);
+            assert!(!or_verify::<G, Sha256>(&reordered, &proof));
+            assert!(!or_verify::<G, Sha256>(&statements[..2], &proof));
+
+            let mut tampered = proof.clone();
+            tampered.branches[(index + 1) % 3].s =
+                &tampered.branches[(index + 1) % 3].s + Scalar::from_biguint(BigUint::from(1u64));
+            assert!(!or_verify::<G, Sha256>(&statements, &tampered));
+        }
+
+        // all-false forgery: a witness of none of the statements
+        let forger = Scalar::<G>::random(rng);
+        for index in 0..3 {
+            let proof = or_prove::<G, Sha256, _>(&statements, index, &forger, rng);
+            assert!(!or_verify::<G, Sha256>(&statements, &proof));
+        }
+    }
+
+    #[test]
+    fn test_from_bytes_errors() {
+        let header = [WireVersion::CURRENT as u8, 5];
+        assert_eq!(
+            OrProof::<G>::from_bytes(&header).unwrap_err(),
+            DhGroupsError::UnexpectedEof
+        );
+        assert_eq!(
+            OrProof::<G>::from_bytes(&[&header[..], &[0; 383]].concat()).unwrap_err(),
+            DhGroupsError::UnexpectedEof
+        );
+        assert!(OrProof::<G>::from_bytes(&[&header[..], &[0; 384]].concat()).is_ok());
+        assert_eq!(
+            OrProof::<G>::from_bytes(&[0; 386]).unwrap_err(),
+            DhGroupsError::UnsupportedVersion(0)
+        );
+        assert_eq!(
+            OrProof::<G>::from_bytes(&[&[WireVersion::CURRENT as u8, 14][..], &[0; 384]].concat())
+                .unwrap_err(),
+            DhGroupsError::GroupMismatch
+        );
+    }
+}
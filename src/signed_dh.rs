@@ -0,0 +1,245 @@
+//! Ephemeral Diffie-Hellman public keys signed with a long-term identity key.
+//!
+//! A party signs its ephemeral key `g^x` together with a context, e.g. a protocol name and a
+//! session identifier, as a [SignedPublicKey] ([SignedPublicKey::new]). The peer checks the
+//! context, the signature and the key ([SignedPublicKey::verify]), and only the resulting
+//! [VerifiedPublicKey] is accepted to compute the shared secret ([exchange]), so that a key
+//! cannot be substituted, nor replayed in another context.
+//!
+//! The signed message is the canonical encoding `dst || g^x || len(context) || context`, where
+//! the key is encoded as in [Element::to_bytes_be] and the length as a big-endian `u32`. The
+//! signature scheme is provided by the caller through the [Signer] and [Verifier] traits of
+//! [sts](crate::sts), e.g. with [SchnorrSigner](crate::sts::SchnorrSigner).
+//!
+//! # Example
+//!
+//! ```rust
+//! use sha2::Sha256;
+//! use diffie_hellman_groups::{
+//!     signed_dh::{self, SignedPublicKey},
+//!     sts::{SchnorrSigner, SchnorrVerifier},
+//!     Element, Scalar, group::MODPGroup5,
+//! };
+//!
+//! type G = MODPGroup5;
+//! let rng = &mut rand::thread_rng();
+//! let identity = Scalar::<G>::random(rng);
+//! let signer = SchnorrSigner::<G, Sha256>::new(identity.clone());
+//! let verifier = SchnorrVerifier::<G, Sha256>::new(Element::from_biguint(identity.value));
+//!
+//! let (x, y) = (Scalar::<G>::random(rng), Scalar::<G>::random(rng));
+//! let signed = SignedPublicKey::new(Element::from_biguint(x.value.clone()), b"session 1", &signer);
+//!
+//! let verified = signed.verify(b"session 1", &verifier).unwrap();
+//! let shared = signed_dh::exchange(&y, &verified);
+//! assert_eq!(shared.as_element(), &Element::from_biguint(&x.value * &y.value));
+//! ```
+//!
+//! A signed key that was not verified does not compile where a [VerifiedPublicKey] is expected:
+//!
+//! ```compile_fail
+//! use sha2::Sha256;
+//! use diffie_hellman_groups::{
+//!     signed_dh::{self, SignedPublicKey},
+//!     sts::SchnorrSigner,
+//!     Element, Scalar, group::MODPGroup5,
+//! };
+//!
+//! type G = MODPGroup5;
+//! let rng = &mut rand::thread_rng();
+//! let signer = SchnorrSigner::<G, Sha256>::new(Scalar::random(rng));
+//! let (x, y) = (Scalar::<G>::random(rng), Scalar::<G>::random(rng));
+//! let signed = SignedPublicKey::<G>::new(Element::from_biguint(x.value.clone()), b"session 1", &signer);
+//!
+//! let shared = signed_dh::exchange(&y, &signed);
+//! ```
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+pub use crate::kdf::SharedSecret;
+pub use crate::sts::{Signer, Verifier};
+use crate::{group::MODPGroup, AuthError, Element, PublicKey, Scalar, UnvalidatedPublicKey};
+
+/// Domain separation tag of the signed message.
+const SIGNED_KEY_DST: &[u8] = b"diffie-hellman-groups signed public key";
+
+/// An ephemeral public key, its context and the signature of both by the identity key of its
+/// owner.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(bound = ""))]
+pub struct SignedPublicKey<G: MODPGroup> {
+    pub key: UnvalidatedPublicKey<G>,
+    pub context: Vec<u8>,
+    pub signature: Vec<u8>,
+}
+
+/// The key of a [SignedPublicKey] whose context, signature and value passed
+/// [SignedPublicKey::verify].
+#[derive(Debug)]
+pub struct VerifiedPublicKey<G: MODPGroup>(PublicKey<G>);
+
+impl<G: MODPGroup> SignedPublicKey<G> {
+    /// Sign `key` in `context` with `signer`.
+    pub fn new<S: Signer + ?Sized>(key: Element<G>, context: &[u8], signer: &S) -> Self {
+        let signature = signer.sign(&signed_message(&key, context));
+        SignedPublicKey {
+            key: key.into(),
+            context: context.to_vec(),
+            signature,
+        }
+    }
+
+    /// Check the context and the signature of the key, and validate the key.
+    ///
+    /// # Errors
+    /// - [AuthError::ContextMismatch] if the context of the key is not `context`.
+    /// - [AuthError::InvalidSignature] if the signature does not verify with `verifier`.
+    /// - [AuthError::InvalidKey] if the key fails [UnvalidatedPublicKey::validate].
+    pub fn verify<V: Verifier + ?Sized>(
+        &self,
+        context: &[u8],
+        verifier: &V,
+    ) -> Result<VerifiedPublicKey<G>, AuthError> {
+        if self.context != context {
+            return Err(AuthError::ContextMismatch);
+        }
+        let message = signed_message(self.key.as_element(), &self.context);
+        if !verifier.verify(&message, &self.signature) {
+            return Err(AuthError::InvalidSignature);
+        }
+        let key = self.key.clone().validate().map_err(AuthError::InvalidKey)?;
+        Ok(VerifiedPublicKey(key))
+    }
+}
+
+impl<G: MODPGroup> VerifiedPublicKey<G> {
+    /// The validated key.
+    pub fn public_key(&self) -> &PublicKey<G> {
+        &self.0
+    }
+
+    /// Take the validated key.
+    pub fn into_public_key(self) -> PublicKey<G> {
+        self.0
+    }
+}
+
+/// Compute the shared secret of our ephemeral `secret` with the key of the peer verified by
+/// [SignedPublicKey::verify].
+pub fn exchange<G: MODPGroup>(secret: &Scalar<G>, peer: &VerifiedPublicKey<G>) -> SharedSecret<G> {
+    SharedSecret::new(peer.0.as_element().pow(&secret.value))
+}
+
+/// Encode `dst || key || len(context) || context`.
+fn signed_message<G: MODPGroup>(key: &Element<G>, context: &[u8]) -> Vec<u8> {
+    let mut msg = SIGNED_KEY_DST.to_vec();
+    msg.extend_from_slice(&key.to_bytes_be());
+    msg.extend_from_slice(&(context.len() as u32).to_be_bytes());
+    msg.extend_from_slice(context);
+    msg
+}
+
+#[cfg(test)]
+mod test {
+    use num_bigint::BigUint;
+    use sha2::Sha256;
+
+    use super::*;
+    use crate::{
+        group::{MODPGroup, MODPGroup5},
+        sts::{SchnorrSigner, SchnorrVerifier},
+        DhGroupsError, ValidationError,
+    };
+
+    type G = MODPGroup5;
+
+    fn keypair() -> (SchnorrSigner<G, Sha256>, SchnorrVerifier<G, Sha256>) {
+        let sk = Scalar::<G>::random(&mut rand::thread_rng());
+        let pk = Element::from_biguint(sk.value.clone());
+        (SchnorrSigner::new(sk), SchnorrVerifier::new(pk))
+    }
+
+    fn ephemeral() -> (Scalar<G>, Element<G>) {
+        let x = Scalar::<G>::random(&mut rand::thread_rng());
+        let gx = Element::from_biguint(x.value.clone());
+        (x, gx)
+    }
+
+    #[test]
+    fn test_good_signature() {
+        let (signer_a, verifier_a) = keypair();
+        let (signer_b, verifier_b) = keypair();
+        let (x, gx) = ephemeral();
+        let (y, gy) = ephemeral();
+
+        let signed_a = SignedPublicKey::new(gx, b"context", &signer_a);
+        let signed_b = SignedPublicKey::new(gy, b"context", &signer_b);
+        let shared_a = exchange(&x, &signed_b.verify(b"context", &verifier_b).unwrap());
+        let shared_b = exchange(&y, &signed_a.verify(b"context", &verifier_a).unwrap());
+        assert_eq!(shared_a.as_element(), shared_b.as_element());
+        assert_eq!(shared_a.to_bytes_be().len(), 192);
+    }
+
+    #[test]
+    fn test_bad_signature() {
+        let (signer, verifier) = keypair();
+        let (_, other_verifier) = keypair();
+        let (_, gy) = ephemeral();
+        let signed = SignedPublicKey::new(gy, b"context", &signer);
+
+        // signed by another identity
+        assert_eq!(
+            signed.verify(b"context", &other_verifier).unwrap_err(),
+            AuthError::InvalidSignature
+        );
+        // substituted key
+        let mut substituted = signed.clone();
+        substituted.key = ephemeral().1.into();
+        assert_eq!(
+            substituted.verify(b"context", &verifier).unwrap_err(),
+            AuthError::InvalidSignature
+        );
+        // tampered signature
+        let mut tampered = signed.clone();
+        tampered.signature[0] ^= 1;
+        assert_eq!(
+            tampered.verify(b"context", &verifier).unwrap_err(),
+            AuthError::InvalidSignature
+        );
+        assert_eq!(
+            DhGroupsError::from(AuthError::InvalidSignature),
+            DhGroupsError::AuthenticationFailed
+        );
+    }
+
+    #[test]
+    fn test_context_mismatch() {
+        let (signer, verifier) = keypair();
+        let (_, gy) = ephemeral();
+        let signed = SignedPublicKey::new(gy, b"session 1", &signer);
+
+        assert_eq!(
+            signed.verify(b"session 2", &verifier).unwrap_err(),
+            AuthError::ContextMismatch
+        );
+        // the context cannot be changed without the signature
+        let mut relabeled = signed.clone();
+        relabeled.context = b"session 2".to_vec();
+        assert_eq!(
+            relabeled.verify(b"session 2", &verifier).unwrap_err(),
+            AuthError::InvalidSignature
+        );
+    }
+
+    #[test]
+    fn test_invalid_key() {
+        let (signer, verifier) = keypair();
+        let degenerate = Element::<G>::from_value(G::prime_modulus() - BigUint::from(1u64));
+        let signed = SignedPublicKey::new(degenerate, b"context", &signer);
+        assert_eq!(
+            signed.verify(b"context", &verifier).unwrap_err(),
+            AuthError::InvalidKey(ValidationError::Degenerate)
+        );
+    }
+}
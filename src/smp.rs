@@ -0,0 +1,626 @@
+//! The Socialist Millionaires' Protocol (SMP) of
+//! [OTR version 3](https://otr.cypherpunks.ca/Protocol-v3-4.1.1.html): Alice and Bob check
+//! whether their secrets `x` and `y` are equal, learning nothing else about them.
+//!
+//! 1. Alice sends `g2a = g^a2` and `g3a = g^a3` with proofs of knowledge of `a2` and `a3`
+//!    ([Smp1]).
+//! 2. Bob sends `g2b = g^b2` and `g3b = g^b3` with their proofs, and, with `g2 = g2a^b2` and
+//!    `g3 = g3a^b3`, `Pb = g3^r` and `Qb = g^r g2^y` with a proof that they are formed so
+//!    ([Smp2]).
+//! 3. Alice computes `g2 = g2b^a2` and `g3 = g3b^a3`, and sends `Pa = g3^s`, `Qa = g^s g2^x` and
+//!    `Ra = (Qa / Qb)^a3`, with proofs that they are formed so ([Smp3]).
+//! 4. Bob sends `Rb = (Qa / Qb)^b3` with a proof ([Smp4]), and checks whether
+//!    `Ra^b3 = Pa / Pb`, which holds exactly when `x = y`. Alice checks whether `Rb^a3 = Pa / Pb`.
+//!
+//! The proofs are Schnorr-style proofs made non-interactive with the hash `D`, applied as in OTR
+//! to a version byte followed by the elements encoded as MPIs, i.e. prefixed by their length as
+//! a big-endian `u32`. With `D = Sha256` over [MODPGroup5], the messages are those of OTRv3.
+//!
+//! Each party runs an [Smp], whose [SmpState] moves forward with each message. A message
+//! received out of order, holding an invalid element or failing a proof, aborts the protocol;
+//! the messages carry no information that would let the parties learn more than the outcome.
+//!
+//! # Example
+//!
+//! ```rust
+//! use sha2::Sha256;
+//! use diffie_hellman_groups::{smp::{Smp, SmpState}, group::MODPGroup5};
+//!
+//! let rng = &mut rand::thread_rng();
+//! let mut alice = Smp::<MODPGroup5, Sha256>::new(b"shared secret");
+//! let mut bob = Smp::<MODPGroup5, Sha256>::new(b"shared secret");
+//!
+//! let smp1 = alice.start(rng).unwrap();
+//! let smp2 = bob.receive_smp1(&smp1, rng).unwrap();
+//! let smp3 = alice.receive_smp2(&smp2, rng).unwrap();
+//! let smp4 = bob.receive_smp3(&smp3, rng).unwrap();
+//! alice.receive_smp4(&smp4).unwrap();
+//!
+//! assert_eq!(alice.state(), SmpState::Succeeded);
+//! assert_eq!(bob.state(), SmpState::Succeeded);
+//! ```
+
+use std::marker::PhantomData;
+
+use num_bigint::BigUint;
+use rand_core::CryptoRngCore;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{group::MODPGroup, DhGroupsError, Element, NamedDigest, Scalar};
+
+/// The first message, sent by Alice.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(bound = ""))]
+pub struct Smp1<G: MODPGroup> {
+    pub g2a: Element<G>,
+    pub c2: Scalar<G>,
+    pub d2: Scalar<G>,
+    pub g3a: Element<G>,
+    pub c3: Scalar<G>,
+    pub d3: Scalar<G>,
+}
+
+/// The second message, sent by Bob.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(bound = ""))]
+pub struct Smp2<G: MODPGroup> {
+    pub g2b: Element<G>,
+    pub c2: Scalar<G>,
+    pub d2: Scalar<G>,
+    pub g3b: Element<G>,
+    pub c3: Scalar<G>,
+    pub d3: Scalar<G>,
+    pub pb: Element<G>,
+    pub qb: Element<G>,
+    pub cp: Scalar<G>,
+    pub d5: Scalar<G>,
+    pub d6: Scalar<G>,
+}
+
+/// The third message, sent by Alice.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(bound = ""))]
+pub struct Smp3<G: MODPGroup> {
+    pub pa: Element<G>,
+    pub qa: Element<G>,
+    pub cp: Scalar<G>,
+    pub d5: Scalar<G>,
+    pub d6: Scalar<G>,
+    pub ra: Element<G>,
+    pub cr: Scalar<G>,
+    pub d7: Scalar<G>,
+}
+
+/// The fourth message, sent by Bob.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(bound = ""))]
+pub struct Smp4<G: MODPGroup> {
+    pub rb: Element<G>,
+    pub cr: Scalar<G>,
+    pub d7: Scalar<G>,
+}
+
+/// The state of a party, named after the message it expects as in OTR.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SmpState {
+    /// Waiting to start the protocol or for [Smp1].
+    Expect1,
+    /// Alice waiting for [Smp2].
+    Expect2,
+    /// Bob waiting for [Smp3].
+    Expect3,
+    /// Alice waiting for [Smp4].
+    Expect4,
+    /// The protocol completed and the secrets are equal.
+    Succeeded,
+    /// The protocol completed and the secrets differ.
+    Failed,
+    /// The protocol was aborted. A new [Smp] must be created to run it again.
+    Aborted,
+}
+
+/// A party of the protocol, holding its secret and the values of the current step.
+pub struct Smp<G: MODPGroup, D: NamedDigest> {
+    secret: Scalar<G>,
+    stage: Stage<G>,
+    phantom: PhantomData<D>,
+}
+
+enum Stage<G: MODPGroup> {
+    Expect1,
+    Expect2 {
+        a2: Scalar<G>,
+        a3: Scalar<G>,
+    },
+    Expect3 {
+        g3a: Element<G>,
+        g2: Element<G>,
+        g3: Element<G>,
+        b3: Scalar<G>,
+        pb: Element<G>,
+        qb: Element<G>,
+    },
+    Expect4 {
+        g3b: Element<G>,
+        pa_pb: Element<G>,
+        qa_qb: Element<G>,
+        a3: Scalar<G>,
+    },
+    Done(bool),
+    Aborted,
+}
+
+impl<G: MODPGroup, D: NamedDigest> Smp<G, D> {
+    /// Create a party with the secret `secret`, which is hashed into the exponent `D(secret)`.
+    ///
+    /// In OTR, `secret` is the version byte 1, the fingerprints of the initiator and the
+    /// responder, the secure session id and the secret of the user, concatenated.
+    pub fn new(secret: &[u8]) -> Self {
+        Smp {
+            secret: Scalar::from_biguint(BigUint::from_bytes_be(&D::digest(secret))),
+            stage: Stage::Expect1,
+            phantom: PhantomData,
+        }
+    }
+
+    /// The current state of the party.
+    pub fn state(&self) -> SmpState {
+        match self.stage {
+            Stage::Expect1 => SmpState::Expect1,
+            Stage::Expect2 { .. } => SmpState::Expect2,
+            Stage::Expect3 { .. } => SmpState::Expect3,
+            Stage::Expect4 { .. } => SmpState::Expect4,
+            Stage::Done(true) => SmpState::Succeeded,
+            Stage::Done(false) => SmpState::Failed,
+            Stage::Aborted => SmpState::Aborted,
+        }
+    }
+
+    /// Abort the protocol, e.g. when the peer aborts it.
+    pub fn abort(&mut self) {
+        self.stage = Stage::Aborted;
+    }
+
+    /// Start the protocol as Alice, returning the message to send to Bob.
+    ///
+    /// # Errors
+    /// Returns [DhGroupsError::SmpAborted] if the state is not [SmpState::Expect1], and aborts.
+    pub fn start<R: CryptoRngCore + ?Sized>(
+        &mut self,
+        rng: &mut R,
+    ) -> Result<Smp1<G>, DhGroupsError> {
+        if !matches!(self.stage, Stage::Expect1) {
+            return self.fail(DhGroupsError::SmpAborted);
+        }
+        let (a2, a3) = (Scalar::<G>::random(rng), Scalar::<G>::random(rng));
+        let (c2, d2) = prove_dlog::<G, D, R>(1, &a2, rng);
+        let (c3, d3) = prove_dlog::<G, D, R>(2, &a3, rng);
+        let msg = Smp1 {
+            g2a: Element::from_biguint(a2.value.clone()),
+            c2,
+            d2,
+            g3a: Element::from_biguint(a3.value.clone()),
+            c3,
+            d3,
+        };
+        self.stage = Stage::Expect2 { a2, a3 };
+        Ok(msg)
+    }
+
+    /// Answer the first message as Bob, returning the message to send to Alice.
+    ///
+    /// # Errors
+    /// Returns [DhGroupsError::Validation] if an element is not valid, or
+    /// [DhGroupsError::SmpAborted] if a proof does not verify or the state is not
+    /// [SmpState::Expect1], and aborts.
+    pub fn receive_smp1<R: CryptoRngCore + ?Sized>(
+        &mut self,
+        msg: &Smp1<G>,
+        rng: &mut R,
+    ) -> Result<Smp2<G>, DhGroupsError> {
+        if !matches!(self.stage, Stage::Expect1) {
+            return self.fail(DhGroupsError::SmpAborted);
+        }
+        if let Err(e) = msg.g2a.validate().and(msg.g3a.validate()) {
+            return self.fail(e.into());
+        }
+        if !verify_dlog::<G, D>(1, &msg.g2a, &msg.c2, &msg.d2)
+            || !verify_dlog::<G, D>(2, &msg.g3a, &msg.c3, &msg.d3)
+        {
+            return self.fail(DhGroupsError::SmpAborted);
+        }
+
+        let (b2, b3) = (Scalar::<G>::random(rng), Scalar::<G>::random(rng));
+        let (c2, d2) = prove_dlog::<G, D, R>(3, &b2, rng);
+        let (c3, d3) = prove_dlog::<G, D, R>(4, &b3, rng);
+        let g2 = msg.g2a.pow(&b2.value);
+        let g3 = msg.g3a.pow(&b3.value);
+
+        let r = Scalar::<G>::random(rng);
+        let pb = g3.pow(&r.value);
+        let qb = Element::<G>::from_biguint(r.value.clone()) * g2.pow(&self.secret.value);
+        let (cp, d5, d6) = prove_pq::<G, D, R>(5, &g2, &g3, &r, &self.secret, rng);
+
+        let reply = Smp2 {
+            g2b: Element::from_biguint(b2.value.clone()),
+            c2,
+            d2,
+            g3b: Element::from_biguint(b3.value.clone()),
+            c3,
+            d3,
+            pb: copy(&pb),
+            qb: copy(&qb),
+            cp,
+            d5,
+            d6,
+        };
+        self.stage = Stage::Expect3 {
+            g3a: copy(&msg.g3a),
+            g2,
+            g3,
+            b3,
+            pb,
+            qb,
+        };
+        Ok(reply)
+    }
+
+    /// Answer the second message as Alice, returning the message to send to Bob.
+    ///
+    /// # Errors
+    /// Returns [DhGroupsError::Validation] if an element is not valid, or
+    /// [DhGroupsError::SmpAborted] if a proof does not verify or the state is not
+    /// [SmpState::Expect2], and aborts.
+    pub fn receive_smp2<R: CryptoRngCore + ?Sized>(
+        &mut self,
+        msg: &Smp2<G>,
+        rng: &mut R,
+    ) -> Result<Smp3<G>, DhGroupsError> {
+        let Stage::Expect2 { a2, a3 } = std::mem::replace(&mut self.stage, Stage::Aborted) else {
+            return self.fail(DhGroupsError::SmpAborted);
+        };
+        if let Err(e) = [&msg.g2b, &msg.g3b, &msg.pb, &msg.qb]
+            .iter()
+            .try_for_each(|e| e.validate())
+        {
+            return self.fail(e.into());
+        }
+        if !verify_dlog::<G, D>(3, &msg.g2b, &msg.c2, &msg.d2)
+            || !verify_dlog::<G, D>(4, &msg.g3b, &msg.c3, &msg.d3)
+        {
+            return self.fail(DhGroupsError::SmpAborted);
+        }
+        let g2 = msg.g2b.pow(&a2.value);
+        let g3 = msg.g3b.pow(&a3.value);
+        if !verify_pq::<G, D>(5, &g2, &g3, &msg.pb, &msg.qb, &msg.cp, &msg.d5, &msg.d6) {
+            return self.fail(DhGroupsError::SmpAborted);
+        }
+
+        let s = Scalar::<G>::random(rng);
+        let pa = g3.pow(&s.value);
+        let qa = Element::<G>::from_biguint(s.value.clone()) * g2.pow(&self.secret.value);
+        let (cp, d5, d6) = prove_pq::<G, D, R>(6, &g2, &g3, &s, &self.secret, rng);
+
+        let qa_qb = div(&qa, &msg.qb);
+        let ra = qa_qb.pow(&a3.value);
+        let (cr, d7) = prove_r::<G, D, R>(7, &qa_qb, &a3, rng);
+
+        let reply = Smp3 {
+            pa: copy(&pa),
+            qa,
+            cp,
+            d5,
+            d6,
+            ra,
+            cr,
+            d7,
+        };
+        self.stage = Stage::Expect4 {
+            g3b: copy(&msg.g3b),
+            pa_pb: div(&pa, &msg.pb),
+            qa_qb,
+            a3,
+        };
+        Ok(reply)
+    }
+
+    /// Answer the third message as Bob, returning the message to send to Alice. The state
+    /// becomes [SmpState::Succeeded] or [SmpState::Failed].
+    ///
+    /// # Errors
+    /// Returns [DhGroupsError::Validation] if an element is not valid, or
+    /// [DhGroupsError::SmpAborted] if a proof does not verify or the state is not
+    /// [SmpState::Expect3], and aborts.
+    pub fn receive_smp3<R: CryptoRngCore + ?Sized>(
+        &mut self,
+        msg: &Smp3<G>,
+        rng: &mut R,
+    ) -> Result<Smp4<G>, DhGroupsError> {
+        let Stage::Expect3 {
+            g3a,
+            g2,
+            g3,
+            b3,
+            pb,
+            qb,
+        } = std::mem::replace(&mut self.stage, Stage::Aborted)
+        else {
+            return self.fail(DhGroupsError::SmpAborted);
+        };
+        if let Err(e) = [&msg.pa, &msg.qa, &msg.ra]
+            .iter()
+            .try_for_each(|e| e.validate())
+        {
+            return self.fail(e.into());
+        }
+        if !verify_pq::<G, D>(6, &g2, &g3, &msg.pa, &msg.qa, &msg.cp, &msg.d5, &msg.d6) {
+            return self.fail(DhGroupsError::SmpAborted);
+        }
+        let qa_qb = div(&msg.qa, &qb);
+        if !verify_r::<G, D>(7, &qa_qb, &g3a, &msg.ra, &msg.cr, &msg.d7) {
+            return self.fail(DhGroupsError::SmpAborted);
+        }
+
+        let rb = qa_qb.pow(&b3.value);
+        let (cr, d7) = prove_r::<G, D, R>(8, &qa_qb, &b3, rng);
+        let rab = msg.ra.pow(&b3.value);
+        self.stage = Stage::Done(rab == div(&msg.pa, &pb));
+        Ok(Smp4 { rb, cr, d7 })
+    }
+
+    /// Process the fourth message as Alice. The state becomes [SmpState::Succeeded] or
+    /// [SmpState::Failed].
+    ///
+    /// # Errors
+    /// Returns [DhGroupsError::Validation] if an element is not valid, or
+    /// [DhGroupsError::SmpAborted] if the proof does not verify or the state is not
+    /// [SmpState::Expect4], and aborts.
+    pub fn receive_smp4(&mut self, msg: &Smp4<G>) -> Result<(), DhGroupsError> {
+        let Stage::Expect4 {
+            g3b,
+            pa_pb,
+            qa_qb,
+            a3,
+        } = std::mem::replace(&mut self.stage, Stage::Aborted)
+        else {
+            return self.fail(DhGroupsError::SmpAborted);
+        };
+        if let Err(e) = msg.rb.validate() {
+            return self.fail(e.into());
+        }
+        if !verify_r::<G, D>(8, &qa_qb, &g3b, &msg.rb, &msg.cr, &msg.d7) {
+            return self.fail(DhGroupsError::SmpAborted);
+        }
+        self.stage = Stage::Done(msg.rb.pow(&a3.value) == pa_pb);
+        Ok(())
+    }
+
+    /// Abort the protocol with the error `e`.
+    fn fail<T>(&mut self, e: DhGroupsError) -> Result<T, DhGroupsError> {
+        self.stage = Stage::Aborted;
+        Err(e)
+    }
+}
+
+/// The hash `D(version || MPI(a) || MPI(b))` of the proofs, or of a single element.
+fn hash<G: MODPGroup, D: NamedDigest>(version: u8, elements: &[&Element<G>]) -> Scalar<G> {
+    let mut hasher = D::new().chain_update([version]);
+    for element in elements {
+        let bytes = element.value.to_bytes_be();
+        hasher.update((bytes.len() as u32).to_be_bytes());
+        hasher.update(&bytes);
+    }
+    Scalar::from_biguint(BigUint::from_bytes_be(&hasher.finalize()))
+}
+
+/// Prove the knowledge of `x` in `g^x`: `c = H(version, g^r)`, `d = r - x * c`.
+fn prove_dlog<G: MODPGroup, D: NamedDigest, R: CryptoRngCore + ?Sized>(
+    version: u8,
+    x: &Scalar<G>,
+    rng: &mut R,
+) -> (Scalar<G>, Scalar<G>) {
+    let r = Scalar::<G>::random(rng);
+    let c = hash::<G, D>(version, &[&Element::from_biguint(r.value.clone())]);
+    let d = r - x * &c;
+    (c, d)
+}
+
+/// Verify a proof of [prove_dlog] for `gx`: `c = H(version, g^d gx^c)`.
+fn verify_dlog<G: MODPGroup, D: NamedDigest>(
+    version: u8,
+    gx: &Element<G>,
+    c: &Scalar<G>,
+    d: &Scalar<G>,
+) -> bool {
+    let t = Element::<G>::from_biguint(d.value.clone()) * gx.pow(&c.value);
+    hash::<G, D>(version, &[&t]) == *c
+}
+
+/// Prove that `P = g3^r` and `Q = g^r g2^x`:
+/// `c = H(version, g3^r4, g^r4 g2^r5)`, `d5 = r4 - r * c`, `d6 = r5 - x * c`.
+fn prove_pq<G: MODPGroup, D: NamedDigest, R: CryptoRngCore + ?Sized>(
+    version: u8,
+    g2: &Element<G>,
+    g3: &Element<G>,
+    r: &Scalar<G>,
+    x: &Scalar<G>,
+    rng: &mut R,
+) -> (Scalar<G>, Scalar<G>, Scalar<G>) {
+    let (r4, r5) = (Scalar::<G>::random(rng), Scalar::<G>::random(rng));
+    let t1 = g3.pow(&r4.value);
+    let t2 = Element::<G>::from_biguint(r4.value.clone()) * g2.pow(&r5.value);
+    let c = hash::<G, D>(version, &[&t1, &t2]);
+    let d5 = r4 - r * &c;
+    let d6 = r5 - x * &c;
+    (c, d5, d6)
+}
+
+/// Verify a proof of [prove_pq]: `c = H(version, g3^d5 P^c, g^d5 g2^d6 Q^c)`.
+#[allow(clippy::too_many_arguments)]
+fn verify_pq<G: MODPGroup, D: NamedDigest>(
+    version: u8,
+    g2: &Element<G>,
+    g3: &Element<G>,
+    p: &Element<G>,
+    q: &Element<G>,
+    c: &Scalar<G>,
+    d5: &Scalar<G>,
+    d6: &Scalar<G>,
+) -> bool {
+    let t1 = g3.pow(&d5.value) * p.pow(&c.value);
+    let t2 = Element::<G>::from_biguint(d5.value.clone()) * g2.pow(&d6.value) * q.pow(&c.value);
+    hash::<G, D>(version, &[&t1, &t2]) == *c
+}
+
+/// Prove that `R = (Qa / Qb)^x` for the `x` of `g^x`:
+/// `c = H(version, g^r7, (Qa / Qb)^r7)`, `d7 = r7 - x * c`.
+fn prove_r<G: MODPGroup, D: NamedDigest, R: CryptoRngCore + ?Sized>(
+    version: u8,
+    qa_qb: &Element<G>,
+    x: &Scalar<G>,
+    rng: &mut R,
+) -> (Scalar<G>, Scalar<G>) {
+    let r7 = Scalar::<G>::random(rng);
+    let t1 = Element::<G>::from_biguint(r7.value.clone());
+    let t2 = qa_qb.pow(&r7.value);
+    let c = hash::<G, D>(version, &[&t1, &t2]);
+    let d7 = r7 - x * &c;
+    (c, d7)
+}
+
+/// Verify a proof of [prove_r] for `gx = g^x`:
+/// `c = H(version, g^d7 gx^c, (Qa / Qb)^d7 R^c)`.
+fn verify_r<G: MODPGroup, D: NamedDigest>(
+    version: u8,
+    qa_qb: &Element<G>,
+    gx: &Element<G>,
+    r: &Element<G>,
+    c: &Scalar<G>,
+    d7: &Scalar<G>,
+) -> bool {
+    let t1 = Element::<G>::from_biguint(d7.value.clone()) * gx.pow(&c.value);
+    let t2 = qa_qb.pow(&d7.value) * r.pow(&c.value);
+    hash::<G, D>(version, &[&t1, &t2]) == *c
+}
+
+/// Compute `a / b` for elements of the subgroup of order q.
+fn div<G: MODPGroup>(a: &Element<G>, b: &Element<G>) -> Element<G> {
+    a * b.pow(&(G::subgroup_order() - 1u64))
+}
+
+fn copy<G: MODPGroup>(e: &Element<G>) -> Element<G> {
+    Element::from_value(e.value.clone())
+}
+
+#[cfg(test)]
+mod test {
+    use sha2::Sha256;
+
+    use super::*;
+    use crate::group::MODPGroup5;
+
+    type G = MODPGroup5;
+    type Party = Smp<G, Sha256>;
+
+    fn run(alice: &mut Party, bob: &mut Party) {
+        let rng = &mut rand::thread_rng();
+        let smp1 = alice.start(rng).unwrap();
+        assert_eq!(alice.state(), SmpState::Expect2);
+        let smp2 = bob.receive_smp1(&smp1, rng).unwrap();
+        assert_eq!(bob.state(), SmpState::Expect3);
+        let smp3 = alice.receive_smp2(&smp2, rng).unwrap();
+        assert_eq!(alice.state(), SmpState::Expect4);
+        let smp4 = bob.receive_smp3(&smp3, rng).unwrap();
+        alice.receive_smp4(&smp4).unwrap();
+    }
+
+    #[test]
+    fn test_equal_secrets() {
+        let (mut alice, mut bob) = (Party::new(b"secret"), Party::new(b"secret"));
+        run(&mut alice, &mut bob);
+        assert_eq!(alice.state(), SmpState::Succeeded);
+        assert_eq!(bob.state(), SmpState::Succeeded);
+    }
+
+    #[test]
+    fn test_unequal_secrets() {
+        let (mut alice, mut bob) = (Party::new(b"secret"), Party::new(b"another secret"));
+        run(&mut alice, &mut bob);
+        assert_eq!(alice.state(), SmpState::Failed);
+        assert_eq!(bob.state(), SmpState::Failed);
+    }
+
+    #[test]
+    fn test_tampered_messages() {
+        let rng = &mut rand::thread_rng();
+        let one = Scalar::<G>::from_biguint(BigUint::from(1u64));
+
+        // a tampered proof in the first message
+        let (mut alice, mut bob) = (Party::new(b"secret"), Party::new(b"secret"));
+        let mut smp1 = alice.start(rng).unwrap();
+        smp1.d2 = &smp1.d2 + &one;
+        assert_eq!(
+            bob.receive_smp1(&smp1, rng).unwrap_err(),
+            DhGroupsError::SmpAborted
+        );
+        assert_eq!(bob.state(), SmpState::Aborted);
+
+        // Pb replaced in the second message
+        let (mut alice, mut bob) = (Party::new(b"secret"), Party::new(b"secret"));
+        let smp1 = alice.start(rng).unwrap();
+        let mut smp2 = bob.receive_smp1(&smp1, rng).unwrap();
+        smp2.pb = Element::from_biguint(BigUint::from(5u64));
+        assert_eq!(
+            alice.receive_smp2(&smp2, rng).unwrap_err(),
+            DhGroupsError::SmpAborted
+        );
+        assert_eq!(alice.state(), SmpState::Aborted);
+
+        // Ra replaced in the third message, with an element outside the subgroup
+        let (mut alice, mut bob) = (Party::new(b"secret"), Party::new(b"secret"));
+        let smp1 = alice.start(rng).unwrap();
+        let smp2 = bob.receive_smp1(&smp1, rng).unwrap();
+        let mut smp3 = alice.receive_smp2(&smp2, rng).unwrap();
+        smp3.ra = Element::from_value(G::prime_modulus() - 2u64);
+        assert!(matches!(
+            bob.receive_smp3(&smp3, rng),
+            Err(DhGroupsError::Validation(_))
+        ));
+        assert_eq!(bob.state(), SmpState::Aborted);
+
+        // a tampered proof in the fourth message
+        let (mut alice, mut bob) = (Party::new(b"secret"), Party::new(b"secret"));
+        let smp1 = alice.start(rng).unwrap();
+        let smp2 = bob.receive_smp1(&smp1, rng).unwrap();
+        let smp3 = alice.receive_smp2(&smp2, rng).unwrap();
+        let mut smp4 = bob.receive_smp3(&smp3, rng).unwrap();
+        smp4.cr = &smp4.cr + &one;
+        assert_eq!(
+            alice.receive_smp4(&smp4).unwrap_err(),
+            DhGroupsError::SmpAborted
+        );
+        assert_eq!(alice.state(), SmpState::Aborted);
+    }
+
+    #[test]
+    fn test_out_of_order() {
+        let rng = &mut rand::thread_rng();
+        let (mut alice, mut bob) = (Party::new(b"secret"), Party::new(b"secret"));
+        let smp1 = alice.start(rng).unwrap();
+
+        // Alice cannot answer her own message, nor start twice
+        assert_eq!(
+            alice.receive_smp1(&smp1, rng).unwrap_err(),
+            DhGroupsError::SmpAborted
+        );
+        assert_eq!(alice.state(), SmpState::Aborted);
+        assert!(alice.start(rng).is_err());
+
+        let smp2 = bob.receive_smp1(&smp1, rng).unwrap();
+        bob.abort();
+        assert_eq!(bob.state(), SmpState::Aborted);
+        let mut alice = Party::new(b"secret");
+        assert!(alice.receive_smp2(&smp2, rng).is_err());
+    }
+}
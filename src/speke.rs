@@ -0,0 +1,291 @@
+//! The Simple Password Exponential Key Exchange (SPEKE) by Jablon: a password authenticated key
+//! exchange in which both parties derive a generator from the shared password and run a plain
+//! Diffie-Hellman exchange over it.
+//!
+//! 1. Both parties hash the password to an element of `[0, p)` and square it, giving a generator
+//!    `g_pw` of the subgroup of order q ([derive_generator]).
+//! 2. Each party picks `x` and sends `g_pw^x` ([Session::start]).
+//! 3. Each party checks the peer's element `y` and computes the shared secret `y^x`, from which
+//!    the key confirmation key and the session key are derived ([Session::finish]).
+//! 4. Each party sends its confirm ([SharedKey::confirm]) and checks the peer's
+//!    ([SharedKey::verify_confirm]).
+//!
+//! A peer sending 0, 1 or `p - 1` would force the shared secret into a set of at most two values
+//! independent of the password, so these elements are rejected, as well as elements outside the
+//! subgroup of order q and a reflection of our own element.
+//!
+//! # Example
+//!
+//! ```rust
+//! use sha2::Sha256;
+//! use diffie_hellman_groups::{group::MODPGroup14, speke::Session};
+//!
+//! let rng = &mut rand::thread_rng();
+//! let (alice, alice_element) = Session::<MODPGroup14>::start::<Sha256, _>(b"password", rng);
+//! let (bob, bob_element) = Session::<MODPGroup14>::start::<Sha256, _>(b"password", rng);
+//!
+//! let alice_key = alice.finish::<Sha256>(&bob_element).unwrap();
+//! let bob_key = bob.finish::<Sha256>(&alice_element).unwrap();
+//!
+//! bob_key.verify_confirm::<Sha256>(&alice_key.confirm::<Sha256>()).unwrap();
+//! alice_key.verify_confirm::<Sha256>(&bob_key.confirm::<Sha256>()).unwrap();
+//! assert_eq!(alice_key.key(), bob_key.key());
+//! ```
+
+use std::fmt;
+
+use digest::Digest;
+use num_bigint::BigUint;
+use rand_core::CryptoRngCore;
+
+use crate::{
+    group::MODPGroup,
+    hash::{expand_message, hash_to_subgroup},
+    Element, NamedDigest, PakeError, Scalar,
+};
+
+/// Domain separation tag of the password generator.
+const GENERATOR_DST: &[u8] = b"SPEKE Password Generator";
+
+/// Domain separation tag of the key confirmation key and the session key.
+const KEY_DST: &[u8] = b"SPEKE Key Confirm Key and Session Key";
+
+/// Derive the generator `g_pw` of `password`, the square of a hash of the password modulo p.
+///
+/// The hash is 128 bits longer than p so that its reduction is close to uniform. A square equal
+/// to 0 or 1 is discarded and the password hashed again with the next counter.
+pub fn derive_generator<G: MODPGroup, D: NamedDigest>(password: &[u8]) -> Element<G> {
+    hash_to_subgroup::<G, D>(password, GENERATOR_DST)
+}
+
+/// A party after sending its element, holding its exponent `x`.
+pub struct Session<G: MODPGroup> {
+    x: Scalar<G>,
+    element: Element<G>,
+}
+
+impl<G: MODPGroup> Session<G> {
+    /// Start the exchange with `password`, returning the party and the element `g_pw^x` to send
+    /// to the peer.
+    pub fn start<D: NamedDigest, R: CryptoRngCore + ?Sized>(
+        password: &[u8],
+        rng: &mut R,
+    ) -> (Self, Element<G>) {
+        let generator = derive_generator::<G, D>(password);
+        let x = Scalar::<G>::random(rng);
+        let element = generator.pow(&x.value);
+        let session = Session {
+            x,
+            element: Element::from_value(element.value.clone()),
+        };
+        (session, element)
+    }
+
+    /// Check the element of the peer and derive the keys.
+    ///
+    /// # Errors
+    /// - [PakeError::InvalidElement] if the peer element is not in `[2, p - 1)` or equals our own
+    ///   element.
+    /// - [PakeError::NotInSubgroup] if the peer element is not in the subgroup of order q.
+    pub fn finish<D: NamedDigest>(self, peer: &Element<G>) -> Result<SharedKey<G>, PakeError> {
+        let one = BigUint::from(1u64);
+        if peer.value <= one || peer.value >= G::p_minus_one() || *peer == self.element {
+            return Err(PakeError::InvalidElement);
+        }
+        if peer.pow(&G::subgroup_order()).value != one {
+            return Err(PakeError::NotInSubgroup);
+        }
+
+        // both parties order the elements the same way
+        let (own, peer_bytes) = (self.element.to_bytes_be(), peer.to_bytes_be());
+        let (first, second) = if own <= peer_bytes {
+            (&own, &peer_bytes)
+        } else {
+            (&peer_bytes, &own)
+        };
+        let len = <D as Digest>::output_size();
+        let mut input = peer.pow(&self.x.value).to_bytes_be();
+        input.extend_from_slice(first);
+        input.extend_from_slice(second);
+        let mut okm = expand_message::<D>(&input, KEY_DST, 0, 2 * len);
+        input.fill(0);
+        std::hint::black_box(&input);
+        let key = okm.split_off(len);
+
+        Ok(SharedKey {
+            kck: okm,
+            key,
+            own: self.element,
+            peer: Element::from_value(peer.value.clone()),
+        })
+    }
+}
+
+/// The keys derived from a completed exchange: the key confirmation key and the session key.
+/// They are zeroized on drop.
+pub struct SharedKey<G: MODPGroup> {
+    kck: Vec<u8>,
+    key: Vec<u8>,
+    own: Element<G>,
+    peer: Element<G>,
+}
+
+impl<G: MODPGroup> SharedKey<G> {
+    /// The session key, to be used only once the confirm of the peer is verified.
+    pub fn key(&self) -> &[u8] {
+        &self.key
+    }
+
+    /// Compute our confirm `D(kck || element || peer_element)`.
+    pub fn confirm<D: NamedDigest>(&self) -> Vec<u8> {
+        confirm::<G, D>(&self.kck, &self.own, &self.peer)
+    }
+
+    /// Check the confirm of the peer, computed with the elements in the peer's order.
+    ///
+    /// # Errors
+    /// Returns [PakeError::ConfirmationFailed] if the confirm does not match, e.g. because the
+    /// peer used a different password.
+    pub fn verify_confirm<D: NamedDigest>(&self, peer_confirm: &[u8]) -> Result<(), PakeError> {
+        let expected = confirm::<G, D>(&self.kck, &self.peer, &self.own);
+        let equal = expected.len() == peer_confirm.len()
+            && expected
+                .iter()
+                .zip(peer_confirm)
+                .fold(0u8, |acc, (a, b)| acc | (a ^ b))
+                == 0;
+        if equal {
+            Ok(())
+        } else {
+            Err(PakeError::ConfirmationFailed)
+        }
+    }
+}
+
+impl<G: MODPGroup> Drop for SharedKey<G> {
+    fn drop(&mut self) {
+        self.kck.fill(0);
+        self.key.fill(0);
+        std::hint::black_box((&self.kck, &self.key));
+    }
+}
+
+impl<G: MODPGroup> fmt::Debug for SharedKey<G> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SharedKey").finish_non_exhaustive()
+    }
+}
+
+fn confirm<G: MODPGroup, D: NamedDigest>(
+    kck: &[u8],
+    first: &Element<G>,
+    second: &Element<G>,
+) -> Vec<u8> {
+    D::new()
+        .chain_update(kck)
+        .chain_update(first.to_bytes_be())
+        .chain_update(second.to_bytes_be())
+        .finalize()
+        .to_vec()
+}
+
+#[cfg(test)]
+mod test {
+    use sha2::Sha256;
+
+    use super::*;
+    use crate::group::MODPGroup5;
+
+    type G = MODPGroup5;
+
+    fn exchange(password_a: &[u8], password_b: &[u8]) -> (SharedKey<G>, SharedKey<G>) {
+        let rng = &mut rand::thread_rng();
+        let (a, element_a) = Session::<G>::start::<Sha256, _>(password_a, rng);
+        let (b, element_b) = Session::<G>::start::<Sha256, _>(password_b, rng);
+        (
+            a.finish::<Sha256>(&element_b).unwrap(),
+            b.finish::<Sha256>(&element_a).unwrap(),
+        )
+    }
+
+    #[test]
+    fn test_generator() {
+        let generator = derive_generator::<G, Sha256>(b"password");
+        assert_eq!(generator, derive_generator::<G, Sha256>(b"password"));
+        assert_ne!(generator, derive_generator::<G, Sha256>(b"passwore"));
+        assert!(generator.value > BigUint::from(1u64));
+        assert_eq!(
+            generator.pow(&G::subgroup_order()).value,
+            BigUint::from(1u64)
+        );
+    }
+
+    #[test]
+    fn test_same_password() {
+        let (key_a, key_b) = exchange(b"password", b"password");
+
+        assert_eq!(key_a.key(), key_b.key());
+        assert_eq!(key_a.key().len(), 32);
+        assert!(key_b
+            .verify_confirm::<Sha256>(&key_a.confirm::<Sha256>())
+            .is_ok());
+        assert!(key_a
+            .verify_confirm::<Sha256>(&key_b.confirm::<Sha256>())
+            .is_ok());
+        // a confirm is not accepted back by its sender
+        assert_eq!(
+            key_a.verify_confirm::<Sha256>(&key_a.confirm::<Sha256>()),
+            Err(PakeError::ConfirmationFailed)
+        );
+    }
+
+    #[test]
+    fn test_different_password() {
+        let (key_a, key_b) = exchange(b"password", b"wrong password");
+
+        assert_ne!(key_a.key(), key_b.key());
+        assert_eq!(
+            key_b.verify_confirm::<Sha256>(&key_a.confirm::<Sha256>()),
+            Err(PakeError::ConfirmationFailed)
+        );
+        assert_eq!(
+            key_a.verify_confirm::<Sha256>(&key_b.confirm::<Sha256>()),
+            Err(PakeError::ConfirmationFailed)
+        );
+    }
+
+    #[test]
+    fn test_invalid_elements() {
+        let rng = &mut rand::thread_rng();
+        let p = G::prime_modulus();
+        let start = || Session::<G>::start::<Sha256, _>(b"password", &mut rand::thread_rng()).0;
+
+        for value in [
+            BigUint::from(0u64),
+            BigUint::from(1u64),
+            &p - 1u64,
+            p.clone(),
+        ] {
+            assert_eq!(
+                start()
+                    .finish::<Sha256>(&Element::from_value(value))
+                    .unwrap_err(),
+                PakeError::InvalidElement
+            );
+        }
+        // -2 is not a quadratic residue modulo p, as p = 7 mod 8
+        assert_eq!(
+            start()
+                .finish::<Sha256>(&Element::from_value(&p - 2u64))
+                .unwrap_err(),
+            PakeError::NotInSubgroup
+        );
+
+        // reflection of our own element
+        let (session, element) = Session::<G>::start::<Sha256, _>(b"password", rng);
+        assert_eq!(
+            session.finish::<Sha256>(&element).unwrap_err(),
+            PakeError::InvalidElement
+        );
+    }
+}
@@ -0,0 +1,732 @@
+//! Message bodies of the SSH Diffie-Hellman Group Exchange, as defined in [RFC4419](https://datatracker.ietf.org/doc/rfc4419/).
+//!
+//! Each message is encoded as an SSH packet payload, i.e. starting with its message number.
+//! Integers are encoded as `mpint` values as defined in [RFC4251](https://datatracker.ietf.org/doc/rfc4251/).
+//!
+//! [Kex] runs the Diffie-Hellman key exchange of [RFC4253](https://datatracker.ietf.org/doc/rfc4253/#section-8)
+//! over a MODP group, e.g. `diffie-hellman-group14-sha256` with [Group14Kex] and SHA-256.
+
+use std::{fmt, marker::PhantomData};
+
+use num_bigint::BigUint;
+use rand_core::CryptoRngCore;
+
+use crate::{
+    group::MODPGroup, DhGroupsError, Element, MODPGroup14, NamedDigest, Scalar, ValidationError,
+};
+
+#[cfg(feature = "primegroup")]
+use crate::PrimeGroup;
+
+/// Message number of SSH_MSG_KEX_DH_GEX_GROUP.
+pub const SSH_MSG_KEX_DH_GEX_GROUP: u8 = 31;
+/// Message number of SSH_MSG_KEX_DH_GEX_INIT.
+pub const SSH_MSG_KEX_DH_GEX_INIT: u8 = 32;
+/// Message number of SSH_MSG_KEX_DH_GEX_REPLY.
+pub const SSH_MSG_KEX_DH_GEX_REPLY: u8 = 33;
+/// Message number of SSH_MSG_KEX_DH_GEX_REQUEST.
+pub const SSH_MSG_KEX_DH_GEX_REQUEST: u8 = 34;
+
+/// SSH_MSG_KEX_DH_GEX_REQUEST, sent by the client with the acceptable modulus sizes in bits.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GexRequest {
+    /// Minimal size in bits of an acceptable group.
+    pub min: u32,
+    /// Preferred size in bits of the group the server will send.
+    pub n: u32,
+    /// Maximal size in bits of an acceptable group.
+    pub max: u32,
+}
+
+/// SSH_MSG_KEX_DH_GEX_GROUP, sent by the server with the selected group.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GexGroup {
+    /// Safe prime modulus.
+    pub p: BigUint,
+    /// Generator for the subgroup.
+    pub g: BigUint,
+}
+
+/// SSH_MSG_KEX_DH_GEX_INIT, sent by the client with its public value `e = g^x mod p`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GexInit {
+    pub e: BigUint,
+}
+
+/// SSH_MSG_KEX_DH_GEX_REPLY, sent by the server with its host key, its public value
+/// `f = g^y mod p` and the signature of the exchange hash.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GexReply {
+    /// Server public host key and certificates (K_S).
+    pub host_key: Vec<u8>,
+    pub f: BigUint,
+    /// Signature of the exchange hash H.
+    pub signature: Vec<u8>,
+}
+
+impl GexRequest {
+    /// Encode the message as an SSH packet payload.
+    pub fn encode(&self) -> Vec<u8> {
+        let mut buf = vec![SSH_MSG_KEX_DH_GEX_REQUEST];
+        write_u32(&mut buf, self.min);
+        write_u32(&mut buf, self.n);
+        write_u32(&mut buf, self.max);
+        buf
+    }
+
+    /// Decode the message from an SSH packet payload.
+    pub fn decode(bytes: &[u8]) -> Result<Self, DhGroupsError> {
+        let mut reader = Reader::new(bytes, SSH_MSG_KEX_DH_GEX_REQUEST)?;
+        let msg = Self {
+            min: reader.read_u32()?,
+            n: reader.read_u32()?,
+            max: reader.read_u32()?,
+        };
+        reader.finish()?;
+        Ok(msg)
+    }
+}
+
+impl GexGroup {
+    /// Encode the message as an SSH packet payload.
+    pub fn encode(&self) -> Vec<u8> {
+        let mut buf = vec![SSH_MSG_KEX_DH_GEX_GROUP];
+        write_mpint(&mut buf, &self.p);
+        write_mpint(&mut buf, &self.g);
+        buf
+    }
+
+    /// Decode the message from an SSH packet payload.
+    pub fn decode(bytes: &[u8]) -> Result<Self, DhGroupsError> {
+        let mut reader = Reader::new(bytes, SSH_MSG_KEX_DH_GEX_GROUP)?;
+        let msg = Self {
+            p: reader.read_mpint()?,
+            g: reader.read_mpint()?,
+        };
+        reader.finish()?;
+        Ok(msg)
+    }
+}
+
+impl GexInit {
+    /// Encode the message as an SSH packet payload.
+    pub fn encode(&self) -> Vec<u8> {
+        let mut buf = vec![SSH_MSG_KEX_DH_GEX_INIT];
+        write_mpint(&mut buf, &self.e);
+        buf
+    }
+
+    /// Decode the message from an SSH packet payload.
+    pub fn decode(bytes: &[u8]) -> Result<Self, DhGroupsError> {
+        let mut reader = Reader::new(bytes, SSH_MSG_KEX_DH_GEX_INIT)?;
+        let msg = Self {
+            e: reader.read_mpint()?,
+        };
+        reader.finish()?;
+        Ok(msg)
+    }
+}
+
+impl GexReply {
+    /// Encode the message as an SSH packet payload.
+    pub fn encode(&self) -> Vec<u8> {
+        let mut buf = vec![SSH_MSG_KEX_DH_GEX_REPLY];
+        write_string(&mut buf, &self.host_key);
+        write_mpint(&mut buf, &self.f);
+        write_string(&mut buf, &self.signature);
+        buf
+    }
+
+    /// Decode the message from an SSH packet payload.
+    pub fn decode(bytes: &[u8]) -> Result<Self, DhGroupsError> {
+        let mut reader = Reader::new(bytes, SSH_MSG_KEX_DH_GEX_REPLY)?;
+        let msg = Self {
+            host_key: reader.read_string()?.to_vec(),
+            f: reader.read_mpint()?,
+            signature: reader.read_string()?.to_vec(),
+        };
+        reader.finish()?;
+        Ok(msg)
+    }
+}
+
+/// Select the group to send in reply to a [GexRequest], following the rules used by OpenSSH:
+/// among the moduli whose size lies in `[min, max]`, the smallest size not less than `preferred`
+/// is chosen, or the largest size below it if there is none. `preferred` is clamped to `[min, max]`.
+///
+/// Returns `None` if `min > max` or no modulus has an acceptable size. If several moduli have
+/// the selected size, the first one is returned.
+///
+/// # Example
+///
+/// ```rust
+/// use diffie_hellman_groups::{PrimeGroup, group::{MODPGroup14, MODPGroup15}, ssh::select_group};
+///
+/// let moduli = [PrimeGroup::new::<MODPGroup14>(64).unwrap(), PrimeGroup::new::<MODPGroup15>(64).unwrap()];
+/// let selected = select_group(2048, 3072, 8192, &moduli).unwrap();
+/// assert_eq!(selected.p.bits(), 3072);
+/// ```
+#[cfg(feature = "primegroup")]
+pub fn select_group(
+    min: u32,
+    preferred: u32,
+    max: u32,
+    moduli: &[PrimeGroup],
+) -> Option<&PrimeGroup> {
+    if min > max {
+        return None;
+    }
+    let (min, max) = (u64::from(min), u64::from(max));
+    let preferred = u64::from(preferred).clamp(min, max);
+
+    let mut best: Option<&PrimeGroup> = None;
+    for group in moduli {
+        let size = group.p.bits();
+        if size < min || size > max {
+            continue;
+        }
+        best = match best {
+            None => Some(group),
+            Some(current) => {
+                let best_size = current.p.bits();
+                if (size >= preferred && size < best_size)
+                    || (best_size < preferred && size > best_size)
+                {
+                    Some(group)
+                } else {
+                    Some(current)
+                }
+            }
+        };
+    }
+    best
+}
+
+/// The Diffie-Hellman key exchange of RFC 4253 over the group `G`, holding the version strings
+/// and the SSH_MSG_KEXINIT payloads of both sides that enter the exchange hash
+/// `H = D(V_C || V_S || I_C || I_S || K_S || e || f || K)`.
+///
+/// The client sends `e = g^x` ([Kex::client_init]), the server replies with `f = g^y`, its host
+/// key `K_S` and a signature of `H` ([Kex::server_reply]), and the client derives the shared
+/// secret `K` and `H` ([KexClient::client_finish]). The exponents are sampled from `[1, q)`.
+///
+/// # Example
+///
+/// ```rust
+/// use sha2::Sha256;
+/// use diffie_hellman_groups::ssh::Group14Kex;
+///
+/// let rng = &mut rand::thread_rng();
+/// let kex = Group14Kex::new(
+///     b"SSH-2.0-client",
+///     b"SSH-2.0-server",
+///     b"client kexinit",
+///     b"server kexinit",
+/// );
+///
+/// let (client, e) = kex.clone().client_init(rng);
+/// let (f, server_k, server_h) = kex.server_reply::<Sha256, _>(&e, b"host key", rng).unwrap();
+/// let (client_k, client_h) = client.client_finish::<Sha256>(&f, b"host key").unwrap();
+/// assert_eq!((client_k, client_h), (server_k, server_h));
+/// ```
+#[derive(Debug)]
+pub struct Kex<G: MODPGroup> {
+    client_version: Vec<u8>,
+    server_version: Vec<u8>,
+    client_kexinit: Vec<u8>,
+    server_kexinit: Vec<u8>,
+    phantom: PhantomData<G>,
+}
+
+/// The key exchange `diffie-hellman-group14-*`, over the 2048-bit MODP group.
+pub type Group14Kex = Kex<MODPGroup14>;
+
+/// The client side of a [Kex] after sending `e`, holding its exponent `x`, which its `Debug`
+/// output leaves out.
+pub struct KexClient<G: MODPGroup> {
+    kex: Kex<G>,
+    x: Scalar<G>,
+    e: BigUint,
+}
+
+impl<G: MODPGroup> fmt::Debug for KexClient<G> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("KexClient")
+            .field("kex", &self.kex)
+            .field("e", &self.e)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<G: MODPGroup> Clone for Kex<G> {
+    fn clone(&self) -> Self {
+        Kex {
+            client_version: self.client_version.clone(),
+            server_version: self.server_version.clone(),
+            client_kexinit: self.client_kexinit.clone(),
+            server_kexinit: self.server_kexinit.clone(),
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<G: MODPGroup> Kex<G> {
+    /// Create the exchange from the version strings (without the trailing CR LF) and the
+    /// SSH_MSG_KEXINIT payloads of the client and the server.
+    pub fn new(
+        client_version: &[u8],
+        server_version: &[u8],
+        client_kexinit: &[u8],
+        server_kexinit: &[u8],
+    ) -> Self {
+        Kex {
+            client_version: client_version.to_vec(),
+            server_version: server_version.to_vec(),
+            client_kexinit: client_kexinit.to_vec(),
+            server_kexinit: server_kexinit.to_vec(),
+            phantom: PhantomData,
+        }
+    }
+
+    /// Start the exchange as the client, returning the client and its public value `e`.
+    pub fn client_init<R: CryptoRngCore + ?Sized>(self, rng: &mut R) -> (KexClient<G>, BigUint) {
+        let x = Scalar::<G>::random(rng);
+        let e = Element::<G>::from_biguint(x.value.clone()).value;
+        let client = KexClient {
+            kex: self,
+            x,
+            e: e.clone(),
+        };
+        (client, e)
+    }
+
+    /// Answer the public value `e` of the client as the server with the host key blob
+    /// `host_key`, returning the public value `f`, the shared secret `K` and the exchange hash
+    /// `H` to sign.
+    ///
+    /// # Errors
+    /// Returns [DhGroupsError::Validation] if `e` is not a valid public value, see [check_public].
+    pub fn server_reply<D: NamedDigest, R: CryptoRngCore + ?Sized>(
+        &self,
+        e: &BigUint,
+        host_key: &[u8],
+        rng: &mut R,
+    ) -> Result<(BigUint, BigUint, Vec<u8>), DhGroupsError> {
+        let e = check_public::<G>(e)?;
+        let y = Scalar::<G>::random(rng);
+        let f = Element::<G>::from_biguint(y.value.clone()).value;
+        let k = e.pow(&y.value).value;
+        let h = self.exchange_hash::<D>(host_key, &e.value, &f, &k);
+        Ok((f, k, h))
+    }
+
+    /// Compute the exchange hash `H` with `D`, framing the byte strings as SSH `string` values
+    /// and the integers as `mpint` values.
+    pub fn exchange_hash<D: NamedDigest>(
+        &self,
+        host_key: &[u8],
+        e: &BigUint,
+        f: &BigUint,
+        k: &BigUint,
+    ) -> Vec<u8> {
+        let mut buf = Vec::new();
+        write_string(&mut buf, &self.client_version);
+        write_string(&mut buf, &self.server_version);
+        write_string(&mut buf, &self.client_kexinit);
+        write_string(&mut buf, &self.server_kexinit);
+        write_string(&mut buf, host_key);
+        write_mpint(&mut buf, e);
+        write_mpint(&mut buf, f);
+        write_mpint(&mut buf, k);
+        D::digest(&buf).to_vec()
+    }
+}
+
+impl<G: MODPGroup> KexClient<G> {
+    /// Finish the exchange with the public value `f` and the host key blob `host_key` received
+    /// from the server, returning the shared secret `K` and the exchange hash `H`, against which
+    /// the signature of the server is verified.
+    ///
+    /// # Errors
+    /// Returns [DhGroupsError::Validation] if `f` is not a valid public value, see [check_public].
+    pub fn client_finish<D: NamedDigest>(
+        self,
+        f: &BigUint,
+        host_key: &[u8],
+    ) -> Result<(BigUint, Vec<u8>), DhGroupsError> {
+        let f = check_public::<G>(f)?;
+        let k = f.pow(&self.x.value).value;
+        let h = self.kex.exchange_hash::<D>(host_key, &self.e, &f.value, &k);
+        Ok((k, h))
+    }
+}
+
+/// Check a public value `e` or `f` received from the peer: RFC 4253 requires it to lie in
+/// `[1, p - 1]`, and 1, `p - 1` and the values outside of the subgroup of order `q` are rejected
+/// as well.
+pub fn check_public<G: MODPGroup>(value: &BigUint) -> Result<Element<G>, ValidationError> {
+    if *value >= G::prime_modulus() {
+        return Err(ValidationError::OutOfRange);
+    }
+    let element = Element::<G>::from_value(value.clone());
+    element.validate()?;
+    Ok(element)
+}
+
+/// Append `x` encoded as an SSH `mpint`: a big-endian two's complement integer prefixed by its
+/// length as a `uint32`. Zero is encoded as the empty string, and a leading zero byte is added
+/// when the most significant bit of a positive number is set.
+pub fn write_mpint(buf: &mut Vec<u8>, x: &BigUint) {
+    let mut bytes = x.to_bytes_be();
+    if bytes == [0] {
+        bytes.clear();
+    } else if bytes[0] & 0x80 != 0 {
+        bytes.insert(0, 0);
+    }
+    write_string(buf, &bytes);
+}
+
+/// Read an SSH `mpint` from the start of `bytes`, returning the value and the remaining bytes.
+/// Negative and non-minimally encoded values are rejected.
+pub fn read_mpint(bytes: &[u8]) -> Result<(BigUint, &[u8]), DhGroupsError> {
+    let (data, rest) = read_string(bytes)?;
+    match data {
+        [] => {}
+        [b, ..] if b & 0x80 != 0 => return Err(DhGroupsError::InvalidMpint),
+        [0] => return Err(DhGroupsError::InvalidMpint),
+        [0, b, ..] if b & 0x80 == 0 => return Err(DhGroupsError::InvalidMpint),
+        _ => {}
+    }
+    Ok((BigUint::from_bytes_be(data), rest))
+}
+
+/// Append `data` encoded as an SSH `string`, i.e. prefixed by its length as a `uint32`.
+pub fn write_string(buf: &mut Vec<u8>, data: &[u8]) {
+    write_u32(buf, data.len() as u32);
+    buf.extend_from_slice(data);
+}
+
+/// Read an SSH `string` from the start of `bytes`, returning the data and the remaining bytes.
+pub fn read_string(bytes: &[u8]) -> Result<(&[u8], &[u8]), DhGroupsError> {
+    let (len, rest) = read_u32(bytes)?;
+    let len = len as usize;
+    if rest.len() < len {
+        return Err(DhGroupsError::UnexpectedEof);
+    }
+    Ok(rest.split_at(len))
+}
+
+fn write_u32(buf: &mut Vec<u8>, x: u32) {
+    buf.extend_from_slice(&x.to_be_bytes());
+}
+
+fn read_u32(bytes: &[u8]) -> Result<(u32, &[u8]), DhGroupsError> {
+    if bytes.len() < 4 {
+        return Err(DhGroupsError::UnexpectedEof);
+    }
+    let (head, rest) = bytes.split_at(4);
+    Ok((u32::from_be_bytes(head.try_into().unwrap()), rest))
+}
+
+/// Cursor over a message payload, used by the decoders above.
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn new(bytes: &'a [u8], msg_type: u8) -> Result<Self, DhGroupsError> {
+        match bytes.split_first() {
+            None => Err(DhGroupsError::UnexpectedEof),
+            Some((&found, rest)) if found == msg_type => Ok(Self { bytes: rest }),
+            Some((&found, _)) => Err(DhGroupsError::UnexpectedMessageType {
+                expected: msg_type,
+                found,
+            }),
+        }
+    }
+
+    fn read_u32(&mut self) -> Result<u32, DhGroupsError> {
+        let (x, rest) = read_u32(self.bytes)?;
+        self.bytes = rest;
+        Ok(x)
+    }
+
+    fn read_mpint(&mut self) -> Result<BigUint, DhGroupsError> {
+        let (x, rest) = read_mpint(self.bytes)?;
+        self.bytes = rest;
+        Ok(x)
+    }
+
+    fn read_string(&mut self) -> Result<&'a [u8], DhGroupsError> {
+        let (data, rest) = read_string(self.bytes)?;
+        self.bytes = rest;
+        Ok(data)
+    }
+
+    fn finish(self) -> Result<(), DhGroupsError> {
+        if self.bytes.is_empty() {
+            Ok(())
+        } else {
+            Err(DhGroupsError::TrailingBytes)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use num_bigint::BigUint;
+
+    use super::*;
+
+    fn mpint(x: &BigUint) -> Vec<u8> {
+        let mut buf = Vec::new();
+        write_mpint(&mut buf, x);
+        buf
+    }
+
+    #[test]
+    fn test_mpint_rfc4251_examples() {
+        let cases: [(&[u8], &[u8]); 3] = [
+            (&[], &[0x00, 0x00, 0x00, 0x00]),
+            (
+                &[0x09, 0xa3, 0x78, 0xf9, 0xb2, 0xe3, 0x32, 0xa7],
+                &[
+                    0x00, 0x00, 0x00, 0x08, 0x09, 0xa3, 0x78, 0xf9, 0xb2, 0xe3, 0x32, 0xa7,
+                ],
+            ),
+            (&[0x80], &[0x00, 0x00, 0x00, 0x02, 0x00, 0x80]),
+        ];
+        for (value, encoded) in cases {
+            let x = BigUint::from_bytes_be(value);
+            assert_eq!(mpint(&x), encoded);
+            assert_eq!(read_mpint(encoded).unwrap(), (x, &[][..]));
+        }
+    }
+
+    #[test]
+    fn test_mpint_rejects_invalid() {
+        // negative
+        assert_eq!(
+            read_mpint(&[0, 0, 0, 1, 0x80]),
+            Err(DhGroupsError::InvalidMpint)
+        );
+        // non-minimal encodings
+        assert_eq!(
+            read_mpint(&[0, 0, 0, 1, 0]),
+            Err(DhGroupsError::InvalidMpint)
+        );
+        assert_eq!(
+            read_mpint(&[0, 0, 0, 2, 0, 0x7f]),
+            Err(DhGroupsError::InvalidMpint)
+        );
+        // truncated
+        assert_eq!(
+            read_mpint(&[0, 0, 0, 2, 1]),
+            Err(DhGroupsError::UnexpectedEof)
+        );
+    }
+
+    #[test]
+    fn test_gex_request() {
+        let msg = GexRequest {
+            min: 2048,
+            n: 3072,
+            max: 8192,
+        };
+        let encoded = [
+            34, 0x00, 0x00, 0x08, 0x00, 0x00, 0x00, 0x0c, 0x00, 0x00, 0x00, 0x20, 0x00,
+        ];
+        assert_eq!(msg.encode(), encoded);
+        assert_eq!(GexRequest::decode(&encoded).unwrap(), msg);
+        assert_eq!(
+            GexRequest::decode(&encoded[..12]),
+            Err(DhGroupsError::UnexpectedEof)
+        );
+        assert_eq!(
+            GexInit::decode(&encoded),
+            Err(DhGroupsError::UnexpectedMessageType {
+                expected: 32,
+                found: 34
+            })
+        );
+    }
+
+    #[test]
+    fn test_gex_group() {
+        let msg = GexGroup {
+            p: BigUint::from(0xe3u32),
+            g: BigUint::from(2u32),
+        };
+        let encoded = [31, 0, 0, 0, 2, 0x00, 0xe3, 0, 0, 0, 1, 0x02];
+        assert_eq!(msg.encode(), encoded);
+        assert_eq!(GexGroup::decode(&encoded).unwrap(), msg);
+
+        let mut trailing = encoded.to_vec();
+        trailing.push(0);
+        assert_eq!(
+            GexGroup::decode(&trailing),
+            Err(DhGroupsError::TrailingBytes)
+        );
+    }
+
+    #[test]
+    fn test_gex_captured() {
+        // a diffie-hellman-group-exchange-sha256 exchange of the OpenSSH client, written by
+        // tests/fixtures/ssh/capture.sh, answered with the group 14 of RFC 3526
+        let request = include_bytes!("../tests/fixtures/ssh/gex_request.bin");
+        let msg = GexRequest::decode(request).unwrap();
+        assert_eq!(
+            msg,
+            GexRequest {
+                min: 2048,
+                n: 8192,
+                max: 8192
+            }
+        );
+        assert_eq!(msg.encode(), request);
+
+        let group = include_bytes!("../tests/fixtures/ssh/gex_group.bin");
+        let msg = GexGroup::decode(group).unwrap();
+        assert_eq!(msg.p, MODPGroup14::prime_modulus());
+        assert_eq!(msg.g, BigUint::from(2u64));
+        assert_eq!(msg.encode(), group);
+
+        let value = capture(include_str!("../tests/fixtures/ssh/gex_sha256.txt"));
+        let int = |name| BigUint::from_bytes_be(&value(name));
+        let e = GexInit { e: int("e") };
+        assert_eq!(GexInit::decode(&e.encode()).unwrap(), e);
+        assert_eq!(
+            check_public::<MODPGroup14>(&e.e)
+                .unwrap()
+                .pow(&int("y"))
+                .value,
+            int("K")
+        );
+    }
+
+    #[test]
+    fn test_gex_init_reply() {
+        let init = GexInit {
+            e: BigUint::from(0x1234u32),
+        };
+        let encoded = [32, 0, 0, 0, 2, 0x12, 0x34];
+        assert_eq!(init.encode(), encoded);
+        assert_eq!(GexInit::decode(&encoded).unwrap(), init);
+
+        let reply = GexReply {
+            host_key: b"key".to_vec(),
+            f: BigUint::from(0xffu32),
+            signature: b"sig".to_vec(),
+        };
+        let encoded = [
+            33, 0, 0, 0, 3, b'k', b'e', b'y', 0, 0, 0, 2, 0x00, 0xff, 0, 0, 0, 3, b's', b'i', b'g',
+        ];
+        assert_eq!(reply.encode(), encoded);
+        assert_eq!(GexReply::decode(&encoded).unwrap(), reply);
+    }
+
+    /// The values of a key exchange, as `name hex` lines.
+    fn capture(text: &str) -> impl Fn(&str) -> Vec<u8> + '_ {
+        move |name| {
+            let line = text
+                .lines()
+                .find_map(|line| line.strip_prefix(name)?.strip_prefix(' '))
+                .unwrap();
+            (0..line.len())
+                .step_by(2)
+                .map(|i| u8::from_str_radix(&line[i..i + 2], 16).unwrap())
+                .collect()
+        }
+    }
+
+    #[test]
+    fn test_group14_kex() {
+        use sha2::Sha256;
+
+        // a diffie-hellman-group14-sha256 exchange of the OpenSSH client with a server whose
+        // exponent y is known, written by tests/fixtures/ssh/capture.sh. The client accepted the
+        // signature of H by the server.
+        let value = capture(include_str!("../tests/fixtures/ssh/group14_sha256.txt"));
+        let int = |name| BigUint::from_bytes_be(&value(name));
+        let kex = Group14Kex::new(&value("V_C"), &value("V_S"), &value("I_C"), &value("I_S"));
+        let (e, f, k) = (int("e"), int("f"), int("K"));
+
+        assert_eq!(
+            check_public::<MODPGroup14>(&e)
+                .unwrap()
+                .pow(&int("y"))
+                .value,
+            k
+        );
+        assert!(check_public::<MODPGroup14>(&f).is_ok());
+        assert_eq!(
+            kex.exchange_hash::<Sha256>(&value("K_S"), &e, &f, &k),
+            value("H")
+        );
+
+        // both sides of the crate agree, and the exponent of the client is not shown
+        let rng = &mut rand::thread_rng();
+        let (client, e) = kex.clone().client_init(rng);
+        assert!(!format!("{client:?}").contains(&client.x.value.to_string()));
+        let (f, server_k, server_h) = kex
+            .server_reply::<Sha256, _>(&e, &value("K_S"), rng)
+            .unwrap();
+        let (client_k, client_h) = client.client_finish::<Sha256>(&f, &value("K_S")).unwrap();
+        assert_eq!((client_k, client_h), (server_k, server_h));
+    }
+
+    #[test]
+    fn test_kex_rejects_invalid_public_values() {
+        use sha2::Sha256;
+
+        let rng = &mut rand::thread_rng();
+        let kex = Group14Kex::new(b"V_C", b"V_S", b"I_C", b"I_S");
+        let p = MODPGroup14::prime_modulus();
+        for (value, error) in [
+            (BigUint::from(0u64), ValidationError::OutOfRange),
+            (p.clone(), ValidationError::OutOfRange),
+            (BigUint::from(1u64), ValidationError::Degenerate),
+            (&p - 1u64, ValidationError::Degenerate),
+            (&p - 2u64, ValidationError::NotInSubgroup),
+        ] {
+            assert_eq!(
+                kex.server_reply::<Sha256, _>(&value, b"host key", rng),
+                Err(DhGroupsError::Validation(error))
+            );
+            let (client, _) = kex.clone().client_init(rng);
+            assert_eq!(
+                client.client_finish::<Sha256>(&value, b"host key"),
+                Err(DhGroupsError::Validation(error))
+            );
+        }
+    }
+
+    #[cfg(feature = "primegroup")]
+    #[test]
+    fn test_select_group() {
+        use crate::group::{MODPGroup14, MODPGroup15, MODPGroup16, MODPGroup5};
+
+        let moduli = [
+            PrimeGroup::new::<MODPGroup15>(64).unwrap(),
+            PrimeGroup::new::<MODPGroup5>(64).unwrap(),
+            PrimeGroup::new::<MODPGroup16>(64).unwrap(),
+            PrimeGroup::new::<MODPGroup14>(64).unwrap(),
+        ];
+        let bits = |g: Option<&PrimeGroup>| g.map(|g| g.p.bits());
+
+        // exact match
+        assert_eq!(bits(select_group(1024, 2048, 8192, &moduli)), Some(2048));
+        // smallest size above the preferred one
+        assert_eq!(bits(select_group(1024, 2049, 8192, &moduli)), Some(3072));
+        // largest size below the preferred one
+        assert_eq!(bits(select_group(1024, 8192, 8192, &moduli)), Some(4096));
+        // preferred size is clamped to the bounds
+        assert_eq!(bits(select_group(2048, 1024, 3072, &moduli)), Some(2048));
+        assert_eq!(bits(select_group(1024, 8192, 3072, &moduli)), Some(3072));
+        // bounds are inclusive
+        assert_eq!(bits(select_group(4096, 4096, 4096, &moduli)), Some(4096));
+        // nothing acceptable
+        assert_eq!(bits(select_group(4097, 6144, 8192, &moduli)), None);
+        assert_eq!(bits(select_group(3072, 3072, 2048, &moduli)), None);
+    }
+}
@@ -0,0 +1,192 @@
+//! Counters of the modular arithmetic performed by the current thread, enabled by the `stats`
+//! feature, e.g. to compare the number of multiplications of two implementations of a protocol.
+//!
+//! The counters are incremented by the default methods of [MODPGroup] and by the arithmetic of the
+//! groups of RFC 3526. The exponentiations of [MODPGroup::pow] run inside num-bigint and are
+//! counted as a whole, while those of [Element::multi_pow](crate::Element::multi_pow) count their
+//! squarings and multiplications. Without the feature, the counting is compiled out.
+//!
+//! # Example
+//!
+//! ```rust
+//! use num_bigint::BigUint;
+//! use diffie_hellman_groups::{group::MODPGroup14, stats, Element};
+//!
+//! let a = Element::<MODPGroup14>::from_value(BigUint::from(3u64));
+//! let b = Element::<MODPGroup14>::from_value(BigUint::from(5u64));
+//!
+//! let guard = stats::guard(|delta| assert_eq!(delta.multiplications, 2));
+//! let _ = &a * &b * &a;
+//! drop(guard);
+//! ```
+//!
+//! [MODPGroup]: crate::group::MODPGroup
+//! [MODPGroup::pow]: crate::group::MODPGroup::pow
+
+use std::{cell::Cell, ops::Sub};
+
+thread_local! {
+    static STATS: Cell<Stats> = const { Cell::new(Stats::ZERO) };
+}
+
+/// The number of operations of each kind counted on a thread.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Stats {
+    /// Modular multiplications of distinct values.
+    pub multiplications: u64,
+    /// Modular squarings.
+    pub squarings: u64,
+    /// Reductions modulo p, e.g. of the products.
+    pub reductions: u64,
+    /// Modular exponentiations with [MODPGroup::pow](crate::group::MODPGroup::pow).
+    pub exponentiations: u64,
+}
+
+impl Stats {
+    const ZERO: Stats = Stats {
+        multiplications: 0,
+        squarings: 0,
+        reductions: 0,
+        exponentiations: 0,
+    };
+}
+
+impl Sub for Stats {
+    type Output = Stats;
+
+    /// The operations counted between the snapshots `rhs` and `self`.
+    fn sub(self, rhs: Stats) -> Stats {
+        Stats {
+            multiplications: self.multiplications - rhs.multiplications,
+            squarings: self.squarings - rhs.squarings,
+            reductions: self.reductions - rhs.reductions,
+            exponentiations: self.exponentiations - rhs.exponentiations,
+        }
+    }
+}
+
+/// A kind of operation to count.
+#[derive(Clone, Copy, Debug)]
+pub(crate) enum Operation {
+    Multiplication,
+    Squaring,
+    Reduction,
+    Exponentiation,
+}
+
+/// Count an operation on the current thread.
+pub(crate) fn record(operation: Operation) {
+    // the counters are gone while the thread is being torn down
+    let _ = STATS.try_with(|stats| {
+        let mut s = stats.get();
+        match operation {
+            Operation::Multiplication => s.multiplications += 1,
+            Operation::Squaring => s.squarings += 1,
+            Operation::Reduction => s.reductions += 1,
+            Operation::Exponentiation => s.exponentiations += 1,
+        }
+        stats.set(s);
+    });
+}
+
+/// The operations counted on the current thread since it started or since the last [reset].
+pub fn snapshot() -> Stats {
+    STATS.with(Cell::get)
+}
+
+/// Set the counters of the current thread to zero.
+pub fn reset() {
+    STATS.with(|stats| stats.set(Stats::ZERO));
+}
+
+/// Count the operations of a scope: `report` is called with the operations of the current
+/// thread from the call of `guard` until the returned [Guard] is dropped. Guards can be nested,
+/// as they do not reset the counters.
+pub fn guard<F: FnOnce(Stats)>(report: F) -> Guard<F> {
+    Guard {
+        start: snapshot(),
+        report: Some(report),
+    }
+}
+
+/// Reports the operations counted during its lifetime when dropped, see [guard].
+pub struct Guard<F: FnOnce(Stats)> {
+    start: Stats,
+    report: Option<F>,
+}
+
+impl<F: FnOnce(Stats)> Guard<F> {
+    /// The operations counted since the creation of the guard.
+    pub fn delta(&self) -> Stats {
+        snapshot() - self.start
+    }
+}
+
+impl<F: FnOnce(Stats)> Drop for Guard<F> {
+    fn drop(&mut self) {
+        if let Some(report) = self.report.take() {
+            report(self.delta());
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use num_bigint::{BigUint, RandBigInt};
+
+    use super::*;
+    use crate::{Element, MODPGroup, MODPGroup14, MODPGroup5};
+
+    #[test]
+    fn test_windowed_exponentiation() {
+        let mut rng = rand::thread_rng();
+        let base = Element::<MODPGroup14>::from_value(rng.gen_biguint(2048));
+        // 256 bits, with the top bit set
+        let exponent = rng.gen_biguint(255) | (BigUint::from(1u64) << 255);
+
+        let guard = guard(|_| {});
+        let expected = base.pow(&exponent);
+        assert_eq!(
+            guard.delta(),
+            Stats {
+                exponentiations: 1,
+                ..Stats::default()
+            }
+        );
+
+        let start = snapshot();
+        assert_eq!(Element::multi_pow(&[(&base, &exponent)]), expected);
+        let delta = snapshot() - start;
+        // 4 squarings per 4-bit window below the top one
+        assert_eq!(delta.squarings, 252);
+        // 14 for the table of powers, and one per nonzero window
+        assert!(
+            (14 + 1..=14 + 64).contains(&delta.multiplications),
+            "{delta:?}"
+        );
+        assert_eq!(
+            delta.reductions,
+            delta.squarings + delta.multiplications + 1
+        );
+    }
+
+    #[test]
+    fn test_guard_and_reset() {
+        let a = BigUint::from(3u64);
+        let reported = Cell::new(None);
+        {
+            let _outer = guard(|delta| reported.set(Some(delta)));
+            let inner = guard(|delta| assert_eq!(delta.squarings, 1));
+            MODPGroup5::mul(&a, &a);
+            MODPGroup5::square(&a);
+            drop(inner);
+        }
+        let reported = reported.get().unwrap();
+        assert_eq!(reported.multiplications, 1);
+        assert_eq!(reported.squarings, 1);
+        assert_eq!(reported.reductions, 2);
+
+        reset();
+        assert_eq!(snapshot(), Stats::default());
+    }
+}
@@ -0,0 +1,420 @@
+//! The Station-to-Station (STS) protocol: a Diffie-Hellman key agreement in which each party
+//! signs both ephemeral public values, and proves knowledge of the derived key by a MAC of its
+//! signature (the STS-MAC variant).
+//!
+//! 1. The initiator sends `g^x` ([Initiator::new]).
+//! 2. The responder sends `g^y`, its signature `sig_B = Sign_B(g^y || g^x)` and
+//!    `MAC_k(sig_B)` ([Responder::new]).
+//! 3. The initiator checks them and sends `sig_A = Sign_A(g^x || g^y)` and `MAC_k(sig_A)`
+//!    ([Initiator::finish]), which the responder checks ([Responder::finish]).
+//!
+//! The MAC key `k` and the [SessionKey] are derived from `g^(xy)` with `D`. Each step consumes
+//! the state of the previous one, so that the messages cannot be processed out of order.
+//!
+//! The signature scheme is provided by the caller through the [Signer] and [Verifier] traits,
+//! e.g. for Ed25519. [SchnorrSigner] and [SchnorrVerifier] implement them with the Schnorr
+//! signatures of this crate.
+//!
+//! # Example
+//!
+//! ```rust
+//! use sha2::Sha256;
+//! use diffie_hellman_groups::{
+//!     sts::{Initiator, Responder, SchnorrSigner, SchnorrVerifier},
+//!     Element, Scalar, group::MODPGroup5,
+//! };
+//!
+//! type G = MODPGroup5;
+//! let rng = &mut rand::thread_rng();
+//! let (sk_a, sk_b) = (Scalar::<G>::random(rng), Scalar::<G>::random(rng));
+//! let signer_a = SchnorrSigner::<G, Sha256>::new(sk_a.clone());
+//! let signer_b = SchnorrSigner::<G, Sha256>::new(sk_b.clone());
+//! let verifier_a = SchnorrVerifier::<G, Sha256>::new(Element::from_biguint(sk_a.value));
+//! let verifier_b = SchnorrVerifier::<G, Sha256>::new(Element::from_biguint(sk_b.value));
+//!
+//! let (initiator, msg1) = Initiator::<G>::new(rng);
+//! let (responder, msg2) = Responder::new::<Sha256, _, _>(&msg1, &signer_b, rng).unwrap();
+//! let (key_a, msg3) = initiator.finish::<Sha256, _, _>(&msg2, &signer_a, &verifier_b).unwrap();
+//! let key_b = responder.finish::<Sha256, _>(&msg3, &verifier_a).unwrap();
+//! assert_eq!(key_a.as_bytes(), key_b.as_bytes());
+//! ```
+
+use std::{fmt, marker::PhantomData};
+
+use digest::{core_api::BlockSizeUser, Digest};
+use hmac::{Mac, SimpleHmac};
+use rand_core::CryptoRngCore;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    group::MODPGroup,
+    hash::expand_message,
+    schnorr::{self, CompactSignature},
+    DhGroupsError, Element, NamedDigest, PublicKey, Scalar, UnvalidatedPublicKey,
+};
+
+/// Domain separation tag of the MAC key and the session key.
+const KEY_DST: &[u8] = b"diffie-hellman-groups sts keys";
+
+/// A signing key of the parties.
+pub trait Signer {
+    /// Sign `msg`.
+    fn sign(&self, msg: &[u8]) -> Vec<u8>;
+}
+
+/// A verifying key of the parties.
+pub trait Verifier {
+    /// Check that `signature` is a valid signature of `msg`.
+    fn verify(&self, msg: &[u8], signature: &[u8]) -> bool;
+}
+
+/// The first message, `g^x`, sent by the initiator.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(bound = ""))]
+pub struct Message1<G: MODPGroup> {
+    pub gx: UnvalidatedPublicKey<G>,
+}
+
+/// The second message, sent by the responder.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(bound = ""))]
+pub struct Message2<G: MODPGroup> {
+    pub gy: UnvalidatedPublicKey<G>,
+    /// Signature of `g^y || g^x`.
+    pub signature: Vec<u8>,
+    /// MAC of the signature.
+    pub mac: Vec<u8>,
+}
+
+/// The third message, sent by the initiator.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Message3 {
+    /// Signature of `g^x || g^y`.
+    pub signature: Vec<u8>,
+    /// MAC of the signature.
+    pub mac: Vec<u8>,
+}
+
+/// The initiator after sending [Message1], holding its exponent `x`.
+pub struct Initiator<G: MODPGroup> {
+    x: Scalar<G>,
+    gx: Element<G>,
+}
+
+/// The responder after sending [Message2], holding the public values and the derived keys.
+pub struct Responder<G: MODPGroup> {
+    gx: PublicKey<G>,
+    gy: Element<G>,
+    keys: Keys,
+}
+
+/// The key agreed by a successful run of the protocol, of the output size of the digest. It is
+/// zeroized on drop.
+pub struct SessionKey(Vec<u8>);
+
+/// The MAC key and the session key.
+struct Keys {
+    mac: SessionKey,
+    session: SessionKey,
+}
+
+impl<G: MODPGroup> Initiator<G> {
+    /// Start the protocol, returning the initiator and the message to send to the responder.
+    pub fn new<R: CryptoRngCore + ?Sized>(rng: &mut R) -> (Self, Message1<G>) {
+        let x = Scalar::<G>::random(rng);
+        let gx = Element::<G>::from_biguint(x.value.clone());
+        let msg = Message1 {
+            gx: PublicKey::from_secret_power(Element::from_value(gx.value.clone())).into(),
+        };
+        (Initiator { x, gx }, msg)
+    }
+
+    /// Check the second message with the verifying key of the responder, and answer it with the
+    /// signing key of the initiator, returning the session key and the message to send.
+    ///
+    /// # Errors
+    /// Returns [DhGroupsError::Validation] if `g^y` is not a valid element, or
+    /// [DhGroupsError::AuthenticationFailed] if the signature or the MAC does not verify.
+    pub fn finish<D, S, V>(
+        self,
+        msg: &Message2<G>,
+        signer: &S,
+        verifier: &V,
+    ) -> Result<(SessionKey, Message3), DhGroupsError>
+    where
+        D: NamedDigest + BlockSizeUser,
+        S: Signer + ?Sized,
+        V: Verifier + ?Sized,
+    {
+        let gy = msg.gy.clone().validate()?;
+        let keys = Keys::derive::<G, D>(&gy.as_element().pow(&self.x.value));
+        keys.verify_mac::<D>(&msg.signature, &msg.mac)?;
+        if !verifier.verify(&transcript(gy.as_element(), &self.gx), &msg.signature) {
+            return Err(DhGroupsError::AuthenticationFailed);
+        }
+
+        let signature = signer.sign(&transcript(&self.gx, gy.as_element()));
+        let mac = keys.mac::<D>(&signature);
+        Ok((keys.session, Message3 { signature, mac }))
+    }
+}
+
+impl<G: MODPGroup> Responder<G> {
+    /// Answer the first message with the signing key of the responder, returning the responder
+    /// and the message to send to the initiator.
+    ///
+    /// # Errors
+    /// Returns [DhGroupsError::Validation] if `g^x` is not a valid element.
+    pub fn new<D, S, R>(
+        msg: &Message1<G>,
+        signer: &S,
+        rng: &mut R,
+    ) -> Result<(Self, Message2<G>), DhGroupsError>
+    where
+        D: NamedDigest + BlockSizeUser,
+        S: Signer + ?Sized,
+        R: CryptoRngCore + ?Sized,
+    {
+        let gx = msg.gx.clone().validate()?;
+        let y = Scalar::<G>::random(rng);
+        let gy = Element::<G>::from_biguint(y.value.clone());
+        let keys = Keys::derive::<G, D>(&gx.as_element().pow(&y.value));
+
+        let signature = signer.sign(&transcript(&gy, gx.as_element()));
+        let mac = keys.mac::<D>(&signature);
+        let reply = Message2 {
+            gy: PublicKey::from_secret_power(Element::from_value(gy.value.clone())).into(),
+            signature,
+            mac,
+        };
+        let responder = Responder { gx, gy, keys };
+        Ok((responder, reply))
+    }
+
+    /// Check the third message with the verifying key of the initiator, returning the session
+    /// key.
+    ///
+    /// # Errors
+    /// Returns [DhGroupsError::AuthenticationFailed] if the signature or the MAC does not verify.
+    pub fn finish<D, V>(self, msg: &Message3, verifier: &V) -> Result<SessionKey, DhGroupsError>
+    where
+        D: NamedDigest + BlockSizeUser,
+        V: Verifier + ?Sized,
+    {
+        self.keys.verify_mac::<D>(&msg.signature, &msg.mac)?;
+        if !verifier.verify(&transcript(self.gx.as_element(), &self.gy), &msg.signature) {
+            return Err(DhGroupsError::AuthenticationFailed);
+        }
+        Ok(self.keys.session)
+    }
+}
+
+impl Keys {
+    /// Expand the shared element into the MAC key and the session key.
+    fn derive<G: MODPGroup, D: NamedDigest>(shared: &Element<G>) -> Self {
+        let len = <D as Digest>::output_size();
+        let mut mac = expand_message::<D>(&shared.to_bytes_be(), KEY_DST, 0, 2 * len);
+        let session = SessionKey(mac.split_off(len));
+        Keys {
+            mac: SessionKey(mac),
+            session,
+        }
+    }
+
+    fn mac<D: NamedDigest + BlockSizeUser>(&self, signature: &[u8]) -> Vec<u8> {
+        let mut mac = SimpleHmac::<D>::new_from_slice(self.mac.as_bytes()).expect("any key length");
+        mac.update(signature);
+        mac.finalize().into_bytes().to_vec()
+    }
+
+    fn verify_mac<D: NamedDigest + BlockSizeUser>(
+        &self,
+        signature: &[u8],
+        tag: &[u8],
+    ) -> Result<(), DhGroupsError> {
+        let mut mac = SimpleHmac::<D>::new_from_slice(self.mac.as_bytes()).expect("any key length");
+        mac.update(signature);
+        mac.verify_slice(tag)
+            .map_err(|_| DhGroupsError::AuthenticationFailed)
+    }
+}
+
+impl SessionKey {
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl Drop for SessionKey {
+    fn drop(&mut self) {
+        self.0.fill(0);
+        std::hint::black_box(&self.0);
+    }
+}
+
+impl fmt::Debug for SessionKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("SessionKey").finish_non_exhaustive()
+    }
+}
+
+/// Encode the public values signed by a party, its own first.
+fn transcript<G: MODPGroup>(own: &Element<G>, peer: &Element<G>) -> Vec<u8> {
+    let mut bytes = own.to_bytes_be();
+    bytes.extend_from_slice(&peer.to_bytes_be());
+    bytes
+}
+
+/// A [Signer] producing deterministic Schnorr signatures of this crate, in the compact form
+/// encoded by [CompactSignature::to_bytes].
+pub struct SchnorrSigner<G: MODPGroup, D: NamedDigest> {
+    sk: Scalar<G>,
+    pk: Element<G>,
+    phantom: PhantomData<D>,
+}
+
+/// A [Verifier] of the signatures of [SchnorrSigner].
+pub struct SchnorrVerifier<G: MODPGroup, D: NamedDigest> {
+    pk: Element<G>,
+    phantom: PhantomData<D>,
+}
+
+impl<G: MODPGroup, D: NamedDigest> SchnorrSigner<G, D> {
+    pub fn new(sk: Scalar<G>) -> Self {
+        let pk = Element::from_biguint(sk.value.clone());
+        SchnorrSigner {
+            sk,
+            pk,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<G: MODPGroup, D: NamedDigest> SchnorrVerifier<G, D> {
+    pub fn new(pk: Element<G>) -> Self {
+        SchnorrVerifier {
+            pk,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<G: MODPGroup, D: NamedDigest> Signer for SchnorrSigner<G, D> {
+    fn sign(&self, msg: &[u8]) -> Vec<u8> {
+        schnorr::sign_deterministic::<G, D>(&self.sk, msg)
+            .to_compact::<D>(&self.pk, msg)
+            .to_bytes()
+    }
+}
+
+impl<G: MODPGroup, D: NamedDigest> Verifier for SchnorrVerifier<G, D> {
+    fn verify(&self, msg: &[u8], signature: &[u8]) -> bool {
+        CompactSignature::from_bytes(signature)
+            .is_ok_and(|sig| schnorr::verify_compact::<G, D>(&self.pk, msg, &sig))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use sha2::Sha256;
+
+    use super::*;
+    use crate::{group::MODPGroup5, ValidationError};
+
+    type G = MODPGroup5;
+
+    fn keypair() -> (SchnorrSigner<G, Sha256>, SchnorrVerifier<G, Sha256>) {
+        let sk = Scalar::<G>::random(&mut rand::thread_rng());
+        let pk = Element::from_biguint(sk.value.clone());
+        (SchnorrSigner::new(sk), SchnorrVerifier::new(pk))
+    }
+
+    #[test]
+    fn test_run() {
+        let rng = &mut rand::thread_rng();
+        let (signer_a, verifier_a) = keypair();
+        let (signer_b, verifier_b) = keypair();
+
+        let (initiator, msg1) = Initiator::<G>::new(rng);
+        let (responder, msg2) = Responder::new::<Sha256, _, _>(&msg1, &signer_b, rng).unwrap();
+        let (key_a, msg3) = initiator
+            .finish::<Sha256, _, _>(&msg2, &signer_a, &verifier_b)
+            .unwrap();
+        let key_b = responder.finish::<Sha256, _>(&msg3, &verifier_a).unwrap();
+        assert_eq!(key_a.as_bytes(), key_b.as_bytes());
+        assert_eq!(key_a.as_bytes().len(), 32);
+    }
+
+    #[test]
+    fn test_man_in_the_middle() {
+        let rng = &mut rand::thread_rng();
+        let (signer_a, verifier_a) = keypair();
+        let (signer_b, verifier_b) = keypair();
+        let (signer_m, _) = keypair();
+
+        // the attacker substitutes its own public value for g^x
+        let (initiator, _) = Initiator::<G>::new(rng);
+        let (_, msg1_m) = Initiator::<G>::new(rng);
+        let (_, msg2) = Responder::new::<Sha256, _, _>(&msg1_m, &signer_b, rng).unwrap();
+        assert_eq!(
+            initiator
+                .finish::<Sha256, _, _>(&msg2, &signer_a, &verifier_b)
+                .unwrap_err(),
+            DhGroupsError::AuthenticationFailed
+        );
+
+        // the attacker substitutes its own public value and signature for those of the responder
+        let (initiator, msg1) = Initiator::<G>::new(rng);
+        let (_, msg2) = Responder::new::<Sha256, _, _>(&msg1, &signer_m, rng).unwrap();
+        assert_eq!(
+            initiator
+                .finish::<Sha256, _, _>(&msg2, &signer_a, &verifier_b)
+                .unwrap_err(),
+            DhGroupsError::AuthenticationFailed
+        );
+
+        // the attacker replaces g^y, keeping the signature and MAC of the responder
+        let (initiator, msg1) = Initiator::<G>::new(rng);
+        let (_, mut msg2) = Responder::new::<Sha256, _, _>(&msg1, &signer_b, rng).unwrap();
+        msg2.gy = Initiator::<G>::new(rng).1.gx;
+        assert_eq!(
+            initiator
+                .finish::<Sha256, _, _>(&msg2, &signer_a, &verifier_b)
+                .unwrap_err(),
+            DhGroupsError::AuthenticationFailed
+        );
+
+        // the attacker replaces the third message with one of another run
+        let (initiator, msg1) = Initiator::<G>::new(rng);
+        let (responder, msg2) = Responder::new::<Sha256, _, _>(&msg1, &signer_b, rng).unwrap();
+        let (other_initiator, other_msg1) = Initiator::<G>::new(rng);
+        let (_, other_msg2) = Responder::new::<Sha256, _, _>(&other_msg1, &signer_b, rng).unwrap();
+        let (_, other_msg3) = other_initiator
+            .finish::<Sha256, _, _>(&other_msg2, &signer_a, &verifier_b)
+            .unwrap();
+        assert!(initiator
+            .finish::<Sha256, _, _>(&msg2, &signer_a, &verifier_b)
+            .is_ok());
+        assert_eq!(
+            responder
+                .finish::<Sha256, _>(&other_msg3, &verifier_a)
+                .unwrap_err(),
+            DhGroupsError::AuthenticationFailed
+        );
+    }
+
+    #[test]
+    fn test_invalid_public_value() {
+        let rng = &mut rand::thread_rng();
+        let (signer_b, _) = keypair();
+        let msg1 = Message1 {
+            gx: Element::<G>::from_value(1u64.into()).into(),
+        };
+        assert_eq!(
+            Responder::new::<Sha256, _, _>(&msg1, &signer_b, rng).err(),
+            Some(DhGroupsError::Validation(ValidationError::Degenerate))
+        );
+    }
+}
@@ -0,0 +1,299 @@
+//! Helpers for reproducible tests, enabled by the `test-utils` feature.
+//!
+//! Every function of the crate consuming randomness takes the random number generator as an
+//! argument, any [CryptoRngCore](rand_core::CryptoRngCore), e.g. [Scalar::random](crate::Scalar::random), `PrimeGroup::generate` or the
+//! protocols, so a test passing a [SeededRng] gets the same values on each run, and a bug report
+//! can be replayed from its seed.
+//!
+//! For property tests, the [proptest] strategies [any_element], [any_subgroup_element],
+//! [any_scalar], [any_keypair] and [degenerate_public_keys] generate the values of a group `G`,
+//! and the `check_*` functions assert invariants every group must keep, returning a
+//! [TestCaseError] to be used with `?` inside `proptest!`.
+//!
+//! # Example
+//!
+//! ```rust
+//! use diffie_hellman_groups::{testing::SeededRng, MODPGroup5, Scalar};
+//!
+//! let a = Scalar::<MODPGroup5>::random(&mut SeededRng::new(42));
+//! let b = Scalar::<MODPGroup5>::random(&mut SeededRng::new(42));
+//! assert_eq!(a.value, b.value);
+//! ```
+//!
+//! ```rust
+//! use proptest::prelude::*;
+//! use diffie_hellman_groups::{testing, MODPGroup5};
+//!
+//! proptest!(ProptestConfig::with_cases(8), |(a in testing::any_keypair::<MODPGroup5>(),
+//!                                            b in testing::any_keypair::<MODPGroup5>())| {
+//!     testing::check_key_exchange(&a, &b)?;
+//! });
+//! ```
+
+use num_bigint::BigUint;
+use proptest::{
+    collection::vec,
+    prelude::{any, Strategy},
+    prop_assert, prop_assert_eq,
+    test_runner::TestCaseError,
+};
+use rand::{CryptoRng, RngCore, SeedableRng};
+use rand_chacha::ChaCha20Rng;
+
+use crate::{group::MODPGroup, Element, PublicKey, Scalar, UnvalidatedPublicKey, ValidationError};
+
+/// A ChaCha20 random number generator seeded from a `u64`, whose output is fixed for a given
+/// seed across platforms and releases of the crate.
+///
+/// It is a cryptographically secure generator, but a seed of 64 bits is guessable, so it is only
+/// meant for tests.
+#[derive(Clone, Debug)]
+pub struct SeededRng(ChaCha20Rng);
+
+impl SeededRng {
+    /// Create the generator of the given seed.
+    pub fn new(seed: u64) -> Self {
+        SeededRng(ChaCha20Rng::seed_from_u64(seed))
+    }
+}
+
+impl RngCore for SeededRng {
+    fn next_u32(&mut self) -> u32 {
+        self.0.next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0.next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.0.fill_bytes(dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.0.try_fill_bytes(dest)
+    }
+}
+
+impl CryptoRng for SeededRng {}
+
+/// A value of `[1, bound]` from big-endian bytes of the length of `bound`, which shrinks towards 1.
+fn nonzero_below(bound: BigUint) -> impl Strategy<Value = BigUint> {
+    let len = bound.bits().div_ceil(8) as usize;
+    vec(any::<u8>(), len).prop_map(move |bytes| BigUint::from_bytes_be(&bytes) % &bound + 1u64)
+}
+
+/// Any element of `[1, p)`, in the subgroup of order q or not.
+pub fn any_element<G: MODPGroup>() -> impl Strategy<Value = Element<G>> {
+    nonzero_below(G::p_minus_one()).prop_map(Element::from_value)
+}
+
+/// Any element `g^x` of the subgroup of order q other than 1, i.e. a valid public key.
+pub fn any_subgroup_element<G: MODPGroup>() -> impl Strategy<Value = Element<G>> {
+    any_scalar::<G>().prop_map(|x| Element::from_biguint(x.value))
+}
+
+/// Any non-zero scalar of `[1, q)`.
+pub fn any_scalar<G: MODPGroup>() -> impl Strategy<Value = Scalar<G>> {
+    nonzero_below(G::subgroup_order() - 1u64).prop_map(Scalar::from_biguint)
+}
+
+/// Any secret key and its public key `g^x`.
+pub fn any_keypair<G: MODPGroup>() -> impl Strategy<Value = (Scalar<G>, PublicKey<G>)> {
+    any_scalar::<G>().prop_map(|x| {
+        let public = PublicKey::from_secret_power(Element::from_biguint(x.value.clone()));
+        (x, public)
+    })
+}
+
+/// Public keys that [UnvalidatedPublicKey::validate] must reject: 0, 1, `p - 1`, and the
+/// elements `p - g^x` outside of the subgroup of order q.
+pub fn degenerate_public_keys<G: MODPGroup>() -> impl Strategy<Value = UnvalidatedPublicKey<G>> {
+    (0u8..4, any_subgroup_element::<G>()).prop_map(|(case, y)| {
+        let value = match case {
+            0 => BigUint::from(0u64),
+            1 => BigUint::from(1u64),
+            2 => G::p_minus_one(),
+            _ => G::prime_modulus() - y.value,
+        };
+        Element::from_value(value).into()
+    })
+}
+
+/// Check that multiplication of `a`, `b` and `c` is associative and commutative, with the
+/// identity 1 and the inverses modulo p, and that exponents add: `a^x * a^y = a^(x + y)`.
+///
+/// # Errors
+/// Returns a [TestCaseError] naming the law that does not hold.
+pub fn check_group_laws<G: MODPGroup>(
+    a: &Element<G>,
+    b: &Element<G>,
+    c: &Element<G>,
+    x: &Scalar<G>,
+    y: &Scalar<G>,
+) -> Result<(), TestCaseError> {
+    let one = Element::<G>::from_value(BigUint::from(1u64));
+    prop_assert_eq!((a * b) * c, a * (b * c), "associativity");
+    prop_assert_eq!(a * b, b * a, "commutativity");
+    prop_assert_eq!(a * &one, Element::from_value(a.value.clone()), "identity");
+    let inverse = Element::<G>::from_value(
+        a.value
+            .modinv(&G::prime_modulus())
+            .expect("elements of [1, p) are invertible"),
+    );
+    prop_assert_eq!(a * &inverse, one, "inverse");
+    prop_assert_eq!(
+        a.pow(&x.value) * a.pow(&y.value),
+        a.pow(&(&x.value + &y.value)),
+        "exponents"
+    );
+    Ok(())
+}
+
+/// Check that `a` decodes from its big-endian, tagged and, in the subgroup, compressed
+/// encodings, and that the compressed encoding is refused outside of the subgroup.
+///
+/// # Errors
+/// Returns a [TestCaseError] naming the encoding that does not round-trip.
+pub fn check_element_round_trips<G: MODPGroup>(a: &Element<G>) -> Result<(), TestCaseError> {
+    prop_assert_eq!(&Element::<G>::from_bytes_be(&a.to_bytes_be()).unwrap(), a);
+    prop_assert_eq!(
+        &Element::<G>::from_tagged_bytes(&a.to_tagged_bytes()).unwrap(),
+        a
+    );
+    match a.validate() {
+        Ok(()) => {
+            let compressed = a.to_compressed_bytes().unwrap();
+            prop_assert_eq!(
+                &Element::<G>::from_compressed_bytes(&compressed).unwrap(),
+                a
+            );
+        }
+        Err(_) => prop_assert!(a.to_compressed_bytes().is_err()),
+    }
+    Ok(())
+}
+
+/// Check that `x` decodes from its big-endian and tagged encodings.
+///
+/// # Errors
+/// Returns a [TestCaseError] naming the encoding that does not round-trip.
+pub fn check_scalar_round_trips<G: MODPGroup>(x: &Scalar<G>) -> Result<(), TestCaseError> {
+    prop_assert_eq!(&Scalar::<G>::from_bytes(&x.to_bytes_be()).unwrap(), x);
+    prop_assert_eq!(
+        &Scalar::<G>::from_tagged_bytes(&x.to_tagged_bytes()).unwrap(),
+        x
+    );
+    Ok(())
+}
+
+/// Check that both parties of a key exchange, each with a key pair of [any_keypair], get the
+/// same shared secret from the public key of the other, decoded and validated.
+///
+/// # Errors
+/// Returns a [TestCaseError] if a public key does not validate or the secrets differ.
+pub fn check_key_exchange<G: MODPGroup>(
+    a: &(Scalar<G>, PublicKey<G>),
+    b: &(Scalar<G>, PublicKey<G>),
+) -> Result<(), TestCaseError> {
+    let receive = |public: &PublicKey<G>| -> Result<PublicKey<G>, TestCaseError> {
+        UnvalidatedPublicKey::<G>::from_bytes_be(&public.to_bytes_be())
+            .map_err(|e| TestCaseError::fail(e.to_string()))?
+            .validate()
+            .map_err(|e: ValidationError| TestCaseError::fail(e.to_string()))
+    };
+    let shared_a = receive(&b.1)?.as_element().pow(&a.0.value);
+    let shared_b = receive(&a.1)?.as_element().pow(&b.0.value);
+    prop_assert_eq!(shared_a, shared_b);
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use sha2::{Digest, Sha256};
+
+    use proptest::{prelude::ProptestConfig, prop_assert_ne, proptest};
+
+    use super::*;
+    use crate::MODPGroup5;
+
+    fn sha256_hex(bytes: &[u8]) -> String {
+        Sha256::digest(bytes)
+            .iter()
+            .map(|b| format!("{b:02x}"))
+            .collect()
+    }
+
+    type G = MODPGroup5;
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(8))]
+
+        #[test]
+        fn test_group_laws(
+            a in any_element::<G>(),
+            b in any_element::<G>(),
+            c in any_subgroup_element::<G>(),
+            x in any_scalar::<G>(),
+            y in any_scalar::<G>(),
+        ) {
+            check_group_laws(&a, &b, &c, &x, &y)?;
+            check_group_laws(&c, &a, &b, &y, &x)?;
+        }
+
+        #[test]
+        fn test_round_trips(a in any_element::<G>(), b in any_subgroup_element::<G>(), x in any_scalar::<G>()) {
+            check_element_round_trips(&a)?;
+            check_element_round_trips(&b)?;
+            check_scalar_round_trips(&x)?;
+        }
+
+        #[test]
+        fn test_key_exchange(a in any_keypair::<G>(), b in any_keypair::<G>()) {
+            prop_assert_ne!(&a.0.value, &BigUint::from(0u64));
+            check_key_exchange(&a, &b)?;
+        }
+
+        #[test]
+        fn test_degenerate_public_keys(key in degenerate_public_keys::<G>()) {
+            prop_assert!(key.validate().is_err());
+        }
+    }
+
+    #[test]
+    fn test_pinned_stream() {
+        assert_eq!(SeededRng::new(42).next_u64(), 9482535800248027256);
+    }
+
+    #[test]
+    fn test_pinned_scalar() {
+        let x = Scalar::<MODPGroup5>::random(&mut SeededRng::new(1));
+        assert_eq!(
+            sha256_hex(&x.to_bytes_be()),
+            "3767bb8da807b2a1e1b7c22436e027c35310a2e99fee2ab66f422418eb30a394"
+        );
+    }
+
+    // with the `parallel` feature, the search depends on the scheduling of the workers
+    #[cfg(all(feature = "primegroup", not(feature = "parallel")))]
+    #[test]
+    fn test_pinned_prime_group() {
+        use num_bigint::BigUint;
+
+        use crate::PrimeGroup;
+
+        let mut rng = SeededRng::new(7);
+        let group = PrimeGroup::generate(64, &mut rng, None).unwrap();
+        assert_eq!(group.p, BigUint::from(9653838219201254279u64));
+        assert_eq!(group.g, BigUint::from(3699283780049002599u64));
+
+        let (_, public) = group.generate_keypair(&mut rng);
+        assert_eq!(*public.value(), BigUint::from(3399089597490481835u64));
+
+        let group = PrimeGroup::new_from_rng::<MODPGroup5, _>(128, &mut SeededRng::new(7)).unwrap();
+        assert_eq!(
+            sha256_hex(&group.g.to_bytes_be()),
+            "beabd61118c510b3e45d5878ce38bee8e1915601165c6fb34d60703faecc6f1b"
+        );
+    }
+}
@@ -0,0 +1,38 @@
+use std::fmt::{self, Write};
+
+use num_bigint::BigUint;
+
+/// Write the parameters `p` and `g` in the layout of `openssl dhparam -text`.
+pub(crate) fn write_dhparam_text<W: Write>(w: &mut W, p: &BigUint, g: &BigUint) -> fmt::Result {
+    writeln!(w, "    DH Parameters: ({} bit)", p.bits())?;
+    write_bn(w, "P:   ", p, 4)?;
+    write_bn(w, "G:   ", g, 4)
+}
+
+/// Write a labelled number as OpenSSL's `ASN1_bn_print`: numbers of up to 8 bytes are written in
+/// decimal and hexadecimal on the label line, larger ones as colon separated bytes below the label,
+/// 15 bytes per line, with a leading zero byte if the most significant bit is set.
+fn write_bn<W: Write>(w: &mut W, label: &str, x: &BigUint, indent: usize) -> fmt::Result {
+    if x.bits() <= 64 {
+        return writeln!(w, "{:indent$}{label} {x} (0x{x:x})", "");
+    }
+    writeln!(w, "{:indent$}{label}", "")?;
+
+    let mut bytes = x.to_bytes_be();
+    if bytes[0] & 0x80 != 0 {
+        bytes.insert(0, 0);
+    }
+    for (i, line) in bytes.chunks(15).enumerate() {
+        let last_line = (i + 1) * 15 >= bytes.len();
+        let hex: Vec<String> = line.iter().map(|b| format!("{b:02x}")).collect();
+        writeln!(
+            w,
+            "{:width$}{}{}",
+            "",
+            hex.join(":"),
+            if last_line { "" } else { ":" },
+            width = indent + 4
+        )?;
+    }
+    Ok(())
+}
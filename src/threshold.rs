@@ -0,0 +1,279 @@
+//! Threshold ElGamal decryption: the secret key `x` is shared among participants so that any `t`
+//! of them can decrypt, e.g. by the [dkg](crate::dkg) or by a dealer.
+//!
+//! The participant `j` holding the share `x_j` of `x`, with the public verification key
+//! `g^x_j`, decrypts a [Ciphertext] `(c1, c2)` partially as `d_j = c1^x_j`, and proves that
+//! `log_g(g^x_j) = log_c1(d_j)` with a [DleqProof] ([PartialDecryption::create]). Any `t` partial
+//! decryptions are combined by Lagrange interpolation in the exponent,
+//! `c1^x = prod_j d_j^lambda_j`, giving the message `c2 / c1^x` ([combine]).
+//!
+//! # Example
+//!
+//! ```rust
+//! use sha2::Sha256;
+//! use diffie_hellman_groups::{elgamal, threshold::{self, PartialDecryption}, Element, Scalar, group::MODPGroup5};
+//!
+//! type G = MODPGroup5;
+//! let rng = &mut rand::thread_rng();
+//!
+//! // a dealer shares x with threshold 2: x_j = x + a * j
+//! let (x, a) = (Scalar::<G>::random(rng), Scalar::<G>::random(rng));
+//! let share = |j: u64| &x + &a * Scalar::from_biguint(j.into());
+//! let pk = Element::<G>::from_biguint(x.value.clone());
+//!
+//! let m = Element::<G>::from_biguint(42u64.into());
+//! let ct = elgamal::encrypt(&pk, &m, rng);
+//!
+//! let (d1, proof1) = PartialDecryption::create::<Sha256, _>(1, &share(1), &ct, rng);
+//! let (d3, proof3) = PartialDecryption::create::<Sha256, _>(3, &share(3), &ct, rng);
+//! let vk1 = Element::<G>::from_biguint(share(1).value);
+//! assert!(d1.verify::<Sha256>(&proof1, &vk1, &ct).is_ok());
+//!
+//! assert_eq!(threshold::combine(&[d1, d3], &ct, 2).unwrap(), m);
+//! ```
+
+use std::collections::BTreeSet;
+
+use num_bigint::BigUint;
+use rand_core::CryptoRngCore;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    elgamal::Ciphertext,
+    group::MODPGroup,
+    sigma::{self, DleqProof, Statement},
+    Element, NamedDigest, Scalar, ThresholdError,
+};
+
+/// The partial decryption `d_j = c1^x_j` of a ciphertext by the participant `j`.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(bound = ""))]
+pub struct PartialDecryption<G: MODPGroup> {
+    pub index: u32,
+    pub d: Element<G>,
+}
+
+impl<G: MODPGroup> PartialDecryption<G> {
+    /// Decrypt `ct` partially as the participant `index` with the share `share`, returning the
+    /// partial decryption and the proof of its correctness.
+    pub fn create<D: NamedDigest, R: CryptoRngCore + ?Sized>(
+        index: u32,
+        share: &Scalar<G>,
+        ct: &Ciphertext<G>,
+        rng: &mut R,
+    ) -> (Self, DleqProof<G>) {
+        let d = ct.c1.pow(&share.value);
+        let statement = statement(&Element::from_biguint(share.value.clone()), ct, &d);
+        let proof = sigma::prove::<G, D, R>(&statement, share, rng);
+        (PartialDecryption { index, d }, proof)
+    }
+
+    /// Verify the proof of the partial decryption of `ct` against the verification key `vk`,
+    /// i.e. `g^x_j`, of the participant.
+    ///
+    /// # Errors
+    /// Returns [ThresholdError::InvalidProof] if the proof does not verify.
+    pub fn verify<D: NamedDigest>(
+        &self,
+        proof: &DleqProof<G>,
+        vk: &Element<G>,
+        ct: &Ciphertext<G>,
+    ) -> Result<(), ThresholdError> {
+        if sigma::verify::<G, D>(&statement(vk, ct, &self.d), proof) {
+            Ok(())
+        } else {
+            Err(ThresholdError::InvalidProof(self.index))
+        }
+    }
+}
+
+/// Combine the first `threshold` partial decryptions of `ct` into the message. The partial
+/// decryptions should be verified beforehand with [PartialDecryption::verify].
+///
+/// # Errors
+/// Returns
+/// - [ThresholdError::NotEnoughShares] if less than `threshold` partial decryptions are given.
+/// - [ThresholdError::InvalidIndex] or [ThresholdError::DuplicateIndex] if an index of the
+///   combined partial decryptions is zero or repeated.
+pub fn combine<G: MODPGroup>(
+    partials: &[PartialDecryption<G>],
+    ct: &Ciphertext<G>,
+    threshold: usize,
+) -> Result<Element<G>, ThresholdError> {
+    if threshold == 0 || partials.len() < threshold {
+        return Err(ThresholdError::NotEnoughShares {
+            needed: threshold.max(1),
+            got: partials.len(),
+        });
+    }
+    let partials = &partials[..threshold];
+    let mut indices = BTreeSet::new();
+    for partial in partials {
+        if partial.index == 0 {
+            return Err(ThresholdError::InvalidIndex);
+        }
+        if !indices.insert(partial.index) {
+            return Err(ThresholdError::DuplicateIndex(partial.index));
+        }
+    }
+    let indices: Vec<u32> = indices.into_iter().collect();
+
+    let c1_x = partials
+        .iter()
+        .fold(Element::from_value(BigUint::from(1u64)), |acc, partial| {
+            acc * partial
+                .d
+                .pow(&lagrange_coefficient::<G>(&indices, partial.index).value)
+        });
+    let c1_x_inv = c1_x.pow(&(G::subgroup_order() - 1u64));
+    Ok(&ct.c2 * c1_x_inv)
+}
+
+/// The Lagrange coefficient `lambda_j = prod_{m != j} m / (m - j) mod q` of the index `j` for
+/// interpolating at 0 the polynomial through the points at `indices`, which must be distinct,
+/// non-zero, and include `j`.
+pub fn lagrange_coefficient<G: MODPGroup>(indices: &[u32], j: u32) -> Scalar<G> {
+    let scalar = |x: u32| Scalar::<G>::from_biguint(BigUint::from(x));
+    let (num, den) = indices
+        .iter()
+        .filter(|&&m| m != j)
+        .fold((scalar(1), scalar(1)), |(num, den), &m| {
+            (num * scalar(m), den * (scalar(m) - scalar(j)))
+        });
+    num * den.inverse().expect("indices are distinct")
+}
+
+/// The statement `log_g(vk) = log_c1(d)`.
+fn statement<G: MODPGroup>(vk: &Element<G>, ct: &Ciphertext<G>, d: &Element<G>) -> Statement<G> {
+    Statement::dleq(
+        Element::from_value(G::generator()),
+        Element::from_value(vk.value.clone()),
+        Element::from_value(ct.c1.value.clone()),
+        Element::from_value(d.value.clone()),
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use sha2::Sha256;
+
+    use super::*;
+    use crate::{
+        dkg::{Parameters, Participant},
+        elgamal,
+        group::MODPGroup5,
+    };
+
+    type G = MODPGroup5;
+
+    #[test]
+    fn test_threshold_decryption() {
+        let rng = &mut rand::thread_rng();
+
+        // 5 participants with threshold 3 from the DKG
+        let params = Parameters::new(5, 3).unwrap();
+        let (mut participants, mut broadcasts, mut shares) = (Vec::new(), Vec::new(), Vec::new());
+        for index in 1..=5 {
+            let (participant, broadcast, messages) =
+                Participant::<G>::new(index, params, rng).unwrap();
+            participants.push(participant);
+            broadcasts.push(broadcast);
+            shares.extend(messages);
+        }
+        for participant in participants.iter_mut() {
+            for broadcast in &broadcasts {
+                participant.receive_broadcast(broadcast.clone()).unwrap();
+            }
+            let index = participant.index();
+            for msg in shares.iter().filter(|m| m.recipient == index) {
+                participant.receive_share(msg.clone()).unwrap();
+            }
+        }
+        let outputs: Vec<_> = participants
+            .into_iter()
+            .map(|p| p.finish().unwrap())
+            .collect();
+
+        let m = Element::<G>::from_biguint(BigUint::from(1234u64));
+        let ct = elgamal::encrypt(&outputs[0].public_key, &m, rng);
+
+        let partials: Vec<_> = outputs
+            .iter()
+            .map(|output| {
+                let (partial, proof) =
+                    PartialDecryption::create::<Sha256, _>(output.index, &output.share, &ct, rng);
+                let vk = output.public_share(output.index);
+                assert!(partial.verify::<Sha256>(&proof, &vk, &ct).is_ok());
+                partial
+            })
+            .collect();
+
+        // exactly t shares, in any order
+        assert_eq!(combine(&partials[..3], &ct, 3).unwrap(), m);
+        let subset = [
+            partials[4].clone(),
+            partials[0].clone(),
+            partials[2].clone(),
+        ];
+        assert_eq!(combine(&subset, &ct, 3).unwrap(), m);
+
+        // t - 1 shares are not enough, and do not decrypt when used with a lower threshold
+        assert_eq!(
+            combine(&partials[..2], &ct, 3).unwrap_err(),
+            ThresholdError::NotEnoughShares { needed: 3, got: 2 }
+        );
+        assert_ne!(combine(&partials[..2], &ct, 2).unwrap(), m);
+
+        let duplicated = [
+            partials[0].clone(),
+            partials[1].clone(),
+            partials[0].clone(),
+        ];
+        assert_eq!(
+            combine(&duplicated, &ct, 3).unwrap_err(),
+            ThresholdError::DuplicateIndex(1)
+        );
+    }
+
+    #[test]
+    fn test_invalid_proof() {
+        let rng = &mut rand::thread_rng();
+        let x = Scalar::<G>::random(rng);
+        let pk = Element::<G>::from_biguint(x.value.clone());
+        let ct = elgamal::encrypt(&pk, &Element::from_biguint(BigUint::from(7u64)), rng);
+
+        let share = Scalar::<G>::random(rng);
+        let vk = Element::<G>::from_biguint(share.value.clone());
+        let (mut partial, proof) = PartialDecryption::create::<Sha256, _>(2, &share, &ct, rng);
+        assert!(partial.verify::<Sha256>(&proof, &vk, &ct).is_ok());
+
+        // a wrong partial decryption
+        partial.d = &partial.d * Element::from_biguint(BigUint::from(1u64));
+        assert_eq!(
+            partial.verify::<Sha256>(&proof, &vk, &ct),
+            Err(ThresholdError::InvalidProof(2))
+        );
+
+        // a correct partial decryption with the share of another participant
+        let other = Scalar::<G>::random(rng);
+        let (partial, proof) = PartialDecryption::create::<Sha256, _>(2, &other, &ct, rng);
+        assert_eq!(
+            partial.verify::<Sha256>(&proof, &vk, &ct),
+            Err(ThresholdError::InvalidProof(2))
+        );
+    }
+
+    #[test]
+    fn test_lagrange_coefficient() {
+        // f(z) = 5 + 3z: f(1) = 8, f(2) = 11
+        let l1 = lagrange_coefficient::<G>(&[1, 2], 1);
+        let l2 = lagrange_coefficient::<G>(&[1, 2], 2);
+        let eight = Scalar::<G>::from_biguint(BigUint::from(8u64));
+        let eleven = Scalar::<G>::from_biguint(BigUint::from(11u64));
+        assert_eq!(
+            l1 * eight + l2 * eleven,
+            Scalar::from_biguint(BigUint::from(5u64))
+        );
+    }
+}
@@ -0,0 +1,356 @@
+//! Diffie-Hellman structures of the TLS handshake up to TLS 1.2, as defined in
+//! [RFC5246](https://datatracker.ietf.org/doc/rfc5246/#section-7.4.3).
+//!
+//! [ServerDhParams] is the start of the body of a DHE ServerKeyExchange message, followed by its
+//! signature, and [ClientDhPublic] is the body of a ClientKeyExchange message. Integers are
+//! encoded as big-endian `opaque <1..2^16-1>` vectors, i.e. prefixed by their length as a
+//! `uint16`. The decoders are strict: an empty integer, or one with a leading zero byte, is
+//! rejected, so that a decoded message re-encodes to the same bytes.
+
+use num_bigint::BigUint;
+
+use crate::DhGroupsError;
+
+#[cfg(feature = "primegroup")]
+use crate::{GroupPolicy, PrimeGroup};
+
+/// ServerDHParams, sent by the server with the group and its public value `Ys = g^X mod p`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServerDhParams {
+    /// Prime modulus (dh_p).
+    pub p: BigUint,
+    /// Generator (dh_g).
+    pub g: BigUint,
+    /// Public value of the server (dh_Ys).
+    pub ys: BigUint,
+}
+
+/// ClientDiffieHellmanPublic, sent by the client with its public value `Yc = g^X mod p`.
+///
+/// Only the explicit encoding is supported, not the empty one used with fixed DH client
+/// certificates.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClientDhPublic {
+    /// Public value of the client (dh_Yc).
+    pub yc: BigUint,
+}
+
+impl ServerDhParams {
+    /// Create the parameters sent by the server of the group `group` with the public value `ys`.
+    #[cfg(feature = "primegroup")]
+    pub fn from_group(group: &PrimeGroup, ys: BigUint) -> Self {
+        Self {
+            p: group.p.clone(),
+            g: group.g.clone(),
+            ys,
+        }
+    }
+
+    /// Encode the parameters.
+    pub fn encode(&self) -> Vec<u8> {
+        let mut buf = Vec::new();
+        write_opaque(&mut buf, &self.p);
+        write_opaque(&mut buf, &self.g);
+        write_opaque(&mut buf, &self.ys);
+        buf
+    }
+
+    /// Decode the parameters, which must span all of `bytes`.
+    pub fn decode(bytes: &[u8]) -> Result<Self, DhGroupsError> {
+        let (params, rest) = Self::decode_prefix(bytes)?;
+        if !rest.is_empty() {
+            return Err(DhGroupsError::TrailingBytes);
+        }
+        Ok(params)
+    }
+
+    /// Decode the parameters from the start of a ServerKeyExchange body, returning them and the
+    /// remaining bytes, i.e. the signature of the parameters.
+    pub fn decode_prefix(bytes: &[u8]) -> Result<(Self, &[u8]), DhGroupsError> {
+        let (p, rest) = read_opaque(bytes)?;
+        let (g, rest) = read_opaque(rest)?;
+        let (ys, rest) = read_opaque(rest)?;
+        Ok((Self { p, g, ys }, rest))
+    }
+}
+
+impl ClientDhPublic {
+    /// Encode the public value.
+    pub fn encode(&self) -> Vec<u8> {
+        let mut buf = Vec::new();
+        write_opaque(&mut buf, &self.yc);
+        buf
+    }
+
+    /// Decode the public value, which must span all of `bytes`.
+    pub fn decode(bytes: &[u8]) -> Result<Self, DhGroupsError> {
+        let (yc, rest) = read_opaque(bytes)?;
+        if !rest.is_empty() {
+            return Err(DhGroupsError::TrailingBytes);
+        }
+        Ok(Self { yc })
+    }
+}
+
+/// Validate the parameters received from a server, returning the group they describe.
+///
+/// The modulus must have at least `min_bits` bits and be a safe prime, with a generator of order
+/// `q = (p - 1) / 2`; the primality tests are skipped for the safe prime groups of RFC 3526 and
+/// RFC 7919 ([AnyModpGroup::is_safe_prime](crate::AnyModpGroup::is_safe_prime)). The public value
+/// of the server must pass [PrimeGroup::validate_public_key]. Groups whose modulus is not a safe
+/// prime, like those of RFC 5114, are rejected.
+///
+/// # Errors
+/// Returns
+/// - [DhGroupsError::BitsOutOfRange] if the modulus has less than `min_bits` bits.
+/// - [DhGroupsError::ModulusTooLarge] if the modulus exceeds the limit of the default
+///   [GroupPolicy], see [validate_peer_parameters_with_policy].
+/// - [DhGroupsError::NotPrime], [DhGroupsError::InvalidCofactor] or
+///   [DhGroupsError::InvalidGenerator] if the group is not valid, see [PrimeGroup::validate].
+/// - [DhGroupsError::Validation] if the public value is not valid.
+///
+/// # Example
+///
+/// ```rust
+/// use diffie_hellman_groups::{DhGroupsError, PrimeGroup, MODPGroup14, tls};
+///
+/// let group = PrimeGroup::new::<MODPGroup14>(64).unwrap();
+/// let ys = group.public_key(&42u64.into()).unwrap();
+/// let params = tls::ServerDhParams::from_group(&group, ys);
+/// let bytes = params.encode();
+///
+/// let decoded = tls::ServerDhParams::decode(&bytes).unwrap();
+/// assert_eq!(tls::validate_peer_parameters(&decoded, 2048).unwrap(), group);
+/// assert_eq!(
+///     tls::validate_peer_parameters(&decoded, 3072),
+///     Err(DhGroupsError::BitsOutOfRange)
+/// );
+/// ```
+#[cfg(feature = "primegroup")]
+pub fn validate_peer_parameters(
+    params: &ServerDhParams,
+    min_bits: u64,
+) -> Result<PrimeGroup, DhGroupsError> {
+    validate_peer_parameters_with_policy(params, min_bits, &GroupPolicy::default())
+}
+
+/// Validate the parameters received from a server like [validate_peer_parameters], with the
+/// limits of `policy` on the modulus.
+#[cfg(feature = "primegroup")]
+pub fn validate_peer_parameters_with_policy(
+    params: &ServerDhParams,
+    min_bits: u64,
+    policy: &GroupPolicy,
+) -> Result<PrimeGroup, DhGroupsError> {
+    if params.p.bits() < min_bits {
+        return Err(DhGroupsError::BitsOutOfRange);
+    }
+    policy.check_modulus(&params.p)?;
+    let two = BigUint::from(2u64);
+    if params.p <= two {
+        return Err(DhGroupsError::NotPrime);
+    }
+    let group = PrimeGroup {
+        q: (&params.p - 1u64) / &two,
+        p: params.p.clone(),
+        g: params.g.clone(),
+        cofactor: two,
+    };
+    if group.identify().is_some_and(|id| id.is_safe_prime()) {
+        group.verify()?;
+    } else {
+        group.validate_with_policy(policy)?;
+    }
+    group.validate_public_key(&params.ys)?;
+    Ok(group)
+}
+
+/// Append `x` as an `opaque <1..2^16-1>` vector of its minimal big-endian encoding.
+///
+/// # Panics
+/// Panics if the encoding is longer than `2^16 - 1` bytes.
+fn write_opaque(buf: &mut Vec<u8>, x: &BigUint) {
+    let bytes = x.to_bytes_be();
+    let len = u16::try_from(bytes.len()).expect("integer too large for a TLS vector");
+    buf.extend_from_slice(&len.to_be_bytes());
+    buf.extend_from_slice(&bytes);
+}
+
+/// Read an `opaque <1..2^16-1>` vector holding a minimally encoded integer from the start of
+/// `bytes`, returning the value and the remaining bytes.
+fn read_opaque(bytes: &[u8]) -> Result<(BigUint, &[u8]), DhGroupsError> {
+    if bytes.len() < 2 {
+        return Err(DhGroupsError::UnexpectedEof);
+    }
+    let (head, rest) = bytes.split_at(2);
+    let len = u16::from_be_bytes([head[0], head[1]]) as usize;
+    if rest.len() < len {
+        return Err(DhGroupsError::UnexpectedEof);
+    }
+    let (data, rest) = rest.split_at(len);
+    match data {
+        [] | [0, ..] => Err(DhGroupsError::InvalidOpaque),
+        _ => Ok((BigUint::from_bytes_be(data), rest)),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::MODPGroup;
+
+    /// A ServerKeyExchange handshake message captured from an OpenSSL server using the
+    /// MODP group 14, with a DHE-RSA cipher suite, by tests/fixtures/group14_key_exchange.sh.
+    const SERVER_KEY_EXCHANGE: &[u8] =
+        include_bytes!("../tests/fixtures/group14_server_key_exchange.bin");
+    /// The ClientKeyExchange handshake message of the same connection.
+    const CLIENT_KEY_EXCHANGE: &[u8] =
+        include_bytes!("../tests/fixtures/group14_client_key_exchange.bin");
+
+    /// Strip the handshake header: the message type and a `uint24` length.
+    fn handshake_body(msg: &[u8], msg_type: u8) -> &[u8] {
+        assert_eq!(msg[0], msg_type);
+        let len = u32::from_be_bytes([0, msg[1], msg[2], msg[3]]) as usize;
+        assert_eq!(msg.len(), 4 + len);
+        &msg[4..]
+    }
+
+    #[test]
+    fn test_server_key_exchange_fixture() {
+        let body = handshake_body(SERVER_KEY_EXCHANGE, 12);
+        let (params, signature) = ServerDhParams::decode_prefix(body).unwrap();
+        assert_eq!(params.p, crate::MODPGroup14::prime_modulus());
+        assert_eq!(params.g, BigUint::from(2u64));
+        // SignatureAndHashAlgorithm followed by a 2048-bit RSA signature
+        assert_eq!(signature.len(), 2 + 2 + 256);
+
+        let mut encoded = params.encode();
+        encoded.extend_from_slice(signature);
+        assert_eq!(encoded, body);
+        assert_eq!(
+            ServerDhParams::decode(body),
+            Err(DhGroupsError::TrailingBytes)
+        );
+        assert_eq!(
+            ServerDhParams::decode(&body[..body.len() - signature.len()]).unwrap(),
+            params
+        );
+    }
+
+    #[test]
+    fn test_client_key_exchange_fixture() {
+        let body = handshake_body(CLIENT_KEY_EXCHANGE, 16);
+        let public = ClientDhPublic::decode(body).unwrap();
+        assert_eq!(public.encode(), body);
+        assert!(public.yc < crate::MODPGroup14::prime_modulus());
+        assert_eq!(
+            ClientDhPublic::decode(&body[..body.len() - 1]),
+            Err(DhGroupsError::UnexpectedEof)
+        );
+    }
+
+    #[test]
+    fn test_strict_integers() {
+        // empty
+        assert_eq!(
+            ClientDhPublic::decode(&[0, 0]),
+            Err(DhGroupsError::InvalidOpaque)
+        );
+        // leading zero
+        assert_eq!(
+            ClientDhPublic::decode(&[0, 2, 0, 1]),
+            Err(DhGroupsError::InvalidOpaque)
+        );
+        assert_eq!(
+            ClientDhPublic::decode(&[0, 2, 1, 0]).unwrap().yc,
+            BigUint::from(256u64)
+        );
+        // truncated length
+        assert_eq!(
+            ClientDhPublic::decode(&[0]),
+            Err(DhGroupsError::UnexpectedEof)
+        );
+        assert_eq!(
+            ServerDhParams::decode(&[0, 1, 23, 0, 1, 5]),
+            Err(DhGroupsError::UnexpectedEof)
+        );
+    }
+
+    #[cfg(feature = "primegroup")]
+    #[test]
+    fn test_validate_peer_parameters() {
+        use crate::{MODPGroup22, MODPGroup23, MODPGroup24, ValidationError};
+
+        let body = handshake_body(SERVER_KEY_EXCHANGE, 12);
+        let (params, _) = ServerDhParams::decode_prefix(body).unwrap();
+        let group = validate_peer_parameters(&params, 2048).unwrap();
+        assert_eq!(group.q, crate::MODPGroup14::sophie_garmain_prime());
+
+        let mut bad = params.clone();
+        bad.ys = &params.p - 1u64;
+        assert_eq!(
+            validate_peer_parameters(&bad, 2048),
+            Err(DhGroupsError::Validation(ValidationError::Degenerate))
+        );
+
+        // a small safe prime goes through the primality tests
+        let small = ServerDhParams {
+            p: BigUint::from(1623299u64),
+            g: BigUint::from(4u64),
+            ys: BigUint::from(16u64),
+        };
+        assert!(validate_peer_parameters(&small, 16).is_ok());
+        let not_safe = ServerDhParams {
+            p: BigUint::from(1623301u64),
+            ..small.clone()
+        };
+        assert_eq!(
+            validate_peer_parameters(&not_safe, 16),
+            Err(DhGroupsError::NotPrime)
+        );
+        let generator = ServerDhParams {
+            g: &small.p - 1u64,
+            ..small
+        };
+        assert_eq!(
+            validate_peer_parameters(&generator, 16),
+            Err(DhGroupsError::InvalidGenerator)
+        );
+
+        // the moduli of RFC 5114 are identified, but are not safe primes
+        for (p, g) in [
+            (MODPGroup22::prime_modulus(), MODPGroup22::generator()),
+            (MODPGroup23::prime_modulus(), MODPGroup23::generator()),
+            (MODPGroup24::prime_modulus(), MODPGroup24::generator()),
+        ] {
+            let ys = g.modpow(&BigUint::from(12345u64), &p);
+            assert_eq!(
+                validate_peer_parameters(&ServerDhParams { p, g, ys }, 1024),
+                Err(DhGroupsError::NotPrime)
+            );
+        }
+
+        let policy = GroupPolicy {
+            max_modulus_bits: 1024,
+        };
+        assert_eq!(
+            validate_peer_parameters_with_policy(&params, 1024, &policy),
+            Err(DhGroupsError::ModulusTooLarge {
+                bits: 2048,
+                max: 1024
+            })
+        );
+        // rejected before the primality tests, which would not finish
+        let huge = ServerDhParams {
+            p: (BigUint::from(1u64) << 100_000u32) - 1u64,
+            ..params
+        };
+        assert_eq!(
+            validate_peer_parameters(&huge, 2048),
+            Err(DhGroupsError::ModulusTooLarge {
+                bits: 100_000,
+                max: crate::primegroup::DEFAULT_MAX_MODULUS_BITS
+            })
+        );
+    }
+}
@@ -0,0 +1,182 @@
+//! Conversions between [Element]s and [Scalar]s and the fixed-size integers of
+//! [crypto-bigint](https://crates.io/crates/crypto-bigint), e.g. to pass a shared secret to
+//! constant-time code, enabled by the `crypto-bigint` feature.
+//!
+//! Each group converts to the integer type of the size of its modulus rounded up to whole bytes:
+//! `U1536` for [MODPGroup5](crate::group::MODPGroup5), `U2048` for
+//! [MODPGroup14](crate::group::MODPGroup14), up to `U8192` for
+//! [MODPGroup18](crate::group::MODPGroup18). Scalars use the same type as the elements of their
+//! group. The conversions go through the fixed-length encoding of [Element::to_bytes_be], so the
+//! bytes of the integer are exactly those of the element.
+//!
+//! # Example
+//!
+//! ```rust
+//! use crypto_bigint::{U2048, U4096};
+//! use diffie_hellman_groups::{group::MODPGroup14, DhGroupsError, Element};
+//! use num_bigint::BigUint;
+//!
+//! let element = Element::<MODPGroup14>::from_biguint(BigUint::from(42u64));
+//! let uint = element.to_uint::<U2048>().unwrap();
+//! assert_eq!(Element::from_uint(&uint).unwrap(), element);
+//!
+//! assert_eq!(
+//!     element.to_uint::<U4096>(),
+//!     Err(DhGroupsError::UintSizeMismatch { expected: 2048, found: 4096 })
+//! );
+//! ```
+
+use crypto_bigint::{Encoding, Zero};
+use num_bigint::BigUint;
+
+use crate::{group::MODPGroup, DhGroupsError, Element, Scalar, ValidationError};
+
+impl<G: MODPGroup> Element<G> {
+    /// Convert the element to the integer type `U` of the size of p.
+    ///
+    /// # Errors
+    /// Returns [DhGroupsError::UintSizeMismatch] if `U` is not the size of p rounded up to whole
+    /// bytes.
+    pub fn to_uint<U: Encoding + Zero>(&self) -> Result<U, DhGroupsError> {
+        to_uint::<G, U>(&self.value)
+    }
+
+    /// Create an element from an integer of the size of p.
+    ///
+    /// # Errors
+    /// Returns [DhGroupsError::UintSizeMismatch] if `U` is not the size of p rounded up to whole
+    /// bytes, or [DhGroupsError::Validation] if the value is not less than p.
+    pub fn from_uint<U: Encoding>(value: &U) -> Result<Self, DhGroupsError> {
+        Element::from_bytes_be(check_size::<G, U>(value.to_be_bytes())?.as_ref())
+    }
+}
+
+impl<G: MODPGroup> Scalar<G> {
+    /// Convert the scalar to the integer type `U` of the size of p.
+    ///
+    /// # Errors
+    /// Returns [DhGroupsError::UintSizeMismatch] if `U` is not the size of p rounded up to whole
+    /// bytes.
+    pub fn to_uint<U: Encoding + Zero>(&self) -> Result<U, DhGroupsError> {
+        to_uint::<G, U>(&self.value)
+    }
+
+    /// Create a scalar from an integer of the size of p.
+    ///
+    /// # Errors
+    /// Returns [DhGroupsError::UintSizeMismatch] if `U` is not the size of p rounded up to whole
+    /// bytes, or [DhGroupsError::Validation] if the value is not less than q.
+    pub fn from_uint<U: Encoding>(value: &U) -> Result<Self, DhGroupsError> {
+        let repr = check_size::<G, U>(value.to_be_bytes())?;
+        let value = BigUint::from_bytes_be(repr.as_ref());
+        if value >= G::subgroup_order() {
+            return Err(ValidationError::OutOfRange.into());
+        }
+        Ok(Scalar::from_biguint(value))
+    }
+}
+
+/// Write `value`, less than p, into the big-endian bytes of `U`.
+fn to_uint<G: MODPGroup, U: Encoding + Zero>(value: &BigUint) -> Result<U, DhGroupsError> {
+    let mut repr = check_size::<G, U>(U::ZERO.to_be_bytes())?;
+    let bytes = value.to_bytes_be();
+    let out = repr.as_mut();
+    let offset = out.len() - bytes.len();
+    out[offset..].copy_from_slice(&bytes);
+    Ok(U::from_be_bytes(repr))
+}
+
+/// Check that `repr` has the length of p.
+fn check_size<G: MODPGroup, U: Encoding>(repr: U::Repr) -> Result<U::Repr, DhGroupsError> {
+    let expected = G::prime_modulus().bits().div_ceil(8) as usize;
+    let found = repr.as_ref().len();
+    if found != expected {
+        return Err(DhGroupsError::UintSizeMismatch {
+            expected: expected * 8,
+            found: found * 8,
+        });
+    }
+    Ok(repr)
+}
+
+#[cfg(test)]
+mod test {
+    use crypto_bigint::{U1024, U1536, U2048, U3072, U4096, U6144, U8192};
+
+    use super::*;
+    use crate::group::{
+        MODPGroup14, MODPGroup15, MODPGroup16, MODPGroup17, MODPGroup18, MODPGroup5,
+    };
+
+    fn test_round_trip<G: MODPGroup, U: Encoding + Zero>() {
+        let rng = &mut rand::thread_rng();
+        let p = G::prime_modulus();
+        // a random element, a value with leading zero bytes, and p - 1
+        for value in [
+            Element::<G>::from_biguint(Scalar::<G>::random(rng).value).value,
+            BigUint::from(0x0100u64),
+            &p - 1u64,
+        ] {
+            let element = Element::<G>::from_value(value);
+            let uint = element.to_uint::<U>().unwrap();
+            assert_eq!(uint.to_be_bytes().as_ref(), element.to_bytes_be());
+            assert_eq!(Element::<G>::from_uint(&uint).unwrap(), element);
+        }
+
+        let scalar = Scalar::<G>::random(rng);
+        let uint = scalar.to_uint::<U>().unwrap();
+        assert_eq!(
+            BigUint::from_bytes_be(uint.to_be_bytes().as_ref()),
+            scalar.value
+        );
+        assert_eq!(Scalar::<G>::from_uint(&uint).unwrap(), scalar);
+
+        // p is not an element, and q not a scalar
+        let mut repr = U::ZERO.to_be_bytes();
+        repr.as_mut().copy_from_slice(&p.to_bytes_be());
+        let uint_p = U::from_be_bytes(repr);
+        assert_eq!(
+            Element::<G>::from_uint(&uint_p),
+            Err(ValidationError::OutOfRange.into())
+        );
+        let q = Element::<G>::from_value(G::subgroup_order())
+            .to_uint::<U>()
+            .unwrap();
+        assert_eq!(
+            Scalar::<G>::from_uint(&q),
+            Err(ValidationError::OutOfRange.into())
+        );
+    }
+
+    #[test]
+    fn test_exact_size() {
+        test_round_trip::<MODPGroup5, U1536>();
+        test_round_trip::<MODPGroup14, U2048>();
+        test_round_trip::<MODPGroup15, U3072>();
+        test_round_trip::<MODPGroup16, U4096>();
+        test_round_trip::<MODPGroup17, U6144>();
+        test_round_trip::<MODPGroup18, U8192>();
+    }
+
+    #[test]
+    fn test_size_mismatch() {
+        let element = Element::<MODPGroup14>::from_biguint(BigUint::from(2u64));
+        let mismatch = |found| {
+            Err::<(), _>(DhGroupsError::UintSizeMismatch {
+                expected: 2048,
+                found,
+            })
+        };
+        // too small, and too large
+        assert_eq!(element.to_uint::<U1024>().map(|_| ()), mismatch(1024));
+        assert_eq!(element.to_uint::<U4096>().map(|_| ()), mismatch(4096));
+        assert_eq!(
+            Element::<MODPGroup14>::from_uint(&U1024::ONE).map(|_| ()),
+            mismatch(1024)
+        );
+        assert_eq!(
+            Scalar::<MODPGroup14>::from_uint(&U4096::ONE).map(|_| ()),
+            mismatch(4096)
+        );
+    }
+}
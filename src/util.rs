@@ -0,0 +1,154 @@
+//! Parsing of numbers as laid out in RFCs, e.g. the primes of RFC 3526, so that they can be
+//! pasted as they are instead of stripping the line breaks and spaces by hand.
+//!
+//! # Example
+//!
+//! ```rust
+//! use diffie_hellman_groups::{group::MODPGroup, util::from_rfc_hex, MODPGroup5};
+//!
+//! let p = from_rfc_hex(
+//!     "
+//!       FFFFFFFF FFFFFFFF C90FDAA2 2168C234 C4C6628B 80DC1CD1
+//!       29024E08 8A67CC74 020BBEA6 3B139B22 514A0879 8E3404DD
+//!       EF9519B3 CD3A431B 302B0A6D F25F1437 4FE1356D 6D51C245
+//!       E485B576 625E7EC6 F44C42E9 A637ED6B 0BFF5CB6 F406B7ED
+//!       EE386BFB 5A899FA5 AE9F2411 7C4B1FE6 49286651 ECE45B3D
+//!       C2007CB8 A163BF05 98DA4836 1C55D39A 69163FA8 FD24CF5F
+//!       83655D23 DCA3AD96 1C62F356 208552BB 9ED52907 7096966D
+//!       670C354E 4ABC9804 F1746C08 CA237327 FFFFFFFF FFFFFFFF
+//!     ",
+//! )
+//! .unwrap();
+//! assert_eq!(p, MODPGroup5::prime_modulus());
+//! ```
+
+use num_bigint::BigUint;
+
+use crate::ParseError;
+
+/// Parse a hexadecimal number, in upper or lower case, ignoring ASCII whitespace. Both continuous
+/// digits and the grouped layout `FFFFFFFF FFFFFFFF ...` of RFCs are accepted, over any number of
+/// lines.
+///
+/// # Errors
+/// Returns [ParseError::Empty] if there are no digits, or [ParseError::InvalidDigit] at the first
+/// character that is neither a hexadecimal digit nor ASCII whitespace.
+pub fn from_rfc_hex(text: &str) -> Result<BigUint, ParseError> {
+    parse_digits(text, 16)
+}
+
+/// Parse a decimal number, ignoring ASCII whitespace, as in the decimal renderings of primes in
+/// some RFCs.
+///
+/// # Errors
+/// Returns [ParseError::Empty] if there are no digits, or [ParseError::InvalidDigit] at the first
+/// character that is neither a decimal digit nor ASCII whitespace.
+pub fn from_rfc_decimal(text: &str) -> Result<BigUint, ParseError> {
+    parse_digits(text, 10)
+}
+
+fn parse_digits(text: &str, radix: u32) -> Result<BigUint, ParseError> {
+    let mut digits = Vec::with_capacity(text.len());
+    for (position, found) in text.char_indices() {
+        if found.is_ascii_whitespace() {
+            continue;
+        }
+        let digit = found
+            .to_digit(radix)
+            .ok_or(ParseError::InvalidDigit { position, found })?;
+        digits.push(digit as u8);
+    }
+    if digits.is_empty() {
+        return Err(ParseError::Empty);
+    }
+    Ok(BigUint::from_radix_be(&digits, radix).expect("digits are less than the radix"))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{MODPGroup, MODPGroup14, MODPGroup5};
+
+    /// The block of section 3 of RFC 3526, pasted verbatim with its indentation.
+    const RFC_3526_GROUP_14: &str = r"
+      FFFFFFFF FFFFFFFF C90FDAA2 2168C234 C4C6628B 80DC1CD1
+      29024E08 8A67CC74 020BBEA6 3B139B22 514A0879 8E3404DD
+      EF9519B3 CD3A431B 302B0A6D F25F1437 4FE1356D 6D51C245
+      E485B576 625E7EC6 F44C42E9 A637ED6B 0BFF5CB6 F406B7ED
+      EE386BFB 5A899FA5 AE9F2411 7C4B1FE6 49286651 ECE45B3D
+      C2007CB8 A163BF05 98DA4836 1C55D39A 69163FA8 FD24CF5F
+      83655D23 DCA3AD96 1C62F356 208552BB 9ED52907 7096966D
+      670C354E 4ABC9804 F1746C08 CA18217C 32905E46 2E36CE3B
+      E39E772C 180E8603 9B2783A2 EC07A28F B5C55DF0 6F4C52C9
+      DE2BCBF6 95581718 3995497C EA956AE5 15D22618 98FA0510
+      15728E5A 8AACAA68 FFFFFFFF FFFFFFFF
+";
+
+    #[test]
+    fn test_rfc_3526_group_14() {
+        let p = MODPGroup14::prime_modulus();
+        assert_eq!(from_rfc_hex(RFC_3526_GROUP_14).unwrap(), p);
+        // continuous digits, in lower case, and with tabs and CRLF line breaks
+        let continuous: String = RFC_3526_GROUP_14.split_whitespace().collect();
+        assert_eq!(from_rfc_hex(&continuous).unwrap(), p);
+        assert_eq!(from_rfc_hex(&continuous.to_lowercase()).unwrap(), p);
+        let crlf = RFC_3526_GROUP_14.replace('\n', "\r\n").replace("  ", "\t");
+        assert_eq!(from_rfc_hex(&crlf).unwrap(), p);
+    }
+
+    #[test]
+    fn test_decimal() {
+        let p = from_rfc_decimal(
+            "24103124269210325885520760221975660748569505485024
+             59942654116941958108831682612228890093858261341614
+             67322714147790401219650364895705058263194273070680
+             50092230627347453410734066962460145893616597740410
+             27169249453200378729434170325843778659198143763193
+             77685986952408894019557734611984354530154704374720
+             77499697637500843089263392955599688824578724129938
+             10129130294592999947926365264059284647209730384947
+             21168143446471443848852094012745984428885933652689
+             6320919633919",
+        );
+        assert_eq!(p.unwrap(), MODPGroup5::prime_modulus());
+        assert_eq!(
+            from_rfc_decimal("1 000 000"),
+            Ok(BigUint::from(1_000_000u64))
+        );
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert_eq!(from_rfc_hex(""), Err(ParseError::Empty));
+        assert_eq!(from_rfc_hex(" \n\t"), Err(ParseError::Empty));
+        assert_eq!(
+            from_rfc_hex("FFFF FFFG"),
+            Err(ParseError::InvalidDigit {
+                position: 8,
+                found: 'G'
+            })
+        );
+        assert_eq!(
+            from_rfc_hex("0xFF"),
+            Err(ParseError::InvalidDigit {
+                position: 1,
+                found: 'x'
+            })
+        );
+        assert_eq!(
+            from_rfc_decimal("12 AB"),
+            Err(ParseError::InvalidDigit {
+                position: 3,
+                found: 'A'
+            })
+        );
+        // non-ASCII whitespace is not ignored
+        assert_eq!(
+            from_rfc_decimal("1\u{a0}2"),
+            Err(ParseError::InvalidDigit {
+                position: 1,
+                found: '\u{a0}'
+            })
+        );
+    }
+}
@@ -0,0 +1,94 @@
+//! [wasm-bindgen](https://crates.io/crates/wasm-bindgen) wrappers of the key exchange over the
+//! MODP groups, to run it in browsers and Node.js, enabled by the `wasm` feature.
+//!
+//! Groups are identified by their number in RFC 3526, e.g. 14. Public keys and shared secrets are
+//! `Uint8Array`s of the big-endian bytes of the value, left-padded with zeros to the length of the
+//! modulus. On `wasm32-unknown-unknown`, the randomness comes from the Web Crypto API through the
+//! `js` feature of `getrandom`.
+//!
+//! ```js
+//! import { KeyPair } from "diffie-hellman-groups";
+//!
+//! const alice = new KeyPair(14);
+//! const bob = new KeyPair(14);
+//! const secret = alice.sharedSecret(bob.publicKey());
+//! ```
+//!
+//! The tests in `tests/wasm.rs` run with `wasm-pack test --node --features wasm`.
+
+use num_bigint::BigUint;
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    group::{with_any_group, MODPGroup},
+    AnyModpGroup, DhGroupsError, Element, Scalar, UnvalidatedPublicKey,
+};
+
+/// A key pair of a MODP group.
+#[wasm_bindgen]
+pub struct KeyPair {
+    id: AnyModpGroup,
+    secret: BigUint,
+    public: Vec<u8>,
+}
+
+#[wasm_bindgen]
+impl KeyPair {
+    /// Generate a key pair in the group numbered `group_id` in RFC 3526.
+    #[wasm_bindgen(constructor)]
+    pub fn new(group_id: u32) -> Result<KeyPair, JsError> {
+        let id = AnyModpGroup::from_rfc3526_number(group_id)
+            .ok_or_else(|| JsError::new(&format!("unknown MODP group {group_id}")))?;
+        let (secret, public) = with_any_group!(id, G => generate_keypair::<G>());
+        Ok(KeyPair { id, secret, public })
+    }
+
+    /// The number of the group in RFC 3526.
+    #[wasm_bindgen(getter, js_name = groupId)]
+    pub fn group_id(&self) -> u32 {
+        u32::from(self.id.group_id())
+    }
+
+    /// The public key, in big-endian bytes of the length of the modulus.
+    #[wasm_bindgen(js_name = publicKey)]
+    pub fn public_key(&self) -> Vec<u8> {
+        self.public.clone()
+    }
+
+    /// Validate the public key `peer` of the same group and compute the shared secret, in
+    /// big-endian bytes of the length of the modulus.
+    #[wasm_bindgen(js_name = sharedSecret)]
+    pub fn shared_secret(&self, peer: &[u8]) -> Result<Vec<u8>, JsError> {
+        with_any_group!(self.id, G => shared_secret::<G>(&self.secret, peer)).map_err(JsError::from)
+    }
+}
+
+fn generate_keypair<G: MODPGroup>() -> (BigUint, Vec<u8>) {
+    let x = Scalar::<G>::random(&mut rand::thread_rng());
+    let y = Element::<G>::from_biguint(x.value.clone()).to_bytes_be();
+    (x.value, y)
+}
+
+fn shared_secret<G: MODPGroup>(secret: &BigUint, peer: &[u8]) -> Result<Vec<u8>, DhGroupsError> {
+    let peer = UnvalidatedPublicKey::<G>::from_bytes_be(peer)?.validate()?;
+    Ok(peer.as_element().pow(secret).to_bytes_be())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_key_exchange() {
+        let alice = KeyPair::new(5).unwrap_or_else(|_| unreachable!());
+        let bob = KeyPair::new(5).unwrap_or_else(|_| unreachable!());
+        assert_eq!(alice.group_id(), 5);
+        assert_eq!(alice.public_key().len(), 192);
+        let (a, b) = (
+            alice.shared_secret(&bob.public_key()),
+            bob.shared_secret(&alice.public_key()),
+        );
+        assert!(a.is_ok() && b.is_ok());
+        assert_eq!(a.ok(), b.ok());
+    }
+}
@@ -0,0 +1,212 @@
+//! Versioned header of the binary encodings specific to this crate, so that peers running
+//! different versions of the crate reject the encodings they do not understand instead of
+//! misreading them.
+//!
+//! The tagged encodings of elements and scalars ([Element::to_tagged_bytes],
+//! [Scalar::to_tagged_bytes]), the messages of [session](crate::session) and the proofs of
+//! [sigma](crate::sigma) start with a [WireVersion] byte:
+//! - [WireVersion::V1]: the version byte, followed by the body,
+//! - [WireVersion::V2]: the version byte and the number of the group in RFC 3526 (0 for other
+//!   groups), followed by the body. Decoding in another group fails with
+//!   [DhGroupsError::GroupMismatch]. The confirm messages of [session](crate::session), which
+//!   hold no group values, have no group number.
+//!
+//! Encoding writes [WireVersion::CURRENT]. Decoding accepts the current and the previous version,
+//! and fails with [DhGroupsError::UnsupportedVersion] otherwise. The body is the same in both
+//! versions. The fixed-length encodings without header, e.g. [Element::to_bytes_be], and the
+//! encodings of other standards, e.g. [ssh](crate::ssh), are not versioned.
+//!
+//! # Example
+//!
+//! ```rust
+//! use diffie_hellman_groups::{group::{MODPGroup14, MODPGroup5}, wire::WireVersion, Element};
+//! use num_bigint::BigUint;
+//!
+//! let element = Element::<MODPGroup5>::from_biguint(BigUint::from(42u64));
+//! let bytes = element.to_tagged_bytes();
+//! assert_eq!(bytes[..2], [WireVersion::CURRENT as u8, 5]);
+//! assert_eq!(Element::from_tagged_bytes(&bytes).unwrap(), element);
+//! assert!(Element::<MODPGroup14>::from_tagged_bytes(&bytes).is_err());
+//! ```
+
+use crate::{group::MODPGroup, AnyModpGroup, DhGroupsError, Element, Scalar};
+
+/// Version of the encodings of this crate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum WireVersion {
+    /// The version byte, followed by the body.
+    V1 = 1,
+    /// The version byte and the group number, followed by the body.
+    V2 = 2,
+}
+
+impl WireVersion {
+    /// The version written by the encoders.
+    pub const CURRENT: WireVersion = WireVersion::V2;
+
+    /// Parse a version byte, accepting the current and the previous version.
+    ///
+    /// # Errors
+    /// Returns [DhGroupsError::UnsupportedVersion] for any other byte.
+    pub fn from_byte(byte: u8) -> Result<Self, DhGroupsError> {
+        match byte {
+            1 => Ok(WireVersion::V1),
+            2 => Ok(WireVersion::V2),
+            found => Err(DhGroupsError::UnsupportedVersion(found)),
+        }
+    }
+}
+
+impl<G: MODPGroup> Element<G> {
+    /// Encode the element as [Element::to_bytes_be] after the header of the current version.
+    pub fn to_tagged_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        write_header::<G>(&mut bytes);
+        bytes.extend_from_slice(&self.to_bytes_be());
+        bytes
+    }
+
+    /// Decode an element written by [Element::to_tagged_bytes], in the current or the previous
+    /// version.
+    ///
+    /// # Errors
+    /// Returns [DhGroupsError::UnsupportedVersion] if the version is not supported,
+    /// [DhGroupsError::GroupMismatch] if the group number is not that of `G`, or the errors of
+    /// [Element::from_bytes_be].
+    pub fn from_tagged_bytes(bytes: &[u8]) -> Result<Self, DhGroupsError> {
+        Element::from_bytes_be(read_header::<G>(bytes)?)
+    }
+}
+
+impl<G: MODPGroup> Scalar<G> {
+    /// Encode the scalar as [Scalar::to_bytes_be] after the header of the current version.
+    pub fn to_tagged_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        write_header::<G>(&mut bytes);
+        bytes.extend_from_slice(&self.to_bytes_be());
+        bytes
+    }
+
+    /// Decode a scalar written by [Scalar::to_tagged_bytes], in the current or the previous
+    /// version.
+    ///
+    /// # Errors
+    /// Returns [DhGroupsError::UnsupportedVersion] if the version is not supported,
+    /// [DhGroupsError::GroupMismatch] if the group number is not that of `G`, or the errors of
+    /// [Scalar::from_bytes].
+    pub fn from_tagged_bytes(bytes: &[u8]) -> Result<Self, DhGroupsError> {
+        Scalar::from_bytes(read_header::<G>(bytes)?)
+    }
+}
+
+/// The number of `G` in RFC 3526, or 0 for other groups.
+fn group_number<G: MODPGroup>() -> u8 {
+    header_number(AnyModpGroup::of::<G>())
+}
+
+/// The group number of the header for `group`: its number in RFC 3526, or 0 for other groups.
+pub(crate) fn header_number(group: Option<AnyModpGroup>) -> u8 {
+    group
+        .filter(|group| AnyModpGroup::RFC3526.contains(group))
+        .map_or(0, |group| group.group_id() as u8)
+}
+
+/// Append the header of the current version for the group `G`.
+pub(crate) fn write_header<G: MODPGroup>(buf: &mut Vec<u8>) {
+    buf.push(WireVersion::CURRENT as u8);
+    buf.push(group_number::<G>());
+}
+
+/// Read the header of an encoding in the group `G`, returning the body.
+///
+/// # Errors
+/// Returns [DhGroupsError::UnexpectedEof] if the header is truncated,
+/// [DhGroupsError::UnsupportedVersion] if the version is not supported, or
+/// [DhGroupsError::GroupMismatch] if the group number is not that of `G`.
+pub(crate) fn read_header<G: MODPGroup>(bytes: &[u8]) -> Result<&[u8], DhGroupsError> {
+    let (version, rest) = read_version(bytes)?;
+    match version {
+        WireVersion::V1 => Ok(rest),
+        WireVersion::V2 => {
+            let (&number, rest) = rest.split_first().ok_or(DhGroupsError::UnexpectedEof)?;
+            if number != group_number::<G>() {
+                return Err(DhGroupsError::GroupMismatch);
+            }
+            Ok(rest)
+        }
+    }
+}
+
+/// Read the version byte of an encoding without group number, returning the version and the body.
+pub(crate) fn read_version(bytes: &[u8]) -> Result<(WireVersion, &[u8]), DhGroupsError> {
+    let (&version, rest) = bytes.split_first().ok_or(DhGroupsError::UnexpectedEof)?;
+    Ok((WireVersion::from_byte(version)?, rest))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{sigma::Proof, MODPGroup14, MODPGroup5};
+
+    /// An element and a scalar of [MODPGroup5] and a sigma proof, encoded in version 1.
+    const ELEMENT_V1: &[u8] = include_bytes!("../tests/fixtures/wire/element_v1.bin");
+    const SCALAR_V1: &[u8] = include_bytes!("../tests/fixtures/wire/scalar_v1.bin");
+    const PROOF_V1: &[u8] = include_bytes!("../tests/fixtures/wire/proof_v1.bin");
+
+    #[test]
+    fn test_decode_v1_fixtures() {
+        assert_eq!(ELEMENT_V1[0], WireVersion::V1 as u8);
+        let element = Element::<MODPGroup5>::from_tagged_bytes(ELEMENT_V1).unwrap();
+        assert_eq!(element.value, 0x0102u64.into());
+        let scalar = Scalar::<MODPGroup5>::from_tagged_bytes(SCALAR_V1).unwrap();
+        assert_eq!(scalar.value, 7u64.into());
+        let proof = Proof::<MODPGroup5>::from_bytes(PROOF_V1).unwrap();
+        assert_eq!(
+            (proof.c.value.clone(), proof.s.value.clone()),
+            (3u64.into(), 5u64.into())
+        );
+
+        // re-encoded in the current version
+        let bytes = element.to_tagged_bytes();
+        assert_eq!(bytes[..2], [WireVersion::V2 as u8, 5]);
+        assert_eq!(bytes[2..], ELEMENT_V1[1..]);
+        assert_eq!(Element::from_tagged_bytes(&bytes).unwrap(), element);
+        assert_eq!(proof.to_bytes()[2..], PROOF_V1[1..]);
+    }
+
+    #[test]
+    fn test_unsupported_version() {
+        let mut v9 = ELEMENT_V1.to_vec();
+        v9[0] = 9;
+        assert_eq!(
+            Element::<MODPGroup5>::from_tagged_bytes(&v9),
+            Err(DhGroupsError::UnsupportedVersion(9))
+        );
+        v9[0] = 0;
+        assert_eq!(
+            Element::<MODPGroup5>::from_tagged_bytes(&v9),
+            Err(DhGroupsError::UnsupportedVersion(0))
+        );
+        assert_eq!(
+            Element::<MODPGroup5>::from_tagged_bytes(&[]),
+            Err(DhGroupsError::UnexpectedEof)
+        );
+        assert_eq!(
+            Element::<MODPGroup5>::from_tagged_bytes(&[2]),
+            Err(DhGroupsError::UnexpectedEof)
+        );
+    }
+
+    #[test]
+    fn test_group_number() {
+        let scalar = Scalar::<MODPGroup14>::from_biguint(7u64.into());
+        let bytes = scalar.to_tagged_bytes();
+        assert_eq!(bytes[1], 14);
+        assert_eq!(Scalar::from_tagged_bytes(&bytes).unwrap(), scalar);
+        assert_eq!(
+            Scalar::<MODPGroup5>::from_tagged_bytes(&bytes),
+            Err(DhGroupsError::GroupMismatch)
+        );
+    }
+}
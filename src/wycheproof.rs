@@ -0,0 +1,148 @@
+//! Runner of test vectors in the layout of the Wycheproof `dh_test.json` files, enabled by the
+//! `wycheproof-tests` feature:
+//!
+//! ```text
+//! cargo test --features wycheproof-tests wycheproof
+//! ```
+//!
+//! Each test group gives the parameters `p`, `q` and `g` of a [PrimeGroup], and each test a
+//! private key, a public key of the peer, the expected shared secret and the expected result.
+//! Integers are big-endian two's complement hex strings, as in all Wycheproof files.
+//!
+//! The group is checked with [PrimeGroup::validate] and the shared secret computed with
+//! [PrimeGroup::shared_secret], so a test passes if
+//! - `valid`: both succeed and the shared secret is the expected one,
+//! - `invalid`: one of them fails, e.g. for a public key out of range or outside the subgroup,
+//! - `acceptable`: either one fails, or the shared secret is the expected one.
+//!
+//! The embedded `tests/fixtures/wycheproof/dh_test.json` is not the file published by the
+//! Wycheproof project: its vectors were written for this crate in the same layout, marked with the
+//! `generatorVersion` `local`. The upstream file, with its license header and the commit it was
+//! taken from, is to be added to [VECTORS] next to it, as are further files of the same layout.
+
+use std::sync::Arc;
+
+use num_bigint::{BigInt, BigUint};
+use serde::Deserialize;
+
+use crate::{PrimeGroup, UnvalidatedRuntimePublicKey};
+
+/// The embedded vector files, by name.
+const VECTORS: &[(&str, &str)] = &[(
+    "dh_test.json",
+    include_str!("../tests/fixtures/wycheproof/dh_test.json"),
+)];
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TestFile {
+    algorithm: String,
+    number_of_tests: usize,
+    test_groups: Vec<TestGroup>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TestGroup {
+    p: String,
+    q: String,
+    g: String,
+    tests: Vec<TestCase>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TestCase {
+    tc_id: u32,
+    comment: String,
+    public: String,
+    private: String,
+    shared: String,
+    result: Expected,
+    flags: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum Expected {
+    Valid,
+    Invalid,
+    Acceptable,
+}
+
+/// Decode a two's complement hex string, returning `None` for a negative integer.
+fn decode(hex: &str) -> Option<BigUint> {
+    let bytes = (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16))
+        .collect::<Result<Vec<_>, _>>()
+        .expect("hex string");
+    BigInt::from_signed_bytes_be(&bytes).to_biguint()
+}
+
+/// Map the parameters of a test group onto a [PrimeGroup], whose cofactor is `(p - 1) / q`.
+fn prime_group(group: &TestGroup) -> Option<PrimeGroup> {
+    let (p, q, g) = (decode(&group.p)?, decode(&group.q)?, decode(&group.g)?);
+    Some(PrimeGroup {
+        cofactor: (&p - 1u64) / &q,
+        p,
+        q,
+        g,
+    })
+}
+
+/// Run a test case, returning the shared secret or `None` if the computation is rejected.
+fn run(pg: Option<&PrimeGroup>, test: &TestCase) -> Option<BigUint> {
+    let pg = pg?;
+    let private = decode(&test.private)?;
+    let public =
+        UnvalidatedRuntimePublicKey::from_biguint(Arc::new(pg.clone()), decode(&test.public)?)
+            .validate()
+            .ok()?;
+    pg.shared_secret_allow_oversized(&private, &public).ok()
+}
+
+/// Run all tests of a file, returning the descriptions of the failed ones.
+fn run_file(name: &str, json: &str) -> Vec<String> {
+    let file: TestFile = serde_json::from_str(json).expect("vector file");
+    assert_eq!(file.algorithm, "DH", "{name}");
+    let count: usize = file.test_groups.iter().map(|group| group.tests.len()).sum();
+    assert_eq!(count, file.number_of_tests, "{name}");
+
+    let mut failures = Vec::new();
+    for group in &file.test_groups {
+        let pg = prime_group(group).filter(|pg| pg.validate().is_ok());
+        for test in &group.tests {
+            let shared = run(pg.as_ref(), test);
+            let matches =
+                shared.as_ref() == decode(&test.shared).as_ref() && !test.shared.is_empty();
+            let passed = match test.result {
+                Expected::Valid => matches,
+                Expected::Invalid => shared.is_none(),
+                Expected::Acceptable => shared.is_none() || matches,
+            };
+            if !passed {
+                failures.push(format!(
+                    "{name} tcId {} ({:?}, {}, {:?}): got {:?}",
+                    test.tc_id, test.result, test.comment, test.flags, shared
+                ));
+            }
+        }
+    }
+    failures
+}
+
+#[test]
+fn test_wycheproof_vectors() {
+    let failures: Vec<String> = VECTORS
+        .iter()
+        .flat_map(|(name, json)| run_file(name, json))
+        .collect();
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
+
+#[test]
+fn test_decode() {
+    assert_eq!(decode("00ff"), Some(BigUint::from(255u64)));
+    assert_eq!(decode("7f"), Some(BigUint::from(127u64)));
+    assert_eq!(decode("ff"), None);
+}
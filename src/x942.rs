@@ -0,0 +1,281 @@
+//! Import and export of Diffie-Hellman parameters in the X9.42 format of RFC 3279, as written by
+//! `openssl genpkey -genparam -algorithm DHX`:
+//!
+//! ```text
+//! DomainParameters ::= SEQUENCE {
+//!     p INTEGER, -- odd prime, p = jq + 1
+//!     g INTEGER, -- generator, g
+//!     q INTEGER, -- factor of p - 1
+//!     j INTEGER OPTIONAL, -- subgroup factor
+//!     validationParams ValidationParams OPTIONAL }
+//!
+//! ValidationParams ::= SEQUENCE {
+//!     seed BIT STRING,
+//!     pgenCounter INTEGER }
+//! ```
+//!
+//! Unlike PKCS #3 (see [crate::dhparam]), the format carries the order q of the subgroup, so it
+//! also describes groups whose modulus is not a safe prime, such as those of RFC 5114. The PEM
+//! form uses the label `X9.42 DH PARAMETERS`.
+
+use num_bigint::BigUint;
+
+use crate::{
+    der::{
+        decode_pem, encode_pem, read_integer, read_tlv, write_integer, write_tlv, TAG_BIT_STRING,
+        TAG_INTEGER, TAG_SEQUENCE,
+    },
+    AnyModpGroup, DhGroupsError, PrimeGroup,
+};
+
+/// PEM label of X9.42 Diffie-Hellman parameters.
+pub const PEM_LABEL: &str = "X9.42 DH PARAMETERS";
+
+/// The seed and counter of the FIPS 186 generation of the primes, with which a verifier can
+/// regenerate them. They are carried as they are, not checked, when parsing.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ValidationParams {
+    /// The seed of the generation.
+    pub seed: Vec<u8>,
+    /// The number of candidates tried for p.
+    pub pgen_counter: u64,
+}
+
+/// Parameters imported from an X9.42 structure.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct X942Params {
+    /// The group.
+    pub group: PrimeGroup,
+    /// The validation parameters, if present.
+    pub validation_params: Option<ValidationParams>,
+}
+
+impl PrimeGroup {
+    /// Encode the group as DER X9.42 parameters, with the cofactor as `j` and no validation
+    /// parameters.
+    pub fn to_x942_der(&self) -> Vec<u8> {
+        to_der(self, None)
+    }
+
+    /// Parse DER encoded X9.42 parameters, ignoring the validation parameters. See [parse_der].
+    pub fn from_x942_der(der: &[u8]) -> Result<Self, DhGroupsError> {
+        parse_der(der).map(|params| params.group)
+    }
+}
+
+impl AnyModpGroup {
+    /// Encode the group as DER X9.42 parameters, with `q` its [order](AnyModpGroup::order) and
+    /// `j = (p - 1) / q`, e.g. 2 for the groups of RFC 3526.
+    pub fn to_x942_der(&self) -> Vec<u8> {
+        let (p, q) = (self.prime_modulus(), self.order());
+        let group = PrimeGroup {
+            cofactor: (&p - 1u64) / &q,
+            p,
+            q,
+            g: self.generator(),
+        };
+        to_der(&group, None)
+    }
+}
+
+#[allow(deprecated)]
+impl crate::ModpGroupId {
+    /// Encode the identified group as DER X9.42 parameters, see [AnyModpGroup::to_x942_der].
+    pub fn to_x942_der(&self) -> Vec<u8> {
+        AnyModpGroup::from(*self).to_x942_der()
+    }
+}
+
+/// Parse DER encoded X9.42 parameters. If present, `j` must be the cofactor `(p - 1) / q`.
+///
+/// # Errors
+/// Returns [DhGroupsError::InvalidDer] if the input is malformed, [DhGroupsError::InvalidCofactor]
+/// if `j` is not the cofactor, or the error of [PrimeGroup::validate] if the parameters are
+/// invalid.
+pub fn parse_der(der: &[u8]) -> Result<X942Params, DhGroupsError> {
+    let (content, rest) = read_tlv(der, TAG_SEQUENCE)?;
+    if !rest.is_empty() {
+        return Err(DhGroupsError::TrailingBytes);
+    }
+    let (p, content) = read_integer(content)?;
+    let (g, content) = read_integer(content)?;
+    let (q, mut content) = read_integer(content)?;
+    let mut j = None;
+    if content.first() == Some(&TAG_INTEGER) {
+        let (value, rest) = read_integer(content)?;
+        j = Some(value);
+        content = rest;
+    }
+    let validation_params = if content.is_empty() {
+        None
+    } else {
+        let (params, rest) = read_tlv(content, TAG_SEQUENCE)?;
+        if !rest.is_empty() {
+            return Err(DhGroupsError::InvalidDer);
+        }
+        Some(parse_validation_params(params)?)
+    };
+
+    let group = PrimeGroup::from_parameters(p, g, Some(q))?;
+    if j.is_some_and(|j| j != group.cofactor) {
+        return Err(DhGroupsError::InvalidCofactor);
+    }
+    Ok(X942Params {
+        group,
+        validation_params,
+    })
+}
+
+/// Parse PEM encoded X9.42 parameters, as in [parse_der].
+pub fn parse_pem(pem: &str) -> Result<X942Params, DhGroupsError> {
+    parse_der(&decode_pem(pem, PEM_LABEL)?)
+}
+
+/// Encode `group` as DER X9.42 parameters, with the cofactor as `j` and the optional validation
+/// parameters.
+pub fn to_der(group: &PrimeGroup, validation_params: Option<&ValidationParams>) -> Vec<u8> {
+    let mut content = Vec::new();
+    write_integer(&mut content, &group.p);
+    write_integer(&mut content, &group.g);
+    write_integer(&mut content, &group.q);
+    write_integer(&mut content, &group.cofactor);
+    if let Some(params) = validation_params {
+        let mut seed = vec![0u8];
+        seed.extend_from_slice(&params.seed);
+        let mut inner = Vec::new();
+        write_tlv(&mut inner, TAG_BIT_STRING, &seed);
+        write_integer(&mut inner, &BigUint::from(params.pgen_counter));
+        write_tlv(&mut content, TAG_SEQUENCE, &inner);
+    }
+    let mut der = Vec::new();
+    write_tlv(&mut der, TAG_SEQUENCE, &content);
+    der
+}
+
+/// Encode `group` as PEM X9.42 parameters, as in [to_der].
+pub fn to_pem(group: &PrimeGroup, validation_params: Option<&ValidationParams>) -> String {
+    encode_pem(&to_der(group, validation_params), PEM_LABEL)
+}
+
+/// Parse the content of the ValidationParams sequence. The seed must be a whole number of bytes.
+fn parse_validation_params(content: &[u8]) -> Result<ValidationParams, DhGroupsError> {
+    let (seed, content) = read_tlv(content, TAG_BIT_STRING)?;
+    let (pgen_counter, content) = read_integer(content)?;
+    if !content.is_empty() {
+        return Err(DhGroupsError::InvalidDer);
+    }
+    let seed = match seed {
+        [0, seed @ ..] => seed.to_vec(),
+        _ => return Err(DhGroupsError::InvalidDer),
+    };
+    Ok(ValidationParams {
+        seed,
+        pgen_counter: u64::try_from(pgen_counter).map_err(|_| DhGroupsError::InvalidDer)?,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Written by `openssl genpkey -genparam -algorithm DHX -pkeyopt dh_paramgen_prime_len:1024
+    /// -pkeyopt dh_paramgen_subprime_len:160 -pkeyopt dh_paramgen_type:1`, without `j`.
+    const OPENSSL_PEM: &str = include_str!("../tests/fixtures/x942_dhparam.pem");
+
+    #[test]
+    fn test_parse_openssl() {
+        let params = parse_pem(OPENSSL_PEM).unwrap();
+        assert_eq!(params.group.p.bits(), 1024);
+        assert_eq!(params.group.q.bits(), 160);
+        assert_eq!(
+            params.validation_params,
+            Some(ValidationParams {
+                seed: vec![
+                    0x9a, 0xf9, 0xda, 0x58, 0x99, 0xb6, 0xfd, 0xad, 0xea, 0xd1, 0x5f, 0x97, 0x62,
+                    0x7d, 0xf8, 0x77, 0x8b, 0xd9, 0x1b, 0x4c,
+                ],
+                pgen_counter: 793,
+            })
+        );
+        assert_eq!(
+            &params.group.q * &params.group.cofactor,
+            &params.group.p - 1u64
+        );
+
+        // the export adds j, and keeps the validation parameters
+        let der = to_der(&params.group, params.validation_params.as_ref());
+        let reparsed = parse_der(&der).unwrap();
+        assert_eq!(reparsed.group, params.group);
+        assert_eq!(reparsed.validation_params, params.validation_params);
+        let pem = to_pem(&params.group, params.validation_params.as_ref());
+        assert_eq!(parse_pem(&pem).unwrap().group, params.group);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        // a group whose order is not (p - 1) / 2
+        let group = PrimeGroup::new_schnorr(3571u64.into(), 17u64.into()).unwrap();
+        let der = group.to_x942_der();
+        assert_eq!(PrimeGroup::from_x942_der(&der).unwrap(), group);
+        assert_eq!(parse_der(&der).unwrap().validation_params, None);
+
+        let params = ValidationParams {
+            seed: vec![],
+            pgen_counter: 0,
+        };
+        let der = to_der(&group, Some(&params));
+        assert_eq!(parse_der(&der).unwrap().validation_params, Some(params));
+    }
+
+    #[test]
+    fn test_static_group() {
+        // the larger groups take long to validate
+        for id in [AnyModpGroup::Modp1536, AnyModpGroup::Modp2048] {
+            let group = PrimeGroup::from_x942_der(&id.to_x942_der()).unwrap();
+            assert_eq!(group.identify_exact(), Some(id));
+            assert_eq!(group.cofactor, BigUint::from(2u64));
+        }
+
+        let id = AnyModpGroup::Modp1024S160;
+        let group = PrimeGroup::from_x942_der(&id.to_x942_der()).unwrap();
+        assert_eq!(group.identify_exact(), Some(id));
+        assert_eq!(group.q, id.order());
+    }
+
+    #[test]
+    fn test_invalid() {
+        let group = PrimeGroup::new_schnorr(3571u64.into(), 17u64.into()).unwrap();
+        let der = group.to_x942_der();
+
+        // j is not the cofactor
+        let mut wrong_j = der.clone();
+        *wrong_j.last_mut().unwrap() += 1;
+        assert_eq!(parse_der(&wrong_j), Err(DhGroupsError::InvalidCofactor));
+
+        // q does not divide p - 1
+        let wrong_q = to_der(
+            &PrimeGroup {
+                q: 19u64.into(),
+                ..group.clone()
+            },
+            None,
+        );
+        assert_eq!(parse_der(&wrong_q), Err(DhGroupsError::InvalidCofactor));
+
+        // a seed with unused bits, and trailing content
+        let mut der = to_der(
+            &group,
+            Some(&ValidationParams {
+                seed: vec![0xff],
+                pgen_counter: 1,
+            }),
+        );
+        let len = der.len();
+        der[len - 5] = 1;
+        assert_eq!(parse_der(&der), Err(DhGroupsError::InvalidDer));
+        der[len - 5] = 0;
+        assert!(parse_der(&der).is_ok());
+        der.push(0);
+        assert_eq!(parse_der(&der), Err(DhGroupsError::TrailingBytes));
+    }
+}
@@ -0,0 +1,191 @@
+//! Exponentiation by secret exponents whose temporaries are overwritten with zeros before they
+//! are freed, enabled by the `zeroize` feature.
+//!
+//! [MODPGroup::pow] runs the `modpow` of num-bigint, whose window table and Montgomery
+//! temporaries hold powers of the base depending on the exponent, and are freed as they are.
+//! [Element::pow_zeroizing] computes the same power with a 4-bit window over the arithmetic of
+//! this crate instead: the table, the accumulator, the products and the limbs of the exponent are
+//! wiped when they are replaced or no longer used, and so is the scratch space of the reductions
+//! of the thread once the exponentiation is done.
+//!
+//! The guarantee covers the groups of RFC 3526, whose products and reductions run entirely in the
+//! scratch space. For any other modulus, the products are reduced by the division of num-bigint,
+//! whose quotients and normalized operands are not wiped. With the feature, `RuntimeSecretKey`
+//! computes its public key and shared secrets this way too.
+//!
+//! The exponentiation skips the multiplications of the zero windows of the exponent, so its
+//! running time is not constant. That is why the entry point is `pow_zeroizing` and not
+//! `pow_ct_zeroizing`: the crate has no constant-time `pow_ct` to pair it with, and a `_ct` name
+//! would promise a running time independent of the exponent.
+//!
+//! # Example
+//!
+//! ```rust
+//! use num_bigint::BigUint;
+//! use diffie_hellman_groups::{Element, MODPGroup14};
+//!
+//! let base = Element::<MODPGroup14>::from_biguint(BigUint::from(5u32));
+//! let exponent = BigUint::from(123456789u64);
+//! assert_eq!(base.pow_zeroizing(&exponent), base.pow(&exponent));
+//! ```
+//!
+//! [MODPGroup::pow]: crate::group::MODPGroup::pow
+
+use num_bigint::BigUint;
+
+use crate::{
+    group::MODPGroup,
+    reduce::{wipe_scratch, SpecialForm},
+    scalar::wipe,
+    AnyModpGroup, Element,
+};
+
+/// The modular arithmetic of an exponentiation.
+#[derive(Clone, Copy)]
+pub(crate) enum Modulus<'a> {
+    /// A prime of RFC 3526, reduced in the scratch space.
+    Special(&'static SpecialForm),
+    /// Any other modulus, reduced by num-bigint.
+    Generic(&'a BigUint),
+}
+
+impl<'a> Modulus<'a> {
+    /// The arithmetic modulo `p`, in the scratch space if it is a prime of RFC 3526.
+    pub(crate) fn new(p: &'a BigUint, group: Option<AnyModpGroup>) -> Self {
+        match group.and_then(|group| group.special_form()) {
+            Some(form) => Modulus::Special(form),
+            None => Modulus::Generic(p),
+        }
+    }
+
+    fn reduce(&self, x: &BigUint) -> BigUint {
+        match self {
+            Modulus::Special(form) => form.reduce(x),
+            Modulus::Generic(p) => x % *p,
+        }
+    }
+
+    fn mul(&self, a: &BigUint, b: &BigUint) -> BigUint {
+        match self {
+            Modulus::Special(form) => form.mul_mod(a, b),
+            Modulus::Generic(p) => {
+                let mut product = a * b;
+                let r = &product % *p;
+                wipe(&mut product);
+                r
+            }
+        }
+    }
+
+    /// Replace `acc` with `acc * b mod p`, wiping its previous value.
+    fn mul_assign(&self, acc: &mut BigUint, b: &BigUint) {
+        let mut r = self.mul(acc, b);
+        std::mem::swap(acc, &mut r);
+        wipe(&mut r);
+    }
+
+    /// Replace `acc` with `acc^2 mod p`, wiping its previous value.
+    fn square_assign(&self, acc: &mut BigUint) {
+        let mut r = match self {
+            Modulus::Special(form) => form.sqr_mod(acc),
+            Modulus::Generic(_) => self.mul(acc, acc),
+        };
+        std::mem::swap(acc, &mut r);
+        wipe(&mut r);
+    }
+}
+
+/// Compute `base^exponent mod p`, wiping every temporary depending on the exponent.
+pub(crate) fn pow_mod(base: &BigUint, exponent: &BigUint, modulus: Modulus<'_>) -> BigUint {
+    const WINDOW: u64 = 4;
+
+    if let Modulus::Special(form) = modulus {
+        form.reserve_scratch();
+    }
+    let mut table = Vec::with_capacity(1 << WINDOW);
+    table.push(modulus.reduce(&BigUint::from(1u64)));
+    table.push(modulus.reduce(base));
+    for i in 2..1 << WINDOW {
+        let power = modulus.mul(&table[i - 1], &table[1]);
+        table.push(power);
+    }
+
+    let mut digits = exponent.to_u64_digits();
+    let windows = exponent.bits().div_ceil(WINDOW);
+    let mut acc = table[0].clone();
+    for i in (0..windows).rev() {
+        if i + 1 < windows {
+            for _ in 0..WINDOW {
+                modulus.square_assign(&mut acc);
+            }
+        }
+        let bit = i * WINDOW;
+        let window = (digits[(bit / 64) as usize] >> (bit % 64)) & ((1 << WINDOW) - 1);
+        if window != 0 {
+            modulus.mul_assign(&mut acc, &table[window as usize]);
+        }
+    }
+
+    table.iter_mut().for_each(wipe);
+    digits.fill(0);
+    std::hint::black_box(&digits);
+    wipe_scratch();
+    acc
+}
+
+impl<G: MODPGroup> Element<G> {
+    /// Compute self^exponent mod p like [Element::pow], overwriting with zeros every temporary
+    /// depending on the exponent before it is freed, see the module [zeroizing](crate::zeroizing).
+    pub fn pow_zeroizing(&self, exponent: &BigUint) -> Self {
+        let p = G::prime_modulus();
+        let modulus = Modulus::new(&p, AnyModpGroup::of::<G>());
+        Element::from_value(pow_mod(&self.value, exponent, modulus))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use num_bigint::RandBigInt;
+
+    use super::*;
+    use crate::{MODPGroup14, MODPGroup18, MODPGroup5};
+
+    fn check<G: MODPGroup>() {
+        let mut rng = rand::thread_rng();
+        let p = G::prime_modulus();
+        let base = Element::<G>::from_value(rng.gen_biguint_below(&p));
+        let exponents = [
+            BigUint::ZERO,
+            BigUint::from(1u64),
+            BigUint::from(16u64),
+            &p - 1u64,
+            rng.gen_biguint(256),
+            rng.gen_biguint(p.bits()),
+        ];
+        for exponent in exponents {
+            assert_eq!(base.pow_zeroizing(&exponent), base.pow(&exponent));
+        }
+    }
+
+    #[test]
+    fn test_matches_pow() {
+        check::<MODPGroup5>();
+        check::<MODPGroup14>();
+        check::<MODPGroup18>();
+    }
+
+    #[test]
+    fn test_generic_modulus() {
+        let mut rng = rand::thread_rng();
+        // a modulus of no particular form, which need not be prime
+        let p = rng.gen_biguint(1000) | BigUint::from(1u64);
+        let base = rng.gen_biguint_below(&p);
+        for bits in [0, 1, 64, 999] {
+            let exponent = rng.gen_biguint(bits);
+            assert_eq!(
+                pow_mod(&base, &exponent, Modulus::Generic(&p)),
+                base.modpow(&exponent, &p)
+            );
+        }
+    }
+}
@@ -0,0 +1,107 @@
+//! Count the allocations of the modular arithmetic of [MODPGroup], checking that the in-place
+//! variants allocate less than the ones returning a new value, and that the reductions reuse the
+//! scratch space of their thread.
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    cell::Cell,
+};
+
+use diffie_hellman_groups::group::{MODPGroup, MODPGroup14};
+
+/// Counts the allocations of the current thread, so that tests running in parallel do not
+/// disturb each other.
+struct CountingAllocator;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.with(|count| count.set(count.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.with(|count| count.set(count.get() + 1));
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+/// The number of allocations of `f`.
+fn allocations<T>(f: impl FnOnce() -> T) -> usize {
+    let before = ALLOCATIONS.with(Cell::get);
+    let result = f();
+    let after = ALLOCATIONS.with(Cell::get);
+    drop(result);
+    after - before
+}
+
+#[test]
+fn test_in_place_arithmetic_allocates_less() {
+    let p = MODPGroup14::prime_modulus();
+    // operands close to p, so that the sums need a reduction
+    let a = &p - 3u64;
+    let b = &p - 5u64;
+
+    let mut sum = a.clone();
+    let add = allocations(|| MODPGroup14::add(&a, &b));
+    let add_assign = allocations(|| MODPGroup14::add_assign(&mut sum, &b));
+    assert_eq!(sum, MODPGroup14::add(&a, &b));
+    assert!(add_assign < add, "add_assign: {add_assign}, add: {add}");
+
+    let mut difference = b.clone();
+    let sub = allocations(|| MODPGroup14::sub(&b, &a));
+    let sub_assign = allocations(|| MODPGroup14::sub_assign(&mut difference, &a));
+    assert_eq!(difference, MODPGroup14::sub(&b, &a));
+    assert!(sub_assign < sub, "sub_assign: {sub_assign}, sub: {sub}");
+
+    let mut product = a.clone();
+    let mul = allocations(|| MODPGroup14::mul(&a, &b));
+    let mul_assign = allocations(|| MODPGroup14::mul_assign(&mut product, &b));
+    assert_eq!(product, MODPGroup14::mul(&a, &b));
+    assert!(mul_assign <= mul, "mul_assign: {mul_assign}, mul: {mul}");
+
+    // a chain of multiplications, as in an exponentiation
+    let mut acc = a.clone();
+    let chain = allocations(|| {
+        for _ in 0..10 {
+            acc = MODPGroup14::mul(&acc, &b);
+        }
+    });
+    let mut acc_assign = a.clone();
+    let chain_assign = allocations(|| {
+        for _ in 0..10 {
+            MODPGroup14::mul_assign(&mut acc_assign, &b);
+        }
+    });
+    assert_eq!(acc_assign, acc);
+    assert!(
+        chain_assign <= chain,
+        "mul_assign chain: {chain_assign}, mul chain: {chain}"
+    );
+}
+
+#[test]
+fn test_reduction_reuses_scratch() {
+    let p = MODPGroup14::prime_modulus();
+    let a = &p - 3u64;
+    let b = &p - 5u64;
+    // the first reduction of the thread sizes its scratch space
+    let warm_up = MODPGroup14::mul(&a, &b);
+
+    let product = &a * &b;
+    let reduce = allocations(|| MODPGroup14::fast_reduce(&product));
+    let remainder = allocations(|| &product % &p);
+    assert_eq!(reduce, 1, "only the result is allocated");
+    assert!(reduce < remainder, "fast_reduce: {reduce}, %: {remainder}");
+    assert_eq!(MODPGroup14::fast_reduce(&product), warm_up);
+}
@@ -0,0 +1,112 @@
+//! Drive the commands of the example `dhgroups-cli` as library calls, so that the example keeps
+//! building and working with the API it exercises.
+
+#![cfg(all(feature = "jwk", feature = "secrecy"))]
+
+#[path = "../examples/dhgroups-cli.rs"]
+#[allow(dead_code)]
+mod cli;
+
+use std::fs;
+
+use diffie_hellman_groups::{dhparam, UnvalidatedRuntimePublicKey};
+
+const GROUP_14_PEM: &str = include_str!("fixtures/group14_dhparam.pem");
+
+#[test]
+fn test_key_exchange() {
+    let rng = &mut rand::thread_rng();
+    let group = cli::builtin_group("modp1536").unwrap();
+    let alice = cli::genkey(&group, rng);
+    let bob = cli::genkey(&group, rng);
+
+    let alice_public = cli::pubkey(&alice).unwrap();
+    let bob_public = cli::pubkey(&bob).unwrap();
+    assert!(!alice_public.contains(r#""x""#));
+    assert_eq!(
+        UnvalidatedRuntimePublicKey::from_jwk(&alice_public)
+            .unwrap()
+            .group(),
+        &group
+    );
+
+    let z = cli::shared(&alice, &bob_public).unwrap();
+    assert_eq!(z, cli::shared(&bob, &alice_public).unwrap());
+    assert_eq!(z.len(), 2 * 1536 / 8);
+}
+
+#[test]
+fn test_invalid_keys() {
+    let rng = &mut rand::thread_rng();
+    let alice = cli::genkey(&cli::builtin_group("modp1536").unwrap(), rng);
+    let other = cli::genkey(&cli::builtin_group("modp2048").unwrap(), rng);
+
+    // a public key of another group
+    assert!(cli::shared(&alice, &cli::pubkey(&other).unwrap()).is_err());
+    // a public key is not a secret key
+    assert!(cli::pubkey(&cli::pubkey(&alice).unwrap()).is_err());
+    // a secret exponent not matching "y"
+    let mut members: serde_json::Map<String, serde_json::Value> =
+        serde_json::from_str(&alice).unwrap();
+    members["x"] = serde_json::from_str::<serde_json::Value>(&other).unwrap()["x"].clone();
+    assert!(cli::pubkey(&serde_json::to_string(&members).unwrap()).is_err());
+
+    assert!(cli::builtin_group("modp1024").is_err());
+}
+
+#[test]
+fn test_inspect() {
+    let text = cli::inspect(GROUP_14_PEM).unwrap();
+    assert!(text.starts_with("    DH Parameters: (2048 bit)\n"));
+    assert!(text.contains("    G:    2 (0x2)\n"));
+    assert!(text.ends_with("Identified: modp2048 (group 14)\n"));
+
+    let pem = cli::genparam(64, &mut rand::thread_rng()).unwrap();
+    let params = dhparam::parse_pem(&pem).unwrap();
+    assert_eq!(params.group.p.bits(), 64);
+    let text = cli::inspect(&pem).unwrap();
+    assert!(text.starts_with("    DH Parameters: (64 bit)\n"));
+    assert!(text.ends_with("Identified: none\n"));
+}
+
+#[test]
+fn test_run() {
+    let rng = &mut rand::thread_rng();
+    let dir = std::env::temp_dir().join(format!("dhgroups-cli-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let path = |name: &str| dir.join(name).to_str().unwrap().to_string();
+    let run = |args: &[&str], rng: &mut rand::rngs::ThreadRng| {
+        let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+        cli::run(&args, rng)
+    };
+
+    let params = path("params.pem");
+    assert_eq!(
+        run(&["genparam", "--bits", "64", "--out", &params], rng).unwrap(),
+        ""
+    );
+    let (alice, bob) = (path("alice.json"), path("bob.json"));
+    run(&["genkey", "--params", &params, "--out", &alice], rng).unwrap();
+    run(&["genkey", "--out", &bob, "--params", &params], rng).unwrap();
+    let bob_public = path("bob_public.json");
+    run(&["pubkey", "--key", &bob, "--out", &bob_public], rng).unwrap();
+    let z = run(&["shared", "--key", &alice, "--peer", &bob_public], rng).unwrap();
+    assert_eq!(z.len(), 2 * 8);
+    assert!(run(&["inspect", &params], rng)
+        .unwrap()
+        .contains("(64 bit)"));
+
+    for invalid in [
+        &[][..],
+        &["unknown"],
+        &["genparam"],
+        &["genparam", "--bits"],
+        &["genparam", "--bits", "many"],
+        &["genkey", "--group", "modp2048", "--params", &params],
+        &["shared", "--key", &alice],
+        &["inspect"],
+    ] {
+        assert!(run(invalid, rng).is_err(), "{invalid:?}");
+    }
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,53 @@
+//! Check that the library builds with no features, with each feature alone, and with all of
+//! them, so that the code gated by features does not rot.
+
+use std::process::Command;
+
+const FEATURES: [&str; 23] = [
+    "serde",
+    "serde_with",
+    "primegroup",
+    "parallel",
+    "async",
+    "xor-hmac",
+    "jwk",
+    "cbor",
+    "openssl",
+    "bigint-dig",
+    "crypto-bigint",
+    "group",
+    "subtle",
+    "secrecy",
+    "fingerprint",
+    "fixed-limbs",
+    "legacy-groups",
+    "stats",
+    "test-utils",
+    "zeroize",
+    "capi",
+    "python",
+    "wasm",
+];
+
+fn check(features: &str) {
+    let status = Command::new(env!("CARGO"))
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .env(
+            "CARGO_TARGET_DIR",
+            concat!(env!("CARGO_MANIFEST_DIR"), "/target/feature-combinations"),
+        )
+        .args(["check", "--lib", "--quiet", "--no-default-features"])
+        .args(["--features", features])
+        .status()
+        .expect("cargo runs");
+    assert!(status.success(), "build with features [{features}] failed");
+}
+
+#[test]
+fn test_feature_combinations() {
+    check("");
+    for feature in FEATURES {
+        check(feature);
+    }
+    check(&FEATURES.join(","));
+}
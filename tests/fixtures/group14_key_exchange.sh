@@ -0,0 +1,31 @@
+#!/bin/sh
+# Captures group14_server_key_exchange.bin and group14_client_key_exchange.bin: the
+# ServerKeyExchange and ClientKeyExchange handshake messages of a TLS 1.2 DHE-RSA connection
+# between openssl s_server and s_client over the 2048-bit MODP group of RFC 3526, with a fresh
+# self-signed RSA 2048 certificate. The committed files were written with OpenSSL 3.5.6.
+#
+#     cd tests/fixtures && sh group14_key_exchange.sh
+
+set -e
+dir=$(mktemp -d)
+trap 'rm -rf "$dir"' EXIT
+
+openssl req -x509 -newkey rsa:2048 -nodes -keyout "$dir/key.pem" -out "$dir/cert.pem" \
+    -days 1 -subj /CN=localhost 2>/dev/null
+openssl s_server -accept 44330 -key "$dir/key.pem" -cert "$dir/cert.pem" \
+    -dhparam group14_dhparam.pem -tls1_2 -cipher DHE-RSA-AES128-GCM-SHA256 \
+    -naccept 1 -quiet >/dev/null 2>&1 &
+sleep 1
+echo | openssl s_client -connect 127.0.0.1:44330 -tls1_2 -cipher DHE-RSA-AES128-GCM-SHA256 \
+    -msg -ign_eof >"$dir/msg.txt" 2>&1
+wait
+
+# the hex dump that follows the header line of the handshake message $1
+message() {
+    awk -v name="$1" '
+        /^(<<<|>>>)/ { dump = ($0 ~ ", " name "$") ; next }
+        dump && /^ / { print }
+    ' "$dir/msg.txt" | xxd -r -p
+}
+message ServerKeyExchange >group14_server_key_exchange.bin
+message ClientKeyExchange >group14_client_key_exchange.bin
@@ -0,0 +1,38 @@
+// Generates group14_public_key.json: a JSON Web Key of the public key g^x of the 2048-bit
+// MODP group of RFC 3526, written with the BigInt and Buffer APIs of Node.js.
+//
+//     node generate.js > group14_public_key.json
+
+const p = BigInt("0xFFFFFFFFFFFFFFFFC90FDAA22168C234C4C6628B80DC1CD129024E088A67CC74020BBEA63B139B22514A08798E3404DDEF9519B3CD3A431B302B0A6DF25F14374FE1356D6D51C245E485B576625E7EC6F44C42E9A637ED6B0BFF5CB6F406B7EDEE386BFB5A899FA5AE9F24117C4B1FE649286651ECE45B3DC2007CB8A163BF0598DA48361C55D39A69163FA8FD24CF5F83655D23DCA3AD961C62F356208552BB9ED529077096966D670C354E4ABC9804F1746C08CA18217C32905E462E36CE3BE39E772C180E86039B2783A2EC07A28FB5C55DF06F4C52C9DE2BCBF6955817183995497CEA956AE515D2261898FA051015728E5A8AACAA68FFFFFFFFFFFFFFFF");
+const g = 2n;
+const q = (p - 1n) / 2n;
+const x = BigInt("0x" + require("crypto").createHash("sha256").update("jwk fixture").digest("hex"));
+
+function modPow(base, exp, mod) {
+  let result = 1n;
+  base %= mod;
+  while (exp > 0n) {
+    if (exp & 1n) result = (result * base) % mod;
+    base = (base * base) % mod;
+    exp >>= 1n;
+  }
+  return result;
+}
+
+function b64url(n) {
+  let hex = n.toString(16);
+  if (hex.length % 2) hex = "0" + hex;
+  return Buffer.from(hex, "hex").toString("base64url");
+}
+
+const jwk = {
+  kty: "DH",
+  kid: "group14-fixture",
+  use: "enc",
+  key_ops: ["deriveBits"],
+  p: b64url(p),
+  g: b64url(g),
+  q: b64url(q),
+  y: b64url(modPow(g, x, p)),
+};
+console.log(JSON.stringify(jwk, null, 2));
@@ -0,0 +1,22 @@
+#!/bin/sh
+# Captures the key exchanges of an OpenSSH client with kex_server.py: group14_sha256.txt for
+# diffie-hellman-group14-sha256, and gex_sha256.txt with the payloads gex_request.bin and
+# gex_group.bin for diffie-hellman-group-exchange-sha256. The committed files were written with
+# OpenSSH_9.2p1 Debian-2+deb12u6 and Python 3.11 with cryptography 48.
+#
+#     cd tests/fixtures/ssh && sh capture.sh
+
+set -e
+
+capture() {
+    python3 kex_server.py 42220 "$1" "$2" &
+    sleep 1
+    ssh -F /dev/null -p 42220 -o BatchMode=yes -o StrictHostKeyChecking=no \
+        -o UserKnownHostsFile=/dev/null -o KexAlgorithms="$1" -o HostKeyAlgorithms=ssh-ed25519 \
+        -o Ciphers=aes128-ctr -o MACs=hmac-sha2-256 capture@127.0.0.1 true 2>/dev/null || true
+    # fails unless the client sent SSH_MSG_NEWKEYS
+    wait $!
+}
+
+capture diffie-hellman-group14-sha256 group14_sha256.txt
+capture diffie-hellman-group-exchange-sha256 gex_sha256.txt
@@ -0,0 +1,204 @@
+"""A minimal SSH server running a single unencrypted key exchange with an OpenSSH client, used by
+capture.sh to record the exchange.
+
+It offers one key exchange method, `diffie-hellman-group14-sha256` or
+`diffie-hellman-group-exchange-sha256` (answering the group request with the 2048-bit MODP group
+of RFC 3526), signs the exchange hash with a fresh ssh-ed25519 host key, and stops once the client
+sends SSH_MSG_NEWKEYS, which OpenSSH only does after verifying the signature of the exchange hash.
+
+    python3 kex_server.py PORT METHOD OUTPUT
+
+writes to OUTPUT the values of the exchange as `name hex` lines. With the group exchange, the
+payloads of SSH_MSG_KEX_DH_GEX_REQUEST and SSH_MSG_KEX_DH_GEX_GROUP are written to
+gex_request.bin and gex_group.bin.
+"""
+
+import hashlib
+import os
+import secrets
+import socket
+import struct
+import sys
+
+from cryptography.hazmat.primitives.asymmetric.ed25519 import Ed25519PrivateKey
+from cryptography.hazmat.primitives.serialization import Encoding, PublicFormat
+
+P = int(
+    "FFFFFFFFFFFFFFFFC90FDAA22168C234C4C6628B80DC1CD129024E088A67CC74"
+    "020BBEA63B139B22514A08798E3404DDEF9519B3CD3A431B302B0A6DF25F1437"
+    "4FE1356D6D51C245E485B576625E7EC6F44C42E9A637ED6B0BFF5CB6F406B7ED"
+    "EE386BFB5A899FA5AE9F24117C4B1FE649286651ECE45B3DC2007CB8A163BF05"
+    "98DA48361C55D39A69163FA8FD24CF5F83655D23DCA3AD961C62F356208552BB"
+    "9ED529077096966D670C354E4ABC9804F1746C08CA18217C32905E462E36CE3B"
+    "E39E772C180E86039B2783A2EC07A28FB5C55DF06F4C52C9DE2BCBF695581718"
+    "3995497CEA956AE515D2261898FA051015728E5A8AACAA68FFFFFFFFFFFFFFFF",
+    16,
+)
+G = 2
+
+SSH_MSG_KEXINIT = 20
+SSH_MSG_NEWKEYS = 21
+SSH_MSG_KEXDH_INIT = 30
+SSH_MSG_KEXDH_REPLY = 31
+SSH_MSG_KEX_DH_GEX_GROUP = 31
+SSH_MSG_KEX_DH_GEX_INIT = 32
+SSH_MSG_KEX_DH_GEX_REPLY = 33
+SSH_MSG_KEX_DH_GEX_REQUEST = 34
+
+
+def string(data):
+    return struct.pack(">I", len(data)) + data
+
+
+def mpint(x):
+    data = x.to_bytes((x.bit_length() + 8) // 8, "big") if x else b""
+    return string(data)
+
+
+def read_mpint(data):
+    (length,) = struct.unpack(">I", data[:4])
+    return int.from_bytes(data[4 : 4 + length], "big"), data[4 + length :]
+
+
+class Connection:
+    def __init__(self, sock):
+        self.sock = sock
+        self.buffer = b""
+
+    def read(self, n):
+        while len(self.buffer) < n:
+            chunk = self.sock.recv(4096)
+            if not chunk:
+                raise EOFError("connection closed by the client")
+            self.buffer += chunk
+        data, self.buffer = self.buffer[:n], self.buffer[n:]
+        return data
+
+    def read_line(self):
+        while b"\r\n" not in self.buffer:
+            chunk = self.sock.recv(4096)
+            if not chunk:
+                raise EOFError("connection closed by the client")
+            self.buffer += chunk
+        line, self.buffer = self.buffer.split(b"\r\n", 1)
+        return line
+
+    def read_packet(self):
+        (length,) = struct.unpack(">I", self.read(4))
+        body = self.read(length)
+        padding = body[0]
+        return body[1 : length - padding]
+
+    def write_packet(self, payload):
+        padding = 8 - (5 + len(payload)) % 8
+        if padding < 4:
+            padding += 8
+        packet = struct.pack(">IB", 1 + len(payload) + padding, padding)
+        self.sock.sendall(packet + payload + os.urandom(padding))
+
+
+def kexinit(method):
+    payload = bytes([SSH_MSG_KEXINIT]) + os.urandom(16)
+    for name_list in [
+        method,
+        b"ssh-ed25519",
+        b"aes128-ctr",
+        b"aes128-ctr",
+        b"hmac-sha2-256",
+        b"hmac-sha2-256",
+        b"none",
+        b"none",
+        b"",
+        b"",
+    ]:
+        payload += string(name_list)
+    return payload + b"\x00" + struct.pack(">I", 0)
+
+
+def main():
+    port, method, output = int(sys.argv[1]), sys.argv[2].encode(), sys.argv[3]
+    payloads = {}
+
+    listener = socket.create_server(("127.0.0.1", port))
+    sock, _ = listener.accept()
+    conn = Connection(sock)
+
+    server_version = b"SSH-2.0-KexCapture_1.0"
+    sock.sendall(server_version + b"\r\n")
+    client_version = conn.read_line()
+
+    server_kexinit = kexinit(method)
+    conn.write_packet(server_kexinit)
+    client_kexinit = conn.read_packet()
+    assert client_kexinit[0] == SSH_MSG_KEXINIT
+
+    host_key = Ed25519PrivateKey.generate()
+    public = host_key.public_key().public_bytes(Encoding.Raw, PublicFormat.Raw)
+    host_key_blob = string(b"ssh-ed25519") + string(public)
+
+    y = secrets.randbelow((P - 1) // 2 - 1) + 1
+    f = pow(G, y, P)
+    hashed = (
+        string(client_version)
+        + string(server_version)
+        + string(client_kexinit)
+        + string(server_kexinit)
+        + string(host_key_blob)
+    )
+
+    if method == b"diffie-hellman-group-exchange-sha256":
+        request = conn.read_packet()
+        assert request[0] == SSH_MSG_KEX_DH_GEX_REQUEST
+        payloads["gex_request"] = request
+        group = bytes([SSH_MSG_KEX_DH_GEX_GROUP]) + mpint(P) + mpint(G)
+        payloads["gex_group"] = group
+        conn.write_packet(group)
+        init = conn.read_packet()
+        assert init[0] == SSH_MSG_KEX_DH_GEX_INIT
+        hashed += request[1:13] + mpint(P) + mpint(G)
+        reply_type = SSH_MSG_KEX_DH_GEX_REPLY
+    else:
+        init = conn.read_packet()
+        assert init[0] == SSH_MSG_KEXDH_INIT
+        reply_type = SSH_MSG_KEXDH_REPLY
+    e, rest = read_mpint(init[1:])
+    assert rest == b""
+
+    k = pow(e, y, P)
+    hashed += mpint(e) + mpint(f) + mpint(k)
+    h = hashlib.sha256(hashed).digest()
+    signature = string(b"ssh-ed25519") + string(host_key.sign(h))
+    conn.write_packet(
+        bytes([reply_type]) + string(host_key_blob) + mpint(f) + string(signature)
+    )
+    conn.write_packet(bytes([SSH_MSG_NEWKEYS]))
+
+    # OpenSSH sends its NEWKEYS only if the signature of H verifies with the host key
+    while True:
+        packet = conn.read_packet()
+        if packet[0] == SSH_MSG_NEWKEYS:
+            break
+    sock.close()
+
+    values = [
+        ("V_C", client_version),
+        ("V_S", server_version),
+        ("I_C", client_kexinit),
+        ("I_S", server_kexinit),
+        ("K_S", host_key_blob),
+        ("e", e.to_bytes((e.bit_length() + 7) // 8, "big")),
+        ("f", f.to_bytes((f.bit_length() + 7) // 8, "big")),
+        ("y", y.to_bytes((y.bit_length() + 7) // 8, "big")),
+        ("K", k.to_bytes((k.bit_length() + 7) // 8, "big")),
+        ("H", h),
+    ]
+    with open(output, "w") as out:
+        for name, value in values:
+            out.write(f"{name} {value.hex()}\n")
+    for name, payload in payloads.items():
+        with open(f"{name}.bin", "wb") as out:
+            out.write(payload)
+
+
+if __name__ == "__main__":
+    main()
@@ -0,0 +1,220 @@
+//! Key exchange between this crate and the `openssl` command-line tool, enabled by the feature
+//! `interop-tests`. Parameters generated by `openssl dhparam` are imported, one key pair is
+//! generated by `openssl genpkey` and one by this crate, and the shared secret derived by
+//! `openssl pkeyutl -derive` must be the bytes of `RuntimeSharedSecret::to_bytes_be`.
+//!
+//! The test is skipped, with a message, when `openssl` is not installed.
+
+#![cfg(feature = "interop-tests")]
+
+use std::{fs, path::PathBuf, process::Command, sync::Arc};
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use diffie_hellman_groups::{
+    dhparam, MODPGroup, MODPGroup14, PrimeGroup, UnvalidatedRuntimePublicKey,
+};
+use num_bigint::BigUint;
+
+/// DER of the object identifier dhKeyAgreement (1.2.840.113549.1.3.1) of PKCS #3.
+const DH_KEY_AGREEMENT: [u8; 11] = [
+    0x06, 0x09, 0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x03, 0x01,
+];
+
+/// A directory of temporary files, removed when dropped, also when a test fails.
+struct TempDir(PathBuf);
+
+impl TempDir {
+    fn new(name: &str) -> Self {
+        let dir = std::env::temp_dir().join(format!("{name}-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        TempDir(dir)
+    }
+
+    fn path(&self, file: &str) -> String {
+        self.0.join(file).to_str().unwrap().to_string()
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
+fn openssl_installed() -> bool {
+    Command::new("openssl")
+        .arg("version")
+        .output()
+        .is_ok_and(|output| output.status.success())
+}
+
+/// Run `openssl` with `args` and return its standard output.
+fn openssl(args: &[&str]) -> Vec<u8> {
+    let output = Command::new("openssl").args(args).output().unwrap();
+    assert!(
+        output.status.success(),
+        "openssl {} failed: {}",
+        args.join(" "),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    output.stdout
+}
+
+fn write_tlv(der: &mut Vec<u8>, tag: u8, content: &[u8]) {
+    der.push(tag);
+    let len = content.len().to_be_bytes();
+    let len = &len[len.iter().position(|&b| b != 0).unwrap_or(len.len() - 1)..];
+    if content.len() >= 0x80 {
+        der.push(0x80 | len.len() as u8);
+    }
+    der.extend_from_slice(len);
+    der.extend_from_slice(content);
+}
+
+/// Split `der` into the content of its first element, which must have the tag `tag`, and the
+/// elements after it.
+fn read_tlv(der: &[u8], tag: u8) -> (&[u8], &[u8]) {
+    assert_eq!(der[0], tag);
+    let (len, start) = match der[1] {
+        short @ 0..0x80 => (short as usize, 2),
+        long => {
+            let n = (long & 0x7f) as usize;
+            let len = der[2..2 + n]
+                .iter()
+                .fold(0usize, |len, &b| len << 8 | b as usize);
+            (len, 2 + n)
+        }
+    };
+    der[start..].split_at(len)
+}
+
+/// The SubjectPublicKeyInfo in PEM of the public key `y` of `group`, as read by OpenSSL.
+fn public_key_pem(group: &PrimeGroup, y: &BigUint) -> String {
+    let mut algorithm = DH_KEY_AGREEMENT.to_vec();
+    algorithm.extend(dhparam::to_der(group));
+    let mut integer = y.to_bytes_be();
+    if integer[0] & 0x80 != 0 {
+        integer.insert(0, 0);
+    }
+    let mut public_key = vec![0];
+    write_tlv(&mut public_key, 0x02, &integer);
+
+    let mut content = Vec::new();
+    write_tlv(&mut content, 0x30, &algorithm);
+    write_tlv(&mut content, 0x03, &public_key);
+    let mut der = Vec::new();
+    write_tlv(&mut der, 0x30, &content);
+
+    let mut pem = "-----BEGIN PUBLIC KEY-----\n".to_string();
+    for line in STANDARD.encode(der).as_bytes().chunks(64) {
+        pem += std::str::from_utf8(line).unwrap();
+        pem += "\n";
+    }
+    pem + "-----END PUBLIC KEY-----\n"
+}
+
+/// The public key `y` of a SubjectPublicKeyInfo in DER written by OpenSSL.
+fn public_key_from_der(der: &[u8]) -> BigUint {
+    let (content, _) = read_tlv(der, 0x30);
+    let (_, content) = read_tlv(content, 0x30);
+    let (public_key, _) = read_tlv(content, 0x03);
+    let (integer, _) = read_tlv(&public_key[1..], 0x02);
+    BigUint::from_bytes_be(integer)
+}
+
+/// Left-pad a shared secret derived by OpenSSL to the length of the modulus.
+fn padded(mut bytes: Vec<u8>, len: usize) -> Vec<u8> {
+    bytes.splice(0..0, vec![0u8; len - bytes.len()]);
+    bytes
+}
+
+/// Derive the shared secret of a key pair of OpenSSL and one of this crate, on both sides, with
+/// the parameters exported by [dhparam::to_pem]. Returns whether the secret derived by OpenSSL
+/// was shorter than the modulus.
+fn exchange(group: &Arc<PrimeGroup>, dir: &TempDir) -> bool {
+    let params = dir.path("params.pem");
+    fs::write(&params, dhparam::to_pem(group)).unwrap();
+    let openssl_key = dir.path("openssl_key.pem");
+    openssl(&["genpkey", "-paramfile", &params, "-out", &openssl_key]);
+    let openssl_public = public_key_from_der(&openssl(&[
+        "pkey",
+        "-in",
+        &openssl_key,
+        "-pubout",
+        "-outform",
+        "DER",
+    ]));
+    let openssl_public = UnvalidatedRuntimePublicKey::from_biguint(group.clone(), openssl_public)
+        .validate()
+        .unwrap();
+
+    let (secret, public) = group.generate_keypair(&mut rand::thread_rng());
+    let public_pem = dir.path("public.pem");
+    fs::write(&public_pem, public_key_pem(group, public.value())).unwrap();
+
+    let len = group.p.bits().div_ceil(8) as usize;
+    let derived = openssl(&[
+        "pkeyutl",
+        "-derive",
+        "-inkey",
+        &openssl_key,
+        "-peerkey",
+        &public_pem,
+    ]);
+    let shared = secret.diffie_hellman(&openssl_public).unwrap();
+    let short = derived.len() < len;
+    assert_eq!(shared.to_bytes_be(), padded(derived, len));
+    short
+}
+
+#[test]
+fn test_key_exchange_with_openssl_cli() {
+    if !openssl_installed() {
+        eprintln!("skipping test_key_exchange_with_openssl_cli: the openssl binary was not found");
+        return;
+    }
+    let dir = TempDir::new("dh-interop");
+
+    // parameters generated by OpenSSL, with new keys until a shared secret has a leading zero
+    // byte, which OpenSSL strips, about once in 256 exchanges
+    let pem = dir.path("dhparam.pem");
+    openssl(&["dhparam", "-out", &pem, "1024"]);
+    let params = dhparam::parse_pem(&fs::read_to_string(&pem).unwrap()).unwrap();
+    assert_eq!(params.group.p.bits(), 1024);
+    assert_eq!(params.builtin, None);
+    let group = Arc::new(params.group);
+    assert!(
+        (0..4096).any(|_| exchange(&group, &dir)),
+        "no shared secret shorter than the modulus"
+    );
+
+    // the 2048-bit MODP group of RFC 3526
+    let p = MODPGroup14::prime_modulus();
+    let group = Arc::new(PrimeGroup {
+        q: (&p - 1u64) >> 1,
+        g: MODPGroup14::generator(),
+        cofactor: BigUint::from(2u64),
+        p,
+    });
+    exchange(&group, &dir);
+}
+
+#[test]
+fn test_der_helpers() {
+    let group = PrimeGroup::new_with(BigUint::from(1623299u64), 15).unwrap();
+    let y = group.public_key(&BigUint::from(1234u64)).unwrap();
+    let pem = public_key_pem(&group, &y);
+    let der = STANDARD
+        .decode(
+            pem.lines()
+                .filter(|l| !l.starts_with("-----"))
+                .collect::<String>(),
+        )
+        .unwrap();
+    assert_eq!(public_key_from_der(&der), y);
+
+    let mut long = Vec::new();
+    write_tlv(&mut long, 0x04, &[7; 300]);
+    assert_eq!(&long[..4], &[0x04, 0x82, 0x01, 0x2c]);
+    assert_eq!(read_tlv(&long, 0x04), (&[7; 300][..], &[][..]));
+}
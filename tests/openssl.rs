@@ -0,0 +1,80 @@
+//! Key exchange between this crate and OpenSSL, over the 2048-bit MODP group of RFC 3526.
+
+#![cfg(feature = "openssl")]
+
+use std::sync::Arc;
+
+use diffie_hellman_groups::{
+    MODPGroup, MODPGroup14, PrimeGroup, RuntimeSecretKey, UnvalidatedRuntimePublicKey,
+};
+use num_bigint::BigUint;
+use openssl::{derive::Deriver, pkey::PKey};
+
+fn group_14() -> Arc<PrimeGroup> {
+    let p = MODPGroup14::prime_modulus();
+    Arc::new(PrimeGroup {
+        q: (&p - 1u64) >> 1,
+        g: MODPGroup14::generator(),
+        cofactor: BigUint::from(2u64),
+        p,
+    })
+}
+
+/// Left-pad a shared secret computed by OpenSSL to the length of the modulus.
+fn padded(mut bytes: Vec<u8>, len: usize) -> Vec<u8> {
+    bytes.splice(0..0, vec![0u8; len - bytes.len()]);
+    bytes
+}
+
+#[test]
+fn test_shared_secret_with_openssl() {
+    let group = group_14();
+    let len = group.p.bits().div_ceil(8) as usize;
+
+    // a key generated by OpenSSL, imported into this crate
+    let openssl_dh = group.to_openssl_dh().unwrap().generate_key().unwrap();
+    let openssl_key = PKey::from_dh(openssl_dh).unwrap();
+    let imported_secret = RuntimeSecretKey::from_openssl_pkey(&openssl_key).unwrap();
+    let imported_public = UnvalidatedRuntimePublicKey::from_openssl_pkey_in(&group, &openssl_key)
+        .unwrap()
+        .validate()
+        .unwrap();
+    assert_eq!(imported_secret.group(), &*group);
+    assert_eq!(imported_secret.public_key(), imported_public);
+
+    // a key generated by this crate, exported to OpenSSL
+    let (secret, public) = group.generate_keypair(&mut rand::thread_rng());
+    let exported_public = public.to_openssl_pkey().unwrap();
+    let mut deriver = Deriver::new(&openssl_key).unwrap();
+    deriver.set_peer(&exported_public).unwrap();
+    let openssl_secret = padded(deriver.derive_to_vec().unwrap(), len);
+
+    let ours = secret.diffie_hellman(&imported_public).unwrap();
+    assert_eq!(ours.to_bytes_be(), openssl_secret);
+    let theirs = imported_secret.diffie_hellman(&public).unwrap();
+    assert_eq!(theirs.to_bytes_be(), openssl_secret);
+
+    // and the other way around, with the key of this crate exported as a private key
+    let exported_secret = secret.to_openssl_pkey().unwrap();
+    let mut deriver = Deriver::new(&exported_secret).unwrap();
+    deriver.set_peer(&openssl_key).unwrap();
+    assert_eq!(
+        padded(deriver.derive_to_vec().unwrap(), len),
+        openssl_secret
+    );
+}
+
+#[test]
+fn test_parameters_from_openssl() {
+    // the parameters OpenSSL uses for the group, without the order q
+    let group = group_14();
+    let dh = openssl::dh::Dh::from_pqg(
+        openssl::bn::BigNum::from_slice(&group.p.to_bytes_be()).unwrap(),
+        None,
+        openssl::bn::BigNum::from_u32(2).unwrap(),
+    )
+    .unwrap();
+    let imported = PrimeGroup::from_openssl_dh(&dh).unwrap();
+    assert_eq!(imported, *group);
+    assert_eq!(imported.identify(), group.identify());
+}
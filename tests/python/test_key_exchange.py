@@ -0,0 +1,55 @@
+"""Key exchange from Python with the bindings of the feature `python`.
+
+Run by `cargo test --features python` on an embedded interpreter, or with pytest against a build
+of the module, e.g. with maturin.
+"""
+
+import diffie_hellman_groups as dhg
+
+
+def test_key_exchange():
+    group = dhg.ModpGroup(14)
+    a, A = group.generate_keypair()
+    b, B = group.generate_keypair()
+    assert A == group.element(a) == group.generator() ** a
+    assert group.shared_secret(a, B) == group.shared_secret(b, A) == (B ** a).to_bytes()
+    assert len(group.shared_secret(a, B)) == 256
+
+
+def test_arithmetic():
+    group = dhg.ModpGroup(5)
+    g = group.generator()
+    assert int(g) == 2
+    assert g ** 2 * g ** 3 == g ** 5 == group.element(5)
+    assert g ** -1 * g == group.element(0)
+    assert g ** group.order == group.element(0)
+    assert pow(int(g), group.order, group.modulus) == 1
+
+
+def test_bytes():
+    group = dhg.ModpGroup(5)
+    element = group.element(12345)
+    data = element.to_bytes()
+    assert len(data) == 192
+    assert group.element_from_bytes(data) == element
+    element.validate()
+
+
+def test_errors():
+    def raises(f, *args):
+        try:
+            f(*args)
+        except dhg.DhGroupsError as err:
+            assert isinstance(err, ValueError)
+            return
+        raise AssertionError(f"{f} did not raise")
+
+    group = dhg.ModpGroup(5)
+    raises(dhg.ModpGroup, 1)
+    raises(group.element_from_bytes, b"\x00")
+    raises(group.element_from_bytes, b"\xff" * 192)
+    raises(group.element(0).validate)
+    _, public = dhg.ModpGroup(14).generate_keypair()
+    raises(group.shared_secret, 1, public)
+    raises(lambda: group.generator() * public)
+    raises(group.shared_secret, 0, group.generator())
@@ -0,0 +1,219 @@
+//! A timing-leak test in the style of [dudect](https://eprint.iacr.org/2016/1123): an operation
+//! claimed to run in constant time is measured on inputs of two classes, e.g. equal and unequal
+//! operands, picked at random for each measurement, and Welch's t-test checks whether the two
+//! distributions of running times differ.
+//!
+//! A test fails when |t| exceeds [T_THRESHOLD]. As the timings of a shared machine are noisy,
+//! setting the environment variable `DH_TIMING_WARN` only prints a warning instead. Checking a new
+//! constant-time claim takes a call to [assert_constant_time].
+//!
+//! The crate does not have a constant-time exponentiation yet, so only the comparisons of the
+//! `group` feature and the decoding of secret scalars of the `subtle` feature are checked.
+
+use std::{hint::black_box, time::Instant};
+
+use rand::{rngs::ThreadRng, Rng};
+
+/// The bound on |t| above which the timings are taken to depend on the class, as in dudect.
+const T_THRESHOLD: f64 = 10.0;
+
+/// The number of measurements of an operation.
+const MEASUREMENTS: usize = 50_000;
+
+/// The number of inputs made at once.
+const BATCH: usize = 1_000;
+
+/// The number of first measurements thrown away while the caches warm up.
+const WARM_UP: usize = 1_000;
+
+/// The fraction of the slowest measurements thrown away, mostly interrupted by the scheduler.
+const CROP: f64 = 0.05;
+
+/// The class of the inputs of a measurement.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Class {
+    /// Inputs fixed to a special value, e.g. equal operands.
+    Fixed,
+    /// Random inputs.
+    Random,
+}
+
+/// Welch's t statistic of two samples.
+fn t_statistic(a: &[f64], b: &[f64]) -> f64 {
+    let mean_var = |x: &[f64]| {
+        let n = x.len() as f64;
+        let mean = x.iter().sum::<f64>() / n;
+        let var = x.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (n - 1.0);
+        (mean, var / n)
+    };
+    let (mean_a, var_a) = mean_var(a);
+    let (mean_b, var_b) = mean_var(b);
+    (mean_a - mean_b) / (var_a + var_b).sqrt()
+}
+
+/// Measure `op` on inputs made by `input` for a random class each time, returning the t
+/// statistic of the running times of the two classes.
+fn measure<T>(mut input: impl FnMut(Class, &mut ThreadRng) -> T, mut op: impl FnMut(&T)) -> f64 {
+    let mut rng = rand::thread_rng();
+    let mut timings: Vec<(Class, f64)> = Vec::with_capacity(MEASUREMENTS);
+    // the inputs are made ahead of a batch of measurements, so that making them does not disturb
+    // the caches and branch predictors differently for each class
+    while timings.len() < MEASUREMENTS {
+        let inputs: Vec<(Class, T)> = (0..BATCH)
+            .map(|_| {
+                let class = if rng.gen() {
+                    Class::Fixed
+                } else {
+                    Class::Random
+                };
+                (class, input(class, &mut rng))
+            })
+            .collect();
+        timings.extend(inputs.iter().map(|(class, input)| {
+            let start = Instant::now();
+            op(black_box(input));
+            (*class, start.elapsed().as_nanos() as f64)
+        }));
+    }
+    timings.drain(..WARM_UP);
+
+    let mut sorted: Vec<f64> = timings.iter().map(|&(_, t)| t).collect();
+    sorted.sort_by(f64::total_cmp);
+    let cutoff = sorted[((1.0 - CROP) * sorted.len() as f64) as usize];
+    timings.retain(|&(_, t)| t <= cutoff);
+
+    let class = |c: Class| -> Vec<f64> {
+        timings
+            .iter()
+            .filter(|&&(class, _)| class == c)
+            .map(|&(_, t)| t)
+            .collect()
+    };
+    t_statistic(&class(Class::Fixed), &class(Class::Random))
+}
+
+/// Check that the running time of `op` does not depend on the class of its inputs, made by
+/// `input`.
+#[cfg_attr(not(any(feature = "group", feature = "subtle")), allow(dead_code))]
+fn assert_constant_time<T>(
+    name: &str,
+    input: impl FnMut(Class, &mut ThreadRng) -> T,
+    op: impl FnMut(&T),
+) {
+    let t = measure(input, op);
+    if t.abs() <= T_THRESHOLD {
+        return;
+    }
+    let message = format!("{name} leaks timing: |t| = {:.2} > {T_THRESHOLD}", t.abs());
+    if std::env::var_os("DH_TIMING_WARN").is_some() {
+        eprintln!("warning: {message}");
+    } else {
+        panic!("{message}");
+    }
+}
+
+#[test]
+fn test_detects_leak() {
+    // an early-exit comparison, slower on equal inputs
+    let t = measure(
+        |class, rng| {
+            let a = vec![rng.gen::<u8>(); 1024];
+            let mut b = a.clone();
+            if class == Class::Random {
+                b[0] ^= 1;
+            }
+            (a, b)
+        },
+        |(a, b)| {
+            black_box(a.iter().zip(b).all(|(x, y)| x == y));
+        },
+    );
+    assert!(t.abs() > T_THRESHOLD, "|t| = {}", t.abs());
+}
+
+#[cfg(feature = "group")]
+mod group_traits {
+    use diffie_hellman_groups::{
+        group::MODPGroup5,
+        group_traits::{QrElement, ScalarField},
+    };
+    use ff::Field;
+    use group::Group;
+    use subtle::ConstantTimeEq;
+
+    use super::*;
+
+    /// Random values to draw the inputs from, as making them is slower than comparing them.
+    fn pool<T>(random: impl Fn(&mut ThreadRng) -> T) -> Vec<T> {
+        let mut rng = rand::thread_rng();
+        (0..64).map(|_| random(&mut rng)).collect()
+    }
+
+    #[test]
+    fn test_qr_element_ct_eq() {
+        let pool = pool(|rng| QrElement::<MODPGroup5>::random(rng));
+        assert_constant_time(
+            "QrElement::ct_eq",
+            |class, rng| {
+                let a = pool[rng.gen_range(0..pool.len())];
+                let b = pool[rng.gen_range(0..pool.len())];
+                match class {
+                    Class::Fixed => (a, a),
+                    Class::Random => (a, b),
+                }
+            },
+            |(a, b)| {
+                black_box(a.ct_eq(b));
+            },
+        );
+    }
+
+    #[test]
+    fn test_scalar_field_ct_eq() {
+        let pool = pool(|rng| ScalarField::<MODPGroup5>::random(rng));
+        assert_constant_time(
+            "ScalarField::ct_eq",
+            |class, rng| {
+                let a = pool[rng.gen_range(0..pool.len())];
+                let b = pool[rng.gen_range(0..pool.len())];
+                match class {
+                    Class::Fixed => (a, a),
+                    Class::Random => (a, b),
+                }
+            },
+            |(a, b)| {
+                black_box(a.ct_eq(b));
+            },
+        );
+    }
+}
+
+#[cfg(feature = "subtle")]
+mod decode {
+    use diffie_hellman_groups::{group::MODPGroup5, MODPGroup, Scalar};
+    use num_bigint::RandBigInt;
+
+    use super::*;
+
+    #[test]
+    fn test_scalar_from_bytes_ct() {
+        let q = MODPGroup5::sophie_garmain_prime();
+        // all ones is above q and differs from it in the most significant limb, where a
+        // comparison exiting early would stop
+        let fixed = vec![0xffu8; 192];
+        let mut rng = rand::thread_rng();
+        let pool: Vec<Vec<u8>> = (0..64)
+            .map(|_| Scalar::<MODPGroup5>::from_biguint(rng.gen_biguint_below(&q)).to_bytes_be())
+            .collect();
+        assert_constant_time(
+            "Scalar::from_bytes_ct",
+            |class, rng| match class {
+                Class::Fixed => fixed.clone(),
+                Class::Random => pool[rng.gen_range(0..pool.len())].clone(),
+            },
+            |bytes| {
+                black_box(Scalar::<MODPGroup5>::from_bytes_ct(bytes).is_some());
+            },
+        );
+    }
+}
@@ -0,0 +1,31 @@
+//! Key exchange over the 2048-bit MODP group in WebAssembly, run with
+//! `wasm-pack test --node --features wasm`.
+
+#![cfg(all(target_arch = "wasm32", feature = "wasm"))]
+
+use diffie_hellman_groups::wasm::KeyPair;
+use wasm_bindgen_test::wasm_bindgen_test;
+
+#[wasm_bindgen_test]
+fn test_group_14_key_exchange() {
+    let alice = KeyPair::new(14).unwrap();
+    let bob = KeyPair::new(14).unwrap();
+    assert_eq!(alice.group_id(), 14);
+    assert_eq!(alice.public_key().len(), 256);
+    assert_ne!(alice.public_key(), bob.public_key());
+
+    let secret = alice.shared_secret(&bob.public_key()).unwrap();
+    assert_eq!(secret.len(), 256);
+    assert_eq!(secret, bob.shared_secret(&alice.public_key()).unwrap());
+}
+
+#[wasm_bindgen_test]
+fn test_invalid_peer() {
+    let alice = KeyPair::new(14).unwrap();
+    assert!(KeyPair::new(1).is_err());
+    assert!(alice.shared_secret(&[0u8; 256]).is_err());
+    assert!(alice.shared_secret(&alice.public_key()[1..]).is_err());
+    assert!(alice
+        .shared_secret(&KeyPair::new(5).unwrap().public_key())
+        .is_err());
+}
@@ -0,0 +1,97 @@
+//! Check with an allocator shim that [Element::pow_zeroizing] frees no memory still holding the
+//! powers of the base in its window table, which depend on the secret exponent.
+
+#![cfg(feature = "zeroize")]
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    cell::{Cell, RefCell},
+};
+
+use diffie_hellman_groups::{Element, MODPGroup, MODPGroup14};
+use num_bigint::RandBigInt;
+
+/// Looks for the watched limbs in the blocks freed by the current thread.
+struct ScanningAllocator;
+
+thread_local! {
+    static WATCHED: RefCell<Vec<u64>> = const { RefCell::new(Vec::new()) };
+    static FOUND: Cell<usize> = const { Cell::new(0) };
+}
+
+/// Count the aligned 64-bit words of the block found in [WATCHED].
+unsafe fn scan(ptr: *const u8, size: usize) {
+    let _ = WATCHED.try_with(|watched| {
+        let Ok(watched) = watched.try_borrow() else {
+            return;
+        };
+        if watched.is_empty() || ptr.align_offset(8) != 0 {
+            return;
+        }
+        let words = unsafe { std::slice::from_raw_parts(ptr as *const u64, size / 8) };
+        let found = words.iter().filter(|w| watched.contains(w)).count();
+        FOUND.with(|count| count.set(count.get() + found));
+    });
+}
+
+unsafe impl GlobalAlloc for ScanningAllocator {
+    // blocks are handed out zeroed, so that the limbs of a block freed earlier are not found
+    // again in a later block only partially written
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        System.alloc_zeroed(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        scan(ptr, layout.size());
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        // the old block is freed, unless it grows in place
+        scan(ptr, layout.size());
+        let new = System.realloc(ptr, layout, new_size);
+        if !new.is_null() && new_size > layout.size() {
+            new.add(layout.size())
+                .write_bytes(0, new_size - layout.size());
+        }
+        new
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: ScanningAllocator = ScanningAllocator;
+
+/// The number of watched limbs in the memory freed by `f`.
+fn found_in_freed<T>(watched: Vec<u64>, f: impl FnOnce() -> T) -> (T, usize) {
+    WATCHED.with(|w| *w.borrow_mut() = watched);
+    FOUND.with(|count| count.set(0));
+    let result = f();
+    let found = FOUND.with(Cell::get);
+    let watched = WATCHED.with(|w| std::mem::take(&mut *w.borrow_mut()));
+    drop(watched);
+    (result, found)
+}
+
+#[test]
+fn test_pow_zeroizing_wipes_temporaries() {
+    let mut rng = rand::thread_rng();
+    let p = MODPGroup14::prime_modulus();
+    let base = Element::<MODPGroup14>::from_value(rng.gen_biguint_below(&p));
+    let exponent = rng.gen_biguint(256);
+
+    // every limb of the powers base^2..base^15 of the window table
+    let mut power = base.value.clone();
+    let mut watched = Vec::new();
+    for _ in 2..16 {
+        power = &power * &base.value % &p;
+        watched.extend(power.iter_u64_digits());
+    }
+
+    // the shim sees a table entry dropped without wiping
+    let (_, found) = found_in_freed(watched.clone(), || drop(power.clone()));
+    assert_eq!(found, power.iter_u64_digits().len());
+
+    let (result, found) = found_in_freed(watched, || base.pow_zeroizing(&exponent));
+    assert_eq!(found, 0, "limbs of the window table were freed unwiped");
+    assert_eq!(result, base.pow(&exponent));
+}